@@ -0,0 +1,184 @@
+//! End-to-end environment of the service: Postgres and the smart-contract-verifier
+//! are started as docker containers (via testcontainers), while the service itself
+//! runs in-process against them, the same way docker-compose.yml deploys it.
+//!
+//! Unlike the server tests, which mock the verifier, requests are verified by the actual
+//! compilers, so that regressions spanning several modules (verifier responses,
+//! their conversion, and the stored data) are caught before the release.
+
+use anyhow::Context;
+use eth_bytecode_db_server::Settings;
+use reqwest::Url;
+use sea_orm::{Database, DatabaseConnection};
+use serde::Deserialize;
+use std::{
+    net::SocketAddr,
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+use testcontainers::{clients::Cli, core::WaitFor, Container, GenericImage};
+
+const POSTGRES_PORT: u16 = 5432;
+const VERIFIER_HTTP_PORT: u16 = 8050;
+const VERIFIER_GRPC_PORT: u16 = 8051;
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(300);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The verifier image tag may be overwritten the same way it is in docker-compose.yml
+const VERIFIER_TAG_ENV: &str = "SMART_CONTRACT_VERIFIER_DOCKER_TAG";
+
+/// A verification request along with the data it is expected to be stored with.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Fixture {
+    pub name: String,
+    pub route: String,
+    pub request: serde_json::Value,
+    pub expected: Expected,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Expected {
+    /// Either "SOLIDITY", "VYPER" or "YUL"
+    pub source_type: String,
+    pub contract_name: String,
+    pub compiler_version: String,
+    /// Either "FULL" or "PARTIAL"; is not checked if absent
+    pub match_type: Option<String>,
+    pub source_files: usize,
+    /// Hex encoded without `0x` prefix
+    pub constructor_arguments: Option<String>,
+}
+
+/// Reads all the `.json` fixtures of the directory sorted by the file names.
+pub fn load_fixtures(dir: &Path) -> anyhow::Result<Vec<Fixture>> {
+    let mut paths = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read fixtures dir {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"));
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            let content = std::fs::read(path)
+                .with_context(|| format!("failed to read fixture {}", path.display()))?;
+            serde_json::from_slice(&content)
+                .with_context(|| format!("invalid fixture {}", path.display()))
+        })
+        .collect()
+}
+
+pub struct Environment<'d> {
+    _postgres: Container<'d, GenericImage>,
+    _verifier: Container<'d, GenericImage>,
+    pub db: DatabaseConnection,
+    pub base: Url,
+}
+
+impl<'d> Environment<'d> {
+    /// Starts the containers and the service, and waits until all of them are ready.
+    pub async fn start(docker: &'d Cli) -> anyhow::Result<Environment<'d>> {
+        let postgres = docker.run(
+            GenericImage::new("postgres", "15")
+                .with_env_var("POSTGRES_HOST_AUTH_METHOD", "trust")
+                .with_env_var("POSTGRES_DB", "eth_bytecode_db")
+                .with_exposed_port(POSTGRES_PORT)
+                .with_wait_for(WaitFor::message_on_stderr(
+                    "database system is ready to accept connections",
+                )),
+        );
+        let db_url = format!(
+            "postgres://postgres@127.0.0.1:{}/eth_bytecode_db",
+            postgres.get_host_port_ipv4(POSTGRES_PORT)
+        );
+        // Postgres restarts once the initialization is finished
+        let db = wait_for("postgres", || Database::connect(&db_url)).await?;
+
+        let tag = std::env::var(VERIFIER_TAG_ENV).unwrap_or_else(|_| "main".to_string());
+        let verifier = docker.run(
+            GenericImage::new("ghcr.io/blockscout/smart-contract-verifier", tag)
+                .with_env_var("SMART_CONTRACT_VERIFIER__SERVER__HTTP__ENABLED", "true")
+                .with_env_var(
+                    "SMART_CONTRACT_VERIFIER__SERVER__HTTP__ADDR",
+                    format!("0.0.0.0:{VERIFIER_HTTP_PORT}"),
+                )
+                .with_env_var("SMART_CONTRACT_VERIFIER__SERVER__GRPC__ENABLED", "true")
+                .with_env_var(
+                    "SMART_CONTRACT_VERIFIER__SERVER__GRPC__ADDR",
+                    format!("0.0.0.0:{VERIFIER_GRPC_PORT}"),
+                )
+                .with_exposed_port(VERIFIER_HTTP_PORT)
+                .with_exposed_port(VERIFIER_GRPC_PORT),
+        );
+        // The verifier is ready once the lists of the compilers are fetched
+        let versions_url = format!(
+            "http://127.0.0.1:{}/api/v2/verifier/solidity/versions",
+            verifier.get_host_port_ipv4(VERIFIER_HTTP_PORT)
+        );
+        wait_for("smart-contract-verifier", || async {
+            reqwest::get(&versions_url).await?.error_for_status()
+        })
+        .await?;
+        let verifier_uri = tonic::transport::Uri::from_str(&format!(
+            "http://127.0.0.1:{}",
+            verifier.get_host_port_ipv4(VERIFIER_GRPC_PORT)
+        ))?;
+
+        let base = start_service(db_url, verifier_uri).await?;
+
+        Ok(Self {
+            _postgres: postgres,
+            _verifier: verifier,
+            db,
+            base,
+        })
+    }
+}
+
+async fn start_service(db_url: String, verifier_uri: tonic::transport::Uri) -> anyhow::Result<Url> {
+    let mut settings = Settings::default(db_url, verifier_uri);
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .port();
+    settings.server.http.addr = SocketAddr::from(([127, 0, 0, 1], port));
+    settings.server.grpc.enabled = false;
+    settings.metrics.enabled = false;
+    settings.tracing.enabled = false;
+    settings.jaeger.enabled = false;
+    settings.database.run_migrations = true;
+
+    tokio::spawn(eth_bytecode_db_server::run(settings));
+
+    let base = Url::parse(&format!("http://127.0.0.1:{port}"))?;
+    let health = base.join("health")?;
+    wait_for("eth-bytecode-db", || async {
+        reqwest::Client::new()
+            .get(health.clone())
+            .query(&[("service", "blockscout.ethBytecodeDb.v2.SourcifyVerifier")])
+            .send()
+            .await?
+            .error_for_status()
+    })
+    .await?;
+    Ok(base)
+}
+
+async fn wait_for<F, Fut, T, E>(name: &str, mut check: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    let start = Instant::now();
+    loop {
+        match check().await {
+            Ok(value) => return Ok(value),
+            Err(err) if start.elapsed() > STARTUP_TIMEOUT => {
+                anyhow::bail!("{name} is not ready: {err}")
+            }
+            Err(_) => tokio::time::sleep(POLL_INTERVAL).await,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use entity::{bytecodes, sea_orm_active_enums, source_files, sources, verified_contracts};
+use eth_bytecode_db_integration_tests::{load_fixtures, Environment, Fixture};
+use pretty_assertions::assert_eq;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use serde_json::Value;
+use std::path::Path;
+use testcontainers::clients::Cli;
+
+const FIXTURES_DIR: &str = "tests/fixtures";
+
+#[tokio::test]
+#[ignore = "Needs docker to run"]
+async fn verifies_fixtures() {
+    let docker = Cli::default();
+    let environment = Environment::start(&docker)
+        .await
+        .expect("environment should start");
+
+    let fixtures = load_fixtures(Path::new(FIXTURES_DIR)).expect("fixtures should be loaded");
+    assert!(!fixtures.is_empty(), "no fixtures found");
+    for fixture in fixtures {
+        let response = verify(&environment, &fixture).await;
+        check_response(&fixture, &response);
+        check_stored(&environment.db, &fixture).await;
+    }
+}
+
+async fn verify(environment: &Environment<'_>, fixture: &Fixture) -> Value {
+    let response = reqwest::Client::new()
+        .post(environment.base.join(&fixture.route).unwrap())
+        .json(&fixture.request)
+        .send()
+        .await
+        .expect("Failed to send verification request");
+    if !response.status().is_success() {
+        let status = response.status();
+        let message = response.text().await.expect("Read body as text");
+        panic!(
+            "{}: invalid status code (success expected). Status: {status}. Message: {message}",
+            fixture.name
+        )
+    }
+    response
+        .json()
+        .await
+        .expect("Verification response deserialization failed")
+}
+
+fn check_response(fixture: &Fixture, response: &Value) {
+    let name = &fixture.name;
+    assert_eq!(
+        Some("SUCCESS"),
+        response["status"].as_str(),
+        "{name}: verification failed: {response}"
+    );
+    let source = &response["source"];
+    let expected = &fixture.expected;
+    assert_eq!(
+        Some(expected.contract_name.as_str()),
+        source["contractName"].as_str(),
+        "{name}: invalid contract name"
+    );
+    assert_eq!(
+        Some(expected.source_type.as_str()),
+        source["sourceType"].as_str(),
+        "{name}: invalid source type"
+    );
+    if let Some(match_type) = &expected.match_type {
+        assert_eq!(
+            Some(match_type.as_str()),
+            source["matchType"].as_str(),
+            "{name}: invalid match type"
+        );
+    }
+    assert_eq!(
+        expected.constructor_arguments.as_deref(),
+        source["constructorArguments"]
+            .as_str()
+            .map(|arguments| arguments.trim_start_matches("0x")),
+        "{name}: invalid constructor arguments"
+    );
+}
+
+async fn check_stored(db: &DatabaseConnection, fixture: &Fixture) {
+    let name = &fixture.name;
+    let expected = &fixture.expected;
+
+    // Fixtures may share the contract names, so the source is looked up by the bytecode
+    let request_bytecode = fixture.request["bytecode"].as_str().unwrap_or_default();
+    let raw_bytecode = hex::decode(request_bytecode.trim_start_matches("0x"))
+        .unwrap_or_else(|err| panic!("{name}: invalid request bytecode: {err}"));
+    let verified_contract = verified_contracts::Entity::find()
+        .filter(verified_contracts::Column::RawBytecode.eq(raw_bytecode))
+        .one(db)
+        .await
+        .expect("Verified contracts query failed")
+        .unwrap_or_else(|| panic!("{name}: verified contract is not stored"));
+    if let Some(match_type) = &expected.match_type {
+        let match_type = match match_type.as_str() {
+            "FULL" => sea_orm_active_enums::MatchType::Full,
+            "PARTIAL" => sea_orm_active_enums::MatchType::Partial,
+            other => panic!("{name}: unknown match type {other}"),
+        };
+        assert_eq!(
+            Some(match_type),
+            verified_contract.match_type,
+            "{name}: invalid stored match type"
+        );
+    }
+
+    let source = sources::Entity::find_by_id(verified_contract.source_id)
+        .one(db)
+        .await
+        .expect("Sources query failed")
+        .unwrap_or_else(|| panic!("{name}: source is not stored"));
+    assert_eq!(
+        (
+            expected.contract_name.as_str(),
+            expected.compiler_version.as_str()
+        ),
+        (
+            source.contract_name.as_str(),
+            source.compiler_version.as_str()
+        ),
+        "{name}: invalid stored source"
+    );
+    let source_type = match expected.source_type.as_str() {
+        "SOLIDITY" => sea_orm_active_enums::SourceType::Solidity,
+        "VYPER" => sea_orm_active_enums::SourceType::Vyper,
+        "YUL" => sea_orm_active_enums::SourceType::Yul,
+        other => panic!("{name}: unknown source type {other}"),
+    };
+    assert_eq!(
+        source_type, source.source_type,
+        "{name}: invalid source type"
+    );
+
+    let files = source_files::Entity::find()
+        .filter(source_files::Column::SourceId.eq(source.id))
+        .count(db)
+        .await
+        .expect("Source files query failed");
+    assert_eq!(
+        expected.source_files as u64, files,
+        "{name}: invalid number of stored source files"
+    );
+
+    let bytecode_types: Vec<_> = bytecodes::Entity::find()
+        .filter(bytecodes::Column::SourceId.eq(source.id))
+        .all(db)
+        .await
+        .expect("Bytecodes query failed")
+        .into_iter()
+        .map(|bytecode| bytecode.bytecode_type)
+        .collect();
+    assert!(
+        bytecode_types.contains(&sea_orm_active_enums::BytecodeType::CreationInput)
+            && bytecode_types.contains(&sea_orm_active_enums::BytecodeType::DeployedBytecode),
+        "{name}: both bytecodes should be stored, found {bytecode_types:?}"
+    );
+}
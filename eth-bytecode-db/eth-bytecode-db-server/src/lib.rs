@@ -2,7 +2,8 @@ mod proto;
 mod server;
 mod services;
 mod settings;
+mod source_files;
 mod types;
 
-pub use server::run;
+pub use server::{migrate, run};
 pub use settings::Settings;
@@ -1,7 +1,21 @@
 use eth_bytecode_db_server::Settings;
 
+/// Starts the server. `migrate [--dry-run]` upgrades the database schema instead,
+/// so that migrations could be applied (or reviewed) before the new release is rolled out.
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     let settings = Settings::new().expect("failed to read config");
-    eth_bytecode_db_server::run(settings).await
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None => eth_bytecode_db_server::run(settings).await,
+        Some("migrate") => {
+            let dry_run = match &args[1..] {
+                [] => false,
+                [flag] if flag == "--dry-run" => true,
+                _ => anyhow::bail!("usage: eth-bytecode-db-server migrate [--dry-run]"),
+            };
+            eth_bytecode_db_server::migrate(settings, dry_run).await
+        }
+        Some(command) => anyhow::bail!("unknown command: {command}"),
+    }
 }
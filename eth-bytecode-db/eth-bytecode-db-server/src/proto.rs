@@ -1,9 +1,24 @@
 pub use eth_bytecode_db_proto::blockscout::eth_bytecode_db::v2::{
-    database_actix, database_server, health_actix, health_check_response, health_server,
+    admin_actix, admin_server, database_actix, database_server, health_actix,
+    health_check_response, health_server, queue_state, reverification_report,
     solidity_verifier_actix, solidity_verifier_server, source, sourcify_verifier_actix,
-    sourcify_verifier_server, verify_response, vyper_verifier_actix, vyper_verifier_server,
-    BytecodeType, HealthCheckRequest, HealthCheckResponse, ListCompilerVersionsRequest,
-    ListCompilerVersionsResponse, SearchSourcesRequest, SearchSourcesResponse, Source,
-    VerificationMetadata, VerifyResponse, VerifySolidityMultiPartRequest,
-    VerifySolidityStandardJsonRequest, VerifySourcifyRequest, VerifyVyperMultiPartRequest,
+    sourcify_verifier_server, verification_job, verification_jobs_actix, verification_jobs_server,
+    verification_previews_actix, verification_previews_server, verified_contracts_actix,
+    verified_contracts_server, verify_response, vyper_verifier_actix, vyper_verifier_server,
+    AbiMatch, BytecodeType, CancelVerificationJobRequest, ChainContract,
+    ConfirmVerificationPreviewRequest, ConstructorArgument, ContractPage, GetContractPageRequest,
+    GetMethodSourceRequest, GetQueueStateRequest, GetVerificationJobRequest, HealthCheckRequest,
+    HealthCheckResponse, Immutable, InvalidateVerifiedContractsRequest,
+    InvalidateVerifiedContractsResponse, JobPriority, ListCompilerVersionsRequest,
+    ListCompilerVersionsResponse, ListVerifiedContractsByLicenseRequest,
+    ListVerifiedContractsByLicenseResponse, ListVerifiedContractsHistoryRequest,
+    ListVerifiedContractsHistoryResponse, MethodSource, NatSpec, QueueState, ReverificationReport,
+    SearchAcrossChainsRequest, SearchAcrossChainsResponse, SearchByAbiRequest, SearchByAbiResponse,
+    SearchSourceCodeRequest, SearchSourceCodeResponse, SearchSourcesByCodeHashRequest,
+    SearchSourcesRequest, SearchSourcesResponse, Source, SourceCodeMatch, StaleReason,
+    SubmitSolidityMultiPartJobRequest, SubmitSolidityStandardJsonJobRequest,
+    SubmitVyperMultiPartJobRequest, TriggerReverificationRequest, VerificationJob,
+    VerificationMetadata, VerificationPreview, VerifiedContract, VerifyResponse,
+    VerifySolidityMultiPartRequest, VerifySolidityStandardJsonRequest, VerifySourcifyRequest,
+    VerifyVyperMultiPartRequest,
 };
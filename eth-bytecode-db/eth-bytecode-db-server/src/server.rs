@@ -1,21 +1,42 @@
 use crate::{
     proto::{
+        admin_actix::route_admin, admin_server::AdminServer,
+        contract_labels_actix::route_contract_labels, contract_labels_server::ContractLabelsServer,
         database_actix::route_database, health_actix::route_health, health_server::HealthServer,
         solidity_verifier_actix::route_solidity_verifier,
         solidity_verifier_server::SolidityVerifierServer,
         sourcify_verifier_actix::route_sourcify_verifier,
         sourcify_verifier_server::SourcifyVerifierServer,
+        verification_jobs_actix::route_verification_jobs,
+        verification_jobs_server::VerificationJobsServer,
+        verification_previews_actix::route_verification_previews,
+        verification_previews_server::VerificationPreviewsServer,
+        verified_contracts_actix::route_verified_contracts,
+        verified_contracts_server::VerifiedContractsServer,
         vyper_verifier_actix::route_vyper_verifier, vyper_verifier_server::VyperVerifierServer,
     },
     services::{
-        DatabaseService, HealthService, SolidityVerifierService, SourcifyVerifierService,
-        VyperVerifierService,
+        AdminService, ContractLabelsService, DatabaseService, HealthService,
+        SolidityVerifierService, SourcifyVerifierService, VerificationJobsService,
+        VerificationPreviewsService, VerifiedContractsService, VyperVerifierService,
     },
-    settings::Settings,
+    settings::{
+        DatabasePoolSettings, EventsBrokerSettings, RetentionSettings, ReverificationSettings,
+        Settings, SourceChecksSettings,
+    },
+    source_files::route_source_files,
 };
 use blockscout_service_launcher::LaunchSettings;
-use eth_bytecode_db::verification::Client;
+use eth_bytecode_db::{
+    abi_search,
+    events::{EventPublisher, Events, KafkaPublisher, NatsPublisher},
+    jobs::{self, JobQueue, WorkerParams},
+    retention, reverification, search,
+    source_checks::{MisleadingGetterCheck, SourceCheck, SourceChecks, UnicodeCheck},
+    verification::Client,
+};
 use migration::{Migrator, MigratorTrait};
+use sea_orm::{ConnectOptions, DatabaseConnection};
 use std::sync::Arc;
 
 const SERVICE_NAME: &str = "eth_bytecode_db";
@@ -26,6 +47,11 @@ struct Router {
     solidity_verifier: Option<Arc<SolidityVerifierService>>,
     vyper_verifier: Option<Arc<VyperVerifierService>>,
     sourcify_verifier: Option<Arc<SourcifyVerifierService>>,
+    admin: Option<Arc<AdminService>>,
+    verification_jobs: Option<Arc<VerificationJobsService>>,
+    verified_contracts: Option<Arc<VerifiedContractsService>>,
+    verification_previews: Option<Arc<VerificationPreviewsService>>,
+    contract_labels: Option<Arc<ContractLabelsService>>,
 
     health: Arc<HealthService>,
 }
@@ -49,6 +75,27 @@ impl Router {
                     .clone()
                     .map(SourcifyVerifierServer::from_arc),
             )
+            .add_optional_service(self.admin.clone().map(AdminServer::from_arc))
+            .add_optional_service(
+                self.verification_jobs
+                    .clone()
+                    .map(VerificationJobsServer::from_arc),
+            )
+            .add_optional_service(
+                self.verified_contracts
+                    .clone()
+                    .map(VerifiedContractsServer::from_arc),
+            )
+            .add_optional_service(
+                self.verification_previews
+                    .clone()
+                    .map(VerificationPreviewsServer::from_arc),
+            )
+            .add_optional_service(
+                self.contract_labels
+                    .clone()
+                    .map(ContractLabelsServer::from_arc),
+            )
     }
 }
 
@@ -58,6 +105,8 @@ impl blockscout_service_launcher::HttpRouter for Router {
 
         if let Some(database) = &self.database {
             service_config.configure(|config| route_database(config, database.clone()));
+            service_config
+                .configure(|config| route_source_files(config, database.db_client.clone()));
         }
         if let Some(solidity) = &self.solidity_verifier {
             service_config.configure(|config| route_solidity_verifier(config, solidity.clone()));
@@ -68,6 +117,23 @@ impl blockscout_service_launcher::HttpRouter for Router {
         if let Some(sourcify) = &self.sourcify_verifier {
             service_config.configure(|config| route_sourcify_verifier(config, sourcify.clone()));
         }
+        if let Some(admin) = &self.admin {
+            service_config.configure(|config| route_admin(config, admin.clone()));
+        }
+        if let Some(jobs) = &self.verification_jobs {
+            service_config.configure(|config| route_verification_jobs(config, jobs.clone()));
+        }
+        if let Some(verified_contracts) = &self.verified_contracts {
+            service_config
+                .configure(|config| route_verified_contracts(config, verified_contracts.clone()));
+        }
+        if let Some(previews) = &self.verification_previews {
+            service_config
+                .configure(|config| route_verification_previews(config, previews.clone()));
+        }
+        if let Some(labels) = &self.contract_labels {
+            service_config.configure(|config| route_contract_labels(config, labels.clone()));
+        }
     }
 }
 
@@ -76,23 +142,109 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
 
     let health = Arc::new(HealthService::default());
 
-    let db_connection = Arc::new(sea_orm::Database::connect(settings.database.url).await?);
+    let db_connection =
+        Arc::new(connect_database(&settings.database.url, &settings.database.pool).await?);
     if settings.database.run_migrations {
         Migrator::up(db_connection.as_ref(), None).await?;
+    } else {
+        let pending = Migrator::get_pending_migrations(db_connection.as_ref()).await?;
+        if !pending.is_empty() {
+            tracing::warn!(
+                pending = pending.len(),
+                "database schema is outdated; run the `migrate` command to upgrade it"
+            );
+        }
+    }
+    // Lookups and searches are served from the replica, if any. Everything that writes,
+    // or reads just written data (verification, jobs, labels), stays on the primary.
+    let read_db_connection = match &settings.database.replica {
+        Some(replica) => Arc::new(connect_database(&replica.url, &replica.pool).await?),
+        None => db_connection.clone(),
+    };
+
+    let mut client = Client::new_arc(db_connection.clone(), settings.verifier.uri)
+        .await?
+        .with_source_checks(source_checks(&settings.source_checks));
+    if settings.events.enabled {
+        let publisher = events_publisher(&settings.events.broker).await?;
+        client = client.with_events(Events::new(publisher));
     }
 
-    let client = Client::new_arc(db_connection.clone(), settings.verifier.uri).await?;
+    tokio::spawn(fill_missing_code_hashes(db_connection.clone()));
+    tokio::spawn(index_abis(db_connection.clone()));
+
+    if settings.retention.enabled {
+        tokio::spawn(run_retention(
+            db_connection.clone(),
+            settings.retention.clone(),
+        ));
+    }
 
-    let database = Arc::new(DatabaseService::new_arc(db_connection));
+    let verified_contracts = Arc::new(VerifiedContractsService::new(
+        db_connection.clone(),
+        read_db_connection.clone(),
+        settings.verified_contracts.invalidation_endpoint_enabled,
+    ));
+    let contract_labels = Arc::new(ContractLabelsService::new(
+        db_connection.clone(),
+        &settings.labels,
+    ));
+    let database = Arc::new(DatabaseService::new_arc(read_db_connection));
     let solidity_verifier = Arc::new(SolidityVerifierService::new(client.clone()));
     let vyper_verifier = Arc::new(VyperVerifierService::new(client.clone()));
     let sourcify_verifier = Arc::new(SourcifyVerifierService::new(client.clone()));
+    let admin = settings.reverification.admin_endpoint_enabled.then(|| {
+        Arc::new(AdminService::new(
+            client.clone(),
+            settings.reverification.batch_size,
+        ))
+    });
+
+    let verification_jobs = settings.jobs.enabled.then(|| {
+        let params = WorkerParams {
+            worker_id: settings
+                .jobs
+                .worker_id
+                .clone()
+                .unwrap_or_else(jobs::default_worker_id),
+            interactive_concurrency: settings.jobs.interactive_concurrency,
+            bulk_concurrency: settings.jobs.bulk_concurrency,
+            max_attempts: settings.jobs.max_attempts,
+            lease_duration: settings.jobs.lease_duration,
+            poll_interval: settings.jobs.poll_interval,
+        };
+        let queue = JobQueue::start(client.clone(), params);
+        Arc::new(VerificationJobsService::new(
+            Arc::new(queue),
+            &settings.jobs,
+        ))
+    });
+
+    let verification_previews = settings.previews.enabled.then(|| {
+        Arc::new(VerificationPreviewsService::new(
+            client.clone(),
+            &settings.previews,
+        ))
+    });
+
+    if let Some(interval) = settings.reverification.interval {
+        tokio::spawn(run_reverification(
+            client.clone(),
+            interval,
+            settings.reverification.clone(),
+        ));
+    }
 
     let router = Router {
         database: Some(database),
         solidity_verifier: Some(solidity_verifier),
         vyper_verifier: Some(vyper_verifier),
         sourcify_verifier: Some(sourcify_verifier),
+        admin,
+        verification_jobs,
+        verified_contracts: Some(verified_contracts),
+        verification_previews,
+        contract_labels: Some(contract_labels),
         health,
     };
 
@@ -107,3 +259,198 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
 
     blockscout_service_launcher::launch(&launch_settings, http_router, grpc_router).await
 }
+
+/// Applies all pending migrations to the primary database. If `dry_run` is set,
+/// only lists the migrations which would be applied.
+pub async fn migrate(settings: Settings, dry_run: bool) -> Result<(), anyhow::Error> {
+    blockscout_service_launcher::init_logs(SERVICE_NAME, &settings.tracing, &settings.jaeger)?;
+
+    let db_connection = connect_database(&settings.database.url, &settings.database.pool).await?;
+    let pending = Migrator::get_pending_migrations(&db_connection).await?;
+    if pending.is_empty() {
+        tracing::info!("database schema is up to date");
+        return Ok(());
+    }
+    for migration in &pending {
+        tracing::info!(migration = migration.name(), "pending migration");
+    }
+    if dry_run {
+        tracing::info!(pending = pending.len(), "dry run; no migrations applied");
+        return Ok(());
+    }
+
+    Migrator::up(&db_connection, None).await?;
+    tracing::info!(applied = pending.len(), "database schema has been upgraded");
+    Ok(())
+}
+
+async fn connect_database(
+    url: &str,
+    settings: &DatabasePoolSettings,
+) -> Result<DatabaseConnection, anyhow::Error> {
+    let url = match settings.statement_timeout {
+        Some(timeout) => with_statement_timeout(url, timeout),
+        None => url.to_string(),
+    };
+    let mut options = ConnectOptions::new(url);
+    options
+        .max_connections(settings.max_connections)
+        .min_connections(settings.min_connections)
+        .connect_timeout(settings.connect_timeout);
+    Ok(sea_orm::Database::connect(options).await?)
+}
+
+/// Sets the timeout for every session of the pool via the connection string `options` parameter
+fn with_statement_timeout(url: &str, timeout: std::time::Duration) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!(
+        "{url}{separator}options=-c%20statement_timeout%3D{}",
+        timeout.as_millis()
+    )
+}
+
+async fn events_publisher(
+    settings: &EventsBrokerSettings,
+) -> Result<Arc<dyn EventPublisher>, anyhow::Error> {
+    let publisher: Arc<dyn EventPublisher> = match settings {
+        EventsBrokerSettings::Kafka(kafka) => Arc::new(KafkaPublisher::new(
+            &kafka.brokers,
+            kafka.topic.clone(),
+            kafka.timeout,
+        )?),
+        EventsBrokerSettings::Nats(nats) => {
+            Arc::new(NatsPublisher::connect(&nats.url, nats.subject.clone()).await?)
+        }
+    };
+    Ok(publisher)
+}
+
+fn source_checks(settings: &SourceChecksSettings) -> SourceChecks {
+    let mut checks: Vec<Arc<dyn SourceCheck>> = vec![];
+    if settings.unicode_enabled {
+        checks.push(Arc::new(UnicodeCheck));
+    }
+    if settings.misleading_getters_enabled {
+        checks.push(Arc::new(MisleadingGetterCheck));
+    }
+    SourceChecks::new(checks)
+}
+
+async fn run_retention(db: Arc<DatabaseConnection>, settings: RetentionSettings) {
+    let days = |days: u64| std::time::Duration::from_secs(days * 24 * 60 * 60);
+    let params = retention::RetentionParams {
+        purge_failed_after: settings.purge_failed_after_days.map(days),
+        compress_after: settings.compress_after_days.map(days),
+        max_store_size: settings.max_store_size,
+        batch_size: settings.batch_size,
+    };
+
+    let mut interval = tokio::time::interval(settings.interval);
+    loop {
+        interval.tick().await;
+        match retention::apply(db.as_ref(), &params).await {
+            Ok(report) if report == retention::Report::default() => {}
+            Ok(report) => tracing::info!(
+                purged = report.purged,
+                compressed = report.compressed,
+                evicted = report.evicted,
+                "retention policies have been applied to verification jobs"
+            ),
+            Err(err) => tracing::error!("verification jobs retention failed: {err:#}"),
+        }
+    }
+}
+
+async fn run_reverification(
+    client: Client,
+    interval: std::time::Duration,
+    settings: ReverificationSettings,
+) {
+    let mut interval = tokio::time::interval(interval);
+    // The first tick completes immediately, so skip it
+    // to not start re-verification on every restart
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+
+        tracing::info!("starting re-verification of stored contracts");
+        let params = reverification::SweepParams {
+            batch_size: settings.batch_size,
+            ..Default::default()
+        };
+        match reverification::sweep(client.clone(), params).await {
+            Ok(report) => tracing::info!(
+                checked = report.checked,
+                skipped = report.skipped,
+                changed = report.changes.len(),
+                changes = ?report.changes,
+                "re-verification finished"
+            ),
+            Err(err) => tracing::error!("re-verification failed: {err:#}"),
+        }
+    }
+}
+
+/// Calculates normalized code hashes for the bytecodes stored before the hashes were introduced,
+/// so that those contracts could be found via code hash lookups as well.
+async fn fill_missing_code_hashes(db_connection: Arc<DatabaseConnection>) {
+    const BATCH_SIZE: u64 = 1000;
+
+    let mut total = 0;
+    loop {
+        match search::fill_missing_code_hashes(db_connection.as_ref(), BATCH_SIZE).await {
+            Ok(0) => break,
+            Ok(updated) => total += updated,
+            Err(err) => {
+                tracing::error!("filling missing code hashes failed: {err:#}");
+                return;
+            }
+        }
+    }
+    if total > 0 {
+        tracing::info!(total, "missing code hashes have been filled");
+    }
+}
+
+/// Indexes the abis of the sources stored before the abi search was introduced,
+/// so that those contracts could be found by their functions and events as well.
+async fn index_abis(db_connection: Arc<DatabaseConnection>) {
+    const BATCH_SIZE: u64 = 1000;
+
+    let mut after_id = None;
+    loop {
+        match abi_search::index_sources(db_connection.as_ref(), after_id, BATCH_SIZE).await {
+            Ok(Some(last_id)) => after_id = Some(last_id),
+            Ok(None) => break,
+            Err(err) => {
+                tracing::error!("indexing abis of the stored sources failed: {err:#}");
+                return;
+            }
+        }
+    }
+    if let Some(last_id) = after_id {
+        tracing::info!(last_id, "abis of the stored sources have been indexed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn statement_timeout_is_added_to_url() {
+        assert_eq!(
+            "postgres://localhost:5432/db?options=-c%20statement_timeout%3D5000",
+            with_statement_timeout("postgres://localhost:5432/db", Duration::from_secs(5))
+        );
+        assert_eq!(
+            "postgres://localhost:5432/db?sslmode=require&options=-c%20statement_timeout%3D250",
+            with_statement_timeout(
+                "postgres://localhost:5432/db?sslmode=require",
+                Duration::from_millis(250)
+            )
+        );
+    }
+}
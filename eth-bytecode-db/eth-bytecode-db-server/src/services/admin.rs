@@ -0,0 +1,41 @@
+use crate::{
+    proto::{admin_server::Admin, ReverificationReport, TriggerReverificationRequest},
+    types::ReverificationReportWrapper,
+};
+use amplify::Wrapper;
+use async_trait::async_trait;
+use eth_bytecode_db::{reverification, verification::Client};
+
+pub struct AdminService {
+    client: Client,
+    batch_size: u64,
+}
+
+impl AdminService {
+    pub fn new(client: Client, batch_size: u64) -> Self {
+        Self { client, batch_size }
+    }
+}
+
+#[async_trait]
+impl Admin for AdminService {
+    async fn trigger_reverification(
+        &self,
+        request: tonic::Request<TriggerReverificationRequest>,
+    ) -> Result<tonic::Response<ReverificationReport>, tonic::Status> {
+        let request = request.into_inner();
+        let params = reverification::SweepParams {
+            after_id: request.after_id,
+            limit: request.limit,
+            batch_size: self.batch_size,
+        };
+
+        let report = reverification::sweep(self.client.clone(), params)
+            .await
+            .map_err(|err| tonic::Status::internal(format!("{err:#}")))?;
+
+        Ok(tonic::Response::new(
+            ReverificationReportWrapper::from(report).into_inner(),
+        ))
+    }
+}
@@ -0,0 +1,160 @@
+use super::verified_contracts::parse_address;
+use crate::{
+    proto::{
+        contract_labels_server::ContractLabels, ContractLabel, CreateContractLabelRequest,
+        DeleteContractLabelRequest, DeleteContractLabelResponse, ListContractLabelsRequest,
+        ListContractLabelsResponse, UpdateContractLabelRequest,
+    },
+    settings::LabelsSettings,
+    types::ContractLabelWrapper,
+};
+use amplify::Wrapper;
+use async_trait::async_trait;
+use eth_bytecode_db::labels;
+use sea_orm::DatabaseConnection;
+use std::{collections::HashSet, sync::Arc};
+
+pub struct ContractLabelsService {
+    db_client: Arc<DatabaseConnection>,
+    api_key_header: String,
+    operator_api_keys: HashSet<String>,
+}
+
+impl ContractLabelsService {
+    pub fn new(db_client: Arc<DatabaseConnection>, settings: &LabelsSettings) -> Self {
+        Self {
+            db_client,
+            api_key_header: settings.api_key_header.clone(),
+            operator_api_keys: settings.operator_api_keys.iter().cloned().collect(),
+        }
+    }
+
+    /// Labels may be modified only by the operators, i.e. requests
+    /// with any of the configured operator api keys.
+    fn authorize<T>(&self, request: &tonic::Request<T>) -> Result<(), tonic::Status> {
+        let is_operator = request
+            .metadata()
+            .get(&self.api_key_header)
+            .and_then(|api_key| api_key.to_str().ok())
+            .map(|api_key| self.operator_api_keys.contains(api_key))
+            .unwrap_or_default();
+
+        if !is_operator {
+            return Err(tonic::Status::permission_denied(
+                "api key is not allowed to modify contract labels",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ContractLabels for ContractLabelsService {
+    async fn list_labels(
+        &self,
+        request: tonic::Request<ListContractLabelsRequest>,
+    ) -> Result<tonic::Response<ListContractLabelsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let (chain_id, contract_address) =
+            parse_address(&request.chain_id, &request.contract_address)?;
+
+        let labels = labels::list(self.db_client.as_ref(), chain_id, &contract_address)
+            .await
+            .map_err(|err| tonic::Status::internal(format!("{err:#}")))?
+            .into_iter()
+            .map(|label| ContractLabelWrapper::from(label).into_inner())
+            .collect();
+
+        Ok(tonic::Response::new(ListContractLabelsResponse { labels }))
+    }
+
+    async fn create_label(
+        &self,
+        request: tonic::Request<CreateContractLabelRequest>,
+    ) -> Result<tonic::Response<ContractLabel>, tonic::Status> {
+        self.authorize(&request)?;
+        let request = request.into_inner();
+        let (chain_id, contract_address) =
+            parse_address(&request.chain_id, &request.contract_address)?;
+
+        let label = labels::create(
+            self.db_client.as_ref(),
+            chain_id,
+            &contract_address,
+            &request.name,
+            request.description,
+        )
+        .await
+        .map_err(|err| match err {
+            labels::Error::InvalidName(_) => tonic::Status::invalid_argument(err.to_string()),
+            labels::Error::ContractNotVerified => tonic::Status::not_found(err.to_string()),
+            labels::Error::AlreadyExists => tonic::Status::already_exists(err.to_string()),
+            labels::Error::Internal(err) => tonic::Status::internal(format!("{err:#}")),
+        })?;
+        tracing::info!(
+            chain_id,
+            contract_address = %request.contract_address,
+            label = %label.name,
+            "contract label has been created"
+        );
+
+        Ok(tonic::Response::new(
+            ContractLabelWrapper::from(label).into_inner(),
+        ))
+    }
+
+    async fn update_label(
+        &self,
+        request: tonic::Request<UpdateContractLabelRequest>,
+    ) -> Result<tonic::Response<ContractLabel>, tonic::Status> {
+        self.authorize(&request)?;
+        let request = request.into_inner();
+        let (chain_id, contract_address) =
+            parse_address(&request.chain_id, &request.contract_address)?;
+
+        let label = labels::update(
+            self.db_client.as_ref(),
+            chain_id,
+            &contract_address,
+            &request.name,
+            request.description,
+        )
+        .await
+        .map_err(|err| tonic::Status::internal(format!("{err:#}")))?
+        .ok_or_else(|| tonic::Status::not_found("Label not found"))?;
+
+        Ok(tonic::Response::new(
+            ContractLabelWrapper::from(label).into_inner(),
+        ))
+    }
+
+    async fn delete_label(
+        &self,
+        request: tonic::Request<DeleteContractLabelRequest>,
+    ) -> Result<tonic::Response<DeleteContractLabelResponse>, tonic::Status> {
+        self.authorize(&request)?;
+        let request = request.into_inner();
+        let (chain_id, contract_address) =
+            parse_address(&request.chain_id, &request.contract_address)?;
+
+        let deleted = labels::delete(
+            self.db_client.as_ref(),
+            chain_id,
+            &contract_address,
+            &request.name,
+        )
+        .await
+        .map_err(|err| tonic::Status::internal(format!("{err:#}")))?;
+        if !deleted {
+            return Err(tonic::Status::not_found("Label not found"));
+        }
+        tracing::info!(
+            chain_id,
+            contract_address = %request.contract_address,
+            label = %request.name,
+            "contract label has been deleted"
+        );
+
+        Ok(tonic::Response::new(DeleteContractLabelResponse {}))
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    proto::{database_server::Database, SearchSourcesRequest, SearchSourcesResponse},
+    proto::{
+        database_server::Database, SearchSourcesByCodeHashRequest, SearchSourcesRequest,
+        SearchSourcesResponse,
+    },
     types::{BytecodeTypeWrapper, SourceWrapper},
 };
 use amplify::Wrapper;
@@ -47,4 +50,33 @@ impl Database for DatabaseService {
         let response = SearchSourcesResponse { sources };
         Ok(tonic::Response::new(response))
     }
+
+    async fn search_sources_by_code_hash(
+        &self,
+        request: tonic::Request<SearchSourcesByCodeHashRequest>,
+    ) -> Result<tonic::Response<SearchSourcesResponse>, tonic::Status> {
+        let request = request.into_inner();
+
+        let code_hash = DisplayBytes::from_str(&request.code_hash)
+            .map_err(|err| tonic::Status::invalid_argument(format!("Invalid code hash: {err}")))?
+            .0;
+        if code_hash.len() != 32 {
+            return Err(tonic::Status::invalid_argument(format!(
+                "Invalid code hash: expected 32 bytes, got {}",
+                code_hash.len()
+            )));
+        }
+
+        let sources = search::find_by_code_hash(self.db_client.as_ref(), &code_hash)
+            .await
+            .map_err(|err| tonic::Status::internal(err.to_string()))?;
+
+        let sources = sources
+            .into_iter()
+            .map(|source| SourceWrapper::from(source).into_inner())
+            .collect();
+
+        let response = SearchSourcesResponse { sources };
+        Ok(tonic::Response::new(response))
+    }
 }
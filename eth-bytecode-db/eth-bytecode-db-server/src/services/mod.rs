@@ -1,12 +1,22 @@
+mod admin;
+mod contract_labels;
 mod database;
 mod health;
 mod solidity_verifier;
 mod sourcify_verifier;
+mod verification_jobs;
+mod verification_previews;
+mod verified_contracts;
 mod verifier_base;
 mod vyper_verifier;
 
+pub use admin::AdminService;
+pub use contract_labels::ContractLabelsService;
 pub use database::DatabaseService;
 pub use health::HealthService;
 pub use solidity_verifier::SolidityVerifierService;
 pub use sourcify_verifier::SourcifyVerifierService;
+pub use verification_jobs::VerificationJobsService;
+pub use verification_previews::VerificationPreviewsService;
+pub use verified_contracts::VerifiedContractsService;
 pub use vyper_verifier::VyperVerifierService;
@@ -4,7 +4,7 @@ use crate::{
         solidity_verifier_server, ListCompilerVersionsRequest, ListCompilerVersionsResponse,
         VerifyResponse, VerifySolidityMultiPartRequest, VerifySolidityStandardJsonRequest,
     },
-    types::{BytecodeTypeWrapper, VerificationMetadataWrapper},
+    types::{VerifySolidityMultiPartRequestWrapper, VerifySolidityStandardJsonRequestWrapper},
 };
 use amplify::Wrapper;
 use async_trait::async_trait;
@@ -28,24 +28,8 @@ impl solidity_verifier_server::SolidityVerifier for SolidityVerifierService {
         &self,
         request: tonic::Request<VerifySolidityMultiPartRequest>,
     ) -> Result<tonic::Response<VerifyResponse>, tonic::Status> {
-        let request = request.into_inner();
-
-        let bytecode_type = request.bytecode_type();
-        let verification_request = VerificationRequest {
-            bytecode: request.bytecode,
-            bytecode_type: BytecodeTypeWrapper::from_inner(bytecode_type).try_into()?,
-            compiler_version: request.compiler_version,
-            content: solidity_multi_part::MultiPartFiles {
-                source_files: request.source_files,
-                evm_version: request.evm_version,
-                optimization_runs: request.optimization_runs,
-                libraries: request.libraries,
-            },
-            metadata: request
-                .metadata
-                .map(|metadata| VerificationMetadataWrapper::from_inner(metadata).try_into())
-                .transpose()?,
-        };
+        let verification_request: VerificationRequest<_> =
+            VerifySolidityMultiPartRequestWrapper::from_inner(request.into_inner()).try_into()?;
         let result = solidity_multi_part::verify(self.client.clone(), verification_request).await;
 
         verifier_base::process_verification_result(result)
@@ -55,21 +39,9 @@ impl solidity_verifier_server::SolidityVerifier for SolidityVerifierService {
         &self,
         request: tonic::Request<VerifySolidityStandardJsonRequest>,
     ) -> Result<tonic::Response<VerifyResponse>, tonic::Status> {
-        let request = request.into_inner();
-
-        let bytecode_type = request.bytecode_type();
-        let verification_request = VerificationRequest {
-            bytecode: request.bytecode,
-            bytecode_type: BytecodeTypeWrapper::from_inner(bytecode_type).try_into()?,
-            compiler_version: request.compiler_version,
-            content: solidity_standard_json::StandardJson {
-                input: request.input,
-            },
-            metadata: request
-                .metadata
-                .map(|metadata| VerificationMetadataWrapper::from_inner(metadata).try_into())
-                .transpose()?,
-        };
+        let verification_request: VerificationRequest<_> =
+            VerifySolidityStandardJsonRequestWrapper::from_inner(request.into_inner())
+                .try_into()?;
         let result =
             solidity_standard_json::verify(self.client.clone(), verification_request).await;
 
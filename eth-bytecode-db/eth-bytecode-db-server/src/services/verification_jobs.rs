@@ -0,0 +1,194 @@
+use crate::{
+    proto::{
+        verification_jobs_server::VerificationJobs, CancelVerificationJobRequest,
+        GetQueueStateRequest, GetVerificationJobRequest, QueueState,
+        SubmitSolidityMultiPartJobRequest, SubmitSolidityStandardJsonJobRequest,
+        SubmitVyperMultiPartJobRequest, VerificationJob,
+    },
+    settings::JobsSettings,
+    types::{
+        JobPriorityWrapper, QueueStateWrapper, VerificationJobWrapper,
+        VerifySolidityMultiPartRequestWrapper, VerifySolidityStandardJsonRequestWrapper,
+        VerifyVyperMultiPartRequestWrapper,
+    },
+};
+use amplify::Wrapper;
+use async_trait::async_trait;
+use eth_bytecode_db::jobs::{Job, JobPriority, JobQueue, JobRequest, JobStatus};
+use std::{collections::HashSet, sync::Arc};
+
+const DEFAULT_RECENT_FAILURES_LIMIT: u32 = 10;
+const MAX_RECENT_FAILURES_LIMIT: u32 = 100;
+
+pub struct VerificationJobsService {
+    queue: Arc<JobQueue>,
+    api_key_header: String,
+    interactive_api_keys: HashSet<String>,
+}
+
+impl VerificationJobsService {
+    pub fn new(queue: Arc<JobQueue>, settings: &JobsSettings) -> Self {
+        Self {
+            queue,
+            api_key_header: settings.api_key_header.clone(),
+            interactive_api_keys: settings.interactive_api_keys.iter().cloned().collect(),
+        }
+    }
+
+    /// Returns the requested priority if it is allowed for the api key of the request.
+    /// If the priority is not specified, the highest allowed one is returned.
+    fn priority<T>(
+        &self,
+        request: &tonic::Request<T>,
+        requested: JobPriorityWrapper,
+    ) -> Result<JobPriority, tonic::Status> {
+        let interactive_allowed = self.interactive_api_keys.is_empty()
+            || request
+                .metadata()
+                .get(&self.api_key_header)
+                .and_then(|api_key| api_key.to_str().ok())
+                .map(|api_key| self.interactive_api_keys.contains(api_key))
+                .unwrap_or_default();
+
+        match (Option::<JobPriority>::from(requested), interactive_allowed) {
+            (Some(JobPriority::Interactive), false) => Err(tonic::Status::permission_denied(
+                "api key is not allowed to submit interactive jobs",
+            )),
+            (Some(priority), _) => Ok(priority),
+            (None, true) => Ok(JobPriority::Interactive),
+            (None, false) => Ok(JobPriority::Bulk),
+        }
+    }
+
+    async fn submit(
+        &self,
+        request: JobRequest,
+        priority: JobPriority,
+    ) -> Result<tonic::Response<VerificationJob>, tonic::Status> {
+        let id = self
+            .queue
+            .submit(request, priority)
+            .await
+            .map_err(|err| tonic::Status::internal(format!("{err:#}")))?;
+
+        let job = Job {
+            id,
+            status: JobStatus::Queued,
+            priority,
+            attempts: 0,
+            result: None,
+            error: None,
+        };
+        Ok(tonic::Response::new(
+            VerificationJobWrapper::from(job).into_inner(),
+        ))
+    }
+}
+
+fn missing_request() -> tonic::Status {
+    tonic::Status::invalid_argument("Verification request is not specified")
+}
+
+#[async_trait]
+impl VerificationJobs for VerificationJobsService {
+    async fn submit_solidity_multi_part(
+        &self,
+        request: tonic::Request<SubmitSolidityMultiPartJobRequest>,
+    ) -> Result<tonic::Response<VerificationJob>, tonic::Status> {
+        let priority = self.priority(
+            &request,
+            JobPriorityWrapper::from(request.get_ref().priority()),
+        )?;
+        let request = request.into_inner().request.ok_or_else(missing_request)?;
+        let request = VerifySolidityMultiPartRequestWrapper::from_inner(request).try_into()?;
+        self.submit(JobRequest::SolidityMultiPart(request), priority)
+            .await
+    }
+
+    async fn submit_solidity_standard_json(
+        &self,
+        request: tonic::Request<SubmitSolidityStandardJsonJobRequest>,
+    ) -> Result<tonic::Response<VerificationJob>, tonic::Status> {
+        let priority = self.priority(
+            &request,
+            JobPriorityWrapper::from(request.get_ref().priority()),
+        )?;
+        let request = request.into_inner().request.ok_or_else(missing_request)?;
+        let request = VerifySolidityStandardJsonRequestWrapper::from_inner(request).try_into()?;
+        self.submit(JobRequest::SolidityStandardJson(request), priority)
+            .await
+    }
+
+    async fn submit_vyper_multi_part(
+        &self,
+        request: tonic::Request<SubmitVyperMultiPartJobRequest>,
+    ) -> Result<tonic::Response<VerificationJob>, tonic::Status> {
+        let priority = self.priority(
+            &request,
+            JobPriorityWrapper::from(request.get_ref().priority()),
+        )?;
+        let request = request.into_inner().request.ok_or_else(missing_request)?;
+        let request = VerifyVyperMultiPartRequestWrapper::from_inner(request).try_into()?;
+        self.submit(JobRequest::VyperMultiPart(request), priority)
+            .await
+    }
+
+    async fn get_job(
+        &self,
+        request: tonic::Request<GetVerificationJobRequest>,
+    ) -> Result<tonic::Response<VerificationJob>, tonic::Status> {
+        let id = request.into_inner().id;
+        let job = self
+            .queue
+            .find(id)
+            .await
+            .map_err(|err| tonic::Status::internal(format!("{err:#}")))?
+            .ok_or_else(|| tonic::Status::not_found(format!("verification job {id} not found")))?;
+
+        Ok(tonic::Response::new(
+            VerificationJobWrapper::from(job).into_inner(),
+        ))
+    }
+
+    async fn cancel_job(
+        &self,
+        request: tonic::Request<CancelVerificationJobRequest>,
+    ) -> Result<tonic::Response<VerificationJob>, tonic::Status> {
+        let id = request.into_inner().id;
+        let job = self
+            .queue
+            .cancel(id)
+            .await
+            .map_err(|err| tonic::Status::internal(format!("{err:#}")))?
+            .ok_or_else(|| tonic::Status::not_found(format!("verification job {id} not found")))?;
+        if job.status != JobStatus::Cancelled {
+            return Err(tonic::Status::failed_precondition(format!(
+                "verification job {id} has already been completed"
+            )));
+        }
+
+        Ok(tonic::Response::new(
+            VerificationJobWrapper::from(job).into_inner(),
+        ))
+    }
+
+    async fn get_queue_state(
+        &self,
+        request: tonic::Request<GetQueueStateRequest>,
+    ) -> Result<tonic::Response<QueueState>, tonic::Status> {
+        let recent_failures_limit = request
+            .into_inner()
+            .recent_failures_limit
+            .unwrap_or(DEFAULT_RECENT_FAILURES_LIMIT)
+            .min(MAX_RECENT_FAILURES_LIMIT);
+        let state = self
+            .queue
+            .state(recent_failures_limit.into())
+            .await
+            .map_err(|err| tonic::Status::internal(format!("{err:#}")))?;
+
+        Ok(tonic::Response::new(
+            QueueStateWrapper::from(state).into_inner(),
+        ))
+    }
+}
@@ -0,0 +1,113 @@
+use super::verifier_base;
+use crate::{
+    proto::{
+        verification_previews_server::VerificationPreviews, ConfirmVerificationPreviewRequest,
+        VerificationPreview, VerifyResponse, VerifySolidityMultiPartRequest,
+        VerifySolidityStandardJsonRequest, VerifyVyperMultiPartRequest,
+    },
+    settings::PreviewsSettings,
+    types::{
+        VerificationPreviewWrapper, VerifySolidityMultiPartRequestWrapper,
+        VerifySolidityStandardJsonRequestWrapper, VerifyVyperMultiPartRequestWrapper,
+    },
+};
+use amplify::Wrapper;
+use async_trait::async_trait;
+use eth_bytecode_db::verification::{
+    preview::{self, Preview},
+    solidity_multi_part, solidity_standard_json, vyper_multi_part, Client, Error,
+    VerificationRequest,
+};
+use sea_orm::prelude::Uuid;
+use std::{str::FromStr, time::Duration};
+
+pub struct VerificationPreviewsService {
+    client: Client,
+    ttl: Duration,
+}
+
+impl VerificationPreviewsService {
+    pub fn new(client: Client, settings: &PreviewsSettings) -> Self {
+        Self {
+            client,
+            ttl: settings.ttl,
+        }
+    }
+}
+
+#[async_trait]
+impl VerificationPreviews for VerificationPreviewsService {
+    async fn preview_solidity_multi_part(
+        &self,
+        request: tonic::Request<VerifySolidityMultiPartRequest>,
+    ) -> Result<tonic::Response<VerificationPreview>, tonic::Status> {
+        let verification_request: VerificationRequest<_> =
+            VerifySolidityMultiPartRequestWrapper::from_inner(request.into_inner()).try_into()?;
+        let result =
+            solidity_multi_part::preview(self.client.clone(), verification_request, self.ttl).await;
+
+        process_preview_result(result)
+    }
+
+    async fn preview_solidity_standard_json(
+        &self,
+        request: tonic::Request<VerifySolidityStandardJsonRequest>,
+    ) -> Result<tonic::Response<VerificationPreview>, tonic::Status> {
+        let verification_request: VerificationRequest<_> =
+            VerifySolidityStandardJsonRequestWrapper::from_inner(request.into_inner())
+                .try_into()?;
+        let result =
+            solidity_standard_json::preview(self.client.clone(), verification_request, self.ttl)
+                .await;
+
+        process_preview_result(result)
+    }
+
+    async fn preview_vyper_multi_part(
+        &self,
+        request: tonic::Request<VerifyVyperMultiPartRequest>,
+    ) -> Result<tonic::Response<VerificationPreview>, tonic::Status> {
+        let verification_request: VerificationRequest<_> =
+            VerifyVyperMultiPartRequestWrapper::from_inner(request.into_inner()).try_into()?;
+        let result =
+            vyper_multi_part::preview(self.client.clone(), verification_request, self.ttl).await;
+
+        process_preview_result(result)
+    }
+
+    async fn confirm(
+        &self,
+        request: tonic::Request<ConfirmVerificationPreviewRequest>,
+    ) -> Result<tonic::Response<VerifyResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let token = Uuid::from_str(&request.token)
+            .map_err(|_err| tonic::Status::invalid_argument("Invalid preview token"))?;
+
+        let result = preview::confirm(&self.client, token).await;
+        if let Ok(source) = &result {
+            tracing::info!(
+                %token,
+                file_name = %source.file_name,
+                contract_name = %source.contract_name,
+                "verification preview has been confirmed"
+            );
+        }
+
+        verifier_base::process_verification_result(result)
+    }
+}
+
+fn process_preview_result(
+    result: Result<Preview, Error>,
+) -> Result<tonic::Response<VerificationPreview>, tonic::Status> {
+    match result {
+        Ok(preview) => Ok(tonic::Response::new(
+            VerificationPreviewWrapper::from(preview).into_inner(),
+        )),
+        Err(Error::VerificationFailed { message }) => Ok(tonic::Response::new(
+            VerificationPreviewWrapper::err(message).into_inner(),
+        )),
+        Err(Error::InvalidArgument(message)) => Err(tonic::Status::invalid_argument(message)),
+        Err(Error::Internal(message)) => Err(tonic::Status::internal(message.to_string())),
+    }
+}
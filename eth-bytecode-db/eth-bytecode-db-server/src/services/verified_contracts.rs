@@ -0,0 +1,362 @@
+use crate::{
+    proto::{
+        verified_contracts_server::VerifiedContracts, ContractPage, GetContractPageRequest,
+        GetMethodSourceRequest, InvalidateVerifiedContractsRequest,
+        InvalidateVerifiedContractsResponse, ListVerifiedContractsByLicenseRequest,
+        ListVerifiedContractsByLicenseResponse, ListVerifiedContractsHistoryRequest,
+        ListVerifiedContractsHistoryResponse, MethodSource, SearchAcrossChainsRequest,
+        SearchAcrossChainsResponse, SearchByAbiRequest, SearchByAbiResponse,
+        SearchSourceCodeRequest, SearchSourceCodeResponse,
+    },
+    types::{
+        AbiMatchWrapper, ChainContractWrapper, ContractPageWrapper, MethodSourceWrapper,
+        SourceCodeMatchWrapper, StaleReasonWrapper, VerifiedContractWrapper,
+    },
+};
+use amplify::Wrapper;
+use async_trait::async_trait;
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use eth_bytecode_db::{
+    abi_search::{self, AbiQuery},
+    code_search, contract_pages, method_sources,
+    multichain_search::{self, SearchQuery},
+    verified_contracts,
+};
+use sea_orm::DatabaseConnection;
+use std::{str::FromStr, sync::Arc};
+
+const DEFAULT_LIST_LIMIT: u64 = 50;
+const MAX_LIST_LIMIT: u64 = 1000;
+/// Highlights of each found file are built on the fly, so the pages are smaller
+const MAX_CODE_SEARCH_LIMIT: u64 = 100;
+
+pub struct VerifiedContractsService {
+    db_client: Arc<DatabaseConnection>,
+    /// Is used by lookups and searches. May point to a read replica
+    read_db_client: Arc<DatabaseConnection>,
+    invalidation_enabled: bool,
+}
+
+impl VerifiedContractsService {
+    pub fn new(
+        db_client: Arc<DatabaseConnection>,
+        read_db_client: Arc<DatabaseConnection>,
+        invalidation_enabled: bool,
+    ) -> Self {
+        Self {
+            db_client,
+            read_db_client,
+            invalidation_enabled,
+        }
+    }
+}
+
+#[async_trait]
+impl VerifiedContracts for VerifiedContractsService {
+    async fn invalidate(
+        &self,
+        request: tonic::Request<InvalidateVerifiedContractsRequest>,
+    ) -> Result<tonic::Response<InvalidateVerifiedContractsResponse>, tonic::Status> {
+        if !self.invalidation_enabled {
+            return Err(tonic::Status::unimplemented(
+                "Invalidation of verified contracts is disabled",
+            ));
+        }
+
+        let request = request.into_inner();
+        let (chain_id, contract_address) =
+            parse_address(&request.chain_id, &request.contract_address)?;
+        let reason = StaleReasonWrapper::from_inner(request.reason()).try_into()?;
+
+        let invalidated = verified_contracts::invalidate(
+            self.db_client.as_ref(),
+            chain_id,
+            &contract_address,
+            reason,
+        )
+        .await
+        .map_err(|err| tonic::Status::internal(format!("{err:#}")))?;
+        if invalidated > 0 {
+            tracing::info!(
+                chain_id,
+                contract_address = %request.contract_address,
+                ?reason,
+                invalidated,
+                "verified contracts have been marked as stale"
+            );
+        }
+
+        Ok(tonic::Response::new(InvalidateVerifiedContractsResponse {
+            invalidated,
+        }))
+    }
+
+    async fn list_history(
+        &self,
+        request: tonic::Request<ListVerifiedContractsHistoryRequest>,
+    ) -> Result<tonic::Response<ListVerifiedContractsHistoryResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let (chain_id, contract_address) =
+            parse_address(&request.chain_id, &request.contract_address)?;
+
+        let verified_contracts =
+            verified_contracts::history(self.read_db_client.as_ref(), chain_id, &contract_address)
+                .await
+                .map_err(|err| tonic::Status::internal(format!("{err:#}")))?
+                .into_iter()
+                .map(|contract| VerifiedContractWrapper::from(contract).into_inner())
+                .collect();
+
+        Ok(tonic::Response::new(ListVerifiedContractsHistoryResponse {
+            verified_contracts,
+        }))
+    }
+
+    async fn list_by_license(
+        &self,
+        request: tonic::Request<ListVerifiedContractsByLicenseRequest>,
+    ) -> Result<tonic::Response<ListVerifiedContractsByLicenseResponse>, tonic::Status> {
+        let request = request.into_inner();
+        if request.license.trim().is_empty() {
+            return Err(tonic::Status::invalid_argument("License is not specified"));
+        }
+        let chain_id = request
+            .chain_id
+            .as_deref()
+            .map(i64::from_str)
+            .transpose()
+            .map_err(|_err| tonic::Status::invalid_argument("Invalid chain id"))?;
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_LIST_LIMIT)
+            .min(MAX_LIST_LIMIT);
+
+        let verified_contracts = verified_contracts::list_by_license(
+            self.read_db_client.as_ref(),
+            &request.license,
+            chain_id,
+            request.after_id,
+            limit,
+        )
+        .await
+        .map_err(|err| tonic::Status::internal(format!("{err:#}")))?;
+        let last_id = verified_contracts.last().map(|contract| contract.id);
+
+        Ok(tonic::Response::new(
+            ListVerifiedContractsByLicenseResponse {
+                verified_contracts: verified_contracts
+                    .into_iter()
+                    .map(|contract| VerifiedContractWrapper::from(contract).into_inner())
+                    .collect(),
+                last_id,
+            },
+        ))
+    }
+
+    async fn get_contract_page(
+        &self,
+        request: tonic::Request<GetContractPageRequest>,
+    ) -> Result<tonic::Response<ContractPage>, tonic::Status> {
+        let request = request.into_inner();
+        let (chain_id, contract_address) =
+            parse_address(&request.chain_id, &request.contract_address)?;
+
+        let page = contract_pages::get(self.read_db_client.as_ref(), chain_id, &contract_address)
+            .await
+            .map_err(|err| tonic::Status::internal(format!("{err:#}")))?
+            .ok_or_else(|| tonic::Status::not_found("Verified contract not found"))?;
+
+        Ok(tonic::Response::new(
+            ContractPageWrapper::from(page).into_inner(),
+        ))
+    }
+
+    async fn get_method_source(
+        &self,
+        request: tonic::Request<GetMethodSourceRequest>,
+    ) -> Result<tonic::Response<MethodSource>, tonic::Status> {
+        let request = request.into_inner();
+        let (chain_id, contract_address) =
+            parse_address(&request.chain_id, &request.contract_address)?;
+        let selector = DisplayBytes::from_str(&request.selector)
+            .ok()
+            .and_then(|selector| <[u8; 4]>::try_from(selector.to_vec()).ok())
+            .ok_or_else(|| tonic::Status::invalid_argument("Invalid selector"))?;
+
+        let method_source = method_sources::get(
+            self.read_db_client.as_ref(),
+            chain_id,
+            &contract_address,
+            selector,
+        )
+        .await
+        .map_err(|err| tonic::Status::internal(format!("{err:#}")))?
+        .ok_or_else(|| tonic::Status::not_found("Method source not found"))?;
+
+        Ok(tonic::Response::new(
+            MethodSourceWrapper::from(method_source).into_inner(),
+        ))
+    }
+
+    async fn search_across_chains(
+        &self,
+        request: tonic::Request<SearchAcrossChainsRequest>,
+    ) -> Result<tonic::Response<SearchAcrossChainsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let parse_bytes = |value: &str, error: &'static str| {
+            DisplayBytes::from_str(value)
+                .map(|bytes| bytes.to_vec())
+                .map_err(|_err| tonic::Status::invalid_argument(error))
+        };
+        let query = match (
+            request.contract_address.as_deref(),
+            request.contract_name,
+            request.code_hash.as_deref(),
+        ) {
+            (Some(address), None, None) => {
+                SearchQuery::ContractAddress(parse_bytes(address, "Invalid contract address")?)
+            }
+            (None, Some(name), None) if !name.trim().is_empty() => SearchQuery::ContractName(name),
+            (None, None, Some(code_hash)) => {
+                SearchQuery::CodeHash(parse_bytes(code_hash, "Invalid code hash")?)
+            }
+            _ => {
+                return Err(tonic::Status::invalid_argument(
+                    "Exactly one search parameter should be specified",
+                ))
+            }
+        };
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_LIST_LIMIT)
+            .min(MAX_LIST_LIMIT);
+
+        let contracts = multichain_search::search(self.read_db_client.as_ref(), &query, limit)
+            .await
+            .map_err(|err| tonic::Status::internal(format!("{err:#}")))?
+            .into_iter()
+            .map(|contract| ChainContractWrapper::from(contract).into_inner())
+            .collect();
+
+        Ok(tonic::Response::new(SearchAcrossChainsResponse {
+            contracts,
+        }))
+    }
+
+    async fn search_source_code(
+        &self,
+        request: tonic::Request<SearchSourceCodeRequest>,
+    ) -> Result<tonic::Response<SearchSourceCodeResponse>, tonic::Status> {
+        let request = request.into_inner();
+        if !code_search::is_searchable(&request.query) {
+            return Err(tonic::Status::invalid_argument(
+                "Query should contain at least one word",
+            ));
+        }
+        let chain_id = request
+            .chain_id
+            .as_deref()
+            .map(i64::from_str)
+            .transpose()
+            .map_err(|_err| tonic::Status::invalid_argument("Invalid chain id"))?;
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_LIST_LIMIT)
+            .min(MAX_CODE_SEARCH_LIMIT);
+
+        let matches = code_search::search(
+            self.read_db_client.as_ref(),
+            &request.query,
+            chain_id,
+            request.after_id,
+            limit,
+        )
+        .await
+        .map_err(|err| tonic::Status::internal(format!("{err:#}")))?;
+        let last_id = matches
+            .last()
+            .map(|code_match| code_match.verified_contract_id);
+
+        Ok(tonic::Response::new(SearchSourceCodeResponse {
+            matches: matches
+                .into_iter()
+                .map(|code_match| SourceCodeMatchWrapper::from(code_match).into_inner())
+                .collect(),
+            last_id,
+        }))
+    }
+
+    async fn search_by_abi(
+        &self,
+        request: tonic::Request<SearchByAbiRequest>,
+    ) -> Result<tonic::Response<SearchByAbiResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let query = match (request.selector.as_deref(), request.signature.as_deref()) {
+            (Some(selector), None) => {
+                let selector = DisplayBytes::from_str(selector)
+                    .map(|selector| selector.to_vec())
+                    .map_err(|_err| tonic::Status::invalid_argument("Invalid selector"))?;
+                if selector.len() != 4 && selector.len() != 32 {
+                    return Err(tonic::Status::invalid_argument(
+                        "Selector should be either 4 or 32 bytes long",
+                    ));
+                }
+                AbiQuery::Selector(selector)
+            }
+            (None, Some(signature)) => {
+                let signature = abi_search::canonical_signature(signature).map_err(|err| {
+                    tonic::Status::invalid_argument(format!("Invalid signature: {err:#}"))
+                })?;
+                AbiQuery::Signature(signature)
+            }
+            _ => {
+                return Err(tonic::Status::invalid_argument(
+                    "Exactly one of selector or signature should be specified",
+                ))
+            }
+        };
+        let chain_id = request
+            .chain_id
+            .as_deref()
+            .map(i64::from_str)
+            .transpose()
+            .map_err(|_err| tonic::Status::invalid_argument("Invalid chain id"))?;
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_LIST_LIMIT)
+            .min(MAX_LIST_LIMIT);
+
+        let matches = abi_search::search(
+            self.read_db_client.as_ref(),
+            &query,
+            chain_id,
+            request.after_id,
+            limit,
+        )
+        .await
+        .map_err(|err| tonic::Status::internal(format!("{err:#}")))?;
+        let last_id = matches
+            .last()
+            .map(|abi_match| abi_match.verified_contract_id);
+
+        Ok(tonic::Response::new(SearchByAbiResponse {
+            matches: matches
+                .into_iter()
+                .map(|abi_match| AbiMatchWrapper::from(abi_match).into_inner())
+                .collect(),
+            last_id,
+        }))
+    }
+}
+
+pub(super) fn parse_address(
+    chain_id: &str,
+    contract_address: &str,
+) -> Result<(i64, Vec<u8>), tonic::Status> {
+    let chain_id = i64::from_str(chain_id)
+        .map_err(|_err| tonic::Status::invalid_argument("Invalid chain id"))?;
+    let contract_address = DisplayBytes::from_str(contract_address)
+        .map_err(|_err| tonic::Status::invalid_argument("Invalid contract address"))?
+        .to_vec();
+    Ok((chain_id, contract_address))
+}
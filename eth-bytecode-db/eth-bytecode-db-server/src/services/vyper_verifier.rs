@@ -4,7 +4,7 @@ use crate::{
         vyper_verifier_server, ListCompilerVersionsRequest, ListCompilerVersionsResponse,
         VerifyResponse, VerifyVyperMultiPartRequest,
     },
-    types::{BytecodeTypeWrapper, VerificationMetadataWrapper},
+    types::VerifyVyperMultiPartRequestWrapper,
 };
 use amplify::Wrapper;
 use async_trait::async_trait;
@@ -28,23 +28,8 @@ impl vyper_verifier_server::VyperVerifier for VyperVerifierService {
         &self,
         request: tonic::Request<VerifyVyperMultiPartRequest>,
     ) -> Result<tonic::Response<VerifyResponse>, tonic::Status> {
-        let request = request.into_inner();
-
-        let bytecode_type = request.bytecode_type();
-        let verification_request = VerificationRequest {
-            bytecode: request.bytecode,
-            bytecode_type: BytecodeTypeWrapper::from_inner(bytecode_type).try_into()?,
-            compiler_version: request.compiler_version,
-            content: vyper_multi_part::MultiPartFiles {
-                source_files: request.source_files,
-                evm_version: request.evm_version,
-                optimizations: request.optimizations,
-            },
-            metadata: request
-                .metadata
-                .map(|metadata| VerificationMetadataWrapper::from_inner(metadata).try_into())
-                .transpose()?,
-        };
+        let verification_request: VerificationRequest<_> =
+            VerifyVyperMultiPartRequestWrapper::from_inner(request.into_inner()).try_into()?;
         let result = vyper_multi_part::verify(self.client.clone(), verification_request).await;
 
         verifier_base::process_verification_result(result)
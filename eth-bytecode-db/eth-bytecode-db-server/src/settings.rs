@@ -3,7 +3,8 @@ use blockscout_service_launcher::{
 };
 use config::{Config, File};
 use serde::{de, Deserialize};
-use serde_with::{serde_as, DisplayFromStr};
+use serde_with::{serde_as, DisplayFromStr, DurationMilliSeconds, DurationSeconds};
+use std::time::Duration;
 
 /// Wrapper under [`serde::de::IgnoredAny`] which implements
 /// [`PartialEq`] and [`Eq`] for fields to be ignored.
@@ -34,6 +35,23 @@ pub struct Settings {
     pub database: DatabaseSettings,
     pub verifier: VerifierSettings,
 
+    #[serde(default)]
+    pub reverification: ReverificationSettings,
+    #[serde(default)]
+    pub jobs: JobsSettings,
+    #[serde(default)]
+    pub retention: RetentionSettings,
+    #[serde(default)]
+    pub verified_contracts: VerifiedContractsSettings,
+    #[serde(default)]
+    pub previews: PreviewsSettings,
+    #[serde(default)]
+    pub events: EventsSettings,
+    #[serde(default)]
+    pub labels: LabelsSettings,
+    #[serde(default)]
+    pub source_checks: SourceChecksSettings,
+
     // Is required as we deny unknown fields, but allow users provide
     // path to config through PREFIX__CONFIG env variable. If removed,
     // the setup would fail with `unknown field `config`, expected one of...`
@@ -46,6 +64,46 @@ pub struct Settings {
 pub struct DatabaseSettings {
     pub url: String,
     pub run_migrations: bool,
+    #[serde(default)]
+    pub pool: DatabasePoolSettings,
+    /// Read-only replica the lookup and search endpoints are served from, so that
+    /// heavy read traffic does not slow down verification writes to the primary.
+    /// If not set, all queries go to the primary.
+    #[serde(default)]
+    pub replica: Option<DatabaseReplicaSettings>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DatabaseReplicaSettings {
+    pub url: String,
+    #[serde(default)]
+    pub pool: DatabasePoolSettings,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabasePoolSettings {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// Time (in seconds) to wait for the connection to be established or acquired from the pool
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub connect_timeout: Duration,
+    /// If set, statements running longer (in milliseconds) are cancelled by the database
+    #[serde_as(as = "Option<DurationMilliSeconds<u64>>")]
+    pub statement_timeout: Option<Duration>,
+}
+
+impl Default for DatabasePoolSettings {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            min_connections: 0,
+            connect_timeout: Duration::from_secs(30),
+            statement_timeout: None,
+        }
+    }
 }
 
 #[serde_as]
@@ -56,6 +114,111 @@ pub struct VerifierSettings {
     pub uri: tonic::transport::Uri,
 }
 
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReverificationSettings {
+    /// Enables the admin endpoint triggering re-verification of stored contracts.
+    /// The endpoint is not protected, so should not be exposed publicly.
+    pub admin_endpoint_enabled: bool,
+    /// If set, all stored contracts are periodically re-verified with the given interval (in seconds)
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub interval: Option<Duration>,
+    /// Number of verified contracts retrieved from the database at once
+    pub batch_size: u64,
+}
+
+impl Default for ReverificationSettings {
+    fn default() -> Self {
+        Self {
+            admin_endpoint_enabled: false,
+            interval: None,
+            batch_size: 100,
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsSettings {
+    /// Enables the endpoints verifying contracts asynchronously via persisted jobs.
+    /// Jobs are processed by all replicas sharing the database.
+    pub enabled: bool,
+    /// Identifies the replica holding job leases.
+    /// Defaults to the host name combined with the process id.
+    pub worker_id: Option<String>,
+    /// Maximum number of interactive jobs processed by the replica at once
+    pub interactive_concurrency: usize,
+    /// Maximum number of bulk jobs processed by the replica at once.
+    /// Is independent of the interactive one, so bulk jobs never delay interactive ones.
+    pub bulk_concurrency: usize,
+    /// Number of times processing of the job may be interrupted
+    /// before the job is marked as orphaned
+    pub max_attempts: u32,
+    /// Time (in seconds) the job is claimed by the replica for, unless the lease is renewed
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub lease_duration: Duration,
+    /// Delay (in milliseconds) before looking for new jobs if the queue has been empty
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub poll_interval: Duration,
+    /// Header containing the api key of the submitter
+    pub api_key_header: String,
+    /// Api keys allowed to submit interactive jobs. Jobs submitted without
+    /// any of them are processed as bulk ones. If empty, any submitter is allowed.
+    pub interactive_api_keys: Vec<String>,
+}
+
+impl Default for JobsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            worker_id: None,
+            interactive_concurrency: 4,
+            bulk_concurrency: 2,
+            max_attempts: 3,
+            lease_duration: Duration::from_secs(60),
+            poll_interval: Duration::from_millis(1000),
+            api_key_header: "x-api-key".to_string(),
+            interactive_api_keys: vec![],
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionSettings {
+    /// Enables periodic cleanup of the stored verification jobs.
+    /// Only the policies configured below are applied.
+    pub enabled: bool,
+    /// Time (in seconds) between the cleanups. Defaults to once a day.
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub interval: Duration,
+    /// Requests of failed, orphaned, and cancelled jobs are purged after the number of days
+    pub purge_failed_after_days: Option<u64>,
+    /// Results of succeeded jobs are compressed after the number of days
+    pub compress_after_days: Option<u64>,
+    /// Maximum total size (in bytes) of the stored requests and results of completed jobs.
+    /// The least recently retrieved jobs are deleted when exceeded.
+    pub max_store_size: Option<u64>,
+    /// Number of results compressed at once
+    pub batch_size: u64,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(24 * 60 * 60),
+            purge_failed_after_days: None,
+            compress_after_days: None,
+            max_store_size: None,
+            batch_size: 100,
+        }
+    }
+}
+
 impl Settings {
     pub fn new() -> anyhow::Result<Self> {
         let config_path = std::env::var("ETH_BYTECODE_DB__CONFIG");
@@ -69,10 +232,19 @@ impl Settings {
             builder.add_source(config::Environment::with_prefix("ETH_BYTECODE_DB").separator("__"));
 
         let settings: Settings = builder.build()?.try_deserialize()?;
+        settings.validate()?;
 
         Ok(settings)
     }
 
+    fn validate(&self) -> anyhow::Result<()> {
+        // Leases are renewed and recovered periodically, which requires non-zero periods
+        if self.jobs.lease_duration.is_zero() {
+            anyhow::bail!("jobs.lease_duration should be at least one second");
+        }
+        Ok(())
+    }
+
     pub fn default(database_url: String, verifier_uri: tonic::transport::Uri) -> Self {
         Self {
             server: Default::default(),
@@ -82,9 +254,149 @@ impl Settings {
             database: DatabaseSettings {
                 url: database_url,
                 run_migrations: false,
+                pool: Default::default(),
+                replica: None,
             },
             verifier: VerifierSettings { uri: verifier_uri },
+            reverification: Default::default(),
+            jobs: Default::default(),
+            retention: Default::default(),
+            verified_contracts: Default::default(),
+            previews: Default::default(),
+            events: Default::default(),
+            labels: Default::default(),
+            source_checks: Default::default(),
             config_path: Default::default(),
         }
     }
 }
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerifiedContractsSettings {
+    /// Enables the endpoint the indexer calls to mark verified contracts as stale,
+    /// when the bytecode deployed at the address changes (reorg, selfdestruct, or redeployment).
+    /// The endpoint is not protected, so should not be exposed publicly.
+    pub invalidation_endpoint_enabled: bool,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreviewsSettings {
+    /// Enables the endpoints verifying contracts without publishing the sources
+    /// until the returned preview is confirmed.
+    pub enabled: bool,
+    /// Time (in seconds) the preview could be confirmed within
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub ttl: Duration,
+}
+
+impl Default for PreviewsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventsSettings {
+    /// Enables publishing of the verification events (verified contracts
+    /// and verification job status changes) to the message broker.
+    pub enabled: bool,
+    pub broker: EventsBrokerSettings,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum EventsBrokerSettings {
+    Kafka(KafkaSettings),
+    Nats(NatsSettings),
+}
+
+impl Default for EventsBrokerSettings {
+    fn default() -> Self {
+        Self::Nats(Default::default())
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct KafkaSettings {
+    /// Comma separated list of the bootstrap servers
+    pub brokers: String,
+    pub topic: String,
+    /// Time (in milliseconds) the event may wait in the producer queue before being dropped
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub timeout: Duration,
+}
+
+impl Default for KafkaSettings {
+    fn default() -> Self {
+        Self {
+            brokers: "localhost:9092".to_string(),
+            topic: "eth-bytecode-db.verification-events".to_string(),
+            timeout: Duration::from_millis(5000),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NatsSettings {
+    pub url: String,
+    /// Events are published to `{subject}.{event_type}` subjects
+    pub subject: String,
+}
+
+impl Default for NatsSettings {
+    fn default() -> Self {
+        Self {
+            url: "nats://localhost:4222".to_string(),
+            subject: "eth_bytecode_db.verification_events".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LabelsSettings {
+    /// Header containing the api key of the operator
+    pub api_key_header: String,
+    /// Api keys allowed to create, update, and delete contract labels.
+    /// If empty, labels could not be modified. Labels are readable by anyone.
+    pub operator_api_keys: Vec<String>,
+}
+
+impl Default for LabelsSettings {
+    fn default() -> Self {
+        Self {
+            api_key_header: "x-api-key".to_string(),
+            operator_api_keys: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SourceChecksSettings {
+    /// Warns about "Trojan Source" tricks (CVE-2021-42574): bidirectional control
+    /// and invisible characters, and words imitating other words with look-alike letters
+    pub unicode_enabled: bool,
+    /// Warns about solidity functions named as getters (e.g., `getOwner`)
+    /// which are neither view nor pure
+    pub misleading_getters_enabled: bool,
+}
+
+impl Default for SourceChecksSettings {
+    fn default() -> Self {
+        Self {
+            unicode_enabled: true,
+            misleading_getters_enabled: true,
+        }
+    }
+}
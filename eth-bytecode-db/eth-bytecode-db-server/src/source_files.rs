@@ -0,0 +1,159 @@
+//! Http endpoints to download files of the stored sources separately,
+//! for the sources too large to be conveniently returned inline.
+//! Are not part of the grpc api, as return the files as is.
+
+use actix_web::{error, http::header, web, HttpResponse};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use eth_bytecode_db::source_files;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Write},
+    str::FromStr,
+    sync::Arc,
+};
+
+#[derive(Debug, Serialize)]
+struct FileNamesResponse {
+    files: Vec<String>,
+    /// Map from the file name to the sha256 hash of its content.
+    /// The content may be downloaded via `/api/v2/sources/{content_hash}`
+    content_hashes: BTreeMap<String, String>,
+}
+
+pub fn route_source_files(
+    service_config: &mut web::ServiceConfig,
+    db_client: Arc<DatabaseConnection>,
+) {
+    service_config
+        .app_data(web::Data::from(db_client))
+        .route(
+            "/api/v2/sources/{source_id}/files",
+            web::get().to(file_names),
+        )
+        .route(
+            "/api/v2/sources/{source_id}/files/{file_name:.*}",
+            web::get().to(file),
+        )
+        .route(
+            "/api/v2/sources/{source_id}/sources.zip",
+            web::get().to(zip_archive),
+        )
+        .route("/api/v2/sources/{content_hash}", web::get().to(content));
+}
+
+async fn file_names(
+    db_client: web::Data<DatabaseConnection>,
+    source_id: web::Path<i64>,
+) -> Result<HttpResponse, error::Error> {
+    let files = source_files::file_names(db_client.as_ref(), *source_id)
+        .await
+        .map_err(error::ErrorInternalServerError)?
+        .ok_or_else(|| error::ErrorNotFound("source not found"))?;
+    let content_hashes = files
+        .iter()
+        .map(|(name, content_hash)| {
+            let content_hash = DisplayBytes::from(content_hash.clone()).to_string();
+            (name.clone(), content_hash)
+        })
+        .collect();
+    let files = files.into_iter().map(|(name, _)| name).collect();
+    Ok(HttpResponse::Ok().json(FileNamesResponse {
+        files,
+        content_hashes,
+    }))
+}
+
+async fn file(
+    db_client: web::Data<DatabaseConnection>,
+    path: web::Path<(i64, String)>,
+) -> Result<HttpResponse, error::Error> {
+    let (source_id, file_name) = path.into_inner();
+    let content = source_files::file(db_client.as_ref(), source_id, &file_name)
+        .await
+        .map_err(error::ErrorInternalServerError)?
+        .ok_or_else(|| error::ErrorNotFound("file not found"))?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(content))
+}
+
+/// Content is immutable for the hash, so may be cached by the clients indefinitely.
+async fn content(
+    db_client: web::Data<DatabaseConnection>,
+    content_hash: web::Path<String>,
+) -> Result<HttpResponse, error::Error> {
+    let content_hash = DisplayBytes::from_str(&content_hash)
+        .map_err(|err| error::ErrorBadRequest(format!("invalid content hash: {err}")))?;
+    let content = source_files::content(db_client.as_ref(), &content_hash)
+        .await
+        .map_err(error::ErrorInternalServerError)?
+        .ok_or_else(|| error::ErrorNotFound("content not found"))?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .insert_header((header::CACHE_CONTROL, "public, max-age=31536000, immutable"))
+        .body(content))
+}
+
+async fn zip_archive(
+    db_client: web::Data<DatabaseConnection>,
+    source_id: web::Path<i64>,
+) -> Result<HttpResponse, error::Error> {
+    let source_id = source_id.into_inner();
+    let files = source_files::files(db_client.as_ref(), source_id)
+        .await
+        .map_err(error::ErrorInternalServerError)?
+        .ok_or_else(|| error::ErrorNotFound("source not found"))?
+        .into_iter()
+        .collect();
+
+    let archive = web::block(move || zip_files(files))
+        .await?
+        .map_err(error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"sources-{source_id}.zip\""),
+        ))
+        .body(archive))
+}
+
+fn zip_files(files: Vec<(String, String)>) -> zip::result::ZipResult<Vec<u8>> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in files {
+        // Absolute paths are stored as relative ones to be extracted into the target directory
+        writer.start_file(name.trim_start_matches('/'), options)?;
+        writer.write_all(content.as_bytes())?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Read;
+
+    #[test]
+    fn zip_and_unzip_files() {
+        let files = vec![
+            ("/contracts/A.sol".to_string(), "contract A {}".to_string()),
+            ("B.sol".to_string(), "contract B {}".to_string()),
+        ];
+        let archive = zip_files(files).expect("zip should be created");
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(archive)).expect("valid zip");
+        assert_eq!(2, archive.len());
+        let mut content = String::new();
+        archive
+            .by_name("contracts/A.sol")
+            .expect("file should exist")
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!("contract A {}", content);
+    }
+}
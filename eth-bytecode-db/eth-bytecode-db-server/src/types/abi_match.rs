@@ -0,0 +1,58 @@
+use crate::proto;
+use amplify::{From, Wrapper};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use eth_bytecode_db::abi_search;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct AbiMatchWrapper(proto::AbiMatch);
+
+impl From<abi_search::AbiMatch> for AbiMatchWrapper {
+    fn from(value: abi_search::AbiMatch) -> Self {
+        Self(proto::AbiMatch {
+            verified_contract_id: value.verified_contract_id,
+            chain_id: value.chain_id.map(|chain_id| chain_id.to_string()),
+            contract_address: value
+                .contract_address
+                .map(|address| DisplayBytes::from(address).to_string()),
+            contract_name: value.contract_name,
+            compiler_version: value.compiler_version,
+            kind: value.kind,
+            signature: value.signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_abi_match() {
+        let abi_match = abi_search::AbiMatch {
+            verified_contract_id: 7,
+            chain_id: Some(5),
+            contract_address: Some(vec![0x12; 20]),
+            contract_name: "Token".into(),
+            compiler_version: "v0.8.19+commit.7dd6d404".into(),
+            kind: "event".into(),
+            signature: "Transfer(address,address,uint256)".into(),
+        };
+
+        let expected = proto::AbiMatch {
+            verified_contract_id: 7,
+            chain_id: Some("5".into()),
+            contract_address: Some("0x1212121212121212121212121212121212121212".into()),
+            contract_name: "Token".into(),
+            compiler_version: "v0.8.19+commit.7dd6d404".into(),
+            kind: "event".into(),
+            signature: "Transfer(address,address,uint256)".into(),
+        };
+
+        assert_eq!(
+            expected,
+            AbiMatchWrapper::from(abi_match).into_inner(),
+            "Invalid abi match conversion"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use super::verified_contract::format_timestamp;
+use crate::proto;
+use amplify::{From, Wrapper};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use eth_bytecode_db::multichain_search;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct ChainContractWrapper(proto::ChainContract);
+
+impl From<multichain_search::ChainContract> for ChainContractWrapper {
+    fn from(value: multichain_search::ChainContract) -> Self {
+        Self(proto::ChainContract {
+            verified_contract_id: value.verified_contract_id,
+            chain_id: value.chain_id.to_string(),
+            contract_address: DisplayBytes::from(value.contract_address).to_string(),
+            contract_name: value.contract_name,
+            file_name: value.file_name,
+            compiler_version: value.compiler_version,
+            code_hash: value
+                .code_hash
+                .map(|code_hash| DisplayBytes::from(code_hash).to_string()),
+            verified_at: format_timestamp(value.verified_at),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use sea_orm::prelude::DateTime;
+
+    #[test]
+    fn from_chain_contract() {
+        let contract = multichain_search::ChainContract {
+            verified_contract_id: 3,
+            chain_id: 100,
+            contract_address: vec![0x12; 20],
+            contract_name: "Token".into(),
+            file_name: "Token.sol".into(),
+            compiler_version: "v0.8.19+commit.7dd6d404".into(),
+            code_hash: Some(vec![0xab; 32]),
+            verified_at: DateTime::parse_from_str("2023-11-01 10:00:00", "%Y-%m-%d %H:%M:%S")
+                .expect("valid timestamp"),
+        };
+
+        let expected = proto::ChainContract {
+            verified_contract_id: 3,
+            chain_id: "100".into(),
+            contract_address: "0x1212121212121212121212121212121212121212".into(),
+            contract_name: "Token".into(),
+            file_name: "Token.sol".into(),
+            compiler_version: "v0.8.19+commit.7dd6d404".into(),
+            code_hash: Some(format!("0x{}", "ab".repeat(32))),
+            verified_at: "2023-11-01T10:00:00Z".into(),
+        };
+
+        assert_eq!(
+            expected,
+            ChainContractWrapper::from(contract).into_inner(),
+            "Invalid chain contract conversion"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use super::verified_contract::format_timestamp;
+use crate::proto;
+use amplify::{From, Wrapper};
+use eth_bytecode_db::labels;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct ContractLabelWrapper(proto::ContractLabel);
+
+impl From<labels::Label> for ContractLabelWrapper {
+    fn from(value: labels::Label) -> Self {
+        Self(proto::ContractLabel {
+            name: value.name,
+            description: value.description,
+            created_at: format_timestamp(value.created_at),
+            updated_at: format_timestamp(value.updated_at),
+        })
+    }
+}
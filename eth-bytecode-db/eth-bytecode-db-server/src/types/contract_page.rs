@@ -0,0 +1,154 @@
+use super::{ContractLabelWrapper, SourceWrapper, VerifiedContractWrapper};
+use crate::proto;
+use amplify::{From, Wrapper};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use eth_bytecode_db::contract_pages;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct ContractPageWrapper(proto::ContractPage);
+
+impl From<contract_pages::ContractPage> for ContractPageWrapper {
+    fn from(value: contract_pages::ContractPage) -> Self {
+        let constructor_arguments = value
+            .constructor_arguments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|argument| proto::ConstructorArgument {
+                name: argument.name,
+                r#type: argument.param_type,
+                value: argument.value,
+            })
+            .collect();
+        let immutables = value
+            .immutables
+            .into_iter()
+            .map(|immutable| proto::Immutable {
+                offset: immutable.offset as u64,
+                value: DisplayBytes::from(immutable.value).to_string(),
+            })
+            .collect();
+        Self(proto::ContractPage {
+            verified_contract: Some(
+                VerifiedContractWrapper::from(value.verified_contract).into_inner(),
+            ),
+            source: Some(SourceWrapper::from(value.contract).into_inner()),
+            constructor_arguments,
+            libraries: value.libraries,
+            immutables,
+            standards: value
+                .standards
+                .into_iter()
+                .map(|standard| standard.to_string())
+                .collect(),
+            natspec: value.natspec.map(|natspec| proto::NatSpec {
+                userdoc: natspec.userdoc.map(|doc| doc.to_string()),
+                devdoc: natspec.devdoc.map(|doc| doc.to_string()),
+            }),
+            labels: value
+                .labels
+                .into_iter()
+                .map(|label| ContractLabelWrapper::from(label).into_inner())
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_bytecode_db::{
+        labels::Label, natspec::NatSpec, search::MatchContract, standards::Standard, verification,
+        verification::preview, verified_contracts,
+    };
+    use pretty_assertions::assert_eq;
+    use sea_orm::prelude::DateTime;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn from_contract_page() {
+        let verified_contract = verified_contracts::VerifiedContract {
+            id: 2,
+            source_id: 1,
+            bytecode_type: verification::BytecodeType::DeployedBytecode,
+            chain_id: Some(5),
+            contract_address: Some(vec![0x12; 20]),
+            verified_at: DateTime::parse_from_str("2023-11-01 10:00:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            stale_at: None,
+            stale_reason: None,
+            match_type: Some(verification::MatchType::Full),
+            superseded_by: None,
+        };
+        let contract = MatchContract {
+            source_id: 1,
+            file_name: "Token.sol".to_string(),
+            contract_name: "Token".to_string(),
+            compiler_version: "v0.8.21+commit.d9974bed".to_string(),
+            compiler_settings: "{}".to_string(),
+            source_type: verification::SourceType::Solidity,
+            source_files: BTreeMap::from([("Token.sol".into(), "contract Token {}".into())]),
+            abi: Some("[]".to_string()),
+            constructor_arguments: None,
+            match_type: verification::MatchType::Full,
+            raw_creation_input: vec![],
+            raw_deployed_bytecode: vec![],
+        };
+        let page = contract_pages::ContractPage {
+            verified_contract: verified_contract.clone(),
+            contract: contract.clone(),
+            constructor_arguments: Some(vec![preview::ConstructorArgument {
+                name: "owner".to_string(),
+                param_type: "address".to_string(),
+                value: "0x00000000219ab540356cbb839cbe05303d7705fa".to_string(),
+            }]),
+            libraries: BTreeMap::from([("Math.sol:Math".into(), "0xcafe".into())]),
+            immutables: vec![contract_pages::Immutable {
+                offset: 10,
+                value: vec![0xab, 0xcd],
+            }],
+            standards: vec![Standard::Erc20, Standard::Ownable],
+            natspec: Some(NatSpec {
+                userdoc: Some(r#"{"kind": "user", "notice": "A token"}"#.parse().unwrap()),
+                devdoc: None,
+            }),
+            labels: vec![Label {
+                name: "DEX".to_string(),
+                description: Some("Decentralized exchange".to_string()),
+                created_at: verified_contract.verified_at,
+                updated_at: verified_contract.verified_at,
+            }],
+        };
+
+        let expected = proto::ContractPage {
+            verified_contract: Some(VerifiedContractWrapper::from(verified_contract).into_inner()),
+            source: Some(SourceWrapper::from(contract).into_inner()),
+            constructor_arguments: vec![proto::ConstructorArgument {
+                name: "owner".to_string(),
+                r#type: "address".to_string(),
+                value: "0x00000000219ab540356cbb839cbe05303d7705fa".to_string(),
+            }],
+            libraries: BTreeMap::from([("Math.sol:Math".into(), "0xcafe".into())]),
+            immutables: vec![proto::Immutable {
+                offset: 10,
+                value: "0xabcd".to_string(),
+            }],
+            standards: vec!["ERC20".to_string(), "Ownable".to_string()],
+            natspec: Some(proto::NatSpec {
+                userdoc: Some(r#"{"kind":"user","notice":"A token"}"#.to_string()),
+                devdoc: None,
+            }),
+            labels: vec![proto::ContractLabel {
+                name: "DEX".to_string(),
+                description: Some("Decentralized exchange".to_string()),
+                created_at: "2023-11-01T10:00:00Z".to_string(),
+                updated_at: "2023-11-01T10:00:00Z".to_string(),
+            }],
+        };
+
+        assert_eq!(
+            expected,
+            ContractPageWrapper::from(page).into_inner(),
+            "Invalid contract page conversion"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::proto;
 use amplify::{From, Wrapper};
-use eth_bytecode_db::{search, verification};
+use eth_bytecode_db::{jobs, search, verification, verified_contracts};
 
 /********** Bytecode Type **********/
 
@@ -77,6 +77,64 @@ impl From<verification::MatchType> for MatchTypeWrapper {
     }
 }
 
+/********** Job Priority **********/
+
+#[derive(Wrapper, From, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JobPriorityWrapper(proto::JobPriority);
+
+/// Unspecified priority results in `None`, so that the default one could be chosen.
+impl From<JobPriorityWrapper> for Option<jobs::JobPriority> {
+    fn from(value: JobPriorityWrapper) -> Self {
+        match value.into_inner() {
+            proto::JobPriority::Unspecified => None,
+            proto::JobPriority::Interactive => Some(jobs::JobPriority::Interactive),
+            proto::JobPriority::Bulk => Some(jobs::JobPriority::Bulk),
+        }
+    }
+}
+
+impl From<jobs::JobPriority> for JobPriorityWrapper {
+    fn from(value: jobs::JobPriority) -> Self {
+        match value {
+            jobs::JobPriority::Interactive => {
+                JobPriorityWrapper::from(proto::JobPriority::Interactive)
+            }
+            jobs::JobPriority::Bulk => JobPriorityWrapper::from(proto::JobPriority::Bulk),
+        }
+    }
+}
+
+/********** Stale Reason **********/
+
+#[derive(Wrapper, From, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StaleReasonWrapper(proto::StaleReason);
+
+impl TryFrom<StaleReasonWrapper> for verified_contracts::StaleReason {
+    type Error = tonic::Status;
+
+    fn try_from(value: StaleReasonWrapper) -> Result<Self, Self::Error> {
+        match value.into_inner() {
+            proto::StaleReason::Unspecified => Err(tonic::Status::invalid_argument(
+                "Stale reason is not specified",
+            )),
+            proto::StaleReason::Reorg => Ok(verified_contracts::StaleReason::Reorg),
+            proto::StaleReason::Selfdestruct => Ok(verified_contracts::StaleReason::Selfdestruct),
+            proto::StaleReason::Redeployed => Ok(verified_contracts::StaleReason::Redeployed),
+        }
+    }
+}
+
+impl From<verified_contracts::StaleReason> for StaleReasonWrapper {
+    fn from(value: verified_contracts::StaleReason) -> Self {
+        let reason = match value {
+            verified_contracts::StaleReason::Reorg => proto::StaleReason::Reorg,
+            verified_contracts::StaleReason::Selfdestruct => proto::StaleReason::Selfdestruct,
+            verified_contracts::StaleReason::Redeployed => proto::StaleReason::Redeployed,
+        };
+        StaleReasonWrapper::from(reason)
+    }
+}
+
 /********** Tests **********/
 
 #[cfg(test)]
@@ -164,3 +222,70 @@ mod match_type_tests {
         assert_eq!(proto_type, result);
     }
 }
+
+#[cfg(test)]
+mod job_priority_tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(proto::JobPriority::Unspecified, None)]
+    #[case(proto::JobPriority::Interactive, Some(jobs::JobPriority::Interactive))]
+    #[case(proto::JobPriority::Bulk, Some(jobs::JobPriority::Bulk))]
+    fn from_proto_to_jobs(
+        #[case] proto_priority: proto::JobPriority,
+        #[case] jobs_priority: Option<jobs::JobPriority>,
+    ) {
+        let result: Option<jobs::JobPriority> = JobPriorityWrapper::from(proto_priority).into();
+        assert_eq!(jobs_priority, result);
+    }
+
+    #[rstest]
+    #[case(jobs::JobPriority::Interactive, proto::JobPriority::Interactive)]
+    #[case(jobs::JobPriority::Bulk, proto::JobPriority::Bulk)]
+    fn from_jobs_to_proto(
+        #[case] jobs_priority: jobs::JobPriority,
+        #[case] proto_priority: proto::JobPriority,
+    ) {
+        let result = JobPriorityWrapper::from(jobs_priority).into_inner();
+        assert_eq!(proto_priority, result);
+    }
+}
+
+#[cfg(test)]
+mod stale_reason_tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use tonic::Code;
+
+    #[rstest]
+    #[case(proto::StaleReason::Reorg, verified_contracts::StaleReason::Reorg)]
+    #[case(
+        proto::StaleReason::Selfdestruct,
+        verified_contracts::StaleReason::Selfdestruct
+    )]
+    #[case(
+        proto::StaleReason::Redeployed,
+        verified_contracts::StaleReason::Redeployed
+    )]
+    fn proto_and_verified_contracts_roundtrip(
+        #[case] proto_reason: proto::StaleReason,
+        #[case] reason: verified_contracts::StaleReason,
+    ) {
+        let result =
+            verified_contracts::StaleReason::try_from(StaleReasonWrapper::from(proto_reason))
+                .expect("Valid reason should not result in error");
+        assert_eq!(reason, result);
+        assert_eq!(proto_reason, StaleReasonWrapper::from(reason).into_inner());
+    }
+
+    #[test]
+    fn try_from_proto_unspecified() {
+        let wrapper = StaleReasonWrapper::from(proto::StaleReason::Unspecified);
+        let err = verified_contracts::StaleReason::try_from(wrapper)
+            .expect_err("Unspecified should result in error");
+        assert_eq!(err.code(), Code::InvalidArgument, "Invalid error code");
+    }
+}
@@ -0,0 +1,45 @@
+use crate::proto;
+use amplify::{From, Wrapper};
+use eth_bytecode_db::method_sources;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct MethodSourceWrapper(proto::MethodSource);
+
+impl From<method_sources::MethodSource> for MethodSourceWrapper {
+    fn from(value: method_sources::MethodSource) -> Self {
+        Self(proto::MethodSource {
+            file_name: value.file_name,
+            line_start: value.line_start as u64,
+            line_end: value.line_end as u64,
+            code: value.code,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_method_source() {
+        let method_source = method_sources::MethodSource {
+            file_name: "contracts/Token.sol".to_string(),
+            line_start: 10,
+            line_end: 12,
+            code: "function pause() public {\n}".to_string(),
+        };
+        let expected = proto::MethodSource {
+            file_name: "contracts/Token.sol".to_string(),
+            line_start: 10,
+            line_end: 12,
+            code: "function pause() public {\n}".to_string(),
+        };
+
+        assert_eq!(
+            expected,
+            MethodSourceWrapper::from(method_source).into_inner(),
+            "Invalid method source conversion"
+        );
+    }
+}
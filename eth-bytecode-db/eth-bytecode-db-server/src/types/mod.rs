@@ -1,9 +1,39 @@
+mod abi_match;
+mod chain_contract;
+mod contract_label;
+mod contract_page;
 mod enums;
+mod method_source;
+mod queue_state;
+mod reverification_report;
 mod source;
+mod source_code_match;
+mod verification_job;
 mod verification_metadata;
+mod verification_preview;
+mod verification_request;
+mod verified_contract;
 mod verify_response;
 
-pub use enums::{BytecodeTypeWrapper, MatchTypeWrapper, SourceTypeWrapper};
+pub use abi_match::AbiMatchWrapper;
+pub use chain_contract::ChainContractWrapper;
+pub use contract_label::ContractLabelWrapper;
+pub use contract_page::ContractPageWrapper;
+pub use enums::{
+    BytecodeTypeWrapper, JobPriorityWrapper, MatchTypeWrapper, SourceTypeWrapper,
+    StaleReasonWrapper,
+};
+pub use method_source::MethodSourceWrapper;
+pub use queue_state::QueueStateWrapper;
+pub use reverification_report::ReverificationReportWrapper;
 pub use source::SourceWrapper;
+pub use source_code_match::SourceCodeMatchWrapper;
+pub use verification_job::VerificationJobWrapper;
 pub use verification_metadata::VerificationMetadataWrapper;
+pub use verification_preview::VerificationPreviewWrapper;
+pub use verification_request::{
+    VerifySolidityMultiPartRequestWrapper, VerifySolidityStandardJsonRequestWrapper,
+    VerifyVyperMultiPartRequestWrapper,
+};
+pub use verified_contract::VerifiedContractWrapper;
 pub use verify_response::VerifyResponseWrapper;
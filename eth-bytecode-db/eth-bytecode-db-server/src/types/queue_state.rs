@@ -0,0 +1,92 @@
+use super::{
+    verification_job::job_status, verified_contract::format_timestamp, JobPriorityWrapper,
+};
+use crate::proto;
+use amplify::{From, Wrapper};
+use eth_bytecode_db::jobs;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct QueueStateWrapper(proto::QueueState);
+
+impl From<jobs::QueueState> for QueueStateWrapper {
+    fn from(value: jobs::QueueState) -> Self {
+        let job_counts = value
+            .job_counts
+            .into_iter()
+            .map(|count| proto::queue_state::JobCount {
+                status: job_status(count.status).into(),
+                priority: JobPriorityWrapper::from(count.priority).into_inner().into(),
+                count: count.count,
+            })
+            .collect();
+        let recent_failures = value
+            .recent_failures
+            .into_iter()
+            .map(|failure| proto::queue_state::Failure {
+                job_id: failure.id,
+                status: job_status(failure.status).into(),
+                compiler_version: failure.compiler_version,
+                error: failure.error,
+                failed_at: format_timestamp(failure.failed_at),
+            })
+            .collect();
+        Self(proto::QueueState {
+            job_counts,
+            oldest_queued_age_secs: value.oldest_queued_age.map(|age| age.as_secs()),
+            in_flight_by_compiler_version: value.in_flight_by_compiler_version,
+            recent_failures,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use sea_orm::prelude::DateTime;
+    use std::{collections::BTreeMap, time::Duration};
+
+    #[test]
+    fn from_queue_state() {
+        let state = jobs::QueueState {
+            job_counts: vec![jobs::JobCount {
+                status: jobs::JobStatus::Queued,
+                priority: jobs::JobPriority::Bulk,
+                count: 7,
+            }],
+            oldest_queued_age: Some(Duration::from_millis(90_500)),
+            in_flight_by_compiler_version: BTreeMap::from([("v0.8.19".into(), 2)]),
+            recent_failures: vec![jobs::FailedJob {
+                id: 12,
+                status: jobs::JobStatus::Orphaned,
+                compiler_version: Some("v0.8.19".into()),
+                error: Some("processing has been interrupted 3 times".into()),
+                failed_at: DateTime::parse_from_str("2023-11-01 10:00:00", "%Y-%m-%d %H:%M:%S")
+                    .unwrap(),
+            }],
+        };
+
+        let expected = proto::QueueState {
+            job_counts: vec![proto::queue_state::JobCount {
+                status: proto::verification_job::Status::Queued.into(),
+                priority: proto::JobPriority::Bulk.into(),
+                count: 7,
+            }],
+            oldest_queued_age_secs: Some(90),
+            in_flight_by_compiler_version: BTreeMap::from([("v0.8.19".into(), 2)]),
+            recent_failures: vec![proto::queue_state::Failure {
+                job_id: 12,
+                status: proto::verification_job::Status::Orphaned.into(),
+                compiler_version: Some("v0.8.19".into()),
+                error: Some("processing has been interrupted 3 times".into()),
+                failed_at: "2023-11-01T10:00:00Z".into(),
+            }],
+        };
+
+        assert_eq!(
+            expected,
+            QueueStateWrapper::from(state).into_inner(),
+            "Invalid queue state conversion"
+        );
+    }
+}
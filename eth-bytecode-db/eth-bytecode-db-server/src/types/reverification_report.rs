@@ -0,0 +1,94 @@
+use crate::proto::{self, reverification_report::status_change};
+use amplify::{From, Wrapper};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use eth_bytecode_db::reverification;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct ReverificationReportWrapper(proto::ReverificationReport);
+
+impl From<reverification::Report> for ReverificationReportWrapper {
+    fn from(value: reverification::Report) -> Self {
+        let changes = value
+            .changes
+            .into_iter()
+            .map(|change| {
+                let (new_status, message) = match change.new_status {
+                    reverification::NewStatus::SourceMismatch {
+                        file_name,
+                        contract_name,
+                    } => (
+                        status_change::NewStatus::SourceMismatch,
+                        format!("{file_name}:{contract_name}"),
+                    ),
+                    reverification::NewStatus::Failed { message } => {
+                        (status_change::NewStatus::Failed, message)
+                    }
+                    reverification::NewStatus::InvalidRequest { message } => {
+                        (status_change::NewStatus::InvalidRequest, message)
+                    }
+                };
+                proto::reverification_report::StatusChange {
+                    verified_contract_id: change.verified_contract_id,
+                    source_id: change.source_id,
+                    chain_id: change.chain_id.map(|chain_id| chain_id.to_string()),
+                    contract_address: change
+                        .contract_address
+                        .map(|address| DisplayBytes::from(address).to_string()),
+                    new_status: new_status.into(),
+                    message,
+                }
+            })
+            .collect();
+
+        Self(proto::ReverificationReport {
+            checked: value.checked,
+            skipped: value.skipped,
+            last_id: value.last_id,
+            changes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_report() {
+        let report = reverification::Report {
+            checked: 2,
+            skipped: 1,
+            last_id: Some(3),
+            changes: vec![reverification::StatusChange {
+                verified_contract_id: 2,
+                source_id: 1,
+                chain_id: Some(5),
+                contract_address: Some(vec![0xca, 0xfe]),
+                new_status: reverification::NewStatus::Failed {
+                    message: "No contract could be verified with provided data".into(),
+                },
+            }],
+        };
+
+        let expected = proto::ReverificationReport {
+            checked: 2,
+            skipped: 1,
+            last_id: Some(3),
+            changes: vec![proto::reverification_report::StatusChange {
+                verified_contract_id: 2,
+                source_id: 1,
+                chain_id: Some("5".into()),
+                contract_address: Some("0xcafe".into()),
+                new_status: status_change::NewStatus::Failed.into(),
+                message: "No contract could be verified with provided data".into(),
+            }],
+        };
+
+        assert_eq!(
+            expected,
+            ReverificationReportWrapper::from(report).into_inner(),
+            "Invalid report conversion"
+        );
+    }
+}
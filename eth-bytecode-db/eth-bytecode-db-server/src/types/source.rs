@@ -3,7 +3,7 @@ use crate::{
     types::{MatchTypeWrapper, SourceTypeWrapper},
 };
 use amplify::{From, Wrapper};
-use eth_bytecode_db::{search, verification};
+use eth_bytecode_db::{licenses, search, verification};
 
 #[derive(Wrapper, From, Clone, Debug, PartialEq)]
 pub struct SourceWrapper(proto::Source);
@@ -12,6 +12,7 @@ impl From<verification::Source> for SourceWrapper {
     fn from(value: verification::Source) -> Self {
         let source_type = SourceTypeWrapper::from(value.source_type).into_inner();
         let match_type = MatchTypeWrapper::from(value.match_type).into_inner();
+        let license = licenses::extract(&value.file_name, &value.source_files);
         proto::Source {
             file_name: value.file_name,
             contract_name: value.contract_name,
@@ -22,6 +23,9 @@ impl From<verification::Source> for SourceWrapper {
             abi: value.abi,
             constructor_arguments: value.constructor_arguments,
             match_type: match_type.into(),
+            source_id: None,
+            etherscan_license_type: licenses::etherscan_license_type(license.as_deref()),
+            license,
         }
         .into()
     }
@@ -31,6 +35,7 @@ impl From<search::MatchContract> for SourceWrapper {
     fn from(value: search::MatchContract) -> Self {
         let source_type = SourceTypeWrapper::from(value.source_type).into_inner();
         let match_type = MatchTypeWrapper::from(value.match_type).into_inner();
+        let license = licenses::extract(&value.file_name, &value.source_files);
         proto::Source {
             file_name: value.file_name,
             contract_name: value.contract_name,
@@ -41,6 +46,9 @@ impl From<search::MatchContract> for SourceWrapper {
             abi: value.abi,
             constructor_arguments: value.constructor_arguments,
             match_type: match_type.into(),
+            source_id: Some(value.source_id),
+            etherscan_license_type: licenses::etherscan_license_type(license.as_deref()),
+            license,
         }
         .into()
     }
@@ -59,7 +67,10 @@ mod tests {
             compiler_version: "compiler_version".to_string(),
             compiler_settings: "compiler_settings".to_string(),
             source_type: verification::SourceType::Solidity,
-            source_files: BTreeMap::from([("source".into(), "content".into())]),
+            source_files: BTreeMap::from([(
+                "source".into(),
+                "// SPDX-License-Identifier: mit\ncontent".into(),
+            )]),
             abi: Some("abi".into()),
             constructor_arguments: Some("args".into()),
             match_type: verification::MatchType::Partial,
@@ -81,6 +92,7 @@ mod tests {
                     data: vec![7u8, 8u8],
                 },
             ],
+            warnings: vec![],
         };
 
         let expected = proto::Source {
@@ -89,10 +101,16 @@ mod tests {
             compiler_version: "compiler_version".to_string(),
             compiler_settings: "compiler_settings".to_string(),
             source_type: proto::source::SourceType::Solidity.into(),
-            source_files: BTreeMap::from([("source".into(), "content".into())]),
+            source_files: BTreeMap::from([(
+                "source".into(),
+                "// SPDX-License-Identifier: mit\ncontent".into(),
+            )]),
             abi: Some("abi".into()),
             constructor_arguments: Some("args".into()),
             match_type: proto::source::MatchType::Partial.into(),
+            source_id: None,
+            license: Some("MIT".into()),
+            etherscan_license_type: Some(3),
         };
 
         let result = SourceWrapper::from(verification_source).into_inner();
@@ -102,6 +120,7 @@ mod tests {
     #[test]
     fn from_search_source_to_proto_source() {
         let search_source = search::MatchContract {
+            source_id: 1,
             file_name: "file_name".to_string(),
             contract_name: "contract_name".to_string(),
             compiler_version: "compiler_version".to_string(),
@@ -125,6 +144,9 @@ mod tests {
             abi: Some("abi".into()),
             constructor_arguments: Some("args".into()),
             match_type: proto::source::MatchType::Partial.into(),
+            source_id: Some(1),
+            license: None,
+            etherscan_license_type: Some(1),
         };
 
         let result = SourceWrapper::from(search_source).into_inner();
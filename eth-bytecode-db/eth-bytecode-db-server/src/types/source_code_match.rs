@@ -0,0 +1,58 @@
+use crate::proto;
+use amplify::{From, Wrapper};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use eth_bytecode_db::code_search;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct SourceCodeMatchWrapper(proto::SourceCodeMatch);
+
+impl From<code_search::CodeMatch> for SourceCodeMatchWrapper {
+    fn from(value: code_search::CodeMatch) -> Self {
+        Self(proto::SourceCodeMatch {
+            verified_contract_id: value.verified_contract_id,
+            chain_id: value.chain_id.map(|chain_id| chain_id.to_string()),
+            contract_address: value
+                .contract_address
+                .map(|address| DisplayBytes::from(address).to_string()),
+            contract_name: value.contract_name,
+            compiler_version: value.compiler_version,
+            file_name: value.file_name,
+            highlight: value.highlight,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_code_match() {
+        let code_match = code_search::CodeMatch {
+            verified_contract_id: 7,
+            chain_id: Some(5),
+            contract_address: Some(vec![0x12; 20]),
+            contract_name: "Token".into(),
+            compiler_version: "v0.8.19+commit.7dd6d404".into(),
+            file_name: "Ownable.sol".into(),
+            highlight: "modifier <mark>onlyOwner</mark>() {".into(),
+        };
+
+        let expected = proto::SourceCodeMatch {
+            verified_contract_id: 7,
+            chain_id: Some("5".into()),
+            contract_address: Some("0x1212121212121212121212121212121212121212".into()),
+            contract_name: "Token".into(),
+            compiler_version: "v0.8.19+commit.7dd6d404".into(),
+            file_name: "Ownable.sol".into(),
+            highlight: "modifier <mark>onlyOwner</mark>() {".into(),
+        };
+
+        assert_eq!(
+            expected,
+            SourceCodeMatchWrapper::from(code_match).into_inner(),
+            "Invalid source code match conversion"
+        );
+    }
+}
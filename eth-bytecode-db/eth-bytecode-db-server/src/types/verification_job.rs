@@ -0,0 +1,97 @@
+use super::{JobPriorityWrapper, VerifyResponseWrapper};
+use crate::proto;
+use amplify::{From, Wrapper};
+use eth_bytecode_db::jobs;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct VerificationJobWrapper(proto::VerificationJob);
+
+impl From<jobs::Job> for VerificationJobWrapper {
+    fn from(job: jobs::Job) -> Self {
+        let result = job.result.map(|result| match result {
+            jobs::JobResult::Success(source) => VerifyResponseWrapper::ok(source).into_inner(),
+            jobs::JobResult::Failure { message } => {
+                VerifyResponseWrapper::err(message).into_inner()
+            }
+        });
+        proto::VerificationJob {
+            id: job.id,
+            status: job_status(job.status).into(),
+            result,
+            error: job.error,
+            priority: JobPriorityWrapper::from(job.priority).into_inner().into(),
+        }
+        .into()
+    }
+}
+
+pub(super) fn job_status(status: jobs::JobStatus) -> proto::verification_job::Status {
+    match status {
+        jobs::JobStatus::Queued => proto::verification_job::Status::Queued,
+        jobs::JobStatus::InProgress => proto::verification_job::Status::InProgress,
+        jobs::JobStatus::Succeeded => proto::verification_job::Status::Succeeded,
+        jobs::JobStatus::Failed => proto::verification_job::Status::Failed,
+        jobs::JobStatus::Orphaned => proto::verification_job::Status::Orphaned,
+        jobs::JobStatus::Cancelled => proto::verification_job::Status::Cancelled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_job_to_proto() {
+        let job = jobs::Job {
+            id: 10,
+            status: jobs::JobStatus::Failed,
+            priority: jobs::JobPriority::Interactive,
+            attempts: 1,
+            result: Some(jobs::JobResult::Failure {
+                message: "No contract could be verified".into(),
+            }),
+            error: None,
+        };
+
+        let expected = proto::VerificationJob {
+            id: 10,
+            status: proto::verification_job::Status::Failed.into(),
+            result: Some(proto::VerifyResponse {
+                message: "No contract could be verified".into(),
+                status: proto::verify_response::Status::Failure.into(),
+                source: None,
+                warnings: vec![],
+            }),
+            error: None,
+            priority: proto::JobPriority::Interactive.into(),
+        };
+
+        let result = VerificationJobWrapper::from(job).into_inner();
+
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn orphaned_job_to_proto() {
+        let job = jobs::Job {
+            id: 11,
+            status: jobs::JobStatus::Orphaned,
+            priority: jobs::JobPriority::Bulk,
+            attempts: 3,
+            result: None,
+            error: Some("processing has been interrupted 3 times".into()),
+        };
+
+        let expected = proto::VerificationJob {
+            id: 11,
+            status: proto::verification_job::Status::Orphaned.into(),
+            result: None,
+            error: Some("processing has been interrupted 3 times".into()),
+            priority: proto::JobPriority::Bulk.into(),
+        };
+
+        let result = VerificationJobWrapper::from(job).into_inner();
+
+        assert_eq!(expected, result);
+    }
+}
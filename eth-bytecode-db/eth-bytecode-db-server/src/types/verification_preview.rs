@@ -0,0 +1,101 @@
+use super::{verified_contract::format_timestamp, VerifyResponseWrapper};
+use crate::proto;
+use amplify::{From, Wrapper};
+use eth_bytecode_db::verification::preview;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct VerificationPreviewWrapper(proto::VerificationPreview);
+
+impl VerificationPreviewWrapper {
+    pub fn err(message: String) -> Self {
+        proto::VerificationPreview {
+            result: Some(VerifyResponseWrapper::err(message).into_inner()),
+            token: None,
+            expires_at: None,
+            constructor_arguments: vec![],
+            warnings: vec![],
+        }
+        .into()
+    }
+}
+
+impl From<preview::Preview> for VerificationPreviewWrapper {
+    fn from(value: preview::Preview) -> Self {
+        let constructor_arguments = value
+            .constructor_arguments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|argument| proto::ConstructorArgument {
+                name: argument.name,
+                r#type: argument.param_type,
+                value: argument.value,
+            })
+            .collect();
+        Self(proto::VerificationPreview {
+            result: Some(VerifyResponseWrapper::ok(value.source).into_inner()),
+            token: Some(value.token.to_string()),
+            expires_at: Some(format_timestamp(value.expires_at)),
+            constructor_arguments,
+            warnings: value.warnings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_bytecode_db::verification;
+    use pretty_assertions::assert_eq;
+    use sea_orm::prelude::{DateTime, Uuid};
+    use std::str::FromStr;
+
+    #[test]
+    fn from_preview() {
+        let source = verification::Source {
+            file_name: "".to_string(),
+            contract_name: "".to_string(),
+            compiler_version: "".to_string(),
+            compiler_settings: "".to_string(),
+            source_type: verification::SourceType::Solidity,
+            source_files: Default::default(),
+            abi: None,
+            constructor_arguments: None,
+            match_type: verification::MatchType::Partial,
+            raw_creation_input: vec![],
+            raw_deployed_bytecode: vec![],
+            creation_input_parts: vec![],
+            deployed_bytecode_parts: vec![],
+            warnings: vec![],
+        };
+        let preview = preview::Preview {
+            token: Uuid::from_str("7ab63a41-4ac8-4a4c-a3c8-9b5ad9bfb7a4").unwrap(),
+            expires_at: DateTime::parse_from_str("2023-11-09 11:00:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            source: source.clone(),
+            constructor_arguments: Some(vec![preview::ConstructorArgument {
+                name: "owner".to_string(),
+                param_type: "address".to_string(),
+                value: "0x00000000219ab540356cbb839cbe05303d7705fa".to_string(),
+            }]),
+            warnings: vec!["Only a partial match has been found".to_string()],
+        };
+
+        let expected = proto::VerificationPreview {
+            result: Some(VerifyResponseWrapper::ok(source).into_inner()),
+            token: Some("7ab63a41-4ac8-4a4c-a3c8-9b5ad9bfb7a4".to_string()),
+            expires_at: Some("2023-11-09T11:00:00Z".to_string()),
+            constructor_arguments: vec![proto::ConstructorArgument {
+                name: "owner".to_string(),
+                r#type: "address".to_string(),
+                value: "0x00000000219ab540356cbb839cbe05303d7705fa".to_string(),
+            }],
+            warnings: vec!["Only a partial match has been found".to_string()],
+        };
+
+        assert_eq!(
+            expected,
+            VerificationPreviewWrapper::from(preview).into_inner(),
+            "Invalid verification preview conversion"
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use super::{BytecodeTypeWrapper, VerificationMetadataWrapper};
+use crate::proto;
+use amplify::{From, Wrapper};
+use eth_bytecode_db::verification::{
+    solidity_multi_part, solidity_standard_json, vyper_multi_part, VerificationMetadata,
+    VerificationRequest,
+};
+
+fn parse_metadata(
+    metadata: Option<proto::VerificationMetadata>,
+) -> Result<Option<VerificationMetadata>, tonic::Status> {
+    metadata
+        .map(|metadata| VerificationMetadataWrapper::from_inner(metadata).try_into())
+        .transpose()
+}
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct VerifySolidityMultiPartRequestWrapper(proto::VerifySolidityMultiPartRequest);
+
+impl TryFrom<VerifySolidityMultiPartRequestWrapper>
+    for VerificationRequest<solidity_multi_part::MultiPartFiles>
+{
+    type Error = tonic::Status;
+
+    fn try_from(value: VerifySolidityMultiPartRequestWrapper) -> Result<Self, Self::Error> {
+        let request = value.into_inner();
+        let bytecode_type = request.bytecode_type();
+        Ok(VerificationRequest {
+            bytecode: request.bytecode,
+            bytecode_type: BytecodeTypeWrapper::from_inner(bytecode_type).try_into()?,
+            compiler_version: request.compiler_version,
+            content: solidity_multi_part::MultiPartFiles {
+                source_files: request.source_files,
+                evm_version: request.evm_version,
+                optimization_runs: request.optimization_runs,
+                libraries: request.libraries,
+            },
+            metadata: parse_metadata(request.metadata)?,
+        })
+    }
+}
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct VerifySolidityStandardJsonRequestWrapper(proto::VerifySolidityStandardJsonRequest);
+
+impl TryFrom<VerifySolidityStandardJsonRequestWrapper>
+    for VerificationRequest<solidity_standard_json::StandardJson>
+{
+    type Error = tonic::Status;
+
+    fn try_from(value: VerifySolidityStandardJsonRequestWrapper) -> Result<Self, Self::Error> {
+        let request = value.into_inner();
+        let bytecode_type = request.bytecode_type();
+        Ok(VerificationRequest {
+            bytecode: request.bytecode,
+            bytecode_type: BytecodeTypeWrapper::from_inner(bytecode_type).try_into()?,
+            compiler_version: request.compiler_version,
+            content: solidity_standard_json::StandardJson {
+                input: request.input,
+            },
+            metadata: parse_metadata(request.metadata)?,
+        })
+    }
+}
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct VerifyVyperMultiPartRequestWrapper(proto::VerifyVyperMultiPartRequest);
+
+impl TryFrom<VerifyVyperMultiPartRequestWrapper>
+    for VerificationRequest<vyper_multi_part::MultiPartFiles>
+{
+    type Error = tonic::Status;
+
+    fn try_from(value: VerifyVyperMultiPartRequestWrapper) -> Result<Self, Self::Error> {
+        let request = value.into_inner();
+        let bytecode_type = request.bytecode_type();
+        Ok(VerificationRequest {
+            bytecode: request.bytecode,
+            bytecode_type: BytecodeTypeWrapper::from_inner(bytecode_type).try_into()?,
+            compiler_version: request.compiler_version,
+            content: vyper_multi_part::MultiPartFiles {
+                source_files: request.source_files,
+                evm_version: request.evm_version,
+                optimizations: request.optimizations,
+            },
+            metadata: parse_metadata(request.metadata)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_bytecode_db::verification::BytecodeType;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn from_proto_to_vyper_verification_request() {
+        let proto_type = proto::VerifyVyperMultiPartRequest {
+            bytecode: "0x1234".into(),
+            bytecode_type: proto::BytecodeType::CreationInput.into(),
+            compiler_version: "compiler_version".into(),
+            evm_version: Some("london".into()),
+            optimizations: Some(true),
+            source_files: BTreeMap::from([("source_file".into(), "content".into())]),
+            metadata: None,
+        };
+
+        let expected = VerificationRequest {
+            bytecode: "0x1234".into(),
+            bytecode_type: BytecodeType::CreationInput,
+            compiler_version: "compiler_version".into(),
+            content: vyper_multi_part::MultiPartFiles {
+                evm_version: Some("london".into()),
+                optimizations: Some(true),
+                source_files: BTreeMap::from([("source_file".into(), "content".into())]),
+            },
+            metadata: None,
+        };
+
+        let wrapper: VerifyVyperMultiPartRequestWrapper = proto_type.into();
+        let result = VerificationRequest::try_from(wrapper);
+
+        assert_eq!(
+            result.expect("Valid request should not result in error"),
+            expected,
+            "Invalid request conversion result"
+        );
+    }
+
+    #[test]
+    fn unspecified_bytecode_type_is_rejected() {
+        let proto_type = proto::VerifySolidityStandardJsonRequest {
+            bytecode: "0x1234".into(),
+            bytecode_type: proto::BytecodeType::Unspecified.into(),
+            compiler_version: "compiler_version".into(),
+            input: "{}".into(),
+            metadata: None,
+        };
+
+        let wrapper: VerifySolidityStandardJsonRequestWrapper = proto_type.into();
+        let result = VerificationRequest::<solidity_standard_json::StandardJson>::try_from(wrapper);
+
+        assert!(
+            result.is_err(),
+            "Unspecified bytecode type should be rejected"
+        );
+    }
+}
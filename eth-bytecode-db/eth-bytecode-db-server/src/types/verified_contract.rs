@@ -0,0 +1,85 @@
+use super::{MatchTypeWrapper, StaleReasonWrapper};
+use crate::proto;
+use amplify::{From, Wrapper};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use eth_bytecode_db::{verification, verified_contracts};
+use sea_orm::prelude::DateTime;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct VerifiedContractWrapper(proto::VerifiedContract);
+
+impl From<verified_contracts::VerifiedContract> for VerifiedContractWrapper {
+    fn from(value: verified_contracts::VerifiedContract) -> Self {
+        let bytecode_type = match value.bytecode_type {
+            verification::BytecodeType::CreationInput => proto::BytecodeType::CreationInput,
+            verification::BytecodeType::DeployedBytecode => proto::BytecodeType::DeployedBytecode,
+        };
+        Self(proto::VerifiedContract {
+            id: value.id,
+            source_id: value.source_id,
+            bytecode_type: bytecode_type.into(),
+            verified_at: format_timestamp(value.verified_at),
+            stale_at: value.stale_at.map(format_timestamp),
+            stale_reason: value
+                .stale_reason
+                .map(|reason| StaleReasonWrapper::from(reason).into_inner().into()),
+            chain_id: value.chain_id.map(|chain_id| chain_id.to_string()),
+            contract_address: value
+                .contract_address
+                .map(|address| DisplayBytes::from(address).to_string()),
+            match_type: value
+                .match_type
+                .map(|match_type| MatchTypeWrapper::from(match_type).into_inner().into()),
+            superseded_by: value.superseded_by,
+        })
+    }
+}
+
+/// Timestamps are stored in UTC
+pub(super) fn format_timestamp(value: DateTime) -> String {
+    value.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_verified_contract() {
+        let timestamp = |value: &str| {
+            DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").expect("valid timestamp")
+        };
+        let verified_contract = verified_contracts::VerifiedContract {
+            id: 2,
+            source_id: 1,
+            bytecode_type: verification::BytecodeType::DeployedBytecode,
+            chain_id: Some(5),
+            contract_address: Some(vec![0x12; 20]),
+            verified_at: timestamp("2023-11-01 10:00:00"),
+            stale_at: Some(timestamp("2023-11-06 12:30:00")),
+            stale_reason: Some(verified_contracts::StaleReason::Reorg),
+            match_type: Some(verification::MatchType::Partial),
+            superseded_by: Some(3),
+        };
+
+        let expected = proto::VerifiedContract {
+            id: 2,
+            source_id: 1,
+            bytecode_type: proto::BytecodeType::DeployedBytecode.into(),
+            verified_at: "2023-11-01T10:00:00Z".into(),
+            stale_at: Some("2023-11-06T12:30:00Z".into()),
+            stale_reason: Some(proto::StaleReason::Reorg.into()),
+            chain_id: Some("5".into()),
+            contract_address: Some("0x1212121212121212121212121212121212121212".into()),
+            match_type: Some(proto::source::MatchType::Partial.into()),
+            superseded_by: Some(3),
+        };
+
+        assert_eq!(
+            expected,
+            VerifiedContractWrapper::from(verified_contract).into_inner(),
+            "Invalid verified contract conversion"
+        );
+    }
+}
@@ -11,6 +11,16 @@ impl VerifyResponseWrapper {
         proto::VerifyResponse {
             message: "OK".to_string(),
             status: proto::verify_response::Status::Success.into(),
+            warnings: verification_source
+                .warnings
+                .iter()
+                .map(|warning| proto::SourceWarning {
+                    check: warning.check.clone(),
+                    file_name: warning.file_name.clone(),
+                    line: warning.line as u64,
+                    message: warning.message.clone(),
+                })
+                .collect(),
             source: Some(SourceWrapper::from(verification_source).into_inner()),
         }
         .into()
@@ -21,6 +31,7 @@ impl VerifyResponseWrapper {
             message,
             status: proto::verify_response::Status::Failure.into(),
             source: None,
+            warnings: vec![],
         }
         .into()
     }
@@ -29,6 +40,7 @@ impl VerifyResponseWrapper {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use eth_bytecode_db::source_checks::SourceWarning;
 
     #[test]
     fn ok_verify_response() {
@@ -46,12 +58,24 @@ mod tests {
             raw_deployed_bytecode: vec![],
             creation_input_parts: vec![],
             deployed_bytecode_parts: vec![],
+            warnings: vec![SourceWarning {
+                check: "unicode".to_string(),
+                file_name: "Token.sol".to_string(),
+                line: 3,
+                message: "Invisible character U+200B is hidden from the readers".to_string(),
+            }],
         };
 
         let expected = proto::VerifyResponse {
             message: "OK".to_string(),
             status: proto::verify_response::Status::Success.into(),
             source: Some(SourceWrapper::from(verification_source.clone()).into_inner()),
+            warnings: vec![proto::SourceWarning {
+                check: "unicode".to_string(),
+                file_name: "Token.sol".to_string(),
+                line: 3,
+                message: "Invisible character U+200B is hidden from the readers".to_string(),
+            }],
         };
 
         let response = VerifyResponseWrapper::ok(verification_source).into_inner();
@@ -66,6 +90,7 @@ mod tests {
             message: "parse error".to_string(),
             status: proto::verify_response::Status::Failure.into(),
             source: None,
+            warnings: vec![],
         };
         assert_eq!(expected, response);
     }
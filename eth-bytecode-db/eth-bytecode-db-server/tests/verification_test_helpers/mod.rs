@@ -236,9 +236,20 @@ pub mod test_cases {
             creation_input_search_response.sources.len(),
             "Invalid number of sources returned"
         );
+        assert!(
+            creation_input_search_response.sources[0]
+                .source_id
+                .is_some(),
+            "Search results should contain source ids"
+        );
+        // Source ids are returned in search results only
+        let search_source = eth_bytecode_db_v2::Source {
+            source_id: None,
+            ..creation_input_search_response.sources[0].clone()
+        };
         assert_eq!(
             verification_response.source.unwrap(),
-            creation_input_search_response.sources[0],
+            search_source,
             "Sources returned on verification and search differ"
         );
     }
@@ -325,9 +336,20 @@ pub mod test_cases {
             creation_input_search_response.sources.len(),
             "Invalid number of sources returned"
         );
+        assert!(
+            creation_input_search_response.sources[0]
+                .source_id
+                .is_some(),
+            "Search results should contain source ids"
+        );
+        // Source ids are returned in search results only
+        let search_source = eth_bytecode_db_v2::Source {
+            source_id: None,
+            ..creation_input_search_response.sources[0].clone()
+        };
         assert_eq!(
             verification_response.source.unwrap(),
-            creation_input_search_response.sources[0],
+            search_source,
             "Sources returned on verification and search differ"
         );
     }
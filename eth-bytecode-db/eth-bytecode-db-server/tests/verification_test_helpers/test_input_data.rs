@@ -112,7 +112,11 @@ pub fn basic(source_type: SourceType, match_type: MatchType) -> TestInputData {
             abi: Some("[]".to_string()),
             constructor_arguments: None,
             match_type: eth_bytecode_db_match_type.into(),
+            source_id: None,
+            license: None,
+            etherscan_license_type: Some(1),
         }),
+        warnings: vec![],
     };
 
     TestInputData {
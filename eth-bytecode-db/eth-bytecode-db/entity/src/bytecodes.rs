@@ -12,6 +12,7 @@ pub struct Model {
     pub updated_at: DateTime,
     pub source_id: i64,
     pub bytecode_type: BytecodeType,
+    pub normalized_code_hash: Option<Vec<u8>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -10,15 +10,29 @@ pub struct Model {
     pub created_at: DateTime,
     pub updated_at: DateTime,
     pub name: String,
-    pub content: String,
+    pub content_hash: Vec<u8>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::file_contents::Entity",
+        from = "Column::ContentHash",
+        to = "super::file_contents::Column::ContentHash",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    FileContents,
     #[sea_orm(has_many = "super::source_files::Entity")]
     SourceFiles,
 }
 
+impl Related<super::file_contents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::FileContents.def()
+    }
+}
+
 impl Related<super::source_files::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SourceFiles.def()
@@ -2,13 +2,18 @@
 
 pub mod prelude;
 
+pub mod abi_items;
 pub mod bytecode_parts;
 pub mod bytecodes;
 pub mod bytecodes_m2m_parts;
+pub mod contract_labels;
+pub mod file_contents;
 pub mod files;
 pub mod parts;
 pub mod sea_orm_active_enums;
 pub mod source_files;
 pub mod sources;
 pub mod sources_m2m_files;
+pub mod verification_jobs;
+pub mod verification_previews;
 pub mod verified_contracts;
@@ -1,7 +1,11 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.2
 
 pub use super::{
-    bytecode_parts::Entity as BytecodeParts, bytecodes::Entity as Bytecodes,
-    files::Entity as Files, parts::Entity as Parts, source_files::Entity as SourceFiles,
-    sources::Entity as Sources, verified_contracts::Entity as VerifiedContracts,
+    abi_items::Entity as AbiItems, bytecode_parts::Entity as BytecodeParts,
+    bytecodes::Entity as Bytecodes, contract_labels::Entity as ContractLabels,
+    file_contents::Entity as FileContents, files::Entity as Files, parts::Entity as Parts,
+    source_files::Entity as SourceFiles, sources::Entity as Sources,
+    verification_jobs::Entity as VerificationJobs,
+    verification_previews::Entity as VerificationPreviews,
+    verified_contracts::Entity as VerifiedContracts,
 };
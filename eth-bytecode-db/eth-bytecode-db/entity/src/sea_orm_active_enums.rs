@@ -40,3 +40,53 @@ pub enum VerificationType {
     #[sea_orm(string_value = "standard_json")]
     StandardJson,
 }
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "verification_job_priority"
+)]
+pub enum VerificationJobPriority {
+    #[sea_orm(string_value = "interactive")]
+    Interactive,
+    #[sea_orm(string_value = "bulk")]
+    Bulk,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "verification_job_status"
+)]
+pub enum VerificationJobStatus {
+    #[sea_orm(string_value = "queued")]
+    Queued,
+    #[sea_orm(string_value = "in_progress")]
+    InProgress,
+    #[sea_orm(string_value = "succeeded")]
+    Succeeded,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+    #[sea_orm(string_value = "orphaned")]
+    Orphaned,
+    #[sea_orm(string_value = "cancelled")]
+    Cancelled,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "stale_reason")]
+pub enum StaleReason {
+    #[sea_orm(string_value = "reorg")]
+    Reorg,
+    #[sea_orm(string_value = "selfdestruct")]
+    Selfdestruct,
+    #[sea_orm(string_value = "redeployed")]
+    Redeployed,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "match_type")]
+pub enum MatchType {
+    #[sea_orm(string_value = "partial")]
+    Partial,
+    #[sea_orm(string_value = "full")]
+    Full,
+}
@@ -19,10 +19,13 @@ pub struct Model {
     pub raw_creation_input: Vec<u8>,
     pub raw_deployed_bytecode: Vec<u8>,
     pub file_ids_hash: Uuid,
+    pub license: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::abi_items::Entity")]
+    AbiItems,
     #[sea_orm(has_many = "super::source_files::Entity")]
     SourceFiles,
     #[sea_orm(has_many = "super::bytecodes::Entity")]
@@ -31,6 +34,12 @@ pub enum Relation {
     VerifiedContracts,
 }
 
+impl Related<super::abi_items::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AbiItems.def()
+    }
+}
+
 impl Related<super::source_files::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SourceFiles.def()
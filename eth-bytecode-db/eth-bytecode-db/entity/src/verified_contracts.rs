@@ -1,6 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.2
 
-use super::sea_orm_active_enums::{BytecodeType, VerificationType};
+use super::sea_orm_active_enums::{BytecodeType, MatchType, StaleReason, VerificationType};
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -17,10 +17,22 @@ pub struct Model {
     pub verification_type: VerificationType,
     pub chain_id: Option<i64>,
     pub contract_address: Option<Vec<u8>>,
+    pub stale_at: Option<DateTime>,
+    pub stale_reason: Option<StaleReason>,
+    pub match_type: Option<MatchType>,
+    pub superseded_by: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::SupersededBy",
+        to = "Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    SelfRef,
     #[sea_orm(
         belongs_to = "super::sources::Entity",
         from = "Column::SourceId",
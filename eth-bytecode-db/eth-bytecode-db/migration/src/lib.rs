@@ -8,6 +8,20 @@ mod m20221201_015147_add_unique_bytecodes_source_id_and_type_index;
 mod m20230222_194726_add_unique_parts_type_and_data_index;
 mod m20230227_014110_add_unique_source_index;
 mod m20230316_020341_verified_contracts_add_chain_id_contract_address_columns;
+mod m20230405_143012_bytecodes_add_normalized_code_hash_column;
+mod m20231016_093514_create_verification_jobs_table;
+mod m20231023_120417_verification_jobs_add_lease_columns;
+mod m20231025_081246_verification_jobs_add_priority_column;
+mod m20231027_104523_verification_jobs_add_retention_columns;
+mod m20231102_091734_create_file_contents_table;
+mod m20231106_142208_verified_contracts_add_stale_columns;
+mod m20231109_103015_create_verification_previews_table;
+mod m20231113_094210_sources_add_license_column;
+mod m20231120_101542_verification_jobs_add_cancelled_status;
+mod m20231122_143027_create_contract_labels_table;
+mod m20231127_101314_file_contents_add_search_index;
+mod m20231129_142651_create_abi_items_table;
+mod m20231204_103418_verified_contracts_add_match_type_columns;
 
 pub struct Migrator;
 
@@ -22,6 +36,20 @@ impl MigratorTrait for Migrator {
             Box::new(m20230222_194726_add_unique_parts_type_and_data_index::Migration),
             Box::new(m20230227_014110_add_unique_source_index::Migration),
             Box::new(m20230316_020341_verified_contracts_add_chain_id_contract_address_columns::Migration),
+            Box::new(m20230405_143012_bytecodes_add_normalized_code_hash_column::Migration),
+            Box::new(m20231016_093514_create_verification_jobs_table::Migration),
+            Box::new(m20231023_120417_verification_jobs_add_lease_columns::Migration),
+            Box::new(m20231025_081246_verification_jobs_add_priority_column::Migration),
+            Box::new(m20231027_104523_verification_jobs_add_retention_columns::Migration),
+            Box::new(m20231102_091734_create_file_contents_table::Migration),
+            Box::new(m20231106_142208_verified_contracts_add_stale_columns::Migration),
+            Box::new(m20231109_103015_create_verification_previews_table::Migration),
+            Box::new(m20231113_094210_sources_add_license_column::Migration),
+            Box::new(m20231120_101542_verification_jobs_add_cancelled_status::Migration),
+            Box::new(m20231122_143027_create_contract_labels_table::Migration),
+            Box::new(m20231127_101314_file_contents_add_search_index::Migration),
+            Box::new(m20231129_142651_create_abi_items_table::Migration),
+            Box::new(m20231204_103418_verified_contracts_add_match_type_columns::Migration),
         ]
     }
 }
@@ -0,0 +1,25 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "bytecodes"
+            ADD COLUMN "normalized_code_hash" bytea;
+            CREATE INDEX bytecodes_normalized_code_hash_index ON bytecodes (normalized_code_hash);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX bytecodes_normalized_code_hash_index;
+            ALTER TABLE "bytecodes"
+            DROP COLUMN "normalized_code_hash";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
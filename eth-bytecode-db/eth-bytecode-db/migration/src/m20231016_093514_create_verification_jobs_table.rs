@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TYPE "verification_job_status" AS ENUM (
+              'queued',
+              'in_progress',
+              'succeeded',
+              'failed',
+              'orphaned'
+            );
+
+            CREATE TABLE "verification_jobs" (
+              "id" BIGSERIAL PRIMARY KEY,
+              "created_at" timestamp NOT NULL DEFAULT (now()),
+              "updated_at" timestamp NOT NULL DEFAULT (now()),
+              "status" verification_job_status NOT NULL DEFAULT 'queued',
+              "request" jsonb NOT NULL,
+              "attempts" integer NOT NULL DEFAULT 0,
+              "result" jsonb,
+              "error" varchar
+            );
+
+            CREATE INDEX verification_jobs_status_index ON verification_jobs (status);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "verification_jobs";
+            DROP TYPE "verification_job_status";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,27 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "verification_jobs"
+            ADD COLUMN "lease_owner" varchar,
+            ADD COLUMN "lease_expires_at" timestamp;
+            CREATE INDEX verification_jobs_status_id_index ON verification_jobs (status, id);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX verification_jobs_status_id_index;
+            ALTER TABLE "verification_jobs"
+            DROP COLUMN "lease_expires_at",
+            DROP COLUMN "lease_owner";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TYPE "verification_job_priority" AS ENUM (
+              'interactive',
+              'bulk'
+            );
+
+            ALTER TABLE "verification_jobs"
+            ADD COLUMN "priority" verification_job_priority NOT NULL DEFAULT 'interactive';
+
+            DROP INDEX verification_jobs_status_id_index;
+            CREATE INDEX verification_jobs_status_priority_id_index
+                ON verification_jobs (status, priority, id);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX verification_jobs_status_priority_id_index;
+            CREATE INDEX verification_jobs_status_id_index ON verification_jobs (status, id);
+
+            ALTER TABLE "verification_jobs"
+            DROP COLUMN "priority";
+
+            DROP TYPE "verification_job_priority";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
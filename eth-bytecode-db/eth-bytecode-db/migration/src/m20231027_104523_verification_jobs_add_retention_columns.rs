@@ -0,0 +1,28 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "verification_jobs"
+            ADD COLUMN "compressed_result" bytea,
+            ADD COLUMN "accessed_at" timestamp;
+            CREATE INDEX verification_jobs_status_updated_at_index
+                ON verification_jobs (status, updated_at);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX verification_jobs_status_updated_at_index;
+            ALTER TABLE "verification_jobs"
+            DROP COLUMN "accessed_at",
+            DROP COLUMN "compressed_result";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "file_contents" (
+              "content_hash" bytea PRIMARY KEY,
+              "created_at" timestamp NOT NULL DEFAULT (now()),
+              "content" varchar NOT NULL
+            );
+
+            COMMENT ON TABLE "file_contents" IS 'Contents of the source files addressed by their sha256 hashes. Are shared by all files with the same content';
+
+            INSERT INTO "file_contents" ("content_hash", "content")
+            SELECT DISTINCT ON (sha256(convert_to("content", 'UTF8')))
+                sha256(convert_to("content", 'UTF8')), "content"
+            FROM "files";
+
+            ALTER TABLE "files" ADD COLUMN "content_hash" bytea;
+            UPDATE "files" SET "content_hash" = sha256(convert_to("content", 'UTF8'));
+            ALTER TABLE "files" ALTER COLUMN "content_hash" SET NOT NULL;
+            ALTER TABLE "files" ADD FOREIGN KEY ("content_hash") REFERENCES "file_contents" ("content_hash");
+
+            DROP INDEX unique_files_name_and_content_index;
+            CREATE UNIQUE INDEX unique_files_name_and_content_hash_index ON files (name, content_hash);
+            ALTER TABLE "files" DROP COLUMN "content";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "files" ADD COLUMN "content" varchar;
+            UPDATE "files" SET "content" = "file_contents"."content"
+            FROM "file_contents"
+            WHERE "files"."content_hash" = "file_contents"."content_hash";
+            ALTER TABLE "files" ALTER COLUMN "content" SET NOT NULL;
+
+            DROP INDEX unique_files_name_and_content_hash_index;
+            CREATE UNIQUE INDEX unique_files_name_and_content_index ON files (name, (md5(content)::uuid));
+            ALTER TABLE "files" DROP COLUMN "content_hash";
+
+            DROP TABLE "file_contents";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
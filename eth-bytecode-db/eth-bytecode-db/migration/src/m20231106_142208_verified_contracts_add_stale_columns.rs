@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TYPE "stale_reason" AS ENUM (
+              'reorg',
+              'selfdestruct',
+              'redeployed'
+            );
+
+            ALTER TABLE "verified_contracts"
+            ADD COLUMN "stale_at" timestamp,
+            ADD COLUMN "stale_reason" stale_reason;
+
+            CREATE INDEX verified_contracts_chain_id_contract_address_index
+                ON verified_contracts (chain_id, contract_address);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX verified_contracts_chain_id_contract_address_index;
+            ALTER TABLE "verified_contracts"
+            DROP COLUMN "stale_reason",
+            DROP COLUMN "stale_at";
+            DROP TYPE "stale_reason";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "verification_previews" (
+              "token" uuid PRIMARY KEY DEFAULT (gen_random_uuid()),
+              "created_at" timestamp NOT NULL DEFAULT (now()),
+              "expires_at" timestamp NOT NULL,
+              "data" jsonb NOT NULL,
+              "confirmed_at" timestamp
+            );
+
+            CREATE INDEX verification_previews_expires_at_index ON verification_previews (expires_at);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "verification_previews";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
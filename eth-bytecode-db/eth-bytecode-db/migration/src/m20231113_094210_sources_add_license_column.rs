@@ -0,0 +1,27 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Licenses of the already stored sources are not backfilled
+        let sql = r#"
+            ALTER TABLE "sources"
+            ADD COLUMN "license" varchar;
+
+            CREATE INDEX sources_license_index ON sources (license);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX sources_license_index;
+            ALTER TABLE "sources"
+            DROP COLUMN "license";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TYPE "verification_job_status" ADD VALUE 'cancelled';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            UPDATE "verification_jobs"
+            SET status = 'failed', error = coalesce(error, 'job has been cancelled')
+            WHERE status = 'cancelled';
+
+            ALTER TYPE "verification_job_status" RENAME TO "verification_job_status_old";
+            CREATE TYPE "verification_job_status" AS ENUM (
+              'queued',
+              'in_progress',
+              'succeeded',
+              'failed',
+              'orphaned'
+            );
+
+            ALTER TABLE "verification_jobs"
+            ALTER COLUMN "status" DROP DEFAULT,
+            ALTER COLUMN "status" TYPE verification_job_status
+                USING status::text::verification_job_status,
+            ALTER COLUMN "status" SET DEFAULT 'queued';
+
+            DROP TYPE "verification_job_status_old";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "contract_labels" (
+              "id" BIGSERIAL PRIMARY KEY,
+              "created_at" timestamp NOT NULL DEFAULT (now()),
+              "updated_at" timestamp NOT NULL DEFAULT (now()),
+              "chain_id" bigint NOT NULL,
+              "contract_address" bytea NOT NULL,
+              "name" varchar NOT NULL,
+              "description" varchar
+            );
+
+            CREATE UNIQUE INDEX unique_contract_labels_index
+                ON contract_labels (chain_id, contract_address, name);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "contract_labels";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
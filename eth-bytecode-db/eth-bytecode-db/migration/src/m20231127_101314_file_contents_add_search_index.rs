@@ -0,0 +1,24 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Only the beginning of the content is indexed, as the size of `tsvector` is limited.
+        // The expression should be kept in sync with the one used by the code search queries.
+        let sql = r#"
+            CREATE INDEX file_contents_search_index ON file_contents
+                USING GIN (to_tsvector('simple', left(content, 262144)));
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX file_contents_search_index;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
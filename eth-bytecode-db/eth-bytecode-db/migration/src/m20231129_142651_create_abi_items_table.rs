@@ -0,0 +1,34 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Items of the already stored sources are filled by `abi_search::index_sources`
+        let sql = r#"
+            CREATE TABLE "abi_items" (
+              "source_id" bigint NOT NULL REFERENCES "sources" ("id"),
+              "kind" varchar NOT NULL,
+              "signature" varchar NOT NULL,
+              "created_at" timestamp NOT NULL DEFAULT (now()),
+              "selector" bytea NOT NULL,
+              PRIMARY KEY ("source_id", "kind", "signature")
+            );
+
+            COMMENT ON TABLE "abi_items" IS 'Functions, events, and errors of the source abis by their selectors (event topics for the events) and canonical signatures';
+
+            CREATE INDEX abi_items_selector_index ON abi_items (selector);
+            CREATE INDEX abi_items_signature_index ON abi_items (signature);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "abi_items";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,34 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Match types of the already stored verified contracts are unknown and left empty
+        let sql = r#"
+            CREATE TYPE "match_type" AS ENUM (
+              'partial',
+              'full'
+            );
+
+            ALTER TABLE "verified_contracts"
+            ADD COLUMN "match_type" match_type,
+            ADD COLUMN "superseded_by" bigint REFERENCES "verified_contracts" ("id");
+
+            COMMENT ON COLUMN "verified_contracts"."superseded_by" IS 'Full match verified contract of the same address which has upgraded the partial match';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "verified_contracts"
+            DROP COLUMN "superseded_by",
+            DROP COLUMN "match_type";
+            DROP TYPE "match_type";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,458 @@
+//! Search of the verified contracts by the functions, events, and errors their ABIs expose
+//! (e.g., to find the contracts implementing a vulnerable interface).
+//!
+//! Items of the source ABIs are indexed by their selectors (topics for the events) and
+//! canonical signatures when the sources are inserted. Sources stored before the index
+//! was introduced are indexed by [`index_sources`].
+
+use anyhow::Context;
+use entity::abi_items;
+use ethabi::{param_type::Reader, ParamType};
+use sea_orm::{
+    prelude::Json, sea_query::OnConflict, ActiveValue::Set, ConnectionTrait, DatabaseBackend,
+    DbErr, EntityTrait, FromQueryResult, Statement,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AbiItemKind {
+    Function,
+    Event,
+    Error,
+}
+
+impl AbiItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AbiItemKind::Function => "function",
+            AbiItemKind::Event => "event",
+            AbiItemKind::Error => "error",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AbiItem {
+    pub kind: AbiItemKind,
+    /// 4-byte selector for the functions and errors, and 32-byte topic for the events
+    pub selector: Vec<u8>,
+    /// Canonical signature, e.g. `transfer(address,uint256)`
+    pub signature: String,
+}
+
+/// Returns the functions, events, and errors of the abi. Anonymous events
+/// are skipped, as they could not be found by their topics.
+pub fn items(abi: &ethabi::Contract) -> Vec<AbiItem> {
+    let item = |kind, name: &str, params: Vec<ParamType>| {
+        let selector = match kind {
+            AbiItemKind::Event => ethabi::long_signature(name, &params).as_bytes().to_vec(),
+            _ => ethabi::short_signature(name, &params).to_vec(),
+        };
+        AbiItem {
+            kind,
+            selector,
+            signature: signature(name, &params),
+        }
+    };
+
+    let functions = abi.functions().map(|function| {
+        let params = function.inputs.iter().map(|param| param.kind.clone());
+        item(AbiItemKind::Function, &function.name, params.collect())
+    });
+    let events = abi.events().filter(|event| !event.anonymous).map(|event| {
+        let params = event.inputs.iter().map(|param| param.kind.clone());
+        item(AbiItemKind::Event, &event.name, params.collect())
+    });
+    let errors = abi.errors().map(|error| {
+        let params = error.inputs.iter().map(|param| param.kind.clone());
+        item(AbiItemKind::Error, &error.name, params.collect())
+    });
+
+    let mut items: Vec<_> = functions.chain(events).chain(errors).collect();
+    items.sort_by(|a, b| (a.kind, &a.signature).cmp(&(b.kind, &b.signature)));
+    items
+}
+
+fn signature(name: &str, params: &[ParamType]) -> String {
+    let params: Vec<_> = params.iter().map(ParamType::to_string).collect();
+    format!("{name}({})", params.join(","))
+}
+
+/// Converts a human-readable signature into the canonical one, e.g.
+/// `function transfer(address to, uint amount) external returns (bool)`
+/// into `transfer(address,uint256)`, or
+/// `event Transfer(address indexed from, address indexed to, uint256 value)`
+/// into `Transfer(address,address,uint256)`.
+pub fn canonical_signature(signature: &str) -> Result<String, anyhow::Error> {
+    let signature = signature.trim();
+    let signature = ["function ", "event ", "error "]
+        .iter()
+        .find_map(|keyword| signature.strip_prefix(keyword))
+        .unwrap_or(signature);
+
+    let (name, rest) = signature
+        .split_once('(')
+        .context("parameters are not specified")?;
+    let name = name.trim();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    {
+        anyhow::bail!("invalid name `{name}`");
+    }
+    // Anything after the parameters (modifiers, return types) is ignored
+    let end = closing_parenthesis(rest).context("unbalanced parentheses")?;
+    let params = parse_params(&rest[..end])?;
+    Ok(self::signature(name, &params))
+}
+
+fn parse_params(params: &str) -> Result<Vec<ParamType>, anyhow::Error> {
+    match params.trim().is_empty() {
+        true => Ok(vec![]),
+        false => split_top_level(params)
+            .into_iter()
+            .map(parse_param)
+            .collect(),
+    }
+}
+
+/// Parses the type of the parameter ignoring its name and modifiers (e.g. `indexed`).
+/// Names of the tuple components are ignored as well.
+fn parse_param(param: &str) -> Result<ParamType, anyhow::Error> {
+    let param = param.trim();
+    let param = param
+        .strip_prefix("tuple")
+        .filter(|rest| rest.starts_with('('))
+        .unwrap_or(param);
+    let rest = match param.strip_prefix('(') {
+        Some(rest) => rest,
+        None => {
+            let param_type = param.split_whitespace().next().unwrap_or_default();
+            // The reader considers unknown types to be enums, while they may be
+            // contracts or structs as well, which are encoded differently
+            let base_type = param_type.split('[').next().unwrap_or_default();
+            if !is_elementary_type(base_type) {
+                anyhow::bail!(
+                    "invalid parameter type `{param_type}` (only elementary types and tuples are supported)"
+                );
+            }
+            return Reader::read(param_type)
+                .map_err(|_err| anyhow::anyhow!("invalid parameter type `{param_type}`"));
+        }
+    };
+    let end = closing_parenthesis(rest).context("unbalanced parentheses")?;
+    let mut param_type = ParamType::Tuple(parse_params(&rest[..end])?);
+
+    // Tuples may be arrays as well, e.g. `(uint256,address)[2][]`
+    let mut dimensions = rest[end + 1..]
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    while let Some(dimension) = dimensions.strip_prefix('[') {
+        let (size, rest) = dimension.split_once(']').context("unbalanced brackets")?;
+        param_type = match size {
+            "" => ParamType::Array(Box::new(param_type)),
+            size => ParamType::FixedArray(
+                Box::new(param_type),
+                size.parse().context("invalid array size")?,
+            ),
+        };
+        dimensions = rest;
+    }
+    if !dimensions.is_empty() {
+        anyhow::bail!("invalid tuple type `{param}`");
+    }
+    Ok(param_type)
+}
+
+fn is_elementary_type(name: &str) -> bool {
+    let is_sized = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .map_or(false, |size| size.chars().all(|c| c.is_ascii_digit()))
+    };
+    matches!(name, "address" | "bool" | "string" | "function")
+        || is_sized("bytes")
+        || is_sized("uint")
+        || is_sized("int")
+}
+
+/// Index of the parenthesis closing the one opened before the `value`
+fn closing_parenthesis(value: &str) -> Option<usize> {
+    let mut depth = 1usize;
+    for (index, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits the parameters by the commas which are not inside the tuple types
+fn split_top_level(params: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                result.push(&params[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(&params[start..]);
+    result
+}
+
+pub(crate) async fn insert_abi_items<C>(
+    db: &C,
+    source_id: i64,
+    abi: Option<&Json>,
+) -> Result<(), anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    // Sources are stored even if their abis could not be parsed
+    let abi = abi.and_then(|abi| serde_json::from_value::<ethabi::Contract>(abi.clone()).ok());
+    let abi = match abi {
+        Some(abi) => abi,
+        None => return Ok(()),
+    };
+    let active_models: Vec<_> = items(&abi)
+        .into_iter()
+        .map(|item| abi_items::ActiveModel {
+            source_id: Set(source_id),
+            kind: Set(item.kind.as_str().to_string()),
+            signature: Set(item.signature),
+            selector: Set(item.selector),
+            ..Default::default()
+        })
+        .collect();
+    if active_models.is_empty() {
+        return Ok(());
+    }
+    let result = abi_items::Entity::insert_many(active_models)
+        .on_conflict(OnConflict::new().do_nothing().to_owned())
+        .exec(db)
+        .await;
+    match result {
+        Ok(_) | Err(DbErr::RecordNotInserted) => Ok(()),
+        Err(err) => Err(err).context("insert into \"abi_items\""),
+    }
+}
+
+#[derive(FromQueryResult)]
+struct SourceAbi {
+    id: i64,
+    abi: Option<Json>,
+}
+
+/// Indexes the abis of at most `batch_size` sources stored before the index was introduced
+/// (i.e. having an abi, but no indexed items) with ids greater than `after_id`.
+/// Returns the id of the last processed source (to be passed as `after_id` for the next
+/// batch), or `None` if there are no sources left.
+pub async fn index_sources<C>(
+    db: &C,
+    after_id: Option<i64>,
+    batch_size: u64,
+) -> Result<Option<i64>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let sql = r#"
+        SELECT s.id, s.abi
+        FROM sources s
+        WHERE s.abi IS NOT NULL
+            AND ($1::bigint IS NULL OR s.id > $1)
+            AND NOT EXISTS (SELECT 1 FROM abi_items ai WHERE ai.source_id = s.id)
+        ORDER BY s.id
+        LIMIT $2
+    "#;
+    let batch = SourceAbi::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        sql,
+        [after_id.into(), (batch_size as i64).into()],
+    ))
+    .all(db)
+    .await
+    .context("select sources to index")?;
+
+    for source in &batch {
+        insert_abi_items(db, source.id, source.abi.as_ref())
+            .await
+            .with_context(|| format!("index abi of source {}", source.id))?;
+    }
+    Ok(batch.last().map(|source| source.id))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AbiQuery {
+    /// 4-byte selector of the functions and errors, or 32-byte topic of the events
+    Selector(Vec<u8>),
+    /// Canonical signature (see [`canonical_signature`])
+    Signature(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult)]
+pub struct AbiMatch {
+    pub verified_contract_id: i64,
+    pub chain_id: Option<i64>,
+    pub contract_address: Option<Vec<u8>>,
+    pub contract_name: String,
+    pub compiler_version: String,
+    /// One of "function", "event", or "error"
+    pub kind: String,
+    /// Canonical signature of the matched item
+    pub signature: String,
+}
+
+/// Returns not stale verified contracts whose abis contain the item, ordered by ids.
+/// Only verified contracts with id greater than `after_id` are returned.
+pub async fn search<C>(
+    db: &C,
+    query: &AbiQuery,
+    chain_id: Option<i64>,
+    after_id: Option<i64>,
+    limit: u64,
+) -> Result<Vec<AbiMatch>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let (condition, value) = match query {
+        AbiQuery::Selector(selector) => ("ai.selector = $1", selector.clone().into()),
+        AbiQuery::Signature(signature) => ("ai.signature = $1", signature.clone().into()),
+    };
+    let sql = format!(
+        r#"
+            SELECT DISTINCT ON (vc.id)
+                vc.id AS verified_contract_id, vc.chain_id, vc.contract_address,
+                s.contract_name, s.compiler_version, ai.kind, ai.signature
+            FROM abi_items ai
+                JOIN sources s ON s.id = ai.source_id
+                JOIN verified_contracts vc ON vc.source_id = s.id
+            WHERE {condition}
+                AND vc.stale_at IS NULL
+                AND ($2::bigint IS NULL OR vc.chain_id = $2)
+                AND ($3::bigint IS NULL OR vc.id > $3)
+            ORDER BY vc.id, ai.kind, ai.signature
+            LIMIT $4
+        "#
+    );
+    AbiMatch::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        &sql,
+        [
+            value,
+            chain_id.into(),
+            after_id.into(),
+            (limit as i64).into(),
+        ],
+    ))
+    .all(db)
+    .await
+    .context("search verified contracts by abi")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const ABI: &str = r#"[
+        {"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],"outputs":[{"name":"","type":"bool"}],"stateMutability":"nonpayable"},
+        {"type":"function","name":"swap","inputs":[{"name":"params","type":"tuple[]","components":[{"name":"amount","type":"uint256"},{"name":"path","type":"address[]"}]}],"outputs":[],"stateMutability":"nonpayable"},
+        {"type":"event","name":"Transfer","inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}],"anonymous":false},
+        {"type":"event","name":"Anonymous","inputs":[],"anonymous":true},
+        {"type":"error","name":"Unauthorized","inputs":[]}
+    ]"#;
+
+    fn hex(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&value[index..index + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn abi_items_are_extracted() {
+        let abi: ethabi::Contract = serde_json::from_str(ABI).unwrap();
+        let expected = vec![
+            AbiItem {
+                kind: AbiItemKind::Function,
+                selector: ethabi::short_signature(
+                    "swap",
+                    &[ParamType::Array(Box::new(ParamType::Tuple(vec![
+                        ParamType::Uint(256),
+                        ParamType::Array(Box::new(ParamType::Address)),
+                    ])))],
+                )
+                .to_vec(),
+                signature: "swap((uint256,address[])[])".into(),
+            },
+            AbiItem {
+                kind: AbiItemKind::Function,
+                selector: hex("a9059cbb"),
+                signature: "transfer(address,uint256)".into(),
+            },
+            AbiItem {
+                kind: AbiItemKind::Event,
+                selector: hex("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"),
+                signature: "Transfer(address,address,uint256)".into(),
+            },
+            AbiItem {
+                kind: AbiItemKind::Error,
+                selector: hex("82b42900"),
+                signature: "Unauthorized()".into(),
+            },
+        ];
+        assert_eq!(expected, items(&abi));
+    }
+
+    #[test]
+    fn signatures_are_canonicalized() {
+        let check = |signature: &str, expected: &str| {
+            assert_eq!(
+                expected,
+                canonical_signature(signature).expect(signature),
+                "signature: {signature}"
+            );
+        };
+        check("transfer(address,uint256)", "transfer(address,uint256)");
+        check(
+            "function transfer(address to, uint amount) external returns (bool)",
+            "transfer(address,uint256)",
+        );
+        check(
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+            "Transfer(address,address,uint256)",
+        );
+        check(
+            "swap((uint256 amount, address[] path)[] calldata params)",
+            "swap((uint256,address[])[])",
+        );
+        check("error Unauthorized()", "Unauthorized()");
+
+        for invalid in [
+            "transfer",
+            "(address)",
+            "transfer(address",
+            "f(foo)",
+            "f((uint256)x)",
+        ] {
+            assert!(
+                canonical_signature(invalid).is_err(),
+                "signature should be invalid: {invalid}"
+            );
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Full-text search of code snippets and identifiers across the verified sources.
+//!
+//! Source files are indexed by Postgres full-text search with the `simple` configuration,
+//! so that identifiers are matched case-insensitively as they are (without stemming).
+//! The query is matched as a phrase, i.e. all its words should follow each other
+//! in the same order as in the query (e.g. `transferFrom(address from` or `onlyOwner`).
+
+use anyhow::Context;
+use sea_orm::{ConnectionTrait, DatabaseBackend, FromQueryResult, Statement};
+
+/// Options of `ts_headline`. Up to three fragments of the matched file are returned,
+/// delimited by " ... ". The matched words are wrapped into `<mark>` and `</mark>`.
+const HEADLINE_OPTIONS: &str =
+    "StartSel=<mark>, StopSel=</mark>, MaxFragments=3, MaxWords=20, MinWords=5";
+
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult)]
+pub struct CodeMatch {
+    pub verified_contract_id: i64,
+    pub chain_id: Option<i64>,
+    pub contract_address: Option<Vec<u8>>,
+    pub contract_name: String,
+    pub compiler_version: String,
+    /// Name of the source file the query has been found in. If the query is found
+    /// in several files of the contract, the first one by name is returned
+    pub file_name: String,
+    /// Fragments of the file with the matched words highlighted
+    pub highlight: String,
+}
+
+/// Returns not stale verified contracts whose sources contain the query, ordered by ids.
+/// Only verified contracts with id greater than `after_id` are returned.
+pub async fn search<C>(
+    db: &C,
+    query: &str,
+    chain_id: Option<i64>,
+    after_id: Option<i64>,
+    limit: u64,
+) -> Result<Vec<CodeMatch>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    // Highlights are built for the found page only, as it requires parsing the whole files.
+    // The indexed expression should be kept in sync with the search index migration.
+    let sql = r#"
+        SELECT
+            page.verified_contract_id, page.chain_id, page.contract_address,
+            page.contract_name, page.compiler_version, page.file_name,
+            ts_headline('simple', fc.content, phraseto_tsquery('simple', $1), $2) AS highlight
+        FROM (
+            SELECT DISTINCT ON (vc.id)
+                vc.id AS verified_contract_id, vc.chain_id, vc.contract_address,
+                s.contract_name, s.compiler_version, f.name AS file_name, f.content_hash
+            FROM file_contents fc
+                JOIN files f ON f.content_hash = fc.content_hash
+                JOIN source_files sf ON sf.file_id = f.id
+                JOIN sources s ON s.id = sf.source_id
+                JOIN verified_contracts vc ON vc.source_id = s.id
+            WHERE to_tsvector('simple', left(fc.content, 262144))
+                    @@ phraseto_tsquery('simple', $1)
+                AND vc.stale_at IS NULL
+                AND ($3::bigint IS NULL OR vc.chain_id = $3)
+                AND ($4::bigint IS NULL OR vc.id > $4)
+            ORDER BY vc.id, f.name
+            LIMIT $5
+        ) page
+            JOIN file_contents fc ON fc.content_hash = page.content_hash
+        ORDER BY page.verified_contract_id
+    "#;
+    CodeMatch::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        sql,
+        [
+            query.into(),
+            HEADLINE_OPTIONS.into(),
+            chain_id.into(),
+            after_id.into(),
+            (limit as i64).into(),
+        ],
+    ))
+    .all(db)
+    .await
+    .context("search verified sources")
+}
+
+/// Whether the query contains any word to be searched for. Queries consisting
+/// of punctuation only are ignored by the full-text search and match nothing.
+pub fn is_searchable(query: &str) -> bool {
+    query.chars().any(|c| c.is_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn searchable_queries() {
+        assert!(is_searchable("onlyOwner"));
+        assert!(is_searchable("transferFrom(address from"));
+        assert!(!is_searchable(" (); "));
+        assert!(!is_searchable(""));
+    }
+}
@@ -0,0 +1,301 @@
+//! Everything the explorer contract page shows for a verified contract collected at once,
+//! so that the page could be rendered without a separate request for each section.
+
+use crate::{
+    labels::{self, Label},
+    natspec::{self, NatSpec},
+    search::{BytecodeRemote, MatchContract},
+    source_files,
+    standards::{self, Standard},
+    verification::{
+        preview::{self, ConstructorArgument},
+        MatchType, SourceType,
+    },
+    verified_contracts::VerifiedContract,
+};
+use anyhow::Context;
+use bytes::Bytes;
+use entity::{
+    bytecode_parts, bytecodes, parts,
+    sea_orm_active_enums::{BytecodeType, PartType},
+    sources, verified_contracts,
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use std::{collections::BTreeMap, ops::Range};
+
+const PUSH32: u8 = 0x7f;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Immutable {
+    /// Offset of the value in the deployed bytecode
+    pub offset: usize,
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractPage {
+    pub verified_contract: VerifiedContract,
+    pub contract: MatchContract,
+    /// `None` if the contract has no constructor arguments or they could not be decoded
+    pub constructor_arguments: Option<Vec<ConstructorArgument>>,
+    /// Addresses of the linked libraries by their fully qualified names (`file:name`)
+    pub libraries: BTreeMap<String, String>,
+    /// Values of the immutables. Are known only if the deployed bytecode has been verified
+    pub immutables: Vec<Immutable>,
+    pub standards: Vec<Standard>,
+    /// Restored from the doc comments of the sources. Is `None` for non solidity contracts
+    /// or if the contract has no documentation
+    pub natspec: Option<NatSpec>,
+    /// Labels assigned to the address by the operators
+    pub labels: Vec<Label>,
+}
+
+/// Returns the page of the latest not stale verified contract of the address, if any.
+pub async fn get<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+) -> Result<Option<ContractPage>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let verified_contract = latest_verified_contract(db, chain_id, contract_address).await?;
+    let verified_contract = match verified_contract {
+        Some(verified_contract) => verified_contract,
+        None => return Ok(None),
+    };
+    let source = sources::Entity::find_by_id(verified_contract.source_id)
+        .one(db)
+        .await
+        .context("select source")?
+        .context("verified contract references a missing source")?;
+    let files = source_files::source_files_contents(db, source.id).await?;
+
+    let local_raw = match verified_contract.bytecode_type {
+        BytecodeType::CreationInput => &source.raw_creation_input,
+        BytecodeType::DeployedBytecode => &source.raw_deployed_bytecode,
+    };
+    // The same as for the search, sources whose bytecode is the prefix
+    // of the verified one (i.e., metadata hashes are equal) are full matches
+    let match_type = if verified_contract.raw_bytecode.starts_with(local_raw) {
+        MatchType::Full
+    } else {
+        MatchType::Partial
+    };
+    let immutables = match verified_contract.bytecode_type {
+        BytecodeType::CreationInput => vec![],
+        BytecodeType::DeployedBytecode => {
+            let parts = deployed_bytecode_parts(db, source.id).await?;
+            let mut main_ranges = vec![];
+            let mut offset = 0;
+            for part in &parts {
+                if part.part_type == PartType::Main {
+                    main_ranges.push(offset..offset + part.data.len());
+                }
+                offset += part.data.len();
+            }
+            immutables(
+                &source.raw_deployed_bytecode,
+                &verified_contract.raw_bytecode,
+                &main_ranges,
+            )
+        }
+    };
+    let libraries = libraries(&source.compiler_settings);
+    let standards = source
+        .abi
+        .clone()
+        .and_then(|abi| serde_json::from_value::<ethabi::Contract>(abi).ok())
+        .map(|abi| standards::detect_standards(&abi, &source.raw_deployed_bytecode))
+        .unwrap_or_default();
+
+    let remote = BytecodeRemote {
+        bytecode_type: verified_contract.bytecode_type.clone(),
+        data: Bytes::copy_from_slice(&verified_contract.raw_bytecode),
+    };
+    let contract = MatchContract::build_from_db_data(source, files, &remote, match_type).await?;
+    let constructor_arguments = preview::decode_constructor_arguments(
+        contract.abi.as_deref(),
+        contract.constructor_arguments.as_deref(),
+    )
+    .unwrap_or_else(|err| {
+        tracing::warn!(
+            source_id = contract.source_id,
+            "constructor arguments could not be decoded: {err:#}"
+        );
+        None
+    });
+    let natspec = match contract.source_type {
+        SourceType::Solidity => {
+            let abi = contract
+                .abi
+                .as_deref()
+                .and_then(|abi| serde_json::from_str::<ethabi::Contract>(abi).ok());
+            contract
+                .source_files
+                .get(&contract.file_name)
+                .and_then(|source| natspec::extract(source, &contract.contract_name, abi.as_ref()))
+        }
+        _ => None,
+    };
+    let labels = labels::list(db, chain_id, contract_address).await?;
+
+    Ok(Some(ContractPage {
+        verified_contract: verified_contract.into(),
+        contract,
+        constructor_arguments,
+        libraries,
+        immutables,
+        standards,
+        natspec,
+        labels,
+    }))
+}
+
+/// Returns the latest not stale verified contract of the address, if any.
+pub(crate) async fn latest_verified_contract<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+) -> Result<Option<verified_contracts::Model>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    verified_contracts::Entity::find()
+        .filter(verified_contracts::Column::ChainId.eq(chain_id))
+        .filter(verified_contracts::Column::ContractAddress.eq(contract_address.to_vec()))
+        .filter(verified_contracts::Column::StaleAt.is_null())
+        .order_by_desc(verified_contracts::Column::Id)
+        .one(db)
+        .await
+        .context("select verified contract")
+}
+
+async fn deployed_bytecode_parts<C>(
+    db: &C,
+    source_id: i64,
+) -> Result<Vec<parts::Model>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let parts = bytecodes::Entity::find()
+        .filter(bytecodes::Column::SourceId.eq(source_id))
+        .filter(bytecodes::Column::BytecodeType.eq(BytecodeType::DeployedBytecode))
+        .find_with_related(parts::Entity)
+        // order by bytecode_parts::Order is important to keep the parts in bytecode order
+        .order_by_asc(bytecode_parts::Column::Order)
+        .all(db)
+        .await
+        .context("select deployed bytecode parts")?
+        .into_iter()
+        .next()
+        .map(|(_, parts)| parts)
+        .unwrap_or_default();
+    Ok(parts)
+}
+
+/// Solidity immutables are pushed via `PUSH32`, whose argument is left zeroed by the compiler
+/// and filled on deployment. Only instructions inside the main parts are considered,
+/// as the metadata parts are not code.
+fn immutables(local: &[u8], remote: &[u8], main_ranges: &[Range<usize>]) -> Vec<Immutable> {
+    let inside_main_part = |range: &Range<usize>| {
+        main_ranges
+            .iter()
+            .any(|main| main.start <= range.start && range.end <= main.end)
+    };
+
+    let mut immutables = vec![];
+    let mut position = 0;
+    while position < local.len() {
+        let opcode = local[position];
+        let push_size = match opcode {
+            0x60..=PUSH32 => (opcode - 0x5f) as usize,
+            _ => 0,
+        };
+        let argument = position + 1..position + 1 + push_size;
+        if opcode == PUSH32 && inside_main_part(&argument) {
+            let local_value = local.get(argument.clone());
+            let remote_value = remote.get(argument.clone());
+            if let (Some(local_value), Some(remote_value)) = (local_value, remote_value) {
+                if local_value.iter().all(|byte| *byte == 0) && local_value != remote_value {
+                    immutables.push(Immutable {
+                        offset: argument.start,
+                        value: remote_value.to_vec(),
+                    });
+                }
+            }
+        }
+        position = argument.end;
+    }
+    immutables
+}
+
+/// Flattens `libraries` of the standard json compiler settings
+/// (a map from the file name to the map from the library name to its address).
+fn libraries(compiler_settings: &serde_json::Value) -> BTreeMap<String, String> {
+    compiler_settings
+        .get("libraries")
+        .and_then(|libraries| libraries.as_object())
+        .into_iter()
+        .flatten()
+        .filter_map(|(file, libraries)| libraries.as_object().map(|libraries| (file, libraries)))
+        .flat_map(|(file, libraries)| {
+            libraries.iter().filter_map(move |(name, address)| {
+                address
+                    .as_str()
+                    .map(|address| (format!("{file}:{name}"), address.to_string()))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn immutables_are_pushed_values() {
+        let zeros = [0u8; 32];
+        let value = [0xab; 32];
+        // PUSH1 0x00 PUSH32 <immutable> PUSH32 <zeros> | <metadata>
+        let mut local = vec![0x60, 0x00, PUSH32];
+        local.extend(zeros);
+        local.push(PUSH32);
+        local.extend(zeros);
+        let main_length = local.len();
+        local.extend([PUSH32, 0x00, 0x01]);
+
+        let mut remote = vec![0x60, 0x00, PUSH32];
+        remote.extend(value);
+        remote.push(PUSH32);
+        remote.extend(zeros);
+        remote.extend([PUSH32, 0x00, 0x02]);
+
+        let expected = vec![Immutable {
+            offset: 3,
+            value: value.to_vec(),
+        }];
+        assert_eq!(expected, immutables(&local, &remote, &[0..main_length]));
+    }
+
+    #[test]
+    fn libraries_are_flattened() {
+        let settings = serde_json::json!({
+            "optimizer": {"enabled": false},
+            "libraries": {
+                "contracts/Math.sol": {"Math": "0x00000000219ab540356cbb839cbe05303d7705fa"},
+                "Strings.sol": {"Strings": "0xcafe", "Invalid": 1},
+            }
+        });
+        let expected = BTreeMap::from([
+            (
+                "contracts/Math.sol:Math".to_string(),
+                "0x00000000219ab540356cbb839cbe05303d7705fa".to_string(),
+            ),
+            ("Strings.sol:Strings".to_string(), "0xcafe".to_string()),
+        ]);
+        assert_eq!(expected, libraries(&settings));
+        assert_eq!(BTreeMap::new(), libraries(&serde_json::json!({})));
+    }
+}
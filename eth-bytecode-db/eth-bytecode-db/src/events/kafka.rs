@@ -0,0 +1,56 @@
+use super::{EventPublisher, VerificationEvent};
+use anyhow::Context;
+use rdkafka::{
+    producer::{FutureProducer, FutureRecord},
+    ClientConfig,
+};
+use std::time::Duration;
+
+/// Publishes the events to the Kafka topic, keyed by the contract (or job) they refer to.
+#[derive(Clone)]
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+    topic: String,
+    timeout: Duration,
+}
+
+impl std::fmt::Debug for KafkaPublisher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaPublisher")
+            .field("topic", &self.topic)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl KafkaPublisher {
+    /// `brokers` is a comma separated list of the bootstrap servers.
+    /// `timeout` limits the time the event may wait in the producer queue.
+    pub fn new(brokers: &str, topic: String, timeout: Duration) -> Result<Self, anyhow::Error> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("message.timeout.ms", timeout.as_millis().to_string())
+            .create()
+            .context("create kafka producer")?;
+        Ok(Self {
+            producer,
+            topic,
+            timeout,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl EventPublisher for KafkaPublisher {
+    async fn publish(&self, event: &VerificationEvent) -> Result<(), anyhow::Error> {
+        let payload = serde_json::to_vec(event).context("serialize event")?;
+        let key = event.key();
+        let record = FutureRecord::to(&self.topic).key(&key).payload(&payload);
+        self.producer
+            .send(record, self.timeout)
+            .await
+            .map_err(|(err, _message)| err)
+            .context("publish event to kafka")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+//! Verification events published to a message broker, so that downstream services
+//! (e.g., search indexers or analytics) could react to verifications without polling.
+//!
+//! An event is published every time a verified contract is stored, and every time
+//! a verification job changes its status. Events are published in the background;
+//! publishing failures are logged and never fail the verification itself.
+
+mod kafka;
+mod nats;
+
+pub use kafka::KafkaPublisher;
+pub use nats::NatsPublisher;
+
+use crate::{
+    jobs::JobStatus,
+    verification::{MatchType, VerificationMetadata},
+};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use serde::Serialize;
+use std::{fmt::Debug, sync::Arc};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VerificationEvent {
+    /// Verified contract has been stored
+    ContractVerified {
+        chain_id: Option<i64>,
+        contract_address: Option<DisplayBytes>,
+        match_type: MatchType,
+        /// Id of the stored source, which may be shared by several verified contracts
+        source_id: i64,
+        /// Id of the stored verified contract
+        verified_contract_id: i64,
+    },
+    /// Verification job has changed its status
+    JobStatusChanged {
+        job_id: i64,
+        status: JobStatus,
+        chain_id: Option<i64>,
+        contract_address: Option<DisplayBytes>,
+        /// Is set for succeeded jobs only
+        match_type: Option<MatchType>,
+    },
+}
+
+impl VerificationEvent {
+    pub fn contract_verified(
+        metadata: Option<VerificationMetadata>,
+        match_type: MatchType,
+        source_id: i64,
+        verified_contract_id: i64,
+    ) -> Self {
+        let (chain_id, contract_address) = split_metadata(metadata);
+        Self::ContractVerified {
+            chain_id,
+            contract_address,
+            match_type,
+            source_id,
+            verified_contract_id,
+        }
+    }
+
+    pub fn job_status_changed(
+        job_id: i64,
+        status: JobStatus,
+        metadata: Option<VerificationMetadata>,
+        match_type: Option<MatchType>,
+    ) -> Self {
+        let (chain_id, contract_address) = split_metadata(metadata);
+        Self::JobStatusChanged {
+            job_id,
+            status,
+            chain_id,
+            contract_address,
+            match_type,
+        }
+    }
+
+    /// Key the events of the same contract (or job) share,
+    /// so that brokers could preserve their order.
+    pub fn key(&self) -> String {
+        match self {
+            Self::ContractVerified {
+                chain_id: Some(chain_id),
+                contract_address: Some(contract_address),
+                ..
+            } => format!("{chain_id}:{contract_address}"),
+            Self::ContractVerified { source_id, .. } => format!("source:{source_id}"),
+            Self::JobStatusChanged { job_id, .. } => format!("job:{job_id}"),
+        }
+    }
+
+    /// Name of the event type, the same as the `type` field of the serialized event
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::ContractVerified { .. } => "contract_verified",
+            Self::JobStatusChanged { .. } => "job_status_changed",
+        }
+    }
+}
+
+fn split_metadata(metadata: Option<VerificationMetadata>) -> (Option<i64>, Option<DisplayBytes>) {
+    match metadata {
+        None => (None, None),
+        Some(metadata) => (
+            Some(metadata.chain_id),
+            Some(DisplayBytes::from(metadata.contract_address)),
+        ),
+    }
+}
+
+#[async_trait::async_trait]
+pub trait EventPublisher: Debug + Send + Sync {
+    async fn publish(&self, event: &VerificationEvent) -> Result<(), anyhow::Error>;
+}
+
+/// Publishes the events if the publisher has been configured, and ignores them otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct Events {
+    publisher: Option<Arc<dyn EventPublisher>>,
+}
+
+impl Events {
+    pub fn new(publisher: Arc<dyn EventPublisher>) -> Self {
+        Self {
+            publisher: Some(publisher),
+        }
+    }
+
+    /// Publishes the event in the background
+    pub fn publish(&self, event: VerificationEvent) {
+        if let Some(publisher) = self.publisher.clone() {
+            tokio::spawn(async move {
+                if let Err(err) = publisher.publish(&event).await {
+                    tracing::error!(
+                        event_key = event.key(),
+                        "verification event could not be published: {err:#}"
+                    );
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn serialize_contract_verified() {
+        let event = VerificationEvent::contract_verified(
+            Some(VerificationMetadata {
+                chain_id: 5,
+                contract_address: bytes::Bytes::from_static(&[0x12, 0x34]),
+            }),
+            MatchType::Full,
+            10,
+            11,
+        );
+
+        let expected = serde_json::json!({
+            "type": "contract_verified",
+            "chain_id": 5,
+            "contract_address": "0x1234",
+            "match_type": "Full",
+            "source_id": 10,
+            "verified_contract_id": 11,
+        });
+        assert_eq!(expected, serde_json::to_value(&event).unwrap());
+        assert_eq!("5:0x1234", event.key());
+    }
+
+    #[test]
+    fn serialize_job_status_changed() {
+        let event = VerificationEvent::job_status_changed(7, JobStatus::InProgress, None, None);
+
+        let expected = serde_json::json!({
+            "type": "job_status_changed",
+            "job_id": 7,
+            "status": "in_progress",
+            "chain_id": null,
+            "contract_address": null,
+            "match_type": null,
+        });
+        assert_eq!(expected, serde_json::to_value(&event).unwrap());
+        assert_eq!(event.event_type(), expected["type"]);
+        assert_eq!("job:7", event.key());
+    }
+}
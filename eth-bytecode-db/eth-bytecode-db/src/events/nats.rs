@@ -0,0 +1,31 @@
+use super::{EventPublisher, VerificationEvent};
+use anyhow::Context;
+
+/// Publishes the events to `{subject}.{event_type}` subjects of the NATS server.
+#[derive(Clone, Debug)]
+pub struct NatsPublisher {
+    client: async_nats::Client,
+    subject: String,
+}
+
+impl NatsPublisher {
+    pub async fn connect(url: &str, subject: String) -> Result<Self, anyhow::Error> {
+        let client = async_nats::connect(url)
+            .await
+            .context("connect to nats server")?;
+        Ok(Self { client, subject })
+    }
+}
+
+#[async_trait::async_trait]
+impl EventPublisher for NatsPublisher {
+    async fn publish(&self, event: &VerificationEvent) -> Result<(), anyhow::Error> {
+        let payload = serde_json::to_vec(event).context("serialize event")?;
+        let subject = format!("{}.{}", self.subject, event.event_type());
+        self.client
+            .publish(subject, payload.into())
+            .await
+            .context("publish event to nats")?;
+        Ok(())
+    }
+}
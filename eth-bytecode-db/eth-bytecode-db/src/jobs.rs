@@ -0,0 +1,976 @@
+//! Asynchronous verification jobs.
+//!
+//! Jobs are stored in the database together with their requests before being processed,
+//! so that queued and in-flight jobs survive service restarts. The database is the queue
+//! itself, so any number of service replicas may process jobs of the same database.
+//!
+//! Workers claim queued jobs via `SELECT ... FOR UPDATE SKIP LOCKED`, which guarantees
+//! each job is claimed by a single worker only. The claim is a lease which the worker
+//! renews via heartbeats while the job is being processed. Jobs with expired leases
+//! (e.g., the worker has been stopped or lost the database connection) are enqueued again,
+//! except for the ones interrupted too many times. Such jobs are likely to be the reason
+//! of the interruptions themselves, so they are marked as orphaned instead.
+//!
+//! Each job has a priority. Interactive jobs (submitted by users waiting for the result)
+//! and bulk ones (e.g., automatic verification or imports) are claimed by separate lanes
+//! with their own concurrency budgets, so that bulk jobs never delay interactive ones.
+//!
+//! Queued and in-flight jobs may be cancelled. Cancelled jobs in progress are aborted
+//! right away by the replica processing them, or on the next lease renewal otherwise;
+//! aborting the verification drops the compilation request, which kills the compiler.
+//!
+//! Every status change made by the queue is published as a verification event.
+
+use crate::{
+    events::{Events, VerificationEvent},
+    retention,
+    verification::{
+        solidity_multi_part, solidity_standard_json, vyper_multi_part, Client, Error, Source,
+        VerificationMetadata, VerificationRequest,
+    },
+};
+use anyhow::Context;
+use entity::{
+    sea_orm_active_enums::{VerificationJobPriority, VerificationJobStatus},
+    verification_jobs,
+};
+use sea_orm::{
+    prelude::DateTime, ActiveEnum, ActiveModelTrait, ActiveValue::Set, ConnectionTrait,
+    DatabaseBackend, DatabaseConnection, EntityTrait, FromQueryResult, Statement,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::{Notify, Semaphore};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "request", rename_all = "snake_case")]
+pub enum JobRequest {
+    SolidityMultiPart(VerificationRequest<solidity_multi_part::MultiPartFiles>),
+    SolidityStandardJson(VerificationRequest<solidity_standard_json::StandardJson>),
+    VyperMultiPart(VerificationRequest<vyper_multi_part::MultiPartFiles>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    InProgress,
+    Succeeded,
+    Failed,
+    /// Processing has been interrupted and could not be resumed
+    Orphaned,
+    /// Cancelled by the user before being completed
+    Cancelled,
+}
+
+impl JobRequest {
+    pub fn metadata(&self) -> Option<&VerificationMetadata> {
+        match self {
+            JobRequest::SolidityMultiPart(request) => request.metadata.as_ref(),
+            JobRequest::SolidityStandardJson(request) => request.metadata.as_ref(),
+            JobRequest::VyperMultiPart(request) => request.metadata.as_ref(),
+        }
+    }
+}
+
+/// Metadata of the stored request, if the request contains it. Only the metadata
+/// is deserialized, as the requests contain all the source files.
+fn stored_request_metadata(request: &serde_json::Value) -> Option<VerificationMetadata> {
+    serde_json::from_value(request["request"]["metadata"].clone())
+        .ok()
+        .flatten()
+}
+
+impl From<VerificationJobStatus> for JobStatus {
+    fn from(value: VerificationJobStatus) -> Self {
+        match value {
+            VerificationJobStatus::Queued => JobStatus::Queued,
+            VerificationJobStatus::InProgress => JobStatus::InProgress,
+            VerificationJobStatus::Succeeded => JobStatus::Succeeded,
+            VerificationJobStatus::Failed => JobStatus::Failed,
+            VerificationJobStatus::Orphaned => JobStatus::Orphaned,
+            VerificationJobStatus::Cancelled => JobStatus::Cancelled,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JobPriority {
+    Interactive,
+    Bulk,
+}
+
+impl From<VerificationJobPriority> for JobPriority {
+    fn from(value: VerificationJobPriority) -> Self {
+        match value {
+            VerificationJobPriority::Interactive => JobPriority::Interactive,
+            VerificationJobPriority::Bulk => JobPriority::Bulk,
+        }
+    }
+}
+
+impl From<JobPriority> for VerificationJobPriority {
+    fn from(value: JobPriority) -> Self {
+        match value {
+            JobPriority::Interactive => VerificationJobPriority::Interactive,
+            JobPriority::Bulk => VerificationJobPriority::Bulk,
+        }
+    }
+}
+
+/// Outcome of the verification. Is stored for completed jobs only.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobResult {
+    Success(Source),
+    /// Verification failed (e.g., the local compilation does not match the bytecode)
+    Failure {
+        message: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Job {
+    pub id: i64,
+    pub status: JobStatus,
+    pub priority: JobPriority,
+    /// Number of times the processing of the job has been started
+    pub attempts: i32,
+    pub result: Option<JobResult>,
+    /// Reason the job has been orphaned or failed without the verification result
+    pub error: Option<String>,
+}
+
+impl TryFrom<verification_jobs::Model> for Job {
+    type Error = anyhow::Error;
+
+    fn try_from(model: verification_jobs::Model) -> Result<Self, Self::Error> {
+        let result = match (model.result, model.compressed_result) {
+            (Some(result), _) => Some(result),
+            (None, Some(compressed)) => {
+                Some(retention::decompress(&compressed).context("decompress job result")?)
+            }
+            (None, None) => None,
+        };
+        let result = result
+            .map(serde_json::from_value)
+            .transpose()
+            .context("deserialize job result")?;
+        Ok(Self {
+            id: model.id,
+            status: model.status.into(),
+            priority: model.priority.into(),
+            attempts: model.attempts,
+            result,
+            error: model.error,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recovery {
+    /// Jobs with expired leases enqueued to be processed again
+    pub requeued: Vec<i64>,
+    /// Jobs marked as orphaned, as their processing has been interrupted too many times
+    pub orphaned: Vec<i64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkerParams {
+    /// Identifies the worker holding the lease. Should be unique among all replicas.
+    pub worker_id: String,
+    /// Maximum number of interactive jobs processed by the worker at once
+    pub interactive_concurrency: usize,
+    /// Maximum number of bulk jobs processed by the worker at once
+    pub bulk_concurrency: usize,
+    /// Number of times processing of the job may be interrupted before
+    /// the job is marked as orphaned
+    pub max_attempts: u32,
+    /// Time the job is claimed for. Leases are renewed every third of the duration.
+    pub lease_duration: Duration,
+    /// Delay before looking for new jobs if the queue has been empty
+    pub poll_interval: Duration,
+}
+
+impl Default for WorkerParams {
+    fn default() -> Self {
+        Self {
+            worker_id: default_worker_id(),
+            interactive_concurrency: 4,
+            bulk_concurrency: 2,
+            max_attempts: 3,
+            lease_duration: Duration::from_secs(60),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Host name (if available via `HOSTNAME`) combined with the process id.
+pub fn default_worker_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "eth-bytecode-db".to_string());
+    format!("{host}-{}", std::process::id())
+}
+
+#[derive(FromQueryResult)]
+struct JobId {
+    id: i64,
+}
+
+#[derive(FromQueryResult)]
+struct ClaimedJob {
+    id: i64,
+    request: serde_json::Value,
+}
+
+/// Enqueues the jobs with expired leases to be processed again, or marks them
+/// as orphaned if they have been interrupted `max_attempts` times already.
+///
+/// Jobs in progress without a lease are considered expired as well.
+pub async fn recover<C: ConnectionTrait>(
+    db: &C,
+    max_attempts: u32,
+) -> Result<Recovery, anyhow::Error> {
+    let orphaned = JobId::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r#"
+            UPDATE verification_jobs
+            SET status = 'orphaned', error = $1, lease_owner = NULL, lease_expires_at = NULL,
+                updated_at = now()
+            WHERE status = 'in_progress'
+                AND (lease_expires_at IS NULL OR lease_expires_at < now())
+                AND attempts >= $2
+            RETURNING id
+        "#,
+        [
+            format!("processing has been interrupted {max_attempts} times").into(),
+            (max_attempts as i32).into(),
+        ],
+    ))
+    .all(db)
+    .await
+    .context("mark orphaned jobs")?;
+
+    let requeued = JobId::find_by_statement(Statement::from_string(
+        DatabaseBackend::Postgres,
+        r#"
+            UPDATE verification_jobs
+            SET status = 'queued', lease_owner = NULL, lease_expires_at = NULL, updated_at = now()
+            WHERE status = 'in_progress'
+                AND (lease_expires_at IS NULL OR lease_expires_at < now())
+            RETURNING id
+        "#
+        .to_string(),
+    ))
+    .all(db)
+    .await
+    .context("requeue expired jobs")?;
+
+    let ids = |jobs: Vec<JobId>| {
+        let mut ids: Vec<_> = jobs.into_iter().map(|job| job.id).collect();
+        ids.sort();
+        ids
+    };
+    Ok(Recovery {
+        requeued: ids(requeued),
+        orphaned: ids(orphaned),
+    })
+}
+
+/// Retrieves the job and marks it as accessed, so that recently retrieved
+/// jobs are evicted last by the retention policy.
+pub async fn find_job<C: ConnectionTrait>(db: &C, id: i64) -> Result<Option<Job>, anyhow::Error> {
+    verification_jobs::Entity::find()
+        .from_raw_sql(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                UPDATE verification_jobs
+                SET accessed_at = now()
+                WHERE id = $1
+                RETURNING *
+            "#,
+            [id.into()],
+        ))
+        .one(db)
+        .await
+        .context("select verification job")?
+        .map(Job::try_from)
+        .transpose()
+}
+
+/// Marks the queued or in-flight job as cancelled. Returns the job after the update;
+/// jobs completed already are returned unchanged. Returns `None` if the job does not exist.
+pub async fn cancel_job<C: ConnectionTrait>(db: &C, id: i64) -> Result<Option<Job>, anyhow::Error> {
+    Ok(cancel(db, id).await?.map(|cancellation| cancellation.job))
+}
+
+struct Cancellation {
+    job: Job,
+    /// Is `false` if the job has been completed or cancelled before the call
+    cancelled: bool,
+    metadata: Option<VerificationMetadata>,
+}
+
+async fn cancel<C: ConnectionTrait>(
+    db: &C,
+    id: i64,
+) -> Result<Option<Cancellation>, anyhow::Error> {
+    let cancelled = verification_jobs::Entity::find()
+        .from_raw_sql(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                UPDATE verification_jobs
+                SET status = 'cancelled', lease_owner = NULL, lease_expires_at = NULL,
+                    updated_at = now(), accessed_at = now()
+                WHERE id = $1 AND status IN ('queued', 'in_progress')
+                RETURNING *
+            "#,
+            [id.into()],
+        ))
+        .one(db)
+        .await
+        .context("cancel verification job")?;
+    match cancelled {
+        Some(job) => {
+            let metadata = stored_request_metadata(&job.request);
+            Ok(Some(Cancellation {
+                job: Job::try_from(job)?,
+                cancelled: true,
+                metadata,
+            }))
+        }
+        None => Ok(find_job(db, id).await?.map(|job| Cancellation {
+            job,
+            cancelled: false,
+            metadata: None,
+        })),
+    }
+}
+
+/// Claims the oldest queued job of the priority for `lease_duration`. Jobs locked
+/// by concurrent claims are skipped, so that workers never wait for each other.
+pub async fn claim_job<C: ConnectionTrait>(
+    db: &C,
+    worker_id: &str,
+    priority: JobPriority,
+    lease_duration: Duration,
+) -> Result<Option<(i64, serde_json::Value)>, anyhow::Error> {
+    let job = ClaimedJob::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r#"
+            UPDATE verification_jobs
+            SET status = 'in_progress', attempts = attempts + 1, lease_owner = $1,
+                lease_expires_at = now() + make_interval(secs => $2), updated_at = now()
+            WHERE id = (
+                SELECT id FROM verification_jobs
+                WHERE status = 'queued' AND priority = $3::verification_job_priority
+                ORDER BY id
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, request
+        "#,
+        [
+            worker_id.into(),
+            lease_duration.as_secs_f64().into(),
+            VerificationJobPriority::from(priority).to_value().into(),
+        ],
+    ))
+    .one(db)
+    .await
+    .context("claim verification job")?;
+    Ok(job.map(|job| (job.id, job.request)))
+}
+
+/// Extends the lease of the job. Returns `false` if the worker does not hold the lease anymore.
+pub async fn renew_lease<C: ConnectionTrait>(
+    db: &C,
+    id: i64,
+    worker_id: &str,
+    lease_duration: Duration,
+) -> Result<bool, anyhow::Error> {
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                UPDATE verification_jobs
+                SET lease_expires_at = now() + make_interval(secs => $3), updated_at = now()
+                WHERE id = $1 AND lease_owner = $2 AND status = 'in_progress'
+            "#,
+            [
+                id.into(),
+                worker_id.into(),
+                lease_duration.as_secs_f64().into(),
+            ],
+        ))
+        .await
+        .context("renew job lease")?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobCount {
+    pub status: JobStatus,
+    pub priority: JobPriority,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailedJob {
+    pub id: i64,
+    /// Either failed or orphaned
+    pub status: JobStatus,
+    pub compiler_version: Option<String>,
+    /// Reason of the failure, or the verification failure message. Messages of the jobs
+    /// whose results have been compressed by the retention policy are not returned.
+    pub error: Option<String>,
+    pub failed_at: DateTime,
+}
+
+/// Live state of the queue for the operators dashboards.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueueState {
+    /// Number of the jobs by their status and priority. Combinations without jobs are omitted
+    pub job_counts: Vec<JobCount>,
+    /// Time the oldest queued job has been waiting for. Is `None` if there are no queued jobs
+    pub oldest_queued_age: Option<Duration>,
+    /// Number of the jobs in progress by the compiler versions of their requests
+    pub in_flight_by_compiler_version: BTreeMap<String, u64>,
+    /// The most recently failed and orphaned jobs, the latest first
+    pub recent_failures: Vec<FailedJob>,
+}
+
+#[derive(FromQueryResult)]
+struct JobCountRow {
+    status: VerificationJobStatus,
+    priority: VerificationJobPriority,
+    count: i64,
+}
+
+#[derive(FromQueryResult)]
+struct OldestQueuedRow {
+    age: Option<f64>,
+}
+
+#[derive(FromQueryResult)]
+struct CompilerVersionCountRow {
+    compiler_version: Option<String>,
+    count: i64,
+}
+
+#[derive(FromQueryResult)]
+struct FailedJobRow {
+    id: i64,
+    status: VerificationJobStatus,
+    compiler_version: Option<String>,
+    error: Option<String>,
+    updated_at: DateTime,
+}
+
+/// Collects the current state of the queue, including up to `recent_failures_limit`
+/// of the latest failures.
+pub async fn queue_state<C: ConnectionTrait>(
+    db: &C,
+    recent_failures_limit: u64,
+) -> Result<QueueState, anyhow::Error> {
+    let job_counts = JobCountRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Postgres,
+        r#"
+            SELECT status, priority, count(*) AS count
+            FROM verification_jobs
+            GROUP BY status, priority
+            ORDER BY status, priority
+        "#
+        .to_string(),
+    ))
+    .all(db)
+    .await
+    .context("count verification jobs")?
+    .into_iter()
+    .map(|row| JobCount {
+        status: row.status.into(),
+        priority: row.priority.into(),
+        count: row.count as u64,
+    })
+    .collect();
+
+    let oldest_queued_age = OldestQueuedRow::find_by_statement(Statement::from_string(
+        DatabaseBackend::Postgres,
+        r#"
+            SELECT EXTRACT(EPOCH FROM now() - min(created_at))::float8 AS age
+            FROM verification_jobs
+            WHERE status = 'queued'
+        "#
+        .to_string(),
+    ))
+    .one(db)
+    .await
+    .context("select oldest queued job age")?
+    .and_then(|row| row.age)
+    .map(|age| Duration::from_secs_f64(age.max(0.0)));
+
+    let in_flight_by_compiler_version =
+        CompilerVersionCountRow::find_by_statement(Statement::from_string(
+            DatabaseBackend::Postgres,
+            r#"
+                SELECT request -> 'request' ->> 'compiler_version' AS compiler_version,
+                    count(*) AS count
+                FROM verification_jobs
+                WHERE status = 'in_progress'
+                GROUP BY 1
+            "#
+            .to_string(),
+        ))
+        .all(db)
+        .await
+        .context("count in-flight verification jobs")?
+        .into_iter()
+        .filter_map(|row| {
+            row.compiler_version
+                .map(|compiler_version| (compiler_version, row.count as u64))
+        })
+        .collect();
+
+    let recent_failures = FailedJobRow::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r#"
+            SELECT id, status, request -> 'request' ->> 'compiler_version' AS compiler_version,
+                coalesce(error, result -> 'Failure' ->> 'message') AS error, updated_at
+            FROM verification_jobs
+            WHERE status IN ('failed', 'orphaned')
+            ORDER BY updated_at DESC, id DESC
+            LIMIT $1
+        "#,
+        [(recent_failures_limit as i64).into()],
+    ))
+    .all(db)
+    .await
+    .context("select recent failed verification jobs")?
+    .into_iter()
+    .map(|row| FailedJob {
+        id: row.id,
+        status: row.status.into(),
+        compiler_version: row.compiler_version,
+        error: row.error,
+        failed_at: row.updated_at,
+    })
+    .collect();
+
+    Ok(QueueState {
+        job_counts,
+        oldest_queued_age,
+        in_flight_by_compiler_version,
+        recent_failures,
+    })
+}
+
+/// Cancellation signals of the jobs processed by the local workers, by the job ids.
+type RunningJobs = Arc<Mutex<HashMap<i64, Arc<Notify>>>>;
+
+/// Queue of the jobs shared by all service replicas using the same database.
+pub struct JobQueue {
+    db_client: Arc<DatabaseConnection>,
+    /// Wake up the local worker lanes when a new job is submitted
+    interactive_notify: Arc<Notify>,
+    bulk_notify: Arc<Notify>,
+    running: RunningJobs,
+    events: Events,
+}
+
+impl JobQueue {
+    /// Starts the worker lanes processing the jobs of the queue and periodically
+    /// recovering the jobs with expired leases.
+    pub fn start(client: Client, params: WorkerParams) -> Self {
+        let db_client = client.db_client.clone();
+        let interactive_notify = Arc::new(Notify::new());
+        let bulk_notify = Arc::new(Notify::new());
+        let running = RunningJobs::default();
+        let events = client.events.clone();
+        tokio::spawn(run_recovery(
+            db_client.clone(),
+            events.clone(),
+            params.lease_duration,
+            params.max_attempts,
+        ));
+
+        let params = Arc::new(params);
+        tokio::spawn(run_lane(
+            client.clone(),
+            params.clone(),
+            JobPriority::Interactive,
+            params.interactive_concurrency,
+            interactive_notify.clone(),
+            running.clone(),
+        ));
+        tokio::spawn(run_lane(
+            client,
+            params.clone(),
+            JobPriority::Bulk,
+            params.bulk_concurrency,
+            bulk_notify.clone(),
+            running.clone(),
+        ));
+
+        Self {
+            db_client,
+            interactive_notify,
+            bulk_notify,
+            running,
+            events,
+        }
+    }
+
+    /// Stores the job to be processed by any of the workers. Returns the job id.
+    pub async fn submit(
+        &self,
+        request: JobRequest,
+        priority: JobPriority,
+    ) -> Result<i64, anyhow::Error> {
+        let metadata = request.metadata().cloned();
+        let request = serde_json::to_value(&request).context("serialize job request")?;
+        let job = verification_jobs::ActiveModel {
+            request: Set(request),
+            priority: Set(priority.into()),
+            ..Default::default()
+        }
+        .insert(self.db_client.as_ref())
+        .await
+        .context("insert verification job")?;
+
+        match priority {
+            JobPriority::Interactive => self.interactive_notify.notify_one(),
+            JobPriority::Bulk => self.bulk_notify.notify_one(),
+        }
+        self.events.publish(VerificationEvent::job_status_changed(
+            job.id,
+            JobStatus::Queued,
+            metadata,
+            None,
+        ));
+        Ok(job.id)
+    }
+
+    pub async fn find(&self, id: i64) -> Result<Option<Job>, anyhow::Error> {
+        find_job(self.db_client.as_ref(), id).await
+    }
+
+    pub async fn state(&self, recent_failures_limit: u64) -> Result<QueueState, anyhow::Error> {
+        queue_state(self.db_client.as_ref(), recent_failures_limit).await
+    }
+
+    /// Cancels the job (see [`cancel_job`]). If the job is being processed by the local
+    /// workers, the processing is aborted immediately; other replicas abort it
+    /// as soon as they fail to renew the lease.
+    pub async fn cancel(&self, id: i64) -> Result<Option<Job>, anyhow::Error> {
+        let cancellation = cancel(self.db_client.as_ref(), id).await?;
+        if let Some(cancelled) = self.running.lock().unwrap().get(&id) {
+            // Stores the permit, so the job is aborted even if it is not awaiting the signal yet
+            cancelled.notify_one();
+        }
+        Ok(cancellation.map(|cancellation| {
+            if cancellation.cancelled {
+                self.events.publish(VerificationEvent::job_status_changed(
+                    id,
+                    JobStatus::Cancelled,
+                    cancellation.metadata,
+                    None,
+                ));
+            }
+            cancellation.job
+        }))
+    }
+}
+
+async fn run_recovery(
+    db: Arc<DatabaseConnection>,
+    events: Events,
+    lease_duration: Duration,
+    max_attempts: u32,
+) {
+    // The first tick completes immediately, so the jobs left by
+    // the previous run are recovered on start
+    let mut interval = tokio::time::interval(lease_duration);
+    loop {
+        interval.tick().await;
+        match recover(db.as_ref(), max_attempts).await {
+            Ok(recovery) if recovery == Recovery::default() => {}
+            Ok(recovery) => {
+                tracing::info!(
+                    requeued = ?recovery.requeued,
+                    orphaned = ?recovery.orphaned,
+                    "verification jobs with expired leases have been recovered"
+                );
+                let changes = recovery
+                    .requeued
+                    .into_iter()
+                    .map(|id| (id, JobStatus::Queued))
+                    .chain(
+                        recovery
+                            .orphaned
+                            .into_iter()
+                            .map(|id| (id, JobStatus::Orphaned)),
+                    );
+                for (id, status) in changes {
+                    events.publish(VerificationEvent::job_status_changed(
+                        id, status, None, None,
+                    ));
+                }
+            }
+            Err(err) => tracing::error!("verification jobs recovery failed: {err:#}"),
+        }
+    }
+}
+
+/// Processes the jobs of the priority using its own concurrency budget.
+async fn run_lane(
+    client: Client,
+    params: Arc<WorkerParams>,
+    priority: JobPriority,
+    concurrency: usize,
+    notify: Arc<Notify>,
+    running: RunningJobs,
+) {
+    if concurrency == 0 {
+        tracing::warn!(
+            ?priority,
+            "verification jobs of the priority are not processed"
+        );
+        return;
+    }
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    loop {
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore is never closed");
+        let claimed = claim_job(
+            client.db_client.as_ref(),
+            &params.worker_id,
+            priority,
+            params.lease_duration,
+        )
+        .await;
+        match claimed {
+            Ok(Some((id, request))) => {
+                client.events.publish(VerificationEvent::job_status_changed(
+                    id,
+                    JobStatus::InProgress,
+                    stored_request_metadata(&request),
+                    None,
+                ));
+                let client = client.clone();
+                let params = params.clone();
+                let running = running.clone();
+                let cancelled = Arc::new(Notify::new());
+                running.lock().unwrap().insert(id, cancelled.clone());
+                tokio::spawn(async move {
+                    if let Err(err) = process_job(client, &params, id, request, &cancelled).await {
+                        tracing::error!(job_id = id, "verification job processing failed: {err:#}");
+                    }
+                    running.lock().unwrap().remove(&id);
+                    drop(permit);
+                });
+            }
+            Ok(None) => {
+                drop(permit);
+                tokio::select! {
+                    _ = notify.notified() => {}
+                    _ = tokio::time::sleep(params.poll_interval) => {}
+                }
+            }
+            Err(err) => {
+                drop(permit);
+                tracing::error!(?priority, "verification job could not be claimed: {err:#}");
+                tokio::time::sleep(params.poll_interval).await;
+            }
+        }
+    }
+}
+
+async fn process_job(
+    client: Client,
+    params: &WorkerParams,
+    id: i64,
+    request: serde_json::Value,
+    cancelled: &Notify,
+) -> Result<(), anyhow::Error> {
+    let db = client.db_client.clone();
+    let events = client.events.clone();
+    let request: JobRequest = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(err) => {
+            let error = format!("stored request is invalid: {err}");
+            let completed = complete_job(
+                db.as_ref(),
+                id,
+                &params.worker_id,
+                VerificationJobStatus::Orphaned,
+                None,
+                Some(error),
+            )
+            .await?;
+            if completed {
+                events.publish(VerificationEvent::job_status_changed(
+                    id,
+                    JobStatus::Orphaned,
+                    None,
+                    None,
+                ));
+            }
+            return Ok(());
+        }
+    };
+    let metadata = request.metadata().cloned();
+
+    // Dropping the verification future aborts the compilation in progress
+    let result = tokio::select! {
+        result = verify(client, request) => result,
+        _ = cancelled.notified() => {
+            tracing::info!(job_id = id, "verification job has been cancelled");
+            return Ok(());
+        }
+        // The job may already be processed by another worker or cancelled,
+        // so the result is discarded
+        _ = keep_lease(db.as_ref(), id, params) => {
+            tracing::warn!(job_id = id, "verification job lease has been lost");
+            return Ok(());
+        }
+    };
+    let (status, result, error) = match result {
+        Ok(source) => (
+            VerificationJobStatus::Succeeded,
+            Some(JobResult::Success(source)),
+            None,
+        ),
+        Err(Error::VerificationFailed { message }) => (
+            VerificationJobStatus::Failed,
+            Some(JobResult::Failure { message }),
+            None,
+        ),
+        Err(Error::InvalidArgument(message)) => (
+            VerificationJobStatus::Failed,
+            None,
+            Some(format!("invalid argument: {message}")),
+        ),
+        Err(Error::Internal(err)) => (
+            VerificationJobStatus::Failed,
+            None,
+            Some(format!("{err:#}")),
+        ),
+    };
+    let match_type = match &result {
+        Some(JobResult::Success(source)) => Some(source.match_type),
+        _ => None,
+    };
+    let job_status = JobStatus::from(status.clone());
+    let completed = complete_job(db.as_ref(), id, &params.worker_id, status, result, error).await?;
+    if completed {
+        events.publish(VerificationEvent::job_status_changed(
+            id, job_status, metadata, match_type,
+        ));
+    }
+    Ok(())
+}
+
+async fn verify(client: Client, request: JobRequest) -> Result<Source, Error> {
+    match request {
+        JobRequest::SolidityMultiPart(request) => {
+            solidity_multi_part::verify(client, request).await
+        }
+        JobRequest::SolidityStandardJson(request) => {
+            solidity_standard_json::verify(client, request).await
+        }
+        JobRequest::VyperMultiPart(request) => vyper_multi_part::verify(client, request).await,
+    }
+}
+
+/// Renews the lease of the job until the lease is lost.
+async fn keep_lease<C: ConnectionTrait>(db: &C, id: i64, params: &WorkerParams) {
+    let mut interval = tokio::time::interval(params.lease_duration / 3);
+    // The lease has just been acquired
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        match renew_lease(db, id, &params.worker_id, params.lease_duration).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            // The lease may still be valid, so retry on the next tick
+            Err(err) => tracing::warn!(job_id = id, "{err:#}"),
+        }
+    }
+}
+
+/// Stores the job outcome, if the worker still holds the lease.
+/// Returns `false` if the lease has been lost.
+async fn complete_job<C: ConnectionTrait>(
+    db: &C,
+    id: i64,
+    worker_id: &str,
+    status: VerificationJobStatus,
+    result: Option<JobResult>,
+    error: Option<String>,
+) -> Result<bool, anyhow::Error> {
+    let result = result
+        .map(|result| serde_json::to_value(&result))
+        .transpose()
+        .context("serialize job result")?;
+    let completed = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                UPDATE verification_jobs
+                SET status = $3::verification_job_status, result = $4, error = $5,
+                    lease_owner = NULL, lease_expires_at = NULL, updated_at = now()
+                WHERE id = $1 AND lease_owner = $2 AND status = 'in_progress'
+            "#,
+            [
+                id.into(),
+                worker_id.into(),
+                status.to_value().into(),
+                result.into(),
+                error.into(),
+            ],
+        ))
+        .await
+        .context("complete verification job")?;
+    if completed.rows_affected() == 0 {
+        tracing::warn!(
+            job_id = id,
+            "verification job lease has expired before the job was completed"
+        );
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verification::BytecodeType;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn job_request_roundtrip() {
+        let request = JobRequest::SolidityMultiPart(VerificationRequest {
+            bytecode: "0x1234".to_string(),
+            bytecode_type: BytecodeType::CreationInput,
+            compiler_version: "compiler_version".to_string(),
+            content: solidity_multi_part::MultiPartFiles {
+                source_files: BTreeMap::from([("source_file".into(), "content".into())]),
+                evm_version: None,
+                optimization_runs: Some(200),
+                libraries: Default::default(),
+            },
+            metadata: None,
+        });
+
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!("solidity_multi_part", value["type"]);
+        assert_eq!("0x1234", value["request"]["bytecode"]);
+        assert_eq!(request, serde_json::from_value(value).unwrap());
+    }
+}
@@ -0,0 +1,193 @@
+//! Labels assigned to verified contracts by the operators (e.g., "DEX", "Bridge", "Scam").
+//!
+//! Labels are attached to the address rather than to the verified contract record,
+//! so that they are retained when the contract is re-verified or marked as stale.
+
+use crate::contract_pages;
+use anyhow::Context;
+use entity::contract_labels;
+use sea_orm::{
+    prelude::DateTime, ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, FromQueryResult,
+    QueryFilter, QueryOrder, Statement,
+};
+use thiserror::Error;
+
+const MAX_NAME_LENGTH: usize = 64;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid label name: {0}")]
+    InvalidName(String),
+    #[error("Contract is not verified")]
+    ContractNotVerified,
+    #[error("Label already exists")]
+    AlreadyExists,
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Label {
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl From<contract_labels::Model> for Label {
+    fn from(value: contract_labels::Model) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+/// Trims the name and checks it is not empty and not too long.
+pub fn normalize_name(name: &str) -> Result<String, Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::InvalidName("name is empty".to_string()));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(Error::InvalidName(format!(
+            "name is longer than {MAX_NAME_LENGTH} characters"
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// Returns the labels of the address ordered by name.
+pub async fn list<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+) -> Result<Vec<Label>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let labels = contract_labels::Entity::find()
+        .filter(contract_labels::Column::ChainId.eq(chain_id))
+        .filter(contract_labels::Column::ContractAddress.eq(contract_address.to_vec()))
+        .order_by_asc(contract_labels::Column::Name)
+        .all(db)
+        .await
+        .context("select contract labels")?;
+    Ok(labels.into_iter().map(Label::from).collect())
+}
+
+/// Assigns the label to the address. Only addresses having a not stale
+/// verified contract could be labeled.
+pub async fn create<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+    name: &str,
+    description: Option<String>,
+) -> Result<Label, Error>
+where
+    C: ConnectionTrait,
+{
+    let name = normalize_name(name)?;
+    if contract_pages::latest_verified_contract(db, chain_id, contract_address)
+        .await?
+        .is_none()
+    {
+        return Err(Error::ContractNotVerified);
+    }
+
+    let label = contract_labels::Model::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r#"
+            INSERT INTO contract_labels (chain_id, contract_address, name, description)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (chain_id, contract_address, name) DO NOTHING
+            RETURNING *
+        "#,
+        [
+            chain_id.into(),
+            contract_address.to_vec().into(),
+            name.into(),
+            description.into(),
+        ],
+    ))
+    .one(db)
+    .await
+    .context("insert contract label")?;
+
+    label.map(Label::from).ok_or(Error::AlreadyExists)
+}
+
+/// Replaces the description of the label. Returns `None` if the address has no such label.
+pub async fn update<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+    name: &str,
+    description: Option<String>,
+) -> Result<Option<Label>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let label = contract_labels::Model::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r#"
+            UPDATE contract_labels
+            SET description = $4, updated_at = now()
+            WHERE chain_id = $1 AND contract_address = $2 AND name = $3
+            RETURNING *
+        "#,
+        [
+            chain_id.into(),
+            contract_address.to_vec().into(),
+            name.trim().into(),
+            description.into(),
+        ],
+    ))
+    .one(db)
+    .await
+    .context("update contract label")?;
+    Ok(label.map(Label::from))
+}
+
+/// Removes the label from the address. Returns `false` if the address has no such label.
+pub async fn delete<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+    name: &str,
+) -> Result<bool, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let result = contract_labels::Entity::delete_many()
+        .filter(contract_labels::Column::ChainId.eq(chain_id))
+        .filter(contract_labels::Column::ContractAddress.eq(contract_address.to_vec()))
+        .filter(contract_labels::Column::Name.eq(name.trim()))
+        .exec(db)
+        .await
+        .context("delete contract label")?;
+    Ok(result.rows_affected > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn names_are_normalized() {
+        assert_eq!("DEX", normalize_name("  DEX ").unwrap());
+        assert!(matches!(normalize_name(" "), Err(Error::InvalidName(_))));
+        assert!(matches!(
+            normalize_name(&"a".repeat(MAX_NAME_LENGTH + 1)),
+            Err(Error::InvalidName(_))
+        ));
+        assert_eq!(
+            "a".repeat(MAX_NAME_LENGTH),
+            normalize_name(&"a".repeat(MAX_NAME_LENGTH)).unwrap()
+        );
+    }
+}
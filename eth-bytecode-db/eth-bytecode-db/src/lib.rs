@@ -1,5 +1,23 @@
+pub mod abi_search;
+pub mod code_search;
+pub mod contract_pages;
+pub mod events;
+pub mod jobs;
+pub mod labels;
+pub mod licenses;
+pub mod method_sources;
+pub mod multichain_search;
+pub mod natspec;
+pub mod retention;
+pub mod reverification;
 pub mod search;
+pub mod source_checks;
+pub mod source_files;
+pub mod standards;
 pub mod verification;
+pub mod verified_contracts;
+
+mod solidity_source;
 
 #[cfg(feature = "test-utils")]
 pub mod tests;
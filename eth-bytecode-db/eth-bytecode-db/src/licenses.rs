@@ -0,0 +1,129 @@
+//! Extraction of the source code license from `SPDX-License-Identifier` comments.
+//!
+//! Identifiers of the licenses Etherscan supports are normalized to their canonical
+//! SPDX spelling (e.g. "mit" -> "MIT", "GPL-3.0-or-later" -> "GPL-3.0"), so that
+//! verified contracts could be filtered by license. Other identifiers
+//! (including license expressions, e.g. "MIT OR Apache-2.0") are kept as is.
+
+use std::collections::BTreeMap;
+
+const SPDX_PREFIX: &str = "SPDX-License-Identifier:";
+
+/// Licenses supported by Etherscan with their license type codes.
+/// Code 1 ("No License") corresponds to the sources without any license.
+const ETHERSCAN_LICENSES: [(&str, u32); 14] = [
+    ("UNLICENSED", 1),
+    ("Unlicense", 2),
+    ("MIT", 3),
+    ("GPL-2.0", 4),
+    ("GPL-3.0", 5),
+    ("LGPL-2.1", 6),
+    ("LGPL-3.0", 7),
+    ("BSD-2-Clause", 8),
+    ("BSD-3-Clause", 9),
+    ("MPL-2.0", 10),
+    ("OSL-3.0", 11),
+    ("Apache-2.0", 12),
+    ("AGPL-3.0", 13),
+    ("BUSL-1.1", 14),
+];
+
+/// Returns the normalized license of the contract. The license of the file
+/// the contract is located at takes precedence over the licenses of other files.
+pub fn extract(file_name: &str, source_files: &BTreeMap<String, String>) -> Option<String> {
+    source_files
+        .get(file_name)
+        .and_then(|content| find_identifier(content))
+        .or_else(|| {
+            source_files
+                .values()
+                .find_map(|content| find_identifier(content))
+        })
+        .map(normalize)
+}
+
+/// Returns the Etherscan license type code. Returns `None` for the licenses Etherscan
+/// does not support, so that such contracts are not reported as unlicensed.
+pub fn etherscan_license_type(license: Option<&str>) -> Option<u32> {
+    match license {
+        None => Some(1),
+        Some(license) => ETHERSCAN_LICENSES
+            .iter()
+            .find(|(identifier, _)| *identifier == license)
+            .map(|(_, code)| *code),
+    }
+}
+
+pub fn normalize(identifier: &str) -> String {
+    let identifier = identifier.trim();
+    // "-only" and "-or-later" suffixes are not distinguished by Etherscan
+    let stripped = identifier
+        .strip_suffix("-only")
+        .or_else(|| identifier.strip_suffix("-or-later"))
+        .or_else(|| identifier.strip_suffix('+'))
+        .unwrap_or(identifier);
+    ETHERSCAN_LICENSES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(stripped))
+        .map(|(known, _)| known.to_string())
+        .unwrap_or_else(|| identifier.to_string())
+}
+
+fn find_identifier(content: &str) -> Option<&str> {
+    content.lines().find_map(|line| {
+        let (_, rest) = line.split_once(SPDX_PREFIX)?;
+        // Block comments may be closed on the same line
+        let identifier = rest.split("*/").next().unwrap_or_default().trim();
+        (!identifier.is_empty()).then_some(identifier)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn files(files: &[(&str, &str)]) -> BTreeMap<String, String> {
+        files
+            .iter()
+            .map(|(name, content)| (name.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn extract_license() {
+        let source_files = files(&[
+            (
+                "A.sol",
+                "// SPDX-License-Identifier: MIT\npragma solidity ^0.8.0;",
+            ),
+            ("B.sol", "/* SPDX-License-Identifier: gpl-3.0-or-later */\n"),
+            ("C.sol", "pragma solidity ^0.8.0;"),
+        ]);
+        assert_eq!(Some("MIT".into()), extract("A.sol", &source_files));
+        assert_eq!(Some("GPL-3.0".into()), extract("B.sol", &source_files));
+        assert_eq!(
+            Some("MIT".into()),
+            extract("C.sol", &source_files),
+            "licenses of other files should be used as a fallback"
+        );
+        assert_eq!(None, extract("C.sol", &files(&[("C.sol", "")])));
+    }
+
+    #[test]
+    fn normalize_identifiers() {
+        assert_eq!("MIT", normalize(" mit "));
+        assert_eq!("GPL-2.0", normalize("GPL-2.0-only"));
+        assert_eq!("LGPL-3.0", normalize("LGPL-3.0+"));
+        assert_eq!("MIT OR Apache-2.0", normalize("MIT OR Apache-2.0"));
+    }
+
+    #[test]
+    fn etherscan_license_types() {
+        assert_eq!(Some(1), etherscan_license_type(None));
+        assert_eq!(Some(1), etherscan_license_type(Some("UNLICENSED")));
+        assert_eq!(Some(3), etherscan_license_type(Some("MIT")));
+        assert_eq!(Some(14), etherscan_license_type(Some("BUSL-1.1")));
+        assert_eq!(None, etherscan_license_type(Some("MIT OR Apache-2.0")));
+    }
+}
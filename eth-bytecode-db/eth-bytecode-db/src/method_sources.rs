@@ -0,0 +1,215 @@
+//! Locates the source code of the verified contract functions by their selectors
+//! (used for "jump to function source" in the explorer).
+//!
+//! The selector is resolved into the function name and the number of parameters via
+//! the contract abi, and the declaration is looked up in the source files. Declarations
+//! of the verified contract itself are preferred over the inherited ones. As overloaded
+//! functions are distinguished only by the number of parameters, overloads with equal
+//! arities resolve into the first declaration.
+
+use crate::{
+    contract_pages,
+    solidity_source::{self, Declaration},
+    source_files,
+};
+use anyhow::Context;
+use entity::{sea_orm_active_enums::SourceType, sources};
+use sea_orm::{ConnectionTrait, EntityTrait};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MethodSource {
+    pub file_name: String,
+    /// The first line of the declaration (1-based)
+    pub line_start: usize,
+    /// The last line of the declaration (1-based, inclusive)
+    pub line_end: usize,
+    pub code: String,
+}
+
+/// Returns the source of the function with the given selector declared in the latest
+/// not stale verified contract of the address. Returns `None` if there is no such contract,
+/// the function is missing in its abi, or its declaration could not be found.
+pub async fn get<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+    selector: [u8; 4],
+) -> Result<Option<MethodSource>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let verified_contract =
+        match contract_pages::latest_verified_contract(db, chain_id, contract_address).await? {
+            Some(verified_contract) => verified_contract,
+            None => return Ok(None),
+        };
+    let source = sources::Entity::find_by_id(verified_contract.source_id)
+        .one(db)
+        .await
+        .context("select source")?
+        .context("verified contract references a missing source")?;
+    if source.source_type != SourceType::Solidity {
+        return Ok(None);
+    }
+    let abi = match source
+        .abi
+        .clone()
+        .and_then(|abi| serde_json::from_value::<ethabi::Contract>(abi).ok())
+    {
+        Some(abi) => abi,
+        None => return Ok(None),
+    };
+    let files = source_files::source_files_contents(db, source.id).await?;
+
+    Ok(find(
+        &files,
+        &source.file_name,
+        &source.contract_name,
+        &abi,
+        selector,
+    ))
+}
+
+fn find(
+    files: &BTreeMap<String, String>,
+    file_name: &str,
+    contract_name: &str,
+    abi: &ethabi::Contract,
+    selector: [u8; 4],
+) -> Option<MethodSource> {
+    let function = abi
+        .functions()
+        .find(|function| function.short_signature() == selector)?;
+    let params = function.inputs.len();
+
+    // The file of the verified contract goes first, so that its
+    // declarations are preferred among the ones of other contracts
+    let files = files
+        .get_key_value(file_name)
+        .into_iter()
+        .chain(files.iter().filter(|(name, _)| *name != file_name));
+    let mut inherited = None;
+    for (name, content) in files {
+        for item in solidity_source::declarations(content) {
+            match item.declaration {
+                Declaration::Function {
+                    contract,
+                    name: function_name,
+                    params: function_params,
+                } if function_name == function.name && function_params == params => {
+                    if name == file_name && contract == contract_name {
+                        return Some(method_source(name, content, item.offset));
+                    }
+                    if inherited.is_none() {
+                        inherited = Some((name, content, item.offset));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    inherited.map(|(name, content, offset)| method_source(name, content, offset))
+}
+
+fn method_source(file_name: &str, content: &str, offset: usize) -> MethodSource {
+    let end = solidity_source::declaration_end(content, offset);
+    let line = |offset: usize| content[..offset].matches('\n').count() + 1;
+    MethodSource {
+        file_name: file_name.to_string(),
+        line_start: line(offset),
+        line_end: line(end),
+        code: content[offset..end].to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const BASE: &str = r#"pragma solidity ^0.8.0;
+
+contract Base {
+    function pause() public virtual {}
+
+    function owner() public view returns (address) {
+        return address(0);
+    }
+}
+"#;
+
+    const TOKEN: &str = r#"pragma solidity ^0.8.0;
+
+import "./Base.sol";
+
+contract Other {
+    function pause() public {}
+}
+
+contract Token is Base {
+    function pause() public override {
+        // paused
+    }
+}
+"#;
+
+    fn abi() -> ethabi::Contract {
+        serde_json::from_value(serde_json::json!([
+            {"type": "function", "name": "pause", "stateMutability": "nonpayable",
+             "inputs": [], "outputs": []},
+            {"type": "function", "name": "owner", "stateMutability": "view",
+             "inputs": [], "outputs": [{"name": "", "type": "address"}]}
+        ]))
+        .unwrap()
+    }
+
+    fn selector(signature: &str) -> [u8; 4] {
+        let hash = ethers_core::utils::keccak256(signature);
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    fn files() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("Base.sol".to_string(), BASE.to_string()),
+            ("Token.sol".to_string(), TOKEN.to_string()),
+        ])
+    }
+
+    #[test]
+    fn finds_contract_declaration() {
+        let expected = MethodSource {
+            file_name: "Token.sol".to_string(),
+            line_start: 10,
+            line_end: 12,
+            code: "function pause() public override {\n        // paused\n    }".to_string(),
+        };
+        let actual = find(&files(), "Token.sol", "Token", &abi(), selector("pause()"));
+        assert_eq!(Some(expected), actual);
+    }
+
+    #[test]
+    fn finds_inherited_declaration() {
+        let expected = MethodSource {
+            file_name: "Base.sol".to_string(),
+            line_start: 6,
+            line_end: 8,
+            code: "function owner() public view returns (address) {\n        return address(0);\n    }"
+                .to_string(),
+        };
+        let actual = find(&files(), "Token.sol", "Token", &abi(), selector("owner()"));
+        assert_eq!(Some(expected), actual);
+    }
+
+    #[test]
+    fn unknown_selector() {
+        let actual = find(
+            &files(),
+            "Token.sol",
+            "Token",
+            &abi(),
+            [0xde, 0xad, 0xbe, 0xef],
+        );
+        assert_eq!(None, actual);
+    }
+}
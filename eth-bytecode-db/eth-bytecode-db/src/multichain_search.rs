@@ -0,0 +1,86 @@
+//! Search of the verified contracts across all chains the deployment stores verifications for
+//! (used by the search bars of multichain explorers).
+//!
+//! Only the latest not stale verification of each address is returned. Contracts
+//! sharing the same normalized code hash are considered the same contract deployed
+//! to several chains (or addresses).
+
+use anyhow::Context;
+use sea_orm::{
+    prelude::DateTime, ConnectionTrait, DatabaseBackend, FromQueryResult, Statement, Value,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchQuery {
+    ContractAddress(Vec<u8>),
+    /// Exact (case-sensitive) name of the verified contract
+    ContractName(String),
+    /// Normalized hash of the deployed bytecode (see [`crate::search::normalized_code_hash`])
+    CodeHash(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult)]
+pub struct ChainContract {
+    pub verified_contract_id: i64,
+    pub chain_id: i64,
+    pub contract_address: Vec<u8>,
+    pub contract_name: String,
+    pub file_name: String,
+    pub compiler_version: String,
+    /// Is missing for the contracts stored before the hashes were introduced
+    pub code_hash: Option<Vec<u8>>,
+    pub verified_at: DateTime,
+}
+
+/// Returns the verified contracts matching the query ordered by chain ids and addresses.
+pub async fn search<C>(
+    db: &C,
+    query: &SearchQuery,
+    limit: u64,
+) -> Result<Vec<ChainContract>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let (condition, value): (_, Value) = match query {
+        SearchQuery::ContractAddress(address) => {
+            ("vc.contract_address = $1", address.clone().into())
+        }
+        SearchQuery::ContractName(name) => ("s.contract_name = $1", name.clone().into()),
+        SearchQuery::CodeHash(code_hash) => {
+            ("b.normalized_code_hash = $1", code_hash.clone().into())
+        }
+    };
+    let sql = format!(
+        r#"
+            SELECT
+                vc.id AS verified_contract_id, vc.chain_id, vc.contract_address,
+                s.contract_name, s.file_name, s.compiler_version,
+                b.normalized_code_hash AS code_hash, vc.created_at AS verified_at
+            FROM verified_contracts vc
+                JOIN sources s ON s.id = vc.source_id
+                LEFT JOIN bytecodes b
+                    ON b.source_id = s.id AND b.bytecode_type = 'deployed_bytecode'
+            WHERE vc.stale_at IS NULL
+                AND vc.chain_id IS NOT NULL
+                AND vc.contract_address IS NOT NULL
+                AND {condition}
+                AND NOT EXISTS (
+                    SELECT 1 FROM verified_contracts newer
+                    WHERE newer.chain_id = vc.chain_id
+                        AND newer.contract_address = vc.contract_address
+                        AND newer.stale_at IS NULL
+                        AND newer.id > vc.id
+                )
+            ORDER BY vc.chain_id, vc.contract_address
+            LIMIT $2
+        "#
+    );
+    ChainContract::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        &sql,
+        [value, (limit as i64).into()],
+    ))
+    .all(db)
+    .await
+    .context("search verified contracts across chains")
+}
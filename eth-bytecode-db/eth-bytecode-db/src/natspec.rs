@@ -0,0 +1,320 @@
+//! NatSpec documentation (https://docs.soliditylang.org/en/latest/natspec-format.html)
+//! restored from the doc comments of the stored solidity sources.
+//!
+//! The compiler `userdoc` and `devdoc` outputs are not stored, so the documentation
+//! is collected in the same format from the file the contract is declared at.
+//! Documentation inherited from other contracts is not resolved, and return values
+//! are always keyed by their position (`_0`, `_1`, ...).
+
+use crate::solidity_source::{self, Declaration};
+use serde_json::{json, Map, Value};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NatSpec {
+    pub userdoc: Option<Value>,
+    pub devdoc: Option<Value>,
+}
+
+/// Returns the documentation of the contract declared in the `source`. Documentation of the
+/// functions is keyed by their canonical signatures resolved via the `abi`, so it is
+/// omitted if the abi is missing or the function could not be found there.
+pub fn extract(
+    source: &str,
+    contract_name: &str,
+    abi: Option<&ethabi::Contract>,
+) -> Option<NatSpec> {
+    let items = documented_items(source);
+
+    let mut user_methods = Map::new();
+    let mut dev_methods = Map::new();
+    let mut contract_tags = Tags::default();
+    for (declaration, tags) in items {
+        match declaration {
+            Declaration::Contract(name) if name == contract_name => contract_tags = tags,
+            Declaration::Function {
+                contract,
+                name,
+                params,
+            } if contract == contract_name => {
+                let signature = match abi.and_then(|abi| signature(abi, &name, params)) {
+                    Some(signature) => signature,
+                    None => continue,
+                };
+                if let Some(notice) = tags.notice.clone() {
+                    user_methods.insert(signature.clone(), json!({ "notice": notice }));
+                }
+                let dev = tags.dev_object();
+                if !dev.is_empty() {
+                    dev_methods.insert(signature, Value::Object(dev));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let userdoc = (contract_tags.notice.is_some() || !user_methods.is_empty()).then(|| {
+        let mut userdoc = json!({"kind": "user", "version": 1, "methods": user_methods});
+        if let Some(notice) = &contract_tags.notice {
+            userdoc["notice"] = json!(notice);
+        }
+        userdoc
+    });
+    let contract_dev = contract_tags.dev_object();
+    let devdoc = (!contract_dev.is_empty() || !dev_methods.is_empty()).then(|| {
+        let mut devdoc = json!({"kind": "dev", "version": 1, "methods": dev_methods});
+        for (key, value) in contract_dev {
+            devdoc[key] = value;
+        }
+        devdoc
+    });
+
+    (userdoc.is_some() || devdoc.is_some()).then_some(NatSpec { userdoc, devdoc })
+}
+
+/// Resolves the canonical signature of the function by its name and the number of parameters.
+fn signature(abi: &ethabi::Contract, name: &str, params: usize) -> Option<String> {
+    let candidates = abi.functions_by_name(name).ok()?;
+    let function = match candidates.as_slice() {
+        [function] => function,
+        _ => {
+            let mut same_arity = candidates
+                .iter()
+                .filter(|function| function.inputs.len() == params);
+            match (same_arity.next(), same_arity.next()) {
+                (Some(function), None) => function,
+                _ => return None,
+            }
+        }
+    };
+    let inputs = function
+        .inputs
+        .iter()
+        .map(|param| param.kind.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    Some(format!("{}({})", function.name, inputs))
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Tags {
+    notice: Option<String>,
+    dev: Option<String>,
+    title: Option<String>,
+    author: Option<String>,
+    params: Vec<(String, String)>,
+    returns: Vec<String>,
+}
+
+impl Tags {
+    fn parse(comment: &str) -> Self {
+        enum Target {
+            Notice,
+            Dev,
+            Title,
+            Author,
+            Param,
+            Return,
+            Ignored,
+        }
+
+        let append = |field: &mut String, text: &str| {
+            if !field.is_empty() && !text.is_empty() {
+                field.push(' ');
+            }
+            field.push_str(text);
+        };
+
+        let mut tags = Self::default();
+        // Untagged comments are treated as `@notice` ones
+        let mut target = Target::Notice;
+        for line in comment.lines() {
+            let line = line.trim().trim_start_matches('*').trim();
+            let text = match line.strip_prefix('@') {
+                Some(tagged) => {
+                    let (tag, text) = tagged
+                        .split_once(char::is_whitespace)
+                        .unwrap_or((tagged, ""));
+                    let mut text = text.trim();
+                    target = match tag {
+                        "notice" => Target::Notice,
+                        "dev" => Target::Dev,
+                        "title" => Target::Title,
+                        "author" => Target::Author,
+                        "param" => {
+                            let (name, description) =
+                                text.split_once(char::is_whitespace).unwrap_or((text, ""));
+                            tags.params.push((name.to_string(), String::new()));
+                            text = description.trim();
+                            Target::Param
+                        }
+                        "return" => {
+                            tags.returns.push(String::new());
+                            Target::Return
+                        }
+                        _ => Target::Ignored,
+                    };
+                    text
+                }
+                None => line,
+            };
+            let field = match target {
+                Target::Notice => tags.notice.get_or_insert_with(String::new),
+                Target::Dev => tags.dev.get_or_insert_with(String::new),
+                Target::Title => tags.title.get_or_insert_with(String::new),
+                Target::Author => tags.author.get_or_insert_with(String::new),
+                Target::Param => match tags.params.last_mut() {
+                    Some((_, description)) => description,
+                    None => continue,
+                },
+                Target::Return => match tags.returns.last_mut() {
+                    Some(description) => description,
+                    None => continue,
+                },
+                Target::Ignored => continue,
+            };
+            append(field, text);
+        }
+        tags.notice = tags.notice.filter(|notice| !notice.is_empty());
+        tags
+    }
+
+    /// Fields of the `devdoc` object describing the documented item
+    fn dev_object(&self) -> Map<String, Value> {
+        let mut object = Map::new();
+        if let Some(dev) = &self.dev {
+            object.insert("details".into(), json!(dev));
+        }
+        if let Some(title) = &self.title {
+            object.insert("title".into(), json!(title));
+        }
+        if let Some(author) = &self.author {
+            object.insert("author".into(), json!(author));
+        }
+        if !self.params.is_empty() {
+            let params: Map<_, _> = self
+                .params
+                .iter()
+                .map(|(name, description)| (name.clone(), json!(description)))
+                .collect();
+            object.insert("params".into(), Value::Object(params));
+        }
+        if !self.returns.is_empty() {
+            let returns: Map<_, _> = self
+                .returns
+                .iter()
+                .enumerate()
+                .map(|(index, description)| (format!("_{index}"), json!(description)))
+                .collect();
+            object.insert("returns".into(), Value::Object(returns));
+        }
+        object
+    }
+}
+
+/// Documentation of the declarations which have doc comments
+fn documented_items(source: &str) -> Vec<(Declaration, Tags)> {
+    solidity_source::declarations(source)
+        .into_iter()
+        .filter(|item| !item.doc.is_empty())
+        .map(|item| (item.declaration, Tags::parse(&item.doc)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SOURCE: &str = r#"
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// @notice Not the verified one
+contract Other {
+    /// @notice Should be ignored
+    function transfer(address to, uint256 amount) external {}
+}
+
+/// @title A simple token
+/// @author Blockscout
+/// @notice Keeps track of balances
+contract Token {
+    string constant NAME = "/// not a comment {";
+
+    /**
+     * @notice Moves `amount` tokens to `to`
+     * @dev Emits no events
+     * @param to The receiver
+     *        of the tokens
+     * @param amount Amount to move
+     * @return Whether the transfer succeeded
+     */
+    function transfer(address to, uint256 amount) external returns (bool) {
+        // @notice inside the body
+        return true;
+    }
+
+    /// Returns the balance
+    function balanceOf(address owner) external view returns (uint256) { return 0; }
+
+    function undocumented() external {}
+}
+"#;
+
+    fn abi() -> ethabi::Contract {
+        serde_json::from_value(json!([
+            {"type": "function", "name": "transfer", "stateMutability": "nonpayable",
+             "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}],
+             "outputs": [{"name": "", "type": "bool"}]},
+            {"type": "function", "name": "balanceOf", "stateMutability": "view",
+             "inputs": [{"name": "owner", "type": "address"}],
+             "outputs": [{"name": "", "type": "uint256"}]},
+            {"type": "function", "name": "undocumented", "stateMutability": "nonpayable",
+             "inputs": [], "outputs": []}
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn extracts_documentation() {
+        let natspec = extract(SOURCE, "Token", Some(&abi())).expect("natspec is missing");
+
+        let expected_userdoc = json!({
+            "kind": "user",
+            "version": 1,
+            "notice": "Keeps track of balances",
+            "methods": {
+                "transfer(address,uint256)": {"notice": "Moves `amount` tokens to `to`"},
+                "balanceOf(address)": {"notice": "Returns the balance"}
+            }
+        });
+        let expected_devdoc = json!({
+            "kind": "dev",
+            "version": 1,
+            "title": "A simple token",
+            "author": "Blockscout",
+            "methods": {
+                "transfer(address,uint256)": {
+                    "details": "Emits no events",
+                    "params": {"to": "The receiver of the tokens", "amount": "Amount to move"},
+                    "returns": {"_0": "Whether the transfer succeeded"}
+                }
+            }
+        });
+        assert_eq!(Some(expected_userdoc), natspec.userdoc);
+        assert_eq!(Some(expected_devdoc), natspec.devdoc);
+    }
+
+    #[test]
+    fn methods_require_abi() {
+        let natspec = extract(SOURCE, "Token", None).expect("natspec is missing");
+        assert_eq!(json!({}), natspec.userdoc.unwrap()["methods"]);
+    }
+
+    #[test]
+    fn missing_documentation() {
+        let source = "contract Token { function transfer() external {} }";
+        assert_eq!(None, extract(source, "Token", Some(&abi())));
+        assert_eq!(None, extract(SOURCE, "Unknown", Some(&abi())));
+    }
+}
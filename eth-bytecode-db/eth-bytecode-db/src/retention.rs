@@ -0,0 +1,212 @@
+//! Retention policies of the stored verification jobs.
+//!
+//! Jobs keep their requests and results (which contain all the source files) after
+//! being completed, so long-running instances would grow unboundedly otherwise.
+//! Policies are applied to completed jobs only; queued and in-flight ones are never touched.
+//!
+//! - Requests of failed, orphaned, and cancelled jobs are purged after the configured period.
+//!   Their statuses and errors are kept, so that the jobs could still be looked up.
+//! - Results of succeeded jobs are gzip compressed after the configured period.
+//!   They are transparently decompressed when the job is retrieved.
+//! - The total size of the stored requests and results is capped. Jobs retrieved
+//!   least recently (or completed, if never retrieved) are deleted first.
+
+use anyhow::Context;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use sea_orm::{ConnectionTrait, DatabaseBackend, FromQueryResult, Statement};
+use std::{
+    io::{Read, Write},
+    time::Duration,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetentionParams {
+    /// Requests of failed, orphaned, and cancelled jobs are purged after the period
+    pub purge_failed_after: Option<Duration>,
+    /// Results of succeeded jobs are compressed after the period
+    pub compress_after: Option<Duration>,
+    /// Maximum total size (in bytes) of the requests and results of completed jobs
+    pub max_store_size: Option<u64>,
+    /// Number of results compressed in a single database round trip
+    pub batch_size: u64,
+}
+
+impl Default for RetentionParams {
+    fn default() -> Self {
+        Self {
+            purge_failed_after: None,
+            compress_after: None,
+            max_store_size: None,
+            batch_size: 100,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Failed, orphaned, and cancelled jobs whose requests have been purged
+    pub purged: u64,
+    /// Succeeded jobs whose results have been compressed
+    pub compressed: u64,
+    /// Jobs deleted to stay under the store size limit
+    pub evicted: u64,
+}
+
+#[derive(FromQueryResult)]
+struct UncompressedResult {
+    id: i64,
+    result: serde_json::Value,
+}
+
+/// Applies all configured policies. Eviction goes last,
+/// so that it accounts for the already purged and compressed jobs.
+pub async fn apply<C: ConnectionTrait>(
+    db: &C,
+    params: &RetentionParams,
+) -> Result<Report, anyhow::Error> {
+    let mut report = Report::default();
+    if let Some(period) = params.purge_failed_after {
+        report.purged = purge_failed_requests(db, period).await?;
+    }
+    if let Some(period) = params.compress_after {
+        report.compressed = compress_results(db, period, params.batch_size).await?;
+    }
+    if let Some(max_store_size) = params.max_store_size {
+        report.evicted = evict_least_recently_used(db, max_store_size).await?;
+    }
+    Ok(report)
+}
+
+/// Replaces requests of the failed, orphaned, and cancelled jobs completed
+/// more than `period` ago with `null`. Returns the number of purged jobs.
+pub async fn purge_failed_requests<C: ConnectionTrait>(
+    db: &C,
+    period: Duration,
+) -> Result<u64, anyhow::Error> {
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                UPDATE verification_jobs
+                SET request = 'null'::jsonb
+                WHERE status IN ('failed', 'orphaned', 'cancelled')
+                    AND updated_at < now() - make_interval(secs => $1)
+                    AND request <> 'null'::jsonb
+            "#,
+            [period.as_secs_f64().into()],
+        ))
+        .await
+        .context("purge failed job requests")?;
+    Ok(result.rows_affected())
+}
+
+/// Compresses results of the jobs succeeded more than `period` ago.
+/// Returns the number of compressed results.
+pub async fn compress_results<C: ConnectionTrait>(
+    db: &C,
+    period: Duration,
+    batch_size: u64,
+) -> Result<u64, anyhow::Error> {
+    let mut compressed = 0;
+    loop {
+        let results = UncompressedResult::find_by_statement(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                SELECT id, result FROM verification_jobs
+                WHERE status = 'succeeded'
+                    AND result IS NOT NULL
+                    AND updated_at < now() - make_interval(secs => $1)
+                ORDER BY id
+                LIMIT $2
+            "#,
+            [period.as_secs_f64().into(), (batch_size as i64).into()],
+        ))
+        .all(db)
+        .await
+        .context("select uncompressed job results")?;
+        let is_last_batch = (results.len() as u64) < batch_size;
+
+        for UncompressedResult { id, result } in results {
+            let data = compress(&result).context("compress job result")?;
+            db.execute(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                r#"
+                    UPDATE verification_jobs
+                    SET compressed_result = $2, result = NULL
+                    WHERE id = $1 AND result IS NOT NULL
+                "#,
+                [id.into(), data.into()],
+            ))
+            .await
+            .context("update compressed job result")?;
+            compressed += 1;
+        }
+
+        if is_last_batch {
+            return Ok(compressed);
+        }
+    }
+}
+
+/// Deletes the least recently used completed jobs until the total size of their
+/// requests and results fits into `max_store_size` bytes. Returns the number of deleted jobs.
+pub async fn evict_least_recently_used<C: ConnectionTrait>(
+    db: &C,
+    max_store_size: u64,
+) -> Result<u64, anyhow::Error> {
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                DELETE FROM verification_jobs
+                WHERE id IN (
+                    SELECT id FROM (
+                        SELECT id, sum(
+                            pg_column_size(request)
+                            + coalesce(pg_column_size(result), 0)
+                            + coalesce(octet_length(compressed_result), 0)
+                        ) OVER (ORDER BY coalesce(accessed_at, updated_at) DESC, id DESC) AS total_size
+                        FROM verification_jobs
+                        WHERE status IN ('succeeded', 'failed', 'orphaned', 'cancelled')
+                    ) AS jobs
+                    WHERE total_size > $1
+                )
+            "#,
+            [(max_store_size.min(i64::MAX as u64) as i64).into()],
+        ))
+        .await
+        .context("evict least recently used jobs")?;
+    Ok(result.rows_affected())
+}
+
+pub(crate) fn compress(value: &serde_json::Value) -> Result<Vec<u8>, anyhow::Error> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&serde_json::to_vec(value)?)?;
+    Ok(encoder.finish()?)
+}
+
+pub(crate) fn decompress(data: &[u8]) -> Result<serde_json::Value, anyhow::Error> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut decoded)?;
+    Ok(serde_json::from_slice(&decoded)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn compression_roundtrip() {
+        let value = serde_json::json!({
+            "Success": {
+                "source_files": {"A.sol": "contract A {}"},
+            }
+        });
+        let compressed = compress(&value).expect("compression failed");
+        assert_eq!(
+            value,
+            decompress(&compressed).expect("decompression failed")
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Re-runs verification for already stored contracts against the bytecode
+//! they have been originally verified with. Is useful after fixes in the
+//! bytecodes comparison logic, as allows to find contracts which verification
+//! status would be different now.
+
+use crate::verification::{
+    reverification::{reverify, StoredRequest},
+    Client, Error,
+};
+use anyhow::Context;
+use entity::{sources, verified_contracts};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepParams {
+    /// Only verified contracts with id greater than the value are processed
+    pub after_id: Option<i64>,
+    /// Maximum number of verified contracts to be processed
+    pub limit: Option<u64>,
+    /// Number of verified contracts retrieved from the database at once
+    pub batch_size: u64,
+}
+
+impl Default for SweepParams {
+    fn default() -> Self {
+        Self {
+            after_id: None,
+            limit: None,
+            batch_size: 100,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NewStatus {
+    /// Verification succeeded but resulted in another contract
+    /// than the one stored for the verified contract
+    SourceMismatch {
+        file_name: String,
+        contract_name: String,
+    },
+    /// Verification failed (e.g., the local compilation does not match the bytecode anymore)
+    Failed { message: String },
+    /// Verifier considered the stored request invalid
+    InvalidRequest { message: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub verified_contract_id: i64,
+    pub source_id: i64,
+    pub chain_id: Option<i64>,
+    pub contract_address: Option<Vec<u8>>,
+    pub new_status: NewStatus,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    /// Number of verified contracts which have been re-verified
+    pub checked: u64,
+    /// Number of verified contracts skipped as their original requests are not stored
+    pub skipped: u64,
+    /// Id of the last processed verified contract.
+    /// May be used as `after_id` to continue the sweep.
+    pub last_id: Option<i64>,
+    /// Verified contracts which status has changed
+    pub changes: Vec<StatusChange>,
+}
+
+/// Re-verifies stored contracts in the order of their ids.
+///
+/// Internal errors of the verifier service abort the sweep,
+/// as they do not tell anything about the contract status.
+pub async fn sweep(client: Client, params: SweepParams) -> Result<Report, anyhow::Error> {
+    let mut report = Report::default();
+    let mut last_id = params.after_id;
+
+    loop {
+        let remaining = params
+            .limit
+            .map(|limit| limit - (report.checked + report.skipped));
+        let batch_size = match remaining {
+            Some(0) => break,
+            Some(remaining) => remaining.min(params.batch_size),
+            None => params.batch_size,
+        };
+
+        let mut query = verified_contracts::Entity::find()
+            .find_also_related(sources::Entity)
+            // Stale contracts do not correspond to the code deployed on chain anymore
+            .filter(verified_contracts::Column::StaleAt.is_null())
+            .order_by_asc(verified_contracts::Column::Id)
+            .limit(batch_size);
+        if let Some(last_id) = last_id {
+            query = query.filter(verified_contracts::Column::Id.gt(last_id));
+        }
+        let batch = query
+            .all(client.db_client.as_ref())
+            .await
+            .context("select verified contracts")?;
+        if batch.is_empty() {
+            break;
+        }
+
+        for (verified_contract, source) in batch {
+            last_id = Some(verified_contract.id);
+            report.last_id = last_id;
+
+            let source = source.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "verified contract {} references non-existing source",
+                    verified_contract.id
+                )
+            })?;
+            let request = match StoredRequest::parse(
+                &source.source_type,
+                &verified_contract.verification_type,
+                verified_contract.verification_settings.clone(),
+            ) {
+                Some(Ok(request)) => request,
+                Some(Err(err)) => {
+                    tracing::warn!(
+                        verified_contract_id = verified_contract.id,
+                        "stored verification settings are invalid: {err}"
+                    );
+                    report.skipped += 1;
+                    continue;
+                }
+                None => {
+                    report.skipped += 1;
+                    continue;
+                }
+            };
+
+            let new_status = match reverify(client.clone(), request).await {
+                Ok(result)
+                    if result.file_name == source.file_name
+                        && result.contract_name == source.contract_name =>
+                {
+                    None
+                }
+                Ok(result) => Some(NewStatus::SourceMismatch {
+                    file_name: result.file_name,
+                    contract_name: result.contract_name,
+                }),
+                Err(Error::VerificationFailed { message }) => Some(NewStatus::Failed { message }),
+                Err(Error::InvalidArgument(message)) => Some(NewStatus::InvalidRequest { message }),
+                Err(Error::Internal(err)) => {
+                    return Err(err.context(format!(
+                        "re-verification of verified contract {}",
+                        verified_contract.id
+                    )))
+                }
+            };
+            report.checked += 1;
+
+            if let Some(new_status) = new_status {
+                tracing::info!(
+                    verified_contract_id = verified_contract.id,
+                    ?new_status,
+                    "verification status has changed"
+                );
+                report.changes.push(StatusChange {
+                    verified_contract_id: verified_contract.id,
+                    source_id: source.id,
+                    chain_id: verified_contract.chain_id,
+                    contract_address: verified_contract.contract_address,
+                    new_status,
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
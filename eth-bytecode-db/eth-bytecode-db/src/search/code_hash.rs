@@ -0,0 +1,109 @@
+//! Lookup of the verified contracts by the hash of their normalized deployed bytecode.
+//!
+//! Normalized code is the deployed bytecode with all metadata hash (CBOR encoded)
+//! parts removed, so that clones of the same contract compiled from differently
+//! formatted sources share the same hash.
+
+use super::MatchContract;
+use crate::verification::MatchType;
+use entity::{
+    bytecode_parts, bytecodes, parts,
+    sea_orm_active_enums::{BytecodeType, PartType},
+};
+use sea_orm::{entity::prelude::*, ActiveValue::Set, ConnectionTrait, QueryOrder, QuerySelect};
+
+/// Returns keccak256 hash of the main (non-metadata) parts of the bytecode concatenated.
+pub fn normalized_code_hash<'a>(main_parts: impl IntoIterator<Item = &'a [u8]>) -> [u8; 32] {
+    let code: Vec<u8> = main_parts.into_iter().flatten().copied().collect();
+    ethers_core::utils::keccak256(code)
+}
+
+pub async fn find_by_code_hash<C>(
+    db: &C,
+    code_hash: &[u8],
+) -> Result<Vec<MatchContract>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let bytecodes = bytecodes::Entity::find()
+        .filter(bytecodes::Column::NormalizedCodeHash.eq(code_hash.to_vec()))
+        .filter(bytecodes::Column::BytecodeType.eq(BytecodeType::DeployedBytecode))
+        .order_by_asc(bytecodes::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut matches = vec![];
+    for bytecode in bytecodes {
+        // Metadata hashes are not part of the normalized code,
+        // so the found contracts may be considered partial matches only
+        let match_contract =
+            MatchContract::build_by_deployed_bytecode(db, bytecode.source_id, MatchType::Partial)
+                .await?;
+        matches.push(match_contract);
+    }
+    Ok(matches)
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+enum QueryAs {
+    BytecodeId,
+}
+
+/// Calculates normalized code hashes of the deployed bytecodes stored
+/// before the hashes were introduced. Processes at most `batch_size` bytecodes
+/// and returns the number of the bytecodes updated.
+pub async fn fill_missing_code_hashes<C>(db: &C, batch_size: u64) -> Result<u64, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let bytecode_ids: Vec<i64> = bytecodes::Entity::find()
+        .filter(bytecodes::Column::NormalizedCodeHash.is_null())
+        .filter(bytecodes::Column::BytecodeType.eq(BytecodeType::DeployedBytecode))
+        .order_by_asc(bytecodes::Column::Id)
+        .limit(batch_size)
+        .select_only()
+        .column_as(bytecodes::Column::Id, QueryAs::BytecodeId)
+        .into_values::<_, QueryAs>()
+        .all(db)
+        .await?;
+
+    let bytecodes_parts = bytecodes::Entity::find()
+        .filter(bytecodes::Column::Id.is_in(bytecode_ids))
+        .find_with_related(parts::Entity)
+        // order by bytecode_parts::Order is important to keep the parts in bytecode order
+        .order_by_asc(bytecode_parts::Column::Order)
+        .all(db)
+        .await?;
+
+    let mut updated = 0;
+    for (bytecode, parts) in bytecodes_parts {
+        let code_hash = normalized_code_hash(
+            parts
+                .iter()
+                .filter(|part| part.part_type == PartType::Main)
+                .map(|part| part.data.as_slice()),
+        );
+        bytecodes::ActiveModel {
+            id: Set(bytecode.id),
+            normalized_code_hash: Set(Some(code_hash.to_vec())),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+        updated += 1;
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn code_hash_of_main_parts() {
+        let main_parts: [&[u8]; 2] = [&[0x60, 0x80, 0x60, 0x40], &[0x52, 0x00]];
+        let expected = ethers_core::utils::keccak256([0x60, 0x80, 0x60, 0x40, 0x52, 0x00]);
+        assert_eq!(expected, normalized_code_hash(main_parts));
+    }
+}
@@ -1,8 +1,8 @@
 use super::{bytecodes_comparison::extract_constructor_args, BytecodeRemote};
-use crate::{verification, verification::SourceType};
+use crate::{source_files, verification, verification::SourceType};
 use anyhow::Context;
 use bytes::Bytes;
-use entity::{files, sea_orm_active_enums::BytecodeType, sources};
+use entity::{sea_orm_active_enums::BytecodeType, sources};
 use ethabi::Constructor;
 use sea_orm::{prelude::DbErr, ConnectionTrait, EntityTrait};
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,8 @@ use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MatchContract {
+    /// Id of the stored source the files could be retrieved by
+    pub source_id: i64,
     pub file_name: String,
     pub contract_name: String,
     pub compiler_version: String,
@@ -33,21 +35,41 @@ impl MatchContract {
     where
         C: ConnectionTrait,
     {
-        let mut result = sources::Entity::find_by_id(source_id)
-            .find_with_related(files::Entity)
-            .all(db)
-            .await?;
-
-        let (source, files) = result
-            .pop()
+        let source = sources::Entity::find_by_id(source_id)
+            .one(db)
+            .await?
             .ok_or_else(|| DbErr::RecordNotFound("bytecode doesn't have valid source_id".into()))?;
+        let files = source_files::source_files_contents(db, source_id).await?;
 
         Self::build_from_db_data(source, files, remote, match_type).await
     }
 
-    async fn build_from_db_data(
+    /// Builds the contract as if its own deployed bytecode has been searched for.
+    /// Is used when the contract has been found without the remote bytecode.
+    pub async fn build_by_deployed_bytecode<C>(
+        db: &C,
+        source_id: i64,
+        match_type: verification::MatchType,
+    ) -> Result<Self, anyhow::Error>
+    where
+        C: ConnectionTrait,
+    {
+        let source = sources::Entity::find_by_id(source_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("bytecode doesn't have valid source_id".into()))?;
+        let files = source_files::source_files_contents(db, source_id).await?;
+        let remote = BytecodeRemote {
+            bytecode_type: BytecodeType::DeployedBytecode,
+            data: Bytes::copy_from_slice(&source.raw_deployed_bytecode),
+        };
+
+        Self::build_from_db_data(source, files, &remote, match_type).await
+    }
+
+    pub(crate) async fn build_from_db_data(
         source: sources::Model,
-        source_files: Vec<files::Model>,
+        source_files: BTreeMap<String, String>,
         remote: &BytecodeRemote,
         match_type: verification::MatchType,
     ) -> Result<Self, anyhow::Error> {
@@ -69,11 +91,8 @@ impl MatchContract {
             e
         })
         .context("invalid constructor arguments")?;
-        let source_files: BTreeMap<String, String> = source_files
-            .into_iter()
-            .map(|f| (f.name, f.content))
-            .collect();
         let match_contract = MatchContract {
+            source_id: source.id,
             file_name: source.file_name,
             contract_name: source.contract_name,
             compiler_version: source.compiler_version,
@@ -108,7 +127,7 @@ mod tests {
     use super::*;
     use crate::verification::MatchType;
     use blockscout_display_bytes::Bytes as DisplayBytes;
-    use entity::{files, sea_orm_active_enums::BytecodeType};
+    use entity::sea_orm_active_enums::BytecodeType;
     use pretty_assertions::assert_eq;
     use std::str::FromStr;
 
@@ -147,19 +166,14 @@ mod tests {
             created_at: Default::default(),
             updated_at: Default::default(),
             file_ids_hash: Default::default(),
+            license: None,
         }
     }
 
     #[tokio::test]
     async fn test_build_match_contract() {
         let source = source();
-        let files = vec![files::Model {
-            id: 1,
-            created_at: Default::default(),
-            updated_at: Default::default(),
-            name: "Number.sol".into(),
-            content: "contract Number {}".into(),
-        }];
+        let files = BTreeMap::from([("Number.sol".into(), "contract Number {}".into())]);
 
         let remote = BytecodeRemote {
             bytecode_type: BytecodeType::CreationInput,
@@ -178,6 +192,7 @@ mod tests {
         .await
         .expect("unexpected error");
 
+        assert_eq!(result.source_id, source.id);
         assert_eq!(result.file_name, source.file_name);
         assert_eq!(result.contract_name, source.contract_name);
         assert_eq!(result.compiler_version, source.compiler_version);
@@ -218,7 +233,7 @@ mod tests {
         };
         let _ = MatchContract::build_from_db_data(
             source,
-            vec![],
+            BTreeMap::new(),
             &remote,
             verification::MatchType::Full,
         )
@@ -1,12 +1,14 @@
 mod any_match;
 mod bytecodes_comparison;
 mod candidates;
+mod code_hash;
 mod full_match;
 mod match_contract;
 mod partial_match;
 mod types;
 
 pub use any_match::find_contract;
+pub use code_hash::{fill_missing_code_hashes, find_by_code_hash, normalized_code_hash};
 pub use entity::sea_orm_active_enums::BytecodeType;
 pub use full_match::find_full_match_contract;
 pub use match_contract::MatchContract;
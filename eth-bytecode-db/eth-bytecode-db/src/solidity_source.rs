@@ -0,0 +1,276 @@
+//! Lightweight lexical scanning of solidity sources.
+//!
+//! Neither asts nor source maps are stored, so declarations are located in the source
+//! text directly. Only comments, string literals and braces are taken into account,
+//! which is enough to find contract and function declarations of a compilable source.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Declaration {
+    Contract(String),
+    Function {
+        contract: String,
+        name: String,
+        params: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SourceDeclaration {
+    pub declaration: Declaration,
+    /// Byte offset of the declaration start in the source
+    pub offset: usize,
+    /// Content of the doc comments (`///` and `/** */`) immediately preceding the declaration
+    pub doc: String,
+}
+
+/// Returns the contract (library, interface) declarations and the functions declared
+/// directly inside them in the order they appear in the source.
+pub(crate) fn declarations(source: &str) -> Vec<SourceDeclaration> {
+    let bytes = source.as_bytes();
+    let mut declarations = vec![];
+    let mut doc = String::new();
+    let mut depth = 0usize;
+    let mut contract = None;
+    let mut position = 0;
+    while position < bytes.len() {
+        let rest = &source[position..];
+        if rest.starts_with("///") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            doc.push_str(&rest[3..end]);
+            doc.push('\n');
+            position += end;
+        } else if rest.starts_with("//") {
+            position += rest.find('\n').unwrap_or(rest.len());
+        } else if rest.starts_with("/*") {
+            let end = comment_end(rest);
+            if rest.starts_with("/**") && !rest.starts_with("/**/") {
+                doc.push_str(&rest[3..end.saturating_sub(2).max(3)]);
+                doc.push('\n');
+            }
+            position += end;
+        } else if bytes[position].is_ascii_whitespace() {
+            position += 1;
+        } else if keyword(rest, "abstract").is_some() {
+            // Keeps the doc comment for the `contract` keyword which follows
+            position += skip_token(rest, &mut depth);
+        } else {
+            let declaration = match depth {
+                0 => contract_name(rest).map(|name| {
+                    contract = Some(name.clone());
+                    Declaration::Contract(name)
+                }),
+                1 => contract.clone().and_then(|contract| {
+                    function(rest).map(|(name, params)| Declaration::Function {
+                        contract,
+                        name,
+                        params,
+                    })
+                }),
+                _ => None,
+            };
+            if let Some(declaration) = declaration {
+                declarations.push(SourceDeclaration {
+                    declaration,
+                    offset: position,
+                    doc: doc.clone(),
+                });
+            }
+            doc.clear();
+            position += skip_token(rest, &mut depth);
+        }
+    }
+    declarations
+}
+
+/// Returns the byte offset right after the declaration starting at `offset`:
+/// either after its closing brace, or after the semicolon if it has no body.
+pub(crate) fn declaration_end(source: &str, offset: usize) -> usize {
+    let bytes = source.as_bytes();
+    let mut depth = 0usize;
+    let mut position = offset;
+    while position < bytes.len() {
+        let rest = &source[position..];
+        if rest.starts_with("//") {
+            position += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+        if rest.starts_with("/*") {
+            position += comment_end(rest);
+            continue;
+        }
+        match bytes[position] {
+            b'"' | b'\'' => {
+                position += string_length(rest);
+                continue;
+            }
+            b'{' => depth += 1,
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return position + 1;
+                }
+            }
+            b';' if depth == 0 => return position + 1,
+            _ => {}
+        }
+        position += 1;
+    }
+    bytes.len()
+}
+
+/// Length of the block comment the code starts with (including the delimiters)
+fn comment_end(code: &str) -> usize {
+    code[2..]
+        .find("*/")
+        .map(|end| end + 4)
+        .unwrap_or(code.len())
+}
+
+/// Skips the code up to the next whitespace or comment, updating the braces depth.
+/// String literals are skipped as a whole. Returns the number of bytes skipped.
+fn skip_token(code: &str, depth: &mut usize) -> usize {
+    let bytes = code.as_bytes();
+    let mut position = 0;
+    while position < bytes.len() {
+        match bytes[position] {
+            b'/' if matches!(bytes.get(position + 1), Some(b'/') | Some(b'*')) => break,
+            byte if byte.is_ascii_whitespace() => break,
+            b'"' | b'\'' => {
+                position += string_length(&code[position..]);
+                continue;
+            }
+            b'{' => *depth += 1,
+            b'}' => *depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        position += 1;
+    }
+    position.clamp(1, bytes.len())
+}
+
+/// Length of the string literal the code starts with (including the quotes)
+fn string_length(code: &str) -> usize {
+    let bytes = code.as_bytes();
+    let quote = bytes[0];
+    let mut position = 1;
+    while position < bytes.len() && bytes[position] != quote {
+        if bytes[position] == b'\\' {
+            position += 1;
+        }
+        position += 1;
+    }
+    (position + 1).min(bytes.len())
+}
+
+/// Parses the name of the contract, library or interface declared at the start of the code
+fn contract_name(code: &str) -> Option<String> {
+    let declaration = ["contract", "library", "interface"]
+        .iter()
+        .find_map(|name| keyword(code, name))?;
+    identifier(declaration)
+}
+
+/// Parses the name and the number of parameters of the function declared at the start of the code
+fn function(code: &str) -> Option<(String, usize)> {
+    let declaration = keyword(code, "function")?;
+    let name = identifier(declaration)?;
+    let params = declaration[name.len()..].trim_start().strip_prefix('(')?;
+
+    let mut depth = 0;
+    let mut commas = 0;
+    let mut empty = true;
+    for char in params.chars() {
+        match char {
+            '(' | '[' => depth += 1,
+            ')' | ']' if depth == 0 => break,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => commas += 1,
+            _ => {}
+        }
+        if !char.is_whitespace() {
+            empty = false;
+        }
+    }
+    let params = if empty { 0 } else { commas + 1 };
+    Some((name, params))
+}
+
+/// If the code starts with the keyword, returns the code following it
+fn keyword<'a>(code: &'a str, keyword: &str) -> Option<&'a str> {
+    code.strip_prefix(keyword)
+        .filter(|rest| rest.starts_with(char::is_whitespace))
+        .map(str::trim_start)
+}
+
+fn identifier(code: &str) -> Option<String> {
+    let end = code
+        .find(|char: char| !(char.is_ascii_alphanumeric() || char == '_' || char == '$'))
+        .unwrap_or(code.len());
+    (end > 0).then(|| code[..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SOURCE: &str = r#"pragma solidity ^0.8.0;
+
+abstract contract Base {
+    function hook(uint256[2][] memory values, bytes calldata) internal virtual;
+}
+
+/// The token
+contract Token is Base {
+    string constant BRACE = "} function fake() {";
+
+    /* function commented() {} */
+    function transfer(address to, uint256 amount) external returns (bool) {
+        if (amount > 0) { return true; }
+        return false;
+    }
+
+    function hook(uint256[2][] memory, bytes calldata) internal override {}
+}
+"#;
+
+    #[test]
+    fn finds_declarations() {
+        let declarations: Vec<_> = declarations(SOURCE)
+            .into_iter()
+            .map(|item| (item.declaration, item.doc.trim().to_string()))
+            .collect();
+        let function = |contract: &str, name: &str, params| Declaration::Function {
+            contract: contract.into(),
+            name: name.into(),
+            params,
+        };
+        let expected = vec![
+            (Declaration::Contract("Base".into()), "".into()),
+            (function("Base", "hook", 2), "".into()),
+            (Declaration::Contract("Token".into()), "The token".into()),
+            (function("Token", "transfer", 2), "".into()),
+            (function("Token", "hook", 2), "".into()),
+        ];
+        assert_eq!(expected, declarations);
+    }
+
+    #[test]
+    fn finds_declaration_ends() {
+        let declarations = declarations(SOURCE);
+        let code = |index: usize| {
+            let offset = declarations[index].offset;
+            &SOURCE[offset..declaration_end(SOURCE, offset)]
+        };
+
+        assert_eq!(
+            "function hook(uint256[2][] memory values, bytes calldata) internal virtual;",
+            code(1)
+        );
+        assert_eq!(
+            "function transfer(address to, uint256 amount) external returns (bool) {\n        \
+            if (amount > 0) { return true; }\n        return false;\n    }",
+            code(3)
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use super::{Source, SourceCheck, SourceWarning};
+use crate::verification::SourceType;
+use std::ops::Range;
+
+/// Detects solidity functions named as getters (e.g., `getOwner`, `isWhitelisted`,
+/// `balanceOf`) which are neither `view` nor `pure`, so that calling them may modify the state.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MisleadingGetterCheck;
+
+impl MisleadingGetterCheck {
+    const NAME: &'static str = "misleading_getters";
+
+    const GETTER_PREFIXES: [&'static str; 4] = ["get", "is", "has", "view"];
+    const GETTER_NAMES: [&'static str; 7] = [
+        "allowance",
+        "balanceOf",
+        "decimals",
+        "name",
+        "owner",
+        "symbol",
+        "totalSupply",
+    ];
+
+    fn is_getter_name(name: &str) -> bool {
+        Self::GETTER_NAMES.contains(&name)
+            || Self::GETTER_PREFIXES.iter().any(|prefix| {
+                name.strip_prefix(prefix)
+                    .and_then(|rest| rest.chars().next())
+                    .map(|c| c.is_ascii_uppercase() || c == '_')
+                    .unwrap_or_default()
+            })
+    }
+
+    /// Returns the names of misleading getters with the byte offsets of their declarations.
+    fn find_misleading_getters(code: &str) -> Vec<(String, usize)> {
+        let mut getters = vec![];
+        for (offset, _) in code.match_indices("function") {
+            let preceded_by_identifier = code[..offset]
+                .chars()
+                .next_back()
+                .map(is_identifier_char)
+                .unwrap_or_default();
+            let rest = &code[offset + "function".len()..];
+            if preceded_by_identifier || !rest.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let rest = rest.trim_start();
+            let name_length = rest
+                .find(|c: char| !is_identifier_char(c))
+                .unwrap_or(rest.len());
+            let name = &rest[..name_length];
+            if !Self::is_getter_name(name) {
+                continue;
+            }
+            // Declarations without a body (interfaces and abstract functions) are skipped
+            let header = match rest.find(['{', ';']) {
+                Some(end) if rest[end..].starts_with('{') => &rest[name_length..end],
+                _ => continue,
+            };
+            let is_read_only = header
+                .split(|c: char| !is_identifier_char(c))
+                .any(|word| word == "view" || word == "pure");
+            if !is_read_only {
+                getters.push((name.to_string(), offset));
+            }
+        }
+        getters
+    }
+}
+
+impl SourceCheck for MisleadingGetterCheck {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn check(&self, source: &Source) -> Vec<SourceWarning> {
+        if source.source_type != SourceType::Solidity {
+            return vec![];
+        }
+        source
+            .source_files
+            .iter()
+            .flat_map(|(file_name, content)| {
+                let code = blank_comments(content);
+                Self::find_misleading_getters(&code)
+                    .into_iter()
+                    .map(|(name, offset)| SourceWarning {
+                        check: Self::NAME.to_string(),
+                        file_name: file_name.clone(),
+                        line: line_number(&code, offset),
+                        message: format!(
+                            "Function \"{name}\" is named as a getter but is neither view nor pure, \
+                            so calling it may modify the state"
+                        ),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+fn line_number(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// Replaces the comments with spaces, so that the offsets and line numbers are retained.
+fn blank_comments(content: &str) -> String {
+    let mut comments: Vec<Range<usize>> = vec![];
+    let mut position = 0;
+    while position < content.len() {
+        let rest = &content[position..];
+        let end = if rest.starts_with("//") {
+            rest.find('\n')
+                .map(|end| position + end)
+                .unwrap_or(content.len())
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            comment
+                .find("*/")
+                .map(|end| position + 2 + end + 2)
+                .unwrap_or(content.len())
+        } else {
+            position += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+            continue;
+        };
+        comments.push(position..end);
+        position = end;
+    }
+
+    let mut result = content.to_string();
+    for comment in comments.into_iter().rev() {
+        let blank: String = content[comment.clone()]
+            .chars()
+            .map(|c| if c == '\n' { '\n' } else { ' ' })
+            .collect();
+        result.replace_range(comment, &blank);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::tests::{lines, source},
+        *,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn misleading_getters_are_detected() {
+        let content = r#"
+            interface IToken {
+                function getOwner() external returns (address);
+            }
+            contract Token {
+                // function getFee() public returns (uint256) {
+                function getOwner() public view returns (address) { return owner; }
+                function isWhitelisted(address account) public returns (bool) {
+                    fee = 0;
+                    return true;
+                }
+                function balanceOf(address) external pure returns (uint256) { return 0; }
+                function totalSupply()
+                    external
+                    returns (uint256)
+                {
+                    drain();
+                }
+                function history() public returns (uint256) { return 1; }
+                function getaway() public { }
+            }
+        "#;
+        let warnings = MisleadingGetterCheck.check(&source(SourceType::Solidity, content));
+        assert_eq!(vec![8, 13], lines(warnings.clone()));
+        assert!(
+            warnings[0].message.contains("\"isWhitelisted\""),
+            "invalid message: {}",
+            warnings[0].message
+        );
+
+        let vyper = MisleadingGetterCheck.check(&source(SourceType::Vyper, content));
+        assert!(vyper.is_empty(), "only solidity sources should be checked");
+    }
+
+    #[test]
+    fn comments_are_blanked() {
+        let content = "a // b\nc /* d\ne */ f";
+        let expected = "a     \nc     \n     f";
+        assert_eq!(expected, blank_comments(content));
+    }
+}
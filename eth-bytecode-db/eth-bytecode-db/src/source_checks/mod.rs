@@ -0,0 +1,85 @@
+//! Checks of the verified sources for the tricks used to hide malicious behaviour
+//! from the readers of the published code.
+//!
+//! Checks are run before the sources are published, and the found issues are attached
+//! to the verification result as warnings for the explorer to display.
+//! The verification itself is never rejected by the checks.
+
+mod misleading_getters;
+mod unicode;
+
+pub use misleading_getters::MisleadingGetterCheck;
+pub use unicode::UnicodeCheck;
+
+use crate::verification::Source;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Debug, sync::Arc};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceWarning {
+    /// Name of the check which has found the issue
+    pub check: String,
+    pub file_name: String,
+    /// 1-based number of the line the issue has been found at
+    pub line: usize,
+    pub message: String,
+}
+
+pub trait SourceCheck: Debug + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn check(&self, source: &Source) -> Vec<SourceWarning>;
+}
+
+/// Set of the checks run on every published source. Is empty by default.
+#[derive(Clone, Debug, Default)]
+pub struct SourceChecks {
+    checks: Vec<Arc<dyn SourceCheck>>,
+}
+
+impl SourceChecks {
+    pub fn new(checks: Vec<Arc<dyn SourceCheck>>) -> Self {
+        Self { checks }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    pub fn run(&self, source: &Source) -> Vec<SourceWarning> {
+        self.checks
+            .iter()
+            .flat_map(|check| check.check(source))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verification::{MatchType, SourceType};
+    use std::collections::BTreeMap;
+
+    pub(super) fn source(source_type: SourceType, content: &str) -> Source {
+        Source {
+            file_name: "Token.sol".to_string(),
+            contract_name: "Token".to_string(),
+            compiler_version: "v0.8.21+commit.d9974bed".to_string(),
+            compiler_settings: "{}".to_string(),
+            source_type,
+            source_files: BTreeMap::from([("Token.sol".to_string(), content.to_string())]),
+            abi: None,
+            constructor_arguments: None,
+            match_type: MatchType::Full,
+            raw_creation_input: vec![],
+            raw_deployed_bytecode: vec![],
+            creation_input_parts: vec![],
+            deployed_bytecode_parts: vec![],
+            warnings: vec![],
+        }
+    }
+
+    pub(super) fn lines(warnings: Vec<SourceWarning>) -> Vec<usize> {
+        warnings.into_iter().map(|warning| warning.line).collect()
+    }
+}
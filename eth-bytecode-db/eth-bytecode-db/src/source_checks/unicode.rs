@@ -0,0 +1,340 @@
+//! Detection of the "Trojan Source" tricks (CVE-2021-42574 and CVE-2021-42694).
+//!
+//! Bidirectional control characters reorder the code the way it is displayed,
+//! so that e.g. a part of the code looks commented out while being compiled.
+//! Invisible characters and confusables (letters of other scripts looking like
+//! the latin ones) make different words look the same to the readers.
+
+use super::{Source, SourceCheck, SourceWarning};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Detects bidirectional control characters, invisible characters,
+/// and words which look like other latin words of the sources or mix
+/// latin letters with the letters of other scripts.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UnicodeCheck;
+
+impl UnicodeCheck {
+    const NAME: &'static str = "unicode";
+
+    fn check_line(line: &str, latin_words: &BTreeMap<String, &str>) -> Vec<String> {
+        let mut messages = vec![];
+
+        let bidi_controls = line
+            .chars()
+            .filter_map(|c| bidi_control_name(c).map(|name| (c, name)))
+            .collect::<Vec<_>>();
+        if !bidi_controls.is_empty() {
+            messages.push(format!(
+                "Bidirectional control characters {} change the order the code is displayed in",
+                describe(&bidi_controls)
+            ));
+        }
+        if !bidi_controls_terminated(line) {
+            messages.push(
+                "Bidirectional control characters are not terminated at the end of the line, \
+                so the following code may be displayed in a different order"
+                    .to_string(),
+            );
+        }
+
+        let invisible = line
+            .chars()
+            .filter_map(|c| invisible_name(c).map(|name| (c, name)))
+            .collect::<Vec<_>>();
+        if !invisible.is_empty() {
+            messages.push(format!(
+                "Invisible characters {} are hidden from the readers",
+                describe(&invisible)
+            ));
+        }
+
+        let mut reported = BTreeSet::new();
+        for word in words(line) {
+            if word.is_ascii() || !reported.insert(word) {
+                continue;
+            }
+            if let Some(latin) = latin_words.get(&skeleton(word)) {
+                messages.push(format!(
+                    "Word \"{word}\" looks the same as \"{latin}\" but consists of different characters"
+                ));
+            } else if word.chars().any(|c| c.is_ascii_alphabetic())
+                && word.chars().any(is_other_script_letter)
+            {
+                messages.push(format!(
+                    "Word \"{word}\" mixes latin letters with letters of other scripts"
+                ));
+            }
+        }
+
+        messages
+    }
+}
+
+impl SourceCheck for UnicodeCheck {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn check(&self, source: &Source) -> Vec<SourceWarning> {
+        // Latin words of all the files by their skeletons, so that the confusables
+        // could be matched against the words they imitate
+        let latin_words: BTreeMap<String, &str> = source
+            .source_files
+            .values()
+            .flat_map(|content| words(content))
+            .filter(|word| word.is_ascii())
+            .map(|word| (word.to_string(), word))
+            .collect();
+
+        source
+            .source_files
+            .iter()
+            .flat_map(|(file_name, content)| {
+                // Byte order mark at the beginning of the file is legitimate
+                let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+                content
+                    .lines()
+                    .enumerate()
+                    .flat_map(|(index, line)| {
+                        Self::check_line(line, &latin_words)
+                            .into_iter()
+                            .map(move |message| SourceWarning {
+                                check: Self::NAME.to_string(),
+                                file_name: file_name.clone(),
+                                line: index + 1,
+                                message,
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+fn words(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .filter(|word| !word.is_empty())
+}
+
+/// Formats the characters as `U+202E (right-to-left override)`. Each character is listed once.
+fn describe(chars: &[(char, &str)]) -> String {
+    chars
+        .iter()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|(c, name)| format!("U+{:04X} ({name})", *c as u32))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn bidi_control_name(c: char) -> Option<&'static str> {
+    let name = match c {
+        '\u{061c}' => "arabic letter mark",
+        '\u{200e}' => "left-to-right mark",
+        '\u{200f}' => "right-to-left mark",
+        '\u{202a}' => "left-to-right embedding",
+        '\u{202b}' => "right-to-left embedding",
+        '\u{202c}' => "pop directional formatting",
+        '\u{202d}' => "left-to-right override",
+        '\u{202e}' => "right-to-left override",
+        '\u{2066}' => "left-to-right isolate",
+        '\u{2067}' => "right-to-left isolate",
+        '\u{2068}' => "first strong isolate",
+        '\u{2069}' => "pop directional isolate",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Embeddings, overrides and isolates opened within the line should be closed
+/// within the same line, otherwise they affect the following code as well.
+fn bidi_controls_terminated(line: &str) -> bool {
+    let mut embeddings = 0usize;
+    let mut isolates = 0usize;
+    for c in line.chars() {
+        match c {
+            '\u{202a}' | '\u{202b}' | '\u{202d}' | '\u{202e}' => embeddings += 1,
+            '\u{202c}' => embeddings = embeddings.saturating_sub(1),
+            '\u{2066}' | '\u{2067}' | '\u{2068}' => isolates += 1,
+            '\u{2069}' => isolates = isolates.saturating_sub(1),
+            _ => {}
+        }
+    }
+    embeddings == 0 && isolates == 0
+}
+
+fn invisible_name(c: char) -> Option<&'static str> {
+    let name = match c {
+        '\u{00ad}' => "soft hyphen",
+        '\u{180e}' => "mongolian vowel separator",
+        '\u{200b}' => "zero width space",
+        '\u{200c}' => "zero width non-joiner",
+        '\u{200d}' => "zero width joiner",
+        '\u{2060}' => "word joiner",
+        '\u{2061}'..='\u{2064}' => "invisible operator",
+        '\u{feff}' => "zero width no-break space",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Letters of the scripts containing look-alikes of the latin letters
+/// (greek, cyrillic, armenian, cherokee, and fullwidth forms)
+fn is_other_script_letter(c: char) -> bool {
+    matches!(c,
+        '\u{0370}'..='\u{03ff}'
+        | '\u{0400}'..='\u{052f}'
+        | '\u{0530}'..='\u{058f}'
+        | '\u{13a0}'..='\u{13ff}'
+        | '\u{ff21}'..='\u{ff3a}'
+        | '\u{ff41}'..='\u{ff5a}')
+}
+
+/// Replaces the look-alikes of the latin letters with the letters they imitate
+/// (a simplified version of the skeletons of Unicode Technical Standard #39).
+fn skeleton(word: &str) -> String {
+    word.chars()
+        .map(|c| match c {
+            // Fullwidth latin letters and digits
+            '\u{ff10}'..='\u{ff19}' | '\u{ff21}'..='\u{ff3a}' | '\u{ff41}'..='\u{ff5a}' => {
+                char::from_u32(c as u32 - 0xfee0).unwrap_or(c)
+            }
+            // Cyrillic
+            'а' => 'a',
+            'е' => 'e',
+            'о' => 'o',
+            'р' => 'p',
+            'с' => 'c',
+            'у' => 'y',
+            'х' => 'x',
+            'і' => 'i',
+            'ј' => 'j',
+            'ѕ' => 's',
+            'ԁ' => 'd',
+            'һ' => 'h',
+            'ӏ' => 'l',
+            'А' => 'A',
+            'В' => 'B',
+            'Е' => 'E',
+            'К' => 'K',
+            'М' => 'M',
+            'Н' => 'H',
+            'О' => 'O',
+            'Р' => 'P',
+            'С' => 'C',
+            'Т' => 'T',
+            'Х' => 'X',
+            'У' => 'Y',
+            'І' => 'I',
+            'Ј' => 'J',
+            'Ѕ' => 'S',
+            // Greek
+            'ο' => 'o',
+            'ν' => 'v',
+            'ι' => 'i',
+            'Α' => 'A',
+            'Β' => 'B',
+            'Ε' => 'E',
+            'Ζ' => 'Z',
+            'Η' => 'H',
+            'Ι' => 'I',
+            'Κ' => 'K',
+            'Μ' => 'M',
+            'Ν' => 'N',
+            'Ο' => 'O',
+            'Ρ' => 'P',
+            'Τ' => 'T',
+            'Υ' => 'Y',
+            'Χ' => 'X',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::tests::{lines, source},
+        *,
+    };
+    use crate::verification::SourceType;
+    use pretty_assertions::assert_eq;
+
+    fn messages(content: &str) -> Vec<(usize, String)> {
+        UnicodeCheck
+            .check(&source(SourceType::Solidity, content))
+            .into_iter()
+            .map(|warning| (warning.line, warning.message))
+            .collect()
+    }
+
+    #[test]
+    fn trojan_source_is_detected() {
+        // Example of CVE-2021-42574: the check is displayed as a part of the comment
+        let content = "\u{feff}contract Token {\n\
+            \x20   bool isAdmin = false;\n\
+            \x20   /*\u{202e} } \u{2066}if (isAdmin)\u{2069} \u{2066} begin admins only */\n\
+            \x20   uint256 zero\u{200b}Width;\n\
+            }";
+        let expected = vec![
+            (
+                3,
+                "Bidirectional control characters U+202E (right-to-left override), \
+                U+2066 (left-to-right isolate), U+2069 (pop directional isolate) \
+                change the order the code is displayed in"
+                    .to_string(),
+            ),
+            (
+                3,
+                "Bidirectional control characters are not terminated at the end of the line, \
+                so the following code may be displayed in a different order"
+                    .to_string(),
+            ),
+            (
+                4,
+                "Invisible characters U+200B (zero width space) are hidden from the readers"
+                    .to_string(),
+            ),
+        ];
+        assert_eq!(expected, messages(content));
+
+        let terminated = "string s = \"\u{202e}abc\u{202c}\";";
+        assert_eq!(1, messages(terminated).len(), "{:?}", messages(terminated));
+    }
+
+    #[test]
+    fn confusables_are_detected() {
+        let content = "contract Token {\n\
+            \x20   address owner;\n\
+            \x20   // Latin comment and русский комментарий\n\
+            \x20   function transfer() { оwner = msg.sender; }\n\
+            \x20   string name = \"Tбken\";\n\
+            }";
+        let expected = vec![
+            (
+                4,
+                "Word \"оwner\" looks the same as \"owner\" but consists of different characters"
+                    .to_string(),
+            ),
+            (
+                5,
+                "Word \"Tбken\" mixes latin letters with letters of other scripts".to_string(),
+            ),
+        ];
+        assert_eq!(expected, messages(content));
+    }
+
+    #[test]
+    fn fullwidth_letters_are_confusables() {
+        let content = "uint256 fee;\nuint256 ｆｅｅ;";
+        let warnings = UnicodeCheck.check(&source(SourceType::Solidity, content));
+        assert_eq!(vec![2], lines(warnings.clone()));
+        assert!(
+            warnings[0].message.contains("looks the same as \"fee\""),
+            "invalid message: {}",
+            warnings[0].message
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Retrieval of the files of stored sources one by one, so that large
+//! verified contracts could be downloaded without loading all files at once.
+//!
+//! File contents are stored content-addressed (by sha256 hash) in "file_contents",
+//! so identical files (e.g., OpenZeppelin contracts) are stored only once
+//! regardless of the number of sources and file names referencing them.
+
+use anyhow::Context;
+use entity::{file_contents, files, source_files, sources};
+use sea_orm::{
+    entity::prelude::*, sea_query::OnConflict, ActiveValue::Set, ConnectionTrait, DatabaseBackend,
+    JoinType, QueryOrder, QuerySelect, Statement,
+};
+use std::collections::BTreeMap;
+
+/// Returns names of the files of the source with hashes of their contents.
+/// Returns `None` if the source does not exist.
+pub async fn file_names<C>(
+    db: &C,
+    source_id: i64,
+) -> Result<Option<Vec<(String, Vec<u8>)>>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    if sources::Entity::find_by_id(source_id)
+        .one(db)
+        .await?
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    let names = files::Entity::find()
+        .join(JoinType::InnerJoin, files::Relation::SourceFiles.def())
+        .filter(source_files::Column::SourceId.eq(source_id))
+        .order_by_asc(files::Column::Name)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|file| (file.name, file.content_hash))
+        .collect();
+    Ok(Some(names))
+}
+
+/// Returns all files of the source as a map from the file name to its content.
+/// Returns `None` if the source does not exist.
+pub async fn files<C>(
+    db: &C,
+    source_id: i64,
+) -> Result<Option<BTreeMap<String, String>>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    if sources::Entity::find_by_id(source_id)
+        .one(db)
+        .await?
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    source_files_contents(db, source_id).await.map(Some)
+}
+
+/// Returns the content of the source file with the given name, if any.
+pub async fn file<C>(db: &C, source_id: i64, name: &str) -> Result<Option<String>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let content = file_contents::Entity::find()
+        .join(JoinType::InnerJoin, file_contents::Relation::Files.def())
+        .join(JoinType::InnerJoin, files::Relation::SourceFiles.def())
+        .filter(source_files::Column::SourceId.eq(source_id))
+        .filter(files::Column::Name.eq(name))
+        .one(db)
+        .await?;
+    Ok(content.map(|content| content.content))
+}
+
+/// Returns the file content with the given sha256 hash, if any.
+pub async fn content<C>(db: &C, content_hash: &[u8]) -> Result<Option<String>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let content = file_contents::Entity::find_by_id(content_hash.to_vec())
+        .one(db)
+        .await?;
+    Ok(content.map(|content| content.content))
+}
+
+/// Stores the content if it has not been stored yet. Returns its sha256 hash.
+pub async fn insert_content<C>(db: &C, content: &str) -> Result<Vec<u8>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    // Is calculated by the database to be consistent with the hashes of the migrated contents
+    let content_hash_query = Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "SELECT sha256(convert_to($1, 'UTF8')) AS content_hash",
+        [sea_orm::Value::from(content)],
+    );
+    let content_hash: Vec<u8> = db
+        .query_one(content_hash_query)
+        .await
+        .context("calculate hash of file content")?
+        .ok_or(anyhow::anyhow!("content hash query returned no data"))?
+        .try_get("", "content_hash")
+        .context("calculate hash of file content")?;
+
+    let active_model = file_contents::ActiveModel {
+        content_hash: Set(content_hash.clone()),
+        content: Set(content.to_string()),
+        ..Default::default()
+    };
+    let result = file_contents::Entity::insert(active_model)
+        .on_conflict(OnConflict::new().do_nothing().to_owned())
+        .exec(db)
+        .await;
+    match result {
+        Ok(_) | Err(DbErr::RecordNotInserted) => Ok(content_hash),
+        Err(err) => Err(err).context("insert into \"file_contents\""),
+    }
+}
+
+/// Returns a map from the file name to its content for all files of the source.
+pub(crate) async fn source_files_contents<C>(
+    db: &C,
+    source_id: i64,
+) -> Result<BTreeMap<String, String>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    files::Entity::find()
+        .join(JoinType::InnerJoin, files::Relation::SourceFiles.def())
+        .filter(source_files::Column::SourceId.eq(source_id))
+        .find_also_related(file_contents::Entity)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|(file, content)| {
+            let content = content.ok_or_else(|| {
+                anyhow::anyhow!("content of the file \"{}\" is missing", file.name)
+            })?;
+            Ok((file.name, content.content))
+        })
+        .collect()
+}
@@ -85,9 +85,10 @@ pub async fn insert_verification_result(
     .await?;
 
     for (name, content) in verification_result.sources {
+        let content_hash = crate::source_files::insert_content(&txn, &content).await?;
         let file = files::Entity::find()
             .filter(Expr::col(files::Column::Name).eq(name.clone()))
-            .filter(Expr::col(files::Column::Content).eq(content.clone()))
+            .filter(Expr::col(files::Column::ContentHash).eq(content_hash.clone()))
             .one(&txn)
             .await?;
 
@@ -96,7 +97,7 @@ pub async fn insert_verification_result(
             None => {
                 files::ActiveModel {
                     name: Set(name),
-                    content: Set(content),
+                    content_hash: Set(content_hash),
                     ..Default::default()
                 }
                 .insert(&txn)
@@ -2,6 +2,7 @@ use super::smart_contract_verifier::{
     solidity_verifier_client::SolidityVerifierClient,
     sourcify_verifier_client::SourcifyVerifierClient, vyper_verifier_client::VyperVerifierClient,
 };
+use crate::{events::Events, source_checks::SourceChecks};
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 use tonic::transport::{Channel, Uri};
@@ -12,6 +13,8 @@ pub struct Client {
     pub solidity_client: SolidityVerifierClient<Channel>,
     pub vyper_client: VyperVerifierClient<Channel>,
     pub sourcify_client: SourcifyVerifierClient<Channel>,
+    pub events: Events,
+    pub source_checks: SourceChecks,
 }
 
 impl Client {
@@ -39,6 +42,20 @@ impl Client {
             solidity_client,
             vyper_client,
             sourcify_client,
+            events: Default::default(),
+            source_checks: Default::default(),
         })
     }
+
+    /// Sets the publisher of the verification events. Events are not published by default.
+    pub fn with_events(mut self, events: Events) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Sets the checks run on the sources before they are published. No checks are run by default.
+    pub fn with_source_checks(mut self, source_checks: SourceChecks) -> Self {
+        self.source_checks = source_checks;
+        self
+    }
 }
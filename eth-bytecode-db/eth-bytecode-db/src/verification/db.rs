@@ -1,5 +1,5 @@
 use super::{types, BytecodeType};
-use crate::verification::VerificationMetadata;
+use crate::{abi_search, search, verification::VerificationMetadata};
 use anyhow::Context;
 use entity::{
     bytecode_parts, bytecodes, files, parts, sea_orm_active_enums, source_files, sources,
@@ -82,6 +82,9 @@ pub(crate) async fn insert_data(
         insert_source_files(&txn, &source, files.as_ref())
             .await
             .context("insert source files")?;
+        abi_search::insert_abi_items(&txn, source.id, source.abi.as_ref())
+            .await
+            .context("insert abi items")?;
 
         insert_bytecodes(
             &txn,
@@ -111,10 +114,11 @@ pub(crate) async fn insert_verified_contract_data(
     source_id: i64,
     raw_bytecode: Vec<u8>,
     bytecode_type: BytecodeType,
+    match_type: types::MatchType,
     verification_settings: serde_json::Value,
     verification_type: types::VerificationType,
     verification_metadata: Option<VerificationMetadata>,
-) -> Result<(), anyhow::Error> {
+) -> Result<i64, anyhow::Error> {
     let (chain_id, contract_address) = match verification_metadata {
         None => (None, None),
         Some(metadata) => (
@@ -122,7 +126,13 @@ pub(crate) async fn insert_verified_contract_data(
             Some(metadata.contract_address.to_vec()),
         ),
     };
-    verified_contracts::ActiveModel {
+
+    let txn = db_client
+        .begin()
+        .await
+        .context("begin database transaction")?;
+
+    let verified_contract = verified_contracts::ActiveModel {
         source_id: Set(source_id),
         raw_bytecode: Set(raw_bytecode),
         bytecode_type: Set(sea_orm_active_enums::BytecodeType::from(bytecode_type)),
@@ -131,14 +141,28 @@ pub(crate) async fn insert_verified_contract_data(
             verification_type,
         )),
         chain_id: Set(chain_id),
-        contract_address: Set(contract_address),
+        contract_address: Set(contract_address.clone()),
+        match_type: Set(match_type.into()),
         ..Default::default()
     }
-    .insert(db_client)
+    .insert(&txn)
     .await
     .context("insert into verified contracts")?;
 
-    Ok(())
+    if let (Some(chain_id), Some(contract_address)) = (chain_id, contract_address) {
+        crate::verified_contracts::supersede_partial_matches(
+            &txn,
+            chain_id,
+            &contract_address,
+            verified_contract.id,
+            match_type,
+        )
+        .await?;
+    }
+
+    txn.commit().await.context("commit transaction")?;
+
+    Ok(verified_contract.id)
 }
 
 async fn insert_files(
@@ -147,13 +171,18 @@ async fn insert_files(
 ) -> Result<Vec<files::Model>, anyhow::Error> {
     let mut result = Vec::new();
     for (name, content) in files {
+        let content_hash = crate::source_files::insert_content(txn, &content).await?;
         let active_model = files::ActiveModel {
             name: Set(name.clone()),
-            content: Set(content.clone()),
+            content_hash: Set(content_hash.clone()),
             ..Default::default()
         };
-        let (file, _inserted) =
-            insert_then_select!(txn, files, active_model, [(Name, name), (Content, content)])?;
+        let (file, _inserted) = insert_then_select!(
+            txn,
+            files,
+            active_model,
+            [(Name, name), (ContentHash, content_hash)]
+        )?;
 
         result.push(file);
     }
@@ -195,6 +224,7 @@ async fn insert_source_details(
 
     let compiler_settings: Json =
         serde_json::from_str(&source.compiler_settings).context("deserialize compiler settings")?;
+    let license = crate::licenses::extract(&source.file_name, &source.source_files);
 
     let active_model = sources::ActiveModel {
         source_type: Set(source.source_type.into()),
@@ -206,6 +236,7 @@ async fn insert_source_details(
         raw_deployed_bytecode: Set(source.raw_deployed_bytecode.clone()),
         abi: Set(abi.clone()),
         file_ids_hash: Set(file_ids_hash),
+        license: Set(license),
         ..Default::default()
     };
     insert_then_select!(
@@ -251,10 +282,19 @@ async fn insert_bytecodes(
     bytecode_type: BytecodeType,
 ) -> Result<(), anyhow::Error> {
     let bytecode = {
+        // Only deployed bytecodes are looked up by the normalized code hash
+        let normalized_code_hash = (bytecode_type == BytecodeType::DeployedBytecode).then(|| {
+            search::normalized_code_hash(bytecode_parts.iter().filter_map(|part| match part {
+                types::BytecodePart::Main { data } => Some(data.as_slice()),
+                types::BytecodePart::Meta { .. } => None,
+            }))
+            .to_vec()
+        });
         let bytecode_type = sea_orm_active_enums::BytecodeType::from(bytecode_type);
         let active_model = bytecodes::ActiveModel {
             source_id: Set(source_id),
             bytecode_type: Set(bytecode_type.clone()),
+            normalized_code_hash: Set(normalized_code_hash),
             ..Default::default()
         };
         let (bytecode, _inserted) = insert_then_select!(
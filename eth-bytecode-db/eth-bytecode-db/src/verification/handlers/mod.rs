@@ -1,4 +1,6 @@
 pub mod compiler_versions;
+pub mod preview;
+pub mod reverification;
 pub mod solidity_multi_part;
 pub mod solidity_standard_json;
 pub mod sourcify;
@@ -7,29 +9,73 @@ pub mod vyper_multi_part;
 ////////////////////////////////////////////////////////////////////////////////////////////
 
 use super::{
+    client::Client,
     db,
     errors::Error,
     smart_contract_verifier,
-    types::{BytecodePart, BytecodeType, Source, VerificationMetadata, VerificationType},
+    types::{
+        BytecodePart, BytecodeType, Source, VerificationMetadata, VerificationRequest,
+        VerificationType,
+    },
 };
+use crate::events::VerificationEvent;
 use anyhow::Context;
-use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+
+/// Request data stored along with the verified source
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct SaveData {
+    bytecode_type: BytecodeType,
+    raw_request_bytecode: Vec<u8>,
+    verification_settings: serde_json::Value,
+    verification_type: VerificationType,
+    verification_metadata: Option<VerificationMetadata>,
+}
+
+impl SaveData {
+    fn new<T: Serialize>(
+        request: &VerificationRequest<T>,
+        verification_type: VerificationType,
+    ) -> Result<Self, Error> {
+        let raw_request_bytecode = hex::decode(request.bytecode.trim_start_matches("0x"))
+            .map_err(|err| Error::InvalidArgument(format!("invalid bytecode: {err}")))?;
+        Ok(Self {
+            bytecode_type: request.bytecode_type,
+            raw_request_bytecode,
+            verification_settings: serde_json::json!(request),
+            verification_type,
+            verification_metadata: request.metadata.clone(),
+        })
+    }
+}
 
 enum ProcessResponseAction {
     IgnoreDb,
-    SaveData {
-        bytecode_type: BytecodeType,
-        raw_request_bytecode: Vec<u8>,
-        verification_settings: serde_json::Value,
-        verification_type: VerificationType,
-        verification_metadata: Option<VerificationMetadata>,
-    },
+    SaveData(SaveData),
 }
 
 async fn process_verify_response(
-    db_client: &DatabaseConnection,
+    client: &Client,
     response: smart_contract_verifier::VerifyResponse,
     action: ProcessResponseAction,
+) -> Result<Source, Error> {
+    let mut source = parse_verify_response(response)?;
+
+    if let ProcessResponseAction::SaveData(data) = action {
+        source.warnings = client.source_checks.run(&source);
+        // For historical data we just log any errors but do not propagate them further
+        let _ = save_data(client, source.clone(), data)
+            .await
+            .map_err(|err: anyhow::Error| {
+                tracing::error!("Error while inserting contract data into database: {err:#}")
+            });
+    }
+
+    Ok(source)
+}
+
+fn parse_verify_response(
+    response: smart_contract_verifier::VerifyResponse,
 ) -> Result<Source, Error> {
     let (source, extra_data) = match (response.status(), response.source, response.extra_data) {
         (smart_contract_verifier::Status::Success, Some(source), Some(extra_data)) => {
@@ -80,7 +126,7 @@ async fn process_verify_response(
 
     let source_type = source.source_type().try_into().map_err(Error::Internal)?;
     let match_type = source.match_type().into();
-    let source = Source {
+    Ok(Source {
         file_name: source.file_name,
         contract_name: source.contract_name,
         compiler_version: source.compiler_version,
@@ -94,44 +140,36 @@ async fn process_verify_response(
         raw_deployed_bytecode,
         creation_input_parts,
         deployed_bytecode_parts,
-    };
-
-    let process_database_insertion = || async {
-        match action {
-            ProcessResponseAction::IgnoreDb => {}
-            ProcessResponseAction::SaveData {
-                bytecode_type,
-                raw_request_bytecode,
-                verification_settings,
-                verification_type,
-                verification_metadata,
-            } => {
-                let source_id = db::insert_data(db_client, source.clone())
-                    .await
-                    .context("Insert data into database")?;
-
-                // For historical data we just log any errors but do not propagate them further
-                db::insert_verified_contract_data(
-                    db_client,
-                    source_id,
-                    raw_request_bytecode,
-                    bytecode_type,
-                    verification_settings,
-                    verification_type,
-                    verification_metadata,
-                )
-                .await
-                .context("Insert verified contract data")?;
-            }
-        };
-        Ok(())
-    };
+        warnings: vec![],
+    })
+}
 
-    let _ = process_database_insertion()
+/// Stores the verified contract and publishes the corresponding event.
+async fn save_data(client: &Client, source: Source, data: SaveData) -> Result<(), anyhow::Error> {
+    let db_client = client.db_client.as_ref();
+    let match_type = source.match_type;
+    let source_id = db::insert_data(db_client, source)
         .await
-        .map_err(|err: anyhow::Error| {
-            tracing::error!("Error while inserting contract data into database: {err:#}")
-        });
+        .context("Insert data into database")?;
 
-    Ok(source)
+    let verified_contract_id = db::insert_verified_contract_data(
+        db_client,
+        source_id,
+        data.raw_request_bytecode,
+        data.bytecode_type,
+        match_type,
+        data.verification_settings,
+        data.verification_type,
+        data.verification_metadata.clone(),
+    )
+    .await
+    .context("Insert verified contract data")?;
+
+    client.events.publish(VerificationEvent::contract_verified(
+        data.verification_metadata,
+        match_type,
+        source_id,
+        verified_contract_id,
+    ));
+    Ok(())
 }
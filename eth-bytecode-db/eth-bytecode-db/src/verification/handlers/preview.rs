@@ -0,0 +1,363 @@
+//! Two-phase verification. The contract is verified without storing the result,
+//! so that the match, decoded constructor arguments and ABI could be reviewed
+//! before the sources are published. The result is stored only when
+//! the preview is confirmed by its token before it expires.
+//!
+//! Expired previews are removed when new previews are created.
+
+use super::{
+    super::{
+        client::Client,
+        errors::Error,
+        smart_contract_verifier,
+        types::{BytecodeType, MatchType, Source, SourceType},
+    },
+    parse_verify_response, save_data, SaveData,
+};
+use anyhow::Context;
+use entity::verification_previews;
+use ethabi::Token;
+use sea_orm::{
+    prelude::{DateTime, Uuid},
+    ConnectionTrait, DatabaseBackend, EntityTrait, Statement,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstructorArgument {
+    pub name: String,
+    pub param_type: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preview {
+    pub token: Uuid,
+    pub expires_at: DateTime,
+    pub source: Source,
+    /// `None` if the contract has no constructor arguments or they could not be decoded
+    pub constructor_arguments: Option<Vec<ConstructorArgument>>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct PreviewData {
+    source: Source,
+    save_data: SaveData,
+}
+
+pub(super) async fn create(
+    client: &Client,
+    response: smart_contract_verifier::VerifyResponse,
+    save_data: SaveData,
+    ttl: Duration,
+) -> Result<Preview, Error> {
+    let db_client = client.db_client.as_ref();
+    let mut source = parse_verify_response(response)?;
+    source.warnings = client.source_checks.run(&source);
+    let (constructor_arguments, warnings) = review(&source, save_data.bytecode_type);
+
+    let data = serde_json::to_value(PreviewData {
+        source: source.clone(),
+        save_data,
+    })
+    .context("serialize verification preview")
+    .map_err(Error::Internal)?;
+
+    let _ = delete_expired(db_client).await.map_err(|err| {
+        tracing::warn!("Error while deleting expired verification previews: {err:#}")
+    });
+
+    let preview = verification_previews::Entity::find()
+        .from_raw_sql(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                INSERT INTO verification_previews (expires_at, data)
+                VALUES (now() + make_interval(secs => $1), $2)
+                RETURNING *
+            "#,
+            [ttl.as_secs_f64().into(), data.into()],
+        ))
+        .one(db_client)
+        .await
+        .context("insert into \"verification_previews\"")
+        .map_err(Error::Internal)?
+        .ok_or_else(|| Error::Internal(anyhow::anyhow!("inserted preview was not returned")))?;
+
+    Ok(Preview {
+        token: preview.token,
+        expires_at: preview.expires_at,
+        source,
+        constructor_arguments,
+        warnings,
+    })
+}
+
+/// Stores the verified source of the preview. Each preview may be confirmed only once.
+pub async fn confirm(client: &Client, token: Uuid) -> Result<Source, Error> {
+    let db_client = client.db_client.as_ref();
+    let preview = verification_previews::Entity::find()
+        .from_raw_sql(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                UPDATE verification_previews
+                SET confirmed_at = now()
+                WHERE token = $1 AND confirmed_at IS NULL AND expires_at > now()
+                RETURNING *
+            "#,
+            [token.into()],
+        ))
+        .one(db_client)
+        .await
+        .context("update \"verification_previews\"")
+        .map_err(Error::Internal)?
+        .ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "verification preview {token} does not exist, has expired or has already been confirmed"
+            ))
+        })?;
+
+    let data: PreviewData = serde_json::from_value(preview.data)
+        .context("deserialize verification preview")
+        .map_err(Error::Internal)?;
+
+    // Unlike the direct verification, the errors are propagated,
+    // as the caller has to know whether the contract has been published
+    if let Err(err) = save_data(client, data.source.clone(), data.save_data).await {
+        // Allows the confirmation to be retried
+        let _ = db_client
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                "UPDATE verification_previews SET confirmed_at = NULL WHERE token = $1",
+                [token.into()],
+            ))
+            .await
+            .map_err(|err| {
+                tracing::error!("Error while resetting verification preview confirmation: {err:#}")
+            });
+        return Err(Error::Internal(err));
+    }
+
+    Ok(data.source)
+}
+
+async fn delete_expired<C: ConnectionTrait>(db: &C) -> Result<u64, anyhow::Error> {
+    let result = db
+        .execute(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "DELETE FROM verification_previews WHERE expires_at <= now()".to_string(),
+        ))
+        .await
+        .context("delete expired verification previews")?;
+    Ok(result.rows_affected())
+}
+
+/// Decodes constructor arguments and collects everything the submitter should double-check
+/// before the sources are published.
+fn review(
+    source: &Source,
+    bytecode_type: BytecodeType,
+) -> (Option<Vec<ConstructorArgument>>, Vec<String>) {
+    let mut warnings = vec![];
+
+    if source.match_type == MatchType::Partial {
+        warnings.push(
+            "Only a partial match has been found: the metadata hash differs, \
+            so the sources may differ from the deployed ones (e.g., in comments or names)"
+                .to_string(),
+        );
+    }
+    if source.abi.is_none() && source.source_type != SourceType::Yul {
+        warnings.push("Compiler output does not contain the contract ABI".to_string());
+    }
+    if bytecode_type == BytecodeType::DeployedBytecode {
+        warnings.push(
+            "Constructor arguments have not been checked, as the deployed bytecode has been provided"
+                .to_string(),
+        );
+    }
+
+    let constructor_arguments = match decode_constructor_arguments(
+        source.abi.as_deref(),
+        source.constructor_arguments.as_deref(),
+    ) {
+        Ok(arguments) => arguments,
+        Err(err) => {
+            warnings.push(format!(
+                "Constructor arguments could not be decoded: {err:#}"
+            ));
+            None
+        }
+    };
+
+    (constructor_arguments, warnings)
+}
+
+/// Decodes hex encoded constructor arguments by the constructor of the abi.
+/// Returns `None` if there are no arguments or abi to decode them by.
+pub(crate) fn decode_constructor_arguments(
+    abi: Option<&str>,
+    encoded: Option<&str>,
+) -> Result<Option<Vec<ConstructorArgument>>, anyhow::Error> {
+    let encoded = match encoded {
+        Some(encoded) => hex::decode(encoded.trim_start_matches("0x"))
+            .context("arguments are not a valid hex")?,
+        None => return Ok(None),
+    };
+    let abi = match abi {
+        Some(abi) => ethabi::Contract::load(abi.as_bytes()).context("invalid abi")?,
+        None => return Ok(None),
+    };
+    let constructor = match abi.constructor {
+        Some(constructor) => constructor,
+        None if encoded.is_empty() => return Ok(None),
+        None => anyhow::bail!("abi does not contain the constructor"),
+    };
+
+    let param_types: Vec<_> = constructor
+        .inputs
+        .iter()
+        .map(|param| param.kind.clone())
+        .collect();
+    let tokens = ethabi::decode(&param_types, &encoded).context("arguments do not match abi")?;
+
+    let arguments = constructor
+        .inputs
+        .into_iter()
+        .zip(tokens.iter())
+        .map(|(param, token)| ConstructorArgument {
+            name: param.name,
+            param_type: param.kind.to_string(),
+            value: format_token(token),
+        })
+        .collect();
+    Ok(Some(arguments))
+}
+
+fn format_token(token: &Token) -> String {
+    let format_tokens = |tokens: &[Token]| {
+        tokens
+            .iter()
+            .map(format_token)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match token {
+        Token::Address(address) => format!("{address:#x}"),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Uint(value) => value.to_string(),
+        // Signed integers are encoded in two's complement
+        Token::Int(value) if value.bit(255) => format!("-{}", value.overflowing_neg().0),
+        Token::Int(value) => value.to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => value.clone(),
+        Token::FixedArray(tokens) | Token::Array(tokens) => format!("[{}]", format_tokens(tokens)),
+        Token::Tuple(tokens) => format!("({})", format_tokens(tokens)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethabi::ethereum_types::{Address, U256};
+    use pretty_assertions::assert_eq;
+    use std::{collections::BTreeMap, str::FromStr};
+
+    const ABI: &str = r#"[{"inputs":[{"internalType":"address","name":"owner","type":"address"},{"internalType":"int256","name":"delta","type":"int256"},{"internalType":"string[]","name":"names","type":"string[]"}],"stateMutability":"nonpayable","type":"constructor"}]"#;
+
+    fn source(abi: Option<&str>, constructor_arguments: Option<&str>) -> Source {
+        Source {
+            file_name: "Contract.sol".to_string(),
+            contract_name: "Contract".to_string(),
+            compiler_version: "v0.8.7+commit.e28d00a7".to_string(),
+            compiler_settings: "{}".to_string(),
+            source_type: SourceType::Solidity,
+            source_files: BTreeMap::new(),
+            abi: abi.map(str::to_string),
+            constructor_arguments: constructor_arguments.map(str::to_string),
+            match_type: MatchType::Full,
+            raw_creation_input: vec![],
+            raw_deployed_bytecode: vec![],
+            creation_input_parts: vec![],
+            deployed_bytecode_parts: vec![],
+            warnings: vec![],
+        }
+    }
+
+    #[test]
+    fn format_tokens() {
+        let address = Address::from_str("0x00000000219ab540356cbb839cbe05303d7705fa").unwrap();
+        assert_eq!(
+            "0x00000000219ab540356cbb839cbe05303d7705fa",
+            format_token(&Token::Address(address))
+        );
+        assert_eq!("0x1234", format_token(&Token::Bytes(vec![0x12, 0x34])));
+        assert_eq!("42", format_token(&Token::Uint(U256::from(42))));
+        assert_eq!("42", format_token(&Token::Int(U256::from(42))));
+        assert_eq!("-1", format_token(&Token::Int(U256::MAX)));
+        assert_eq!(
+            "[(true, abc), (false, )]",
+            format_token(&Token::Array(vec![
+                Token::Tuple(vec![Token::Bool(true), Token::String("abc".into())]),
+                Token::Tuple(vec![Token::Bool(false), Token::String("".into())]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn decode_arguments() {
+        let address = Address::from_str("0x00000000219ab540356cbb839cbe05303d7705fa").unwrap();
+        let encoded = ethabi::encode(&[
+            Token::Address(address),
+            Token::Int(U256::MAX - 1),
+            Token::Array(vec![Token::String("a".into()), Token::String("b".into())]),
+        ]);
+        let source = source(Some(ABI), Some(&hex::encode(encoded)));
+
+        let (arguments, warnings) = review(&source, BytecodeType::CreationInput);
+        let expected = vec![
+            ConstructorArgument {
+                name: "owner".to_string(),
+                param_type: "address".to_string(),
+                value: "0x00000000219ab540356cbb839cbe05303d7705fa".to_string(),
+            },
+            ConstructorArgument {
+                name: "delta".to_string(),
+                param_type: "int256".to_string(),
+                value: "-2".to_string(),
+            },
+            ConstructorArgument {
+                name: "names".to_string(),
+                param_type: "string[]".to_string(),
+                value: "[a, b]".to_string(),
+            },
+        ];
+        assert_eq!(Some(expected), arguments);
+        assert!(warnings.is_empty(), "unexpected warnings: {warnings:?}");
+    }
+
+    #[test]
+    fn review_warnings() {
+        let mut partial_match = source(None, None);
+        partial_match.match_type = MatchType::Partial;
+        let (arguments, warnings) = review(&partial_match, BytecodeType::DeployedBytecode);
+        assert_eq!(None, arguments);
+        assert_eq!(3, warnings.len(), "unexpected warnings: {warnings:?}");
+
+        let invalid_arguments = source(Some(ABI), Some("0x1234"));
+        let (arguments, warnings) = review(&invalid_arguments, BytecodeType::CreationInput);
+        assert_eq!(None, arguments);
+        assert_eq!(1, warnings.len(), "unexpected warnings: {warnings:?}");
+        assert!(
+            warnings[0].starts_with("Constructor arguments could not be decoded"),
+            "unexpected warning: {}",
+            warnings[0]
+        );
+
+        let no_constructor = source(Some("[]"), None);
+        let (arguments, warnings) = review(&no_constructor, BytecodeType::CreationInput);
+        assert_eq!(None, arguments);
+        assert!(warnings.is_empty(), "unexpected warnings: {warnings:?}");
+    }
+}
@@ -0,0 +1,110 @@
+use super::{
+    super::{
+        client::Client,
+        errors::Error,
+        smart_contract_verifier::{
+            VerifySolidityMultiPartRequest, VerifySolidityStandardJsonRequest,
+            VerifyVyperMultiPartRequest,
+        },
+        types::{Source, VerificationRequest},
+    },
+    process_verify_response, solidity_multi_part, solidity_standard_json, vyper_multi_part,
+    ProcessResponseAction,
+};
+use entity::sea_orm_active_enums::{SourceType, VerificationType};
+
+/// Verification request as it has been stored in the database
+/// on the original successful verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StoredRequest {
+    SolidityMultiPart(VerificationRequest<solidity_multi_part::MultiPartFiles>),
+    SolidityStandardJson(VerificationRequest<solidity_standard_json::StandardJson>),
+    VyperMultiPart(VerificationRequest<vyper_multi_part::MultiPartFiles>),
+}
+
+impl StoredRequest {
+    /// Restores the request from `verified_contracts.verification_settings` value.
+    /// Returns `None` for verification types which requests are not stored.
+    pub fn parse(
+        source_type: &SourceType,
+        verification_type: &VerificationType,
+        verification_settings: serde_json::Value,
+    ) -> Option<Result<Self, serde_json::Error>> {
+        let request = match (source_type, verification_type) {
+            (SourceType::Solidity | SourceType::Yul, VerificationType::MultiPartFiles) => {
+                serde_json::from_value(verification_settings).map(Self::SolidityMultiPart)
+            }
+            (SourceType::Solidity | SourceType::Yul, VerificationType::StandardJson) => {
+                serde_json::from_value(verification_settings).map(Self::SolidityStandardJson)
+            }
+            (SourceType::Vyper, VerificationType::MultiPartFiles) => {
+                serde_json::from_value(verification_settings).map(Self::VyperMultiPart)
+            }
+            _ => return None,
+        };
+        Some(request)
+    }
+}
+
+/// Re-runs verification of the stored request. Results are not saved into the database.
+pub async fn reverify(mut client: Client, request: StoredRequest) -> Result<Source, Error> {
+    let response = match request {
+        StoredRequest::SolidityMultiPart(request) => client
+            .solidity_client
+            .verify_multi_part(VerifySolidityMultiPartRequest::from(request))
+            .await
+            .map_err(Error::from)?,
+        StoredRequest::SolidityStandardJson(request) => client
+            .solidity_client
+            .verify_standard_json(VerifySolidityStandardJsonRequest::from(request))
+            .await
+            .map_err(Error::from)?,
+        StoredRequest::VyperMultiPart(request) => client
+            .vyper_client
+            .verify_multi_part(VerifyVyperMultiPartRequest::from(request))
+            .await
+            .map_err(Error::from)?,
+    }
+    .into_inner();
+
+    process_verify_response(&client, response, ProcessResponseAction::IgnoreDb).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::super::types, *};
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn parse_stored_request() {
+        let request = VerificationRequest {
+            bytecode: "0x1234".to_string(),
+            bytecode_type: types::BytecodeType::CreationInput,
+            compiler_version: "compiler_version".to_string(),
+            content: vyper_multi_part::MultiPartFiles {
+                evm_version: None,
+                optimizations: Some(true),
+                source_files: BTreeMap::from([("source_file".into(), "content".into())]),
+            },
+            metadata: None,
+        };
+        let verification_settings = serde_json::json!(&request);
+
+        let parsed = StoredRequest::parse(
+            &SourceType::Vyper,
+            &VerificationType::MultiPartFiles,
+            verification_settings.clone(),
+        )
+        .expect("request should be stored for vyper multi-part verification")
+        .expect("parsing failed");
+        assert_eq!(StoredRequest::VyperMultiPart(request), parsed);
+
+        let not_stored = StoredRequest::parse(
+            &SourceType::Solidity,
+            &VerificationType::FlattenedContract,
+            verification_settings,
+        );
+        assert!(not_stored.is_none(), "flattened contracts are not stored");
+    }
+}
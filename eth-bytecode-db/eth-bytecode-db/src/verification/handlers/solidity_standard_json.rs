@@ -2,12 +2,16 @@ use super::{
     super::{
         client::Client,
         errors::Error,
-        smart_contract_verifier::{BytecodeType, VerifySolidityStandardJsonRequest},
+        smart_contract_verifier::{
+            BytecodeType, VerifyResponse, VerifySolidityStandardJsonRequest,
+        },
         types::{Source, VerificationRequest, VerificationType},
     },
-    process_verify_response, ProcessResponseAction,
+    preview::{self, Preview},
+    process_verify_response, ProcessResponseAction, SaveData,
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StandardJson {
@@ -29,11 +33,31 @@ pub async fn verify(
     mut client: Client,
     request: VerificationRequest<StandardJson>,
 ) -> Result<Source, Error> {
-    let bytecode_type = request.bytecode_type;
-    let raw_request_bytecode = hex::decode(request.bytecode.clone().trim_start_matches("0x"))
-        .map_err(|err| Error::InvalidArgument(format!("invalid bytecode: {err}")))?;
-    let verification_settings = serde_json::json!(&request);
-    let verification_metadata = request.metadata.clone();
+    let (response, save_data) = send_request(&mut client, request).await?;
+    process_verify_response(
+        &client,
+        response,
+        ProcessResponseAction::SaveData(save_data),
+    )
+    .await
+}
+
+/// Verifies the contract without storing the result.
+/// The source is stored only when the returned preview is confirmed.
+pub async fn preview(
+    mut client: Client,
+    request: VerificationRequest<StandardJson>,
+    ttl: Duration,
+) -> Result<Preview, Error> {
+    let (response, save_data) = send_request(&mut client, request).await?;
+    preview::create(&client, response, save_data, ttl).await
+}
+
+async fn send_request(
+    client: &mut Client,
+    request: VerificationRequest<StandardJson>,
+) -> Result<(VerifyResponse, SaveData), Error> {
+    let save_data = SaveData::new(&request, VerificationType::StandardJson)?;
 
     let request: VerifySolidityStandardJsonRequest = request.into();
     let response = client
@@ -43,18 +67,7 @@ pub async fn verify(
         .map_err(Error::from)?
         .into_inner();
 
-    process_verify_response(
-        &client.db_client,
-        response,
-        ProcessResponseAction::SaveData {
-            bytecode_type,
-            raw_request_bytecode,
-            verification_settings,
-            verification_type: VerificationType::StandardJson,
-            verification_metadata,
-        },
-    )
-    .await
+    Ok((response, save_data))
 }
 
 #[cfg(test)]
@@ -2,13 +2,14 @@ use super::{
     super::{
         client::Client,
         errors::Error,
-        smart_contract_verifier::{BytecodeType, VerifyVyperMultiPartRequest},
+        smart_contract_verifier::{BytecodeType, VerifyResponse, VerifyVyperMultiPartRequest},
         types::{Source, VerificationRequest, VerificationType},
     },
-    process_verify_response, ProcessResponseAction,
+    preview::{self, Preview},
+    process_verify_response, ProcessResponseAction, SaveData,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Duration};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MultiPartFiles {
@@ -34,11 +35,31 @@ pub async fn verify(
     mut client: Client,
     request: VerificationRequest<MultiPartFiles>,
 ) -> Result<Source, Error> {
-    let bytecode_type = request.bytecode_type;
-    let raw_request_bytecode = hex::decode(request.bytecode.clone().trim_start_matches("0x"))
-        .map_err(|err| Error::InvalidArgument(format!("invalid bytecode: {err}")))?;
-    let verification_settings = serde_json::json!(&request);
-    let verification_metadata = request.metadata.clone();
+    let (response, save_data) = send_request(&mut client, request).await?;
+    process_verify_response(
+        &client,
+        response,
+        ProcessResponseAction::SaveData(save_data),
+    )
+    .await
+}
+
+/// Verifies the contract without storing the result.
+/// The source is stored only when the returned preview is confirmed.
+pub async fn preview(
+    mut client: Client,
+    request: VerificationRequest<MultiPartFiles>,
+    ttl: Duration,
+) -> Result<Preview, Error> {
+    let (response, save_data) = send_request(&mut client, request).await?;
+    preview::create(&client, response, save_data, ttl).await
+}
+
+async fn send_request(
+    client: &mut Client,
+    request: VerificationRequest<MultiPartFiles>,
+) -> Result<(VerifyResponse, SaveData), Error> {
+    let save_data = SaveData::new(&request, VerificationType::MultiPartFiles)?;
 
     let request: VerifyVyperMultiPartRequest = request.into();
     let response = client
@@ -48,18 +69,7 @@ pub async fn verify(
         .map_err(Error::from)?
         .into_inner();
 
-    process_verify_response(
-        &client.db_client,
-        response,
-        ProcessResponseAction::SaveData {
-            bytecode_type,
-            raw_request_bytecode,
-            verification_settings,
-            verification_type: VerificationType::MultiPartFiles,
-            verification_metadata,
-        },
-    )
-    .await
+    Ok((response, save_data))
 }
 
 #[cfg(test)]
@@ -8,7 +8,8 @@ mod types;
 pub use client::Client;
 pub use errors::Error;
 pub use handlers::{
-    compiler_versions, solidity_multi_part, solidity_standard_json, sourcify, vyper_multi_part,
+    compiler_versions, preview, reverification, solidity_multi_part, solidity_standard_json,
+    sourcify, vyper_multi_part,
 };
 pub use types::{
     BytecodePart, BytecodeType, MatchType, Source, SourceType, VerificationMetadata,
@@ -1,4 +1,5 @@
 use super::smart_contract_verifier;
+use crate::source_checks::SourceWarning;
 use entity::sea_orm_active_enums;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -66,6 +67,15 @@ impl From<BytecodeType> for sea_orm_active_enums::BytecodeType {
     }
 }
 
+impl From<sea_orm_active_enums::BytecodeType> for BytecodeType {
+    fn from(value: sea_orm_active_enums::BytecodeType) -> Self {
+        match value {
+            sea_orm_active_enums::BytecodeType::CreationInput => BytecodeType::CreationInput,
+            sea_orm_active_enums::BytecodeType::DeployedBytecode => BytecodeType::DeployedBytecode,
+        }
+    }
+}
+
 impl From<BytecodeType> for smart_contract_verifier::BytecodeType {
     fn from(value: BytecodeType) -> Self {
         match value {
@@ -150,6 +160,26 @@ impl From<smart_contract_verifier::MatchType> for MatchType {
     }
 }
 
+/// Unknown match types are stored as nulls
+impl From<MatchType> for Option<sea_orm_active_enums::MatchType> {
+    fn from(value: MatchType) -> Self {
+        match value {
+            MatchType::Unknown => None,
+            MatchType::Partial => Some(sea_orm_active_enums::MatchType::Partial),
+            MatchType::Full => Some(sea_orm_active_enums::MatchType::Full),
+        }
+    }
+}
+
+impl From<sea_orm_active_enums::MatchType> for MatchType {
+    fn from(value: sea_orm_active_enums::MatchType) -> Self {
+        match value {
+            sea_orm_active_enums::MatchType::Partial => MatchType::Partial,
+            sea_orm_active_enums::MatchType::Full => MatchType::Full,
+        }
+    }
+}
+
 /********** Source **********/
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -168,6 +198,10 @@ pub struct Source {
     pub raw_deployed_bytecode: Vec<u8>,
     pub creation_input_parts: Vec<BytecodePart>,
     pub deployed_bytecode_parts: Vec<BytecodePart>,
+
+    /// Issues found by the source checks before the source has been published
+    #[serde(default)]
+    pub warnings: Vec<SourceWarning>,
 }
 
 /********** Verification Request **********/
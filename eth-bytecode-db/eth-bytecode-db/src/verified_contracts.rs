@@ -0,0 +1,221 @@
+//! Invalidation of the verified contracts whose bytecode has changed on chain
+//! (the contract has been selfdestructed or redeployed, or the block has been reorged).
+//!
+//! Stale verified contracts are never deleted, so that the verification history
+//! of the address is retained for audit. New verifications of the address
+//! are stored as separate records.
+//!
+//! The same applies to partial matches: when a full match of the address arrives,
+//! the partial ones are not overwritten, but marked as superseded by the full match.
+
+use crate::{
+    licenses,
+    verification::{BytecodeType, MatchType},
+};
+use anyhow::Context;
+use entity::{sea_orm_active_enums, sources, verified_contracts};
+use sea_orm::{
+    prelude::DateTime, ActiveEnum, ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Statement,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaleReason {
+    /// The block containing the contract deployment has been reorged
+    Reorg,
+    /// The contract has been selfdestructed
+    Selfdestruct,
+    /// Another contract has been deployed at the same address
+    Redeployed,
+}
+
+impl From<StaleReason> for sea_orm_active_enums::StaleReason {
+    fn from(value: StaleReason) -> Self {
+        match value {
+            StaleReason::Reorg => sea_orm_active_enums::StaleReason::Reorg,
+            StaleReason::Selfdestruct => sea_orm_active_enums::StaleReason::Selfdestruct,
+            StaleReason::Redeployed => sea_orm_active_enums::StaleReason::Redeployed,
+        }
+    }
+}
+
+impl From<sea_orm_active_enums::StaleReason> for StaleReason {
+    fn from(value: sea_orm_active_enums::StaleReason) -> Self {
+        match value {
+            sea_orm_active_enums::StaleReason::Reorg => StaleReason::Reorg,
+            sea_orm_active_enums::StaleReason::Selfdestruct => StaleReason::Selfdestruct,
+            sea_orm_active_enums::StaleReason::Redeployed => StaleReason::Redeployed,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedContract {
+    pub id: i64,
+    pub source_id: i64,
+    pub bytecode_type: BytecodeType,
+    pub chain_id: Option<i64>,
+    pub contract_address: Option<Vec<u8>>,
+    pub verified_at: DateTime,
+    pub stale_at: Option<DateTime>,
+    pub stale_reason: Option<StaleReason>,
+    /// Is not set for the contracts verified before match types have been stored
+    pub match_type: Option<MatchType>,
+    /// Id of the full match verified contract which has upgraded the partial match
+    pub superseded_by: Option<i64>,
+}
+
+impl From<verified_contracts::Model> for VerifiedContract {
+    fn from(value: verified_contracts::Model) -> Self {
+        Self {
+            id: value.id,
+            source_id: value.source_id,
+            bytecode_type: value.bytecode_type.into(),
+            chain_id: value.chain_id,
+            contract_address: value.contract_address,
+            verified_at: value.created_at,
+            stale_at: value.stale_at,
+            stale_reason: value.stale_reason.map(StaleReason::from),
+            match_type: value.match_type.map(MatchType::from),
+            superseded_by: value.superseded_by,
+        }
+    }
+}
+
+/// Marks all not yet stale verified contracts of the address as stale.
+/// Returns the number of the verified contracts marked.
+pub async fn invalidate<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+    reason: StaleReason,
+) -> Result<u64, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let reason = sea_orm_active_enums::StaleReason::from(reason);
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                UPDATE verified_contracts
+                SET stale_at = now(), stale_reason = $3::stale_reason, updated_at = now()
+                WHERE chain_id = $1 AND contract_address = $2 AND stale_at IS NULL
+            "#,
+            [
+                chain_id.into(),
+                contract_address.to_vec().into(),
+                reason.to_value().into(),
+            ],
+        ))
+        .await
+        .context("mark verified contracts as stale")?;
+    Ok(result.rows_affected())
+}
+
+/// Links the just inserted verified contract with the other matches of the address.
+/// A full match supersedes all not stale partial matches which have not been superseded yet.
+/// A partial match is superseded right away if there is a not stale full match already.
+pub async fn supersede_partial_matches<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+    verified_contract_id: i64,
+    match_type: MatchType,
+) -> Result<(), anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let sql = match match_type {
+        MatchType::Full => {
+            r#"
+                UPDATE verified_contracts
+                SET superseded_by = $3, updated_at = now()
+                WHERE chain_id = $1 AND contract_address = $2 AND id <> $3
+                    AND match_type = 'partial' AND stale_at IS NULL AND superseded_by IS NULL
+            "#
+        }
+        MatchType::Partial => {
+            r#"
+                UPDATE verified_contracts
+                SET superseded_by = (
+                    SELECT id FROM verified_contracts
+                    WHERE chain_id = $1 AND contract_address = $2 AND id <> $3
+                        AND match_type = 'full' AND stale_at IS NULL
+                    ORDER BY id DESC
+                    LIMIT 1
+                )
+                WHERE id = $3
+            "#
+        }
+        MatchType::Unknown => return Ok(()),
+    };
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        sql,
+        [
+            chain_id.into(),
+            contract_address.to_vec().into(),
+            verified_contract_id.into(),
+        ],
+    ))
+    .await
+    .context("supersede partial matches")?;
+    Ok(())
+}
+
+/// Returns all verified contracts of the address including the stale ones, oldest first.
+pub async fn history<C>(
+    db: &C,
+    chain_id: i64,
+    contract_address: &[u8],
+) -> Result<Vec<VerifiedContract>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let verified_contracts = verified_contracts::Entity::find()
+        .filter(verified_contracts::Column::ChainId.eq(chain_id))
+        .filter(verified_contracts::Column::ContractAddress.eq(contract_address.to_vec()))
+        .order_by_asc(verified_contracts::Column::Id)
+        .all(db)
+        .await
+        .context("select verified contracts")?;
+    Ok(verified_contracts
+        .into_iter()
+        .map(VerifiedContract::from)
+        .collect())
+}
+
+/// Returns not stale verified contracts whose sources are distributed under the license,
+/// ordered by ids. Only verified contracts with id greater than `after_id` are returned.
+pub async fn list_by_license<C>(
+    db: &C,
+    license: &str,
+    chain_id: Option<i64>,
+    after_id: Option<i64>,
+    limit: u64,
+) -> Result<Vec<VerifiedContract>, anyhow::Error>
+where
+    C: ConnectionTrait,
+{
+    let mut query = verified_contracts::Entity::find()
+        .inner_join(sources::Entity)
+        .filter(sources::Column::License.eq(licenses::normalize(license)))
+        .filter(verified_contracts::Column::StaleAt.is_null());
+    if let Some(chain_id) = chain_id {
+        query = query.filter(verified_contracts::Column::ChainId.eq(chain_id));
+    }
+    if let Some(after_id) = after_id {
+        query = query.filter(verified_contracts::Column::Id.gt(after_id));
+    }
+    let verified_contracts = query
+        .order_by_asc(verified_contracts::Column::Id)
+        .limit(limit)
+        .all(db)
+        .await
+        .context("select verified contracts by license")?;
+    Ok(verified_contracts
+        .into_iter()
+        .map(VerifiedContract::from)
+        .collect())
+}
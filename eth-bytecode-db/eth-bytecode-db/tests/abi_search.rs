@@ -0,0 +1,115 @@
+mod verification_test_helpers;
+
+use entity::sources;
+use eth_bytecode_db::abi_search::{self, AbiQuery};
+use pretty_assertions::assert_eq;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use verification_test_helpers::{
+    insert_source, insert_verified_contract, source_model, verified_contract_model,
+};
+
+const DB_PREFIX: &str = "abi_search";
+
+async fn insert_source_with_abi(db: &DatabaseConnection, id: u8, abi: serde_json::Value) -> i64 {
+    let source = sources::ActiveModel {
+        abi: Set(Some(abi)),
+        ..source_model(id, &format!("Contract{id}"))
+    };
+    insert_source(db, source).await
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn search_by_abi() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "search_by_abi").await;
+    let db = db.client();
+
+    let token = insert_source_with_abi(
+        &db,
+        1,
+        serde_json::json!([
+            {"type": "function", "name": "transfer", "stateMutability": "nonpayable", "outputs": [],
+                "inputs": [{"name": "to", "type": "address"}, {"name": "value", "type": "uint256"}]},
+            {"type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+                {"name": "from", "type": "address", "indexed": true},
+                {"name": "to", "type": "address", "indexed": true},
+                {"name": "value", "type": "uint256", "indexed": false}]}
+        ]),
+    )
+    .await;
+    let vault = insert_source_with_abi(
+        &db,
+        2,
+        serde_json::json!([
+            {"type": "function", "name": "withdraw", "stateMutability": "nonpayable", "outputs": [],
+                "inputs": []}
+        ]),
+    )
+    .await;
+
+    // Sources are inserted directly, so their abis have to be indexed the same way
+    // as the ones stored before the index was introduced
+    let last_id = abi_search::index_sources(db.as_ref(), None, 1)
+        .await
+        .expect("indexing failed");
+    assert_eq!(Some(token), last_id);
+    let last_id = abi_search::index_sources(db.as_ref(), last_id, 1)
+        .await
+        .expect("indexing failed");
+    assert_eq!(Some(vault), last_id);
+    let last_id = abi_search::index_sources(db.as_ref(), None, 1)
+        .await
+        .expect("indexing failed");
+    assert_eq!(
+        None, last_id,
+        "indexed sources should not be selected again"
+    );
+
+    let token_address = [token as u8; 20];
+    let vault_address = [vault as u8; 20];
+    let token_id =
+        insert_verified_contract(&db, verified_contract_model(token, 5, &token_address)).await;
+    let vault_id =
+        insert_verified_contract(&db, verified_contract_model(vault, 5, &vault_address)).await;
+    let token_mainnet_id =
+        insert_verified_contract(&db, verified_contract_model(token, 1, &token_address)).await;
+
+    let search = |query: AbiQuery, chain_id: Option<i64>, after_id: Option<i64>| {
+        let db = db.clone();
+        async move {
+            abi_search::search(db.as_ref(), &query, chain_id, after_id, 10)
+                .await
+                .expect("search failed")
+        }
+    };
+    let ids = |matches: Vec<abi_search::AbiMatch>| -> Vec<i64> {
+        matches
+            .into_iter()
+            .map(|abi_match| abi_match.verified_contract_id)
+            .collect()
+    };
+
+    let transfer_selector = AbiQuery::Selector(vec![0xa9, 0x05, 0x9c, 0xbb]);
+    let matches = search(transfer_selector.clone(), None, None).await;
+    assert_eq!(vec![token_id, token_mainnet_id], ids(matches.clone()));
+    assert_eq!("function", matches[0].kind);
+    assert_eq!("transfer(address,uint256)", matches[0].signature);
+
+    let transfer_event = AbiQuery::Signature("Transfer(address,address,uint256)".into());
+    let matches = search(transfer_event, None, None).await;
+    assert_eq!(vec![token_id, token_mainnet_id], ids(matches.clone()));
+    assert_eq!("event", matches[0].kind);
+
+    assert_eq!(
+        vec![vault_id],
+        ids(search(AbiQuery::Signature("withdraw()".into()), None, None).await)
+    );
+    assert_eq!(
+        vec![token_mainnet_id],
+        ids(search(transfer_selector.clone(), Some(1), None).await)
+    );
+    assert_eq!(
+        vec![token_mainnet_id],
+        ids(search(transfer_selector, None, Some(token_id)).await)
+    );
+}
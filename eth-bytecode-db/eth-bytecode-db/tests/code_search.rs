@@ -0,0 +1,139 @@
+mod verification_test_helpers;
+
+use entity::{file_contents, files, source_files, sources};
+use eth_bytecode_db::code_search;
+use pretty_assertions::assert_eq;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection};
+use verification_test_helpers::{
+    insert_source, insert_verified_contract, source_model, verified_contract_model,
+};
+
+const DB_PREFIX: &str = "code_search";
+
+async fn insert_source_with_files(
+    db: &DatabaseConnection,
+    id: u8,
+    source_files: &[(&str, &str)],
+) -> i64 {
+    let source = sources::ActiveModel {
+        file_name: Set(source_files[0].0.to_string()),
+        ..source_model(id, &format!("Contract{id}"))
+    };
+    let source_id = insert_source(db, source).await;
+    for (index, (name, content)) in source_files.iter().enumerate() {
+        let content_hash = vec![id, index as u8];
+        file_contents::ActiveModel {
+            content_hash: Set(content_hash.clone()),
+            content: Set(content.to_string()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .expect("file content insertion failed");
+        let file_id = files::ActiveModel {
+            name: Set(name.to_string()),
+            content_hash: Set(content_hash),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .expect("file insertion failed")
+        .id;
+        source_files::ActiveModel {
+            source_id: Set(source_id),
+            file_id: Set(file_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .expect("source file insertion failed");
+    }
+    source_id
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn search_source_code() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "search_source_code").await;
+    let db = db.client();
+
+    let ownable = "contract Ownable {\n    modifier onlyOwner() { _; }\n}";
+    let token = insert_source_with_files(
+        &db,
+        1,
+        &[
+            (
+                "Token.sol",
+                "contract Token is Ownable {\n    function mint() external onlyOwner {}\n}",
+            ),
+            ("Ownable.sol", ownable),
+        ],
+    )
+    .await;
+    let vault = insert_source_with_files(
+        &db,
+        2,
+        &[(
+            "Vault.sol",
+            "contract Vault { function withdraw() external {} }",
+        )],
+    )
+    .await;
+
+    let token_address = [token as u8; 20];
+    let vault_address = [vault as u8; 20];
+    let token_id =
+        insert_verified_contract(&db, verified_contract_model(token, 5, &token_address)).await;
+    let vault_id =
+        insert_verified_contract(&db, verified_contract_model(vault, 5, &vault_address)).await;
+    let token_mainnet_id =
+        insert_verified_contract(&db, verified_contract_model(token, 1, &token_address)).await;
+
+    let search = |query: &'static str, chain_id: Option<i64>, after_id: Option<i64>| {
+        let db = db.clone();
+        async move {
+            code_search::search(db.as_ref(), query, chain_id, after_id, 10)
+                .await
+                .expect("search failed")
+        }
+    };
+    let ids = |matches: Vec<code_search::CodeMatch>| -> Vec<i64> {
+        matches
+            .into_iter()
+            .map(|code_match| code_match.verified_contract_id)
+            .collect()
+    };
+
+    let matches = search("ONLYOWNER", None, None).await;
+    assert_eq!(
+        vec![token_id, token_mainnet_id],
+        ids(matches.clone()),
+        "identifiers should be matched case-insensitively"
+    );
+    assert_eq!(
+        "Ownable.sol", matches[0].file_name,
+        "the first matched file by name should be returned"
+    );
+    assert!(
+        matches[0].highlight.contains("<mark>onlyOwner</mark>"),
+        "invalid highlight: {}",
+        matches[0].highlight
+    );
+
+    assert_eq!(
+        vec![vault_id],
+        ids(search("function withdraw()", None, None).await)
+    );
+    assert!(
+        search("withdraw function", None, None).await.is_empty(),
+        "words should follow each other in the same order"
+    );
+    assert_eq!(
+        vec![token_mainnet_id],
+        ids(search("onlyOwner", Some(1), None).await)
+    );
+    assert_eq!(
+        vec![token_mainnet_id],
+        ids(search("onlyOwner", None, Some(token_id)).await)
+    );
+}
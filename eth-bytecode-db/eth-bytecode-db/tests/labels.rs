@@ -0,0 +1,79 @@
+mod verification_test_helpers;
+
+use eth_bytecode_db::labels;
+use pretty_assertions::assert_eq;
+use verification_test_helpers::{
+    insert_source, insert_verified_contract, source_model, verified_contract_model,
+};
+
+const DB_PREFIX: &str = "labels";
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn manage_labels() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "manage_labels").await;
+    let db = db.client();
+
+    let address = [0x11; 20];
+    let names = |assigned: Vec<labels::Label>| -> Vec<String> {
+        assigned.into_iter().map(|label| label.name).collect()
+    };
+
+    let result = labels::create(db.as_ref(), 5, &address, "DEX", None).await;
+    assert!(
+        matches!(result, Err(labels::Error::ContractNotVerified)),
+        "not verified contracts should not be labeled"
+    );
+
+    let source_id = insert_source(&db, source_model(1, "Router")).await;
+    insert_verified_contract(&db, verified_contract_model(source_id, 5, &address)).await;
+    labels::create(db.as_ref(), 5, &address, " DEX ", None)
+        .await
+        .expect("label creation failed");
+    labels::create(
+        db.as_ref(),
+        5,
+        &address,
+        "Bridge",
+        Some("Canonical bridge".to_string()),
+    )
+    .await
+    .expect("label creation failed");
+    let result = labels::create(db.as_ref(), 5, &address, "DEX", None).await;
+    assert!(matches!(result, Err(labels::Error::AlreadyExists)));
+
+    let assigned = labels::list(db.as_ref(), 5, &address).await.unwrap();
+    assert_eq!(vec!["Bridge", "DEX"], names(assigned));
+    assert!(
+        labels::list(db.as_ref(), 1, &address)
+            .await
+            .unwrap()
+            .is_empty(),
+        "labels should be assigned per chain"
+    );
+
+    let updated = labels::update(
+        db.as_ref(),
+        5,
+        &address,
+        "DEX",
+        Some("Uniswap router".to_string()),
+    )
+    .await
+    .unwrap()
+    .expect("label should exist");
+    assert_eq!(Some("Uniswap router".to_string()), updated.description);
+    assert!(labels::update(db.as_ref(), 5, &address, "Scam", None)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert!(labels::delete(db.as_ref(), 5, &address, "Bridge")
+        .await
+        .unwrap());
+    assert!(!labels::delete(db.as_ref(), 5, &address, "Bridge")
+        .await
+        .unwrap());
+    let assigned = labels::list(db.as_ref(), 5, &address).await.unwrap();
+    assert_eq!(vec!["DEX"], names(assigned));
+}
@@ -0,0 +1,77 @@
+mod verification_test_helpers;
+
+use entity::verified_contracts;
+use eth_bytecode_db::{verification::MatchType, verified_contracts as eth_verified_contracts};
+use pretty_assertions::assert_eq;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use verification_test_helpers::{insert_source, source_model, verified_contract_model};
+
+const DB_PREFIX: &str = "match_types";
+
+async fn insert_verified_contract(
+    db: &DatabaseConnection,
+    source_id: i64,
+    chain_id: i64,
+    contract_address: &[u8],
+    match_type: MatchType,
+) -> i64 {
+    let verified_contract = verified_contracts::ActiveModel {
+        match_type: Set(match_type.into()),
+        ..verified_contract_model(source_id, chain_id, contract_address)
+    };
+    let id = verification_test_helpers::insert_verified_contract(db, verified_contract).await;
+    eth_verified_contracts::supersede_partial_matches(
+        db,
+        chain_id,
+        contract_address,
+        id,
+        match_type,
+    )
+    .await
+    .expect("superseding partial matches failed");
+    id
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn full_match_supersedes_partial_matches() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "full_match_supersedes_partial_matches")
+        .await;
+    let db = db.client();
+
+    let source_id = insert_source(&db, source_model(1, "Router")).await;
+    let address = [0x11; 20];
+    let partial_id =
+        insert_verified_contract(&db, source_id, 5, &address, MatchType::Partial).await;
+    let other_chain_id =
+        insert_verified_contract(&db, source_id, 1, &address, MatchType::Partial).await;
+    let full_id = insert_verified_contract(&db, source_id, 5, &address, MatchType::Full).await;
+    let late_partial_id =
+        insert_verified_contract(&db, source_id, 5, &address, MatchType::Partial).await;
+
+    let history = eth_verified_contracts::history(db.as_ref(), 5, &address)
+        .await
+        .expect("history retrieval failed");
+    let matches: Vec<_> = history
+        .into_iter()
+        .map(|contract| (contract.id, contract.match_type, contract.superseded_by))
+        .collect();
+    assert_eq!(
+        vec![
+            (partial_id, Some(MatchType::Partial), Some(full_id)),
+            (full_id, Some(MatchType::Full), None),
+            (late_partial_id, Some(MatchType::Partial), Some(full_id)),
+        ],
+        matches,
+        "both records should be kept with the partial matches superseded"
+    );
+
+    let history = eth_verified_contracts::history(db.as_ref(), 1, &address)
+        .await
+        .expect("history retrieval failed");
+    assert_eq!(
+        None, history[0].superseded_by,
+        "partial matches of other chains should not be superseded"
+    );
+    assert_eq!(other_chain_id, history[0].id);
+}
@@ -0,0 +1,102 @@
+mod verification_test_helpers;
+
+use entity::{bytecodes, sea_orm_active_enums};
+use eth_bytecode_db::multichain_search::{self, SearchQuery};
+use pretty_assertions::assert_eq;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection};
+
+const DB_PREFIX: &str = "multichain_search";
+
+async fn insert_source(db: &DatabaseConnection, contract_name: &str, code_hash: u8) -> i64 {
+    let source_id = verification_test_helpers::insert_source(
+        db,
+        verification_test_helpers::source_model(code_hash, contract_name),
+    )
+    .await;
+    bytecodes::ActiveModel {
+        source_id: Set(source_id),
+        bytecode_type: Set(sea_orm_active_enums::BytecodeType::DeployedBytecode),
+        normalized_code_hash: Set(Some(vec![code_hash; 32])),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .expect("bytecode insertion failed");
+    source_id
+}
+
+async fn insert_verified_contract(
+    db: &DatabaseConnection,
+    source_id: i64,
+    chain_id: i64,
+    contract_address: &[u8],
+) -> i64 {
+    verification_test_helpers::insert_verified_contract(
+        db,
+        verification_test_helpers::verified_contract_model(source_id, chain_id, contract_address),
+    )
+    .await
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn search_across_chains() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "search_across_chains").await;
+    let db = db.client();
+
+    let token = insert_source(&db, "Token", 1).await;
+    let token_v2 = insert_source(&db, "Token", 2).await;
+    let vault = insert_source(&db, "Vault", 3).await;
+
+    let address = [0x11; 20];
+    insert_verified_contract(&db, token, 5, &address).await;
+    // Re-verification of the same address hides the previous one
+    let reverified = insert_verified_contract(&db, token_v2, 5, &address).await;
+    let mainnet = insert_verified_contract(&db, token, 1, &address).await;
+    insert_verified_contract(&db, token, 100, &[0x22; 20]).await;
+    insert_verified_contract(&db, vault, 1, &[0x33; 20]).await;
+
+    let search = |query| {
+        let db = db.clone();
+        async move {
+            multichain_search::search(db.as_ref(), &query, 10)
+                .await
+                .expect("search failed")
+                .into_iter()
+                .map(|contract| {
+                    (
+                        contract.verified_contract_id,
+                        contract.chain_id,
+                        contract.code_hash.map(|hash| hash[0]),
+                    )
+                })
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        vec![(mainnet, 1, Some(1)), (reverified, 5, Some(2))],
+        search(SearchQuery::ContractAddress(address.to_vec())).await
+    );
+    assert_eq!(
+        vec![1, 5, 100],
+        search(SearchQuery::ContractName("Token".to_string()))
+            .await
+            .into_iter()
+            .map(|(_, chain_id, _)| chain_id)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![1, 100],
+        search(SearchQuery::CodeHash(vec![1; 32]))
+            .await
+            .into_iter()
+            .map(|(_, chain_id, _)| chain_id)
+            .collect::<Vec<_>>(),
+        "re-verified address should not match the previous code hash"
+    );
+    assert_eq!(
+        Vec::<(i64, i64, Option<u8>)>::new(),
+        search(SearchQuery::ContractName("token".to_string())).await
+    );
+}
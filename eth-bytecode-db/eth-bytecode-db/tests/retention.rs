@@ -0,0 +1,149 @@
+mod verification_test_helpers;
+
+use entity::{sea_orm_active_enums::VerificationJobStatus, verification_jobs};
+use eth_bytecode_db::{
+    jobs::{self, JobResult},
+    retention::{self, Report, RetentionParams},
+};
+use pretty_assertions::assert_eq;
+use sea_orm::{
+    ActiveValue::Set, ConnectionTrait, DatabaseBackend, DatabaseConnection, EntityTrait, Statement,
+};
+use std::time::Duration;
+
+const DB_PREFIX: &str = "retention";
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// `age` is the interval passed since the job has been completed (e.g., `10 days`).
+async fn insert_job(
+    db: &DatabaseConnection,
+    status: VerificationJobStatus,
+    result: Option<serde_json::Value>,
+    age: &str,
+) -> i64 {
+    let job = verification_jobs::ActiveModel {
+        result: Set(result),
+        ..verification_test_helpers::verification_job_model(status)
+    };
+    let id = verification_test_helpers::insert_verification_job(db, job).await;
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "UPDATE verification_jobs SET updated_at = now() - $2::interval WHERE id = $1",
+        [id.into(), age.into()],
+    ))
+    .await
+    .expect("job age update failed");
+    id
+}
+
+async fn find_model(db: &DatabaseConnection, id: i64) -> Option<verification_jobs::Model> {
+    verification_jobs::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .expect("job selection failed")
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn purge_and_compress_old_jobs() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "purge_and_compress_old_jobs").await;
+    let db = db.client();
+
+    let failure = serde_json::to_value(JobResult::Failure {
+        message: "no match".to_string(),
+    })
+    .unwrap();
+    let old_failed = insert_job(&db, VerificationJobStatus::Failed, None, "10 days").await;
+    let recent_failed = insert_job(&db, VerificationJobStatus::Failed, None, "1 day").await;
+    let old_succeeded = insert_job(
+        &db,
+        VerificationJobStatus::Succeeded,
+        Some(failure.clone()),
+        "10 days",
+    )
+    .await;
+    let queued = insert_job(&db, VerificationJobStatus::Queued, None, "10 days").await;
+
+    let params = RetentionParams {
+        purge_failed_after: Some(7 * DAY),
+        compress_after: Some(7 * DAY),
+        max_store_size: None,
+        batch_size: 1,
+    };
+    let report = retention::apply(db.as_ref(), &params)
+        .await
+        .expect("retention failed");
+    assert_eq!(
+        Report {
+            purged: 1,
+            compressed: 1,
+            evicted: 0,
+        },
+        report
+    );
+
+    let request = |id| {
+        let db = db.clone();
+        async move { find_model(&db, id).await.expect("job not found").request }
+    };
+    assert_eq!(serde_json::Value::Null, request(old_failed).await);
+    assert_ne!(serde_json::Value::Null, request(recent_failed).await);
+    assert_ne!(serde_json::Value::Null, request(queued).await);
+
+    let model = find_model(&db, old_succeeded).await.unwrap();
+    assert_eq!(None, model.result, "compressed result should be removed");
+    assert!(model.compressed_result.is_some());
+    let job = jobs::find_job(db.as_ref(), old_succeeded)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        Some(serde_json::from_value(failure).unwrap()),
+        job.result,
+        "compressed result should be retrieved"
+    );
+
+    assert_eq!(
+        Report::default(),
+        retention::apply(db.as_ref(), &params).await.unwrap(),
+        "policies should not be applied twice"
+    );
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn evict_least_recently_used_jobs() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "evict_least_recently_used_jobs").await;
+    let db = db.client();
+
+    let oldest = insert_job(&db, VerificationJobStatus::Succeeded, None, "3 days").await;
+    let accessed = insert_job(&db, VerificationJobStatus::Failed, None, "2 days").await;
+    let recent = insert_job(&db, VerificationJobStatus::Succeeded, None, "1 day").await;
+    let queued = insert_job(&db, VerificationJobStatus::Queued, None, "3 days").await;
+    jobs::find_job(db.as_ref(), accessed).await.unwrap();
+
+    // All jobs have the same requests and no results
+    let job_size: i32 = db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT pg_column_size(request) AS size FROM verification_jobs WHERE id = $1",
+            [recent.into()],
+        ))
+        .await
+        .expect("job size selection failed")
+        .expect("job not found")
+        .try_get("", "size")
+        .unwrap();
+    let job_size = job_size as u64;
+    let evicted = retention::evict_least_recently_used(db.as_ref(), 2 * job_size + 1)
+        .await
+        .expect("eviction failed");
+    assert_eq!(1, evicted);
+
+    assert_eq!(None, find_model(&db, oldest).await, "oldest job");
+    assert!(find_model(&db, accessed).await.is_some(), "accessed job");
+    assert!(find_model(&db, recent).await.is_some(), "recent job");
+    assert!(find_model(&db, queued).await.is_some(), "queued job");
+}
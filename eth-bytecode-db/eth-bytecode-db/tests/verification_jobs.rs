@@ -0,0 +1,294 @@
+mod verification_test_helpers;
+
+use entity::{
+    sea_orm_active_enums::{VerificationJobPriority, VerificationJobStatus},
+    verification_jobs,
+};
+use eth_bytecode_db::jobs::{self, JobPriority, JobStatus, Recovery};
+use pretty_assertions::assert_eq;
+use sea_orm::{ActiveValue::Set, ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement};
+use std::time::Duration;
+
+const DB_PREFIX: &str = "verification_jobs";
+
+/// `lease` is the interval the job lease expires in (e.g., `-1 minute` for expired ones).
+async fn insert_job(
+    db: &DatabaseConnection,
+    status: VerificationJobStatus,
+    attempts: i32,
+    lease: Option<&str>,
+) -> i64 {
+    let job = verification_jobs::ActiveModel {
+        attempts: Set(attempts),
+        ..verification_test_helpers::verification_job_model(status)
+    };
+    let id = verification_test_helpers::insert_verification_job(db, job).await;
+
+    if let Some(lease) = lease {
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"
+                UPDATE verification_jobs
+                SET lease_owner = 'worker', lease_expires_at = now() + $2::interval
+                WHERE id = $1
+            "#,
+            [id.into(), lease.into()],
+        ))
+        .await
+        .expect("job lease update failed");
+    }
+    id
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn recover_incomplete_jobs() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "recover_incomplete_jobs").await;
+    let db = db.client();
+
+    let expired = Some("-1 minute");
+    let active = Some("1 minute");
+
+    let queued = insert_job(&db, VerificationJobStatus::Queued, 0, None).await;
+    let expired_lease = insert_job(&db, VerificationJobStatus::InProgress, 1, expired).await;
+    let without_lease = insert_job(&db, VerificationJobStatus::InProgress, 1, None).await;
+    let active_lease = insert_job(&db, VerificationJobStatus::InProgress, 3, active).await;
+    let interrupted_too_often =
+        insert_job(&db, VerificationJobStatus::InProgress, 3, expired).await;
+    let succeeded = insert_job(&db, VerificationJobStatus::Succeeded, 1, None).await;
+
+    let recovery = jobs::recover(db.as_ref(), 3)
+        .await
+        .expect("recovery failed");
+    assert_eq!(
+        Recovery {
+            requeued: vec![expired_lease, without_lease],
+            orphaned: vec![interrupted_too_often],
+        },
+        recovery
+    );
+
+    let status = |id| {
+        let db = db.clone();
+        async move {
+            jobs::find_job(db.as_ref(), id)
+                .await
+                .expect("job selection failed")
+                .expect("job not found")
+                .status
+        }
+    };
+    assert_eq!(JobStatus::Queued, status(queued).await);
+    assert_eq!(JobStatus::Queued, status(expired_lease).await);
+    assert_eq!(JobStatus::InProgress, status(active_lease).await);
+    assert_eq!(JobStatus::Orphaned, status(interrupted_too_often).await);
+    assert_eq!(JobStatus::Succeeded, status(succeeded).await);
+
+    let orphaned = jobs::find_job(db.as_ref(), interrupted_too_often)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        orphaned.error.is_some(),
+        "orphaned jobs should contain the reason"
+    );
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn claim_jobs_by_multiple_workers() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "claim_jobs_by_multiple_workers").await;
+    let db = db.client();
+    let lease = Duration::from_secs(60);
+
+    let first = insert_job(&db, VerificationJobStatus::Queued, 0, None).await;
+    let second = insert_job(&db, VerificationJobStatus::Queued, 0, None).await;
+
+    let claim = |worker_id: &'static str| {
+        let db = db.clone();
+        async move {
+            jobs::claim_job(db.as_ref(), worker_id, JobPriority::Interactive, lease)
+                .await
+                .expect("job claiming failed")
+                .map(|(id, _request)| id)
+        }
+    };
+    let (claimed_1, claimed_2) = futures::join!(claim("worker-1"), claim("worker-2"));
+    let mut claimed = vec![
+        claimed_1.expect("job should be claimed"),
+        claimed_2.expect("job should be claimed"),
+    ];
+    claimed.sort();
+    assert_eq!(
+        vec![first, second],
+        claimed,
+        "each job should be claimed once"
+    );
+    assert_eq!(
+        None,
+        claim("worker-3").await,
+        "no queued jobs should remain"
+    );
+
+    let job = jobs::find_job(db.as_ref(), first).await.unwrap().unwrap();
+    assert_eq!(JobStatus::InProgress, job.status);
+    assert_eq!(1, job.attempts);
+
+    let owner = if claimed_1 == Some(first) {
+        "worker-1"
+    } else {
+        "worker-2"
+    };
+    assert!(jobs::renew_lease(db.as_ref(), first, owner, lease)
+        .await
+        .unwrap());
+    assert!(
+        !jobs::renew_lease(db.as_ref(), first, "worker-3", lease)
+            .await
+            .unwrap(),
+        "lease should not be renewed by another worker"
+    );
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn claim_jobs_by_priority() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "claim_jobs_by_priority").await;
+    let db = db.client();
+    let lease = Duration::from_secs(60);
+
+    let bulk = verification_jobs::ActiveModel {
+        priority: Set(VerificationJobPriority::Bulk),
+        ..verification_test_helpers::verification_job_model(VerificationJobStatus::Queued)
+    };
+    let bulk = verification_test_helpers::insert_verification_job(&db, bulk).await;
+    let interactive = insert_job(&db, VerificationJobStatus::Queued, 0, None).await;
+
+    let claim = |priority| {
+        let db = db.clone();
+        async move {
+            jobs::claim_job(db.as_ref(), "worker", priority, lease)
+                .await
+                .expect("job claiming failed")
+                .map(|(id, _request)| id)
+        }
+    };
+    assert_eq!(
+        Some(interactive),
+        claim(JobPriority::Interactive).await,
+        "older bulk job should not be claimed by the interactive lane"
+    );
+    assert_eq!(None, claim(JobPriority::Interactive).await);
+    assert_eq!(Some(bulk), claim(JobPriority::Bulk).await);
+
+    let job = jobs::find_job(db.as_ref(), bulk).await.unwrap().unwrap();
+    assert_eq!(JobPriority::Bulk, job.priority);
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn collect_queue_state() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "collect_queue_state").await;
+    let db = db.client();
+
+    let with_request =
+        |status, compiler_version: &str, error: Option<&str>| verification_jobs::ActiveModel {
+            request: Set(serde_json::json!({
+                "type": "solidity_multi_part",
+                "request": {"compiler_version": compiler_version}
+            })),
+            error: Set(error.map(str::to_string)),
+            ..verification_test_helpers::verification_job_model(status)
+        };
+    insert_job(&db, VerificationJobStatus::Queued, 0, None).await;
+    for compiler_version in ["v0.8.19", "v0.8.19", "v0.7.6"] {
+        let job = with_request(VerificationJobStatus::InProgress, compiler_version, None);
+        verification_test_helpers::insert_verification_job(&db, job).await;
+    }
+    let failed = with_request(VerificationJobStatus::Failed, "v0.8.19", Some("internal"));
+    let failed = verification_test_helpers::insert_verification_job(&db, failed).await;
+    let orphaned = with_request(
+        VerificationJobStatus::Orphaned,
+        "v0.7.6",
+        Some("interrupted"),
+    );
+    let orphaned = verification_test_helpers::insert_verification_job(&db, orphaned).await;
+
+    let state = jobs::queue_state(db.as_ref(), 1)
+        .await
+        .expect("queue state collection failed");
+
+    let count = |status| {
+        state
+            .job_counts
+            .iter()
+            .find(|count| count.status == status && count.priority == JobPriority::Interactive)
+            .map(|count| count.count)
+    };
+    assert_eq!(Some(1), count(JobStatus::Queued));
+    assert_eq!(Some(3), count(JobStatus::InProgress));
+    assert_eq!(None, count(JobStatus::Succeeded));
+    assert!(state.oldest_queued_age.is_some());
+    assert_eq!(
+        std::collections::BTreeMap::from([("v0.7.6".to_string(), 1), ("v0.8.19".to_string(), 2)]),
+        state.in_flight_by_compiler_version
+    );
+    assert_eq!(
+        1,
+        state.recent_failures.len(),
+        "failures limit is not applied"
+    );
+    let failure = &state.recent_failures[0];
+    assert!(
+        [failed, orphaned].contains(&failure.id),
+        "unexpected failed job: {}",
+        failure.id
+    );
+    assert!(failure.error.is_some());
+}
+
+#[tokio::test]
+#[ignore = "Needs database to run"]
+async fn cancel_jobs() {
+    let db = verification_test_helpers::init_db(DB_PREFIX, "cancel_jobs").await;
+    let db = db.client();
+    let lease = Duration::from_secs(60);
+
+    let queued = insert_job(&db, VerificationJobStatus::Queued, 0, None).await;
+    let in_progress = insert_job(&db, VerificationJobStatus::Queued, 0, None).await;
+    let succeeded = insert_job(&db, VerificationJobStatus::Succeeded, 1, None).await;
+
+    let cancel = |id| {
+        let db = db.clone();
+        async move {
+            jobs::cancel_job(db.as_ref(), id)
+                .await
+                .expect("job cancellation failed")
+                .map(|job| job.status)
+        }
+    };
+    assert_eq!(Some(JobStatus::Cancelled), cancel(queued).await);
+    assert_eq!(
+        Some(in_progress),
+        jobs::claim_job(db.as_ref(), "worker", JobPriority::Interactive, lease)
+            .await
+            .unwrap()
+            .map(|(id, _request)| id),
+        "cancelled jobs should not be claimed"
+    );
+
+    assert_eq!(Some(JobStatus::Cancelled), cancel(in_progress).await);
+    assert!(
+        !jobs::renew_lease(db.as_ref(), in_progress, "worker", lease)
+            .await
+            .unwrap(),
+        "lease of the cancelled job should not be renewed"
+    );
+
+    assert_eq!(
+        Some(JobStatus::Succeeded),
+        cancel(succeeded).await,
+        "completed jobs should not be cancelled"
+    );
+    assert_eq!(None, cancel(i64::MAX).await);
+}
@@ -0,0 +1,73 @@
+//! Rows inserted directly into the database by the tests of the read-only functionality
+//! (search, labels, etc.) and of the job processing, which do not need the verification to be run.
+
+use entity::{sea_orm_active_enums, sources, verification_jobs, verified_contracts};
+use sea_orm::{prelude::Uuid, ActiveModelTrait, ActiveValue::Set, DatabaseConnection};
+
+/// Returns the source with the fields the tests do not check filled in.
+/// `id` makes the bytecodes and the files hash of the source unique.
+pub fn source_model(id: u8, contract_name: &str) -> sources::ActiveModel {
+    sources::ActiveModel {
+        source_type: Set(sea_orm_active_enums::SourceType::Solidity),
+        compiler_version: Set("v0.8.19+commit.7dd6d404".to_string()),
+        compiler_settings: Set(serde_json::json!({})),
+        file_name: Set(format!("{contract_name}.sol")),
+        contract_name: Set(contract_name.to_string()),
+        raw_creation_input: Set(vec![0x60, id]),
+        raw_deployed_bytecode: Set(vec![0x60, id]),
+        file_ids_hash: Set(Uuid::from_u128(id as u128)),
+        ..Default::default()
+    }
+}
+
+/// Returns the contract deployed at the address verified with the source,
+/// with the fields the tests do not check filled in.
+pub fn verified_contract_model(
+    source_id: i64,
+    chain_id: i64,
+    contract_address: &[u8],
+) -> verified_contracts::ActiveModel {
+    verified_contracts::ActiveModel {
+        source_id: Set(source_id),
+        raw_bytecode: Set(vec![0x60]),
+        bytecode_type: Set(sea_orm_active_enums::BytecodeType::DeployedBytecode),
+        verification_settings: Set(serde_json::json!({})),
+        verification_type: Set(sea_orm_active_enums::VerificationType::MultiPartFiles),
+        chain_id: Set(Some(chain_id)),
+        contract_address: Set(Some(contract_address.to_vec())),
+        ..Default::default()
+    }
+}
+
+pub async fn insert_source(db: &DatabaseConnection, source: sources::ActiveModel) -> i64 {
+    source.insert(db).await.expect("source insertion failed").id
+}
+
+pub async fn insert_verified_contract(
+    db: &DatabaseConnection,
+    verified_contract: verified_contracts::ActiveModel,
+) -> i64 {
+    verified_contract
+        .insert(db)
+        .await
+        .expect("verified contract insertion failed")
+        .id
+}
+
+/// Returns the job with the fields the tests do not check filled in.
+pub fn verification_job_model(
+    status: sea_orm_active_enums::VerificationJobStatus,
+) -> verification_jobs::ActiveModel {
+    verification_jobs::ActiveModel {
+        status: Set(status),
+        request: Set(serde_json::json!({"type": "solidity_multi_part"})),
+        ..Default::default()
+    }
+}
+
+pub async fn insert_verification_job(
+    db: &DatabaseConnection,
+    job: verification_jobs::ActiveModel,
+) -> i64 {
+    job.insert(db).await.expect("job insertion failed").id
+}
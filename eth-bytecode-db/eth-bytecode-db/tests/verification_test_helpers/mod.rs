@@ -1,15 +1,20 @@
 #![allow(dead_code)]
 
+mod database_fixtures;
 mod database_helpers;
 pub mod smart_contract_veriifer_mock;
 mod test_input_data;
 
 use async_trait::async_trait;
 use blockscout_display_bytes::Bytes as DisplayBytes;
+pub use database_fixtures::{
+    insert_source, insert_verification_job, insert_verified_contract, source_model,
+    verification_job_model, verified_contract_model,
+};
 use database_helpers::TestDbGuard;
 use entity::{
-    bytecode_parts, bytecodes, files, parts, sea_orm_active_enums, source_files, sources,
-    verified_contracts,
+    bytecode_parts, bytecodes, file_contents, files, parts, sea_orm_active_enums, source_files,
+    sources, verified_contracts,
 };
 use eth_bytecode_db::verification::{
     BytecodeType, Client, Error, Source, SourceType, VerificationMetadata, VerificationRequest,
@@ -55,7 +60,7 @@ pub fn generate_verification_request<T>(
     }
 }
 
-async fn init_db(db_prefix: &str, test_name: &str) -> TestDbGuard {
+pub async fn init_db(db_prefix: &str, test_name: &str) -> TestDbGuard {
     #[allow(unused_variables)]
     let db_url: Option<String> = None;
     // Uncomment if providing url explicitly is more convenient
@@ -172,9 +177,10 @@ where
         "Invalid raw deployed bytecode"
     );
 
-    /* Assert inserted into "files" */
+    /* Assert inserted into "files" and "file_contents" */
 
     let files = files::Entity::find()
+        .find_also_related(file_contents::Entity)
         .all(db_client)
         .await
         .expect("Error while reading files");
@@ -184,16 +190,18 @@ where
         "Invalid number of files returned. Expected 2, actual {}",
         files.len()
     );
+    let has_file = |name: &str, content: &str| {
+        files.iter().any(|(file, file_content)| {
+            file.name == name
+                && file_content.as_ref().map(|value| value.content.as_str()) == Some(content)
+        })
+    };
     assert!(
-        files
-            .iter()
-            .any(|value| value.name == "source_file1.sol" && value.content == "content1"),
+        has_file("source_file1.sol", "content1"),
         "Source file 1 has not been added into 'files'"
     );
     assert!(
-        files
-            .iter()
-            .any(|value| value.name == "source_file2.sol" && value.content == "content2"),
+        has_file("source_file2.sol", "content2"),
         "Source file 1 has not been added into 'files'"
     );
 
@@ -84,6 +84,7 @@ pub fn input_data_1<T>(request: T, source_type: SourceType) -> TestInputData<T>
                 data: vec![0xcdu8, 0xefu8],
             },
         ],
+        warnings: vec![],
     };
 
     TestInputData {
@@ -2,11 +2,13 @@ use crate::{
     metrics::Metrics,
     router::{configure_router, HttpRouter},
     settings::{MetricsSettings, ServerSettings},
-    HttpServerSettings,
+    HttpServerSettings, TlsSettings,
 };
 use actix_web::{App, HttpServer};
 use actix_web_prom::PrometheusMetrics;
-use std::net::SocketAddr;
+use anyhow::Context;
+use rustls_pemfile::Item;
+use std::{fs::File, io::BufReader, net::SocketAddr, path::PathBuf, time::Duration};
 
 pub struct LaunchSettings {
     pub service_name: String,
@@ -37,7 +39,7 @@ where
                     .as_ref()
                     .map(|metrics| metrics.http_middleware().clone()),
                 &settings.server.http,
-            );
+            )?;
             tokio::spawn(async move { http_server_future.await.map_err(anyhow::Error::msg) })
         };
         futures.push(http_server)
@@ -66,37 +68,106 @@ where
     res?
 }
 
+/// Applies the connection settings to the server, binds it, and starts it.
+/// Is a macro, as the server types differ depending on whether the metrics are enabled.
+macro_rules! run_http_server {
+    ($server:expr, $settings:expr) => {{
+        let settings: &HttpServerSettings = $settings;
+        let mut server = $server;
+        if let Some(keep_alive) = settings.keep_alive {
+            server = server.keep_alive(Duration::from_secs(keep_alive));
+        }
+        if let Some(timeout) = settings.client_request_timeout {
+            server = server.client_request_timeout(Duration::from_millis(timeout));
+        }
+        #[cfg_attr(not(unix), allow(unused_mut))]
+        let mut server = match &settings.tls {
+            Some(tls) => server.bind_rustls(settings.addr, tls_config(tls)?),
+            None => server.bind(settings.addr),
+        }
+        .context("failed to bind server")?;
+        #[cfg(unix)]
+        if let Some(path) = &settings.unix_socket {
+            tracing::info!("starting http server on unix socket {}", path.display());
+            server = server
+                .bind_uds(path)
+                .with_context(|| format!("failed to bind unix socket {}", path.display()))?;
+        }
+        Ok(server.run())
+    }};
+}
+
 fn http_serve<R>(
     http: R,
     metrics: Option<PrometheusMetrics>,
     settings: &HttpServerSettings,
-) -> actix_web::dev::Server
+) -> Result<actix_web::dev::Server, anyhow::Error>
 where
     R: HttpRouter + Send + Sync + Clone + 'static,
 {
-    tracing::info!("starting http server on addr {}", settings.addr);
+    let scheme = match settings.tls {
+        Some(_) => "https",
+        None => "http",
+    };
+    tracing::info!("starting {} server on addr {}", scheme, settings.addr);
 
     let json_cfg = actix_web::web::JsonConfig::default().limit(settings.max_body_size);
     if let Some(metrics) = metrics {
-        HttpServer::new(move || {
-            App::new()
-                .wrap(metrics.clone())
-                .app_data(json_cfg.clone())
-                .configure(configure_router(&http))
-        })
-        .bind(settings.addr)
-        .expect("failed to bind server")
-        .run()
+        run_http_server!(
+            HttpServer::new(move || {
+                App::new()
+                    .wrap(metrics.clone())
+                    .app_data(json_cfg.clone())
+                    .configure(configure_router(&http))
+            }),
+            settings
+        )
     } else {
-        HttpServer::new(move || {
-            App::new()
-                .app_data(json_cfg.clone())
-                .configure(configure_router(&http))
-        })
-        .bind(settings.addr)
-        .expect("failed to bind server")
-        .run()
+        run_http_server!(
+            HttpServer::new(move || {
+                App::new()
+                    .app_data(json_cfg.clone())
+                    .configure(configure_router(&http))
+            }),
+            settings
+        )
+    }
+}
+
+fn tls_config(settings: &TlsSettings) -> Result<rustls::ServerConfig, anyhow::Error> {
+    let open = |path: &PathBuf| {
+        File::open(path)
+            .map(BufReader::new)
+            .with_context(|| format!("failed to open {}", path.display()))
+    };
+
+    let certs: Vec<_> = rustls_pemfile::certs(&mut open(&settings.cert_path)?)
+        .context("invalid tls certificate")?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+    if certs.is_empty() {
+        anyhow::bail!("no certificates found in {}", settings.cert_path.display());
     }
+    let key = rustls_pemfile::read_all(&mut open(&settings.key_path)?)
+        .context("invalid tls private key")?
+        .into_iter()
+        .find_map(|item| match item {
+            Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => {
+                Some(rustls::PrivateKey(key))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            anyhow::anyhow!("no private key found in {}", settings.key_path.display())
+        })?;
+
+    let config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("invalid tls certificate or private key")?;
+    Ok(config)
 }
 
 fn grpc_serve(
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, str::FromStr};
+use std::{net::SocketAddr, path::PathBuf, str::FromStr};
 
 /// HTTP and GRPC server settings.
 /// Notice that, by default, HTTP server is enabled, and GRPC is disabled.
@@ -16,6 +16,19 @@ pub struct HttpServerSettings {
     pub enabled: bool,
     pub addr: SocketAddr,
     pub max_body_size: usize,
+    /// Time (in seconds) idle keep-alive connections are kept open for.
+    /// Zero disables keep-alive. If not set, the Actix default (5 seconds) is used.
+    pub keep_alive: Option<u64>,
+    /// Time (in milliseconds) the client should send the request headers within.
+    /// Zero disables the timeout. If not set, the Actix default (5 seconds) is used.
+    pub client_request_timeout: Option<u64>,
+    /// If set, the server accepts HTTPS connections only.
+    /// HTTP/2 is negotiated via ALPN, falling back to HTTP/1.1.
+    pub tls: Option<TlsSettings>,
+    /// If set, the server listens to the unix domain socket at the path as well.
+    /// Connections via the socket are not encrypted, even if `tls` is set.
+    /// Is supported on unix platforms only.
+    pub unix_socket: Option<PathBuf>,
 }
 
 impl Default for HttpServerSettings {
@@ -24,10 +37,23 @@ impl Default for HttpServerSettings {
             enabled: true,
             addr: SocketAddr::from_str("0.0.0.0:8050").unwrap(),
             max_body_size: 2 * 1024 * 1024, // 2 Mb - default Actix value
+            keep_alive: None,
+            client_request_timeout: None,
+            tls: None,
+            unix_socket: None,
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsSettings {
+    /// PEM encoded certificate chain, the server certificate first
+    pub cert_path: PathBuf,
+    /// PEM encoded private key (PKCS#8, PKCS#1, or SEC1)
+    pub key_path: PathBuf,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct GrpcServerSettings {
@@ -3,8 +3,8 @@ use ethabi::{ethereum_types::H256, RawLog};
 use sig_provider::SourceAggregator;
 use sig_provider_proto::blockscout::sig_provider::v1::{
     abi_service_server::AbiService, signature_service_server::SignatureService,
-    CreateSignaturesRequest, CreateSignaturesResponse, GetEventAbiRequest, GetEventAbiResponse,
-    GetFunctionAbiRequest, GetFunctionAbiResponse,
+    CreateSignaturesRequest, CreateSignaturesResponse, GetErrorAbiRequest, GetErrorAbiResponse,
+    GetEventAbiRequest, GetEventAbiResponse, GetFunctionAbiRequest, GetFunctionAbiResponse,
 };
 use std::sync::Arc;
 
@@ -82,4 +82,17 @@ impl AbiService for Service {
             .map(|abi| tonic::Response::new(GetEventAbiResponse { abi }))
             .map_err(|e| tonic::Status::internal(e.to_string()))
     }
+
+    async fn get_error_abi(
+        &self,
+        request: tonic::Request<GetErrorAbiRequest>,
+    ) -> Result<tonic::Response<GetErrorAbiResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let bytes = decode(&request.data)?;
+        self.agg
+            .get_error_abi(&bytes)
+            .await
+            .map(|abi| tonic::Response::new(GetErrorAbiResponse { abi }))
+            .map_err(|e| tonic::Status::internal(e.to_string()))
+    }
 }
@@ -134,7 +134,7 @@ async fn get_function() {
 
     assert_eq!(
         sort_json(
-            serde_json::json!([{"inputs":[{"components":[],"indexed":null,"name":"arg0","type":"address","value":"00000000219ab540356cbb839cbe05303d7705fa"}],"name":"balanceOf"}])
+            serde_json::json!([{"inputs":[{"components":[],"indexed":null,"name":"arg0","type":"address","value":"00000000219ab540356cbb839cbe05303d7705fa"}],"name":"balanceOf","description":null}])
         ),
         sort_json(response)
     );
@@ -197,8 +197,65 @@ async fn get_event() {
 
     assert_eq!(
         sort_json(
-            serde_json::json!([{"inputs":[{"components":[],"indexed":true,"name":"arg0","type":"address","value":"b8ace4d9bc469ddc8e788e636e817c299a1a8150"},{"components":[],"indexed":true,"name":"arg1","type":"address","value":"f76c5b19e86c256482f4aad1dae620a0c3ac0cd6"},{"components":[],"indexed":false,"name":"arg2","type":"uint256","value":"6acfc0"}],"name":"Transfer"}])
+            serde_json::json!([{"inputs":[{"components":[],"indexed":true,"name":"arg0","type":"address","value":"b8ace4d9bc469ddc8e788e636e817c299a1a8150"},{"components":[],"indexed":true,"name":"arg1","type":"address","value":"f76c5b19e86c256482f4aad1dae620a0c3ac0cd6"},{"components":[],"indexed":false,"name":"arg2","type":"uint256","value":"6acfc0"}],"name":"Transfer","description":null}])
         ),
         sort_json(response),
     );
 }
+
+#[tokio::test]
+async fn get_error() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let fourbyte_response = serde_json::json!({"count":1,"next":null,"previous":null,"results":[{"id":1004853,"created_at":"2023-01-14T10:41:47.523941Z","text_signature":"InsufficientBalance(uint256,uint256)","hex_signature":"0xcf479181","bytes_signature":"ÏG"}]});
+    let fourbyte = MockServer::start();
+    let fourbyte_handle = fourbyte.mock(|when, then| {
+        when.method(httpmock::Method::GET)
+            .path("/api/v1/signatures/")
+            .query_param("hex_signature", "cf479181");
+        then.status(200)
+            .header("Content-type", "application/json")
+            .json_body(fourbyte_response);
+    });
+
+    let sigeth_response = serde_json::json!({"ok":true,"result":{"event":{},"function":{},"error":{"0xcf479181":[{"name":"InsufficientBalance(uint256,uint256)","filtered":false}]}}});
+    let sigeth = MockServer::start();
+    let sigeth_handle = sigeth.mock(|when, then| {
+        when.method(httpmock::Method::GET)
+            .path("/api/v1/signatures")
+            .query_param("error", "0xcf479181")
+            .query_param_exists("all");
+        then.status(200)
+            .header("Content-type", "application/json")
+            .json_body(sigeth_response);
+    });
+
+    let service = new_service(SourcesSettings {
+        fourbyte: format!("http://127.0.0.1:{}/", fourbyte.port())
+            .parse()
+            .unwrap(),
+        sigeth: format!("http://127.0.0.1:{}/", sigeth.port())
+            .parse()
+            .unwrap(),
+    });
+    let app = actix_web::test::init_service(
+        App::new().configure(|config| http_configure(config, service.clone(), service.clone())),
+    )
+    .await;
+
+    let request = actix_web::test::TestRequest::default()
+        .method(http::Method::GET)
+        .uri("/api/v1/abi/error?data=0xcf479181000000000000000000000000000000000000000000000000000000000000006400000000000000000000000000000000000000000000000000000000000000c8")
+        .to_request();
+    let response: serde_json::Value = actix_web::test::call_and_read_body_json(&app, request).await;
+
+    fourbyte_handle.assert();
+    sigeth_handle.assert();
+
+    assert_eq!(
+        sort_json(
+            serde_json::json!([{"inputs":[{"components":[],"indexed":null,"name":"arg0","type":"uint256","value":"64"},{"components":[],"indexed":null,"name":"arg1","type":"uint256","value":"c8"}],"name":"InsufficientBalance","description":null}])
+        ),
+        sort_json(response)
+    );
+}
@@ -68,6 +68,13 @@ impl SourceAggregator {
         Ok(signatures)
     }
 
+    pub async fn get_error_signatures(&self, hex: &str) -> Result<Vec<String>, anyhow::Error> {
+        let sources = &self.sources;
+        let responses = proxy!(sources, hex, get_error_signatures);
+        let signatures = Self::merge_signatures(responses);
+        Ok(signatures)
+    }
+
     pub async fn get_function_abi(&self, tx_input: &[u8]) -> Result<Vec<Abi>, anyhow::Error> {
         if tx_input.len() < 4 {
             anyhow::bail!("tx input len must be at least 4 bytes");
@@ -82,6 +89,37 @@ impl SourceAggregator {
                 let inputs = parse_args("arg".into(), &args, &values);
                 Some(Abi {
                     name: name.into(),
+                    description: None,
+                    inputs,
+                })
+            })
+            .collect())
+    }
+
+    pub async fn get_error_abi(&self, revert_data: &[u8]) -> Result<Vec<Abi>, anyhow::Error> {
+        if revert_data.len() < 4 {
+            anyhow::bail!("revert data len must be at least 4 bytes");
+        }
+        let hex_sig = hex::encode(&revert_data[..4]);
+        // built-in errors are decoded without any lookup
+        let sigs = match hex_sig.as_str() {
+            ERROR_SELECTOR => vec!["Error(string)".to_string()],
+            PANIC_SELECTOR => vec!["Panic(uint256)".to_string()],
+            _ => self.get_error_signatures(&hex_sig).await?,
+        };
+        Ok(sigs
+            .into_iter()
+            .filter_map(|sig| {
+                let (name, args) = parse_signature(&sig)?;
+                let values = decode_txinput(&args, &revert_data[4..])?;
+                let description = match hex_sig.as_str() {
+                    PANIC_SELECTOR => panic_description(&values[0]),
+                    _ => None,
+                };
+                let inputs = parse_args("arg".into(), &args, &values);
+                Some(Abi {
+                    name: name.into(),
+                    description,
                     inputs,
                 })
             })
@@ -105,6 +143,7 @@ impl SourceAggregator {
                 }
                 Some(Abi {
                     name: name.into(),
+                    description: None,
                     inputs,
                 })
             })
@@ -112,6 +151,27 @@ impl SourceAggregator {
     }
 }
 
+const ERROR_SELECTOR: &str = "08c379a0";
+const PANIC_SELECTOR: &str = "4e487b71";
+
+// https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require
+fn panic_description(code: &Token) -> Option<String> {
+    let description = match u64::try_from(code.clone().into_uint()?).ok()? {
+        0x00 => "generic compiler inserted panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic operation resulted in underflow or overflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "conversion into non-existent enum type",
+        0x22 => "access to incorrectly encoded storage byte array",
+        0x31 => "pop() on an empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "too much memory allocated or array too large",
+        0x51 => "call to a zero-initialized variable of internal function type",
+        _ => return None,
+    };
+    Some(description.to_string())
+}
+
 fn parse_signature(sig: &str) -> Option<(&str, Vec<ParamType>)> {
     let start = sig.find('(')?;
     let name = &sig[..start];
@@ -220,6 +280,7 @@ mod tests {
                 "70a0823100000000000000000000000000000000219ab540356cbb839cbe05303d7705fa",
                 Abi {
                     name: "balanceOf".into(),
+                    description: None,
                     inputs: vec![Argument {
                         name: "arg0".into(),
                         r#type: "address".into(),
@@ -233,6 +294,7 @@ mod tests {
                 "70a082310000000000000000000000000000000000000000000000000000000000bc61591234567812345678000000000000000000000000000000000000000000000000",
                 Abi {
                     name: "branch_passphrase_public".into(),
+                    description: None,
                     inputs: vec![
                         Argument {
                             name: "arg0".into(),
@@ -255,6 +317,7 @@ mod tests {
                 "70a082310000000000000000000000000000000000000000000000000000000000bc615900000000000000000000000000000000219ab540356cbb839cbe05303d7705fa",
                 Abi {
                     name: "passphrase_calculate_transfer".into(),
+                    description: None,
                     inputs: vec![
                         Argument {
                             name: "arg0".into(),
@@ -350,6 +413,7 @@ mod tests {
 
         let expected = Abi {
             name: "test".into(),
+            description: None,
             inputs: vec![
                 Argument {
                     name: "arg0".into(),
@@ -440,6 +504,7 @@ mod tests {
                 "Transfer(address,address,uint256)",
                 Abi {
                     name: "Transfer".into(),
+                    description: None,
                     inputs: vec![
                         Argument {
                             name: "arg0".into(),
@@ -483,6 +548,7 @@ mod tests {
                 "Sync(uint112,uint112)",
                 Abi {
                     name: "Sync".into(),
+                    description: None,
                     inputs: vec![
                         Argument {
                             name: "arg0".into(),
@@ -533,6 +599,7 @@ mod tests {
                 "Swap(address,uint256,uint256,uint256,uint256,address)",
                 Abi {
                     name: "Swap".into(),
+                    description: None,
                     inputs: vec![
                         Argument {
                             name: "arg0".into(),
@@ -622,6 +689,7 @@ mod tests {
         let sig = "Test(string,string)";
         let abi = Abi {
             name: "Test".into(),
+            description: None,
             inputs: vec![
                 Argument {
                     name: "arg0".into(),
@@ -678,6 +746,7 @@ mod tests {
         let sig = "Test(address,(address,address))";
         let abi = Abi {
             name: "Test".into(),
+            description: None,
             inputs: vec![
                 Argument {
                     name: "arg0".into(),
@@ -728,4 +797,105 @@ mod tests {
         let event = agg.get_event_abi(input).await.unwrap();
         assert_eq!(abi, event[0]);
     }
+
+    #[tokio::test]
+    async fn builtin_errors() {
+        use ethabi::Token::*;
+        let tests = vec![
+            (
+                "08c379a0".to_string()
+                    + &hex::encode(ethabi::encode(&[String(
+                        "Not enough Ether provided.".into(),
+                    )])),
+                Abi {
+                    name: "Error".into(),
+                    description: None,
+                    inputs: vec![Argument {
+                        name: "arg0".into(),
+                        r#type: "string".into(),
+                        components: vec![],
+                        indexed: None,
+                        value: "Not enough Ether provided.".into(),
+                    }],
+                },
+            ),
+            (
+                "4e487b71".to_string() + &hex::encode(ethabi::encode(&[Uint(U256::from(0x11))])),
+                Abi {
+                    name: "Panic".into(),
+                    description: Some(
+                        "arithmetic operation resulted in underflow or overflow".into(),
+                    ),
+                    inputs: vec![Argument {
+                        name: "arg0".into(),
+                        r#type: "uint256".into(),
+                        components: vec![],
+                        indexed: None,
+                        value: "11".into(),
+                    }],
+                },
+            ),
+        ];
+
+        for (input, abi) in tests {
+            let mut source = MockSignatureSource::new();
+            source.expect_get_error_signatures().times(0);
+            let source = Arc::new(source);
+
+            let agg = Arc::new(SourceAggregator::new(vec![source.clone()]));
+
+            let error = agg
+                .get_error_abi(&hex::decode(input).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(vec![abi], error);
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_error() {
+        use ethabi::Token::*;
+        let input = "cf479181".to_string()
+            + &hex::encode(ethabi::encode(&[
+                Uint(U256::from(100)),
+                Uint(U256::from(200)),
+            ]));
+
+        let mut source = MockSignatureSource::new();
+        source
+            .expect_get_error_signatures()
+            .with(mockall::predicate::eq("cf479181"))
+            .times(1)
+            .returning(|_| Ok(vec!["InsufficientBalance(uint256,uint256)".into()]));
+        let source = Arc::new(source);
+
+        let agg = Arc::new(SourceAggregator::new(vec![source.clone()]));
+
+        let error = agg
+            .get_error_abi(&hex::decode(input).unwrap())
+            .await
+            .unwrap();
+
+        let expected = Abi {
+            name: "InsufficientBalance".into(),
+            description: None,
+            inputs: vec![
+                Argument {
+                    name: "arg0".into(),
+                    r#type: "uint256".into(),
+                    components: vec![],
+                    indexed: None,
+                    value: "64".into(),
+                },
+                Argument {
+                    name: "arg1".into(),
+                    r#type: "uint256".into(),
+                    components: vec![],
+                    indexed: None,
+                    value: "c8".into(),
+                },
+            ],
+        };
+        assert_eq!(vec![expected], error);
+    }
 }
@@ -60,6 +60,13 @@ impl SignatureSource for Source {
             .await
     }
 
+    async fn get_error_signatures(&self, hex: &str) -> Result<Vec<String>, anyhow::Error> {
+        // 4byte has no separate storage for errors, but error selectors
+        // are calculated the same way as function ones
+        self.fetch(format!("/api/v1/signatures/?hex_signature={hex}"))
+            .await
+    }
+
     fn source(&self) -> String {
         self.host.to_string()
     }
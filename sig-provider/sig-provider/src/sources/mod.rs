@@ -15,6 +15,8 @@ pub trait SignatureSource {
     async fn get_function_signatures(&self, hex: &str) -> Result<Vec<String>, anyhow::Error>;
     // Resulting signatures should be sorted in priority descending order (first - max priority)
     async fn get_event_signatures(&self, hex: &str) -> Result<Vec<String>, anyhow::Error>;
+    // Resulting signatures should be sorted in priority descending order (first - max priority)
+    async fn get_error_signatures(&self, hex: &str) -> Result<Vec<String>, anyhow::Error>;
 
     // for errors
     fn source(&self) -> String;
@@ -77,6 +77,15 @@ impl SignatureSource for Source {
         Ok(signatures)
     }
 
+    async fn get_error_signatures(&self, hex: &str) -> Result<Vec<String>, anyhow::Error> {
+        let hash = Self::hash(hex);
+        let resp = self
+            .fetch(&format!("/api/v1/signatures?error={hash}&all"))
+            .await?;
+        let signatures = Self::convert(resp.result.error, &hash);
+        Ok(signatures)
+    }
+
     fn source(&self) -> String {
         self.host.to_string()
     }
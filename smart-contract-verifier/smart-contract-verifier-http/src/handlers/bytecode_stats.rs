@@ -0,0 +1,86 @@
+use actix_web::{error, web::Json};
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{analyzer::bytecode_stats, decode_hex};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+#[derive(Debug, Deserialize)]
+pub struct BytecodeStatsRequest {
+    /// Bytecode stored in the blockchain
+    pub bytecode: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BytecodeStatsResponse {
+    pub opcode_histogram: BTreeMap<String, u64>,
+    pub code_size: usize,
+    pub push_data_size: usize,
+    pub metadata_size: usize,
+    pub estimated_deployment_gas: u64,
+    pub has_selfdestruct: bool,
+    pub has_delegatecall: bool,
+    pub has_create2: bool,
+}
+
+impl From<bytecode_stats::BytecodeStats> for BytecodeStatsResponse {
+    fn from(stats: bytecode_stats::BytecodeStats) -> Self {
+        Self {
+            opcode_histogram: stats.opcode_histogram,
+            code_size: stats.code_size,
+            push_data_size: stats.push_data_size,
+            metadata_size: stats.metadata_size,
+            estimated_deployment_gas: stats.estimated_deployment_gas,
+            has_selfdestruct: stats.has_selfdestruct,
+            has_delegatecall: stats.has_delegatecall,
+            has_create2: stats.has_create2,
+        }
+    }
+}
+
+/// Computes statistics of the deployed bytecode (opcode histogram, sizes of its parts,
+/// presence of the risky opcodes), which do not require the contract to be verified.
+#[instrument(skip(params), level = "debug")]
+pub async fn bytecode_stats(
+    params: Json<BytecodeStatsRequest>,
+) -> Result<Json<BytecodeStatsResponse>, actix_web::Error> {
+    let bytecode = decode_hex(&params.bytecode)
+        .map_err(|err| error::ErrorBadRequest(format!("Invalid bytecode: {err}")))?;
+    let stats = bytecode_stats::analyze(&bytecode);
+    Ok(Json(stats.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::parse::test_serialize_json_ok;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_stats() {
+        test_serialize_json_ok(vec![(
+            BytecodeStatsResponse {
+                opcode_histogram: BTreeMap::from([
+                    ("DELEGATECALL".to_string(), 1),
+                    ("PUSH1".to_string(), 2),
+                ]),
+                code_size: 3,
+                push_data_size: 2,
+                metadata_size: 0,
+                estimated_deployment_gas: 54_092,
+                has_selfdestruct: false,
+                has_delegatecall: true,
+                has_create2: false,
+            },
+            json!({
+                "opcode_histogram": {"DELEGATECALL": 1, "PUSH1": 2},
+                "code_size": 3,
+                "push_data_size": 2,
+                "metadata_size": 0,
+                "estimated_deployment_gas": 54092,
+                "has_selfdestruct": false,
+                "has_delegatecall": true,
+                "has_create2": false,
+            }),
+        )]);
+    }
+}
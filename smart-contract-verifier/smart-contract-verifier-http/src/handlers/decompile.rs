@@ -0,0 +1,54 @@
+use actix_web::{error, web, web::Json};
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{decode_hex, DecompilationError, Decompiler};
+use tracing::instrument;
+
+#[derive(Debug, Deserialize)]
+pub struct DecompileRequest {
+    /// Bytecode stored in the blockchain
+    pub bytecode: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct DecompileResponse {
+    /// Pseudo-Solidity source reconstructed from the bytecode
+    pub source: String,
+}
+
+/// Decompiles the deployed bytecode of an unverified contract. The results
+/// are approximate and are intended to be shown for informational purposes only.
+#[instrument(skip(decompiler, params), level = "debug")]
+pub async fn decompile(
+    decompiler: web::Data<dyn Decompiler>,
+    params: Json<DecompileRequest>,
+) -> Result<Json<DecompileResponse>, actix_web::Error> {
+    let bytecode = decode_hex(&params.bytecode)
+        .map_err(|err| error::ErrorBadRequest(format!("Invalid bytecode: {err}")))?;
+
+    match decompiler.decompile(&bytecode).await {
+        Ok(source) => Ok(Json(DecompileResponse { source })),
+        Err(err @ DecompilationError::EmptyBytecode) => Err(error::ErrorBadRequest(err)),
+        Err(err @ DecompilationError::Failed(_)) => Err(error::ErrorUnprocessableEntity(err)),
+        Err(err @ DecompilationError::Timeout(_)) => Err(error::ErrorGatewayTimeout(err)),
+        Err(err @ DecompilationError::Internal(_)) => Err(error::ErrorInternalServerError(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::parse::test_serialize_json_ok;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_decompiled() {
+        test_serialize_json_ok(vec![(
+            DecompileResponse {
+                source: "contract DecompiledContract {}".to_string(),
+            },
+            json!({
+                "source": "contract DecompiledContract {}",
+            }),
+        )]);
+    }
+}
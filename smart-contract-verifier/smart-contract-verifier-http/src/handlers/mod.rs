@@ -1,5 +1,11 @@
+pub mod bytecode_stats;
+pub mod decompile;
 pub mod status;
+pub mod verify;
 
+pub mod solidity_compile;
+pub mod solidity_compile_matrix;
+pub mod solidity_compiler_bugs;
 pub mod solidity_multi_part;
 pub mod solidity_standard_json;
 pub mod solidity_version_list;
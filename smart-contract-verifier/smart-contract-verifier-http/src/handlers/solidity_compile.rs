@@ -0,0 +1,161 @@
+use super::solidity_standard_json::{ParseError, StandardJson};
+use crate::verification_response::VerificationStatus;
+use actix_web::{error, web, web::Json};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{solidity, SolidityClient, VerificationError, Version};
+use std::{fmt::Display, str::FromStr};
+use tracing::instrument;
+
+#[derive(Debug, Deserialize)]
+pub struct CompileRequest {
+    pub compiler_version: String,
+
+    #[serde(flatten)]
+    pub content: StandardJson,
+}
+
+impl TryFrom<CompileRequest> for solidity::compile::CompileRequest {
+    type Error = ParseError;
+
+    fn try_from(value: CompileRequest) -> Result<Self, Self::Error> {
+        let compiler_version = Version::from_str(&value.compiler_version)
+            .map_err(|err| anyhow!("Invalid compiler version: {}", err))?;
+        Ok(Self {
+            compiler_version,
+            auto_select_compiler_version: false,
+            content: value.content.try_into()?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CompileResponse {
+    pub message: String,
+    pub status: VerificationStatus,
+    /// Compiler version used for compilation
+    pub compiler_version: Option<String>,
+    pub contracts: Vec<CompiledContract>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CompiledContract {
+    pub file_name: String,
+    pub contract_name: String,
+    pub abi: Option<String>,
+    pub creation_bytecode: Option<String>,
+    pub deployed_bytecode: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: String,
+    pub message: String,
+}
+
+impl CompileResponse {
+    fn ok(success: solidity::compile::CompileSuccess) -> Self {
+        Self {
+            message: "OK".to_string(),
+            status: VerificationStatus::Ok,
+            compiler_version: Some(success.compiler_version.to_string()),
+            contracts: success
+                .contracts
+                .into_iter()
+                .map(|contract| CompiledContract {
+                    file_name: contract.file_path,
+                    contract_name: contract.contract_name,
+                    abi: contract.abi.map(|abi| abi.to_string()),
+                    creation_bytecode: contract.creation_bytecode,
+                    deployed_bytecode: contract.deployed_bytecode,
+                })
+                .collect(),
+            diagnostics: success
+                .diagnostics
+                .into_iter()
+                .map(|diagnostic| Diagnostic {
+                    severity: diagnostic.severity,
+                    message: diagnostic.message,
+                })
+                .collect(),
+        }
+    }
+
+    fn err(message: impl Display, errors: Vec<String>) -> Self {
+        Self {
+            message: message.to_string(),
+            status: VerificationStatus::Failed,
+            compiler_version: None,
+            contracts: vec![],
+            diagnostics: errors
+                .into_iter()
+                .map(|message| Diagnostic {
+                    severity: "error".to_string(),
+                    message,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Compiles the sources without verifying them against any bytecode.
+#[instrument(skip(client, params), level = "debug")]
+pub async fn compile(
+    client: web::Data<SolidityClient>,
+    params: Json<CompileRequest>,
+) -> Result<Json<CompileResponse>, actix_web::Error> {
+    let request = match params.into_inner().try_into() {
+        Ok(request) => request,
+        Err(err @ (ParseError::InvalidContent(_) | ParseError::InvalidSourcePath(_))) => {
+            return Err(error::ErrorBadRequest(err))
+        }
+        Err(err @ ParseError::BadRequest(_)) => return Ok(Json(CompileResponse::err(err, vec![]))),
+    };
+
+    match solidity::compile::compile(client.into_inner(), request).await {
+        Ok(success) => Ok(Json(CompileResponse::ok(success))),
+        Err(VerificationError::Compilation(errors)) => {
+            Ok(Json(CompileResponse::err("Compilation error", errors)))
+        }
+        Err(
+            err @ (VerificationError::NoMatchingContracts(_)
+            | VerificationError::CompilerVersionMismatch(_)
+            | VerificationError::IncompatibleCompilerVersion { .. }
+            | VerificationError::MissingSources(_)
+            | VerificationError::UnsupportedOpcodes { .. }),
+        ) => Ok(Json(CompileResponse::err(err, vec![]))),
+        Err(
+            err @ (VerificationError::Initialization(_)
+            | VerificationError::VersionNotFound(_)
+            | VerificationError::LimitExceeded(_)),
+        ) => Err(error::ErrorBadRequest(err)),
+        Err(err @ VerificationError::Internal(_)) => Err(error::ErrorInternalServerError(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::parse::test_serialize_json_ok;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_compilation_failure() {
+        test_serialize_json_ok(vec![(
+            CompileResponse::err(
+                "Compilation error",
+                vec!["A.sol:1:1: ParserError: Expected pragma".to_string()],
+            ),
+            json!({
+                "message": "Compilation error",
+                "status": "1",
+                "compiler_version": null,
+                "contracts": [],
+                "diagnostics": [
+                    {"severity": "error", "message": "A.sol:1:1: ParserError: Expected pragma"}
+                ],
+            }),
+        )]);
+    }
+}
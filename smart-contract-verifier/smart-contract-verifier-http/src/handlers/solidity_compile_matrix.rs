@@ -0,0 +1,193 @@
+use super::solidity_standard_json::{ParseError, StandardJson};
+use crate::verification_response::VerificationStatus;
+use actix_web::{error, web, web::Json};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{
+    decode_hex,
+    solidity::{self, compile_matrix::Target},
+    MatchType, SolidityClient, VerificationError, Version,
+};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+use tracing::instrument;
+
+#[derive(Debug, Deserialize)]
+pub struct CompileMatrixRequest {
+    pub compiler_versions: Vec<String>,
+    /// Overrides of the standard json `settings`, each compiled with every compiler version
+    #[serde(default)]
+    pub settings: Vec<serde_json::Value>,
+    /// Bytecode the compiled contracts are matched against. The creation one
+    /// is used if both are provided, the same way as during verification
+    pub deployed_bytecode: Option<String>,
+    pub creation_bytecode: Option<String>,
+
+    #[serde(flatten)]
+    pub content: StandardJson,
+}
+
+impl TryFrom<CompileMatrixRequest> for solidity::compile_matrix::CompileMatrixRequest {
+    type Error = ParseError;
+
+    fn try_from(value: CompileMatrixRequest) -> Result<Self, Self::Error> {
+        let compiler_versions = value
+            .compiler_versions
+            .iter()
+            .map(|version| {
+                Version::from_str(version)
+                    .map_err(|err| anyhow!("Invalid compiler version {version}: {err}"))
+            })
+            .collect::<Result<_, _>>()?;
+        let parse_bytecode =
+            |bytecode: &str| decode_hex(bytecode).map_err(|err| anyhow!("Invalid bytecode: {err}"));
+        let target = match (value.creation_bytecode, value.deployed_bytecode) {
+            (Some(creation_bytecode), _) => {
+                Some(Target::CreationTxInput(parse_bytecode(&creation_bytecode)?))
+            }
+            (None, Some(deployed_bytecode)) => Some(Target::DeployedBytecode(parse_bytecode(
+                &deployed_bytecode,
+            )?)),
+            (None, None) => None,
+        };
+        Ok(Self {
+            compiler_versions,
+            settings: value.settings,
+            target,
+            content: value.content.try_into()?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CompileMatrixResponse {
+    pub message: String,
+    pub status: VerificationStatus,
+    pub cells: Vec<MatrixCell>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct MatrixCell {
+    pub compiler_version: String,
+    /// Index of the settings overrides in the request
+    pub settings_index: usize,
+    /// Keccak256 hashes of the deployed bytecodes by fully qualified contract names
+    pub code_hashes: BTreeMap<String, String>,
+    pub matched_contract: Option<String>,
+    /// Either `full` or `partial`, if some contract matches
+    pub match_type: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<solidity::compile_matrix::MatrixCell> for MatrixCell {
+    fn from(cell: solidity::compile_matrix::MatrixCell) -> Self {
+        Self {
+            compiler_version: cell.compiler_version.to_string(),
+            settings_index: cell.settings_index,
+            code_hashes: cell.code_hashes,
+            matched_contract: cell.matched_contract,
+            match_type: cell.match_type.map(|match_type| {
+                match match_type {
+                    MatchType::Full => "full",
+                    MatchType::Partial => "partial",
+                }
+                .to_string()
+            }),
+            error: cell.error,
+        }
+    }
+}
+
+impl CompileMatrixResponse {
+    fn ok(cells: Vec<solidity::compile_matrix::MatrixCell>) -> Self {
+        Self {
+            message: "OK".to_string(),
+            status: VerificationStatus::Ok,
+            cells: cells.into_iter().map(MatrixCell::from).collect(),
+        }
+    }
+
+    fn err(message: impl Display) -> Self {
+        Self {
+            message: message.to_string(),
+            status: VerificationStatus::Failed,
+            cells: vec![],
+        }
+    }
+}
+
+/// Compiles the sources with every combination of the requested compiler versions
+/// and settings, reporting the resulting code hashes and the contracts matching the bytecode.
+#[instrument(skip(client, params), level = "debug")]
+pub async fn compile_matrix(
+    client: web::Data<SolidityClient>,
+    params: Json<CompileMatrixRequest>,
+) -> Result<Json<CompileMatrixResponse>, actix_web::Error> {
+    let request = match params.into_inner().try_into() {
+        Ok(request) => request,
+        Err(err @ (ParseError::InvalidContent(_) | ParseError::InvalidSourcePath(_))) => {
+            return Err(error::ErrorBadRequest(err))
+        }
+        Err(err @ ParseError::BadRequest(_)) => return Ok(Json(CompileMatrixResponse::err(err))),
+    };
+
+    match solidity::compile_matrix::compile_matrix(client.into_inner(), request).await {
+        Ok(cells) => Ok(Json(CompileMatrixResponse::ok(cells))),
+        Err(
+            err @ (VerificationError::Compilation(_)
+            | VerificationError::NoMatchingContracts(_)
+            | VerificationError::CompilerVersionMismatch(_)
+            | VerificationError::IncompatibleCompilerVersion { .. }
+            | VerificationError::MissingSources(_)
+            | VerificationError::UnsupportedOpcodes { .. }),
+        ) => Ok(Json(CompileMatrixResponse::err(err))),
+        Err(
+            err @ (VerificationError::Initialization(_)
+            | VerificationError::VersionNotFound(_)
+            | VerificationError::LimitExceeded(_)),
+        ) => Err(error::ErrorBadRequest(err)),
+        Err(err @ VerificationError::Internal(_)) => Err(error::ErrorInternalServerError(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::parse::test_serialize_json_ok;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_matrix() {
+        let cells = vec![MatrixCell {
+            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            settings_index: 1,
+            code_hashes: BTreeMap::from([(
+                "A.sol:A".to_string(),
+                "0x2b9e6b7e9e8dd3a5d6e3f8e62d1d2f0c5b8d4f7c0a3e2d1c5b7a9f8e6d4c3b2a".to_string(),
+            )]),
+            matched_contract: Some("A.sol:A".to_string()),
+            match_type: Some("full".to_string()),
+            error: None,
+        }];
+        test_serialize_json_ok(vec![(
+            CompileMatrixResponse {
+                message: "OK".to_string(),
+                status: VerificationStatus::Ok,
+                cells,
+            },
+            json!({
+                "message": "OK",
+                "status": "0",
+                "cells": [{
+                    "compiler_version": "v0.8.17+commit.8df45f5f",
+                    "settings_index": 1,
+                    "code_hashes": {
+                        "A.sol:A": "0x2b9e6b7e9e8dd3a5d6e3f8e62d1d2f0c5b8d4f7c0a3e2d1c5b7a9f8e6d4c3b2a"
+                    },
+                    "matched_contract": "A.sol:A",
+                    "match_type": "full",
+                    "error": null,
+                }],
+            }),
+        )]);
+    }
+}
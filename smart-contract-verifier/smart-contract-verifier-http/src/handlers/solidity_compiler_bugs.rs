@@ -0,0 +1,210 @@
+use super::solidity_standard_json::StandardJson;
+use actix_web::{error, web, web::Json};
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{
+    solidity::{
+        compiler_bugs::{self, AffectingBug, BugReport, CompilerBugs, Impact},
+        standard_json::StandardJsonContent,
+    },
+    VerificationSuccess, Version,
+};
+use std::str::FromStr;
+use tracing::instrument;
+
+#[derive(Debug, Deserialize)]
+pub struct CompilerBugsRequest {
+    /// Compiler version the sources have been compiled with
+    pub compiler_version: String,
+
+    #[serde(flatten)]
+    pub content: StandardJson,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BugImpact {
+    Affected,
+    NotAffected,
+    Unknown,
+}
+
+impl From<Impact> for BugImpact {
+    fn from(value: Impact) -> Self {
+        match value {
+            Impact::Affected => Self::Affected,
+            Impact::NotAffected => Self::NotAffected,
+            Impact::Unknown => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CompilerBug {
+    pub uid: String,
+    pub name: String,
+    pub summary: String,
+    pub link: Option<String>,
+    pub severity: String,
+    pub fixed: Option<String>,
+    /// Is absent if the bugs are requested for the compiler version only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact: Option<BugImpact>,
+}
+
+impl From<compiler_bugs::CompilerBug> for CompilerBug {
+    fn from(value: compiler_bugs::CompilerBug) -> Self {
+        Self {
+            uid: value.uid,
+            name: value.name,
+            summary: value.summary,
+            link: value.link,
+            severity: value.severity,
+            fixed: value.fixed.map(|version| version.to_string()),
+            impact: None,
+        }
+    }
+}
+
+impl From<AffectingBug> for CompilerBug {
+    fn from(value: AffectingBug) -> Self {
+        Self {
+            impact: Some(value.impact.into()),
+            ..value.bug.into()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CompilerBugsResponse {
+    pub bugs: Vec<CompilerBug>,
+    /// The earliest version all bugs possibly impacting the contract are fixed in
+    pub recommended_version: Option<String>,
+}
+
+impl From<BugReport> for CompilerBugsResponse {
+    fn from(value: BugReport) -> Self {
+        Self {
+            bugs: value.bugs.into_iter().map(CompilerBug::from).collect(),
+            recommended_version: value.recommended_version.map(|version| version.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct VersionBugsResponse {
+    pub bugs: Vec<CompilerBug>,
+}
+
+/// Returns known bugs of the compiler version the sources have been compiled with,
+/// and estimates whether the contract is impacted by them.
+#[instrument(skip(compiler_bugs, params), level = "debug")]
+pub async fn compiler_bugs(
+    compiler_bugs: web::Data<CompilerBugs>,
+    params: Json<CompilerBugsRequest>,
+) -> Result<Json<CompilerBugsResponse>, actix_web::Error> {
+    let params = params.into_inner();
+    let compiler_version = Version::from_str(&params.compiler_version)
+        .map_err(|err| error::ErrorBadRequest(format!("Invalid compiler version: {err}")))?;
+    let content = StandardJsonContent::try_from(params.content).map_err(error::ErrorBadRequest)?;
+
+    let report = compiler_bugs.report(compiler_version.version(), &content.input);
+    Ok(Json(report.into()))
+}
+
+/// Returns known bugs of the compiler version. Both long (e.g., "v0.8.13+commit.abaa5c0e")
+/// and short (e.g., "0.8.13") versions are accepted.
+#[instrument(skip(compiler_bugs), level = "debug")]
+pub async fn version_bugs(
+    compiler_bugs: web::Data<CompilerBugs>,
+    version: web::Path<String>,
+) -> Result<Json<VersionBugsResponse>, actix_web::Error> {
+    let version = match Version::from_str(&version) {
+        Ok(version) => version.version().clone(),
+        Err(_) => semver::Version::parse(version.trim_start_matches('v'))
+            .map_err(|err| error::ErrorBadRequest(format!("Invalid compiler version: {err}")))?,
+    };
+
+    let bugs = compiler_bugs.version_bugs(&version);
+    Ok(Json(VersionBugsResponse {
+        bugs: bugs.into_iter().map(CompilerBug::from).collect(),
+    }))
+}
+
+/// Returns known bugs of the compiler which may impact the verified contract,
+/// so that they could be shown as warnings.
+pub fn verification_warnings(
+    compiler_bugs: &CompilerBugs,
+    success: &VerificationSuccess,
+) -> Vec<CompilerBug> {
+    compiler_bugs
+        .report(success.compiler_version.version(), &success.compiler_input)
+        .bugs
+        .into_iter()
+        .filter(|affecting| affecting.impact != Impact::NotAffected)
+        .map(CompilerBug::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::parse::test_serialize_json_ok;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_compiler_bugs() {
+        test_serialize_json_ok(vec![(
+            CompilerBugsResponse {
+                bugs: vec![CompilerBug {
+                    uid: "SOL-2022-4".to_string(),
+                    name: "InlineAssemblyMemorySideEffects".to_string(),
+                    summary: "The Yul optimizer may incorrectly remove memory writes".to_string(),
+                    link: None,
+                    severity: "medium".to_string(),
+                    fixed: Some("0.8.15".to_string()),
+                    impact: Some(BugImpact::NotAffected),
+                }],
+                recommended_version: None,
+            },
+            json!({
+                "bugs": [{
+                    "uid": "SOL-2022-4",
+                    "name": "InlineAssemblyMemorySideEffects",
+                    "summary": "The Yul optimizer may incorrectly remove memory writes",
+                    "link": null,
+                    "severity": "medium",
+                    "fixed": "0.8.15",
+                    "impact": "not_affected",
+                }],
+                "recommended_version": null,
+            }),
+        )]);
+    }
+
+    #[test]
+    fn serialize_version_bugs() {
+        test_serialize_json_ok(vec![(
+            VersionBugsResponse {
+                bugs: vec![CompilerBug {
+                    uid: "SOL-2022-4".to_string(),
+                    name: "InlineAssemblyMemorySideEffects".to_string(),
+                    summary: "The Yul optimizer may incorrectly remove memory writes".to_string(),
+                    link: None,
+                    severity: "medium".to_string(),
+                    fixed: Some("0.8.15".to_string()),
+                    impact: None,
+                }],
+            },
+            json!({
+                "bugs": [{
+                    "uid": "SOL-2022-4",
+                    "name": "InlineAssemblyMemorySideEffects",
+                    "summary": "The Yul optimizer may incorrectly remove memory writes",
+                    "link": null,
+                    "severity": "medium",
+                    "fixed": "0.8.15",
+                }],
+            }),
+        )]);
+    }
+}
@@ -1,8 +1,16 @@
-use crate::{metrics, verification_response::VerificationResponse, DisplayBytes};
+use super::solidity_compiler_bugs;
+use crate::{
+    metrics,
+    verification_response::{VerificationResponse, VerificationResult},
+};
 use actix_web::{error, web, web::Json};
 use ethers_solc::EvmVersion;
 use serde::Deserialize;
-use smart_contract_verifier::{solidity, SolidityClient, VerificationError, Version};
+use smart_contract_verifier::{
+    decode_hex,
+    solidity::{self, compiler_bugs::CompilerBugs},
+    validate_source_path, SolidityClient, VerificationError, Version,
+};
 use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
 use tracing::instrument;
 
@@ -22,24 +30,21 @@ pub struct MultiPartFiles {
     pub evm_version: String,
     pub optimization_runs: Option<usize>,
     pub contract_libraries: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    pub extra_outputs: bool,
 }
 
 impl TryFrom<VerificationRequest> for solidity::multi_part::VerificationRequest {
     type Error = actix_web::Error;
 
     fn try_from(value: VerificationRequest) -> Result<Self, Self::Error> {
-        let deployed_bytecode = DisplayBytes::from_str(&value.deployed_bytecode)
-            .map_err(|err| error::ErrorBadRequest(format!("Invalid deployed bytecode: {err:?}")))?
-            .0;
+        let deployed_bytecode = decode_hex(&value.deployed_bytecode)
+            .map_err(|err| error::ErrorBadRequest(format!("Invalid deployed bytecode: {err}")))?;
         let creation_bytecode = match value.creation_bytecode {
             None => None,
-            Some(creation_bytecode) => Some(
-                DisplayBytes::from_str(&creation_bytecode)
-                    .map_err(|err| {
-                        error::ErrorBadRequest(format!("Invalid creation bytecode: {err:?}"))
-                    })?
-                    .0,
-            ),
+            Some(creation_bytecode) => Some(decode_hex(&creation_bytecode).map_err(|err| {
+                error::ErrorBadRequest(format!("Invalid creation bytecode: {err}"))
+            })?),
         };
         let compiler_version = Version::from_str(&value.compiler_version)
             .map_err(|err| error::ErrorBadRequest(format!("Invalid compiler version: {err}")))?;
@@ -47,6 +52,12 @@ impl TryFrom<VerificationRequest> for solidity::multi_part::VerificationRequest
             deployed_bytecode,
             creation_bytecode,
             compiler_version,
+            auto_select_compiler_version: false,
+            bytecode_mask: vec![],
+            eof_enabled: false,
+            evm_fork: None,
+            normalization: Default::default(),
+            trace: None,
             content: value.content.try_into()?,
         })
     }
@@ -56,6 +67,9 @@ impl TryFrom<MultiPartFiles> for solidity::multi_part::MultiFileContent {
     type Error = actix_web::Error;
 
     fn try_from(value: MultiPartFiles) -> Result<Self, Self::Error> {
+        for path in value.sources.keys() {
+            validate_source_path(&path.to_string_lossy()).map_err(error::ErrorBadRequest)?;
+        }
         let sources: BTreeMap<PathBuf, String> = value
             .sources
             .into_iter()
@@ -73,13 +87,15 @@ impl TryFrom<MultiPartFiles> for solidity::multi_part::MultiFileContent {
             evm_version,
             optimization_runs: value.optimization_runs,
             contract_libraries: value.contract_libraries,
+            extra_outputs: value.extra_outputs,
         })
     }
 }
 
-#[instrument(skip(client, params), level = "debug")]
+#[instrument(skip(client, compiler_bugs, params), level = "debug")]
 pub async fn verify(
     client: web::Data<SolidityClient>,
+    compiler_bugs: Option<web::Data<CompilerBugs>>,
     params: Json<VerificationRequest>,
 ) -> Result<Json<VerificationResponse>, actix_web::Error> {
     let request = params.into_inner().try_into()?;
@@ -87,7 +103,14 @@ pub async fn verify(
     let result = solidity::multi_part::verify(client.into_inner(), request).await;
 
     if let Ok(verification_success) = result {
-        let response = VerificationResponse::ok(verification_success.into());
+        let warnings = compiler_bugs
+            .map(|compiler_bugs| {
+                solidity_compiler_bugs::verification_warnings(&compiler_bugs, &verification_success)
+            })
+            .unwrap_or_default();
+        let mut result = VerificationResult::from(verification_success);
+        result.compiler_bugs = warnings;
+        let response = VerificationResponse::ok(result);
         metrics::count_verify_contract("solidity", &response.status, "multi-part");
         return Ok(Json(response));
     }
@@ -95,11 +118,14 @@ pub async fn verify(
     let err = result.unwrap_err();
     match err {
         VerificationError::Compilation(_)
-        | VerificationError::NoMatchingContracts
-        | VerificationError::CompilerVersionMismatch(_) => Ok(Json(VerificationResponse::err(err))),
-        VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
-            Err(error::ErrorBadRequest(err))
-        }
+        | VerificationError::NoMatchingContracts(_)
+        | VerificationError::CompilerVersionMismatch(_)
+        | VerificationError::IncompatibleCompilerVersion { .. }
+        | VerificationError::MissingSources(_)
+        | VerificationError::UnsupportedOpcodes { .. } => Ok(Json(VerificationResponse::err(err))),
+        VerificationError::Initialization(_)
+        | VerificationError::VersionNotFound(_)
+        | VerificationError::LimitExceeded(_) => Err(error::ErrorBadRequest(err)),
         VerificationError::Internal(_) => Err(error::ErrorInternalServerError(err)),
     }
 }
@@ -140,6 +166,7 @@ mod tests {
                         evm_version: format!("{}", EvmVersion::London),
                         optimization_runs: Some(200),
                         contract_libraries: None,
+                        extra_outputs: false,
                     },
                 },
             ),
@@ -176,6 +203,7 @@ mod tests {
                             "Lib.sol".into(),
                             "0x1234567890123456789012345678901234567890".into(),
                         )])),
+                        extra_outputs: false,
                     },
                 },
             ),
@@ -190,6 +218,7 @@ mod tests {
             evm_version: "default".to_string(),
             optimization_runs: None,
             contract_libraries: None,
+            extra_outputs: false,
         };
         let content = solidity::multi_part::MultiFileContent::try_from(multi_part)
             .expect("Structure is valid");
@@ -198,4 +227,17 @@ mod tests {
             "'default' should result in `None`"
         )
     }
+
+    #[test]
+    fn path_traversal_is_rejected() {
+        let multi_part = MultiPartFiles {
+            sources: sources(&[("contracts/../../A.sol", "A")]),
+            evm_version: "default".to_string(),
+            optimization_runs: None,
+            contract_libraries: None,
+            extra_outputs: false,
+        };
+        solidity::multi_part::MultiFileContent::try_from(multi_part)
+            .expect_err("path traversal should be rejected");
+    }
 }
@@ -1,9 +1,17 @@
-use crate::{metrics, verification_response::VerificationResponse, DisplayBytes};
+use super::solidity_compiler_bugs;
+use crate::{
+    metrics,
+    verification_response::{VerificationResponse, VerificationResult},
+};
 use actix_web::{error, web, web::Json};
 use anyhow::anyhow;
 use ethers_solc::CompilerInput;
 use serde::Deserialize;
-use smart_contract_verifier::{solidity, SolidityClient, VerificationError, Version};
+use smart_contract_verifier::{
+    decode_hex,
+    solidity::{self, compiler_bugs::CompilerBugs},
+    validate_source_path, SolidityClient, SourcePathError, VerificationError, Version,
+};
 use std::str::FromStr;
 use thiserror::Error;
 use tracing::instrument;
@@ -20,7 +28,9 @@ pub struct VerificationRequest {
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct StandardJson {
-    input: String,
+    pub input: String,
+    #[serde(default)]
+    pub extra_outputs: bool,
 }
 
 #[derive(Error, Debug)]
@@ -28,6 +38,8 @@ pub enum ParseError {
     #[error("content is not valid standard json: {0}")]
     InvalidContent(#[from] serde_json::Error),
     #[error("{0}")]
+    InvalidSourcePath(#[from] SourcePathError),
+    #[error("{0}")]
     BadRequest(#[from] anyhow::Error),
 }
 
@@ -35,15 +47,13 @@ impl TryFrom<VerificationRequest> for solidity::standard_json::VerificationReque
     type Error = ParseError;
 
     fn try_from(value: VerificationRequest) -> Result<Self, Self::Error> {
-        let deployed_bytecode = DisplayBytes::from_str(&value.deployed_bytecode)
-            .map_err(|err| anyhow!("Invalid deployed bytecode: {:?}", err))?
-            .0;
+        let deployed_bytecode = decode_hex(&value.deployed_bytecode)
+            .map_err(|err| anyhow!("Invalid deployed bytecode: {err}"))?;
         let creation_bytecode = match value.creation_bytecode {
             None => None,
             Some(creation_bytecode) => Some(
-                DisplayBytes::from_str(&creation_bytecode)
-                    .map_err(|err| anyhow!("Invalid creation bytecode: {:?}", err))?
-                    .0,
+                decode_hex(&creation_bytecode)
+                    .map_err(|err| anyhow!("Invalid creation bytecode: {err}"))?,
             ),
         };
         let compiler_version = Version::from_str(&value.compiler_version)
@@ -52,6 +62,13 @@ impl TryFrom<VerificationRequest> for solidity::standard_json::VerificationReque
             deployed_bytecode,
             creation_bytecode,
             compiler_version,
+            auto_select_compiler_version: false,
+            bytecode_mask: vec![],
+            eof_enabled: false,
+            evm_fork: None,
+            normalization: Default::default(),
+            retry_with_flipped_via_ir: false,
+            trace: None,
             content: value.content.try_into()?,
         })
     }
@@ -62,21 +79,30 @@ impl TryFrom<StandardJson> for solidity::standard_json::StandardJsonContent {
 
     fn try_from(value: StandardJson) -> Result<Self, Self::Error> {
         let input: CompilerInput = serde_json::from_str(&value.input)?;
+        for path in input.sources.keys() {
+            validate_source_path(&path.to_string_lossy())?;
+        }
 
-        Ok(Self { input })
+        Ok(Self {
+            input,
+            extra_outputs: value.extra_outputs,
+        })
     }
 }
 
-#[instrument(skip(client, params), level = "debug")]
+#[instrument(skip(client, compiler_bugs, params), level = "debug")]
 pub async fn verify(
     client: web::Data<SolidityClient>,
+    compiler_bugs: Option<web::Data<CompilerBugs>>,
     params: Json<VerificationRequest>,
 ) -> Result<Json<VerificationResponse>, actix_web::Error> {
     let request = {
         let request: Result<_, ParseError> = params.into_inner().try_into();
         if let Err(err) = request {
             match err {
-                ParseError::InvalidContent(_) => return Err(error::ErrorBadRequest(err)),
+                ParseError::InvalidContent(_) | ParseError::InvalidSourcePath(_) => {
+                    return Err(error::ErrorBadRequest(err))
+                }
                 ParseError::BadRequest(_) => return Ok(Json(VerificationResponse::err(err))),
             }
         }
@@ -86,7 +112,14 @@ pub async fn verify(
     let result = solidity::standard_json::verify(client.into_inner(), request).await;
 
     if let Ok(verification_success) = result {
-        let response = VerificationResponse::ok(verification_success.into());
+        let warnings = compiler_bugs
+            .map(|compiler_bugs| {
+                solidity_compiler_bugs::verification_warnings(&compiler_bugs, &verification_success)
+            })
+            .unwrap_or_default();
+        let mut result = VerificationResult::from(verification_success);
+        result.compiler_bugs = warnings;
+        let response = VerificationResponse::ok(result);
         metrics::count_verify_contract("solidity", &response.status, "json");
         return Ok(Json(response));
     }
@@ -94,11 +127,14 @@ pub async fn verify(
     let err = result.unwrap_err();
     match err {
         VerificationError::Compilation(_)
-        | VerificationError::NoMatchingContracts
-        | VerificationError::CompilerVersionMismatch(_) => Ok(Json(VerificationResponse::err(err))),
-        VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
-            Err(error::ErrorBadRequest(err))
-        }
+        | VerificationError::NoMatchingContracts(_)
+        | VerificationError::CompilerVersionMismatch(_)
+        | VerificationError::IncompatibleCompilerVersion { .. }
+        | VerificationError::MissingSources(_)
+        | VerificationError::UnsupportedOpcodes { .. } => Ok(Json(VerificationResponse::err(err))),
+        VerificationError::Initialization(_)
+        | VerificationError::VersionNotFound(_)
+        | VerificationError::LimitExceeded(_) => Err(error::ErrorBadRequest(err)),
         VerificationError::Internal(_) => Err(error::ErrorInternalServerError(err)),
     }
 }
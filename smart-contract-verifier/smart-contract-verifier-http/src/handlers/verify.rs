@@ -0,0 +1,194 @@
+use super::{solidity_multi_part, solidity_standard_json, vyper_multi_part};
+use crate::verification_response::VerificationResponse;
+use actix_web::{error, web, web::Json};
+use serde::Deserialize;
+use smart_contract_verifier::{SolidityClient, VyperClient};
+use tracing::instrument;
+
+/// Verification request for any of the supported languages.
+/// The language is specified explicitly, so that all of them
+/// could be processed by the same route.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "language", content = "payload", rename_all = "lowercase")]
+pub enum VerificationRequest {
+    Solidity(SolidityPayload),
+    Vyper(vyper_multi_part::VerificationRequest),
+    /// Yul sources are compiled by solc, thus, only multi-part files are accepted.
+    /// All the sources must have the `.yul` extension (see [`check_yul_sources`])
+    Yul(solidity_multi_part::VerificationRequest),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "input_type", rename_all = "kebab-case")]
+pub enum SolidityPayload {
+    MultipleFiles(solidity_multi_part::VerificationRequest),
+    StandardJson(solidity_standard_json::VerificationRequest),
+}
+
+#[instrument(skip(solidity_client, vyper_client, params), level = "debug")]
+pub async fn verify(
+    solidity_client: Option<web::Data<SolidityClient>>,
+    vyper_client: Option<web::Data<VyperClient>>,
+    params: Json<VerificationRequest>,
+) -> Result<Json<VerificationResponse>, actix_web::Error> {
+    match params.into_inner() {
+        VerificationRequest::Solidity(SolidityPayload::MultipleFiles(request)) => {
+            let client = enabled(solidity_client, "solidity")?;
+            solidity_multi_part::verify(client, Json(request)).await
+        }
+        VerificationRequest::Yul(request) => {
+            let client = enabled(solidity_client, "solidity")?;
+            check_yul_sources(&request.content)?;
+            solidity_multi_part::verify(client, Json(request)).await
+        }
+        VerificationRequest::Solidity(SolidityPayload::StandardJson(request)) => {
+            let client = enabled(solidity_client, "solidity")?;
+            solidity_standard_json::verify(client, Json(request)).await
+        }
+        VerificationRequest::Vyper(request) => {
+            let client = enabled(vyper_client, "vyper")?;
+            vyper_multi_part::verify(client, Json(request)).await
+        }
+    }
+}
+
+/// The language of multi-part sources is chosen by their extensions, so Yul requests
+/// with other sources would be compiled (and verified) as Solidity ones.
+fn check_yul_sources(
+    content: &solidity_multi_part::MultiPartFiles,
+) -> Result<(), actix_web::Error> {
+    let not_yul: Vec<_> = content
+        .sources
+        .keys()
+        .filter(|path| {
+            path.extension()
+                .map_or(true, |extension| extension != "yul")
+        })
+        .map(|path| path.to_string_lossy())
+        .collect();
+    if not_yul.is_empty() {
+        Ok(())
+    } else {
+        Err(error::ErrorBadRequest(format!(
+            "Yul sources must have the .yul extension: {}",
+            not_yul.join(", ")
+        )))
+    }
+}
+
+/// Clients are registered only for enabled languages
+fn enabled<T>(
+    client: Option<web::Data<T>>,
+    language: &str,
+) -> Result<web::Data<T>, actix_web::Error> {
+    client.ok_or_else(|| error::ErrorNotFound(format!("{language} verification is disabled")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_verification_request() {
+        let solidity = r#"{
+            "language": "solidity",
+            "payload": {
+                "input_type": "multiple-files",
+                "deployed_bytecode": "0x6001",
+                "creation_bytecode": "0x6001",
+                "compiler_version": "0.8.3",
+                "sources": {
+                    "source.sol": "pragma"
+                },
+                "evm_version": "london"
+            }
+        }"#;
+        let request: VerificationRequest = serde_json::from_str(solidity).expect("valid json");
+        assert!(
+            matches!(
+                request,
+                VerificationRequest::Solidity(SolidityPayload::MultipleFiles(_))
+            ),
+            "expected solidity multi-part request, got {request:?}"
+        );
+
+        let standard_json = r#"{
+            "language": "solidity",
+            "payload": {
+                "input_type": "standard-json",
+                "deployed_bytecode": "0x6001",
+                "compiler_version": "0.8.3",
+                "input": "{}"
+            }
+        }"#;
+        let request: VerificationRequest = serde_json::from_str(standard_json).expect("valid json");
+        assert!(
+            matches!(
+                request,
+                VerificationRequest::Solidity(SolidityPayload::StandardJson(_))
+            ),
+            "expected solidity standard-json request, got {request:?}"
+        );
+
+        let vyper = r#"{
+            "language": "vyper",
+            "payload": {
+                "deployed_bytecode": "0x6001",
+                "compiler_version": "0.3.6",
+                "sources": {
+                    "source.vy": "x: public(uint256)"
+                }
+            }
+        }"#;
+        let request: VerificationRequest = serde_json::from_str(vyper).expect("valid json");
+        assert!(
+            matches!(request, VerificationRequest::Vyper(_)),
+            "expected vyper request, got {request:?}"
+        );
+
+        let yul = r#"{
+            "language": "yul",
+            "payload": {
+                "deployed_bytecode": "0x6001",
+                "compiler_version": "0.8.3",
+                "sources": {
+                    "source.yul": "object \"A\" {}"
+                },
+                "evm_version": "london"
+            }
+        }"#;
+        let request: VerificationRequest = serde_json::from_str(yul).expect("valid json");
+        match request {
+            VerificationRequest::Yul(request) => check_yul_sources(&request.content)
+                .expect("sources with .yul extension should be accepted"),
+            request => panic!("expected yul request, got {request:?}"),
+        }
+
+        let unknown = r#"{"language": "fe", "payload": {}}"#;
+        assert!(
+            serde_json::from_str::<VerificationRequest>(unknown).is_err(),
+            "unknown languages should be rejected"
+        );
+    }
+
+    #[test]
+    fn yul_requests_with_solidity_sources_are_rejected() {
+        let content = solidity_multi_part::MultiPartFiles {
+            sources: [
+                ("A.yul".into(), "object \"A\" {}".to_string()),
+                ("B.sol".into(), "contract B {}".to_string()),
+                ("C".into(), "object \"C\" {}".to_string()),
+            ]
+            .into(),
+            evm_version: "london".to_string(),
+            optimization_runs: None,
+            contract_libraries: None,
+            extra_outputs: false,
+        };
+        let err = check_yul_sources(&content).expect_err("non-yul sources should be rejected");
+        assert_eq!(
+            "Yul sources must have the .yul extension: B.sol, C",
+            err.to_string()
+        );
+    }
+}
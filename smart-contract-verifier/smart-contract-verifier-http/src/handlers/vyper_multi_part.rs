@@ -1,8 +1,10 @@
-use crate::{metrics, verification_response::VerificationResponse, DisplayBytes};
+use crate::{metrics, verification_response::VerificationResponse};
 use actix_web::{error, web, web::Json};
 use ethers_solc::EvmVersion;
 use serde::Deserialize;
-use smart_contract_verifier::{vyper, VerificationError, Version, VyperClient};
+use smart_contract_verifier::{
+    decode_hex, validate_source_path, vyper, VerificationError, Version, VyperClient,
+};
 use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
 use tracing::instrument;
 
@@ -20,24 +22,21 @@ pub struct VerificationRequest {
 pub struct MultiPartFiles {
     pub sources: BTreeMap<PathBuf, String>,
     pub evm_version: Option<String>,
+    #[serde(default)]
+    pub extra_outputs: bool,
 }
 
 impl TryFrom<VerificationRequest> for vyper::multi_part::VerificationRequest {
     type Error = actix_web::Error;
 
     fn try_from(value: VerificationRequest) -> Result<Self, Self::Error> {
-        let deployed_bytecode = DisplayBytes::from_str(&value.deployed_bytecode)
-            .map_err(|err| error::ErrorBadRequest(format!("Invalid deployed bytecode: {err}")))?
-            .0;
+        let deployed_bytecode = decode_hex(&value.deployed_bytecode)
+            .map_err(|err| error::ErrorBadRequest(format!("Invalid deployed bytecode: {err}")))?;
         let creation_bytecode = match value.creation_bytecode {
             None => None,
-            Some(creation_bytecode) => Some(
-                DisplayBytes::from_str(&creation_bytecode)
-                    .map_err(|err| {
-                        error::ErrorBadRequest(format!("Invalid creation bytecode: {err:?}"))
-                    })?
-                    .0,
-            ),
+            Some(creation_bytecode) => Some(decode_hex(&creation_bytecode).map_err(|err| {
+                error::ErrorBadRequest(format!("Invalid creation bytecode: {err}"))
+            })?),
         };
         let compiler_version = Version::from_str(&value.compiler_version)
             .map_err(|err| error::ErrorBadRequest(format!("Invalid compiler version: {err}")))?;
@@ -54,6 +53,9 @@ impl TryFrom<MultiPartFiles> for vyper::multi_part::MultiFileContent {
     type Error = actix_web::Error;
 
     fn try_from(value: MultiPartFiles) -> Result<Self, Self::Error> {
+        for path in value.sources.keys() {
+            validate_source_path(&path.to_string_lossy()).map_err(error::ErrorBadRequest)?;
+        }
         let sources: BTreeMap<PathBuf, String> = value
             .sources
             .into_iter()
@@ -70,6 +72,7 @@ impl TryFrom<MultiPartFiles> for vyper::multi_part::MultiFileContent {
         Ok(Self {
             sources,
             evm_version,
+            extra_outputs: value.extra_outputs,
         })
     }
 }
@@ -92,11 +95,14 @@ pub async fn verify(
     let err = result.unwrap_err();
     match err {
         VerificationError::Compilation(_)
-        | VerificationError::NoMatchingContracts
-        | VerificationError::CompilerVersionMismatch(_) => Ok(Json(VerificationResponse::err(err))),
-        VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
-            Err(error::ErrorBadRequest(err))
-        }
+        | VerificationError::NoMatchingContracts(_)
+        | VerificationError::CompilerVersionMismatch(_)
+        | VerificationError::IncompatibleCompilerVersion { .. }
+        | VerificationError::MissingSources(_)
+        | VerificationError::UnsupportedOpcodes { .. } => Ok(Json(VerificationResponse::err(err))),
+        VerificationError::Initialization(_)
+        | VerificationError::VersionNotFound(_)
+        | VerificationError::LimitExceeded(_) => Err(error::ErrorBadRequest(err)),
         VerificationError::Internal(_) => Err(error::ErrorInternalServerError(err)),
     }
 }
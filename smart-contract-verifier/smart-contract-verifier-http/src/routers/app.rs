@@ -2,9 +2,13 @@ use super::{
     router::{configure_router, Router},
     solidity::SolidityRouter,
     sourcify::SourcifyRouter,
+    tools::ToolsRouter,
     vyper::VyperRouter,
 };
-use crate::{handlers::status, settings::Settings};
+use crate::{
+    handlers::{status, verify},
+    settings::Settings,
+};
 use actix_web::web;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
@@ -13,6 +17,7 @@ pub struct AppRouter {
     solidity: Option<SolidityRouter>,
     vyper: Option<VyperRouter>,
     sourcify: Option<SourcifyRouter>,
+    tools: ToolsRouter,
 }
 
 impl AppRouter {
@@ -41,12 +46,27 @@ impl AppRouter {
                 Some(SourcifyRouter::new(settings.sourcify, settings.extensions.sourcify).await?)
             }
         };
+        let tools = ToolsRouter::new(settings.decompiler);
         Ok(Self {
             solidity,
             vyper,
             sourcify,
+            tools,
         })
     }
+
+    /// Route accepting requests for any of the languages. Language specific
+    /// routes are kept as aliases for backward compatibility.
+    fn verify_resource(&self) -> actix_web::Resource {
+        let mut resource = web::resource("/verify").route(web::post().to(verify::verify));
+        if let Some(solidity) = &self.solidity {
+            resource = resource.app_data(solidity.client());
+        }
+        if let Some(vyper) = &self.vyper {
+            resource = resource.app_data(vyper.client());
+        }
+        resource
+    }
 }
 
 impl Router for AppRouter {
@@ -55,9 +75,11 @@ impl Router for AppRouter {
             .route("/health", web::get().to(status::status))
             .service(
                 web::scope("/api/v1")
+                    .service(self.verify_resource())
                     .service(web::scope("/solidity").configure(configure_router(&self.solidity)))
                     .service(web::scope("/vyper").configure(configure_router(&self.vyper)))
-                    .service(web::scope("/sourcify").configure(configure_router(&self.sourcify))),
+                    .service(web::scope("/sourcify").configure(configure_router(&self.sourcify)))
+                    .service(web::scope("/tools").configure(configure_router(&self.tools))),
             );
     }
 }
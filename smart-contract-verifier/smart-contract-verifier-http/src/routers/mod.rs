@@ -2,6 +2,7 @@ mod app;
 mod router;
 mod solidity;
 mod sourcify;
+mod tools;
 mod vyper;
 
 pub use app::AppRouter;
@@ -1,18 +1,24 @@
 use super::router::Router;
 use crate::{
-    handlers::{solidity_multi_part, solidity_standard_json, solidity_version_list},
+    handlers::{
+        solidity_compile, solidity_compile_matrix, solidity_compiler_bugs, solidity_multi_part,
+        solidity_standard_json, solidity_version_list,
+    },
     settings::{Extensions, FetcherSettings, S3FetcherSettings, SoliditySettings},
 };
 use actix_web::web;
+use anyhow::Context;
 use s3::{creds::Credentials, Bucket, Region};
 use smart_contract_verifier::{
-    Compilers, Fetcher, ListFetcher, S3Fetcher, SolcValidator, SolidityClient, SolidityCompiler,
+    solidity::compiler_bugs::CompilerBugs, Compilers, Fetcher, ListFetcher, S3Fetcher,
+    SolcValidator, SolidityClient, SolidityCompiler,
 };
 use std::{str::FromStr, sync::Arc};
 use tokio::sync::Semaphore;
 
 pub struct SolidityRouter {
     client: web::Data<SolidityClient>,
+    compiler_bugs: Option<web::Data<CompilerBugs>>,
 }
 
 fn new_region(region: Option<String>, endpoint: Option<String>) -> Option<Region> {
@@ -97,12 +103,32 @@ impl SolidityRouter {
                 .with_middleware(sig_provider_extension::SigProvider::new(sig_provider).await?);
         }
 
+        let compiler_bugs = if settings.compiler_bugs.enabled {
+            let compiler_bugs = CompilerBugs::fetch(
+                settings.compiler_bugs.bugs_url,
+                settings.compiler_bugs.bugs_by_version_url,
+                Some(settings.compiler_bugs.refresh_schedule),
+            )
+            .await
+            .context("failed to fetch solidity compiler bugs")?;
+            Some(web::Data::from(compiler_bugs))
+        } else {
+            None
+        };
+
         Ok(Self {
             client: web::Data::new(client),
+            compiler_bugs,
         })
     }
 }
 
+impl SolidityRouter {
+    pub fn client(&self) -> web::Data<SolidityClient> {
+        self.client.clone()
+    }
+}
+
 impl Router for SolidityRouter {
     fn register_routes(&self, service_config: &mut web::ServiceConfig) {
         service_config
@@ -118,9 +144,27 @@ impl Router for SolidityRouter {
                         web::post().to(solidity_standard_json::verify),
                     ),
             )
+            .route("/compile", web::post().to(solidity_compile::compile))
+            .route(
+                "/compile-matrix",
+                web::post().to(solidity_compile_matrix::compile_matrix),
+            )
             .route(
                 "/versions",
                 web::get().to(solidity_version_list::get_version_list),
             );
+        // Verification handlers use compiler bugs as well to warn about them
+        if let Some(compiler_bugs) = &self.compiler_bugs {
+            service_config
+                .app_data(compiler_bugs.clone())
+                .route(
+                    "/compiler-bugs",
+                    web::post().to(solidity_compiler_bugs::compiler_bugs),
+                )
+                .route(
+                    "/compiler-bugs/{version}",
+                    web::get().to(solidity_compiler_bugs::version_bugs),
+                );
+        }
     }
 }
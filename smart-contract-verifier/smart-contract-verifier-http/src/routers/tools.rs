@@ -0,0 +1,43 @@
+use super::router::Router;
+use crate::{
+    handlers::{bytecode_stats, decompile},
+    settings::DecompilerSettings,
+};
+use actix_web::web;
+use smart_contract_verifier::{CachedDecompiler, Decompiler, HeimdallDecompiler};
+use std::{sync::Arc, time::Duration};
+
+pub struct ToolsRouter {
+    decompiler: Option<web::Data<dyn Decompiler>>,
+}
+
+impl ToolsRouter {
+    pub fn new(decompiler_settings: DecompilerSettings) -> Self {
+        let decompiler = decompiler_settings.enabled.then(|| {
+            let heimdall = HeimdallDecompiler::new(
+                decompiler_settings.heimdall_path,
+                Duration::from_secs(decompiler_settings.timeout),
+            );
+            let decompiler: Arc<dyn Decompiler> = Arc::new(CachedDecompiler::new(
+                heimdall,
+                decompiler_settings.cache_size,
+            ));
+            web::Data::from(decompiler)
+        });
+        Self { decompiler }
+    }
+}
+
+impl Router for ToolsRouter {
+    fn register_routes(&self, service_config: &mut web::ServiceConfig) {
+        service_config.route(
+            "/bytecode-stats",
+            web::post().to(bytecode_stats::bytecode_stats),
+        );
+        if let Some(decompiler) = &self.decompiler {
+            service_config
+                .app_data(decompiler.clone())
+                .route("/decompile", web::post().to(decompile::decompile));
+        }
+    }
+}
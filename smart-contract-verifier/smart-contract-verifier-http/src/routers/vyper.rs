@@ -55,6 +55,12 @@ impl VyperRouter {
     }
 }
 
+impl VyperRouter {
+    pub fn client(&self) -> web::Data<VyperClient> {
+        self.client.clone()
+    }
+}
+
 impl Router for VyperRouter {
     fn register_routes(&self, service_config: &mut web::ServiceConfig) {
         service_config
@@ -4,6 +4,7 @@ use cron::Schedule;
 use serde::{de, Deserialize};
 use serde_with::{serde_as, DisplayFromStr};
 use smart_contract_verifier::{
+    DEFAULT_SOLIDITY_BUGS_BY_VERSION_LIST, DEFAULT_SOLIDITY_BUGS_LIST,
     DEFAULT_SOLIDITY_COMPILER_LIST, DEFAULT_SOURCIFY_HOST, DEFAULT_VYPER_COMPILER_LIST,
 };
 use std::{
@@ -39,6 +40,7 @@ pub struct Settings {
     pub jaeger: JaegerSettings,
     pub compilers: CompilersSettings,
     pub extensions: ExtensionsSettings,
+    pub decompiler: DecompilerSettings,
 
     // Is required as we deny unknown fields, but allow users provide
     // path to config through PREFIX__CONFIG env variable. If removed,
@@ -69,6 +71,7 @@ pub struct SoliditySettings {
     #[serde_as(as = "DisplayFromStr")]
     pub refresh_versions_schedule: Schedule,
     pub fetcher: FetcherSettings,
+    pub compiler_bugs: CompilerBugsSettings,
 }
 
 impl Default for SoliditySettings {
@@ -80,6 +83,32 @@ impl Default for SoliditySettings {
             compilers_dir: default_dir,
             refresh_versions_schedule: Schedule::from_str("0 0 * * * * *").unwrap(), // every hour
             fetcher: Default::default(),
+            compiler_bugs: Default::default(),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompilerBugsSettings {
+    pub enabled: bool,
+    /// List of known compiler bugs in the format of solidity `docs/bugs.json`
+    pub bugs_url: Url,
+    /// Names of the bugs by compiler versions in the format of solidity `docs/bugs_by_version.json`
+    pub bugs_by_version_url: Url,
+    #[serde_as(as = "DisplayFromStr")]
+    pub refresh_schedule: Schedule,
+}
+
+impl Default for CompilerBugsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bugs_url: Url::try_from(DEFAULT_SOLIDITY_BUGS_LIST).expect("valid url"),
+            bugs_by_version_url: Url::try_from(DEFAULT_SOLIDITY_BUGS_BY_VERSION_LIST)
+                .expect("valid url"),
+            refresh_schedule: Schedule::from_str("0 0 0 * * * *").unwrap(), // every day
         }
     }
 }
@@ -170,6 +199,29 @@ impl Default for SourcifySettings {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DecompilerSettings {
+    pub enabled: bool,
+    /// Path to the heimdall-rs binary
+    pub heimdall_path: PathBuf,
+    /// The maximum period (in seconds) a single decompilation may take
+    pub timeout: u64,
+    /// Number of the most recently decompiled bytecodes the results are kept for
+    pub cache_size: usize,
+}
+
+impl Default for DecompilerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            heimdall_path: PathBuf::from("heimdall"),
+            timeout: 30,
+            cache_size: 1000,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MetricsSettings {
@@ -1,6 +1,6 @@
-use crate::DisplayBytes;
+use crate::{handlers::solidity_compiler_bugs::CompilerBug, DisplayBytes};
 use serde::{Deserialize, Serialize};
-use smart_contract_verifier::{SourcifySuccess, VerificationSuccess};
+use smart_contract_verifier::{address_format, SourcifySuccess, VerificationSuccess};
 use std::{collections::BTreeMap, fmt::Display, sync::Arc};
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -48,12 +48,18 @@ pub struct VerificationResult {
     pub local_creation_input_parts: Option<Vec<BytecodePart>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub local_deployed_bytecode_parts: Option<Vec<BytecodePart>>,
+
+    /// Known bugs of the compiler version which may impact the contract,
+    /// to be shown as warnings. Is empty if compiler bugs are not enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compiler_bugs: Vec<CompilerBug>,
 }
 
 impl From<VerificationSuccess> for VerificationResult {
     fn from(verification_success: VerificationSuccess) -> Self {
         let compiler_input = verification_success.compiler_input;
         let compiler_settings = serde_json::to_string(&compiler_input.settings).unwrap();
+        let compiler_settings = address_format::format_settings_libraries(&compiler_settings, None);
         VerificationResult {
             file_name: verification_success.file_path,
             contract_name: verification_success.contract_name,
@@ -72,6 +78,7 @@ impl From<VerificationSuccess> for VerificationResult {
                 .libs
                 .into_iter()
                 .flat_map(|(_path, libs)| libs)
+                .map(format_library)
                 .collect(),
             abi: verification_success.abi.as_ref().map(|abi| {
                 serde_json::to_string(abi)
@@ -105,6 +112,7 @@ impl From<VerificationSuccess> for VerificationResult {
                     .map(|part| part.into())
                     .collect(),
             ),
+            compiler_bugs: vec![],
         }
     }
 }
@@ -121,18 +129,32 @@ impl From<SourcifySuccess> for VerificationResult {
                 .map(DisplayBytes::from),
             optimization: sourcify_success.optimization,
             optimization_runs: sourcify_success.optimization_runs,
-            contract_libraries: sourcify_success.contract_libraries,
+            contract_libraries: sourcify_success
+                .contract_libraries
+                .into_iter()
+                .map(format_library)
+                .collect(),
             abi: Some(sourcify_success.abi),
             sources: sourcify_success.sources,
-            compiler_settings: sourcify_success.compiler_settings,
+            compiler_settings: address_format::format_settings_libraries(
+                &sourcify_success.compiler_settings,
+                None,
+            ),
 
             // We have no notion of bytecode parts for Sourcify verification
             local_creation_input_parts: None,
             local_deployed_bytecode_parts: None,
+            compiler_bugs: vec![],
         }
     }
 }
 
+/// Checksums the library address, if it is a valid one.
+fn format_library((name, address): (String, String)) -> (String, String) {
+    let address = address_format::format_address(&address, None).unwrap_or(address);
+    (name, address)
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub enum VerificationStatus {
     #[serde(rename = "0")]
@@ -199,6 +221,7 @@ mod tests {
                         },
                     ]),
                     local_deployed_bytecode_parts: Some(vec![]),
+                    compiler_bugs: vec![],
                 }),
                 json!({
                     "message": "OK",
@@ -0,0 +1,237 @@
+//! Attestations of the reproducible builds for the fully matched contracts.
+//!
+//! An attestation is an [in-toto statement](https://github.com/in-toto/attestation)
+//! with [SLSA provenance](https://slsa.dev/provenance/v0.2) predicate. It binds the source
+//! files, the compiler version and binary, and the compiler settings to the hash of the
+//! deployed bytecode they result in, so that the teams could keep supply-chain evidence
+//! of what is deployed on chain. Partial matches do not reproduce the bytecode exactly,
+//! thus are not attested.
+
+use crate::proto::{
+    source::{MatchType, SourceType},
+    VerifyResponse,
+};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use serde::Serialize;
+use sha2::{Digest as _, Sha256};
+use sha3::Keccak256;
+use std::{collections::BTreeMap, str::FromStr};
+
+const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v0.1";
+const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v0.2";
+const BUILDER_ID: &str = "https://github.com/blockscout/blockscout-rs/smart-contract-verifier";
+const BUILD_TYPE: &str =
+    "https://github.com/blockscout/blockscout-rs/smart-contract-verifier/compilation@v1";
+
+/// Digests by the algorithm names, hex encoded without the prefix.
+type Digest = BTreeMap<&'static str, String>;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Statement<'a> {
+    #[serde(rename = "_type")]
+    statement_type: &'static str,
+    subject: Vec<Subject>,
+    predicate_type: &'static str,
+    predicate: Provenance<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct Subject {
+    name: String,
+    digest: Digest,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Provenance<'a> {
+    builder: Builder,
+    build_type: &'static str,
+    invocation: Invocation<'a>,
+    metadata: Metadata,
+    materials: Vec<Material>,
+}
+
+#[derive(Debug, Serialize)]
+struct Builder {
+    id: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Invocation<'a> {
+    parameters: Parameters<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Parameters<'a> {
+    file_name: &'a str,
+    contract_name: &'a str,
+    compiler_version: &'a str,
+    compiler_settings: serde_json::Value,
+    /// sha256 of the JSON encoded map from source file names to their contents
+    source_tree_hash: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    build_finished_on: String,
+    reproducible: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Material {
+    uri: String,
+    digest: Digest,
+}
+
+/// Attaches the attestation to the successful response of a full match.
+/// `compiler_hash` is sha256 of the compiler binary, if it is available.
+pub fn attest(response: &mut VerifyResponse, compiler_hash: Option<&[u8]>) {
+    let (source, extra_data) = match (&response.source, &mut response.extra_data) {
+        (Some(source), Some(extra_data)) if source.match_type() == MatchType::Full => {
+            (source, extra_data)
+        }
+        _ => return,
+    };
+    let deployed_bytecode = match extra_data
+        .local_deployed_bytecode_parts
+        .iter()
+        .map(|part| DisplayBytes::from_str(&part.data).map(|bytes| bytes.0))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(parts) if !parts.is_empty() => parts.concat(),
+        _ => return,
+    };
+
+    let compiler = match source.source_type() {
+        SourceType::Vyper => "vyper",
+        SourceType::Huff => "huffc",
+        _ => "solc",
+    };
+    let compiler_digest = compiler_hash
+        .map(|hash| Digest::from([("sha256", hex(hash))]))
+        .unwrap_or_default();
+    let mut materials = vec![Material {
+        uri: format!("pkg:generic/{compiler}@{}", source.compiler_version),
+        digest: compiler_digest,
+    }];
+    materials.extend(source.source_files.iter().map(|(name, content)| Material {
+        uri: name.clone(),
+        digest: Digest::from([("sha256", hex(&Sha256::digest(content)))]),
+    }));
+
+    let sources = serde_json::to_string(&source.source_files)
+        .expect("map of strings serialization never fails");
+    let statement = Statement {
+        statement_type: STATEMENT_TYPE,
+        subject: vec![Subject {
+            name: format!("{}:{}", source.file_name, source.contract_name),
+            digest: Digest::from([
+                ("sha256", hex(&Sha256::digest(&deployed_bytecode))),
+                ("keccak256", hex(&Keccak256::digest(&deployed_bytecode))),
+            ]),
+        }],
+        predicate_type: PREDICATE_TYPE,
+        predicate: Provenance {
+            builder: Builder { id: BUILDER_ID },
+            build_type: BUILD_TYPE,
+            invocation: Invocation {
+                parameters: Parameters {
+                    file_name: &source.file_name,
+                    contract_name: &source.contract_name,
+                    compiler_version: &source.compiler_version,
+                    compiler_settings: serde_json::from_str(&source.compiler_settings)
+                        .unwrap_or_else(|_| source.compiler_settings.clone().into()),
+                    source_tree_hash: hex(&Sha256::digest(sources)),
+                },
+            },
+            metadata: Metadata {
+                build_finished_on: chrono::Utc::now()
+                    .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                reproducible: true,
+            },
+            materials,
+        },
+    };
+    extra_data.attestation =
+        Some(serde_json::to_string(&statement).expect("statement serialization never fails"));
+}
+
+fn hex(data: &[u8]) -> String {
+    DisplayBytes::from(data.to_vec())
+        .to_string()
+        .trim_start_matches("0x")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{
+        verify_response::{extra_data::BytecodePart, ExtraData},
+        Source,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn response(match_type: MatchType) -> VerifyResponse {
+        VerifyResponse {
+            source: Some(Source {
+                file_name: "A.sol".to_string(),
+                contract_name: "A".to_string(),
+                compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+                compiler_settings: r#"{"optimizer":{"enabled":false}}"#.to_string(),
+                source_type: SourceType::Solidity.into(),
+                source_files: BTreeMap::from([("A.sol".to_string(), "contract A {}".to_string())]),
+                match_type: match_type.into(),
+                ..Default::default()
+            }),
+            extra_data: Some(ExtraData {
+                local_deployed_bytecode_parts: vec![BytecodePart {
+                    r#type: "main".to_string(),
+                    data: "0x6080".to_string(),
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn attest_full_match() {
+        let mut response = response(MatchType::Full);
+        attest(&mut response, Some(&[0x11; 32]));
+
+        let attestation = response.extra_data.unwrap().attestation.unwrap();
+        let statement: serde_json::Value = serde_json::from_str(&attestation).unwrap();
+        assert_eq!(STATEMENT_TYPE, statement["_type"]);
+        assert_eq!("A.sol:A", statement["subject"][0]["name"]);
+        assert_eq!(
+            hex(&Sha256::digest([0x60, 0x80])),
+            statement["subject"][0]["digest"]["sha256"]
+        );
+
+        let predicate = &statement["predicate"];
+        assert_eq!(
+            false,
+            predicate["invocation"]["parameters"]["compilerSettings"]["optimizer"]["enabled"]
+        );
+        assert_eq!(
+            "pkg:generic/solc@v0.8.17+commit.8df45f5f",
+            predicate["materials"][0]["uri"]
+        );
+        assert_eq!(
+            "11".repeat(32),
+            predicate["materials"][0]["digest"]["sha256"]
+        );
+        assert_eq!("A.sol", predicate["materials"][1]["uri"]);
+    }
+
+    #[test]
+    fn partial_match_is_not_attested() {
+        let mut response = response(MatchType::Partial);
+        attest(&mut response, None);
+        assert_eq!(None, response.extra_data.unwrap().attestation);
+    }
+}
@@ -0,0 +1,46 @@
+use smart_contract_verifier_server::{generate_test_vector, Settings, TestVectorRequest};
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: generate-test-vector <source.sol | standard_input.json> \
+    <compiler version> <contract name> <output dir> \
+    [--settings <settings.json>] [--constructor-args <hex>]";
+
+/// Compiles the source by the compilers of the config and writes the test vector
+/// into the output dir (e.g., `tests/contracts/<name>`), printing it as well.
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let settings = Settings::new().expect("failed to read config");
+    let request = parse_args(std::env::args().skip(1).collect())?;
+    let vector = generate_test_vector(settings, request).await?;
+    println!("{}", serde_json::to_string_pretty(&vector)?);
+    Ok(())
+}
+
+fn parse_args(args: Vec<String>) -> anyhow::Result<TestVectorRequest> {
+    let mut positional = Vec::new();
+    let mut settings = None;
+    let mut constructor_arguments = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--settings" => settings = Some(args.next().map(PathBuf::from).ok_or_else(usage)?),
+            "--constructor-args" => constructor_arguments = Some(args.next().ok_or_else(usage)?),
+            _ => positional.push(arg),
+        }
+    }
+    match <[String; 4]>::try_from(positional) {
+        Ok([source, compiler_version, contract_name, output_dir]) => Ok(TestVectorRequest {
+            source: source.into(),
+            compiler_version,
+            contract_name,
+            settings,
+            constructor_arguments,
+            output_dir: output_dir.into(),
+        }),
+        Err(_) => Err(usage()),
+    }
+}
+
+fn usage() -> anyhow::Error {
+    anyhow::anyhow!(USAGE)
+}
@@ -0,0 +1,162 @@
+//! Registry of the chains known by the service: the embedded one with the configured overrides.
+
+use crate::settings::{ChainSettings, ChainsSettings, ForkActivationSettings};
+use anyhow::Context;
+use smart_contract_verifier::{
+    chains::{Activation, ForkActivation},
+    Chain, ChainRegistry, Normalization, PatternPass,
+};
+use std::collections::BTreeMap;
+
+pub fn registry(settings: &ChainsSettings) -> anyhow::Result<ChainRegistry> {
+    let mut registry = ChainRegistry::embedded();
+    for (id, chain_settings) in &settings.overrides {
+        let id = id
+            .parse::<u64>()
+            .with_context(|| format!("invalid chain id `{id}`"))?;
+        let chain = override_chain(registry.get(id).as_deref(), id, chain_settings)
+            .with_context(|| format!("chain {id}"))?;
+        registry = registry.with_chain(chain);
+    }
+    Ok(registry)
+}
+
+/// Compiles the byte patterns into the normalization passes named after the patterns
+pub fn normalization(patterns: &BTreeMap<String, String>) -> anyhow::Result<Normalization> {
+    patterns
+        .iter()
+        .try_fold(Normalization::new(), |normalization, (name, pattern)| {
+            let pass = PatternPass::new(name.as_str(), pattern)
+                .map_err(|err| anyhow::anyhow!("invalid normalization pattern `{name}`: {err}"))?;
+            Ok::<_, anyhow::Error>(normalization.with_pass(pass))
+        })
+}
+
+fn override_chain(
+    embedded: Option<&Chain>,
+    id: u64,
+    settings: &ChainSettings,
+) -> anyhow::Result<Chain> {
+    let mut chain = match embedded {
+        Some(embedded) => embedded.clone(),
+        None => {
+            let name = settings
+                .name
+                .as_deref()
+                .context("`name` is required for the chains missing from the registry")?;
+            Chain::new(id, name)
+        }
+    };
+    if let Some(name) = &settings.name {
+        chain.name = name.clone();
+    }
+    if settings.explorer_url.is_some() {
+        chain = chain.with_explorer_url(settings.explorer_url.clone());
+    }
+    if !settings.forks.is_empty() {
+        let forks = settings
+            .forks
+            .iter()
+            .map(fork_activation)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        chain = chain.with_forks(forks);
+    }
+    if !settings.normalization_patterns.is_empty() {
+        chain = chain.with_normalization(normalization(&settings.normalization_patterns)?);
+    }
+    Ok(chain)
+}
+
+fn fork_activation(settings: &ForkActivationSettings) -> anyhow::Result<ForkActivation> {
+    let activation = match (settings.block, settings.timestamp) {
+        (Some(block), None) => Activation::Block(block),
+        (None, Some(timestamp)) => Activation::Timestamp(timestamp),
+        _ => anyhow::bail!(
+            "exactly one of `block` or `timestamp` should be set for {}",
+            settings.fork
+        ),
+    };
+    Ok(ForkActivation {
+        fork: settings.fork,
+        activation,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use smart_contract_verifier::EvmFork;
+    use url::Url;
+
+    fn settings(overrides: Vec<(&str, ChainSettings)>) -> ChainsSettings {
+        ChainsSettings {
+            overrides: overrides
+                .into_iter()
+                .map(|(id, chain)| (id.to_string(), chain))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn override_embedded_chains() {
+        let explorer_url = Url::parse("https://explorer.example.com/").unwrap();
+        let registry = registry(&settings(vec![
+            (
+                "1",
+                ChainSettings {
+                    explorer_url: Some(explorer_url.clone()),
+                    normalization_patterns: BTreeMap::from([(
+                        "address".to_string(),
+                        "73????30".to_string(),
+                    )]),
+                    ..Default::default()
+                },
+            ),
+            (
+                "31337",
+                ChainSettings {
+                    name: Some("Devnet".to_string()),
+                    forks: vec![ForkActivationSettings {
+                        fork: EvmFork::Paris,
+                        block: Some(0),
+                        timestamp: None,
+                    }],
+                    ..Default::default()
+                },
+            ),
+        ]))
+        .expect("valid settings");
+
+        let ethereum = registry.get(1).unwrap();
+        assert_eq!("Ethereum", ethereum.name, "name should be kept");
+        assert_eq!(Some(explorer_url), ethereum.explorer_url);
+        assert_eq!(Some(EvmFork::Shanghai), ethereum.evm_fork_at(1681338455));
+        assert_eq!(vec!["address"], ethereum.normalization.names());
+
+        let devnet = registry.get(31337).unwrap();
+        assert_eq!("Devnet", devnet.name);
+        assert_eq!(Some(EvmFork::Paris), devnet.current_evm_fork());
+    }
+
+    #[test]
+    fn invalid_overrides() {
+        registry(&settings(vec![("31337", ChainSettings::default())]))
+            .expect_err("name is required for new chains");
+        registry(&settings(vec![("mainnet", ChainSettings::default())]))
+            .expect_err("invalid chain id");
+        let forks = vec![ForkActivationSettings {
+            fork: EvmFork::Paris,
+            block: Some(0),
+            timestamp: Some(0),
+        }];
+        registry(&settings(vec![(
+            "1",
+            ChainSettings {
+                forks,
+                ..Default::default()
+            },
+        )]))
+        .expect_err("both block and timestamp are set");
+    }
+}
@@ -0,0 +1,278 @@
+//! Logging of the http request and response bodies for debugging.
+//!
+//! Bodies are logged only if enabled in the config, and only for the requests marked
+//! with the debug header, so that user-reported verification failures could be reproduced
+//! without flooding the logs. Bodies are truncated, and the values of the api key headers
+//! and of the secret looking json fields are redacted.
+
+use crate::settings::{AdminSettings, DebugLoggingSettings, TenantsSettings};
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{Payload, Service, ServiceRequest, ServiceResponse},
+    http::header::{self, HeaderMap},
+};
+use bytes::BytesMut;
+use futures::{
+    future::{FutureExt, LocalBoxFuture},
+    StreamExt,
+};
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+const REDACTED: &str = "[REDACTED]";
+
+pub struct BodyLogger {
+    enabled: bool,
+    header: String,
+    max_body_size: usize,
+    redacted_headers: HashSet<String>,
+    redacted_fields: HashSet<String>,
+}
+
+impl BodyLogger {
+    pub fn new(
+        settings: &DebugLoggingSettings,
+        tenants: &TenantsSettings,
+        admin: &AdminSettings,
+    ) -> Self {
+        let redacted_headers = [
+            header::AUTHORIZATION.as_str(),
+            header::COOKIE.as_str(),
+            tenants.api_key_header.as_str(),
+            admin.api_key_header.as_str(),
+        ]
+        .into_iter()
+        .map(str::to_lowercase)
+        .collect();
+        Self {
+            enabled: settings.enabled,
+            header: settings.header.clone(),
+            max_body_size: settings.max_body_size,
+            redacted_headers,
+            redacted_fields: settings
+                .redacted_fields
+                .iter()
+                .map(|field| normalize_field(field))
+                .collect(),
+        }
+    }
+
+    /// Middleware processing the requests of the service. Is expected to be used via `wrap_fn`
+    /// inside the compression middleware, so that the uncompressed responses are logged.
+    pub fn handle<S, B>(
+        self: &Arc<Self>,
+        mut request: ServiceRequest,
+        service: &S,
+    ) -> LocalBoxFuture<'static, Result<ServiceResponse<BoxBody>, actix_web::Error>>
+    where
+        S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+        S::Future: 'static,
+        B: MessageBody + 'static,
+    {
+        let marked = request
+            .headers()
+            .get(self.header.as_str())
+            .and_then(|value| value.to_str().ok())
+            .map_or(false, |value| value.eq_ignore_ascii_case("true"));
+        if !self.enabled || !marked {
+            let response = service.call(request);
+            return async move { Ok(response.await?.map_into_boxed_body()) }.boxed_local();
+        }
+
+        // The request body is recorded while being read by the handler
+        let request_body = Arc::new(Mutex::new(BytesMut::new()));
+        let payload = {
+            let request_body = request_body.clone();
+            request.take_payload().map(move |chunk| {
+                if let Ok(bytes) = &chunk {
+                    request_body
+                        .lock()
+                        .expect("request body lock is poisoned")
+                        .extend_from_slice(bytes);
+                }
+                chunk
+            })
+        };
+        request.set_payload(Payload::Stream {
+            payload: Box::pin(payload),
+        });
+
+        let method = request.method().to_string();
+        let path = request.path().to_string();
+        let request_headers = self.headers(request.headers());
+        let encoded = request.headers().contains_key(header::CONTENT_ENCODING);
+        let logger = self.clone();
+        let response = service.call(request);
+        async move {
+            let (request, response) = response.await?.into_parts();
+            let (head, body) = response.into_parts();
+            let body = body::to_bytes(body).await.map_err(|err| {
+                let err: Box<dyn std::error::Error> = err.into();
+                actix_web::error::ErrorInternalServerError(err.to_string())
+            })?;
+
+            let request_body = request_body
+                .lock()
+                .expect("request body lock is poisoned")
+                .split()
+                .freeze();
+            let request_body = match encoded {
+                true => format!("[{} bytes encoded]", request_body.len()),
+                false => logger.body(&request_body),
+            };
+            tracing::info!(
+                method,
+                path,
+                headers = ?request_headers,
+                body = request_body,
+                status = head.status().as_u16(),
+                response_body = logger.body(&body),
+                "debug request"
+            );
+
+            Ok(ServiceResponse::new(
+                request,
+                head.set_body(body).map_into_boxed_body(),
+            ))
+        }
+        .boxed_local()
+    }
+
+    fn headers(&self, headers: &HeaderMap) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = match self.redacted_headers.contains(name.as_str()) {
+                    true => REDACTED.to_string(),
+                    false => String::from_utf8_lossy(value.as_bytes()).to_string(),
+                };
+                (name.to_string(), value)
+            })
+            .collect()
+    }
+
+    /// Json bodies are redacted before being truncated. Other bodies are truncated only.
+    fn body(&self, body: &[u8]) -> String {
+        let body = match serde_json::from_slice::<Value>(body) {
+            Ok(mut value) => {
+                self.redact(&mut value);
+                value.to_string()
+            }
+            Err(_) => String::from_utf8_lossy(body).to_string(),
+        };
+        truncate(body, self.max_body_size)
+    }
+
+    fn redact(&self, value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                for (field, value) in object.iter_mut() {
+                    if self.redacted_fields.contains(&normalize_field(field)) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.redact(value)),
+            _ => {}
+        }
+    }
+}
+
+fn normalize_field(field: &str) -> String {
+    field
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn truncate(mut value: String, max_size: usize) -> String {
+    if value.len() <= max_size {
+        return value;
+    }
+    let mut end = max_size;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    let truncated = value.len() - end;
+    value.truncate(end);
+    format!("{value}... ({truncated} bytes truncated)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::{HeaderName, HeaderValue};
+    use pretty_assertions::assert_eq;
+
+    fn logger(max_body_size: usize) -> BodyLogger {
+        BodyLogger::new(
+            &DebugLoggingSettings {
+                enabled: true,
+                max_body_size,
+                ..Default::default()
+            },
+            &TenantsSettings::default(),
+            &AdminSettings::default(),
+        )
+    }
+
+    #[test]
+    fn json_secrets_are_redacted() {
+        let body = serde_json::json!({
+            "compilerVersion": "v0.8.19+commit.7dd6d404",
+            "apiKey": "key",
+            "sources": [{"Private-Key": "key", "content": "contract A {}"}],
+        });
+        let expected = serde_json::json!({
+            "compilerVersion": "v0.8.19+commit.7dd6d404",
+            "apiKey": REDACTED,
+            "sources": [{"Private-Key": REDACTED, "content": "contract A {}"}],
+        });
+        assert_eq!(
+            expected.to_string(),
+            logger(1024).body(body.to_string().as_bytes())
+        );
+    }
+
+    #[test]
+    fn api_key_headers_are_redacted() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::from_static("x-api-key"),
+            HeaderValue::from_static("key"),
+        );
+        headers.insert(
+            HeaderName::from_static("x-admin-key"),
+            HeaderValue::from_static("admin key"),
+        );
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+
+        let mut logged = logger(1024).headers(&headers);
+        logged.sort();
+        assert_eq!(
+            vec![
+                ("content-type".to_string(), "text/plain".to_string()),
+                ("x-admin-key".to_string(), REDACTED.to_string()),
+                ("x-api-key".to_string(), REDACTED.to_string()),
+            ],
+            logged
+        );
+    }
+
+    #[test]
+    fn bodies_are_truncated() {
+        assert_eq!("not json", logger(1024).body(b"not json"));
+        assert_eq!("not... (5 bytes truncated)", logger(3).body(b"not json"));
+        assert_eq!(
+            "\"é... (3 bytes truncated)",
+            logger(4).body("\"éé\"".as_bytes()),
+            "truncated at char boundary"
+        );
+    }
+}
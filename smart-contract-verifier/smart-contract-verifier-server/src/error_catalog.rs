@@ -0,0 +1,245 @@
+//! Catalog of the failures verification responses are returned with.
+//!
+//! Every failure has a stable code and a map of parameters (e.g. expected and found
+//! compiler versions), so that explorer frontends could translate and render the failures
+//! themselves. The service renders the failures in English via the catalog templates,
+//! which is returned as the response message.
+//!
+//! Failures may additionally be assigned the category of the most likely user mistake
+//! (see [`FailureBucket`]), which is not a part of the rendered message.
+
+use crate::proto::verify_response::Failure as ProtoFailure;
+use smart_contract_verifier::{analyzer::failure_classifier::FailureBucket, VerificationError};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureCode {
+    /// None of the compiled contracts matches the bytecode.
+    /// Params: `inferredSettings` (optional) - settings the bytecode seems to be compiled with,
+    /// `viaIrHint` (optional) - hint to flip `viaIR` setting of the request
+    NoMatchingContracts,
+    /// The sources cannot be compiled. Params: `errors` - compiler errors separated by new lines
+    CompilationFailed,
+    /// The compiler version differs from the one included into the bytecode metadata.
+    /// Params: `expected` - version of the metadata, `found` - the requested one
+    CompilerVersionMismatch,
+    /// The compiler version does not satisfy the source pragmas.
+    /// Params: `version`, `pragmas` - unsatisfied pragmas separated by "; "
+    IncompatibleCompilerVersion,
+    /// Imported files are missing from the sources. Params: `imports` - separated by "; "
+    MissingSources,
+    /// Sourcify has not verified the contract. Params: `reason` - as returned by Sourcify
+    SourcifyVerificationFailed,
+    /// None of the compiled contracts matches the bytecode, while the compiled bytecodes use
+    /// opcodes not supported by the chain. Params: `evmFork` - EVM version of the chain,
+    /// `opcodes` - separated by ", "
+    UnsupportedOpcodes,
+}
+
+impl FailureCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureCode::NoMatchingContracts => "NO_MATCHING_CONTRACTS",
+            FailureCode::CompilationFailed => "COMPILATION_FAILED",
+            FailureCode::CompilerVersionMismatch => "COMPILER_VERSION_MISMATCH",
+            FailureCode::IncompatibleCompilerVersion => "INCOMPATIBLE_COMPILER_VERSION",
+            FailureCode::MissingSources => "MISSING_SOURCES",
+            FailureCode::SourcifyVerificationFailed => "SOURCIFY_VERIFICATION_FAILED",
+            FailureCode::UnsupportedOpcodes => "UNSUPPORTED_OPCODES",
+        }
+    }
+
+    /// English template of the message. Parameters are referenced as `{name}`.
+    fn template(&self) -> &'static str {
+        match self {
+            FailureCode::NoMatchingContracts => "No contract could be verified with provided data",
+            FailureCode::CompilationFailed => "Compilation error: {errors}",
+            FailureCode::CompilerVersionMismatch => {
+                "Invalid compiler version: Expected {expected}, found {found}"
+            }
+            FailureCode::IncompatibleCompilerVersion => {
+                "Compiler version {version} does not satisfy the source pragmas: {pragmas}"
+            }
+            FailureCode::MissingSources => "{imports}",
+            FailureCode::SourcifyVerificationFailed => "{reason}",
+            FailureCode::UnsupportedOpcodes => {
+                "Compiled bytecode uses opcodes not supported by the chain EVM version ({evmFork}): \
+                {opcodes}; set evmVersion to {evmFork} or earlier"
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub code: FailureCode,
+    pub params: BTreeMap<String, String>,
+    pub category: Option<FailureBucket>,
+}
+
+impl Failure {
+    pub fn new(code: FailureCode) -> Self {
+        Self {
+            code,
+            params: BTreeMap::new(),
+            category: None,
+        }
+    }
+
+    pub fn with_param(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_category(mut self, category: Option<FailureBucket>) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Describes the errors the verification fails with because of the provided data.
+    /// Returns `None` for the errors which reject the request instead.
+    pub fn from_verification_error(err: &VerificationError) -> Option<Self> {
+        let failure = match err {
+            VerificationError::NoMatchingContracts(_) => {
+                Self::new(FailureCode::NoMatchingContracts)
+            }
+            VerificationError::Compilation(errors) => {
+                Self::new(FailureCode::CompilationFailed).with_param("errors", errors.join("\n"))
+            }
+            VerificationError::CompilerVersionMismatch(mismatch) => {
+                let failure = Self::new(FailureCode::CompilerVersionMismatch)
+                    .with_param("expected", &mismatch.expected);
+                match &mismatch.found {
+                    Some(found) => failure.with_param("found", found),
+                    None => failure,
+                }
+            }
+            VerificationError::IncompatibleCompilerVersion { version, pragmas } => {
+                Self::new(FailureCode::IncompatibleCompilerVersion)
+                    .with_param("version", version)
+                    .with_param("pragmas", pragmas.join("; "))
+            }
+            VerificationError::MissingSources(imports) => {
+                Self::new(FailureCode::MissingSources).with_param("imports", imports.join("; "))
+            }
+            VerificationError::UnsupportedOpcodes { evm_fork, opcodes } => {
+                Self::new(FailureCode::UnsupportedOpcodes)
+                    .with_param("evmFork", evm_fork)
+                    .with_param("opcodes", opcodes.join(", "))
+            }
+            VerificationError::Initialization(_)
+            | VerificationError::VersionNotFound(_)
+            | VerificationError::LimitExceeded(_)
+            | VerificationError::Internal(_) => return None,
+        };
+        Some(failure)
+    }
+
+    /// Default English rendering of the failure. Parameters missing
+    /// from the template are appended to the message.
+    pub fn render(&self) -> String {
+        let mut message = self.code.template().to_string();
+        for (name, value) in &self.params {
+            let placeholder = format!("{{{name}}}");
+            if message.contains(&placeholder) {
+                message = message.replace(&placeholder, value);
+            } else {
+                message = format!("{message}; {value}");
+            }
+        }
+        message
+    }
+}
+
+impl From<Failure> for ProtoFailure {
+    fn from(failure: Failure) -> Self {
+        ProtoFailure {
+            code: failure.code.as_str().to_string(),
+            params: failure.params,
+            category: failure
+                .category
+                .map(|category| category.as_str().to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mismatch::Mismatch;
+    use pretty_assertions::assert_eq;
+    use smart_contract_verifier::{EvmFork, Version};
+    use std::str::FromStr;
+
+    #[test]
+    fn renders_as_verification_errors() {
+        let errors = [
+            VerificationError::NoMatchingContracts(vec![]),
+            VerificationError::CompilerVersionMismatch(Mismatch::new(
+                semver::Version::new(0, 8, 17),
+                semver::Version::new(0, 8, 18),
+            )),
+            VerificationError::IncompatibleCompilerVersion {
+                version: Version::from_str("v0.8.17+commit.8df45f5f").unwrap(),
+                pragmas: vec!["A.sol: ^0.7.0".into(), "B.sol: ^0.6.0".into()],
+            },
+            VerificationError::MissingSources(vec!["A.sol imports B.sol".into()]),
+            VerificationError::UnsupportedOpcodes {
+                evm_fork: EvmFork::Paris,
+                opcodes: vec!["MCOPY".into(), "PUSH0".into()],
+            },
+        ];
+        for err in errors {
+            let failure = Failure::from_verification_error(&err).expect("failure expected");
+            assert_eq!(err.to_string(), failure.render(), "{:?}", failure.code);
+        }
+
+        let err = VerificationError::Compilation(vec!["ParserError".into(), "TypeError".into()]);
+        assert_eq!(
+            "Compilation error: ParserError\nTypeError",
+            Failure::from_verification_error(&err).unwrap().render(),
+            "compiler errors should not be debug formatted"
+        );
+    }
+
+    #[test]
+    fn failure_params() {
+        let err = VerificationError::CompilerVersionMismatch(Mismatch::new(
+            semver::Version::new(0, 8, 17),
+            semver::Version::new(0, 8, 18),
+        ));
+        let failure: ProtoFailure = Failure::from_verification_error(&err).unwrap().into();
+        assert_eq!("COMPILER_VERSION_MISMATCH", failure.code);
+        assert_eq!("0.8.17", failure.params["expected"]);
+        assert_eq!("0.8.18", failure.params["found"]);
+
+        let failure = Failure::new(FailureCode::NoMatchingContracts).with_param(
+            "inferredSettings",
+            "the contract seems to be compiled with solc 0.8",
+        );
+        assert_eq!(
+            "No contract could be verified with provided data; \
+            the contract seems to be compiled with solc 0.8",
+            failure.render()
+        );
+
+        let failure = failure.with_category(Some(FailureBucket::OptimizerMismatch));
+        assert_eq!(
+            "No contract could be verified with provided data; \
+            the contract seems to be compiled with solc 0.8",
+            failure.render(),
+            "category should not be rendered"
+        );
+        assert_eq!(
+            Some("optimizer_mismatch".to_string()),
+            ProtoFailure::from(failure).category
+        );
+
+        assert_eq!(
+            None,
+            Failure::from_verification_error(&VerificationError::Internal(anyhow::anyhow!(
+                "error"
+            )))
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Aggregated statistics of failed verifications.
+//!
+//! Failures are classified into the most common user mistakes
+//! (see [`failure_classifier`](smart_contract_verifier::analyzer::failure_classifier)),
+//! so that maintainers could see what users struggle with. The counts are kept in memory
+//! since the service start and are additionally reported via metrics.
+
+use crate::metrics;
+use smart_contract_verifier::analyzer::failure_classifier::FailureBucket;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailureCount {
+    pub language: &'static str,
+    pub bucket: FailureBucket,
+    pub count: u64,
+}
+
+#[derive(Debug)]
+pub struct FailureStats {
+    /// Unix timestamp the failures are counted since
+    since: u64,
+    counts: Mutex<HashMap<(&'static str, FailureBucket), u64>>,
+}
+
+impl Default for FailureStats {
+    fn default() -> Self {
+        let since = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        Self {
+            since,
+            counts: Default::default(),
+        }
+    }
+}
+
+impl FailureStats {
+    pub fn since(&self) -> u64 {
+        self.since
+    }
+
+    pub fn record(&self, language: &'static str, bucket: FailureBucket) {
+        *self
+            .counts
+            .lock()
+            .unwrap()
+            .entry((language, bucket))
+            .or_default() += 1;
+    }
+
+    /// Returns the counts sorted in descending order
+    pub fn counts(&self) -> Vec<FailureCount> {
+        let mut counts: Vec<_> = self
+            .counts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&(language, bucket), &count)| FailureCount {
+                language,
+                bucket,
+                count,
+            })
+            .collect();
+        counts.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then(a.language.cmp(b.language))
+                .then(a.bucket.cmp(&b.bucket))
+        });
+        counts
+    }
+}
+
+/// Records the failure both into the statistics (if enabled) and into the metrics.
+pub fn record_failure(
+    stats: Option<&FailureStats>,
+    language: &'static str,
+    bucket: FailureBucket,
+    tenant: &str,
+) {
+    metrics::count_verification_failure(language, bucket.as_str(), tenant);
+    if let Some(stats) = stats {
+        stats.record(language, bucket);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn counts_are_sorted() {
+        let stats = FailureStats::default();
+        stats.record("solidity", FailureBucket::Other);
+        stats.record("vyper", FailureBucket::OptimizerMismatch);
+        stats.record("solidity", FailureBucket::OptimizerMismatch);
+        stats.record("solidity", FailureBucket::OptimizerMismatch);
+
+        let expected = vec![
+            FailureCount {
+                language: "solidity",
+                bucket: FailureBucket::OptimizerMismatch,
+                count: 2,
+            },
+            FailureCount {
+                language: "solidity",
+                bucket: FailureBucket::Other,
+                count: 1,
+            },
+            FailureCount {
+                language: "vyper",
+                bucket: FailureBucket::OptimizerMismatch,
+                count: 1,
+            },
+        ];
+        assert_eq!(expected, stats.counts());
+    }
+}
@@ -0,0 +1,277 @@
+//! Canned verification scenarios for the frontend development.
+//!
+//! Fixtures cover successful verifications (full and partial matches) and failed ones
+//! of every failure code of the catalog, so that explorer frontends could be built against
+//! stable payloads without running the compilers. Responses are built the same way
+//! the verification handlers build them.
+
+use crate::{
+    error_catalog::{Failure, FailureCode},
+    proto::{
+        source::{MatchType, SourceType},
+        verify_response::{
+            extra_data::{BytecodePart, NatSpec},
+            ExtraData, Status,
+        },
+        Source, VerificationFixture, VerifyResponse,
+    },
+    types::VerifyResponseWrapper,
+};
+use smart_contract_verifier::analyzer::failure_classifier::FailureBucket;
+use std::collections::BTreeMap;
+
+const FILE_NAME: &str = "contracts/Storage.sol";
+const CONTRACT_NAME: &str = "Storage";
+const COMPILER_VERSION: &str = "v0.8.19+commit.7dd6d404";
+
+const SOURCE: &str = r#"// SPDX-License-Identifier: GPL-3.0
+pragma solidity >=0.8.2 <0.9.0;
+
+/// @title Storage
+/// @notice Store & retrieve value in a variable
+contract Storage {
+    uint256 number;
+
+    /// @notice Store value in variable
+    /// @param num value to store
+    function store(uint256 num) public {
+        number = num;
+    }
+
+    /// @notice Return value
+    /// @return value of 'number'
+    function retrieve() public view returns (uint256) {
+        return number;
+    }
+}
+"#;
+
+const ABI: &str = r#"[{"inputs":[],"name":"retrieve","outputs":[{"internalType":"uint256","name":"","type":"uint256"}],"stateMutability":"view","type":"function"},{"inputs":[{"internalType":"uint256","name":"num","type":"uint256"}],"name":"store","outputs":[],"stateMutability":"nonpayable","type":"function"}]"#;
+
+const COMPILER_SETTINGS: &str = r#"{"optimizer":{"enabled":true,"runs":200},"evmVersion":"paris","libraries":{},"outputSelection":{"*":{"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers","metadata"]}}}"#;
+
+const USERDOC: &str = r#"{"kind":"user","methods":{"retrieve()":{"notice":"Return value"},"store(uint256)":{"notice":"Store value in variable"}},"notice":"Store & retrieve value in a variable","version":1}"#;
+
+const DEVDOC: &str = r#"{"kind":"dev","methods":{"retrieve()":{"returns":{"_0":"value of 'number'"}},"store(uint256)":{"params":{"num":"value to store"}}},"title":"Storage","version":1}"#;
+
+const CREATION_CODE: &str = "0x6080604052348015600f57600080fd5b5060ac80601d6000396000f3fe";
+
+const DEPLOYED_CODE: &str = "0x6080604052348015600f57600080fd5b506004361060325760003560e01c80632e64cec11460375780636057361d14604c575b600080fd5b60005460405190815260200160405180910390f35b605c6057366004605e565b600055565b005b600060208284031215606f57600080fd5b503591905056fe";
+
+const METADATA: &str = "0xa26469706673582212203f8a0c6b2e5d4f1a9b7c8d0e1f2a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c64736f6c63430008130033";
+
+/// All the scenarios sorted by the names.
+pub fn verification_fixtures() -> Vec<VerificationFixture> {
+    let mut fixtures = vec![
+        fixture(
+            "success_full_match",
+            "Solidity contract whose bytecode, including the metadata hash, \
+            matches the compiled one",
+            success(MatchType::Full),
+        ),
+        fixture(
+            "success_partial_match",
+            "Solidity contract whose executable bytecode matches the compiled one, \
+            while the metadata hash differs (e.g., sources comments have been changed)",
+            success(MatchType::Partial),
+        ),
+        fixture(
+            "failure_no_matching_contracts",
+            "None of the compiled contracts matches the bytecode; \
+            the settings inferred from the bytecode are suggested",
+            failure(
+                Failure::new(FailureCode::NoMatchingContracts)
+                    .with_param(
+                        "inferredSettings",
+                        "likely settings: compiler version 0.8.19, optimization enabled, \
+                        legacy code generation",
+                    )
+                    .with_category(Some(FailureBucket::OptimizerMismatch)),
+            ),
+        ),
+        fixture(
+            "failure_no_matching_contracts_via_ir",
+            "None of the compiled contracts matches the bytecode, \
+            which looks like the output of the other code generation pipeline",
+            failure(
+                Failure::new(FailureCode::NoMatchingContracts)
+                    .with_param(
+                        "inferredSettings",
+                        "likely settings: compiler version 0.8.19, optimization enabled, \
+                        via-ir code generation",
+                    )
+                    .with_param(
+                        "viaIrHint",
+                        "compiled code looks like via-ir output, retry with viaIR=true",
+                    )
+                    .with_category(Some(FailureBucket::ViaIrMismatch)),
+            ),
+        ),
+        fixture(
+            "failure_compilation_failed",
+            "The sources cannot be compiled",
+            failure(
+                Failure::new(FailureCode::CompilationFailed)
+                    .with_param(
+                        "errors",
+                        "contracts/Storage.sol:12:18: ParserError: Expected ';' but got '}'",
+                    )
+                    .with_category(Some(FailureBucket::CompilationError)),
+            ),
+        ),
+        fixture(
+            "failure_compiler_version_mismatch",
+            "The requested compiler version differs from the one \
+            included into the bytecode metadata",
+            failure(
+                Failure::new(FailureCode::CompilerVersionMismatch)
+                    .with_param("expected", "0.8.19")
+                    .with_param("found", "0.8.17")
+                    .with_category(Some(FailureBucket::WrongCompilerVersion)),
+            ),
+        ),
+        fixture(
+            "failure_incompatible_compiler_version",
+            "The requested compiler version does not satisfy the source pragmas",
+            failure(
+                Failure::new(FailureCode::IncompatibleCompilerVersion)
+                    .with_param("version", "v0.7.6+commit.7338295f")
+                    .with_param("pragmas", "contracts/Storage.sol: >=0.8.2 <0.9.0")
+                    .with_category(Some(FailureBucket::WrongCompilerVersion)),
+            ),
+        ),
+        fixture(
+            "failure_missing_sources",
+            "Files imported by the sources are missing from the request",
+            failure(
+                Failure::new(FailureCode::MissingSources)
+                    .with_param(
+                        "imports",
+                        "contracts/Token.sol imports @openzeppelin/contracts/token/ERC20/ERC20.sol",
+                    )
+                    .with_category(Some(FailureBucket::CompilationError)),
+            ),
+        ),
+        fixture(
+            "failure_unsupported_opcodes",
+            "The compiled bytecode uses opcodes the chain does not support yet",
+            failure(
+                Failure::new(FailureCode::UnsupportedOpcodes)
+                    .with_param("evmFork", "paris")
+                    .with_param("opcodes", "PUSH0"),
+            ),
+        ),
+        fixture(
+            "failure_sourcify_verification_failed",
+            "Sourcify has not verified the contract",
+            failure(
+                Failure::new(FailureCode::SourcifyVerificationFailed).with_param(
+                    "reason",
+                    "The deployed and recompiled bytecode don't match.",
+                ),
+            ),
+        ),
+    ];
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    fixtures
+}
+
+fn fixture(name: &str, description: &str, response: VerifyResponseWrapper) -> VerificationFixture {
+    VerificationFixture {
+        name: name.to_string(),
+        description: description.to_string(),
+        response: Some(response.into_inner()),
+    }
+}
+
+fn failure(failure: Failure) -> VerifyResponseWrapper {
+    VerifyResponseWrapper::failure(failure)
+}
+
+fn success(match_type: MatchType) -> VerifyResponseWrapper {
+    let bytecode_part = |r#type: &str, data: &str| BytecodePart {
+        r#type: r#type.to_string(),
+        data: data.to_string(),
+    };
+    let creation_code = format!("{CREATION_CODE}{}", DEPLOYED_CODE.trim_start_matches("0x"));
+    let source = Source {
+        file_name: FILE_NAME.to_string(),
+        contract_name: CONTRACT_NAME.to_string(),
+        compiler_version: COMPILER_VERSION.to_string(),
+        compiler_settings: COMPILER_SETTINGS.to_string(),
+        source_type: SourceType::Solidity.into(),
+        source_files: BTreeMap::from([(FILE_NAME.to_string(), SOURCE.to_string())]),
+        abi: Some(ABI.to_string()),
+        constructor_arguments: None,
+        match_type: match_type.into(),
+    };
+    let extra_data = ExtraData {
+        local_creation_input_parts: vec![
+            bytecode_part("main", &creation_code),
+            bytecode_part("meta", METADATA),
+        ],
+        local_deployed_bytecode_parts: vec![
+            bytecode_part("main", DEPLOYED_CODE),
+            bytecode_part("meta", METADATA),
+        ],
+        natspec: Some(NatSpec {
+            userdoc: Some(USERDOC.to_string()),
+            devdoc: Some(DEVDOC.to_string()),
+        }),
+        method_identifiers: BTreeMap::from([
+            ("retrieve()".to_string(), "2e64cec1".to_string()),
+            ("store(uint256)".to_string(), "6057361d".to_string()),
+        ]),
+        ..Default::default()
+    };
+    VerifyResponseWrapper::from(VerifyResponse {
+        message: "OK".to_string(),
+        status: Status::Success.into(),
+        source: Some(source),
+        extra_data: Some(extra_data),
+        failure: None,
+        trace: None,
+    })
+    .with_attestation(None::<&[u8]>)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn fixtures_are_sorted_and_unique() {
+        let names: Vec<_> = verification_fixtures()
+            .into_iter()
+            .map(|fixture| fixture.name)
+            .collect();
+        let mut expected = names.clone();
+        expected.sort();
+        expected.dedup();
+        assert_eq!(expected, names);
+    }
+
+    #[test]
+    fn fixtures_match_scenarios() {
+        for fixture in verification_fixtures() {
+            let response = fixture.response.expect("response expected");
+            let attestation = response
+                .extra_data
+                .as_ref()
+                .and_then(|extra_data| extra_data.attestation.as_ref());
+            if fixture.name.starts_with("success") {
+                assert_eq!(Status::Success, response.status(), "{}", fixture.name);
+                assert_eq!(
+                    fixture.name == "success_full_match",
+                    attestation.is_some(),
+                    "only full matches are attested"
+                );
+            } else {
+                assert_eq!(Status::Failure, response.status(), "{}", fixture.name);
+                assert!(response.failure.is_some(), "{}", fixture.name);
+                assert!(!response.message.contains('{'), "{}", response.message);
+            }
+        }
+    }
+}
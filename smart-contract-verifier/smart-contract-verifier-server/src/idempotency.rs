@@ -0,0 +1,531 @@
+//! Idempotency keys of the http requests.
+//!
+//! Clients retrying a request (e.g., after a timeout) may mark it with the idempotency key
+//! header, so that the retries do not start duplicate compilations. The response to the first
+//! request with the key is kept for the configured window and is returned for the repeated ones
+//! (marked with `Idempotent-Replayed` header). Retries arriving while the first request is still
+//! processed wait for its response. Keys are scoped by the route and the tenant headers.
+//! Repeated requests with the same key but a different body are rejected
+//! with `422 Unprocessable Entity`.
+
+use crate::{
+    metrics,
+    settings::{IdempotencySettings, TenantsSettings},
+    validation::{ErrorCode, ValidationError},
+};
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{Payload, Service, ServiceRequest, ServiceResponse},
+    http::{header::HeaderMap, Method, StatusCode},
+    HttpResponse,
+};
+use bytes::Bytes;
+use futures::{
+    future::{self, FutureExt, LocalBoxFuture},
+    stream, StreamExt,
+};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    task::Poll,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+use tonic::Code;
+
+/// Header the replayed responses are marked with
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+
+const MAX_KEY_LENGTH: usize = 255;
+
+const INTERRUPTED_MESSAGE: &str =
+    "request with the same idempotency key was interrupted, the request should be retried";
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Key {
+    route: String,
+    /// Values of the tenant headers
+    scope: Vec<Option<String>>,
+    key: String,
+}
+
+/// Sha256 hash of the request body
+type BodyDigest = [u8; 32];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    /// Digest of the body of the request the response has been made for
+    request_digest: BodyDigest,
+}
+
+impl CachedResponse {
+    /// Replays the response, if the repeated request has the same body as the original one.
+    fn replay(&self, request_digest: &BodyDigest, header: &str) -> HttpResponse {
+        if request_digest != &self.request_digest {
+            let err = ValidationError::new(
+                ErrorCode::InvalidValue,
+                header,
+                format!("`{header}` has already been used for a request with a different body"),
+            );
+            let body = serde_json::json!({
+                "code": Code::InvalidArgument as i32,
+                "message": err.encode(),
+            });
+            return HttpResponse::UnprocessableEntity().json(body);
+        }
+
+        let mut response = HttpResponse::build(self.status);
+        for (name, value) in &self.headers {
+            response.append_header((name.clone(), value.clone()));
+        }
+        response
+            .insert_header((REPLAYED_HEADER, "true"))
+            .body(self.body.clone())
+    }
+}
+
+type Entries = Arc<Mutex<HashMap<Key, Entry>>>;
+
+enum Entry {
+    InFlight(watch::Receiver<Option<Arc<CachedResponse>>>),
+    Completed {
+        response: Arc<CachedResponse>,
+        expires_at: Instant,
+    },
+}
+
+enum Begin {
+    /// The request is processed as usual
+    Untracked,
+    /// The request is the first one with the key
+    Process(InFlight),
+    /// The request with the key is being processed
+    Wait(watch::Receiver<Option<Arc<CachedResponse>>>),
+    /// The request with the key has been processed
+    Replay(Arc<CachedResponse>),
+}
+
+/// Request with the key being processed. The key is released if the request
+/// is dropped (e.g., the client disconnected) before its response is ready.
+struct InFlight {
+    entries: Entries,
+    key: Key,
+    window: Duration,
+    sender: Option<watch::Sender<Option<Arc<CachedResponse>>>>,
+}
+
+impl InFlight {
+    /// Passes the response to the waiting requests, and keeps it
+    /// for the repeated ones if the response may be replayed.
+    fn finish(mut self, response: Arc<CachedResponse>, now: Instant) {
+        let mut entries = self.entries.lock().expect("idempotency lock is poisoned");
+        if is_replayable(response.status) {
+            entries.insert(
+                self.key.clone(),
+                Entry::Completed {
+                    response: response.clone(),
+                    expires_at: now + self.window,
+                },
+            );
+        } else {
+            entries.remove(&self.key);
+        }
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Some(response));
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.sender.is_some() {
+            self.entries
+                .lock()
+                .expect("idempotency lock is poisoned")
+                .remove(&self.key);
+        }
+    }
+}
+
+/// Responses which depend on the service state rather than on the request itself
+/// (server errors, timeouts, quotas) are not replayed, so that the retries could succeed.
+fn is_replayable(status: StatusCode) -> bool {
+    !status.is_server_error()
+        && status != StatusCode::REQUEST_TIMEOUT
+        && status != StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Computes the digest of the request body while the body is read by the handler.
+#[derive(Clone, Default)]
+struct RequestHasher(Arc<Mutex<(Sha256, bool)>>);
+
+impl RequestHasher {
+    fn wrap_payload(request: &mut ServiceRequest) -> Self {
+        let request_hasher = Self::default();
+        let payload = {
+            let hasher = request_hasher.clone();
+            let finished = request_hasher.clone();
+            request
+                .take_payload()
+                .map(move |chunk| {
+                    if let Ok(bytes) = &chunk {
+                        hasher.lock().0.update(bytes);
+                    }
+                    chunk
+                })
+                .chain(stream::poll_fn(move |_| {
+                    finished.lock().1 = true;
+                    Poll::Ready(None)
+                }))
+        };
+        request.set_payload(Payload::Stream {
+            payload: Box::pin(payload),
+        });
+        request_hasher
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, (Sha256, bool)> {
+        self.0.lock().expect("request hasher lock is poisoned")
+    }
+
+    /// Returns the digest if the body has been read completely.
+    fn digest(&self) -> Option<BodyDigest> {
+        let state = self.lock();
+        let (hasher, finished) = &*state;
+        finished.then(|| hasher.clone().finalize().into())
+    }
+}
+
+async fn body_digest(mut payload: Payload) -> Result<BodyDigest, actix_web::Error> {
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = payload.next().await {
+        hasher.update(chunk?);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Replays are counted for the known routes only,
+/// so that the metrics labels are not polluted by arbitrary paths
+fn count_replay(request: &ServiceRequest) {
+    if let Some(endpoint) = request.match_pattern() {
+        metrics::IDEMPOTENT_REPLAYS
+            .with_label_values(&[&endpoint])
+            .inc();
+    }
+}
+
+pub struct IdempotencyKeys {
+    enabled: bool,
+    header: String,
+    scope_headers: Vec<String>,
+    window: Duration,
+    max_keys: usize,
+    entries: Entries,
+}
+
+impl IdempotencyKeys {
+    pub fn new(settings: &IdempotencySettings, tenants: &TenantsSettings) -> Self {
+        let scope_headers = std::iter::once(tenants.api_key_header.clone())
+            .chain(tenants.tenant_header.clone())
+            .collect();
+        Self {
+            enabled: settings.enabled,
+            header: settings.header.clone(),
+            scope_headers,
+            window: Duration::from_secs(settings.window),
+            max_keys: settings.max_keys,
+            entries: Default::default(),
+        }
+    }
+
+    /// Middleware processing the requests of the service. Is expected to be used via `wrap_fn`
+    /// inside the compression middleware, so that the uncompressed responses are kept.
+    pub fn handle<S, B>(
+        self: &Arc<Self>,
+        mut request: ServiceRequest,
+        service: &S,
+    ) -> LocalBoxFuture<'static, Result<ServiceResponse<BoxBody>, actix_web::Error>>
+    where
+        S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+        S::Future: 'static,
+        B: MessageBody + 'static,
+    {
+        let key = match self.key(&request) {
+            Ok(key) => key,
+            Err(err) => {
+                let body = serde_json::json!({
+                    "code": Code::InvalidArgument as i32,
+                    "message": err.encode(),
+                });
+                let response = HttpResponse::BadRequest().json(body);
+                return future::ok(request.into_response(response)).boxed_local();
+            }
+        };
+        let begin = match key {
+            Some(key) => self.begin(key, Instant::now()),
+            None => Begin::Untracked,
+        };
+
+        let header = self.header.clone();
+        match begin {
+            Begin::Untracked => {
+                let response = service.call(request);
+                async move { Ok(response.await?.map_into_boxed_body()) }.boxed_local()
+            }
+            Begin::Replay(cached) => async move {
+                let request_digest = body_digest(request.take_payload()).await?;
+                count_replay(&request);
+                let response = cached.replay(&request_digest, &header);
+                Ok(request.into_response(response))
+            }
+            .boxed_local(),
+            Begin::Wait(mut receiver) => async move {
+                let request_digest = body_digest(request.take_payload()).await?;
+                loop {
+                    let cached = receiver.borrow().clone();
+                    if let Some(cached) = cached {
+                        count_replay(&request);
+                        let response = cached.replay(&request_digest, &header);
+                        return Ok(request.into_response(response));
+                    }
+                    if receiver.changed().await.is_err() {
+                        let body = serde_json::json!({
+                            "code": Code::Aborted as i32,
+                            "message": INTERRUPTED_MESSAGE,
+                        });
+                        let response = HttpResponse::Conflict().json(body);
+                        return Ok(request.into_response(response));
+                    }
+                }
+            }
+            .boxed_local(),
+            Begin::Process(in_flight) => {
+                let request_hasher = RequestHasher::wrap_payload(&mut request);
+                let response = service.call(request);
+                async move {
+                    let (request, response) = response.await?.into_parts();
+                    let (head, body) = response.into_parts();
+                    let body = body::to_bytes(body).await.map_err(|err| {
+                        let err: Box<dyn std::error::Error> = err.into();
+                        actix_web::error::ErrorInternalServerError(err.to_string())
+                    })?;
+                    match request_hasher.digest() {
+                        Some(request_digest) => in_flight.finish(
+                            Arc::new(CachedResponse {
+                                status: head.status(),
+                                headers: head.headers().clone(),
+                                body: body.clone(),
+                                request_digest,
+                            }),
+                            Instant::now(),
+                        ),
+                        // The body has not been read completely, so the repeated
+                        // requests could not be compared with the processed one
+                        None => drop(in_flight),
+                    }
+                    Ok(ServiceResponse::new(
+                        request,
+                        head.set_body(body).map_into_boxed_body(),
+                    ))
+                }
+                .boxed_local()
+            }
+        }
+    }
+
+    /// Key of the request, if the request is marked with it.
+    fn key(&self, request: &ServiceRequest) -> Result<Option<Key>, ValidationError> {
+        if !self.enabled || request.method() != Method::POST {
+            return Ok(None);
+        }
+        let key = match request.headers().get(self.header.as_str()) {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        let key = key
+            .to_str()
+            .ok()
+            .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH)
+            .ok_or_else(|| {
+                let header = &self.header;
+                ValidationError::new(
+                    ErrorCode::InvalidValue,
+                    header,
+                    format!("`{header}` header must be of 1 to {MAX_KEY_LENGTH} ascii chars"),
+                )
+            })?;
+        let scope = self
+            .scope_headers
+            .iter()
+            .map(|name| {
+                request
+                    .headers()
+                    .get(name.as_str())
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string)
+            })
+            .collect();
+        Ok(Some(Key {
+            route: request.path().to_string(),
+            scope,
+            key: key.to_string(),
+        }))
+    }
+
+    fn begin(&self, key: Key, now: Instant) -> Begin {
+        let mut entries = self.entries.lock().expect("idempotency lock is poisoned");
+        match entries.get(&key) {
+            Some(Entry::InFlight(receiver)) => return Begin::Wait(receiver.clone()),
+            Some(Entry::Completed {
+                response,
+                expires_at,
+            }) if *expires_at > now => return Begin::Replay(response.clone()),
+            _ => {}
+        }
+
+        if entries.len() >= self.max_keys {
+            entries.retain(|_, entry| match entry {
+                Entry::InFlight(_) => true,
+                Entry::Completed { expires_at, .. } => *expires_at > now,
+            });
+            if entries.len() >= self.max_keys {
+                return Begin::Untracked;
+            }
+        }
+        let (sender, receiver) = watch::channel(None);
+        entries.insert(key.clone(), Entry::InFlight(receiver));
+        Begin::Process(InFlight {
+            entries: self.entries.clone(),
+            key,
+            window: self.window,
+            sender: Some(sender),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn keys(max_keys: usize) -> IdempotencyKeys {
+        IdempotencyKeys::new(
+            &IdempotencySettings {
+                enabled: true,
+                window: 60,
+                max_keys,
+                ..Default::default()
+            },
+            &TenantsSettings::default(),
+        )
+    }
+
+    fn key(key: &str) -> Key {
+        Key {
+            route: "/api/v2/verifier/solidity/sources:verify-multi-part".to_string(),
+            scope: vec![None],
+            key: key.to_string(),
+        }
+    }
+
+    fn response(status: StatusCode) -> Arc<CachedResponse> {
+        Arc::new(CachedResponse {
+            status,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(b"{}"),
+            request_digest: [1; 32],
+        })
+    }
+
+    fn in_flight(begin: Begin) -> InFlight {
+        match begin {
+            Begin::Process(in_flight) => in_flight,
+            _ => panic!("request is expected to be processed"),
+        }
+    }
+
+    fn replayed(begin: Begin) -> Arc<CachedResponse> {
+        match begin {
+            Begin::Replay(response) => response,
+            _ => panic!("response is expected to be replayed"),
+        }
+    }
+
+    #[test]
+    fn repeated_requests_are_replayed() {
+        let keys = keys(10);
+        let now = Instant::now();
+
+        let first = in_flight(keys.begin(key("a"), now));
+        let mut waiting = match keys.begin(key("a"), now) {
+            Begin::Wait(receiver) => receiver,
+            _ => panic!("request is expected to wait"),
+        };
+        in_flight(keys.begin(key("b"), now));
+
+        first.finish(response(StatusCode::OK), now);
+        assert_eq!(
+            Some(response(StatusCode::OK)),
+            waiting.borrow_and_update().clone()
+        );
+        assert_eq!(
+            response(StatusCode::OK),
+            replayed(keys.begin(key("a"), now + Duration::from_secs(59)))
+        );
+        in_flight(keys.begin(key("a"), now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn interrupted_and_failed_requests_are_not_replayed() {
+        let keys = keys(10);
+        let now = Instant::now();
+
+        let first = in_flight(keys.begin(key("a"), now));
+        let waiting = match keys.begin(key("a"), now) {
+            Begin::Wait(receiver) => receiver,
+            _ => panic!("request is expected to wait"),
+        };
+        drop(first);
+        assert!(
+            waiting.has_changed().is_err(),
+            "waiting request is notified"
+        );
+
+        let retry = in_flight(keys.begin(key("a"), now));
+        retry.finish(response(StatusCode::INTERNAL_SERVER_ERROR), now);
+        let retry = in_flight(keys.begin(key("a"), now));
+        retry.finish(response(StatusCode::TOO_MANY_REQUESTS), now);
+        let retry = in_flight(keys.begin(key("a"), now));
+        retry.finish(response(StatusCode::BAD_REQUEST), now);
+        replayed(keys.begin(key("a"), now));
+    }
+
+    #[test]
+    fn number_of_keys_is_limited() {
+        let keys = keys(1);
+        let now = Instant::now();
+
+        in_flight(keys.begin(key("a"), now)).finish(response(StatusCode::OK), now);
+        assert!(matches!(keys.begin(key("b"), now), Begin::Untracked));
+
+        let expired = now + Duration::from_secs(60);
+        in_flight(keys.begin(key("b"), expired));
+    }
+
+    #[test]
+    fn requests_with_different_body_are_not_replayed() {
+        let cached = response(StatusCode::OK);
+        assert_eq!(
+            StatusCode::OK,
+            cached.replay(&[1; 32], "idempotency-key").status()
+        );
+        assert_eq!(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            cached.replay(&[2; 32], "idempotency-key").status()
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    proto::{verify_response::ExtraData, Source},
+    settings::KnownContractsSettings,
+    types::{VerifyResponseOk, VerifyResponseWrapper},
+};
+use anyhow::Context;
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use bytes::Bytes;
+use serde::Deserialize;
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+/// Description of a well-known contract (router, multisig, wrapped native token, etc.)
+/// as it is stored in the known contracts directory.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct KnownContractEntry {
+    /// Label returned to the clients, e.g. "Uniswap V2: Router 2"
+    label: String,
+    /// Deployed bytecodes of the contract. Several bytecodes may be specified
+    /// if the contract has immutables which differ between the chains.
+    deployed_bytecodes: Vec<DisplayBytes>,
+    /// Verified sources in the same format as returned by successful verifications
+    source: Source,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KnownContract {
+    pub label: String,
+    pub source: Source,
+}
+
+/// Database of well-known contracts, which bytecodes are verified without compilation.
+#[derive(Debug, Default)]
+pub struct KnownContracts {
+    contracts: HashMap<Bytes, Arc<KnownContract>>,
+}
+
+impl KnownContracts {
+    pub async fn new(settings: &KnownContractsSettings) -> anyhow::Result<Self> {
+        let mut known_contracts = Self::default();
+        let mut entries = tokio::fs::read_dir(&settings.dir)
+            .await
+            .with_context(|| format!("cannot read known contracts directory {:?}", settings.dir))?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path
+                .extension()
+                .map_or(false, |extension| extension == "json")
+            {
+                let content = tokio::fs::read(&path).await?;
+                known_contracts.insert(&path, &content)?;
+            }
+        }
+        tracing::info!(
+            "loaded {} known contract bytecodes",
+            known_contracts.contracts.len()
+        );
+        Ok(known_contracts)
+    }
+
+    fn insert(&mut self, path: &Path, content: &[u8]) -> anyhow::Result<()> {
+        let entry: KnownContractEntry = serde_json::from_slice(content)
+            .with_context(|| format!("invalid known contract description {path:?}"))?;
+        let contract = Arc::new(KnownContract {
+            label: entry.label,
+            source: entry.source,
+        });
+        for bytecode in entry.deployed_bytecodes {
+            if let Some(existing) = self.contracts.insert(bytecode.0, contract.clone()) {
+                return Err(anyhow::anyhow!(
+                    "{path:?}: bytecode is already registered for `{}`",
+                    existing.label
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the successful verification response if the deployed bytecode is a known one.
+    /// Creation inputs are not looked up, as they may contain constructor arguments.
+    pub fn verify(
+        &self,
+        creation_bytecode: Option<&Bytes>,
+        deployed_bytecode: &Bytes,
+    ) -> Option<VerifyResponseWrapper> {
+        if creation_bytecode.is_some() {
+            return None;
+        }
+        self.contracts
+            .get(deployed_bytecode)
+            .map(|contract| VerifyResponseWrapper::ok(contract.as_ref().clone()))
+    }
+}
+
+impl VerifyResponseOk for KnownContract {
+    fn result(self) -> (Source, ExtraData) {
+        let extra_data = ExtraData {
+            local_creation_input_parts: vec![],
+            local_deployed_bytecode_parts: vec![],
+            standards: vec![],
+            signed_record: None,
+            blueprint: None,
+            label: Some(self.label),
+            immutable_values: Default::default(),
+            library_addresses: Default::default(),
+            attestation: None,
+            natspec: None,
+            method_identifiers: Default::default(),
+            storage_layout: None,
+            gas_estimates: None,
+            risk_flags: None,
+        };
+        (self.source, extra_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    const WETH: &str = r#"{
+        "label": "WETH9",
+        "deployed_bytecodes": ["0x6060604052", "0x6080604052"],
+        "source": {
+            "fileName": "WETH9.sol",
+            "contractName": "WETH9",
+            "compilerVersion": "v0.4.19+commit.c4cbbb05",
+            "compilerSettings": "{}",
+            "sourceType": "SOLIDITY",
+            "sourceFiles": { "WETH9.sol": "contract WETH9 {}" },
+            "matchType": "FULL"
+        }
+    }"#;
+
+    fn bytes(value: &str) -> Bytes {
+        DisplayBytes::from_str(value).unwrap().0
+    }
+
+    #[test]
+    fn verify_known_bytecode() {
+        let mut known_contracts = KnownContracts::default();
+        known_contracts
+            .insert(Path::new("weth.json"), WETH.as_bytes())
+            .expect("valid description");
+
+        for bytecode in ["0x6060604052", "0x6080604052"] {
+            let response = known_contracts
+                .verify(None, &bytes(bytecode))
+                .expect("bytecode should be known")
+                .into_inner();
+            let source = response.source.expect("source is present");
+            assert_eq!("WETH9", source.contract_name);
+            assert_eq!(
+                Some("WETH9".to_string()),
+                response.extra_data.and_then(|extra_data| extra_data.label)
+            );
+        }
+
+        assert_eq!(None, known_contracts.verify(None, &bytes("0x6001")));
+        assert_eq!(
+            None,
+            known_contracts.verify(Some(&bytes("0x6060604052")), &Bytes::new()),
+            "creation inputs should not be looked up"
+        );
+    }
+
+    #[test]
+    fn duplicate_bytecodes_are_rejected() {
+        let mut known_contracts = KnownContracts::default();
+        known_contracts
+            .insert(Path::new("weth.json"), WETH.as_bytes())
+            .expect("valid description");
+        known_contracts
+            .insert(Path::new("weth_copy.json"), WETH.as_bytes())
+            .expect_err("bytecodes are already registered");
+    }
+}
@@ -1,12 +1,32 @@
+mod attestation;
+mod chains;
+mod debug_logging;
+mod error_catalog;
+mod failure_stats;
+mod fixtures;
+mod idempotency;
+mod known_contracts;
+mod load_shedding;
 mod metrics;
 mod proto;
 mod run;
 mod services;
 mod settings;
+mod signing;
+mod tenants;
+mod test_vector;
 mod types;
+mod usage;
+mod validation;
+mod warm_start;
 
 pub use run::run;
 pub use services::{
-    HealthService, SolidityVerifierService, SourcifyVerifierService, VyperVerifierService,
+    ChainsService, CompilersAdminService, ContractAnalyzerService, HealthService,
+    HuffVerifierService, SolidityVerifierService, SourcifyVerifierService,
+    VerificationFixturesService, VerificationRecordsService, VerificationStatsService,
+    VyperVerifierService,
 };
 pub use settings::Settings;
+pub use test_vector::{generate_test_vector, TestVector, TestVectorRequest};
+pub use warm_start::{export_manifest, warm_from_manifest};
@@ -0,0 +1,220 @@
+//! Latency tracking and overload shedding of the http endpoints.
+//!
+//! Latencies of the served requests are kept for the configured window, and their
+//! 95th percentile is reported per endpoint. When the compilation queue grows over
+//! the threshold, low priority requests (requests to the low priority routes, or the ones
+//! marked via the priority header) are rejected with `503 Service Unavailable` and
+//! `Retry-After` header, so that the latency of interactive requests stays bounded
+//! instead of growing for everyone.
+
+use crate::{metrics, settings::LoadSheddingSettings};
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{Service, ServiceRequest, ServiceResponse},
+    http::header,
+    HttpResponse,
+};
+use futures::future::{self, Either};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tonic::Code;
+
+/// Maximum number of latencies kept per endpoint, regardless of the window
+const MAX_SAMPLES: usize = 4096;
+
+pub struct LoadShedder {
+    enabled: bool,
+    max_queued_compilations: usize,
+    retry_after: Duration,
+    priority_header: String,
+    low_priority_routes: HashSet<String>,
+    latency_window: Duration,
+    latency_slo: Option<Duration>,
+    latencies: Mutex<HashMap<String, VecDeque<(Instant, Duration)>>>,
+}
+
+impl LoadShedder {
+    pub fn new(settings: &LoadSheddingSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            max_queued_compilations: settings.max_queued_compilations,
+            retry_after: Duration::from_secs(settings.retry_after),
+            priority_header: settings.priority_header.clone(),
+            low_priority_routes: settings.low_priority_routes.iter().cloned().collect(),
+            latency_window: Duration::from_secs(settings.latency_window),
+            latency_slo: settings.latency_slo.map(Duration::from_millis),
+            latencies: Default::default(),
+        }
+    }
+
+    /// Middleware processing the requests of the service. Is expected to be used via `wrap_fn`.
+    pub fn handle<S, B>(
+        self: &Arc<Self>,
+        request: ServiceRequest,
+        service: &S,
+    ) -> impl Future<Output = Result<ServiceResponse<EitherBody<B>>, actix_web::Error>>
+    where
+        S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+        B: MessageBody,
+    {
+        let low_priority = self.is_low_priority(
+            request.path(),
+            request
+                .headers()
+                .get(self.priority_header.as_str())
+                .and_then(|value| value.to_str().ok()),
+        );
+        if self.should_shed(
+            low_priority,
+            smart_contract_verifier::compilations_in_queue(),
+        ) {
+            // Arbitrary paths are not used as labels, so that the metrics are not polluted
+            let endpoint = request.match_pattern();
+            metrics::SHED_REQUESTS
+                .with_label_values(&[endpoint.as_deref().unwrap_or("unmatched")])
+                .inc();
+            let body = serde_json::json!({
+                "code": Code::Unavailable as i32,
+                "message": "service is overloaded, the request should be retried later",
+            });
+            let response = HttpResponse::ServiceUnavailable()
+                .insert_header((header::RETRY_AFTER, self.retry_after.as_secs()))
+                .json(body);
+            return Either::Left(future::ok(
+                request.into_response(response).map_into_right_body(),
+            ));
+        }
+
+        let shedder = self.clone();
+        let started = Instant::now();
+        let response = service.call(request);
+        Either::Right(async move {
+            let response = response.await?;
+            // Latencies are tracked for the known routes only,
+            // so that the metrics labels are not polluted by arbitrary paths
+            if let Some(endpoint) = response.request().match_pattern() {
+                shedder.report_latency(&endpoint, started.elapsed());
+            }
+            Ok(response.map_into_left_body())
+        })
+    }
+
+    fn is_low_priority(&self, path: &str, priority: Option<&str>) -> bool {
+        self.low_priority_routes.contains(path)
+            || priority.map_or(false, |priority| priority.eq_ignore_ascii_case("low"))
+    }
+
+    fn should_shed(&self, low_priority: bool, queued_compilations: usize) -> bool {
+        self.enabled && low_priority && queued_compilations > self.max_queued_compilations
+    }
+
+    fn report_latency(&self, endpoint: &str, latency: Duration) {
+        let p95 = self.record_latency(endpoint, latency, Instant::now());
+        metrics::ENDPOINT_LATENCY_P95
+            .with_label_values(&[endpoint])
+            .set(p95.as_secs_f64());
+        if let Some(slo) = self.latency_slo {
+            metrics::ENDPOINT_SLO_BREACHED
+                .with_label_values(&[endpoint])
+                .set((p95 > slo) as i64);
+        }
+    }
+
+    /// Records the latency of the endpoint and returns
+    /// the 95th percentile of the latencies inside the window.
+    fn record_latency(&self, endpoint: &str, latency: Duration, now: Instant) -> Duration {
+        let mut latencies = self.latencies.lock().expect("latencies lock is poisoned");
+        let samples = latencies.entry(endpoint.to_string()).or_default();
+        samples.push_back((now, latency));
+        while let Some((recorded_at, _)) = samples.front() {
+            let expired = now.duration_since(*recorded_at) > self.latency_window;
+            if !expired && samples.len() <= MAX_SAMPLES {
+                break;
+            }
+            samples.pop_front();
+        }
+        percentile(samples.iter().map(|(_, latency)| *latency), 0.95)
+    }
+}
+
+/// Nearest-rank percentile. Returns zero if there are no values.
+fn percentile(values: impl Iterator<Item = Duration>, p: f64) -> Duration {
+    let mut values: Vec<_> = values.collect();
+    if values.is_empty() {
+        return Duration::ZERO;
+    }
+    values.sort();
+    let rank = (values.len() as f64 * p).ceil() as usize;
+    values[rank.clamp(1, values.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn shedder(enabled: bool) -> LoadShedder {
+        LoadShedder::new(&LoadSheddingSettings {
+            enabled,
+            max_queued_compilations: 10,
+            latency_window: 60,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn shed_low_priority_requests() {
+        let shedder = shedder(true);
+        let batch = "/api/v2/verifier/solidity/sources:verify-standard-json-batch";
+        let multi_part = "/api/v2/verifier/solidity/sources:verify-multi-part";
+
+        assert!(shedder.is_low_priority(batch, None), "low priority route");
+        assert!(shedder.is_low_priority(multi_part, Some("low")), "header");
+        assert!(!shedder.is_low_priority(multi_part, Some("high")));
+        assert!(!shedder.is_low_priority(multi_part, None));
+
+        assert!(shedder.should_shed(true, 11));
+        assert!(!shedder.should_shed(true, 10), "queue is not overloaded");
+        assert!(!shedder.should_shed(false, 11), "interactive request");
+        assert!(
+            !self::shedder(false).should_shed(true, 11),
+            "shedding is disabled"
+        );
+    }
+
+    #[test]
+    fn rolling_p95_latency() {
+        let shedder = shedder(false);
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+
+        for i in 1..=100 {
+            shedder.record_latency("endpoint", ms(i), start);
+        }
+        assert_eq!(ms(95), shedder.record_latency("endpoint", ms(1), start));
+        assert_eq!(
+            ms(1),
+            shedder.record_latency("other", ms(1), start),
+            "endpoints are tracked separately"
+        );
+
+        let later = start + Duration::from_secs(61);
+        assert_eq!(
+            ms(5),
+            shedder.record_latency("endpoint", ms(5), later),
+            "latencies outside the window are discarded"
+        );
+    }
+
+    #[test]
+    fn nearest_rank_percentile() {
+        let ms = Duration::from_millis;
+        assert_eq!(Duration::ZERO, percentile(std::iter::empty(), 0.95));
+        assert_eq!(ms(3), percentile([ms(3)].into_iter(), 0.95));
+        assert_eq!(ms(1), percentile([ms(2), ms(1)].into_iter(), 0.5));
+    }
+}
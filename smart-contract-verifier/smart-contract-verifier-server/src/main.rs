@@ -1,7 +1,22 @@
 use smart_contract_verifier_server::Settings;
+use std::path::Path;
 
+/// Starts the server. `export-compilers <manifest>` writes the manifest of the cached
+/// compilers instead, and `warm-compilers <manifest>` downloads the compilers listed in it.
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     let settings = Settings::new().expect("failed to read config");
-    smart_contract_verifier_server::run(settings).await
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [] => smart_contract_verifier_server::run(settings).await,
+        ["export-compilers", manifest] => {
+            smart_contract_verifier_server::export_manifest(settings, Path::new(manifest)).await
+        }
+        ["warm-compilers", manifest] => {
+            smart_contract_verifier_server::warm_from_manifest(settings, Path::new(manifest)).await
+        }
+        _ => anyhow::bail!(
+            "usage: smart-contract-verifier-server [export-compilers <manifest> | warm-compilers <manifest>]"
+        ),
+    }
 }
@@ -1,17 +1,93 @@
 use lazy_static::lazy_static;
-use prometheus::{register_int_counter_vec, IntCounterVec};
+use prometheus::{
+    register_counter_vec, register_gauge_vec, register_int_counter_vec, register_int_gauge_vec,
+    CounterVec, GaugeVec, IntCounterVec, IntGaugeVec,
+};
+use smart_contract_verifier::CompilationUsage;
 
 lazy_static! {
     pub static ref VERIFICATION: IntCounterVec = register_int_counter_vec!(
         "smart_contract_verifier_verify_contract",
         "number of contract verifications",
-        &["language", "endpoint", "status"],
+        &["language", "endpoint", "status", "tenant"],
+    )
+    .unwrap();
+    pub static ref VERIFICATION_FAILURES: IntCounterVec = register_int_counter_vec!(
+        "smart_contract_verifier_verification_failures",
+        "number of failed contract verifications by the most likely mistake",
+        &["language", "category", "tenant"],
+    )
+    .unwrap();
+    pub static ref COMPILER_INVOCATIONS: IntCounterVec = register_int_counter_vec!(
+        "smart_contract_verifier_usage_compiler_invocations",
+        "number of compiler invocations by the verifications",
+        &["language", "tenant"],
+    )
+    .unwrap();
+    pub static ref COMPILATION_SECONDS: CounterVec = register_counter_vec!(
+        "smart_contract_verifier_usage_compilation_seconds",
+        "time the compiler processes of the verifications have been running in seconds",
+        &["language", "tenant"],
+    )
+    .unwrap();
+    pub static ref BYTES_COMPILED: IntCounterVec = register_int_counter_vec!(
+        "smart_contract_verifier_usage_bytes_compiled",
+        "total size of the sources compiled by the verifications in bytes",
+        &["language", "tenant"],
+    )
+    .unwrap();
+    pub static ref BILLING_HOOK_FAILURES: IntCounterVec = register_int_counter_vec!(
+        "smart_contract_verifier_billing_hook_failures",
+        "number of usage records which could not be delivered to the billing hook",
+        &["tenant"],
+    )
+    .unwrap();
+    pub static ref ENDPOINT_LATENCY_P95: GaugeVec = register_gauge_vec!(
+        "smart_contract_verifier_endpoint_latency_p95_seconds",
+        "rolling 95th percentile of the endpoint latency in seconds",
+        &["endpoint"],
+    )
+    .unwrap();
+    pub static ref ENDPOINT_SLO_BREACHED: IntGaugeVec = register_int_gauge_vec!(
+        "smart_contract_verifier_endpoint_latency_slo_breached",
+        "1 if the rolling 95th percentile of the endpoint latency exceeds the target",
+        &["endpoint"],
+    )
+    .unwrap();
+    pub static ref SHED_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "smart_contract_verifier_shed_requests",
+        "number of low priority requests rejected due to the overload",
+        &["endpoint"],
+    )
+    .unwrap();
+    pub static ref IDEMPOTENT_REPLAYS: IntCounterVec = register_int_counter_vec!(
+        "smart_contract_verifier_idempotent_replays",
+        "number of requests answered with the response to the request with the same idempotency key",
+        &["endpoint"],
     )
     .unwrap();
 }
 
-pub fn count_verify_contract(language: &str, status: &str, method: &str) {
+pub fn count_verify_contract(language: &str, status: &str, method: &str, tenant: &str) {
     VERIFICATION
-        .with_label_values(&[language, method, status])
+        .with_label_values(&[language, method, status, tenant])
         .inc();
 }
+
+pub fn count_verification_failure(language: &str, category: &str, tenant: &str) {
+    VERIFICATION_FAILURES
+        .with_label_values(&[language, category, tenant])
+        .inc();
+}
+
+pub fn count_compilation_usage(language: &str, tenant: &str, usage: &CompilationUsage) {
+    COMPILER_INVOCATIONS
+        .with_label_values(&[language, tenant])
+        .inc_by(usage.compiler_invocations);
+    COMPILATION_SECONDS
+        .with_label_values(&[language, tenant])
+        .inc_by(usage.compilation_time.as_secs_f64());
+    BYTES_COMPILED
+        .with_label_values(&[language, tenant])
+        .inc_by(usage.bytes_compiled);
+}
@@ -1,8 +1,29 @@
 pub use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2::{
-    health_actix, health_check_response, health_server, solidity_verifier_actix,
-    solidity_verifier_server, source, sourcify_verifier_actix, sourcify_verifier_server,
-    verify_response, vyper_verifier_actix, vyper_verifier_server, BytecodeType, HealthCheckRequest,
-    HealthCheckResponse, ListCompilerVersionsRequest, ListCompilerVersionsResponse, Source,
-    VerifyResponse, VerifySolidityMultiPartRequest, VerifySolidityStandardJsonRequest,
+    chain, chains_actix, chains_server, check_token_metadata_response, compare_bytecodes_response,
+    compilers_admin_actix, compilers_admin_server, contract_analyzer_actix,
+    contract_analyzer_server, diff_contracts_response, get_failure_stats_response,
+    get_usage_response, health_actix, health_check_response, health_server, huff_verifier_actix,
+    huff_verifier_server, list_cached_compilers_response, list_signing_keys_response,
+    prewarm_compilers_response, solidity_verifier_actix, solidity_verifier_server, source,
+    sourcify_verifier_actix, sourcify_verifier_server, verification_fixtures_actix,
+    verification_fixtures_server, verification_records_actix, verification_records_server,
+    verification_stats_actix, verification_stats_server, verification_trace, verify_batch_response,
+    verify_record_signature_response, verify_response, verify_solidity_github_repository_request,
+    vyper_verifier_actix, vyper_verifier_server, BytecodeRange, BytecodeType, Chain,
+    CheckTokenMetadataRequest, CheckTokenMetadataResponse, CompareBytecodesRequest,
+    CompareBytecodesResponse, DetectVyperCompilerVersionRequest,
+    DetectVyperCompilerVersionResponse, DiffContractsRequest, DiffContractsResponse,
+    EvictCachedCompilersRequest, EvictCachedCompilersResponse, GetChainRequest,
+    GetFailureStatsRequest, GetFailureStatsResponse, GetUsageRequest, GetUsageResponse,
+    GetVerificationFixtureRequest, HealthCheckRequest, HealthCheckResponse,
+    ListCachedCompilersRequest, ListCachedCompilersResponse, ListChainsRequest, ListChainsResponse,
+    ListCompilerVersionsRequest, ListCompilerVersionsResponse, ListSigningKeysRequest,
+    ListSigningKeysResponse, ListVerificationFixturesRequest, ListVerificationFixturesResponse,
+    PrewarmCompilersRequest, PrewarmCompilersResponse, RefreshCompilerVersionsRequest,
+    RefreshCompilerVersionsResponse, Source, VerificationFixture, VerificationTarget,
+    VerificationTrace, VerifyBatchResponse, VerifyHuffMultiPartRequest,
+    VerifyRecordSignatureRequest, VerifyRecordSignatureResponse, VerifyResponse,
+    VerifySolidityGithubRepositoryRequest, VerifySolidityMultiPartRequest,
+    VerifySolidityStandardJsonBatchRequest, VerifySolidityStandardJsonRequest,
     VerifySourcifyRequest, VerifyVyperMultiPartRequest,
 };
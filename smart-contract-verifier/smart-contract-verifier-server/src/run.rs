@@ -1,16 +1,43 @@
 use crate::{
+    chains,
+    debug_logging::BodyLogger,
+    failure_stats::FailureStats,
+    idempotency::IdempotencyKeys,
+    known_contracts::KnownContracts,
+    load_shedding::LoadShedder,
     proto::{
-        health_actix::route_health, health_server::HealthServer,
-        solidity_verifier_actix::route_solidity_verifier,
+        chains_actix::route_chains, chains_server::ChainsServer,
+        compilers_admin_actix::route_compilers_admin, compilers_admin_server::CompilersAdminServer,
+        contract_analyzer_actix::route_contract_analyzer,
+        contract_analyzer_server::ContractAnalyzerServer, health_actix::route_health,
+        health_server::HealthServer, huff_verifier_actix::route_huff_verifier,
+        huff_verifier_server::HuffVerifierServer, solidity_verifier_actix::route_solidity_verifier,
         solidity_verifier_server::SolidityVerifierServer,
         sourcify_verifier_actix::route_sourcify_verifier,
         sourcify_verifier_server::SourcifyVerifierServer,
+        verification_fixtures_actix::route_verification_fixtures,
+        verification_fixtures_server::VerificationFixturesServer,
+        verification_records_actix::route_verification_records,
+        verification_records_server::VerificationRecordsServer,
+        verification_stats_actix::route_verification_stats,
+        verification_stats_server::VerificationStatsServer,
         vyper_verifier_actix::route_vyper_verifier, vyper_verifier_server::VyperVerifierServer,
     },
     services::{
-        HealthService, SolidityVerifierService, SourcifyVerifierService, VyperVerifierService,
+        ChainsService, CompilersAdminService, ContractAnalyzerService, HealthService,
+        HuffVerifierService, SolidityVerifierService, SourcifyVerifierService,
+        VerificationFixturesService, VerificationRecordsService, VerificationStatsService,
+        VyperVerifierService,
     },
     settings::Settings,
+    signing::RecordSigner,
+    tenants::Tenants,
+    usage::UsageMeter,
+    validation,
+};
+use actix_web::{
+    middleware::Compress,
+    web::{self, JsonConfig, ServiceConfig},
 };
 use blockscout_service_launcher::LaunchSettings;
 use std::sync::Arc;
@@ -20,14 +47,49 @@ use tokio::sync::Semaphore;
 struct HttpRouter {
     solidity_verifier: Option<Arc<SolidityVerifierService>>,
     vyper_verifier: Option<Arc<VyperVerifierService>>,
+    huff_verifier: Option<Arc<HuffVerifierService>>,
     sourcify_verifier: Option<Arc<SourcifyVerifierService>>,
+    contract_analyzer: Option<Arc<ContractAnalyzerService>>,
+    verification_records: Option<Arc<VerificationRecordsService>>,
+    compilers_admin: Option<Arc<CompilersAdminService>>,
+    verification_stats: Option<Arc<VerificationStatsService>>,
+    verification_fixtures: Option<Arc<VerificationFixturesService>>,
+    chains: Arc<ChainsService>,
     health: Arc<HealthService>,
+    json_config: JsonConfig,
+    load_shedder: Arc<LoadShedder>,
+    idempotency_keys: Arc<IdempotencyKeys>,
+    body_logger: Arc<BodyLogger>,
 }
 
 impl blockscout_service_launcher::HttpRouter for HttpRouter {
-    fn register_routes(&self, service_config: &mut actix_web::web::ServiceConfig) {
+    fn register_routes(&self, service_config: &mut ServiceConfig) {
+        // Compressed (gzip, zstd, brotli) request bodies are decoded by the json extractor,
+        // whose limit applies to the decoded body. Responses are compressed
+        // according to the `Accept-Encoding` header of the request. Responses kept
+        // for the idempotency keys are stored, and bodies of the debug requests are logged,
+        // before the compression.
+        let load_shedder = self.load_shedder.clone();
+        let idempotency_keys = self.idempotency_keys.clone();
+        let body_logger = self.body_logger.clone();
+        service_config.service(
+            web::scope("")
+                .app_data(self.json_config.clone())
+                .wrap_fn(move |request, service| idempotency_keys.handle(request, service))
+                .wrap_fn(move |request, service| body_logger.handle(request, service))
+                .wrap(Compress::default())
+                .wrap_fn(move |request, service| load_shedder.handle(request, service))
+                .configure(|config| self.configure_routes(config)),
+        );
+    }
+}
+
+impl HttpRouter {
+    fn configure_routes(&self, service_config: &mut ServiceConfig) {
         let service_config =
             service_config.configure(|config| route_health(config, self.health.clone()));
+        let service_config =
+            service_config.configure(|config| route_chains(config, self.chains.clone()));
 
         let service_config = if let Some(solidity) = &self.solidity_verifier {
             service_config.configure(|config| route_solidity_verifier(config, solidity.clone()))
@@ -39,11 +101,41 @@ impl blockscout_service_launcher::HttpRouter for HttpRouter {
         } else {
             service_config
         };
+        let service_config = if let Some(huff) = &self.huff_verifier {
+            service_config.configure(|config| route_huff_verifier(config, huff.clone()))
+        } else {
+            service_config
+        };
         let service_config = if let Some(sourcify) = &self.sourcify_verifier {
             service_config.configure(|config| route_sourcify_verifier(config, sourcify.clone()))
         } else {
             service_config
         };
+        let service_config = if let Some(analyzer) = &self.contract_analyzer {
+            service_config.configure(|config| route_contract_analyzer(config, analyzer.clone()))
+        } else {
+            service_config
+        };
+        let service_config = if let Some(records) = &self.verification_records {
+            service_config.configure(|config| route_verification_records(config, records.clone()))
+        } else {
+            service_config
+        };
+        let service_config = if let Some(admin) = &self.compilers_admin {
+            service_config.configure(|config| route_compilers_admin(config, admin.clone()))
+        } else {
+            service_config
+        };
+        let service_config = if let Some(stats) = &self.verification_stats {
+            service_config.configure(|config| route_verification_stats(config, stats.clone()))
+        } else {
+            service_config
+        };
+        let service_config = if let Some(fixtures) = &self.verification_fixtures {
+            service_config.configure(|config| route_verification_fixtures(config, fixtures.clone()))
+        } else {
+            service_config
+        };
 
         let _ = service_config;
     }
@@ -52,18 +144,57 @@ impl blockscout_service_launcher::HttpRouter for HttpRouter {
 fn grpc_router(
     solidity_verifier: Option<Arc<SolidityVerifierService>>,
     vyper_verifier: Option<Arc<VyperVerifierService>>,
+    huff_verifier: Option<Arc<HuffVerifierService>>,
     sourcify_verifier: Option<Arc<SourcifyVerifierService>>,
+    contract_analyzer: Option<Arc<ContractAnalyzerService>>,
+    verification_records: Option<Arc<VerificationRecordsService>>,
+    compilers_admin: Option<Arc<CompilersAdminService>>,
+    verification_stats: Option<Arc<VerificationStatsService>>,
+    verification_fixtures: Option<Arc<VerificationFixturesService>>,
+    chains: Arc<ChainsService>,
     health: Arc<HealthService>,
 ) -> tonic::transport::server::Router {
     tonic::transport::Server::builder()
         .add_service(HealthServer::from_arc(health))
+        .add_service(ChainsServer::from_arc(chains))
         .add_optional_service(solidity_verifier.map(SolidityVerifierServer::from_arc))
         .add_optional_service(vyper_verifier.map(VyperVerifierServer::from_arc))
+        .add_optional_service(huff_verifier.map(HuffVerifierServer::from_arc))
         .add_optional_service(sourcify_verifier.map(SourcifyVerifierServer::from_arc))
+        .add_optional_service(contract_analyzer.map(ContractAnalyzerServer::from_arc))
+        .add_optional_service(verification_records.map(VerificationRecordsServer::from_arc))
+        .add_optional_service(compilers_admin.map(CompilersAdminServer::from_arc))
+        .add_optional_service(verification_stats.map(VerificationStatsServer::from_arc))
+        .add_optional_service(verification_fixtures.map(VerificationFixturesServer::from_arc))
 }
 
 pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
     let compilers_lock = Arc::new(Semaphore::new(settings.compilers.max_threads.get()));
+    let signer = match settings.signing.enabled {
+        true => Some(Arc::new(RecordSigner::new(&settings.signing)?)),
+        false => None,
+    };
+    let chains = Arc::new(chains::registry(&settings.chains)?);
+    let tenants = match settings.tenants.enabled {
+        true => Some(Arc::new(Tenants::new(&settings.tenants, &chains)?)),
+        false => None,
+    };
+    let known_contracts = match settings.known_contracts.enabled {
+        true => Some(Arc::new(
+            KnownContracts::new(&settings.known_contracts).await?,
+        )),
+        false => None,
+    };
+    let failure_stats = settings
+        .failure_stats
+        .enabled
+        .then(|| Arc::new(FailureStats::default()));
+    let usage_meter = settings
+        .usage
+        .enabled
+        .then(|| UsageMeter::new(&settings.usage))
+        .transpose()?
+        .map(Arc::new);
 
     let solidity_verifier = match settings.solidity.enabled {
         true => Some(Arc::new(
@@ -72,7 +203,12 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
                 compilers_lock.clone(),
                 settings.extensions.solidity,
             )
-            .await?,
+            .await?
+            .with_signer(signer.clone())
+            .with_tenants(tenants.clone())
+            .with_known_contracts(known_contracts.clone())
+            .with_failure_stats(failure_stats.clone())
+            .with_usage_meter(usage_meter.clone()),
         )),
         false => None,
     };
@@ -83,28 +219,105 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
                 compilers_lock.clone(),
                 settings.extensions.vyper,
             )
-            .await?,
+            .await?
+            .with_signer(signer.clone())
+            .with_tenants(tenants.clone())
+            .with_known_contracts(known_contracts.clone())
+            .with_failure_stats(failure_stats.clone())
+            .with_usage_meter(usage_meter.clone()),
+        )),
+        false => None,
+    };
+    let huff_verifier = match settings.huff.enabled {
+        true => Some(Arc::new(
+            HuffVerifierService::new(
+                settings.huff,
+                compilers_lock.clone(),
+                settings.extensions.huff,
+            )
+            .await?
+            .with_signer(signer.clone())
+            .with_tenants(tenants.clone())
+            .with_known_contracts(known_contracts.clone())
+            .with_usage_meter(usage_meter.clone()),
         )),
         false => None,
     };
     let sourcify_verifier = match settings.sourcify.enabled {
         true => Some(Arc::new(
-            SourcifyVerifierService::new(settings.sourcify, settings.extensions.sourcify).await?,
+            SourcifyVerifierService::new(settings.sourcify, settings.extensions.sourcify)
+                .await?
+                .with_signer(signer.clone())
+                .with_tenants(tenants.clone()),
+        )),
+        false => None,
+    };
+    let contract_analyzer = settings
+        .analyzer
+        .enabled
+        .then(|| Arc::new(ContractAnalyzerService::default()));
+    let verification_records =
+        signer.map(|signer| Arc::new(VerificationRecordsService::new(signer)));
+    let compilers_admin = match settings.admin.enabled {
+        true => Some(Arc::new(
+            CompilersAdminService::new(&settings.admin)?
+                .with_solidity(solidity_verifier.as_ref().map(|service| service.client()))
+                .with_vyper(vyper_verifier.as_ref().map(|service| service.client()))
+                .with_huff(huff_verifier.as_ref().map(|service| service.client())),
         )),
         false => None,
     };
+    let verification_stats = (failure_stats.is_some() || usage_meter.is_some()).then(|| {
+        Arc::new(
+            VerificationStatsService::new(failure_stats, usage_meter)
+                .with_tenants(tenants.clone())
+                .with_admin(&settings.admin),
+        )
+    });
+    let verification_fixtures = settings
+        .fixtures
+        .enabled
+        .then(|| Arc::new(VerificationFixturesService::default()));
+    let chains = Arc::new(ChainsService::new(chains));
     let health = Arc::new(HealthService::default());
     let grpc_router = grpc_router(
         solidity_verifier.clone(),
         vyper_verifier.clone(),
+        huff_verifier.clone(),
         sourcify_verifier.clone(),
+        contract_analyzer.clone(),
+        verification_records.clone(),
+        compilers_admin.clone(),
+        verification_stats.clone(),
+        verification_fixtures.clone(),
+        chains.clone(),
         health.clone(),
     );
     let http_router = HttpRouter {
         solidity_verifier,
         vyper_verifier,
+        huff_verifier,
         sourcify_verifier,
+        contract_analyzer,
+        verification_records,
+        compilers_admin,
+        verification_stats,
+        verification_fixtures,
+        chains,
         health,
+        json_config: JsonConfig::default()
+            .limit(settings.compression.max_decompressed_size)
+            .error_handler(validation::json_error_handler),
+        load_shedder: Arc::new(LoadShedder::new(&settings.load_shedding)),
+        idempotency_keys: Arc::new(IdempotencyKeys::new(
+            &settings.idempotency,
+            &settings.tenants,
+        )),
+        body_logger: Arc::new(BodyLogger::new(
+            &settings.debug_logging,
+            &settings.tenants,
+            &settings.admin,
+        )),
     };
     let launch_settings = LaunchSettings {
         service_name: "smart_contract_verifier".to_owned(),
@@ -0,0 +1,70 @@
+use crate::proto::{
+    chain::ForkActivation, chains_server::Chains, Chain, GetChainRequest, ListChainsRequest,
+    ListChainsResponse,
+};
+use smart_contract_verifier::{chains::Activation, ChainRegistry};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+pub struct ChainsService {
+    chains: Arc<ChainRegistry>,
+}
+
+impl ChainsService {
+    pub fn new(chains: Arc<ChainRegistry>) -> Self {
+        Self { chains }
+    }
+}
+
+#[async_trait::async_trait]
+impl Chains for ChainsService {
+    async fn list_chains(
+        &self,
+        _request: Request<ListChainsRequest>,
+    ) -> Result<Response<ListChainsResponse>, Status> {
+        let chains = self.chains.chains().map(chain).collect();
+        Ok(Response::new(ListChainsResponse { chains }))
+    }
+
+    async fn get_chain(
+        &self,
+        request: Request<GetChainRequest>,
+    ) -> Result<Response<Chain>, Status> {
+        let chain_id = request.into_inner().chain_id;
+        self.chains
+            .get(chain_id)
+            .map(|value| Response::new(chain(&value)))
+            .ok_or_else(|| Status::not_found(format!("unknown chain {chain_id}")))
+    }
+}
+
+fn chain(chain: &smart_contract_verifier::Chain) -> Chain {
+    let forks = chain
+        .forks
+        .iter()
+        .map(|fork| {
+            let (block, timestamp) = match fork.activation {
+                Activation::Block(block) => (Some(block), None),
+                Activation::Timestamp(timestamp) => (None, Some(timestamp)),
+            };
+            ForkActivation {
+                evm_version: fork.fork.to_string(),
+                block,
+                timestamp,
+            }
+        })
+        .collect();
+    Chain {
+        id: chain.id,
+        name: chain.name.clone(),
+        explorer_url: chain.explorer_url.as_ref().map(ToString::to_string),
+        forks,
+        current_evm_version: chain.current_evm_fork().map(|fork| fork.to_string()),
+        normalization_passes: chain
+            .normalization
+            .names()
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+    }
+}
@@ -0,0 +1,232 @@
+//! Management of the downloaded compilers by the service operators.
+//!
+//! All requests should contain one of the configured admin api keys in the corresponding header.
+
+use crate::{
+    proto::{
+        compilers_admin_server::CompilersAdmin, list_cached_compilers_response::CachedCompiler,
+        prewarm_compilers_response, EvictCachedCompilersRequest, EvictCachedCompilersResponse,
+        ListCachedCompilersRequest, ListCachedCompilersResponse, PrewarmCompilersRequest,
+        PrewarmCompilersResponse, RefreshCompilerVersionsRequest, RefreshCompilerVersionsResponse,
+    },
+    settings::AdminSettings,
+    validation::{ErrorCode, ValidationError},
+};
+use smart_contract_verifier::{
+    Compilers, EvmCompiler, HuffClient, SolidityClient, Version, VyperClient,
+};
+use std::{collections::HashSet, str::FromStr, sync::Arc, time::UNIX_EPOCH};
+use tonic::{Request, Response, Status};
+
+pub struct CompilersAdminService {
+    api_key_header: String,
+    api_keys: HashSet<String>,
+    solidity: Option<Arc<SolidityClient>>,
+    vyper: Option<Arc<VyperClient>>,
+    huff: Option<Arc<HuffClient>>,
+}
+
+impl CompilersAdminService {
+    pub fn new(settings: &AdminSettings) -> anyhow::Result<Self> {
+        if settings.api_keys.is_empty() {
+            anyhow::bail!("admin api is enabled, but no api keys are specified");
+        }
+        Ok(Self {
+            api_key_header: settings.api_key_header.clone(),
+            api_keys: settings.api_keys.iter().cloned().collect(),
+            solidity: None,
+            vyper: None,
+            huff: None,
+        })
+    }
+
+    pub fn with_solidity(mut self, client: Option<Arc<SolidityClient>>) -> Self {
+        self.solidity = client;
+        self
+    }
+
+    pub fn with_vyper(mut self, client: Option<Arc<VyperClient>>) -> Self {
+        self.vyper = client;
+        self
+    }
+
+    pub fn with_huff(mut self, client: Option<Arc<HuffClient>>) -> Self {
+        self.huff = client;
+        self
+    }
+
+    fn authenticate<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let api_key = request
+            .metadata()
+            .get(&self.api_key_header)
+            .ok_or_else(|| Status::unauthenticated("admin api key is not specified"))?
+            .to_str()
+            .map_err(|_| Status::unauthenticated("invalid admin api key"))?;
+        if !self.api_keys.contains(api_key) {
+            return Err(Status::permission_denied("unknown admin api key"));
+        }
+        Ok(())
+    }
+}
+
+/// Dispatches the call to the compilers of the requested language.
+macro_rules! with_compilers {
+    ($service:expr, $language:expr, |$compilers:ident| $body:expr) => {
+        match $language {
+            "solidity" => {
+                let $compilers = enabled($service.solidity.as_ref(), $language)?.compilers();
+                $body
+            }
+            "vyper" => {
+                let $compilers = enabled($service.vyper.as_ref(), $language)?.compilers();
+                $body
+            }
+            "huff" => {
+                let $compilers = enabled($service.huff.as_ref(), $language)?.compilers();
+                $body
+            }
+            language => {
+                return Err(ValidationError::new(
+                    ErrorCode::InvalidValue,
+                    "language",
+                    format!("unknown language `{language}`: expected one of solidity, vyper, huff"),
+                )
+                .into())
+            }
+        }
+    };
+}
+
+fn enabled<'a, T>(client: Option<&'a Arc<T>>, language: &str) -> Result<&'a T, Status> {
+    client
+        .map(AsRef::as_ref)
+        .ok_or_else(|| Status::failed_precondition(format!("{language} verifier is disabled")))
+}
+
+fn parse_versions(versions: &[String]) -> Result<Vec<Version>, Status> {
+    versions
+        .iter()
+        .map(|version| {
+            Version::from_str(version).map_err(|err| {
+                Status::from(ValidationError::new(
+                    ErrorCode::InvalidCompilerVersion,
+                    "versions",
+                    format!("Invalid compiler version `{version}`: {err}"),
+                ))
+            })
+        })
+        .collect()
+}
+
+async fn list_cached<C: EvmCompiler>(compilers: &Compilers<C>) -> Vec<CachedCompiler> {
+    compilers
+        .cached_compilers()
+        .await
+        .into_iter()
+        .map(|compiler| CachedCompiler {
+            version: compiler.version.to_string(),
+            size: compiler.size,
+            last_used_at: compiler
+                .last_used
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs()),
+        })
+        .collect()
+}
+
+async fn evict<C: EvmCompiler>(
+    compilers: &Compilers<C>,
+    versions: Vec<Version>,
+) -> Result<EvictCachedCompilersResponse, Status> {
+    let mut response = EvictCachedCompilersResponse::default();
+    for version in versions {
+        let evicted = compilers.evict(&version).await.map_err(|err| {
+            Status::internal(format!("failed to evict compiler {version}: {err}"))
+        })?;
+        match evicted {
+            true => response.evicted.push(version.to_string()),
+            false => response.not_cached.push(version.to_string()),
+        }
+    }
+    tracing::info!(
+        evicted = ?response.evicted,
+        not_cached = ?response.not_cached,
+        "compilers evicted by admin request"
+    );
+    Ok(response)
+}
+
+async fn prewarm<C: EvmCompiler>(
+    compilers: &Compilers<C>,
+    versions: Vec<Version>,
+) -> Vec<prewarm_compilers_response::Result> {
+    compilers
+        .prewarm(&versions)
+        .await
+        .into_iter()
+        .map(|(version, result)| prewarm_compilers_response::Result {
+            version: version.to_string(),
+            error: result.err().map(|err| err.to_string()),
+        })
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl CompilersAdmin for CompilersAdminService {
+    async fn list_cached_compilers(
+        &self,
+        request: Request<ListCachedCompilersRequest>,
+    ) -> Result<Response<ListCachedCompilersResponse>, Status> {
+        self.authenticate(&request)?;
+        let request = request.into_inner();
+
+        let compilers = with_compilers!(self, request.language.as_str(), |compilers| {
+            list_cached(compilers).await
+        });
+        Ok(Response::new(ListCachedCompilersResponse { compilers }))
+    }
+
+    async fn evict_cached_compilers(
+        &self,
+        request: Request<EvictCachedCompilersRequest>,
+    ) -> Result<Response<EvictCachedCompilersResponse>, Status> {
+        self.authenticate(&request)?;
+        let request = request.into_inner();
+        let versions = parse_versions(&request.versions)?;
+
+        let response = with_compilers!(self, request.language.as_str(), |compilers| {
+            evict(compilers, versions).await?
+        });
+        Ok(Response::new(response))
+    }
+
+    async fn prewarm_compilers(
+        &self,
+        request: Request<PrewarmCompilersRequest>,
+    ) -> Result<Response<PrewarmCompilersResponse>, Status> {
+        self.authenticate(&request)?;
+        let request = request.into_inner();
+        let versions = parse_versions(&request.versions)?;
+
+        let results = with_compilers!(self, request.language.as_str(), |compilers| {
+            prewarm(compilers, versions).await
+        });
+        Ok(Response::new(PrewarmCompilersResponse { results }))
+    }
+
+    async fn refresh_compiler_versions(
+        &self,
+        request: Request<RefreshCompilerVersionsRequest>,
+    ) -> Result<Response<RefreshCompilerVersionsResponse>, Status> {
+        self.authenticate(&request)?;
+        let request = request.into_inner();
+
+        let compiler_versions = with_compilers!(self, request.language.as_str(), |compilers| {
+            compilers.refresh_versions().await;
+            compilers.all_versions_sorted_str()
+        });
+        Ok(Response::new(RefreshCompilerVersionsResponse {
+            compiler_versions,
+        }))
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    proto::{
+        contract_analyzer_server::ContractAnalyzer, CheckTokenMetadataRequest,
+        CheckTokenMetadataResponse, CompareBytecodesRequest, CompareBytecodesResponse,
+        DiffContractsRequest, DiffContractsResponse,
+    },
+    types::{
+        CheckTokenMetadataRequestWrapper, CheckTokenMetadataResponseWrapper, CompareBytecodes,
+        CompareBytecodesRequestWrapper, CompareBytecodesResponseWrapper, DiffContracts,
+        DiffContractsRequestWrapper, DiffContractsResponseWrapper,
+    },
+    validation::{ErrorCode, ValidationError},
+};
+use smart_contract_verifier::analyzer::{abi_diff, bytecode_comparison, token_metadata};
+use tonic::{Request, Response, Status};
+
+#[derive(Default)]
+pub struct ContractAnalyzerService {}
+
+#[async_trait::async_trait]
+impl ContractAnalyzer for ContractAnalyzerService {
+    async fn check_token_metadata(
+        &self,
+        request: Request<CheckTokenMetadataRequest>,
+    ) -> Result<Response<CheckTokenMetadataResponse>, Status> {
+        let request: CheckTokenMetadataRequestWrapper = request.into_inner().into();
+        let check_request: token_metadata::CheckRequest = request.try_into()?;
+        let discrepancies = token_metadata::check_token_metadata(&check_request)
+            .map_err(|err| ValidationError::new(ErrorCode::InvalidValue, "", err.to_string()))?;
+
+        Ok(Response::new(
+            CheckTokenMetadataResponseWrapper::from(discrepancies).into_inner(),
+        ))
+    }
+
+    async fn compare_bytecodes(
+        &self,
+        request: Request<CompareBytecodesRequest>,
+    ) -> Result<Response<CompareBytecodesResponse>, Status> {
+        let request: CompareBytecodesRequestWrapper = request.into_inner().into();
+        let request: CompareBytecodes = request.try_into()?;
+        let comparison =
+            bytecode_comparison::compare(&request.local, &request.remote, &request.options);
+
+        Ok(Response::new(
+            CompareBytecodesResponseWrapper::from(comparison).into_inner(),
+        ))
+    }
+
+    async fn diff_contracts(
+        &self,
+        request: Request<DiffContractsRequest>,
+    ) -> Result<Response<DiffContractsResponse>, Status> {
+        let request: DiffContractsRequestWrapper = request.into_inner().into();
+        let request: DiffContracts = request.try_into()?;
+        let diff = abi_diff::diff_contracts(
+            &request.old_abi,
+            &request.new_abi,
+            request
+                .storage_layouts
+                .as_ref()
+                .map(|(old, new)| (old, new)),
+        );
+
+        Ok(Response::new(
+            DiffContractsResponseWrapper::from(diff).into_inner(),
+        ))
+    }
+}
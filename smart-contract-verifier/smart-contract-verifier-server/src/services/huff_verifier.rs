@@ -0,0 +1,192 @@
+use crate::{
+    known_contracts::KnownContracts,
+    metrics,
+    proto::{
+        huff_verifier_server::HuffVerifier, ListCompilerVersionsRequest,
+        ListCompilerVersionsResponse, VerifyHuffMultiPartRequest, VerifyResponse,
+    },
+    settings::{Extensions, FetcherSettings, HuffSettings},
+    signing::RecordSigner,
+    tenants::{TenantContext, Tenants},
+    types::{VerifyHuffMultiPartRequestWrapper, VerifyResponseWrapper},
+    usage::{self, UsageMeter},
+    validation::ValidationError,
+};
+use smart_contract_verifier::{
+    huff, Compilers, Downloader, HuffClient, HuffCompiler, ListFetcher, VerificationError,
+};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tonic::{Request, Response, Status};
+
+pub struct HuffVerifierService {
+    client: Arc<HuffClient>,
+    signer: Option<Arc<RecordSigner>>,
+    tenants: Option<Arc<Tenants>>,
+    known_contracts: Option<Arc<KnownContracts>>,
+    usage_meter: Option<Arc<UsageMeter>>,
+}
+
+impl HuffVerifierService {
+    pub async fn new(
+        settings: HuffSettings,
+        compilers_threads_semaphore: Arc<Semaphore>,
+        /* Otherwise, results in compilation warning if all extensions are disabled */
+        #[allow(unused_variables)] extensions: Extensions,
+    ) -> anyhow::Result<Self> {
+        let dir = settings.compilers_dir.clone();
+        let list_settings = match settings.fetcher {
+            FetcherSettings::List(s) => s,
+            FetcherSettings::S3(_) => {
+                return Err(anyhow::anyhow!("S3 fetcher for huff not supported"))
+            }
+            FetcherSettings::Mock(_) => {
+                return Err(anyhow::anyhow!("mock fetcher for huff not supported"))
+            }
+        };
+        let fetcher = Arc::new(
+            ListFetcher::new(
+                list_settings.list_url,
+                settings.compilers_dir,
+                Some(settings.refresh_versions_schedule),
+                None,
+            )
+            .await?
+            .with_downloader(Downloader::new(list_settings.max_parallel_downloads)),
+        );
+        let compiler = HuffCompiler::new().with_sources_dirs(settings.sources_dirs.sources_dirs());
+        let compilers = Compilers::new(fetcher, compiler, compilers_threads_semaphore)
+            .with_limits(settings.limits.into());
+        compilers.load_from_dir(&dir).await;
+
+        /* Otherwise, results in compilation warning if all extensions are disabled */
+        #[allow(unused_mut)]
+        let mut client = HuffClient::new(compilers);
+
+        #[cfg(feature = "sig-provider-extension")]
+        if let Some(sig_provider) = extensions.sig_provider {
+            // TODO(#221): create only one instance of middleware/connection
+            client = client
+                .with_middleware(sig_provider_extension::SigProvider::new(sig_provider).await?);
+        }
+
+        Ok(Self {
+            client: Arc::new(client),
+            signer: None,
+            tenants: None,
+            known_contracts: None,
+            usage_meter: None,
+        })
+    }
+
+    pub fn client(&self) -> Arc<HuffClient> {
+        self.client.clone()
+    }
+
+    pub fn with_signer(mut self, signer: Option<Arc<RecordSigner>>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    pub fn with_tenants(mut self, tenants: Option<Arc<Tenants>>) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
+    pub fn with_known_contracts(mut self, known_contracts: Option<Arc<KnownContracts>>) -> Self {
+        self.known_contracts = known_contracts;
+        self
+    }
+
+    pub fn with_usage_meter(mut self, usage_meter: Option<Arc<UsageMeter>>) -> Self {
+        self.usage_meter = usage_meter;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl HuffVerifier for HuffVerifierService {
+    async fn verify_multi_part(
+        &self,
+        request: Request<VerifyHuffMultiPartRequest>,
+    ) -> Result<Response<VerifyResponse>, Status> {
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        tenant.check_compiler_version(&request.get_ref().compiler_version)?;
+        tenant.acquire_verification()?;
+        let request: VerifyHuffMultiPartRequestWrapper = request.into_inner().into();
+        let verification_request: huff::multi_part::VerificationRequest = request.try_into()?;
+        if let Some(response) = self.known_contracts.as_ref().and_then(|known_contracts| {
+            known_contracts.verify(
+                verification_request.creation_bytecode.as_ref(),
+                &verification_request.deployed_bytecode,
+            )
+        }) {
+            let response = response
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .signed(self.signer.as_deref());
+            metrics::count_verify_contract(
+                "huff",
+                response.status().as_str_name(),
+                "known-bytecode",
+                tenant.id(),
+            );
+            return Ok(Response::new(response.into_inner()));
+        }
+        let result = usage::metered(
+            self.usage_meter.as_deref(),
+            "huff",
+            tenant.id(),
+            huff::multi_part::verify(self.client.clone(), verification_request),
+        )
+        .await;
+
+        if let Ok(verification_success) = result {
+            let compiler_hash = self
+                .client
+                .compilers()
+                .compiler_hash(&verification_success.compiler_version)
+                .await;
+            let response = VerifyResponseWrapper::ok(verification_success)
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .with_attestation(compiler_hash)
+                .signed(self.signer.as_deref());
+            metrics::count_verify_contract(
+                "huff",
+                response.status().as_str_name(),
+                "multi-part",
+                tenant.id(),
+            );
+            return Ok(Response::new(response.into_inner()));
+        }
+
+        let err = result.unwrap_err();
+        match err {
+            VerificationError::Compilation(_)
+            | VerificationError::NoMatchingContracts(_)
+            | VerificationError::CompilerVersionMismatch(_)
+            | VerificationError::IncompatibleCompilerVersion { .. }
+            | VerificationError::MissingSources(_)
+            | VerificationError::UnsupportedOpcodes { .. } => Ok(Response::new(
+                VerifyResponseWrapper::verification_err(&err).into_inner(),
+            )),
+            VerificationError::Initialization(_)
+            | VerificationError::VersionNotFound(_)
+            | VerificationError::LimitExceeded(_) => {
+                Err(ValidationError::from_verification_error(&err).into())
+            }
+            VerificationError::Internal(_) => Err(Status::internal(err.to_string())),
+        }
+    }
+
+    async fn list_compiler_versions(
+        &self,
+        request: Request<ListCompilerVersionsRequest>,
+    ) -> Result<Response<ListCompilerVersionsResponse>, Status> {
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        let compiler_versions =
+            tenant.filter_compiler_versions(self.client.compilers().all_versions_sorted_str());
+        Ok(Response::new(ListCompilerVersionsResponse {
+            compiler_versions,
+        }))
+    }
+}
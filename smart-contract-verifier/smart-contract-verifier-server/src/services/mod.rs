@@ -1,9 +1,23 @@
+mod chains;
+mod compilers_admin;
+mod contract_analyzer;
 mod health;
+mod huff_verifier;
 mod solidity_verifier;
 mod sourcify_verifier;
+mod verification_fixtures;
+mod verification_records;
+mod verification_stats;
 mod vyper_verifier;
 
+pub use chains::ChainsService;
+pub use compilers_admin::CompilersAdminService;
+pub use contract_analyzer::ContractAnalyzerService;
 pub use health::HealthService;
+pub use huff_verifier::HuffVerifierService;
 pub use solidity_verifier::SolidityVerifierService;
 pub use sourcify_verifier::SourcifyVerifierService;
+pub use verification_fixtures::VerificationFixturesService;
+pub use verification_records::VerificationRecordsService;
+pub use verification_stats::VerificationStatsService;
 pub use vyper_verifier::VyperVerifierService;
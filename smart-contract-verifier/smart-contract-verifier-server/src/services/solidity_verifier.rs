@@ -1,81 +1,276 @@
 use crate::{
+    chains,
+    error_catalog::Failure,
+    failure_stats::{self, FailureStats},
+    known_contracts::KnownContracts,
     metrics,
     proto::{
-        solidity_verifier_server::SolidityVerifier, ListCompilerVersionsRequest,
-        ListCompilerVersionsResponse, VerifyResponse, VerifySolidityMultiPartRequest,
+        solidity_verifier_server::SolidityVerifier, verify_batch_response::TargetResult,
+        BytecodeType, ListCompilerVersionsRequest, ListCompilerVersionsResponse,
+        VerifyBatchResponse, VerifyResponse, VerifySolidityGithubRepositoryRequest,
+        VerifySolidityMultiPartRequest, VerifySolidityStandardJsonBatchRequest,
         VerifySolidityStandardJsonRequest,
     },
-    settings::{Extensions, FetcherSettings, S3FetcherSettings, SoliditySettings},
+    settings::{
+        DependenciesSettings, Extensions, FetcherSettings, GenesisPatchSettings, S3FetcherSettings,
+        SoliditySettings,
+    },
+    signing::RecordSigner,
+    tenants::{TenantContext, Tenants},
     types::{
-        StandardJsonParseError, VerifyResponseWrapper, VerifySolidityMultiPartRequestWrapper,
+        VerifyResponseWrapper, VerifySolidityGithubRepositoryRequestWrapper,
+        VerifySolidityMultiPartRequestWrapper, VerifySolidityStandardJsonBatchRequestWrapper,
         VerifySolidityStandardJsonRequestWrapper,
     },
+    usage::{self, UsageMeter},
+    validation::{ErrorCode, ValidationError},
 };
+use blockscout_display_bytes::Bytes as DisplayBytes;
 use s3::{creds::Credentials, Bucket, Region};
+use sha3::{Digest, Keccak256};
 use smart_contract_verifier::{
-    solidity, Compilers, Fetcher, ListFetcher, S3Fetcher, SolcValidator, SolidityClient,
-    SolidityCompiler, VerificationError,
+    analyzer::{
+        failure_classifier::{self, RequestedSettings},
+        settings_inference,
+    },
+    solidity, Compilers, Downloader, EvmFork, Fetcher, ListFetcher, MockCompiler, MockFetcher,
+    Normalization, ProcessPoolBackend, S3Fetcher, SolcBackend, SolcBackendKind, SolcValidator,
+    SolidityClient, SolidityCompiler, VerificationError, Version,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
 };
-use std::{str::FromStr, sync::Arc};
 use tokio::sync::Semaphore;
 use tonic::{Request, Response, Status};
 
 pub struct SolidityVerifierService {
     client: Arc<SolidityClient>,
+    /// Clients of the compiler families configured by the operator, by the family names
+    compiler_families: BTreeMap<String, Arc<SolidityClient>>,
+    signer: Option<Arc<RecordSigner>>,
+    tenants: Option<Arc<Tenants>>,
+    known_contracts: Option<Arc<KnownContracts>>,
+    failure_stats: Option<Arc<FailureStats>>,
+    usage_meter: Option<Arc<UsageMeter>>,
+    genesis_patches: BTreeMap<String, GenesisPatch>,
+    eof_enabled: bool,
+    evm_fork: Option<EvmFork>,
+    normalization: Normalization,
 }
 
 impl SolidityVerifierService {
     pub async fn new(
         settings: SoliditySettings,
         compilers_threads_semaphore: Arc<Semaphore>,
-        /* Otherwise, results in compilation warning if all extensions are disabled */
-        #[allow(unused_variables)] extensions: Extensions,
+        extensions: Extensions,
     ) -> anyhow::Result<Self> {
         let dir = settings.compilers_dir.clone();
-        let schedule = settings.refresh_versions_schedule;
+        let schedule = settings.refresh_versions_schedule.clone();
+        let eof_enabled = settings.eof_enabled;
+        let evm_fork = settings.evm_fork;
+        let genesis_patches = settings
+            .genesis_patches
+            .iter()
+            .map(|(name, patch)| (name.clone(), GenesisPatch::from(patch)))
+            .collect();
+        let normalization = chains::normalization(&settings.normalization_patterns)?;
         let validator = Arc::new(SolcValidator::default());
-        let fetcher: Arc<dyn Fetcher> = match settings.fetcher {
+        // Compiler families share the pools of parallel downloads and compilations
+        let downloader = match &settings.fetcher {
+            FetcherSettings::List(list_settings) => {
+                Downloader::new(list_settings.max_parallel_downloads)
+            }
+            _ => Downloader::default(),
+        };
+        let compilation_pool = Arc::new(Semaphore::new(settings.max_parallel_compilations.get()));
+        let fetcher: Arc<dyn Fetcher> = match &settings.fetcher {
             FetcherSettings::List(list_settings) => Arc::new(
                 ListFetcher::new(
-                    list_settings.list_url,
-                    settings.compilers_dir,
-                    Some(schedule),
-                    Some(validator),
+                    list_settings.list_url.clone(),
+                    dir.clone(),
+                    Some(schedule.clone()),
+                    Some(validator.clone()),
                 )
-                .await?,
+                .await?
+                .with_downloader(downloader.clone()),
             ),
             FetcherSettings::S3(s3_settings) => Arc::new(
                 S3Fetcher::new(
-                    new_bucket(&s3_settings)?,
-                    settings.compilers_dir,
-                    Some(schedule),
-                    Some(validator),
+                    new_bucket(s3_settings)?,
+                    dir.clone(),
+                    Some(schedule.clone()),
+                    Some(validator.clone()),
                 )
                 .await?,
             ),
+            FetcherSettings::Mock(mock_settings) => {
+                Arc::new(MockFetcher::new(mock_settings.versions.clone()))
+            }
         };
-        let compilers = Compilers::new(
+        let client = new_client(
             fetcher,
-            SolidityCompiler::new(),
-            compilers_threads_semaphore,
-        );
-        compilers.load_from_dir(&dir).await;
-
-        /* Otherwise, results in compilation warning if all extensions are disabled */
-        #[allow(unused_mut)]
-        let mut client = SolidityClient::new(compilers);
+            dir.clone(),
+            &settings,
+            compilers_threads_semaphore.clone(),
+            compilation_pool.clone(),
+            &extensions,
+        )
+        .await?;
 
-        #[cfg(feature = "sig-provider-extension")]
-        if let Some(sig_provider) = extensions.sig_provider {
-            // TODO(#221): create only one instance of middleware/connection
-            client = client
-                .with_middleware(sig_provider_extension::SigProvider::new(sig_provider).await?);
+        let mut compiler_families = BTreeMap::new();
+        for (name, family) in &settings.compiler_families {
+            let dir = dir.join("families").join(name);
+            let fetcher = ListFetcher::new(
+                family.list_url.clone(),
+                dir.clone(),
+                Some(schedule.clone()),
+                Some(validator.clone()),
+            )
+            .await?
+            .with_download_url_template(family.download_url_template.clone())
+            .with_downloader(downloader.clone());
+            let client = new_client(
+                Arc::new(fetcher),
+                dir,
+                &settings,
+                compilers_threads_semaphore.clone(),
+                compilation_pool.clone(),
+                &extensions,
+            )
+            .await?;
+            compiler_families.insert(name.clone(), Arc::new(client));
         }
 
         Ok(Self {
             client: Arc::new(client),
+            compiler_families,
+            signer: None,
+            tenants: None,
+            known_contracts: None,
+            failure_stats: None,
+            usage_meter: None,
+            genesis_patches,
+            eof_enabled,
+            evm_fork,
+            normalization,
         })
     }
+
+    pub fn client(&self) -> Arc<SolidityClient> {
+        self.client.clone()
+    }
+
+    /// Returns the client of the compiler family requested, or of the upstream solc if absent.
+    fn family_client(&self, compiler: Option<&str>) -> Result<Arc<SolidityClient>, Status> {
+        let compiler = match compiler {
+            None => return Ok(self.client.clone()),
+            Some(compiler) => compiler,
+        };
+        self.compiler_families
+            .get(compiler)
+            .cloned()
+            .ok_or_else(|| {
+                ValidationError::new(
+                    ErrorCode::InvalidValue,
+                    "compiler",
+                    format!("compiler family `{compiler}` not found"),
+                )
+                .into()
+            })
+    }
+
+    pub fn with_signer(mut self, signer: Option<Arc<RecordSigner>>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    pub fn with_tenants(mut self, tenants: Option<Arc<Tenants>>) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
+    pub fn with_known_contracts(mut self, known_contracts: Option<Arc<KnownContracts>>) -> Self {
+        self.known_contracts = known_contracts;
+        self
+    }
+
+    pub fn with_failure_stats(mut self, failure_stats: Option<Arc<FailureStats>>) -> Self {
+        self.failure_stats = failure_stats;
+        self
+    }
+
+    pub fn with_usage_meter(mut self, usage_meter: Option<Arc<UsageMeter>>) -> Self {
+        self.usage_meter = usage_meter;
+        self
+    }
+
+    /// Resolves the genesis patch referenced by the request into the bytecode mask.
+    /// The patch is applied only to the deployed bytecodes it has been configured for.
+    fn bytecode_mask(
+        &self,
+        genesis_patch: Option<&str>,
+        bytecode_type: BytecodeType,
+        deployed_bytecode: &[u8],
+    ) -> Result<Vec<Range<usize>>, Status> {
+        let genesis_patch = match genesis_patch {
+            None => return Ok(vec![]),
+            Some(genesis_patch) => genesis_patch,
+        };
+        if bytecode_type != BytecodeType::DeployedBytecode {
+            return Err(ValidationError::new(
+                ErrorCode::InvalidValue,
+                "genesisPatch",
+                "genesis patch may be applied to the deployed bytecode only",
+            )
+            .into());
+        }
+        let patch = self.genesis_patches.get(genesis_patch).ok_or_else(|| {
+            ValidationError::new(
+                ErrorCode::InvalidValue,
+                "genesisPatch",
+                format!("genesis patch `{genesis_patch}` not found"),
+            )
+        })?;
+        let code_hash = Keccak256::digest(deployed_bytecode);
+        if !patch.code_hashes.contains(code_hash.as_slice()) {
+            return Err(ValidationError::new(
+                ErrorCode::InvalidValue,
+                "genesisPatch",
+                format!("genesis patch `{genesis_patch}` is not configured for the bytecode"),
+            )
+            .with_param("codeHash", DisplayBytes::from(code_hash.to_vec()))
+            .into());
+        }
+        Ok(patch.mask.clone())
+    }
+}
+
+/// Genesis patch with the hashes of the deployed bytecodes it may be applied to.
+struct GenesisPatch {
+    code_hashes: BTreeSet<Vec<u8>>,
+    mask: Vec<Range<usize>>,
+}
+
+impl From<&GenesisPatchSettings> for GenesisPatch {
+    fn from(settings: &GenesisPatchSettings) -> Self {
+        Self {
+            code_hashes: settings
+                .code_hashes
+                .iter()
+                .map(|hash| hash.to_vec())
+                .collect(),
+            mask: settings
+                .ranges
+                .iter()
+                .map(|range| range.start..range.end)
+                .collect(),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -84,28 +279,93 @@ impl SolidityVerifier for SolidityVerifierService {
         &self,
         request: Request<VerifySolidityMultiPartRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        tenant.check_compiler_version(&request.get_ref().compiler_version)?;
+        tenant.acquire_verification()?;
+        let client = self.family_client(request.get_ref().compiler.as_deref())?;
         let request: VerifySolidityMultiPartRequestWrapper = request.into_inner().into();
-        let result = solidity::multi_part::verify(self.client.clone(), request.try_into()?).await;
+        let genesis_patch = request.genesis_patch.clone();
+        let bytecode_type = request.bytecode_type();
+        let mut verification_request: solidity::multi_part::VerificationRequest =
+            request.try_into()?;
+        verification_request.bytecode_mask = self.bytecode_mask(
+            genesis_patch.as_deref(),
+            bytecode_type,
+            &verification_request.deployed_bytecode,
+        )?;
+        verification_request.eof_enabled = tenant.eof_enabled(self.eof_enabled);
+        verification_request.evm_fork = tenant.evm_fork(self.evm_fork);
+        verification_request.normalization = tenant.normalization(&self.normalization);
+        if let Some(response) = self.known_contracts.as_ref().and_then(|known_contracts| {
+            known_contracts.verify(
+                verification_request.creation_bytecode.as_ref(),
+                &verification_request.deployed_bytecode,
+            )
+        }) {
+            let response = response
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .signed(self.signer.as_deref());
+            metrics::count_verify_contract(
+                "solidity",
+                response.status().as_str_name(),
+                "known-bytecode",
+                tenant.id(),
+            );
+            return Ok(Response::new(response.into_inner()));
+        }
+        let deployed_bytecode = verification_request.deployed_bytecode.clone();
+        let requested_settings = RequestedSettings {
+            compiler_version: requested_compiler_version(
+                &verification_request.compiler_version,
+                verification_request.auto_select_compiler_version,
+            ),
+            optimization_enabled: Some(verification_request.content.optimization_runs.is_some()),
+            via_ir: Some(false),
+        };
+        let trace = verification_request.trace.clone();
+        let result = usage::metered(
+            self.usage_meter.as_deref(),
+            "solidity",
+            tenant.id(),
+            solidity::multi_part::verify(client.clone(), verification_request),
+        )
+        .await;
 
         if let Ok(verification_success) = result {
-            let response = VerifyResponseWrapper::ok(verification_success);
+            let compiler_hash = client
+                .compilers()
+                .compiler_hash(&verification_success.compiler_version)
+                .await;
+            let response = VerifyResponseWrapper::ok(verification_success)
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .with_attestation(compiler_hash)
+                .signed(self.signer.as_deref())
+                .with_trace(trace.as_ref());
             metrics::count_verify_contract(
                 "solidity",
                 response.status().as_str_name(),
                 "multi-part",
+                tenant.id(),
             );
             return Ok(Response::new(response.into_inner()));
         }
 
         let err = result.unwrap_err();
         match err {
-            VerificationError::Compilation(_)
-            | VerificationError::NoMatchingContracts
-            | VerificationError::CompilerVersionMismatch(_) => {
-                Ok(Response::new(VerifyResponseWrapper::err(err).into_inner()))
-            }
-            VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
-                Err(Status::invalid_argument(err.to_string()))
+            VerificationError::NoMatchingContracts(_)
+            | VerificationError::Compilation(_)
+            | VerificationError::CompilerVersionMismatch(_)
+            | VerificationError::IncompatibleCompilerVersion { .. }
+            | VerificationError::MissingSources(_)
+            | VerificationError::UnsupportedOpcodes { .. } => Ok(Response::new(
+                self.failure_response(&err, &deployed_bytecode, &requested_settings, tenant.id())
+                    .with_trace(trace.as_ref())
+                    .into_inner(),
+            )),
+            VerificationError::Initialization(_)
+            | VerificationError::VersionNotFound(_)
+            | VerificationError::LimitExceeded(_) => {
+                Err(ValidationError::from_verification_error(&err).into())
             }
             VerificationError::Internal(_) => Err(Status::internal(err.to_string())),
         }
@@ -115,43 +375,277 @@ impl SolidityVerifier for SolidityVerifierService {
         &self,
         request: Request<VerifySolidityStandardJsonRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        tenant.check_compiler_version(&request.get_ref().compiler_version)?;
+        tenant.acquire_verification()?;
+        let client = self.family_client(request.get_ref().compiler.as_deref())?;
         let request: VerifySolidityStandardJsonRequestWrapper = request.into_inner().into();
-        let verification_request = {
-            let request: Result<_, StandardJsonParseError> = request.try_into();
-            if let Err(err) = request {
-                match err {
-                    StandardJsonParseError::InvalidContent(_) => {
-                        return Err(Status::invalid_argument(err.to_string()))
-                    }
-                    StandardJsonParseError::BadRequest(_) => {
-                        return Ok(Response::new(VerifyResponseWrapper::err(err).into_inner()))
-                    }
-                }
-            }
-            request.unwrap()
+        let genesis_patch = request.genesis_patch.clone();
+        let bytecode_type = request.bytecode_type();
+        let mut verification_request: solidity::standard_json::VerificationRequest =
+            request.try_into()?;
+        verification_request.bytecode_mask = self.bytecode_mask(
+            genesis_patch.as_deref(),
+            bytecode_type,
+            &verification_request.deployed_bytecode,
+        )?;
+        verification_request.eof_enabled = tenant.eof_enabled(self.eof_enabled);
+        verification_request.evm_fork = tenant.evm_fork(self.evm_fork);
+        verification_request.normalization = tenant.normalization(&self.normalization);
+        if let Some(response) = self.known_contracts.as_ref().and_then(|known_contracts| {
+            known_contracts.verify(
+                verification_request.creation_bytecode.as_ref(),
+                &verification_request.deployed_bytecode,
+            )
+        }) {
+            let response = response
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .signed(self.signer.as_deref());
+            metrics::count_verify_contract(
+                "solidity",
+                response.status().as_str_name(),
+                "known-bytecode",
+                tenant.id(),
+            );
+            return Ok(Response::new(response.into_inner()));
+        }
+        let deployed_bytecode = verification_request.deployed_bytecode.clone();
+        let requested_settings = RequestedSettings {
+            compiler_version: requested_compiler_version(
+                &verification_request.compiler_version,
+                verification_request.auto_select_compiler_version,
+            ),
+            optimization_enabled: Some(
+                verification_request
+                    .content
+                    .input
+                    .settings
+                    .optimizer
+                    .enabled
+                    .unwrap_or_default(),
+            ),
+            via_ir: Some(
+                verification_request
+                    .content
+                    .input
+                    .settings
+                    .via_ir
+                    .unwrap_or_default(),
+            ),
         };
-        let result =
-            solidity::standard_json::verify(self.client.clone(), verification_request).await;
+        let trace = verification_request.trace.clone();
+        let result = usage::metered(
+            self.usage_meter.as_deref(),
+            "solidity",
+            tenant.id(),
+            solidity::standard_json::verify(client.clone(), verification_request),
+        )
+        .await;
 
         if let Ok(verification_success) = result {
-            let response = VerifyResponseWrapper::ok(verification_success);
+            let compiler_hash = client
+                .compilers()
+                .compiler_hash(&verification_success.compiler_version)
+                .await;
+            let response = VerifyResponseWrapper::ok(verification_success)
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .with_attestation(compiler_hash)
+                .signed(self.signer.as_deref())
+                .with_trace(trace.as_ref());
             metrics::count_verify_contract(
                 "solidity",
                 response.status().as_str_name(),
                 "multi-part",
+                tenant.id(),
             );
             return Ok(Response::new(response.into_inner()));
         }
 
         let err = result.unwrap_err();
         match err {
-            VerificationError::Compilation(_)
-            | VerificationError::NoMatchingContracts
-            | VerificationError::CompilerVersionMismatch(_) => {
-                Ok(Response::new(VerifyResponseWrapper::err(err).into_inner()))
+            VerificationError::NoMatchingContracts(_)
+            | VerificationError::Compilation(_)
+            | VerificationError::CompilerVersionMismatch(_)
+            | VerificationError::IncompatibleCompilerVersion { .. }
+            | VerificationError::MissingSources(_)
+            | VerificationError::UnsupportedOpcodes { .. } => Ok(Response::new(
+                self.failure_response(&err, &deployed_bytecode, &requested_settings, tenant.id())
+                    .with_trace(trace.as_ref())
+                    .into_inner(),
+            )),
+            VerificationError::Initialization(_)
+            | VerificationError::VersionNotFound(_)
+            | VerificationError::LimitExceeded(_) => {
+                Err(ValidationError::from_verification_error(&err).into())
             }
-            VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
-                Err(Status::invalid_argument(err.to_string()))
+            VerificationError::Internal(_) => Err(Status::internal(err.to_string())),
+        }
+    }
+
+    async fn verify_standard_json_batch(
+        &self,
+        request: Request<VerifySolidityStandardJsonBatchRequest>,
+    ) -> Result<Response<VerifyBatchResponse>, Status> {
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        tenant.check_compiler_version(&request.get_ref().compiler_version)?;
+        // Each target is a separate verification, even though the sources are compiled once
+        tenant.acquire_verifications(request.get_ref().targets.len())?;
+        let client = self.family_client(request.get_ref().compiler.as_deref())?;
+        let request: VerifySolidityStandardJsonBatchRequestWrapper = request.into_inner().into();
+        let addresses = request.addresses();
+        let mut verification_request: solidity::standard_json::BatchVerificationRequest =
+            request.try_into()?;
+        verification_request.eof_enabled = tenant.eof_enabled(self.eof_enabled);
+        verification_request.evm_fork = tenant.evm_fork(self.evm_fork);
+        verification_request.normalization = tenant.normalization(&self.normalization);
+        let result = usage::metered(
+            self.usage_meter.as_deref(),
+            "solidity",
+            tenant.id(),
+            solidity::standard_json::verify_batch(client.clone(), verification_request),
+        )
+        .await;
+
+        // All the targets are compiled with the same compiler
+        let compiler_version = match &result {
+            Ok(results) => results
+                .iter()
+                .find_map(|result| result.as_ref().ok())
+                .map(|success| success.compiler_version.clone()),
+            Err(_) => None,
+        };
+        let compiler_hash = match compiler_version {
+            Some(version) => client.compilers().compiler_hash(&version).await,
+            None => None,
+        };
+
+        let responses: Vec<VerifyResponseWrapper> = match result {
+            Ok(results) => results
+                .into_iter()
+                .map(|result| match result {
+                    Ok(verification_success) => VerifyResponseWrapper::ok(verification_success)
+                        .with_chain_checksum(tenant.checksum_chain_id())
+                        .with_attestation(compiler_hash)
+                        .signed(self.signer.as_deref()),
+                    // Errors of the specific targets should not fail the whole batch
+                    Err(err) => VerifyResponseWrapper::verification_err(&err),
+                })
+                .collect(),
+            Err(err) => match err {
+                // The sources cannot be verified for any of the targets
+                VerificationError::Compilation(_)
+                | VerificationError::NoMatchingContracts(_)
+                | VerificationError::CompilerVersionMismatch(_)
+                | VerificationError::IncompatibleCompilerVersion { .. }
+                | VerificationError::MissingSources(_)
+                | VerificationError::UnsupportedOpcodes { .. } => addresses
+                    .iter()
+                    .map(|_| VerifyResponseWrapper::verification_err(&err))
+                    .collect(),
+                VerificationError::Initialization(_)
+                | VerificationError::VersionNotFound(_)
+                | VerificationError::LimitExceeded(_) => {
+                    return Err(ValidationError::from_verification_error(&err).into())
+                }
+                VerificationError::Internal(_) => return Err(Status::internal(err.to_string())),
+            },
+        };
+
+        let results = addresses
+            .into_iter()
+            .zip(responses)
+            .map(|(address, response)| {
+                metrics::count_verify_contract(
+                    "solidity",
+                    response.status().as_str_name(),
+                    "standard-json-batch",
+                    tenant.id(),
+                );
+                TargetResult {
+                    address,
+                    response: Some(response.into_inner()),
+                }
+            })
+            .collect();
+        Ok(Response::new(VerifyBatchResponse { results }))
+    }
+
+    async fn verify_github_repository(
+        &self,
+        request: Request<VerifySolidityGithubRepositoryRequest>,
+    ) -> Result<Response<VerifyResponse>, Status> {
+        if self.client.repository_fetcher().is_none() {
+            return Err(Status::unimplemented(
+                "verification from GitHub repositories is disabled",
+            ));
+        }
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        tenant.check_compiler_version(&request.get_ref().compiler_version)?;
+        tenant.acquire_verification()?;
+        let client = self.family_client(request.get_ref().compiler.as_deref())?;
+        let request: VerifySolidityGithubRepositoryRequestWrapper = request.into_inner().into();
+        let genesis_patch = request.genesis_patch.clone();
+        let bytecode_type = request.bytecode_type();
+        let mut verification_request: solidity::repository::VerificationRequest =
+            request.try_into()?;
+        verification_request.bytecode_mask = self.bytecode_mask(
+            genesis_patch.as_deref(),
+            bytecode_type,
+            &verification_request.deployed_bytecode,
+        )?;
+        verification_request.eof_enabled = tenant.eof_enabled(self.eof_enabled);
+        verification_request.evm_fork = tenant.evm_fork(self.evm_fork);
+        verification_request.normalization = tenant.normalization(&self.normalization);
+        let deployed_bytecode = verification_request.deployed_bytecode.clone();
+        // Settings are read from the repository configuration, which is not available here
+        let requested_settings = RequestedSettings {
+            compiler_version: requested_compiler_version(
+                &verification_request.compiler_version,
+                verification_request.auto_select_compiler_version,
+            ),
+            ..Default::default()
+        };
+        let result = usage::metered(
+            self.usage_meter.as_deref(),
+            "solidity",
+            tenant.id(),
+            solidity::repository::verify(client.clone(), verification_request),
+        )
+        .await;
+
+        if let Ok(verification_success) = result {
+            let compiler_hash = client
+                .compilers()
+                .compiler_hash(&verification_success.compiler_version)
+                .await;
+            let response = VerifyResponseWrapper::ok(verification_success)
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .with_attestation(compiler_hash)
+                .signed(self.signer.as_deref());
+            metrics::count_verify_contract(
+                "solidity",
+                response.status().as_str_name(),
+                "github-repository",
+                tenant.id(),
+            );
+            return Ok(Response::new(response.into_inner()));
+        }
+
+        let err = result.unwrap_err();
+        match err {
+            VerificationError::NoMatchingContracts(_)
+            | VerificationError::Compilation(_)
+            | VerificationError::CompilerVersionMismatch(_)
+            | VerificationError::IncompatibleCompilerVersion { .. }
+            | VerificationError::MissingSources(_)
+            | VerificationError::UnsupportedOpcodes { .. } => Ok(Response::new(
+                self.failure_response(&err, &deployed_bytecode, &requested_settings, tenant.id())
+                    .into_inner(),
+            )),
+            VerificationError::Initialization(_)
+            | VerificationError::VersionNotFound(_)
+            | VerificationError::LimitExceeded(_) => {
+                Err(ValidationError::from_verification_error(&err).into())
             }
             VerificationError::Internal(_) => Err(Status::internal(err.to_string())),
         }
@@ -159,15 +653,136 @@ impl SolidityVerifier for SolidityVerifierService {
 
     async fn list_compiler_versions(
         &self,
-        _request: Request<ListCompilerVersionsRequest>,
+        request: Request<ListCompilerVersionsRequest>,
     ) -> Result<Response<ListCompilerVersionsResponse>, Status> {
-        let compiler_versions = self.client.compilers().all_versions_sorted_str();
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        let client = self.family_client(request.get_ref().compiler.as_deref())?;
+        let compiler_versions =
+            tenant.filter_compiler_versions(client.compilers().all_versions_sorted_str());
         Ok(Response::new(ListCompilerVersionsResponse {
             compiler_versions,
         }))
     }
 }
 
+impl SolidityVerifierService {
+    /// Describes the failure via the error catalog and classifies it for the failure stats.
+    /// For [`VerificationError::NoMatchingContracts`] appends the settings inferred from
+    /// the deployed bytecode, so that users have a hint which settings to try next.
+    /// If `viaIR` value of the request is known and the bytecode looks like the output of
+    /// the other code generation pipeline, the hint to flip the setting is appended as well.
+    fn failure_response(
+        &self,
+        err: &VerificationError,
+        deployed_bytecode: &[u8],
+        requested_settings: &RequestedSettings,
+        tenant: &str,
+    ) -> VerifyResponseWrapper {
+        let inferred_settings = settings_inference::infer_settings(deployed_bytecode);
+        let category = failure_classifier::classify(err, &inferred_settings, requested_settings);
+        if let Some(category) = category {
+            failure_stats::record_failure(
+                self.failure_stats.as_deref(),
+                "solidity",
+                category,
+                tenant,
+            );
+        }
+
+        let mut failure = match Failure::from_verification_error(err) {
+            Some(failure) => failure.with_category(category),
+            None => return VerifyResponseWrapper::err(err),
+        };
+        if matches!(err, VerificationError::NoMatchingContracts(_)) && !inferred_settings.is_empty()
+        {
+            failure = failure.with_param("inferredSettings", &inferred_settings);
+            if let Some(hint) = requested_settings
+                .via_ir
+                .and_then(|requested| inferred_settings.via_ir_hint(requested))
+            {
+                failure = failure.with_param("viaIrHint", hint);
+            }
+        }
+        VerifyResponseWrapper::failure(failure)
+    }
+}
+
+/// The requested version is not the one used for the compilation,
+/// if the compiler version could have been selected automatically.
+fn requested_compiler_version(
+    compiler_version: &Version,
+    auto_select_compiler_version: bool,
+) -> Option<semver::Version> {
+    (!auto_select_compiler_version).then(|| compiler_version.version().clone())
+}
+
+fn compiler_backend(settings: &SoliditySettings) -> anyhow::Result<Box<dyn SolcBackend>> {
+    let backend: Box<dyn SolcBackend> = match settings.compiler_backend {
+        SolcBackendKind::StandardJsonPool => Box::new(
+            ProcessPoolBackend::new(settings.compiler_pool.warm_processes)
+                .with_max_idle_time(Duration::from_secs(settings.compiler_pool.max_idle_time)),
+        ),
+        SolcBackendKind::Mock => {
+            let dir = settings
+                .mock_outputs_dir
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("mock compiler backend requires outputs dir"))?;
+            Box::new(MockCompiler::from_dir(dir)?)
+        }
+        kind => kind.backend(),
+    };
+    Ok(backend)
+}
+
+/// Creates the client compiling with the compilers provided by the fetcher.
+/// Clients of all the compiler families share the rest of the settings.
+async fn new_client(
+    fetcher: Arc<dyn Fetcher>,
+    dir: PathBuf,
+    settings: &SoliditySettings,
+    compilers_threads_semaphore: Arc<Semaphore>,
+    compilation_pool: Arc<Semaphore>,
+    /* Otherwise, results in compilation warning if all extensions are disabled */
+    #[allow(unused_variables)] extensions: &Extensions,
+) -> anyhow::Result<SolidityClient> {
+    let compilers = Compilers::new(
+        fetcher,
+        SolidityCompiler::new()
+            .with_backend(compiler_backend(settings)?)
+            .with_sources_dirs(settings.sources_dirs.sources_dirs()),
+        compilers_threads_semaphore,
+    )
+    .with_limits(settings.limits.into());
+    compilers.load_from_dir(&dir).await;
+
+    /* Otherwise, results in compilation warning if all extensions are disabled */
+    #[allow(unused_mut)]
+    let mut client = SolidityClient::new(compilers)
+        .with_max_parallel_compilations(settings.max_parallel_compilations_per_request)
+        .with_compilation_pool(compilation_pool);
+    if let Some(package_mirror) = &settings.package_mirror {
+        client = client.with_package_mirror(solidity::PackageMirror::new(package_mirror.clone()));
+    }
+    if settings.dependencies.enabled {
+        client =
+            client.with_dependency_resolver(new_dependency_resolver(settings.dependencies.clone()));
+    }
+    if settings.github_repositories.enabled {
+        client = client.with_repository_fetcher(solidity::repository::RepositoryFetcher::new(
+            settings.github_repositories.github_url.clone(),
+        )?);
+    }
+
+    #[cfg(feature = "sig-provider-extension")]
+    if let Some(sig_provider) = extensions.sig_provider.clone() {
+        // TODO(#221): create only one instance of middleware/connection
+        client =
+            client.with_middleware(sig_provider_extension::SigProvider::new(sig_provider).await?);
+    }
+
+    Ok(client)
+}
+
 fn new_region(region: Option<String>, endpoint: Option<String>) -> Option<Region> {
     let region = region.unwrap_or_default();
     if let Some(endpoint) = endpoint {
@@ -201,3 +816,20 @@ fn new_bucket(settings: &S3FetcherSettings) -> anyhow::Result<Arc<Bucket>> {
     )?);
     Ok(bucket)
 }
+
+fn new_dependency_resolver(settings: DependenciesSettings) -> solidity::DependencyResolver {
+    let allowed_packages = settings
+        .allowed_packages
+        .into_iter()
+        .map(|(name, package)| {
+            let package = solidity::AllowedPackage {
+                github_repository: package.github_repository,
+                tag_prefix: package.tag_prefix,
+                directory: package.directory,
+                checksums: package.checksums,
+            };
+            (name, package)
+        })
+        .collect();
+    solidity::DependencyResolver::new(settings.npm_registry, settings.github_url, allowed_packages)
+}
@@ -1,8 +1,12 @@
 use crate::{
+    error_catalog::{Failure, FailureCode},
     metrics,
     proto::{sourcify_verifier_server::SourcifyVerifier, VerifyResponse, VerifySourcifyRequest},
     settings::{Extensions, SourcifySettings},
+    signing::RecordSigner,
+    tenants::{TenantContext, Tenants},
     types::{VerifyResponseWrapper, VerifySourcifyRequestWrapper},
+    validation::{ErrorCode, ValidationError},
 };
 use smart_contract_verifier::{sourcify, sourcify::Error, SourcifyApiClient};
 use std::sync::Arc;
@@ -10,6 +14,8 @@ use tonic::{Request, Response, Status};
 
 pub struct SourcifyVerifierService {
     client: Arc<SourcifyApiClient>,
+    signer: Option<Arc<RecordSigner>>,
+    tenants: Option<Arc<Tenants>>,
 }
 
 impl SourcifyVerifierService {
@@ -37,8 +43,20 @@ impl SourcifyVerifierService {
 
         Ok(Self {
             client: Arc::new(client),
+            signer: None,
+            tenants: None,
         })
     }
+
+    pub fn with_signer(mut self, signer: Option<Arc<RecordSigner>>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    pub fn with_tenants(mut self, tenants: Option<Arc<Tenants>>) -> Self {
+        self.tenants = tenants;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -47,19 +65,32 @@ impl SourcifyVerifier for SourcifyVerifierService {
         &self,
         request: Request<VerifySourcifyRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        tenant.acquire_verification()?;
         let request: VerifySourcifyRequestWrapper = request.into_inner().into();
         let response = sourcify::api::verify(self.client.clone(), request.try_into()?).await;
 
         let result = match response {
-            Ok(verification_success) => Ok(VerifyResponseWrapper::ok(verification_success)),
+            Ok(verification_success) => Ok(VerifyResponseWrapper::ok(verification_success)
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .signed(self.signer.as_deref())),
             Err(err) => match err {
                 Error::Internal(err) => Err(Status::internal(err.to_string())),
-                Error::Verification(err) => Ok(VerifyResponseWrapper::err(err)),
-                Error::Validation(err) => Err(Status::invalid_argument(err)),
+                Error::Verification(err) => Ok(VerifyResponseWrapper::failure(
+                    Failure::new(FailureCode::SourcifyVerificationFailed).with_param("reason", err),
+                )),
+                Error::Validation(err) => {
+                    Err(ValidationError::new(ErrorCode::InvalidValue, "files", err).into())
+                }
             },
         }?;
 
-        metrics::count_verify_contract("solidity", result.status().as_str_name(), "sourcify");
+        metrics::count_verify_contract(
+            "solidity",
+            result.status().as_str_name(),
+            "sourcify",
+            tenant.id(),
+        );
         return Ok(Response::new(result.into_inner()));
     }
 }
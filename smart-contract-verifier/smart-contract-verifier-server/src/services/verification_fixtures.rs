@@ -0,0 +1,44 @@
+use crate::{
+    fixtures,
+    proto::{
+        verification_fixtures_server::VerificationFixtures, GetVerificationFixtureRequest,
+        ListVerificationFixturesRequest, ListVerificationFixturesResponse, VerificationFixture,
+    },
+};
+use tonic::{Request, Response, Status};
+
+pub struct VerificationFixturesService {
+    fixtures: Vec<VerificationFixture>,
+}
+
+impl Default for VerificationFixturesService {
+    fn default() -> Self {
+        Self {
+            fixtures: fixtures::verification_fixtures(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl VerificationFixtures for VerificationFixturesService {
+    async fn list_verification_fixtures(
+        &self,
+        _request: Request<ListVerificationFixturesRequest>,
+    ) -> Result<Response<ListVerificationFixturesResponse>, Status> {
+        Ok(Response::new(ListVerificationFixturesResponse {
+            fixtures: self.fixtures.clone(),
+        }))
+    }
+
+    async fn get_verification_fixture(
+        &self,
+        request: Request<GetVerificationFixtureRequest>,
+    ) -> Result<Response<VerificationFixture>, Status> {
+        let name = request.into_inner().name;
+        self.fixtures
+            .iter()
+            .find(|fixture| fixture.name == name)
+            .map(|fixture| Response::new(fixture.clone()))
+            .ok_or_else(|| Status::not_found(format!("unknown fixture {name}")))
+    }
+}
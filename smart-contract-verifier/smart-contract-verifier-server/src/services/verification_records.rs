@@ -0,0 +1,66 @@
+use crate::{
+    proto::{
+        list_signing_keys_response::Key, verification_records_server::VerificationRecords,
+        verify_record_signature_response, ListSigningKeysRequest, ListSigningKeysResponse,
+        VerifyRecordSignatureRequest, VerifyRecordSignatureResponse,
+    },
+    signing::{KeyStatus, RecordSigner},
+    validation,
+};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+pub struct VerificationRecordsService {
+    signer: Arc<RecordSigner>,
+}
+
+impl VerificationRecordsService {
+    pub fn new(signer: Arc<RecordSigner>) -> Self {
+        Self { signer }
+    }
+}
+
+#[async_trait::async_trait]
+impl VerificationRecords for VerificationRecordsService {
+    async fn verify_signature(
+        &self,
+        request: Request<VerifyRecordSignatureRequest>,
+    ) -> Result<Response<VerifyRecordSignatureResponse>, Status> {
+        let request = request.into_inner();
+        let signature = validation::parse_hex("signature", "signature", &request.signature)?;
+
+        let (valid, key_status) = self
+            .signer
+            .verify(&request.key_id, &request.record, &signature)
+            .ok_or_else(|| {
+                Status::not_found(format!("signing key `{}` not found", request.key_id))
+            })?;
+        let key_status = match key_status {
+            KeyStatus::Active => verify_record_signature_response::KeyStatus::Active,
+            KeyStatus::Retired => verify_record_signature_response::KeyStatus::Retired,
+        };
+
+        Ok(Response::new(VerifyRecordSignatureResponse {
+            valid,
+            key_status: key_status.into(),
+        }))
+    }
+
+    async fn list_signing_keys(
+        &self,
+        _request: Request<ListSigningKeysRequest>,
+    ) -> Result<Response<ListSigningKeysResponse>, Status> {
+        let keys = self
+            .signer
+            .keys()
+            .map(|key| Key {
+                id: key.id.clone(),
+                public_key: DisplayBytes::from(key.public_key.clone()).to_string(),
+                active: key.status == KeyStatus::Active,
+            })
+            .collect();
+
+        Ok(Response::new(ListSigningKeysResponse { keys }))
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    failure_stats::FailureStats,
+    proto::{
+        get_failure_stats_response::FailureCount, get_usage_response,
+        verification_stats_server::VerificationStats, GetFailureStatsRequest,
+        GetFailureStatsResponse, GetUsageRequest, GetUsageResponse,
+    },
+    settings::AdminSettings,
+    tenants::{TenantContext, Tenants},
+    usage::UsageMeter,
+};
+use std::{collections::HashSet, sync::Arc};
+use tonic::{Request, Response, Status};
+
+pub struct VerificationStatsService {
+    failure_stats: Option<Arc<FailureStats>>,
+    usage_meter: Option<Arc<UsageMeter>>,
+    tenants: Option<Arc<Tenants>>,
+    admin_api_key_header: String,
+    admin_api_keys: HashSet<String>,
+}
+
+impl VerificationStatsService {
+    pub fn new(
+        failure_stats: Option<Arc<FailureStats>>,
+        usage_meter: Option<Arc<UsageMeter>>,
+    ) -> Self {
+        Self {
+            failure_stats,
+            usage_meter,
+            tenants: None,
+            admin_api_key_header: AdminSettings::default().api_key_header,
+            admin_api_keys: HashSet::new(),
+        }
+    }
+
+    pub fn with_tenants(mut self, tenants: Option<Arc<Tenants>>) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
+    /// Admins are allowed to read the usage of all tenants. The keys are ignored
+    /// if the admin api is disabled.
+    pub fn with_admin(mut self, settings: &AdminSettings) -> Self {
+        self.admin_api_key_header = settings.api_key_header.clone();
+        self.admin_api_keys = match settings.enabled {
+            true => settings.api_keys.iter().cloned().collect(),
+            false => HashSet::new(),
+        };
+        self
+    }
+
+    fn is_admin<T>(&self, request: &Request<T>) -> bool {
+        request
+            .metadata()
+            .get(&self.admin_api_key_header)
+            .and_then(|api_key| api_key.to_str().ok())
+            .map(|api_key| self.admin_api_keys.contains(api_key))
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl VerificationStats for VerificationStatsService {
+    async fn get_failure_stats(
+        &self,
+        _request: Request<GetFailureStatsRequest>,
+    ) -> Result<Response<GetFailureStatsResponse>, Status> {
+        let failure_stats = self
+            .failure_stats
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("failure stats are disabled"))?;
+        let failures = failure_stats
+            .counts()
+            .into_iter()
+            .map(|count| FailureCount {
+                language: count.language.to_string(),
+                category: count.bucket.as_str().to_string(),
+                count: count.count,
+            })
+            .collect();
+
+        Ok(Response::new(GetFailureStatsResponse {
+            failures,
+            since: failure_stats.since(),
+        }))
+    }
+
+    async fn get_usage(
+        &self,
+        request: Request<GetUsageRequest>,
+    ) -> Result<Response<GetUsageResponse>, Status> {
+        let usage_meter = self
+            .usage_meter
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("usage accounting is disabled"))?;
+
+        // Admins may read the usage of any tenant, while tenants only their own one
+        let usage = if self.is_admin(&request) {
+            usage_meter.usage(request.get_ref().tenant.as_deref())
+        } else {
+            let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+            let tenant_id = tenant.tenant_id().ok_or_else(|| {
+                Status::permission_denied("usage is available to the tenants and admins only")
+            })?;
+            if let Some(requested) = request.get_ref().tenant.as_deref() {
+                if requested != tenant_id {
+                    return Err(Status::permission_denied(
+                        "usage of other tenants is available to the admins only",
+                    ));
+                }
+            }
+            usage_meter.usage(Some(tenant_id))
+        };
+
+        let usage = usage
+            .into_iter()
+            .map(|usage| get_usage_response::TenantUsage {
+                tenant: usage.tenant,
+                verifications: usage.verifications,
+                compiler_invocations: usage.compiler_invocations,
+                cpu_seconds: usage.cpu_seconds,
+                bytes_compiled: usage.bytes_compiled,
+            })
+            .collect();
+
+        Ok(Response::new(GetUsageResponse {
+            usage,
+            since: usage_meter.since(),
+        }))
+    }
+}
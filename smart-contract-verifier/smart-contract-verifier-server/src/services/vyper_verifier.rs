@@ -1,14 +1,26 @@
 use crate::{
+    error_catalog::Failure,
+    failure_stats::{self, FailureStats},
+    known_contracts::KnownContracts,
     metrics,
     proto::{
-        vyper_verifier_server::VyperVerifier, ListCompilerVersionsRequest,
+        vyper_verifier_server::VyperVerifier, DetectVyperCompilerVersionRequest,
+        DetectVyperCompilerVersionResponse, ListCompilerVersionsRequest,
         ListCompilerVersionsResponse, VerifyResponse, VerifyVyperMultiPartRequest,
     },
     settings::{Extensions, FetcherSettings, VyperSettings},
+    signing::RecordSigner,
+    tenants::{TenantContext, Tenants},
     types::{VerifyResponseWrapper, VerifyVyperMultiPartRequestWrapper},
+    usage::{self, UsageMeter},
+    validation::{self, ValidationError},
 };
 use smart_contract_verifier::{
-    vyper, Compilers, ListFetcher, VerificationError, VyperClient, VyperCompiler,
+    analyzer::{
+        failure_classifier::{self, RequestedSettings},
+        settings_inference::InferredSettings,
+    },
+    vyper, Compilers, Downloader, ListFetcher, VerificationError, VyperClient, VyperCompiler,
 };
 use std::sync::Arc;
 use tokio::sync::Semaphore;
@@ -16,6 +28,11 @@ use tonic::{Request, Response, Status};
 
 pub struct VyperVerifierService {
     client: Arc<VyperClient>,
+    signer: Option<Arc<RecordSigner>>,
+    tenants: Option<Arc<Tenants>>,
+    known_contracts: Option<Arc<KnownContracts>>,
+    failure_stats: Option<Arc<FailureStats>>,
+    usage_meter: Option<Arc<UsageMeter>>,
 }
 
 impl VyperVerifierService {
@@ -26,22 +43,27 @@ impl VyperVerifierService {
         #[allow(unused_variables)] extensions: Extensions,
     ) -> anyhow::Result<Self> {
         let dir = settings.compilers_dir.clone();
-        let list_url = match settings.fetcher {
-            FetcherSettings::List(s) => s.list_url,
+        let list_settings = match settings.fetcher {
+            FetcherSettings::List(s) => s,
             FetcherSettings::S3(_) => {
                 return Err(anyhow::anyhow!("S3 fetcher for vyper not supported"))
             }
+            FetcherSettings::Mock(_) => {
+                return Err(anyhow::anyhow!("mock fetcher for vyper not supported"))
+            }
         };
         let fetcher = Arc::new(
             ListFetcher::new(
-                list_url,
+                list_settings.list_url,
                 settings.compilers_dir,
                 Some(settings.refresh_versions_schedule),
                 None,
             )
-            .await?,
+            .await?
+            .with_downloader(Downloader::new(list_settings.max_parallel_downloads)),
         );
-        let compilers = Compilers::new(fetcher, VyperCompiler::new(), compilers_threads_semaphore);
+        let compilers = Compilers::new(fetcher, VyperCompiler::new(), compilers_threads_semaphore)
+            .with_limits(settings.limits.into());
         compilers.load_from_dir(&dir).await;
 
         /* Otherwise, results in compilation warning if all extensions are disabled */
@@ -57,8 +79,61 @@ impl VyperVerifierService {
 
         Ok(Self {
             client: Arc::new(client),
+            signer: None,
+            tenants: None,
+            known_contracts: None,
+            failure_stats: None,
+            usage_meter: None,
         })
     }
+
+    pub fn client(&self) -> Arc<VyperClient> {
+        self.client.clone()
+    }
+
+    pub fn with_signer(mut self, signer: Option<Arc<RecordSigner>>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    pub fn with_tenants(mut self, tenants: Option<Arc<Tenants>>) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
+    pub fn with_known_contracts(mut self, known_contracts: Option<Arc<KnownContracts>>) -> Self {
+        self.known_contracts = known_contracts;
+        self
+    }
+
+    pub fn with_failure_stats(mut self, failure_stats: Option<Arc<FailureStats>>) -> Self {
+        self.failure_stats = failure_stats;
+        self
+    }
+
+    pub fn with_usage_meter(mut self, usage_meter: Option<Arc<UsageMeter>>) -> Self {
+        self.usage_meter = usage_meter;
+        self
+    }
+
+    /// Describes the failure via the error catalog and classifies it for the failure stats.
+    /// Settings inference is not supported for Vyper bytecodes, so the failures
+    /// are classified by the mismatch reasons only.
+    fn failure_response(&self, err: &VerificationError, tenant: &str) -> VerifyResponseWrapper {
+        let category = failure_classifier::classify(
+            err,
+            &InferredSettings::default(),
+            &RequestedSettings::default(),
+        );
+        if let Some(category) = category {
+            failure_stats::record_failure(self.failure_stats.as_deref(), "vyper", category, tenant);
+        }
+
+        match Failure::from_verification_error(err) {
+            Some(failure) => VerifyResponseWrapper::failure(failure.with_category(category)),
+            None => VerifyResponseWrapper::err(err),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -67,24 +142,69 @@ impl VyperVerifier for VyperVerifierService {
         &self,
         request: Request<VerifyVyperMultiPartRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        tenant.check_compiler_version(&request.get_ref().compiler_version)?;
+        tenant.acquire_verification()?;
         let request: VerifyVyperMultiPartRequestWrapper = request.into_inner().into();
-        let result = vyper::multi_part::verify(self.client.clone(), request.try_into()?).await;
+        let verification_request: vyper::multi_part::VerificationRequest = request.try_into()?;
+        if let Some(response) = self.known_contracts.as_ref().and_then(|known_contracts| {
+            known_contracts.verify(
+                verification_request.creation_bytecode.as_ref(),
+                &verification_request.deployed_bytecode,
+            )
+        }) {
+            let response = response
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .signed(self.signer.as_deref());
+            metrics::count_verify_contract(
+                "vyper",
+                response.status().as_str_name(),
+                "known-bytecode",
+                tenant.id(),
+            );
+            return Ok(Response::new(response.into_inner()));
+        }
+        let result = usage::metered(
+            self.usage_meter.as_deref(),
+            "vyper",
+            tenant.id(),
+            vyper::multi_part::verify(self.client.clone(), verification_request),
+        )
+        .await;
 
         if let Ok(verification_success) = result {
-            let response = VerifyResponseWrapper::ok(verification_success);
-            metrics::count_verify_contract("vyper", response.status().as_str_name(), "multi-part");
+            let compiler_hash = self
+                .client
+                .compilers()
+                .compiler_hash(&verification_success.compiler_version)
+                .await;
+            let response = VerifyResponseWrapper::ok(verification_success)
+                .with_chain_checksum(tenant.checksum_chain_id())
+                .with_attestation(compiler_hash)
+                .signed(self.signer.as_deref());
+            metrics::count_verify_contract(
+                "vyper",
+                response.status().as_str_name(),
+                "multi-part",
+                tenant.id(),
+            );
             return Ok(Response::new(response.into_inner()));
         }
 
         let err = result.unwrap_err();
         match err {
             VerificationError::Compilation(_)
-            | VerificationError::NoMatchingContracts
-            | VerificationError::CompilerVersionMismatch(_) => {
-                Ok(Response::new(VerifyResponseWrapper::err(err).into_inner()))
-            }
-            VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
-                Err(Status::invalid_argument(err.to_string()))
+            | VerificationError::NoMatchingContracts(_)
+            | VerificationError::CompilerVersionMismatch(_)
+            | VerificationError::IncompatibleCompilerVersion { .. }
+            | VerificationError::MissingSources(_)
+            | VerificationError::UnsupportedOpcodes { .. } => Ok(Response::new(
+                self.failure_response(&err, tenant.id()).into_inner(),
+            )),
+            VerificationError::Initialization(_)
+            | VerificationError::VersionNotFound(_)
+            | VerificationError::LimitExceeded(_) => {
+                Err(ValidationError::from_verification_error(&err).into())
             }
             VerificationError::Internal(_) => Err(Status::internal(err.to_string())),
         }
@@ -92,11 +212,42 @@ impl VyperVerifier for VyperVerifierService {
 
     async fn list_compiler_versions(
         &self,
-        _request: Request<ListCompilerVersionsRequest>,
+        request: Request<ListCompilerVersionsRequest>,
     ) -> Result<Response<ListCompilerVersionsResponse>, Status> {
-        let compiler_versions = self.client.compilers().all_versions_sorted_str();
+        let tenant = TenantContext::resolve(self.tenants.as_deref(), &request)?;
+        let compiler_versions =
+            tenant.filter_compiler_versions(self.client.compilers().all_versions_sorted_str());
         Ok(Response::new(ListCompilerVersionsResponse {
             compiler_versions,
         }))
     }
+
+    async fn detect_compiler_version(
+        &self,
+        request: Request<DetectVyperCompilerVersionRequest>,
+    ) -> Result<Response<DetectVyperCompilerVersionResponse>, Status> {
+        let bytecode =
+            validation::parse_hex("bytecode", "bytecode", &request.into_inner().bytecode)?;
+
+        let version = vyper::metadata::detect_compiler_version(&bytecode);
+        let mut compiler_versions: Vec<_> = self
+            .client
+            .compilers()
+            .all_versions()
+            .into_iter()
+            .filter(|compiler_version| {
+                let v = compiler_version.version();
+                version.as_ref().map_or(false, |version| {
+                    (v.major, v.minor, v.patch) == (version.major, version.minor, version.patch)
+                })
+            })
+            .collect();
+        // sort in descending order
+        compiler_versions.sort_by(|x, y| x.cmp(y).reverse());
+
+        Ok(Response::new(DetectVyperCompilerVersionResponse {
+            version: version.map(|version| version.to_string()),
+            compiler_versions: compiler_versions.iter().map(ToString::to_string).collect(),
+        }))
+    }
 }
@@ -1,4 +1,5 @@
 use anyhow::anyhow;
+use blockscout_display_bytes::Bytes as DisplayBytes;
 use blockscout_service_launcher::{
     JaegerSettings, MetricsSettings, ServerSettings, TracingSettings,
 };
@@ -7,12 +8,17 @@ use cron::Schedule;
 use serde::{de, Deserialize};
 use serde_with::{serde_as, DisplayFromStr};
 use smart_contract_verifier::{
-    DEFAULT_SOLIDITY_COMPILER_LIST, DEFAULT_SOURCIFY_HOST, DEFAULT_VYPER_COMPILER_LIST,
+    expand_download_url, solidity::DEFAULT_MAX_PARALLEL_COMPILATIONS_PER_REQUEST,
+    CompilationLimits, EvmFork, SolcBackendKind, SourcesDirs, Version, DEFAULT_HUFF_COMPILER_LIST,
+    DEFAULT_MAX_PARALLEL_DOWNLOADS, DEFAULT_SOLIDITY_COMPILER_LIST, DEFAULT_SOURCIFY_HOST,
+    DEFAULT_VYPER_COMPILER_LIST,
 };
 use std::{
+    collections::BTreeMap,
     num::{NonZeroU32, NonZeroUsize},
     path::PathBuf,
     str::FromStr,
+    time::Duration,
 };
 use url::Url;
 
@@ -34,13 +40,27 @@ impl Eq for IgnoredAny {}
 #[serde(default, deny_unknown_fields)]
 pub struct Settings {
     pub server: ServerSettings,
+    pub compression: CompressionSettings,
     pub solidity: SoliditySettings,
     pub vyper: VyperSettings,
+    pub huff: HuffSettings,
     pub sourcify: SourcifySettings,
+    pub analyzer: AnalyzerSettings,
+    pub signing: SigningSettings,
+    pub known_contracts: KnownContractsSettings,
+    pub tenants: TenantsSettings,
+    pub chains: ChainsSettings,
+    pub admin: AdminSettings,
+    pub failure_stats: FailureStatsSettings,
+    pub usage: UsageSettings,
+    pub fixtures: FixturesSettings,
     pub metrics: MetricsSettings,
     pub jaeger: JaegerSettings,
     pub tracing: TracingSettings,
     pub compilers: CompilersSettings,
+    pub load_shedding: LoadSheddingSettings,
+    pub idempotency: IdempotencySettings,
+    pub debug_logging: DebugLoggingSettings,
     pub extensions: ExtensionsSettings,
 
     // Is required as we deny unknown fields, but allow users provide
@@ -50,6 +70,23 @@ pub struct Settings {
     config_path: IgnoredAny,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompressionSettings {
+    /// Maximum size of http request bodies after decompression (in bytes).
+    /// Protects the service from compression bombs. Replaces `server.http.max_body_size`
+    /// for the service routes.
+    pub max_decompressed_size: usize,
+}
+
+impl Default for CompressionSettings {
+    fn default() -> Self {
+        Self {
+            max_decompressed_size: 50 * 1024 * 1024, // 50 Mb
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(default, deny_unknown_fields)]
@@ -59,6 +96,49 @@ pub struct SoliditySettings {
     #[serde_as(as = "DisplayFromStr")]
     pub refresh_versions_schedule: Schedule,
     pub fetcher: FetcherSettings,
+    /// Maximum number of compilations all verification requests may run concurrently.
+    /// Requests wait for the compilations in the order they have been started in.
+    pub max_parallel_compilations: NonZeroUsize,
+    /// Maximum number of compilations a single verification request may run concurrently.
+    /// Is used to prevent one request from occupying all compilation slots.
+    pub max_parallel_compilations_per_request: NonZeroUsize,
+    /// Known patches of predeployed (genesis) contracts bytecode, by the patch name.
+    /// Bytes inside the ranges are ignored when a request references the patch.
+    pub genesis_patches: BTreeMap<String, GenesisPatchSettings>,
+    /// Mirror of npm packages (e.g., `https://unpkg.com`) the well-known dependencies
+    /// (e.g., `@openzeppelin/contracts/...`) missing from the sources are fetched from.
+    pub package_mirror: Option<Url>,
+    /// Whether bytecodes starting with `0xEF00` are parsed as EOF containers (EIP-3540).
+    /// Should be enabled only if the chain supports EOF. May be overridden by the tenants.
+    pub eof_enabled: bool,
+    /// EVM version supported by the chain (e.g., "paris" for chains without `PUSH0`).
+    /// If set, failed verifications report the compiled opcodes the chain does not support.
+    /// May be overridden by the tenants.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub evm_fork: Option<EvmFork>,
+    /// How compilers supporting the standard json input are invoked:
+    /// "ethers-solc" (default), "standard-json" (runs `solc --standard-json` directly),
+    /// "standard-json-pool" (as "standard-json", but keeps warm compiler processes),
+    /// or "mock" (returns the outputs from `mock_outputs_dir`; for tests and local development).
+    #[serde_as(as = "DisplayFromStr")]
+    pub compiler_backend: SolcBackendKind,
+    /// Is used only by the "standard-json-pool" compiler backend
+    pub compiler_pool: CompilerPoolSettings,
+    /// Is used only by the "mock" compiler backend. Contains the compiler outputs
+    /// stored as `{input hash}.json` files (hex encoded sha256 of the json encoded input).
+    pub mock_outputs_dir: Option<PathBuf>,
+    pub limits: CompilationLimitsSettings,
+    /// Is used only by the compilers older than 0.4.11, which read the sources from the disk
+    pub sources_dirs: SourcesDirsSettings,
+    /// Byte patterns excluded from the comparison, by the pattern names. Patterns are hex
+    /// strings where `??` stands for any byte (e.g., `73` followed by 20 `??` and `30`
+    /// masks the address pushed before `ADDRESS`). Matched bytecodes result in a partial match.
+    pub normalization_patterns: BTreeMap<String, String>,
+    pub dependencies: DependenciesSettings,
+    pub github_repositories: GithubRepositoriesSettings,
+    /// Alternative compiler families (e.g., chain-specific forks of solc) by their names.
+    /// Requests select the family via the `compiler` field; the upstream solc is used otherwise.
+    pub compiler_families: BTreeMap<String, CompilerFamilySettings>,
 }
 
 impl Default for SoliditySettings {
@@ -70,10 +150,202 @@ impl Default for SoliditySettings {
             compilers_dir: default_dir,
             refresh_versions_schedule: Schedule::from_str("0 0 * * * * *").unwrap(), // every hour
             fetcher: Default::default(),
+            max_parallel_compilations: std::thread::available_parallelism()
+                .unwrap_or_else(|_| NonZeroUsize::new(8).unwrap()),
+            max_parallel_compilations_per_request: NonZeroUsize::new(
+                DEFAULT_MAX_PARALLEL_COMPILATIONS_PER_REQUEST,
+            )
+            .unwrap(),
+            genesis_patches: Default::default(),
+            package_mirror: None,
+            eof_enabled: false,
+            evm_fork: None,
+            compiler_backend: Default::default(),
+            compiler_pool: Default::default(),
+            mock_outputs_dir: None,
+            limits: Default::default(),
+            sources_dirs: Default::default(),
+            normalization_patterns: Default::default(),
+            dependencies: Default::default(),
+            github_repositories: Default::default(),
+            compiler_families: Default::default(),
+        }
+    }
+}
+
+/// Warm `solc --standard-json` processes kept spawned and waiting for the input.
+/// Solc compiles a single input per process, so each process is replaced after its use.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompilerPoolSettings {
+    /// Number of warm processes kept per compiler version
+    pub warm_processes: NonZeroUsize,
+    /// Time (in seconds) after which unused warm processes are recycled
+    pub max_idle_time: u64,
+}
+
+impl Default for CompilerPoolSettings {
+    fn default() -> Self {
+        Self {
+            warm_processes: NonZeroUsize::new(2).unwrap(),
+            max_idle_time: 300,
+        }
+    }
+}
+
+/// Limits of the compiler inputs and outputs. Requests exceeding them fail
+/// with the `LIMIT_EXCEEDED` error. Not set limits are not enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompilationLimitsSettings {
+    /// Maximum number of source files
+    pub max_source_files: Option<usize>,
+    /// Maximum total size of the source files (in bytes)
+    pub max_sources_size: Option<usize>,
+    /// Maximum size of the json encoded compiler output (in bytes)
+    pub max_output_size: Option<usize>,
+}
+
+impl From<CompilationLimitsSettings> for CompilationLimits {
+    fn from(settings: CompilationLimitsSettings) -> Self {
+        Self {
+            max_source_files: settings.max_source_files,
+            max_sources_size: settings.max_sources_size,
+            max_output_size: settings.max_output_size,
+        }
+    }
+}
+
+/// Ephemeral directories the sources are written to for the compilers reading them from the disk.
+/// Each compilation removes its directory on completion; the directories leaked anyway
+/// (e.g., the process was killed) are removed once they get older than `max_age`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SourcesDirsSettings {
+    /// Directory the per-compilation directories are created in
+    pub root: PathBuf,
+    /// Maximum total size (in bytes) of the sources written to disk at the same time.
+    /// Compilations exceeding it fail. Not enforced if not set
+    pub max_total_size: Option<u64>,
+    /// Age (in seconds) after which the directories are considered leaked.
+    /// Should exceed the longest compilation time
+    pub max_age: u64,
+    /// Period (in seconds) the leaked directories are looked for with. Zero disables the cleanup
+    pub cleanup_interval: u64,
+}
+
+impl Default for SourcesDirsSettings {
+    fn default() -> Self {
+        Self {
+            root: SourcesDirs::default().root().to_path_buf(),
+            max_total_size: None,
+            max_age: 3600,
+            cleanup_interval: 300,
         }
     }
 }
 
+impl SourcesDirsSettings {
+    /// Creates the directories and spawns the task removing the leaked ones
+    pub fn sources_dirs(&self) -> SourcesDirs {
+        let sources_dirs =
+            SourcesDirs::new(self.root.clone()).with_max_total_size(self.max_total_size);
+        if self.cleanup_interval > 0 {
+            sources_dirs.spawn_janitor(
+                Duration::from_secs(self.max_age),
+                Duration::from_secs(self.cleanup_interval),
+            );
+        }
+        sources_dirs
+    }
+}
+
+/// Pinned dependencies (e.g., `@openzeppelin/contracts@4.9.3`) referenced via remappings
+/// but missing from the sources. Only the packages from the allowlist are fetched.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DependenciesSettings {
+    pub enabled: bool,
+    pub npm_registry: Url,
+    /// Host the archives of GitHub tags are downloaded from
+    pub github_url: Url,
+    pub allowed_packages: BTreeMap<String, AllowedPackageSettings>,
+}
+
+impl Default for DependenciesSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            npm_registry: Url::from_str("https://registry.npmjs.org/").unwrap(),
+            github_url: Url::from_str("https://codeload.github.com/").unwrap(),
+            allowed_packages: Default::default(),
+        }
+    }
+}
+
+/// Verification of the contracts from the sources of GitHub repositories at the specified commits.
+/// Requires outbound requests to GitHub, so is disabled by default.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GithubRepositoriesSettings {
+    pub enabled: bool,
+    /// Host the archives of repository commits are downloaded from
+    pub github_url: Url,
+}
+
+impl Default for GithubRepositoriesSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            github_url: Url::from_str("https://codeload.github.com/").unwrap(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AllowedPackageSettings {
+    /// GitHub repository (`owner/name`) to fetch the package from by tags instead of npm
+    pub github_repository: Option<String>,
+    /// Prefix of the tags corresponding to the package versions (e.g., `v`)
+    pub tag_prefix: String,
+    /// Directory of the repository the package files are located at
+    pub directory: String,
+    /// Expected sha256 checksums of the package archives by the package versions
+    pub checksums: BTreeMap<String, String>,
+}
+
+/// Fork of the compiler released separately from the upstream solc. The compilers are stored
+/// in the `families/{name}` subdirectory of `solidity.compilers_dir`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompilerFamilySettings {
+    /// Url of the `list.json` file (in the solc-bin format) listing the released versions
+    pub list_url: Url,
+    /// Url the compilers are downloaded from instead of the paths of the list.
+    /// `{version}` is replaced with the full version (e.g., `v0.8.19+commit.7dd6d404`),
+    /// and `{short_version}` with the version without the commit hash (e.g., `0.8.19`)
+    pub download_url_template: Option<String>,
+}
+
+/// Patch of the predeployed contracts, which deployed bytecodes are known in advance.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisPatchSettings {
+    /// Keccak256 hashes of the deployed bytecodes (as they are stored in the genesis)
+    /// the patch may be applied to. Requests for any other bytecode are rejected.
+    pub code_hashes: Vec<DisplayBytes>,
+    pub ranges: Vec<ByteRangeSettings>,
+}
+
+/// Range of bytes of the deployed bytecode. `start` is inclusive, while `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ByteRangeSettings {
+    pub start: usize,
+    pub end: usize,
+}
+
 #[serde_as]
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(default, deny_unknown_fields)]
@@ -83,6 +355,7 @@ pub struct VyperSettings {
     #[serde_as(as = "DisplayFromStr")]
     pub refresh_versions_schedule: Schedule,
     pub fetcher: FetcherSettings,
+    pub limits: CompilationLimitsSettings,
 }
 
 impl Default for VyperSettings {
@@ -91,12 +364,46 @@ impl Default for VyperSettings {
         default_dir.push("vyper-compilers");
         let fetcher = FetcherSettings::List(ListFetcherSettings {
             list_url: Url::try_from(DEFAULT_VYPER_COMPILER_LIST).expect("valid url"),
+            ..Default::default()
         });
         Self {
             enabled: true,
             compilers_dir: default_dir,
             refresh_versions_schedule: Schedule::from_str("0 0 * * * * *").unwrap(), // every hour
             fetcher,
+            limits: Default::default(),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HuffSettings {
+    pub enabled: bool,
+    pub compilers_dir: PathBuf,
+    #[serde_as(as = "DisplayFromStr")]
+    pub refresh_versions_schedule: Schedule,
+    pub fetcher: FetcherSettings,
+    pub limits: CompilationLimitsSettings,
+    pub sources_dirs: SourcesDirsSettings,
+}
+
+impl Default for HuffSettings {
+    fn default() -> Self {
+        let mut default_dir = std::env::temp_dir();
+        default_dir.push("huff-compilers");
+        let fetcher = FetcherSettings::List(ListFetcherSettings {
+            list_url: Url::try_from(DEFAULT_HUFF_COMPILER_LIST).expect("valid url"),
+            ..Default::default()
+        });
+        Self {
+            enabled: false,
+            compilers_dir: default_dir,
+            refresh_versions_schedule: Schedule::from_str("0 0 * * * * *").unwrap(), // every hour
+            fetcher,
+            limits: Default::default(),
+            sources_dirs: Default::default(),
         }
     }
 }
@@ -106,6 +413,7 @@ impl Default for VyperSettings {
 pub enum FetcherSettings {
     List(ListFetcherSettings),
     S3(S3FetcherSettings),
+    Mock(MockFetcherSettings),
 }
 
 impl Default for FetcherSettings {
@@ -118,12 +426,15 @@ impl Default for FetcherSettings {
 #[serde(default, deny_unknown_fields)]
 pub struct ListFetcherSettings {
     pub list_url: Url,
+    /// Compilers downloaded at once; interrupted downloads are resumed
+    pub max_parallel_downloads: NonZeroUsize,
 }
 
 impl Default for ListFetcherSettings {
     fn default() -> Self {
         Self {
             list_url: Url::try_from(DEFAULT_SOLIDITY_COMPILER_LIST).expect("valid url"),
+            max_parallel_downloads: NonZeroUsize::new(DEFAULT_MAX_PARALLEL_DOWNLOADS).unwrap(),
         }
     }
 }
@@ -138,6 +449,16 @@ pub struct S3FetcherSettings {
     pub bucket: String,
 }
 
+/// Provides the listed compiler versions without downloading them.
+/// Is expected to be used along with the "mock" compiler backend.
+#[serde_as]
+#[derive(Deserialize, Default, Clone, PartialEq, Eq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MockFetcherSettings {
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub versions: Vec<Version>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct SourcifySettings {
@@ -160,6 +481,297 @@ impl Default for SourcifySettings {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalyzerSettings {
+    pub enabled: bool,
+}
+
+impl Default for AnalyzerSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SigningSettings {
+    pub enabled: bool,
+    /// Identifier of the key used to sign new verification records
+    pub active_key: Option<String>,
+    /// All keys known by the service. To rotate the keys, add a new one
+    /// and make it active, while keeping the public parts of the previous ones,
+    /// so that signatures made by them could still be validated.
+    pub keys: Vec<SigningKeySettings>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SigningKeySettings {
+    pub id: String,
+    /// Hex encoded 32-bytes Ed25519 seed. Required for the active key only
+    pub private_key: Option<String>,
+    /// Hex encoded Ed25519 public key. Is derived from the private key if the latter is set
+    pub public_key: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KnownContractsSettings {
+    pub enabled: bool,
+    /// Directory with JSON descriptions of well-known contracts (one contract per file).
+    /// Bytecodes found there are verified without compilation.
+    pub dir: PathBuf,
+}
+
+impl Default for KnownContractsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: PathBuf::from("known-contracts"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TenantsSettings {
+    pub enabled: bool,
+    /// Header containing the tenant id. Should be set by a trusted proxy only,
+    /// as no authentication is performed for it. If absent, only api keys are used.
+    pub tenant_header: Option<String>,
+    /// Header containing the api key of the tenant
+    pub api_key_header: String,
+    /// If set, requests which do not belong to any tenant are rejected
+    pub require_tenant: bool,
+    pub tenants: BTreeMap<String, TenantSettings>,
+}
+
+impl Default for TenantsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tenant_header: None,
+            api_key_header: "x-api-key".to_string(),
+            require_tenant: false,
+            tenants: Default::default(),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TenantSettings {
+    pub api_keys: Vec<String>,
+    /// Semver requirements (e.g. ">=0.8.0") for compiler versions the tenant may use.
+    /// All versions are allowed if absent.
+    pub allowed_compilers: Option<Vec<String>>,
+    /// Semver requirements for compiler versions the tenant may not use
+    pub denied_compilers: Vec<String>,
+    /// Maximum number of verification requests per minute
+    pub max_verifications_per_minute: Option<NonZeroU32>,
+    /// Chain id to include into the address checksums (EIP-1191, e.g. 30 for RSK).
+    /// Addresses are EIP-55 checksummed if absent.
+    pub checksum_chain_id: Option<u64>,
+    /// Whether the chain of the tenant supports EOF (EIP-3540).
+    /// `solidity.eof_enabled` is used if absent.
+    pub eof_enabled: Option<bool>,
+    /// EVM version supported by the chain of the tenant. The current fork of `chain_id`
+    /// from the chains registry, or `solidity.evm_fork`, is used if absent.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub evm_fork: Option<EvmFork>,
+    /// Chain of the tenant. Its EVM version and normalization patterns are taken
+    /// from the chains registry. Should be either embedded or configured via `chains.overrides`.
+    pub chain_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainsSettings {
+    /// Chains added to the embedded registry or replacing the embedded ones, by the chain ids
+    pub overrides: BTreeMap<String, ChainSettings>,
+}
+
+/// Unset fields of the chains present in the embedded registry keep their embedded values
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainSettings {
+    /// Required for the chains missing from the embedded registry
+    pub name: Option<String>,
+    pub explorer_url: Option<Url>,
+    /// Replaces the embedded fork schedule if not empty
+    pub forks: Vec<ForkActivationSettings>,
+    /// Byte patterns (in the `solidity.normalization_patterns` format) applied
+    /// to the bytecodes of the chain in addition to the global ones
+    pub normalization_patterns: BTreeMap<String, String>,
+}
+
+/// Exactly one of `block` or `timestamp` should be set
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForkActivationSettings {
+    #[serde_as(as = "DisplayFromStr")]
+    pub fork: EvmFork,
+    pub block: Option<u64>,
+    /// Unix timestamp (in seconds)
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminSettings {
+    pub enabled: bool,
+    /// Header containing the admin api key
+    pub api_key_header: String,
+    pub api_keys: Vec<String>,
+}
+
+impl Default for AdminSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_key_header: "x-admin-key".to_string(),
+            api_keys: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FailureStatsSettings {
+    /// When enabled, failed verifications are counted by the most likely mistake,
+    /// and the counts are exposed via the stats endpoint. Counting via metrics
+    /// is independent of the setting.
+    pub enabled: bool,
+}
+
+/// Accounting of the compute resources (compiler invocations, compilation time,
+/// and compiled bytes) consumed by the verifications of each tenant.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UsageSettings {
+    /// When enabled, the usage is accounted per tenant and exposed via the usage endpoint
+    /// to the tenants (their own usage) and to the admins (usage of all tenants).
+    /// Reporting via metrics is independent of the setting.
+    pub enabled: bool,
+    /// Url the usage of each verification is posted to (e.g., by an external billing system)
+    pub billing_hook_url: Option<Url>,
+    /// Timeout (in seconds) of the requests to the billing hook
+    pub billing_hook_timeout: u64,
+}
+
+impl Default for UsageSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            billing_hook_url: None,
+            billing_hook_timeout: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FixturesSettings {
+    /// When enabled, canned verification responses are available for the frontend development.
+    /// Should not be enabled in production.
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoadSheddingSettings {
+    /// When enabled, low priority requests are rejected while the compilation queue
+    /// is overloaded. Latencies of the endpoints are tracked regardless.
+    pub enabled: bool,
+    /// Number of compilations waiting for a free thread, exceeding which
+    /// the queue is considered overloaded
+    pub max_queued_compilations: usize,
+    /// Delay (in seconds) clients are asked to retry the rejected requests after
+    pub retry_after: u64,
+    /// Header marking the request as a low priority one (if its value is `low`)
+    pub priority_header: String,
+    /// Routes all requests to which are low priority (e.g., batch verification)
+    pub low_priority_routes: Vec<String>,
+    /// Period (in seconds) the latency percentiles are calculated over
+    pub latency_window: u64,
+    /// Target 95th percentile latency (in milliseconds). Breaches are reported via metrics
+    pub latency_slo: Option<u64>,
+}
+
+impl Default for LoadSheddingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_queued_compilations: 64,
+            retry_after: 30,
+            priority_header: "x-priority".to_string(),
+            low_priority_routes: vec![
+                "/api/v2/verifier/solidity/sources:verify-standard-json-batch".to_string(),
+            ],
+            latency_window: 300,
+            latency_slo: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdempotencySettings {
+    /// When enabled, repeated http requests marked with the same idempotency key
+    /// are answered with the response to the first one instead of being processed again.
+    pub enabled: bool,
+    /// Header containing the idempotency key
+    pub header: String,
+    /// Time (in seconds) the responses are kept for
+    pub window: u64,
+    /// Maximum number of keys kept. While the limit is reached,
+    /// requests with new keys are processed as the unmarked ones.
+    pub max_keys: usize,
+}
+
+impl Default for IdempotencySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            header: "idempotency-key".to_string(),
+            window: 3600,
+            max_keys: 10000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DebugLoggingSettings {
+    /// When enabled, bodies of the http requests marked with the debug header
+    /// and of their responses are logged. Should be enabled while debugging only.
+    pub enabled: bool,
+    /// Header the requests to be logged are marked with (as `{header}: true`)
+    pub header: String,
+    /// Maximum number of bytes logged per body. Longer bodies are truncated.
+    pub max_body_size: usize,
+    /// Json fields (at any depth) whose values are redacted. Case, `-` and `_` are ignored.
+    /// Values of the tenant and admin api key headers are always redacted.
+    pub redacted_fields: Vec<String>,
+}
+
+impl Default for DebugLoggingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            header: "x-debug-log".to_string(),
+            max_body_size: 16 * 1024,
+            redacted_fields: ["api_key", "token", "password", "secret", "private_key"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct CompilersSettings {
@@ -182,6 +794,7 @@ pub struct ExtensionsSettings {
     pub solidity: Extensions,
     pub sourcify: Extensions,
     pub vyper: Extensions,
+    pub huff: Extensions,
 }
 
 #[derive(Default, Deserialize, Clone, PartialEq, Eq, Debug)]
@@ -212,6 +825,54 @@ impl Settings {
     }
 
     fn validate(&self) -> anyhow::Result<()> {
+        // Validate genesis patches
+        for (name, patch) in &self.solidity.genesis_patches {
+            if patch.code_hashes.is_empty() {
+                return Err(anyhow!(
+                    "genesis patch `{name}` should be restricted by code hashes"
+                ));
+            }
+            if let Some(hash) = patch.code_hashes.iter().find(|hash| hash.len() != 32) {
+                return Err(anyhow!(
+                    "genesis patch `{name}` contains an invalid code hash: {hash}"
+                ));
+            }
+            if let Some(range) = patch.ranges.iter().find(|range| range.start >= range.end) {
+                return Err(anyhow!(
+                    "genesis patch `{name}` contains an empty byte range: {}..{}",
+                    range.start,
+                    range.end
+                ));
+            }
+        }
+
+        // Validate compiler families
+        for (name, family) in &self.solidity.compiler_families {
+            if name.is_empty() {
+                return Err(anyhow!("compiler family name should not be empty"));
+            }
+            if let Some(template) = &family.download_url_template {
+                let version = Version::from_str("0.8.19+commit.7dd6d404").unwrap();
+                expand_download_url(template, &version).map_err(|err| {
+                    anyhow!("compiler family `{name}` has invalid download url template: {err}")
+                })?;
+            }
+        }
+
+        // Validate usage accounting
+        if !self.usage.enabled && self.usage.billing_hook_url.is_some() {
+            return Err(anyhow!(
+                "billing hook is configured, but usage accounting is disabled"
+            ));
+        }
+
+        // Validate mock compiler
+        if self.solidity.compiler_backend == SolcBackendKind::Mock
+            && self.solidity.mock_outputs_dir.is_none()
+        {
+            return Err(anyhow!("mock compiler backend requires `mock_outputs_dir`"));
+        }
+
         // Validate s3 fetcher
         if let FetcherSettings::S3(settings) = &self.solidity.fetcher {
             if settings.region.is_none() && settings.endpoint.is_none() {
@@ -0,0 +1,309 @@
+use crate::{
+    proto::{verify_response::extra_data::SignedRecord, Source, VerifyResponse},
+    settings::SigningSettings,
+};
+use anyhow::Context;
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use ring::signature::{self, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The data signed by the service for each successful verification.
+///
+/// Is serialized into JSON, and the signature is calculated
+/// over the bytes of the resultant string.
+#[derive(Debug, Serialize)]
+struct VerificationRecord<'a> {
+    file_name: &'a str,
+    contract_name: &'a str,
+    compiler_version: &'a str,
+    match_type: &'a str,
+    /// sha256 of the JSON encoded map from source file names to their contents
+    sources_hash: String,
+    /// sha256 of the compiler settings as returned in the response
+    settings_hash: String,
+    /// sha256 of the locally compiled deployed bytecode. Is absent for Sourcify verification.
+    bytecode_hash: Option<String>,
+    /// Unix timestamp (in seconds) of the moment the record has been signed
+    timestamp: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyStatus {
+    Active,
+    Retired,
+}
+
+pub struct SigningKey {
+    pub id: String,
+    pub public_key: Vec<u8>,
+    pub status: KeyStatus,
+}
+
+/// Signs verification results with the active service key.
+///
+/// Keys are rotated by adding a new key into the configuration and making it active.
+/// Previous keys should be kept with their public parts only (as retired ones),
+/// so that the signatures they made could still be validated.
+pub struct RecordSigner {
+    active_key: (String, Ed25519KeyPair),
+    keys: BTreeMap<String, SigningKey>,
+}
+
+impl RecordSigner {
+    pub fn new(settings: &SigningSettings) -> anyhow::Result<Self> {
+        let active_key_id = settings
+            .active_key
+            .as_ref()
+            .context("active signing key is not specified")?;
+
+        let mut active_key = None;
+        let mut keys = BTreeMap::new();
+        for key in &settings.keys {
+            let is_active = &key.id == active_key_id;
+            let public_key = match (&key.private_key, &key.public_key) {
+                (Some(private_key), _) => {
+                    let seed = DisplayBytes::from_str(private_key)
+                        .with_context(|| format!("invalid private key of `{}`", key.id))?;
+                    let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed.0).map_err(|err| {
+                        anyhow::anyhow!("invalid private key of `{}`: {err}", key.id)
+                    })?;
+                    let public_key = key_pair.public_key().as_ref().to_vec();
+                    if is_active {
+                        active_key = Some((key.id.clone(), key_pair));
+                    }
+                    public_key
+                }
+                (None, Some(public_key)) => DisplayBytes::from_str(public_key)
+                    .with_context(|| format!("invalid public key of `{}`", key.id))?
+                    .0
+                    .to_vec(),
+                (None, None) => anyhow::bail!(
+                    "neither private nor public key of `{}` is specified",
+                    key.id
+                ),
+            };
+            let status = if is_active {
+                KeyStatus::Active
+            } else {
+                KeyStatus::Retired
+            };
+            let signing_key = SigningKey {
+                id: key.id.clone(),
+                public_key,
+                status,
+            };
+            if keys.insert(key.id.clone(), signing_key).is_some() {
+                anyhow::bail!("signing key `{}` is specified more than once", key.id)
+            }
+        }
+
+        let active_key = active_key.with_context(|| {
+            format!("private key of the active signing key `{active_key_id}` is not specified")
+        })?;
+        Ok(Self { active_key, keys })
+    }
+
+    /// Attaches signed verification record to the successful verification response.
+    pub fn sign(&self, response: &mut VerifyResponse) {
+        let (source, extra_data) = match (&response.source, &mut response.extra_data) {
+            (Some(source), Some(extra_data)) => (source, extra_data),
+            _ => return,
+        };
+
+        let bytecode = extra_data
+            .local_deployed_bytecode_parts
+            .iter()
+            .map(|part| DisplayBytes::from_str(&part.data).map(|bytes| bytes.0))
+            .collect::<Result<Vec<_>, _>>();
+        let bytecode = match bytecode {
+            Ok(parts) => parts.concat(),
+            Err(err) => {
+                // A record which does not reflect the bytecode should not be signed
+                tracing::error!(
+                    "bytecode parts are not valid hex, the response is not signed: {err}"
+                );
+                return;
+            }
+        };
+        let record = new_record(source, &bytecode);
+        let record = serde_json::to_string(&record).expect("record serialization never fails");
+
+        let (key_id, key_pair) = &self.active_key;
+        let signature = key_pair.sign(record.as_bytes());
+        extra_data.signed_record = Some(SignedRecord {
+            record,
+            key_id: key_id.clone(),
+            signature: DisplayBytes::from(signature.as_ref().to_vec()).to_string(),
+        });
+    }
+
+    /// Checks that the record has been signed by the specified service key.
+    /// Returns `None` if there is no key with the given id.
+    pub fn verify(
+        &self,
+        key_id: &str,
+        record: &str,
+        signature: &[u8],
+    ) -> Option<(bool, KeyStatus)> {
+        let key = self.keys.get(key_id)?;
+        let is_valid = UnparsedPublicKey::new(&signature::ED25519, &key.public_key)
+            .verify(record.as_bytes(), signature)
+            .is_ok();
+        Some((is_valid, key.status))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &SigningKey> {
+        self.keys.values()
+    }
+}
+
+fn new_record<'a>(source: &'a Source, deployed_bytecode: &[u8]) -> VerificationRecord<'a> {
+    let sources = serde_json::to_string(&source.source_files)
+        .expect("map of strings serialization never fails");
+    let bytecode_hash = (!deployed_bytecode.is_empty()).then(|| sha256(deployed_bytecode));
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("current time is after unix epoch")
+        .as_secs();
+
+    VerificationRecord {
+        file_name: &source.file_name,
+        contract_name: &source.contract_name,
+        compiler_version: &source.compiler_version,
+        match_type: source.match_type().as_str_name(),
+        sources_hash: sha256(sources.as_bytes()),
+        settings_hash: sha256(source.compiler_settings.as_bytes()),
+        bytecode_hash,
+        timestamp,
+    }
+}
+
+fn sha256(data: &[u8]) -> String {
+    DisplayBytes::from(Sha256::digest(data).to_vec()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        proto::verify_response::{extra_data::BytecodePart, ExtraData},
+        settings::SigningKeySettings,
+    };
+    use pretty_assertions::assert_eq;
+
+    const PRIVATE_KEY: &str = "0x9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const PUBLIC_KEY: &str = "0xd75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+    fn settings(keys: Vec<SigningKeySettings>) -> SigningSettings {
+        SigningSettings {
+            enabled: true,
+            active_key: Some("new".to_string()),
+            keys,
+        }
+    }
+
+    fn key(id: &str, private_key: Option<&str>, public_key: Option<&str>) -> SigningKeySettings {
+        SigningKeySettings {
+            id: id.to_string(),
+            private_key: private_key.map(str::to_string),
+            public_key: public_key.map(str::to_string),
+        }
+    }
+
+    fn response() -> VerifyResponse {
+        VerifyResponse {
+            source: Some(Source {
+                file_name: "A.sol".to_string(),
+                contract_name: "A".to_string(),
+                ..Default::default()
+            }),
+            extra_data: Some(ExtraData {
+                local_deployed_bytecode_parts: vec![BytecodePart {
+                    r#type: "main".to_string(),
+                    data: "0x6080".to_string(),
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let signer = RecordSigner::new(&settings(vec![key("new", Some(PRIVATE_KEY), None)]))
+            .expect("signer initialization failed");
+
+        let mut response = response();
+        signer.sign(&mut response);
+        let signed_record = response.extra_data.unwrap().signed_record.unwrap();
+        assert_eq!("new", signed_record.key_id);
+
+        let signature = DisplayBytes::from_str(&signed_record.signature).unwrap().0;
+        assert_eq!(
+            Some((true, KeyStatus::Active)),
+            signer.verify("new", &signed_record.record, &signature)
+        );
+
+        let tampered = signed_record.record.replace("A.sol", "B.sol");
+        assert_eq!(
+            Some((false, KeyStatus::Active)),
+            signer.verify("new", &tampered, &signature)
+        );
+        assert_eq!(
+            None,
+            signer.verify("unknown", &signed_record.record, &signature)
+        );
+    }
+
+    #[test]
+    fn invalid_bytecode_is_not_signed() {
+        let signer = RecordSigner::new(&settings(vec![key("new", Some(PRIVATE_KEY), None)]))
+            .expect("signer initialization failed");
+
+        let mut response = response();
+        response
+            .extra_data
+            .as_mut()
+            .unwrap()
+            .local_deployed_bytecode_parts[0]
+            .data = "0xzz".to_string();
+        signer.sign(&mut response);
+        assert_eq!(None, response.extra_data.unwrap().signed_record);
+    }
+
+    #[test]
+    fn retired_key_signatures_remain_valid() {
+        let old_signer = RecordSigner::new(&SigningSettings {
+            active_key: Some("old".to_string()),
+            ..settings(vec![key("old", Some(PRIVATE_KEY), None)])
+        })
+        .unwrap();
+        let mut response = response();
+        old_signer.sign(&mut response);
+        let signed_record = response.extra_data.unwrap().signed_record.unwrap();
+        let signature = DisplayBytes::from_str(&signed_record.signature).unwrap().0;
+
+        let new_private_key = format!("0x{}", "01".repeat(32));
+        let signer = RecordSigner::new(&settings(vec![
+            key("old", None, Some(PUBLIC_KEY)),
+            key("new", Some(&new_private_key), None),
+        ]))
+        .unwrap();
+        assert_eq!(
+            Some((true, KeyStatus::Retired)),
+            signer.verify("old", &signed_record.record, &signature)
+        );
+    }
+
+    #[test]
+    fn active_key_without_private_part_fails() {
+        let result = RecordSigner::new(&settings(vec![key("new", None, Some(PUBLIC_KEY))]));
+        assert!(result.is_err(), "signer should require active private key");
+    }
+}
@@ -0,0 +1,495 @@
+//! Isolation of the explorers sharing one verifier deployment.
+//!
+//! Each request is attributed to a tenant either by the API key or by the tenant id header
+//! (the latter is expected to be set by a trusted proxy). Tenants may have their own lists
+//! of allowed and denied compiler versions and verification quotas, and their requests
+//! are reported under separate metrics labels. Tenants bound to a chain from the chains
+//! registry validate and normalize the bytecodes according to the chain.
+
+use crate::{
+    settings::{TenantSettings, TenantsSettings},
+    validation::{ErrorCode, ValidationError},
+};
+use anyhow::Context;
+use semver::VersionReq;
+use smart_contract_verifier::{Chain, ChainRegistry, EvmFork, Normalization, Version};
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tonic::{Request, Status};
+
+/// Label used for the requests which do not belong to any tenant
+pub const DEFAULT_TENANT: &str = "default";
+
+const QUOTA_WINDOW: Duration = Duration::from_secs(60);
+
+pub struct Tenant {
+    id: String,
+    allowed_compilers: Option<Vec<VersionReq>>,
+    denied_compilers: Vec<VersionReq>,
+    quota: Option<Quota>,
+    checksum_chain_id: Option<u64>,
+    eof_enabled: Option<bool>,
+    evm_fork: Option<EvmFork>,
+    chain: Option<Arc<Chain>>,
+}
+
+/// Fixed-window limit on the number of verifications per minute
+struct Quota {
+    limit: NonZeroU32,
+    window: Mutex<(Instant, u32)>,
+}
+
+impl Quota {
+    fn new(limit: NonZeroU32) -> Self {
+        Self {
+            limit,
+            window: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    /// Either consumes all `count` verifications, or none of them if the quota is insufficient.
+    fn acquire(&self, now: Instant, count: u32) -> bool {
+        let mut window = self.window.lock().expect("quota lock is poisoned");
+        if now.duration_since(window.0) >= QUOTA_WINDOW {
+            *window = (now, 0);
+        }
+        match window.1.checked_add(count) {
+            Some(consumed) if consumed <= self.limit.get() => {
+                window.1 = consumed;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Tenant {
+    fn new(id: String, settings: &TenantSettings, chains: &ChainRegistry) -> anyhow::Result<Self> {
+        let parse = |requirements: &[String]| {
+            requirements
+                .iter()
+                .map(|requirement| {
+                    VersionReq::parse(requirement).with_context(|| {
+                        format!("tenant `{id}`: invalid compiler requirement `{requirement}`")
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        };
+        let chain = settings
+            .chain_id
+            .map(|chain_id| {
+                chains
+                    .get(chain_id)
+                    .with_context(|| format!("tenant `{id}`: unknown chain {chain_id}"))
+            })
+            .transpose()?;
+        Ok(Self {
+            allowed_compilers: settings
+                .allowed_compilers
+                .as_deref()
+                .map(parse)
+                .transpose()?,
+            denied_compilers: parse(&settings.denied_compilers)?,
+            quota: settings.max_verifications_per_minute.map(Quota::new),
+            checksum_chain_id: settings.checksum_chain_id,
+            eof_enabled: settings.eof_enabled,
+            evm_fork: settings.evm_fork,
+            chain,
+            id,
+        })
+    }
+
+    fn is_compiler_allowed(&self, version: &Version) -> bool {
+        // Pre-release and build parts are ignored, so that nightly builds
+        // are matched by the requirements of corresponding releases
+        let version = version.version();
+        let version = semver::Version::new(version.major, version.minor, version.patch);
+        let allowed = self.allowed_compilers.as_ref().map_or(true, |allowed| {
+            allowed.iter().any(|req| req.matches(&version))
+        });
+        allowed
+            && !self
+                .denied_compilers
+                .iter()
+                .any(|req| req.matches(&version))
+    }
+}
+
+pub struct Tenants {
+    tenant_header: Option<String>,
+    api_key_header: String,
+    require_tenant: bool,
+    tenants: HashMap<String, Arc<Tenant>>,
+    api_keys: HashMap<String, Arc<Tenant>>,
+}
+
+impl Tenants {
+    pub fn new(settings: &TenantsSettings, chains: &ChainRegistry) -> anyhow::Result<Self> {
+        let mut tenants = HashMap::new();
+        let mut api_keys = HashMap::new();
+        for (id, tenant_settings) in &settings.tenants {
+            let tenant = Arc::new(Tenant::new(id.clone(), tenant_settings, chains)?);
+            for api_key in &tenant_settings.api_keys {
+                if api_keys.insert(api_key.clone(), tenant.clone()).is_some() {
+                    anyhow::bail!("tenant `{id}`: api key is used by several tenants");
+                }
+            }
+            tenants.insert(id.clone(), tenant);
+        }
+        Ok(Self {
+            tenant_header: settings.tenant_header.clone(),
+            api_key_header: settings.api_key_header.clone(),
+            require_tenant: settings.require_tenant,
+            tenants,
+            api_keys,
+        })
+    }
+
+    fn resolve_tenant<T>(&self, request: &Request<T>) -> Result<Option<Arc<Tenant>>, Status> {
+        let header = |name: &str| {
+            request
+                .metadata()
+                .get(name)
+                .map(|value| {
+                    value.to_str().map_err(|_| {
+                        ValidationError::new(
+                            ErrorCode::InvalidValue,
+                            name,
+                            format!("invalid `{name}` header"),
+                        )
+                    })
+                })
+                .transpose()
+        };
+
+        if let Some(api_key) = header(&self.api_key_header)? {
+            return self
+                .api_keys
+                .get(api_key)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| Status::unauthenticated("unknown api key"));
+        }
+        if let Some(tenant_header) = &self.tenant_header {
+            if let Some(id) = header(tenant_header)? {
+                return self
+                    .tenants
+                    .get(id)
+                    .cloned()
+                    .map(Some)
+                    .ok_or_else(|| Status::unauthenticated(format!("unknown tenant `{id}`")));
+            }
+        }
+        if self.require_tenant {
+            return Err(Status::unauthenticated("tenant is not specified"));
+        }
+        Ok(None)
+    }
+}
+
+/// Tenant the request belongs to. Has no restrictions if tenants are disabled
+/// or the request does not belong to any tenant.
+#[derive(Clone, Default)]
+pub struct TenantContext(Option<Arc<Tenant>>);
+
+impl TenantContext {
+    pub fn resolve<T>(tenants: Option<&Tenants>, request: &Request<T>) -> Result<Self, Status> {
+        let tenant = match tenants {
+            Some(tenants) => tenants.resolve_tenant(request)?,
+            None => None,
+        };
+        Ok(Self(tenant))
+    }
+
+    /// Label the metrics of the request are reported under
+    pub fn id(&self) -> &str {
+        self.0
+            .as_ref()
+            .map_or(DEFAULT_TENANT, |tenant| tenant.id.as_str())
+    }
+
+    /// Id of the tenant the request belongs to, if any
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.0.as_ref().map(|tenant| tenant.id.as_str())
+    }
+
+    /// Chain id the addresses of the responses are checksummed with (EIP-1191)
+    pub fn checksum_chain_id(&self) -> Option<u64> {
+        self.0.as_ref().and_then(|tenant| tenant.checksum_chain_id)
+    }
+
+    /// Whether the chain of the tenant supports EOF. Returns `default` if not configured.
+    pub fn eof_enabled(&self, default: bool) -> bool {
+        self.0
+            .as_ref()
+            .and_then(|tenant| tenant.eof_enabled)
+            .unwrap_or(default)
+    }
+
+    /// EVM version supported by the chain of the tenant: either the configured one,
+    /// or the current fork of the tenant chain. Returns `default` if neither is set.
+    pub fn evm_fork(&self, default: Option<EvmFork>) -> Option<EvmFork> {
+        self.0
+            .as_ref()
+            .and_then(|tenant| {
+                tenant.evm_fork.or_else(|| {
+                    tenant
+                        .chain
+                        .as_ref()
+                        .and_then(|chain| chain.current_evm_fork())
+                })
+            })
+            .or(default)
+    }
+
+    /// The `default` normalization extended with the passes of the tenant chain
+    pub fn normalization(&self, default: &Normalization) -> Normalization {
+        let chain = self.0.as_ref().and_then(|tenant| tenant.chain.as_ref());
+        match chain {
+            Some(chain) => default.clone().with_passes_of(&chain.normalization),
+            None => default.clone(),
+        }
+    }
+
+    /// Consumes one verification from the tenant quota.
+    pub fn acquire_verification(&self) -> Result<(), Status> {
+        self.acquire_verifications(1)
+    }
+
+    /// Consumes a verification per target (e.g., of the batch request) from the tenant quota.
+    /// Nothing is consumed if the quota is not enough for all of them.
+    pub fn acquire_verifications(&self, count: usize) -> Result<(), Status> {
+        let quota = self.0.as_ref().and_then(|tenant| tenant.quota.as_ref());
+        let count = u32::try_from(count).unwrap_or(u32::MAX);
+        match quota {
+            Some(quota) if !quota.acquire(Instant::now(), count) => {
+                Err(Status::resource_exhausted(format!(
+                    "verification quota of tenant `{}` is exceeded",
+                    self.id()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn check_compiler_version(&self, compiler_version: &str) -> Result<(), Status> {
+        let tenant = match &self.0 {
+            Some(tenant) => tenant,
+            None => return Ok(()),
+        };
+        // Invalid versions are reported later by the request conversion
+        match Version::from_str(compiler_version) {
+            Ok(version) if !tenant.is_compiler_allowed(&version) => {
+                Err(Status::permission_denied(format!(
+                    "compiler version {compiler_version} is not allowed for tenant `{}`",
+                    tenant.id
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Leaves only the versions allowed for the tenant.
+    pub fn filter_compiler_versions(&self, versions: Vec<String>) -> Vec<String> {
+        match &self.0 {
+            None => versions,
+            Some(tenant) => versions
+                .into_iter()
+                .filter(|version| {
+                    Version::from_str(version)
+                        .map_or(false, |version| tenant.is_compiler_allowed(&version))
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use smart_contract_verifier::{
+        chains::{Activation, ForkActivation},
+        PatternPass,
+    };
+    use std::collections::BTreeMap;
+
+    fn settings() -> TenantsSettings {
+        TenantsSettings {
+            enabled: true,
+            tenant_header: Some("x-tenant-id".to_string()),
+            api_key_header: "x-api-key".to_string(),
+            require_tenant: false,
+            tenants: BTreeMap::from([
+                (
+                    "explorer-a".to_string(),
+                    TenantSettings {
+                        api_keys: vec!["key-a".to_string()],
+                        allowed_compilers: Some(vec![">=0.8.0".to_string()]),
+                        denied_compilers: vec!["=0.8.13".to_string()],
+                        max_verifications_per_minute: NonZeroU32::new(1),
+                        checksum_chain_id: Some(30),
+                        eof_enabled: Some(true),
+                        evm_fork: Some(EvmFork::Paris),
+                        chain_id: None,
+                    },
+                ),
+                ("explorer-b".to_string(), TenantSettings::default()),
+                (
+                    "explorer-c".to_string(),
+                    TenantSettings {
+                        chain_id: Some(31337),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        }
+    }
+
+    fn chains() -> ChainRegistry {
+        let devnet = Chain::new(31337, "Devnet")
+            .with_forks([ForkActivation {
+                fork: EvmFork::London,
+                activation: Activation::Block(0),
+            }])
+            .with_normalization(
+                Normalization::new().with_pass(PatternPass::new("address", "73????30").unwrap()),
+            );
+        ChainRegistry::new().with_chain(devnet)
+    }
+
+    fn request(headers: &[(&'static str, &'static str)]) -> Request<()> {
+        let mut request = Request::new(());
+        for (name, value) in headers {
+            request.metadata_mut().insert(*name, value.parse().unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn resolve_tenants() {
+        let tenants = Tenants::new(&settings(), &chains()).expect("valid settings");
+        let resolve = |headers: &[(&'static str, &'static str)]| {
+            TenantContext::resolve(Some(&tenants), &request(headers))
+        };
+
+        let tenant = resolve(&[("x-api-key", "key-a")]).expect("known api key");
+        assert_eq!("explorer-a", tenant.id());
+        assert!(tenant.eof_enabled(false));
+        assert_eq!(Some(EvmFork::Paris), tenant.evm_fork(None));
+        let tenant = resolve(&[("x-tenant-id", "explorer-b")]).expect("known tenant");
+        assert_eq!("explorer-b", tenant.id());
+        assert!(!tenant.eof_enabled(false));
+        assert_eq!(
+            Some(EvmFork::Shanghai),
+            tenant.evm_fork(Some(EvmFork::Shanghai))
+        );
+        let tenant = resolve(&[("x-tenant-id", "explorer-c")]).expect("known tenant");
+        assert_eq!(
+            Some(EvmFork::London),
+            tenant.evm_fork(Some(EvmFork::Shanghai)),
+            "chain fork should be used"
+        );
+        assert_eq!(
+            vec!["global", "address"],
+            tenant
+                .normalization(
+                    &Normalization::new().with_pass(PatternPass::new("global", "5f").unwrap())
+                )
+                .names()
+        );
+        let tenant = resolve(&[]).expect("tenant is not required");
+        assert_eq!(DEFAULT_TENANT, tenant.id());
+
+        resolve(&[("x-api-key", "key-b")]).expect_err("unknown api key");
+        resolve(&[("x-tenant-id", "explorer-d")]).expect_err("unknown tenant");
+
+        let tenants = Tenants::new(
+            &TenantsSettings {
+                require_tenant: true,
+                ..settings()
+            },
+            &chains(),
+        )
+        .unwrap();
+        TenantContext::resolve(Some(&tenants), &request(&[])).expect_err("tenant is required");
+    }
+
+    #[test]
+    fn unknown_chain() {
+        let mut settings = settings();
+        settings.tenants.insert(
+            "explorer-d".to_string(),
+            TenantSettings {
+                chain_id: Some(1),
+                ..Default::default()
+            },
+        );
+        Tenants::new(&settings, &chains()).expect_err("chain is missing from the registry");
+    }
+
+    #[test]
+    fn compiler_lists() {
+        let tenants = Tenants::new(&settings(), &chains()).expect("valid settings");
+        let tenant =
+            TenantContext::resolve(Some(&tenants), &request(&[("x-api-key", "key-a")])).unwrap();
+
+        tenant
+            .check_compiler_version("v0.8.17+commit.8df45f5f")
+            .expect("allowed version");
+        tenant
+            .check_compiler_version("v0.8.13+commit.abaa5c0e")
+            .expect_err("denied version");
+        tenant
+            .check_compiler_version("v0.7.6+commit.7338295f")
+            .expect_err("not allowed version");
+
+        let versions = vec![
+            "v0.8.17+commit.8df45f5f".to_string(),
+            "v0.8.13+commit.abaa5c0e".to_string(),
+            "v0.7.6+commit.7338295f".to_string(),
+        ];
+        assert_eq!(
+            vec!["v0.8.17+commit.8df45f5f".to_string()],
+            tenant.filter_compiler_versions(versions.clone())
+        );
+        assert_eq!(
+            versions.clone(),
+            TenantContext::default().filter_compiler_versions(versions)
+        );
+    }
+
+    #[test]
+    fn quota() {
+        let quota = Quota::new(NonZeroU32::new(2).unwrap());
+        let now = Instant::now();
+        assert!(quota.acquire(now, 1));
+        assert!(quota.acquire(now, 1));
+        assert!(!quota.acquire(now, 1), "quota should be exceeded");
+        assert!(
+            quota.acquire(now + QUOTA_WINDOW, 1),
+            "quota should be reset in the next window"
+        );
+    }
+
+    #[test]
+    fn quota_of_several_verifications() {
+        let quota = Quota::new(NonZeroU32::new(3).unwrap());
+        let now = Instant::now();
+        assert!(quota.acquire(now, 2));
+        assert!(
+            !quota.acquire(now, 2),
+            "quota should not be enough for all verifications"
+        );
+        assert!(
+            quota.acquire(now, 1),
+            "nothing should be consumed on failure"
+        );
+        assert!(!quota.acquire(now, u32::MAX), "overflow should not pass");
+    }
+}
@@ -0,0 +1,265 @@
+//! Generation of the Solidity test vectors used by the verifier test suites.
+//!
+//! The sources are compiled by the configured compilers, and the resulting bytecodes
+//! are written in the layout expected by the tests (`tests/contracts/<name>`), so that
+//! regression cases from bug reports could be added without deploying the contracts.
+//! The vector itself is stored as `test_vector.json` next to them.
+
+use crate::{services::SolidityVerifierService, settings::Settings};
+use anyhow::Context;
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use ethers_solc::{
+    artifacts::{Settings as CompilerSettings, Source, Sources},
+    CompilerInput,
+};
+use serde::Serialize;
+use smart_contract_verifier::{
+    solidity::{
+        compile::{self, CompileRequest},
+        metadata,
+        standard_json::StandardJsonContent,
+    },
+    Version,
+};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+use tokio::sync::Semaphore;
+
+const VECTOR_FILE: &str = "test_vector.json";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestVectorRequest {
+    /// Either a `.sol` source file or a `.json` standard input
+    pub source: PathBuf,
+    pub compiler_version: String,
+    pub contract_name: String,
+    /// Compiler settings of the `.sol` source (standard json `settings` object)
+    pub settings: Option<PathBuf>,
+    /// Hex encoded constructor arguments appended to the creation bytecode
+    pub constructor_arguments: Option<String>,
+    pub output_dir: PathBuf,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TestVector {
+    pub compiler_version: String,
+    pub file_name: String,
+    pub contract_name: String,
+    pub compiler_settings: serde_json::Value,
+    pub creation_tx_input: String,
+    pub deployed_bytecode: String,
+    pub constructor_arguments: Option<String>,
+    pub metadata: Option<TestVectorMetadata>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TestVectorMetadata {
+    pub solc: Option<String>,
+    pub experimental: bool,
+    /// Hex encoded metadata including its length, as appended to the deployed bytecode
+    pub encoded: String,
+}
+
+/// Compiles the request sources and writes the test vector into the `output_dir`.
+pub async fn generate_test_vector(
+    settings: Settings,
+    request: TestVectorRequest,
+) -> anyhow::Result<TestVector> {
+    let compiler_version = Version::from_str(&request.compiler_version)
+        .with_context(|| format!("invalid compiler version {}", request.compiler_version))?;
+    let constructor_arguments = request
+        .constructor_arguments
+        .as_deref()
+        .map(DisplayBytes::from_str)
+        .transpose()
+        .context("invalid constructor arguments")?;
+
+    let source_content = std::fs::read_to_string(&request.source)
+        .with_context(|| format!("failed to read {}", request.source.display()))?;
+    let (input, source_file) = match request.source.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => {
+            anyhow::ensure!(
+                request.settings.is_none(),
+                "settings are taken from the standard json input"
+            );
+            let input: CompilerInput =
+                serde_json::from_str(&source_content).context("invalid standard json input")?;
+            (input, "standard_input.json")
+        }
+        Some("sol") => {
+            let settings: CompilerSettings = match &request.settings {
+                Some(path) => {
+                    let content = std::fs::read(path)
+                        .with_context(|| format!("failed to read {}", path.display()))?;
+                    serde_json::from_slice(&content).context("invalid compiler settings")?
+                }
+                None => Default::default(),
+            };
+            // The tests send the source under its path relative to the crate root,
+            // so that the metadata hash of the compiled contract stays the same
+            let path = request.output_dir.join("source.sol");
+            let input = CompilerInput {
+                language: "Solidity".to_string(),
+                sources: Sources::from([(path, Source::new(source_content.clone()))]),
+                settings,
+            };
+            (input, "source.sol")
+        }
+        _ => anyhow::bail!("source is expected to be either a .sol or a .json file"),
+    };
+    let compiler_settings = serde_json::to_value(&input.settings)?;
+
+    let compilers_lock = Arc::new(Semaphore::new(settings.compilers.max_threads.get()));
+    let service = SolidityVerifierService::new(
+        settings.solidity,
+        compilers_lock,
+        settings.extensions.solidity,
+    )
+    .await?;
+    let compiled = compile::compile(
+        service.client(),
+        CompileRequest {
+            compiler_version,
+            auto_select_compiler_version: false,
+            content: StandardJsonContent {
+                input: input.clone(),
+                extra_outputs: false,
+            },
+        },
+    )
+    .await
+    .map_err(|err| anyhow::anyhow!("compilation failed: {err}"))?;
+    let contract = compiled
+        .contracts
+        .into_iter()
+        .find(|contract| contract.contract_name == request.contract_name)
+        .with_context(|| format!("contract {} not found", request.contract_name))?;
+
+    let vector = test_vector(
+        compiled.compiler_version.to_string(),
+        contract.file_path,
+        contract.contract_name,
+        compiler_settings,
+        contract.creation_bytecode.unwrap_or_default(),
+        contract.deployed_bytecode.unwrap_or_default(),
+        constructor_arguments,
+    )?;
+
+    let output_dir = &request.output_dir;
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("failed to create {}", output_dir.display()))?;
+    write(output_dir, source_file, &source_content)?;
+    write(output_dir, "creation_tx_input", &vector.creation_tx_input)?;
+    write(output_dir, "deployed_bytecode", &vector.deployed_bytecode)?;
+    if let Some(constructor_arguments) = &vector.constructor_arguments {
+        write(output_dir, "constructor_arguments", constructor_arguments)?;
+    }
+    write(
+        output_dir,
+        VECTOR_FILE,
+        &serde_json::to_string_pretty(&vector)?,
+    )?;
+
+    Ok(vector)
+}
+
+fn test_vector(
+    compiler_version: String,
+    file_name: String,
+    contract_name: String,
+    compiler_settings: serde_json::Value,
+    creation_bytecode: String,
+    deployed_bytecode: String,
+    constructor_arguments: Option<DisplayBytes>,
+) -> anyhow::Result<TestVector> {
+    // Placeholders of unlinked libraries would make the bytecodes invalid
+    anyhow::ensure!(
+        !creation_bytecode.contains("__"),
+        "contract {contract_name} has unlinked libraries; link them via the settings"
+    );
+    let deployed =
+        DisplayBytes::from_str(&deployed_bytecode).context("invalid deployed bytecode")?;
+    let metadata = match metadata::split(&deployed.0) {
+        (code, Some(metadata)) => Some(TestVectorMetadata {
+            solc: metadata.solc.map(|version| version.to_string()),
+            experimental: metadata.experimental,
+            encoded: DisplayBytes::from(deployed.0.slice(code.len()..)).to_string(),
+        }),
+        (_, None) => None,
+    };
+    let constructor_arguments =
+        constructor_arguments.map(|arguments| hex_without_prefix(&arguments));
+    let creation_tx_input = format!(
+        "{creation_bytecode}{}",
+        constructor_arguments.as_deref().unwrap_or_default()
+    );
+
+    Ok(TestVector {
+        compiler_version,
+        file_name,
+        contract_name,
+        compiler_settings,
+        creation_tx_input,
+        deployed_bytecode,
+        constructor_arguments,
+        metadata,
+    })
+}
+
+fn hex_without_prefix(bytes: &DisplayBytes) -> String {
+    bytes.to_string().trim_start_matches("0x").to_string()
+}
+
+fn write(dir: &Path, file: &str, content: &str) -> anyhow::Result<()> {
+    let path = dir.join(file);
+    std::fs::write(&path, content).with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // {"ipfs": h'1220 00..00', "solc": h'000813'}
+    const DEPLOYED_BYTECODE: &str = concat!(
+        "0x6080604052600080fdfe",
+        "a2646970667358221220",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "64736f6c63430008130033"
+    );
+
+    #[test]
+    fn constructor_arguments_and_metadata_are_split() {
+        let vector = test_vector(
+            "v0.8.19+commit.7dd6d404".to_string(),
+            "source.sol".to_string(),
+            "A".to_string(),
+            serde_json::json!({}),
+            "0x6080".to_string(),
+            DEPLOYED_BYTECODE.to_string(),
+            Some(DisplayBytes::from_str("0x0102").unwrap()),
+        )
+        .expect("vector should be built");
+
+        assert_eq!("0x60800102", vector.creation_tx_input);
+        assert_eq!(Some("0102".to_string()), vector.constructor_arguments);
+        let metadata = vector.metadata.expect("metadata should be found");
+        assert_eq!(Some("0.8.19".to_string()), metadata.solc);
+        assert!(DEPLOYED_BYTECODE.ends_with(metadata.encoded.trim_start_matches("0x")));
+
+        let err = test_vector(
+            "v0.8.19+commit.7dd6d404".to_string(),
+            "source.sol".to_string(),
+            "A".to_string(),
+            serde_json::json!({}),
+            "0x73__$1234$__".to_string(),
+            DEPLOYED_BYTECODE.to_string(),
+            None,
+        )
+        .expect_err("unlinked libraries should be rejected");
+        assert!(err.to_string().contains("unlinked"), "{err}");
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{
+    proto::{
+        compare_bytecodes_response, BytecodeRange, CompareBytecodesRequest,
+        CompareBytecodesResponse,
+    },
+    validation,
+};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::analyzer::bytecode_comparison::{CompareOptions, Comparison};
+use std::ops::Deref;
+
+/// Request parsed into the bytecodes to compare and the comparison options.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompareBytecodes {
+    pub local: Bytes,
+    pub remote: Bytes,
+    pub options: CompareOptions,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CompareBytecodesRequestWrapper(CompareBytecodesRequest);
+
+impl From<CompareBytecodesRequest> for CompareBytecodesRequestWrapper {
+    fn from(inner: CompareBytecodesRequest) -> Self {
+        Self(inner)
+    }
+}
+
+impl Deref for CompareBytecodesRequestWrapper {
+    type Target = CompareBytecodesRequest;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl CompareBytecodesRequestWrapper {
+    pub fn new(inner: CompareBytecodesRequest) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> CompareBytecodesRequest {
+        self.0
+    }
+}
+
+impl TryFrom<CompareBytecodesRequestWrapper> for CompareBytecodes {
+    type Error = tonic::Status;
+
+    fn try_from(request: CompareBytecodesRequestWrapper) -> Result<Self, Self::Error> {
+        let request = request.into_inner();
+
+        let parse_bytecode =
+            |bytecode: &str, field: &str| validation::parse_hex(field, "bytecode", bytecode);
+        let local = parse_bytecode(&request.local_bytecode, "localBytecode")?;
+        let remote = parse_bytecode(&request.remote_bytecode, "remoteBytecode")?;
+        let ignored_ranges = request
+            .ignored_ranges
+            .into_iter()
+            .map(|range| {
+                let start = range.start as usize;
+                start..start + range.length as usize
+            })
+            .collect();
+
+        Ok(Self {
+            local,
+            remote,
+            options: CompareOptions {
+                ignore_metadata: request.ignore_metadata,
+                ignored_ranges,
+                ignore_constructor_args: request.ignore_constructor_arguments,
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CompareBytecodesResponseWrapper(CompareBytecodesResponse);
+
+impl CompareBytecodesResponseWrapper {
+    pub fn into_inner(self) -> CompareBytecodesResponse {
+        self.0
+    }
+}
+
+impl From<Comparison> for CompareBytecodesResponseWrapper {
+    fn from(comparison: Comparison) -> Self {
+        let differences = comparison
+            .differences
+            .into_iter()
+            .map(|difference| compare_bytecodes_response::Difference {
+                offset: difference.offset as u32,
+                local: DisplayBytes::from(difference.local).to_string(),
+                remote: DisplayBytes::from(difference.remote).to_string(),
+            })
+            .collect();
+        let ignored_ranges = comparison
+            .ignored_ranges
+            .into_iter()
+            .map(|range| BytecodeRange {
+                start: range.start as u32,
+                length: range.len() as u32,
+            })
+            .collect();
+        Self(CompareBytecodesResponse {
+            equal: comparison.equal,
+            differences,
+            ignored_ranges,
+            constructor_arguments: comparison
+                .constructor_args
+                .map(|args| DisplayBytes::from(args).to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use smart_contract_verifier::analyzer::bytecode_comparison::Difference;
+
+    #[test]
+    fn try_into_compare_bytecodes() {
+        let request = CompareBytecodesRequest {
+            local_bytecode: "0x6080".to_string(),
+            remote_bytecode: "0x60801234".to_string(),
+            ignore_metadata: true,
+            ignored_ranges: vec![BytecodeRange {
+                start: 1,
+                length: 32,
+            }],
+            ignore_constructor_arguments: true,
+        };
+
+        let compare_bytecodes: CompareBytecodes = <CompareBytecodesRequestWrapper>::from(request)
+            .try_into()
+            .expect("Try_into compare bytecodes failed");
+
+        let expected = CompareBytecodes {
+            local: Bytes::from_static(&[0x60, 0x80]),
+            remote: Bytes::from_static(&[0x60, 0x80, 0x12, 0x34]),
+            options: CompareOptions {
+                ignore_metadata: true,
+                ignored_ranges: vec![1..33],
+                ignore_constructor_args: true,
+            },
+        };
+        assert_eq!(expected, compare_bytecodes);
+    }
+
+    #[test]
+    fn invalid_bytecode() {
+        let request = CompareBytecodesRequest {
+            local_bytecode: "0x6080".to_string(),
+            remote_bytecode: "not a bytecode".to_string(),
+            ..Default::default()
+        };
+        let result: Result<CompareBytecodes, _> =
+            <CompareBytecodesRequestWrapper>::from(request).try_into();
+        assert!(result.is_err(), "invalid bytecode should result in error");
+    }
+
+    #[test]
+    fn from_comparison() {
+        let response = CompareBytecodesResponseWrapper::from(Comparison {
+            equal: false,
+            differences: vec![Difference {
+                offset: 2,
+                local: Bytes::from_static(&[0x60]),
+                remote: Bytes::from_static(&[0x61]),
+            }],
+            ignored_ranges: vec![4..6],
+            constructor_args: Some(Bytes::from_static(&[0xca, 0xfe])),
+        })
+        .into_inner();
+
+        let expected = CompareBytecodesResponse {
+            equal: false,
+            differences: vec![compare_bytecodes_response::Difference {
+                offset: 2,
+                local: "0x60".to_string(),
+                remote: "0x61".to_string(),
+            }],
+            ignored_ranges: vec![BytecodeRange {
+                start: 4,
+                length: 2,
+            }],
+            constructor_arguments: Some("0xcafe".to_string()),
+        };
+        assert_eq!(expected, response);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{
+    proto::{diff_contracts_response, DiffContractsRequest, DiffContractsResponse},
+    validation::{ErrorCode, ValidationError},
+};
+use ethabi::Contract;
+use ethers_solc::artifacts::StorageLayout;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use smart_contract_verifier::analyzer::abi_diff::{ChangeKind, ContractDiff, ItemKind};
+use std::ops::Deref;
+
+/// Request parsed into the versions of the contract to compare.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffContracts {
+    pub old_abi: Contract,
+    pub new_abi: Contract,
+    pub storage_layouts: Option<(StorageLayout, StorageLayout)>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DiffContractsRequestWrapper(DiffContractsRequest);
+
+impl From<DiffContractsRequest> for DiffContractsRequestWrapper {
+    fn from(inner: DiffContractsRequest) -> Self {
+        Self(inner)
+    }
+}
+
+impl Deref for DiffContractsRequestWrapper {
+    type Target = DiffContractsRequest;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DiffContractsRequestWrapper {
+    pub fn new(inner: DiffContractsRequest) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> DiffContractsRequest {
+        self.0
+    }
+}
+
+fn parse_json<T: DeserializeOwned>(value: &str, field: &str) -> Result<T, tonic::Status> {
+    serde_json::from_str(value).map_err(|err| {
+        ValidationError::new(
+            ErrorCode::InvalidJson,
+            field,
+            format!("Invalid {field}: {err}"),
+        )
+        .into()
+    })
+}
+
+impl TryFrom<DiffContractsRequestWrapper> for DiffContracts {
+    type Error = tonic::Status;
+
+    fn try_from(request: DiffContractsRequestWrapper) -> Result<Self, Self::Error> {
+        let request = request.into_inner();
+
+        let old_abi = parse_json(&request.old_abi, "oldAbi")?;
+        let new_abi = parse_json(&request.new_abi, "newAbi")?;
+        let storage_layouts = match (request.old_storage_layout, request.new_storage_layout) {
+            (Some(old), Some(new)) => Some((
+                parse_json(&old, "oldStorageLayout")?,
+                parse_json(&new, "newStorageLayout")?,
+            )),
+            _ => None,
+        };
+
+        Ok(Self {
+            old_abi,
+            new_abi,
+            storage_layouts,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DiffContractsResponseWrapper(DiffContractsResponse);
+
+impl DiffContractsResponseWrapper {
+    pub fn into_inner(self) -> DiffContractsResponse {
+        self.0
+    }
+}
+
+impl From<ContractDiff> for DiffContractsResponseWrapper {
+    fn from(diff: ContractDiff) -> Self {
+        use diff_contracts_response::abi_change;
+
+        let abi_changes = diff
+            .abi_changes
+            .into_iter()
+            .map(|change| {
+                let kind = match change.kind {
+                    ItemKind::Function => abi_change::ItemKind::Function,
+                    ItemKind::Event => abi_change::ItemKind::Event,
+                    ItemKind::Error => abi_change::ItemKind::Error,
+                };
+                let change_kind = match change.change {
+                    ChangeKind::Added => abi_change::ChangeKind::Added,
+                    ChangeKind::Removed => abi_change::ChangeKind::Removed,
+                    ChangeKind::Changed => abi_change::ChangeKind::Changed,
+                };
+                diff_contracts_response::AbiChange {
+                    kind: kind.into(),
+                    change: change_kind.into(),
+                    name: change.name,
+                    old_declaration: change.old_declaration,
+                    new_declaration: change.new_declaration,
+                }
+            })
+            .collect();
+        let storage_incompatibilities = diff
+            .storage_incompatibilities
+            .into_iter()
+            .map(
+                |incompatibility| diff_contracts_response::StorageIncompatibility {
+                    slot: incompatibility.slot,
+                    offset: incompatibility.offset,
+                    old_variable: incompatibility.old_variable,
+                    new_variable: incompatibility.new_variable,
+                },
+            )
+            .collect();
+        Self(DiffContractsResponse {
+            abi_changes,
+            storage_incompatibilities,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use smart_contract_verifier::analyzer::abi_diff;
+
+    #[test]
+    fn try_into_diff_contracts() {
+        let request = DiffContractsRequest {
+            old_abi: "[]".to_string(),
+            new_abi: "[]".to_string(),
+            old_storage_layout: Some(r#"{"storage":[],"types":{}}"#.to_string()),
+            new_storage_layout: None,
+        };
+
+        let diff_contracts: DiffContracts = <DiffContractsRequestWrapper>::from(request)
+            .try_into()
+            .expect("Try_into diff contracts failed");
+
+        let expected = DiffContracts {
+            old_abi: Contract::default(),
+            new_abi: Contract::default(),
+            storage_layouts: None,
+        };
+        assert_eq!(expected, diff_contracts);
+    }
+
+    #[test]
+    fn invalid_storage_layout() {
+        let request = DiffContractsRequest {
+            old_abi: "[]".to_string(),
+            new_abi: "[]".to_string(),
+            old_storage_layout: Some("{}".to_string()),
+            new_storage_layout: Some("not a layout".to_string()),
+        };
+        let result: Result<DiffContracts, _> =
+            <DiffContractsRequestWrapper>::from(request).try_into();
+        assert!(
+            result.is_err(),
+            "invalid storage layout should result in error"
+        );
+    }
+
+    #[test]
+    fn from_contract_diff() {
+        let response = DiffContractsResponseWrapper::from(ContractDiff {
+            abi_changes: vec![abi_diff::AbiChange {
+                kind: ItemKind::Event,
+                change: ChangeKind::Removed,
+                name: "Paused".to_string(),
+                old_declaration: Some("Paused()".to_string()),
+                new_declaration: None,
+            }],
+            storage_incompatibilities: vec![abi_diff::StorageIncompatibility {
+                slot: "1".to_string(),
+                offset: 0,
+                old_variable: "total: uint256".to_string(),
+                new_variable: None,
+            }],
+        })
+        .into_inner();
+
+        let expected = DiffContractsResponse {
+            abi_changes: vec![diff_contracts_response::AbiChange {
+                kind: diff_contracts_response::abi_change::ItemKind::Event.into(),
+                change: diff_contracts_response::abi_change::ChangeKind::Removed.into(),
+                name: "Paused".to_string(),
+                old_declaration: Some("Paused()".to_string()),
+                new_declaration: None,
+            }],
+            storage_incompatibilities: vec![diff_contracts_response::StorageIncompatibility {
+                slot: "1".to_string(),
+                offset: 0,
+                old_variable: "total: uint256".to_string(),
+                new_variable: None,
+            }],
+        };
+        assert_eq!(expected, response);
+    }
+}
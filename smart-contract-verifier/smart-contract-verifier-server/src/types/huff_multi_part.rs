@@ -0,0 +1,144 @@
+use crate::{
+    proto::{BytecodeType, VerifyHuffMultiPartRequest},
+    validation::{self, ErrorCode, ValidationError},
+};
+use ethers_solc::EvmVersion;
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{
+    huff::multi_part::{MultiFileContent, VerificationRequest},
+    Version,
+};
+use std::{ops::Deref, str::FromStr};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct VerifyHuffMultiPartRequestWrapper(VerifyHuffMultiPartRequest);
+
+impl From<VerifyHuffMultiPartRequest> for VerifyHuffMultiPartRequestWrapper {
+    fn from(inner: VerifyHuffMultiPartRequest) -> Self {
+        Self(inner)
+    }
+}
+
+impl Deref for VerifyHuffMultiPartRequestWrapper {
+    type Target = VerifyHuffMultiPartRequest;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl VerifyHuffMultiPartRequestWrapper {
+    pub fn new(inner: VerifyHuffMultiPartRequest) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> VerifyHuffMultiPartRequest {
+        self.0
+    }
+}
+
+impl TryFrom<VerifyHuffMultiPartRequestWrapper> for VerificationRequest {
+    type Error = tonic::Status;
+
+    fn try_from(request: VerifyHuffMultiPartRequestWrapper) -> Result<Self, Self::Error> {
+        let request = request.into_inner();
+
+        let bytecode = validation::parse_hex("bytecode", "bytecode", &request.bytecode)?;
+        let (creation_bytecode, deployed_bytecode) = match request.bytecode_type() {
+            BytecodeType::Unspecified => Err(ValidationError::new(
+                ErrorCode::MissingField,
+                "bytecodeType",
+                "bytecode type is unspecified",
+            ))?,
+            BytecodeType::CreationInput => (Some(bytecode), bytes::Bytes::new()),
+            BytecodeType::DeployedBytecode => (None, bytecode),
+        };
+        let compiler_version = Version::from_str(&request.compiler_version).map_err(|err| {
+            ValidationError::new(
+                ErrorCode::InvalidCompilerVersion,
+                "compilerVersion",
+                format!("Invalid compiler version: {err}"),
+            )
+        })?;
+
+        let sources = super::parse_source_files("sourceFiles", request.source_files)?;
+
+        let evm_version = match request.evm_version {
+            Some(version) if version != "default" => {
+                Some(EvmVersion::from_str(&version).map_err(|err| {
+                    ValidationError::new(ErrorCode::InvalidEvmVersion, "evmVersion", err)
+                })?)
+            }
+            // huffc uses its own default evm version
+            _ => None,
+        };
+
+        Ok(Self {
+            deployed_bytecode,
+            creation_bytecode,
+            compiler_version,
+            content: MultiFileContent {
+                sources,
+                evm_version,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockscout_display_bytes::Bytes as DisplayBytes;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn try_into_verification_request() {
+        let request = VerifyHuffMultiPartRequest {
+            bytecode: "0x1234".to_string(),
+            bytecode_type: BytecodeType::DeployedBytecode.into(),
+            compiler_version: "0.3.2+commit.cbd9dd1d".to_string(),
+            source_files: BTreeMap::from([("Main.huff".into(), "source_content".into())]),
+            evm_version: Some("london".to_string()),
+        };
+
+        let verification_request: VerificationRequest =
+            <VerifyHuffMultiPartRequestWrapper>::from(request)
+                .try_into()
+                .expect("Try_into verification request failed");
+
+        let expected = VerificationRequest {
+            creation_bytecode: None,
+            deployed_bytecode: DisplayBytes::from_str("0x1234").unwrap().0,
+            compiler_version: Version::from_str("0.3.2+commit.cbd9dd1d").unwrap(),
+            content: MultiFileContent {
+                sources: BTreeMap::from([("Main.huff".into(), "source_content".into())]),
+                evm_version: Some(EvmVersion::London),
+            },
+        };
+
+        assert_eq!(expected, verification_request);
+    }
+
+    #[test]
+    // 'default' should result in None in MultiFileContent
+    fn default_evm_version() {
+        let request = VerifyHuffMultiPartRequest {
+            bytecode: "".to_string(),
+            bytecode_type: BytecodeType::CreationInput.into(),
+            compiler_version: "0.3.2+commit.cbd9dd1d".to_string(),
+            source_files: Default::default(),
+            evm_version: Some("default".to_string()),
+        };
+
+        let verification_request: VerificationRequest =
+            <VerifyHuffMultiPartRequestWrapper>::from(request)
+                .try_into()
+                .expect("Try_into verification request failed");
+
+        assert_eq!(
+            None, verification_request.content.evm_version,
+            "'default' should result in the compiler default evm version"
+        )
+    }
+}
@@ -1,14 +1,64 @@
+mod bytecode_comparison;
+mod contract_diff;
+mod huff_multi_part;
+mod solidity_github_repository;
 mod solidity_multi_part;
 mod solidity_standard_json;
+mod solidity_standard_json_batch;
 mod source;
 mod sourcify;
+mod token_metadata;
 mod verify_response;
 mod vyper_multi_part;
 
-pub use solidity_multi_part::VerifySolidityMultiPartRequestWrapper;
-pub use solidity_standard_json::{
-    ParseError as StandardJsonParseError, VerifySolidityStandardJsonRequestWrapper,
+pub use bytecode_comparison::{
+    CompareBytecodes, CompareBytecodesRequestWrapper, CompareBytecodesResponseWrapper,
 };
+pub use contract_diff::{DiffContracts, DiffContractsRequestWrapper, DiffContractsResponseWrapper};
+pub use huff_multi_part::VerifyHuffMultiPartRequestWrapper;
+pub use solidity_github_repository::VerifySolidityGithubRepositoryRequestWrapper;
+pub use solidity_multi_part::VerifySolidityMultiPartRequestWrapper;
+pub use solidity_standard_json::VerifySolidityStandardJsonRequestWrapper;
+pub use solidity_standard_json_batch::VerifySolidityStandardJsonBatchRequestWrapper;
 pub use sourcify::VerifySourcifyRequestWrapper;
-pub use verify_response::VerifyResponseWrapper;
+pub use token_metadata::{CheckTokenMetadataRequestWrapper, CheckTokenMetadataResponseWrapper};
+pub use verify_response::{VerifyResponseOk, VerifyResponseWrapper};
 pub use vyper_multi_part::VerifyVyperMultiPartRequestWrapper;
+
+use crate::validation::{self, ErrorCode, ValidationError};
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Parses the optional deployed bytecode accompanying the creation input.
+/// Returns empty bytes if the bytecode is not provided.
+fn parse_deployed_bytecode(
+    deployed_bytecode: Option<&str>,
+) -> Result<bytes::Bytes, ValidationError> {
+    match deployed_bytecode {
+        None => Ok(bytes::Bytes::new()),
+        Some(deployed_bytecode) => {
+            validation::parse_hex("deployedBytecode", "deployed bytecode", deployed_bytecode)
+        }
+    }
+}
+
+/// Validates the source file paths, which become compiler virtual paths
+/// and may be written to disk, before the files are passed to the compilers.
+fn parse_source_files(
+    field: &str,
+    source_files: impl IntoIterator<Item = (String, String)>,
+) -> Result<BTreeMap<PathBuf, String>, ValidationError> {
+    source_files
+        .into_iter()
+        .map(|(path, content)| {
+            validate_source_path(field, &path)?;
+            Ok((PathBuf::from(path), content))
+        })
+        .collect()
+}
+
+fn validate_source_path(field: &str, path: &str) -> Result<(), ValidationError> {
+    smart_contract_verifier::validate_source_path(path).map_err(|err| {
+        ValidationError::new(ErrorCode::InvalidSourcePath, field, err.to_string())
+            .with_param("path", path.escape_default())
+    })
+}
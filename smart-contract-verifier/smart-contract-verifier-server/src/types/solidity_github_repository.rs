@@ -0,0 +1,191 @@
+use crate::{
+    proto::{
+        verify_solidity_github_repository_request::Framework as ProtoFramework, BytecodeType,
+        VerifySolidityGithubRepositoryRequest,
+    },
+    validation::{self, ErrorCode, ValidationError},
+};
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{
+    solidity::repository::{self, Framework, GithubRepository, VerificationRequest},
+    Version,
+};
+use std::{ops::Deref, str::FromStr};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct VerifySolidityGithubRepositoryRequestWrapper(VerifySolidityGithubRepositoryRequest);
+
+impl From<VerifySolidityGithubRepositoryRequest> for VerifySolidityGithubRepositoryRequestWrapper {
+    fn from(inner: VerifySolidityGithubRepositoryRequest) -> Self {
+        Self(inner)
+    }
+}
+
+impl Deref for VerifySolidityGithubRepositoryRequestWrapper {
+    type Target = VerifySolidityGithubRepositoryRequest;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl VerifySolidityGithubRepositoryRequestWrapper {
+    pub fn new(inner: VerifySolidityGithubRepositoryRequest) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> VerifySolidityGithubRepositoryRequest {
+        self.0
+    }
+}
+
+impl TryFrom<VerifySolidityGithubRepositoryRequestWrapper> for VerificationRequest {
+    type Error = tonic::Status;
+
+    fn try_from(
+        request: VerifySolidityGithubRepositoryRequestWrapper,
+    ) -> Result<Self, Self::Error> {
+        let framework = match request.framework() {
+            ProtoFramework::Unspecified => None,
+            ProtoFramework::Foundry => Some(Framework::Foundry),
+            ProtoFramework::Hardhat => Some(Framework::Hardhat),
+        };
+        let request = request.into_inner();
+
+        let bytecode = validation::parse_hex("bytecode", "bytecode", &request.bytecode)?;
+        let (creation_bytecode, deployed_bytecode) = match request.bytecode_type() {
+            BytecodeType::Unspecified => Err(ValidationError::new(
+                ErrorCode::MissingField,
+                "bytecodeType",
+                "bytecode type is unspecified",
+            ))?,
+            BytecodeType::CreationInput => (Some(bytecode), bytes::Bytes::new()),
+            BytecodeType::DeployedBytecode => (None, bytecode),
+        };
+        let compiler_version = Version::from_str(&request.compiler_version).map_err(|err| {
+            ValidationError::new(
+                ErrorCode::InvalidCompilerVersion,
+                "compilerVersion",
+                format!("Invalid compiler version: {err}"),
+            )
+        })?;
+
+        let repository = GithubRepository::from_str(&request.repository_url).map_err(|err| {
+            ValidationError::new(
+                ErrorCode::InvalidValue,
+                "repositoryUrl",
+                format!("Invalid repository url: {err}"),
+            )
+        })?;
+        if !repository::is_commit_hash(&request.commit) {
+            Err(ValidationError::new(
+                ErrorCode::InvalidHex,
+                "commit",
+                "commit should be specified by its full hash",
+            ))?
+        }
+        if request.path.split('/').any(|segment| segment == "..") {
+            Err(ValidationError::new(
+                ErrorCode::InvalidValue,
+                "path",
+                "path should not leave the repository",
+            ))?
+        }
+
+        Ok(Self {
+            deployed_bytecode,
+            creation_bytecode,
+            compiler_version,
+            auto_select_compiler_version: request.auto_select_compiler_version,
+            bytecode_mask: vec![],
+            eof_enabled: false,
+            evm_fork: None,
+            normalization: Default::default(),
+            repository,
+            commit: request.commit,
+            path: request.path,
+            framework,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockscout_display_bytes::Bytes as DisplayBytes;
+    use pretty_assertions::assert_eq;
+
+    fn default_request() -> VerifySolidityGithubRepositoryRequest {
+        VerifySolidityGithubRepositoryRequest {
+            bytecode: "0x1234".to_string(),
+            bytecode_type: BytecodeType::CreationInput.into(),
+            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            repository_url: "https://github.com/blockscout/blockscout-rs".to_string(),
+            commit: "8df45f5f8632da4817bc7ceb81497518f298d290".to_string(),
+            path: "contracts".to_string(),
+            framework: ProtoFramework::Foundry.into(),
+            auto_select_compiler_version: true,
+            compiler: None,
+            genesis_patch: None,
+        }
+    }
+
+    #[test]
+    fn try_into_verification_request() {
+        let verification_request: VerificationRequest =
+            VerifySolidityGithubRepositoryRequestWrapper::from(default_request())
+                .try_into()
+                .expect("Try_into verification request failed");
+
+        assert_eq!(
+            Some(DisplayBytes::from_str("0x1234").unwrap().0),
+            verification_request.creation_bytecode,
+            "creation bytecode"
+        );
+        assert_eq!(
+            Version::from_str("v0.8.17+commit.8df45f5f").unwrap(),
+            verification_request.compiler_version,
+            "compiler version"
+        );
+        assert_eq!(
+            GithubRepository {
+                owner: "blockscout".into(),
+                name: "blockscout-rs".into(),
+            },
+            verification_request.repository,
+            "repository"
+        );
+        assert_eq!(
+            "8df45f5f8632da4817bc7ceb81497518f298d290", verification_request.commit,
+            "commit"
+        );
+        assert_eq!("contracts", verification_request.path, "path");
+        assert_eq!(
+            Some(Framework::Foundry),
+            verification_request.framework,
+            "framework"
+        );
+        assert!(verification_request.auto_select_compiler_version);
+    }
+
+    #[test]
+    fn invalid_repository() {
+        let mut request = default_request();
+        request.repository_url = "https://gitlab.com/blockscout/blockscout-rs".to_string();
+        let result: Result<VerificationRequest, _> =
+            VerifySolidityGithubRepositoryRequestWrapper::from(request).try_into();
+        assert!(result.is_err(), "repository url");
+
+        let mut request = default_request();
+        request.commit = "main".to_string();
+        let result: Result<VerificationRequest, _> =
+            VerifySolidityGithubRepositoryRequestWrapper::from(request).try_into();
+        assert!(result.is_err(), "commit");
+
+        let mut request = default_request();
+        request.path = "../other".to_string();
+        let result: Result<VerificationRequest, _> =
+            VerifySolidityGithubRepositoryRequestWrapper::from(request).try_into();
+        assert!(result.is_err(), "path");
+    }
+}
@@ -1,12 +1,14 @@
-use crate::proto::{BytecodeType, VerifySolidityMultiPartRequest};
-use blockscout_display_bytes::Bytes as DisplayBytes;
+use crate::{
+    proto::{BytecodeType, VerifySolidityMultiPartRequest},
+    validation::{self, ErrorCode, ValidationError},
+};
 use ethers_solc::EvmVersion;
 use serde::{Deserialize, Serialize};
 use smart_contract_verifier::{
     solidity::multi_part::{MultiFileContent, VerificationRequest},
-    Version,
+    Trace, Version,
 };
-use std::{collections::BTreeMap, ops::Deref, path::PathBuf, str::FromStr};
+use std::{ops::Deref, str::FromStr};
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct VerifySolidityMultiPartRequestWrapper(VerifySolidityMultiPartRequest);
@@ -41,35 +43,42 @@ impl TryFrom<VerifySolidityMultiPartRequestWrapper> for VerificationRequest {
     fn try_from(request: VerifySolidityMultiPartRequestWrapper) -> Result<Self, Self::Error> {
         let request = request.into_inner();
 
-        let bytecode = DisplayBytes::from_str(&request.bytecode)
-            .map_err(|err| tonic::Status::invalid_argument(format!("Invalid bytecode: {err:?}")))?
-            .0;
+        let bytecode = validation::parse_hex("bytecode", "bytecode", &request.bytecode)?;
         let (creation_bytecode, deployed_bytecode) = match request.bytecode_type() {
-            BytecodeType::Unspecified => Err(tonic::Status::invalid_argument(
+            BytecodeType::Unspecified => Err(ValidationError::new(
+                ErrorCode::MissingField,
+                "bytecodeType",
                 "bytecode type is unspecified",
             ))?,
-            BytecodeType::CreationInput => (Some(bytecode), bytes::Bytes::new()),
+            BytecodeType::CreationInput => (
+                Some(bytecode),
+                super::parse_deployed_bytecode(request.deployed_bytecode.as_deref())?,
+            ),
+            BytecodeType::DeployedBytecode if request.deployed_bytecode.is_some() => {
+                Err(ValidationError::new(
+                    ErrorCode::InvalidValue,
+                    "deployedBytecode",
+                    "deployed bytecode may be provided along with the creation input only",
+                ))?
+            }
             BytecodeType::DeployedBytecode => (None, bytecode),
         };
 
         let compiler_version = Version::from_str(&request.compiler_version).map_err(|err| {
-            tonic::Status::invalid_argument(format!("Invalid compiler version: {err}"))
+            ValidationError::new(
+                ErrorCode::InvalidCompilerVersion,
+                "compilerVersion",
+                format!("Invalid compiler version: {err}"),
+            )
         })?;
 
-        let sources: BTreeMap<PathBuf, String> = request
-            .source_files
-            .into_iter()
-            .map(|(name, content)| {
-                (
-                    PathBuf::from_str(&name).unwrap(), /* TODO: why unwrap? */
-                    content,
-                )
-            })
-            .collect();
+        let sources = super::parse_source_files("sourceFiles", request.source_files)?;
 
         let evm_version = match request.evm_version {
             Some(version) if version != "default" => {
-                Some(EvmVersion::from_str(&version).map_err(tonic::Status::invalid_argument)?)
+                Some(EvmVersion::from_str(&version).map_err(|err| {
+                    ValidationError::new(ErrorCode::InvalidEvmVersion, "evmVersion", err)
+                })?)
             }
             _ => None,
         };
@@ -78,11 +87,18 @@ impl TryFrom<VerifySolidityMultiPartRequestWrapper> for VerificationRequest {
             deployed_bytecode,
             creation_bytecode,
             compiler_version,
+            auto_select_compiler_version: request.auto_select_compiler_version,
+            bytecode_mask: vec![],
+            eof_enabled: false,
+            evm_fork: None,
+            normalization: Default::default(),
+            trace: request.trace.then(Trace::default),
             content: MultiFileContent {
                 sources,
                 evm_version,
                 optimization_runs: request.optimization_runs.map(|i| i as usize),
                 contract_libraries: Some(request.libraries.into_iter().collect()),
+                extra_outputs: request.extra_outputs,
             },
         })
     }
@@ -91,7 +107,9 @@ impl TryFrom<VerifySolidityMultiPartRequestWrapper> for VerificationRequest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use blockscout_display_bytes::Bytes as DisplayBytes;
     use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
 
     #[test]
     fn try_into_verification_request() {
@@ -105,17 +123,30 @@ mod tests {
             evm_version: Some("london".to_string()),
             optimization_runs: Some(200),
             libraries: BTreeMap::from([("Lib".into(), "0xcafe".into())]),
+            extra_outputs: true,
+            genesis_patch: None,
+            auto_select_compiler_version: true,
+            deployed_bytecode: None,
+            trace: false,
+            compiler: None,
         };
 
         let mut expected = VerificationRequest {
             creation_bytecode: Some(DisplayBytes::from_str("0x1234").unwrap().0),
             deployed_bytecode: DisplayBytes::from_str("").unwrap().0,
             compiler_version: Version::from_str("v0.8.17+commit.8df45f5f").unwrap(),
+            auto_select_compiler_version: true,
+            bytecode_mask: vec![],
+            eof_enabled: false,
+            evm_fork: None,
+            normalization: Default::default(),
+            trace: None,
             content: MultiFileContent {
                 sources: BTreeMap::from([("source_path".into(), "source_content".into())]),
                 evm_version: Some(EvmVersion::London),
                 optimization_runs: Some(200),
                 contract_libraries: Some(BTreeMap::from([("Lib".into(), "0xcafe".into())])),
+                extra_outputs: true,
             },
         };
 
@@ -137,6 +168,33 @@ mod tests {
         assert_eq!(expected, verification_request, "Deployed bytecode");
     }
 
+    #[test]
+    fn deployed_bytecode_with_creation_input() {
+        let mut request = VerifySolidityMultiPartRequest {
+            bytecode: "0x1234".to_string(),
+            bytecode_type: BytecodeType::CreationInput.into(),
+            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            deployed_bytecode: Some("0x5678".to_string()),
+            ..Default::default()
+        };
+
+        let verification_request: VerificationRequest =
+            <VerifySolidityMultiPartRequestWrapper>::from(request.clone())
+                .try_into()
+                .expect("Try_into verification request failed");
+        assert_eq!(
+            DisplayBytes::from_str("0x5678").unwrap().0,
+            verification_request.deployed_bytecode
+        );
+
+        request.bytecode_type = BytecodeType::DeployedBytecode.into();
+        let status =
+            VerificationRequest::try_from(<VerifySolidityMultiPartRequestWrapper>::from(request))
+                .expect_err("deployed bytecode should be rejected");
+        assert_eq!(tonic::Code::InvalidArgument, status.code());
+        assert!(status.message().contains("deployedBytecode"), "{status}");
+    }
+
     #[test]
     // 'default' should result in None in MultiFileContent
     fn default_evm_version() {
@@ -148,6 +206,12 @@ mod tests {
             evm_version: Some("default".to_string()),
             optimization_runs: None,
             libraries: Default::default(),
+            extra_outputs: false,
+            genesis_patch: None,
+            auto_select_compiler_version: false,
+            deployed_bytecode: None,
+            trace: false,
+            compiler: None,
         };
 
         let verification_request: VerificationRequest =
@@ -172,6 +236,12 @@ mod tests {
             evm_version: None,
             optimization_runs: None,
             libraries: Default::default(),
+            extra_outputs: false,
+            genesis_patch: None,
+            auto_select_compiler_version: false,
+            deployed_bytecode: None,
+            trace: false,
+            compiler: None,
         };
 
         let verification_request: VerificationRequest =
@@ -184,4 +254,29 @@ mod tests {
             "Absent evm_version should result in `None`"
         )
     }
+
+    #[test]
+    fn path_traversal_is_rejected() {
+        let request = VerifySolidityMultiPartRequest {
+            bytecode: "0x1234".to_string(),
+            bytecode_type: BytecodeType::CreationInput.into(),
+            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            source_files: BTreeMap::from([
+                ("contracts/A.sol".into(), "".into()),
+                ("../../B.sol".into(), "".into()),
+            ]),
+            ..Default::default()
+        };
+
+        let status =
+            VerificationRequest::try_from(<VerifySolidityMultiPartRequestWrapper>::from(request))
+                .expect_err("path traversal should be rejected");
+        let error: ValidationError = serde_json::from_str(status.message()).unwrap();
+        assert_eq!(ErrorCode::InvalidSourcePath, error.code);
+        assert_eq!("sourceFiles", error.field);
+        assert_eq!(
+            Some("../../B.sol"),
+            error.params.get("path").map(String::as_str)
+        );
+    }
 }
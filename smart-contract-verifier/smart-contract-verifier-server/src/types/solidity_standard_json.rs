@@ -1,22 +1,14 @@
-use crate::proto::{BytecodeType, VerifySolidityStandardJsonRequest};
-use anyhow::anyhow;
-use blockscout_display_bytes::Bytes as DisplayBytes;
+use crate::{
+    proto::{BytecodeType, VerifySolidityStandardJsonRequest},
+    validation::{self, ErrorCode, ValidationError},
+};
 use ethers_solc::CompilerInput;
 use serde::{Deserialize, Serialize};
 use smart_contract_verifier::{
     solidity::standard_json::{StandardJsonContent, VerificationRequest},
-    Version,
+    Trace, Version,
 };
 use std::{ops::Deref, str::FromStr};
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum ParseError {
-    #[error("content is not valid standard json: {0}")]
-    InvalidContent(#[from] serde_json::Error),
-    #[error("{0}")]
-    BadRequest(#[from] anyhow::Error),
-}
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct VerifySolidityStandardJsonRequestWrapper(VerifySolidityStandardJsonRequest);
@@ -46,31 +38,65 @@ impl VerifySolidityStandardJsonRequestWrapper {
 }
 
 impl TryFrom<VerifySolidityStandardJsonRequestWrapper> for VerificationRequest {
-    type Error = ParseError;
+    type Error = tonic::Status;
 
     fn try_from(request: VerifySolidityStandardJsonRequestWrapper) -> Result<Self, Self::Error> {
         let request = request.into_inner();
 
-        let bytecode = DisplayBytes::from_str(&request.bytecode)
-            .map_err(|err| anyhow!("Invalid deployed bytecode: {:?}", err))?
-            .0;
+        let bytecode = validation::parse_hex("bytecode", "bytecode", &request.bytecode)?;
         let (creation_bytecode, deployed_bytecode) = match request.bytecode_type() {
-            BytecodeType::Unspecified => Err(ParseError::BadRequest(anyhow!(
-                "Bytecode type is unspecified"
-            )))?,
-            BytecodeType::CreationInput => (Some(bytecode), bytes::Bytes::new()),
+            BytecodeType::Unspecified => Err(ValidationError::new(
+                ErrorCode::MissingField,
+                "bytecodeType",
+                "bytecode type is unspecified",
+            ))?,
+            BytecodeType::CreationInput => (
+                Some(bytecode),
+                super::parse_deployed_bytecode(request.deployed_bytecode.as_deref())?,
+            ),
+            BytecodeType::DeployedBytecode if request.deployed_bytecode.is_some() => {
+                Err(ValidationError::new(
+                    ErrorCode::InvalidValue,
+                    "deployedBytecode",
+                    "deployed bytecode may be provided along with the creation input only",
+                ))?
+            }
             BytecodeType::DeployedBytecode => (None, bytecode),
         };
-        let compiler_version = Version::from_str(&request.compiler_version)
-            .map_err(|err| anyhow!("Invalid compiler version: {}", err))?;
-
-        let input: CompilerInput = serde_json::from_str(&request.input)?;
+        let compiler_version = Version::from_str(&request.compiler_version).map_err(|err| {
+            ValidationError::new(
+                ErrorCode::InvalidCompilerVersion,
+                "compilerVersion",
+                format!("Invalid compiler version: {err}"),
+            )
+        })?;
+
+        let input: CompilerInput = serde_json::from_str(&request.input).map_err(|err| {
+            ValidationError::new(
+                ErrorCode::InvalidJson,
+                "input",
+                format!("content is not valid standard json: {err}"),
+            )
+        })?;
+        for path in input.sources.keys() {
+            super::validate_source_path("input", &path.to_string_lossy())?;
+        }
 
         Ok(Self {
             deployed_bytecode,
             creation_bytecode,
             compiler_version,
-            content: StandardJsonContent { input },
+            auto_select_compiler_version: request.auto_select_compiler_version,
+            bytecode_mask: vec![],
+            eof_enabled: false,
+            evm_fork: None,
+            normalization: Default::default(),
+            retry_with_flipped_via_ir: request.retry_with_flipped_via_ir,
+            trace: request.trace.then(Trace::default),
+            content: StandardJsonContent {
+                input,
+                extra_outputs: request.extra_outputs,
+            },
         })
     }
 }
@@ -78,6 +104,7 @@ impl TryFrom<VerifySolidityStandardJsonRequestWrapper> for VerificationRequest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use blockscout_display_bytes::Bytes as DisplayBytes;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -88,7 +115,14 @@ mod tests {
             bytecode: "0x1234".to_string(),
             bytecode_type: BytecodeType::CreationInput.into(),
             compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
-            input: "{\"language\": \"Solidity\", \"sources\": {\"./src/contracts/Foo.sol\": {\"content\": \"pragma solidity ^0.8.2;\\n\\ncontract Foo {\\n    function bar() external pure returns (uint256) {\\n        return 42;\\n    }\\n}\\n\"}}, \"settings\": {\"metadata\": {\"useLiteralContent\": true}, \"optimizer\": {\"enabled\": true, \"runs\": 200}, \"outputSelection\": {\"*\": {\"*\": [\"abi\", \"evm.bytecode\", \"evm.deployedBytecode\", \"evm.methodIdentifiers\"], \"\": [\"id\", \"ast\"]}}}}".to_string()
+            input: "{\"language\": \"Solidity\", \"sources\": {\"./src/contracts/Foo.sol\": {\"content\": \"pragma solidity ^0.8.2;\\n\\ncontract Foo {\\n    function bar() external pure returns (uint256) {\\n        return 42;\\n    }\\n}\\n\"}}, \"settings\": {\"metadata\": {\"useLiteralContent\": true}, \"optimizer\": {\"enabled\": true, \"runs\": 200}, \"outputSelection\": {\"*\": {\"*\": [\"abi\", \"evm.bytecode\", \"evm.deployedBytecode\", \"evm.methodIdentifiers\"], \"\": [\"id\", \"ast\"]}}}}".to_string(),
+            extra_outputs: true,
+            genesis_patch: None,
+            auto_select_compiler_version: true,
+            retry_with_flipped_via_ir: true,
+            deployed_bytecode: None,
+            trace: false,
+            compiler: None,
         };
         let input: CompilerInput = serde_json::from_str(&request.input).unwrap();
 
@@ -101,7 +135,17 @@ mod tests {
             creation_bytecode: Some(DisplayBytes::from_str("0x1234").unwrap().0),
             deployed_bytecode: DisplayBytes::from_str("").unwrap().0,
             compiler_version: Version::from_str("v0.8.17+commit.8df45f5f").unwrap(),
-            content: StandardJsonContent { input },
+            auto_select_compiler_version: true,
+            bytecode_mask: vec![],
+            eof_enabled: false,
+            evm_fork: None,
+            normalization: Default::default(),
+            retry_with_flipped_via_ir: true,
+            trace: None,
+            content: StandardJsonContent {
+                input,
+                extra_outputs: true,
+            },
         };
 
         // We cannot compare requests directly, as CompilerInput does not implement PartialEq
@@ -117,11 +161,24 @@ mod tests {
             expected.compiler_version, verification_request.compiler_version,
             "compiler version"
         );
+        assert_eq!(
+            expected.auto_select_compiler_version,
+            verification_request.auto_select_compiler_version,
+            "auto select compiler version"
+        );
+        assert_eq!(
+            expected.retry_with_flipped_via_ir, verification_request.retry_with_flipped_via_ir,
+            "retry with flipped via ir"
+        );
         assert_eq!(
             serde_json::to_string(&expected.content.input).unwrap(),
             serde_json::to_string(&verification_request.content.input).unwrap(),
             "compiler input"
         );
+        assert_eq!(
+            expected.content.extra_outputs, verification_request.content.extra_outputs,
+            "extra outputs"
+        );
 
         /********** Deployed Bytecode **********/
 
@@ -141,4 +198,23 @@ mod tests {
             "Invalid deployed bytecode when deployed bytecode provided"
         );
     }
+
+    #[test]
+    fn absolute_source_path_is_rejected() {
+        let request = VerifySolidityStandardJsonRequest {
+            bytecode: "0x1234".to_string(),
+            bytecode_type: BytecodeType::CreationInput.into(),
+            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            input: r#"{"language": "Solidity", "sources": {"/etc/passwd": {"content": ""}}, "settings": {"optimizer": {"enabled": false}}}"#.to_string(),
+            ..Default::default()
+        };
+
+        let status = VerificationRequest::try_from(
+            <VerifySolidityStandardJsonRequestWrapper>::from(request),
+        )
+        .expect_err("absolute path should be rejected");
+        let error: ValidationError = serde_json::from_str(status.message()).unwrap();
+        assert_eq!(ErrorCode::InvalidSourcePath, error.code);
+        assert_eq!("input", error.field);
+    }
 }
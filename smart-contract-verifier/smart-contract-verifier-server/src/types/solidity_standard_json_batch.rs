@@ -0,0 +1,210 @@
+use crate::{
+    proto::{BytecodeType, VerifySolidityStandardJsonBatchRequest},
+    validation::{self, ErrorCode, ValidationError},
+};
+use ethers_solc::CompilerInput;
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{
+    solidity::standard_json::{BatchVerificationRequest, StandardJsonContent, VerificationTarget},
+    Version,
+};
+use std::{ops::Deref, str::FromStr};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct VerifySolidityStandardJsonBatchRequestWrapper(VerifySolidityStandardJsonBatchRequest);
+
+impl From<VerifySolidityStandardJsonBatchRequest>
+    for VerifySolidityStandardJsonBatchRequestWrapper
+{
+    fn from(inner: VerifySolidityStandardJsonBatchRequest) -> Self {
+        Self(inner)
+    }
+}
+
+impl Deref for VerifySolidityStandardJsonBatchRequestWrapper {
+    type Target = VerifySolidityStandardJsonBatchRequest;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl VerifySolidityStandardJsonBatchRequestWrapper {
+    pub fn new(inner: VerifySolidityStandardJsonBatchRequest) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> VerifySolidityStandardJsonBatchRequest {
+        self.0
+    }
+
+    /// Addresses of the targets in the order of the request
+    pub fn addresses(&self) -> Vec<Option<String>> {
+        self.targets
+            .iter()
+            .map(|target| target.address.clone())
+            .collect()
+    }
+}
+
+impl TryFrom<VerifySolidityStandardJsonBatchRequestWrapper> for BatchVerificationRequest {
+    type Error = tonic::Status;
+
+    fn try_from(
+        request: VerifySolidityStandardJsonBatchRequestWrapper,
+    ) -> Result<Self, Self::Error> {
+        let request = request.into_inner();
+
+        if request.targets.is_empty() {
+            return Err(ValidationError::new(
+                ErrorCode::MissingField,
+                "targets",
+                "at least one target should be specified",
+            )
+            .into());
+        }
+        let targets = request
+            .targets
+            .into_iter()
+            .enumerate()
+            .map(|(i, target)| {
+                let bytecode = validation::parse_hex(
+                    &format!("targets[{i}].bytecode"),
+                    "bytecode",
+                    &target.bytecode,
+                )?;
+                let (creation_bytecode, deployed_bytecode) = match target.bytecode_type() {
+                    BytecodeType::Unspecified => Err(ValidationError::new(
+                        ErrorCode::MissingField,
+                        &format!("targets[{i}].bytecodeType"),
+                        "bytecode type is unspecified",
+                    ))?,
+                    BytecodeType::CreationInput => (Some(bytecode), bytes::Bytes::new()),
+                    BytecodeType::DeployedBytecode => (None, bytecode),
+                };
+                Ok(VerificationTarget {
+                    deployed_bytecode,
+                    creation_bytecode,
+                })
+            })
+            .collect::<Result<Vec<_>, tonic::Status>>()?;
+
+        let compiler_version = Version::from_str(&request.compiler_version).map_err(|err| {
+            ValidationError::new(
+                ErrorCode::InvalidCompilerVersion,
+                "compilerVersion",
+                format!("Invalid compiler version: {err}"),
+            )
+        })?;
+
+        let input: CompilerInput = serde_json::from_str(&request.input).map_err(|err| {
+            ValidationError::new(
+                ErrorCode::InvalidJson,
+                "input",
+                format!("content is not valid standard json: {err}"),
+            )
+        })?;
+        for path in input.sources.keys() {
+            super::validate_source_path("input", &path.to_string_lossy())?;
+        }
+
+        Ok(Self {
+            targets,
+            compiler_version,
+            auto_select_compiler_version: request.auto_select_compiler_version,
+            eof_enabled: false,
+            evm_fork: None,
+            normalization: Default::default(),
+            content: StandardJsonContent {
+                input,
+                extra_outputs: request.extra_outputs,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::VerificationTarget as ProtoVerificationTarget;
+    use blockscout_display_bytes::Bytes as DisplayBytes;
+    use pretty_assertions::assert_eq;
+
+    fn request(
+        targets: Vec<ProtoVerificationTarget>,
+    ) -> VerifySolidityStandardJsonBatchRequestWrapper {
+        VerifySolidityStandardJsonBatchRequest {
+            targets,
+            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            input: "{\"language\": \"Solidity\", \"sources\": {}, \"settings\": {}}".to_string(),
+            extra_outputs: false,
+            auto_select_compiler_version: false,
+            compiler: None,
+        }
+        .into()
+    }
+
+    #[test]
+    fn try_into_verification_request() {
+        let request = request(vec![
+            ProtoVerificationTarget {
+                address: Some("0x0000000000000000000000000000000000000001".to_string()),
+                bytecode: "0x1234".to_string(),
+                bytecode_type: BytecodeType::CreationInput.into(),
+            },
+            ProtoVerificationTarget {
+                address: None,
+                bytecode: "0x5678".to_string(),
+                bytecode_type: BytecodeType::DeployedBytecode.into(),
+            },
+        ]);
+        assert_eq!(
+            vec![
+                Some("0x0000000000000000000000000000000000000001".to_string()),
+                None
+            ],
+            request.addresses()
+        );
+
+        let verification_request: BatchVerificationRequest =
+            request.try_into().expect("valid request");
+        assert_eq!(
+            vec![
+                VerificationTarget {
+                    deployed_bytecode: bytes::Bytes::new(),
+                    creation_bytecode: Some(DisplayBytes::from_str("0x1234").unwrap().0),
+                },
+                VerificationTarget {
+                    deployed_bytecode: DisplayBytes::from_str("0x5678").unwrap().0,
+                    creation_bytecode: None,
+                },
+            ],
+            verification_request.targets
+        );
+    }
+
+    #[test]
+    fn invalid_targets() {
+        assert!(
+            BatchVerificationRequest::try_from(request(vec![])).is_err(),
+            "targets are empty"
+        );
+
+        let status = BatchVerificationRequest::try_from(request(vec![
+            ProtoVerificationTarget {
+                address: None,
+                bytecode: "0x1234".to_string(),
+                bytecode_type: BytecodeType::CreationInput.into(),
+            },
+            ProtoVerificationTarget {
+                address: None,
+                bytecode: "0xkeklol".to_string(),
+                bytecode_type: BytecodeType::CreationInput.into(),
+            },
+        ]))
+        .err()
+        .expect("second bytecode is invalid");
+        let error: ValidationError = serde_json::from_str(status.message()).unwrap();
+        assert_eq!("targets[1].bytecode", error.field);
+    }
+}
@@ -12,6 +12,7 @@ pub fn from_verification_success(value: VerificationSuccess) -> Source {
         "Solidity" => source::SourceType::Solidity,
         "Yul" => source::SourceType::Yul,
         "Vyper" => source::SourceType::Vyper,
+        "Huff" => source::SourceType::Huff,
         _ => source::SourceType::Unspecified,
     };
 
@@ -108,6 +109,9 @@ mod tests {
             constructor_args: Some(DisplayBytes::from_str("0x123456").unwrap()),
             local_bytecode_parts: Default::default(),
             match_type: MatchType::Partial,
+            blueprint: None,
+            immutable_values: Default::default(),
+            library_addresses: Default::default(),
         };
 
         let result = from_verification_success(verification_success);
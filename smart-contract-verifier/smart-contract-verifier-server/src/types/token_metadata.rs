@@ -0,0 +1,183 @@
+use crate::{
+    proto::{
+        check_token_metadata_response::{discrepancy, Discrepancy},
+        CheckTokenMetadataRequest, CheckTokenMetadataResponse,
+    },
+    validation::{self, ErrorCode, ValidationError},
+};
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::analyzer::token_metadata::{
+    self, CheckRequest, TokenMetadata, ValueSource,
+};
+use std::{collections::BTreeMap, ops::Deref};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CheckTokenMetadataRequestWrapper(CheckTokenMetadataRequest);
+
+impl From<CheckTokenMetadataRequest> for CheckTokenMetadataRequestWrapper {
+    fn from(inner: CheckTokenMetadataRequest) -> Self {
+        Self(inner)
+    }
+}
+
+impl Deref for CheckTokenMetadataRequestWrapper {
+    type Target = CheckTokenMetadataRequest;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl CheckTokenMetadataRequestWrapper {
+    pub fn new(inner: CheckTokenMetadataRequest) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> CheckTokenMetadataRequest {
+        self.0
+    }
+}
+
+impl TryFrom<CheckTokenMetadataRequestWrapper> for CheckRequest {
+    type Error = tonic::Status;
+
+    fn try_from(request: CheckTokenMetadataRequestWrapper) -> Result<Self, Self::Error> {
+        let request = request.into_inner();
+
+        let abi = serde_json::from_str(&request.abi).map_err(|err| {
+            ValidationError::new(ErrorCode::InvalidJson, "abi", format!("Invalid abi: {err}"))
+        })?;
+        let constructor_args = request
+            .constructor_arguments
+            .map(|args| {
+                validation::parse_hex("constructorArguments", "constructor arguments", &args)
+            })
+            .transpose()?;
+        let immutables = request
+            .immutables
+            .into_iter()
+            .map(|(name, value)| {
+                let value = validation::parse_hex(
+                    "immutables",
+                    &format!("value of immutable `{name}`"),
+                    &value,
+                )?;
+                Ok((name, value))
+            })
+            .collect::<Result<BTreeMap<_, _>, tonic::Status>>()?;
+
+        Ok(Self {
+            abi,
+            constructor_args,
+            immutables,
+            metadata: TokenMetadata {
+                name: request.name,
+                symbol: request.symbol,
+                decimals: request.decimals,
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CheckTokenMetadataResponseWrapper(CheckTokenMetadataResponse);
+
+impl CheckTokenMetadataResponseWrapper {
+    pub fn into_inner(self) -> CheckTokenMetadataResponse {
+        self.0
+    }
+}
+
+impl From<Vec<token_metadata::Discrepancy>> for CheckTokenMetadataResponseWrapper {
+    fn from(discrepancies: Vec<token_metadata::Discrepancy>) -> Self {
+        let discrepancies = discrepancies
+            .into_iter()
+            .map(|discrepancy| {
+                let source = match discrepancy.source {
+                    ValueSource::ConstructorArguments => {
+                        discrepancy::ValueSource::ConstructorArguments
+                    }
+                    ValueSource::Immutables => discrepancy::ValueSource::Immutables,
+                };
+                Discrepancy {
+                    field: discrepancy.field,
+                    source: source.into(),
+                    expected: discrepancy.expected,
+                    actual: discrepancy.actual,
+                }
+            })
+            .collect();
+        Self(CheckTokenMetadataResponse { discrepancies })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockscout_display_bytes::Bytes as DisplayBytes;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[test]
+    fn try_into_check_request() {
+        let request = CheckTokenMetadataRequest {
+            abi: "[]".to_string(),
+            constructor_arguments: Some("0x1234".to_string()),
+            immutables: BTreeMap::from([("_decimals".into(), "0x12".into())]),
+            name: Some("Token".to_string()),
+            symbol: None,
+            decimals: Some("18".to_string()),
+        };
+
+        let check_request: CheckRequest = <CheckTokenMetadataRequestWrapper>::from(request)
+            .try_into()
+            .expect("Try_into check request failed");
+
+        let expected = CheckRequest {
+            abi: serde_json::from_str("[]").unwrap(),
+            constructor_args: Some(DisplayBytes::from_str("0x1234").unwrap().0),
+            immutables: BTreeMap::from([(
+                "_decimals".into(),
+                DisplayBytes::from_str("0x12").unwrap().0,
+            )]),
+            metadata: TokenMetadata {
+                name: Some("Token".to_string()),
+                symbol: None,
+                decimals: Some("18".to_string()),
+            },
+        };
+        assert_eq!(expected, check_request);
+    }
+
+    #[test]
+    fn invalid_abi() {
+        let request = CheckTokenMetadataRequest {
+            abi: "not an abi".to_string(),
+            ..Default::default()
+        };
+        let result: Result<CheckRequest, _> =
+            <CheckTokenMetadataRequestWrapper>::from(request).try_into();
+        assert!(result.is_err(), "invalid abi should result in error");
+    }
+
+    #[test]
+    fn from_discrepancies() {
+        let response = CheckTokenMetadataResponseWrapper::from(vec![token_metadata::Discrepancy {
+            field: "name".to_string(),
+            source: ValueSource::Immutables,
+            expected: "Token".to_string(),
+            actual: "Tether USD".to_string(),
+        }])
+        .into_inner();
+
+        let expected = CheckTokenMetadataResponse {
+            discrepancies: vec![Discrepancy {
+                field: "name".to_string(),
+                source: discrepancy::ValueSource::Immutables.into(),
+                expected: "Token".to_string(),
+                actual: "Tether USD".to_string(),
+            }],
+        };
+        assert_eq!(expected, response);
+    }
+}
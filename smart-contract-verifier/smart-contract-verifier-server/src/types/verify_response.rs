@@ -1,9 +1,21 @@
-use crate::proto::{
-    verify_response::{ExtraData, Status},
-    Source, VerifyResponse,
+use crate::{
+    attestation,
+    error_catalog::Failure,
+    proto::{
+        verification_trace,
+        verify_response::{
+            extra_data::{gas_estimates, Blueprint, GasEstimates, NatSpec, RiskFlags},
+            ExtraData, Status,
+        },
+        Source, VerificationTrace, VerifyResponse,
+    },
+    signing::RecordSigner,
 };
 use serde::{Deserialize, Serialize};
-use smart_contract_verifier::{SourcifySuccess, VerificationSuccess};
+use smart_contract_verifier::{
+    address_format, detect_standards, extract_extra_outputs, extract_natspec, extract_risk_flags,
+    SourcifySuccess, Trace, VerificationError, VerificationSuccess,
+};
 use std::{fmt::Display, mem, ops::Deref};
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -36,6 +48,40 @@ pub trait VerifyResponseOk {
 impl VerifyResponseOk for VerificationSuccess {
     fn result(mut self) -> (Source, ExtraData) {
         let local_bytecode_parts = mem::take(&mut self.local_bytecode_parts);
+        let standards = self
+            .abi
+            .as_ref()
+            .map(|abi| {
+                let deployed_bytecode: Vec<u8> = local_bytecode_parts
+                    .deployed_bytecode_parts
+                    .iter()
+                    .flat_map(|part| match part {
+                        smart_contract_verifier::BytecodePart::Main { raw }
+                        | smart_contract_verifier::BytecodePart::Metadata { raw, .. } => {
+                            raw.iter().copied()
+                        }
+                    })
+                    .collect();
+                standards_to_strings(abi, &deployed_bytecode)
+            })
+            .unwrap_or_default();
+        let natspec = extract_natspec(&self.compiler_output, &self.file_path, &self.contract_name)
+            .map(|natspec| NatSpec {
+                userdoc: natspec.userdoc.map(|doc| doc.to_string()),
+                devdoc: natspec.devdoc.map(|doc| doc.to_string()),
+            });
+        let extra_outputs =
+            extract_extra_outputs(&self.compiler_output, &self.file_path, &self.contract_name)
+                .unwrap_or_default();
+        let risk_flags =
+            extract_risk_flags(&self.compiler_output, &self.file_path, &self.contract_name).map(
+                |flags| RiskFlags {
+                    selfdestruct: flags.selfdestruct,
+                    delegatecall: flags.delegatecall,
+                    tx_origin_auth: flags.tx_origin_auth,
+                    inline_assembly: flags.inline_assembly,
+                },
+            );
         let local_creation_input_parts = local_bytecode_parts
             .creation_tx_input_parts
             .into_iter()
@@ -49,6 +95,39 @@ impl VerifyResponseOk for VerificationSuccess {
         let extra_data = ExtraData {
             local_creation_input_parts,
             local_deployed_bytecode_parts,
+            standards,
+            signed_record: None,
+            blueprint: self.blueprint.take().map(|preamble| Blueprint {
+                erc_version: preamble.erc_version.into(),
+                data: preamble
+                    .data
+                    .map(|data| blockscout_display_bytes::Bytes::from(data).to_string()),
+            }),
+            label: None,
+            immutable_values: mem::take(&mut self.immutable_values)
+                .into_iter()
+                .map(|(name, value)| (name, value.to_string()))
+                .collect(),
+            library_addresses: mem::take(&mut self.library_addresses)
+                .into_iter()
+                .map(|(name, address)| (name, address.to_string()))
+                .collect(),
+            attestation: None,
+            natspec,
+            method_identifiers: extra_outputs.method_identifiers.into_iter().collect(),
+            storage_layout: extra_outputs
+                .storage_layout
+                .map(|layout| layout.to_string()),
+            gas_estimates: extra_outputs.gas_estimates.map(|estimates| GasEstimates {
+                creation: Some(gas_estimates::Creation {
+                    code_deposit_cost: estimates.creation.code_deposit_cost,
+                    execution_cost: estimates.creation.execution_cost,
+                    total_cost: estimates.creation.total_cost,
+                }),
+                external: estimates.external,
+                internal: estimates.internal,
+            }),
+            risk_flags,
         };
 
         let source = super::source::from_verification_success(self);
@@ -59,9 +138,25 @@ impl VerifyResponseOk for VerificationSuccess {
 
 impl VerifyResponseOk for SourcifySuccess {
     fn result(self) -> (Source, ExtraData) {
+        // Sourcify does not return the bytecode, so only the abi is used for detection
+        let standards = serde_json::from_str::<ethabi::Contract>(&self.abi)
+            .map(|abi| standards_to_strings(&abi, &[]))
+            .unwrap_or_default();
         let extra_data = ExtraData {
             local_creation_input_parts: vec![],
             local_deployed_bytecode_parts: vec![],
+            standards,
+            signed_record: None,
+            blueprint: None,
+            label: None,
+            immutable_values: Default::default(),
+            library_addresses: Default::default(),
+            attestation: None,
+            natspec: None,
+            method_identifiers: Default::default(),
+            storage_layout: None,
+            gas_estimates: None,
+            risk_flags: None,
         };
         let source = super::source::from_sourcify_success(self);
 
@@ -69,16 +164,89 @@ impl VerifyResponseOk for SourcifySuccess {
     }
 }
 
+/// Checksums library addresses (both of the compiler settings and the extracted ones)
+/// and values of address typed immutables.
+fn format_addresses(response: &mut VerifyResponse, chain_id: Option<u64>) {
+    if let Some(source) = response.source.as_mut() {
+        source.compiler_settings =
+            address_format::format_settings_libraries(&source.compiler_settings, chain_id);
+    }
+    if let Some(extra_data) = response.extra_data.as_mut() {
+        let values = extra_data
+            .library_addresses
+            .values_mut()
+            .chain(extra_data.immutable_values.values_mut());
+        for value in values {
+            if let Some(address) = address_format::format_address(value, chain_id) {
+                *value = address;
+            }
+        }
+    }
+}
+
+fn standards_to_strings(abi: &ethabi::Contract, deployed_bytecode: &[u8]) -> Vec<String> {
+    detect_standards(abi, deployed_bytecode)
+        .into_iter()
+        .map(|standard| standard.to_string())
+        .collect()
+}
+
 impl VerifyResponseWrapper {
     pub fn ok<T: VerifyResponseOk>(success: T) -> Self {
         let (source, extra_data) = success.result();
-        VerifyResponse {
+        let mut response = VerifyResponse {
             message: "OK".to_string(),
             status: Status::Success.into(),
             source: Some(source),
             extra_data: Some(extra_data),
+            failure: None,
+            trace: None,
+        };
+        format_addresses(&mut response, None);
+        response.into()
+    }
+
+    /// Checksums the addresses with the chain id included (EIP-1191) if it is provided.
+    /// Otherwise, addresses are left EIP-55 checksummed. Should be applied before signing.
+    pub fn with_chain_checksum(mut self, chain_id: Option<u64>) -> Self {
+        if chain_id.is_some() {
+            format_addresses(&mut self.0, chain_id);
         }
-        .into()
+        self
+    }
+
+    /// Attaches the attestation of the build if the contract has been fully matched.
+    /// `compiler_hash` is sha256 of the compiler binary. Should be applied before signing.
+    pub fn with_attestation(mut self, compiler_hash: Option<impl AsRef<[u8]>>) -> Self {
+        attestation::attest(
+            &mut self.0,
+            compiler_hash.as_ref().map(|hash| hash.as_ref()),
+        );
+        self
+    }
+
+    /// Attaches the signed verification record if the signer is provided.
+    pub fn signed(mut self, signer: Option<&RecordSigner>) -> Self {
+        if let Some(signer) = signer {
+            signer.sign(&mut self.0);
+        }
+        self
+    }
+
+    /// Attaches the steps recorded into the verification trace if it has been requested.
+    pub fn with_trace(mut self, trace: Option<&Trace>) -> Self {
+        self.0.trace = trace.map(|trace| VerificationTrace {
+            steps: trace
+                .steps()
+                .into_iter()
+                .map(|step| verification_trace::Step {
+                    stage: step.stage.as_str().to_string(),
+                    contract: step.contract,
+                    details: step.details,
+                })
+                .collect(),
+        });
+        self
     }
 
     pub fn err(message: impl Display) -> Self {
@@ -87,9 +255,34 @@ impl VerifyResponseWrapper {
             status: Status::Failure.into(),
             source: None,
             extra_data: None,
+            failure: None,
+            trace: None,
         }
         .into()
     }
+
+    /// Failed response with the machine-readable failure.
+    /// The message is the default English rendering of the failure.
+    pub fn failure(failure: Failure) -> Self {
+        VerifyResponse {
+            message: failure.render(),
+            status: Status::Failure.into(),
+            source: None,
+            extra_data: None,
+            failure: Some(failure.into()),
+            trace: None,
+        }
+        .into()
+    }
+
+    /// Failed response describing the verification error via the error catalog,
+    /// if the error is listed there.
+    pub fn verification_err(err: &VerificationError) -> Self {
+        match Failure::from_verification_error(err) {
+            Some(failure) => Self::failure(failure),
+            None => Self::err(err),
+        }
+    }
 }
 
 pub mod extra_data {
@@ -149,7 +342,7 @@ mod tests {
     use ethers_solc::CompilerInput;
     use pretty_assertions::assert_eq;
     use smart_contract_verifier::{MatchType, VerificationSuccess, Version};
-    use std::str::FromStr;
+    use std::{collections::BTreeMap, str::FromStr};
 
     #[test]
     fn ok_verify_response() {
@@ -167,6 +360,9 @@ mod tests {
             constructor_args: None,
             local_bytecode_parts: Default::default(),
             match_type: MatchType::Partial,
+            blueprint: None,
+            immutable_values: Default::default(),
+            library_addresses: Default::default(),
         };
 
         let response = VerifyResponseWrapper::ok(verification_success.clone()).into_inner();
@@ -180,12 +376,74 @@ mod tests {
             extra_data: Some(ExtraData {
                 local_creation_input_parts: vec![],
                 local_deployed_bytecode_parts: vec![],
+                standards: vec![],
+                signed_record: None,
+                blueprint: None,
+                label: None,
+                immutable_values: Default::default(),
+                library_addresses: Default::default(),
+                attestation: None,
+                natspec: None,
+                method_identifiers: Default::default(),
+                storage_layout: None,
+                gas_estimates: None,
+                risk_flags: None,
             }),
+            failure: None,
+            trace: None,
         };
 
         assert_eq!(expected, response);
     }
 
+    #[test]
+    fn checksummed_addresses() {
+        let address = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+        let response = VerifyResponse {
+            message: "OK".to_string(),
+            status: Status::Success.into(),
+            source: Some(Source {
+                compiler_settings: format!(
+                    r#"{{"libraries":{{"Lib.sol":{{"Lib":"{address}"}}}}}}"#
+                ),
+                ..Default::default()
+            }),
+            extra_data: Some(ExtraData {
+                immutable_values: BTreeMap::from([
+                    ("owner".to_string(), address.to_string()),
+                    ("decimals".to_string(), "0x12".to_string()),
+                ]),
+                library_addresses: BTreeMap::from([(
+                    "Lib.sol:Lib".to_string(),
+                    address.to_string(),
+                )]),
+                ..Default::default()
+            }),
+            failure: None,
+            trace: None,
+        };
+
+        let mut eip55_response = response.clone();
+        format_addresses(&mut eip55_response, None);
+        let eip55_address = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let extra_data = eip55_response.extra_data.unwrap();
+        assert_eq!(eip55_address, extra_data.library_addresses["Lib.sol:Lib"]);
+        assert_eq!(eip55_address, extra_data.immutable_values["owner"]);
+        assert_eq!("0x12", extra_data.immutable_values["decimals"]);
+        assert_eq!(
+            format!(r#"{{"libraries":{{"Lib.sol":{{"Lib":"{eip55_address}"}}}}}}"#),
+            eip55_response.source.unwrap().compiler_settings
+        );
+
+        let rsk_response = VerifyResponseWrapper::from(response)
+            .with_chain_checksum(Some(30))
+            .into_inner();
+        assert_eq!(
+            "0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD",
+            rsk_response.extra_data.unwrap().library_addresses["Lib.sol:Lib"]
+        );
+    }
+
     #[test]
     fn err_verify_response() {
         let response = VerifyResponseWrapper::err("parse error").into_inner();
@@ -194,8 +452,42 @@ mod tests {
             status: Status::Failure.into(),
             source: None,
             extra_data: None,
+            failure: None,
+            trace: None,
         };
         assert_eq!(expected, response);
+
+        let err = smart_contract_verifier::VerificationError::NoMatchingContracts(vec![]);
+        let response = VerifyResponseWrapper::verification_err(&err).into_inner();
+        assert_eq!(err.to_string(), response.message);
+        assert_eq!(
+            "NO_MATCHING_CONTRACTS",
+            response.failure.expect("failure should be present").code
+        );
+    }
+
+    #[test]
+    fn with_trace_verify_response() {
+        let response = VerifyResponseWrapper::err("no match").with_trace(None);
+        assert_eq!(None, response.trace);
+
+        let trace = smart_contract_verifier::Trace::default();
+        trace.record(
+            smart_contract_verifier::TraceStep::new(
+                smart_contract_verifier::TraceStage::Comparison,
+            )
+            .with_contract("A.sol", "A")
+            .with_detail("result", "full"),
+        );
+        let response = response.with_trace(Some(&trace)).into_inner();
+        let expected = VerificationTrace {
+            steps: vec![verification_trace::Step {
+                stage: "comparison".to_string(),
+                contract: Some("A.sol:A".to_string()),
+                details: BTreeMap::from([("result".to_string(), "full".to_string())]),
+            }],
+        };
+        assert_eq!(Some(expected), response.trace);
     }
 
     #[test]
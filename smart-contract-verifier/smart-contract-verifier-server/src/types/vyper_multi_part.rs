@@ -1,12 +1,14 @@
-use crate::proto::{BytecodeType, VerifyVyperMultiPartRequest};
-use blockscout_display_bytes::Bytes as DisplayBytes;
+use crate::{
+    proto::{BytecodeType, VerifyVyperMultiPartRequest},
+    validation::{self, ErrorCode, ValidationError},
+};
 use ethers_solc::EvmVersion;
 use serde::{Deserialize, Serialize};
 use smart_contract_verifier::{
     vyper::multi_part::{MultiFileContent, VerificationRequest},
     Version,
 };
-use std::{collections::BTreeMap, ops::Deref, path::PathBuf, str::FromStr};
+use std::{ops::Deref, str::FromStr};
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct VerifyVyperMultiPartRequestWrapper(VerifyVyperMultiPartRequest);
@@ -41,34 +43,31 @@ impl TryFrom<VerifyVyperMultiPartRequestWrapper> for VerificationRequest {
     fn try_from(request: VerifyVyperMultiPartRequestWrapper) -> Result<Self, Self::Error> {
         let request = request.into_inner();
 
-        let bytecode = DisplayBytes::from_str(&request.bytecode)
-            .map_err(|err| tonic::Status::invalid_argument(format!("Invalid bytecode: {err:?}")))?
-            .0;
+        let bytecode = validation::parse_hex("bytecode", "bytecode", &request.bytecode)?;
         let (creation_bytecode, deployed_bytecode) = match request.bytecode_type() {
-            BytecodeType::Unspecified => Err(tonic::Status::invalid_argument(
+            BytecodeType::Unspecified => Err(ValidationError::new(
+                ErrorCode::MissingField,
+                "bytecodeType",
                 "bytecode type is unspecified",
             ))?,
             BytecodeType::CreationInput => (Some(bytecode), bytes::Bytes::new()),
             BytecodeType::DeployedBytecode => (None, bytecode),
         };
         let compiler_version = Version::from_str(&request.compiler_version).map_err(|err| {
-            tonic::Status::invalid_argument(format!("Invalid compiler version: {err}"))
+            ValidationError::new(
+                ErrorCode::InvalidCompilerVersion,
+                "compilerVersion",
+                format!("Invalid compiler version: {err}"),
+            )
         })?;
 
-        let sources: BTreeMap<PathBuf, String> = request
-            .source_files
-            .into_iter()
-            .map(|(name, content)| {
-                (
-                    PathBuf::from_str(&name).unwrap(), /* TODO: why unwrap? */
-                    content,
-                )
-            })
-            .collect();
+        let sources = super::parse_source_files("sourceFiles", request.source_files)?;
 
         let evm_version = match request.evm_version {
             Some(version) if version != "default" => {
-                Some(EvmVersion::from_str(&version).map_err(tonic::Status::invalid_argument)?)
+                Some(EvmVersion::from_str(&version).map_err(|err| {
+                    ValidationError::new(ErrorCode::InvalidEvmVersion, "evmVersion", err)
+                })?)
             }
             _ => {
                 // default evm version for vyper
@@ -83,6 +82,7 @@ impl TryFrom<VerifyVyperMultiPartRequestWrapper> for VerificationRequest {
             content: MultiFileContent {
                 sources,
                 evm_version,
+                extra_outputs: request.extra_outputs,
             },
         })
     }
@@ -91,7 +91,9 @@ impl TryFrom<VerifyVyperMultiPartRequestWrapper> for VerificationRequest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use blockscout_display_bytes::Bytes as DisplayBytes;
     use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
 
     #[test]
     fn try_into_verification_request() {
@@ -102,6 +104,7 @@ mod tests {
             source_files: BTreeMap::from([("source_path".into(), "source_content".into())]),
             evm_version: Some("byzantium".to_string()),
             optimizations: None,
+            extra_outputs: true,
         };
 
         let verification_request: VerificationRequest =
@@ -116,6 +119,7 @@ mod tests {
             content: MultiFileContent {
                 sources: BTreeMap::from([("source_path".into(), "source_content".into())]),
                 evm_version: Some(EvmVersion::Byzantium),
+                extra_outputs: true,
             },
         };
 
@@ -132,6 +136,7 @@ mod tests {
             source_files: Default::default(),
             evm_version: Some("default".to_string()),
             optimizations: None,
+            extra_outputs: false,
         };
 
         let verification_request: VerificationRequest =
@@ -156,6 +161,7 @@ mod tests {
             source_files: Default::default(),
             evm_version: None,
             optimizations: None,
+            extra_outputs: false,
         };
 
         let verification_request: VerificationRequest =
@@ -0,0 +1,214 @@
+//! Accounting of the compute resources consumed by the verifications.
+//!
+//! Compilations run by a verification (see [`CompilationUsage`]) are attributed to the tenant
+//! the request belongs to, so that hosted providers could bill their clients. The totals are
+//! kept in memory since the service start and are additionally reported via metrics.
+//! The usage of each verification may also be posted to an external billing system.
+
+use crate::{metrics, settings::UsageSettings};
+use serde::Serialize;
+use smart_contract_verifier::CompilationUsage;
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TenantUsage {
+    pub tenant: String,
+    /// Number of verifications which have run the compilers
+    pub verifications: u64,
+    pub compiler_invocations: u64,
+    pub cpu_seconds: f64,
+    pub bytes_compiled: u64,
+}
+
+impl TenantUsage {
+    fn add(&mut self, usage: &CompilationUsage) {
+        self.verifications += 1;
+        self.compiler_invocations += usage.compiler_invocations;
+        self.cpu_seconds += usage.compilation_time.as_secs_f64();
+        self.bytes_compiled += usage.bytes_compiled;
+    }
+}
+
+/// Usage of a single verification as it is posted to the billing hook
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageRecord<'a> {
+    tenant: &'a str,
+    language: &'a str,
+    compiler_invocations: u64,
+    cpu_seconds: f64,
+    bytes_compiled: u64,
+    /// Unix timestamp the verification has been completed at
+    timestamp: u64,
+}
+
+struct BillingHook {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl BillingHook {
+    /// Delivers the record in the background, so that verifications do not wait for the hook.
+    /// Failed deliveries are not retried, but are reported via logs and metrics.
+    fn send(&self, record: &UsageRecord) {
+        let request = self.client.post(self.url.clone()).json(record);
+        let tenant = record.tenant.to_string();
+        tokio::spawn(async move {
+            let result = request
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(err) = result {
+                tracing::warn!(%tenant, "usage record cannot be delivered to the billing hook: {err}");
+                metrics::BILLING_HOOK_FAILURES
+                    .with_label_values(&[&tenant])
+                    .inc();
+            }
+        });
+    }
+}
+
+pub struct UsageMeter {
+    /// Unix timestamp the usage is accounted since
+    since: u64,
+    totals: Mutex<BTreeMap<String, TenantUsage>>,
+    billing_hook: Option<BillingHook>,
+}
+
+impl UsageMeter {
+    pub fn new(settings: &UsageSettings) -> anyhow::Result<Self> {
+        let billing_hook = match &settings.billing_hook_url {
+            Some(url) => Some(BillingHook {
+                client: reqwest::Client::builder()
+                    .timeout(Duration::from_secs(settings.billing_hook_timeout))
+                    .build()?,
+                url: url.clone(),
+            }),
+            None => None,
+        };
+        Ok(Self {
+            since: unix_timestamp(),
+            totals: Default::default(),
+            billing_hook,
+        })
+    }
+
+    pub fn since(&self) -> u64 {
+        self.since
+    }
+
+    pub fn record(&self, tenant: &str, language: &str, usage: &CompilationUsage) {
+        self.totals
+            .lock()
+            .unwrap()
+            .entry(tenant.to_string())
+            .or_insert_with(|| TenantUsage {
+                tenant: tenant.to_string(),
+                ..Default::default()
+            })
+            .add(usage);
+
+        if let Some(billing_hook) = &self.billing_hook {
+            billing_hook.send(&UsageRecord {
+                tenant,
+                language,
+                compiler_invocations: usage.compiler_invocations,
+                cpu_seconds: usage.compilation_time.as_secs_f64(),
+                bytes_compiled: usage.bytes_compiled,
+                timestamp: unix_timestamp(),
+            });
+        }
+    }
+
+    /// Returns the usage of the tenant, or of all tenants (sorted by their ids) if not specified
+    pub fn usage(&self, tenant: Option<&str>) -> Vec<TenantUsage> {
+        let totals = self.totals.lock().unwrap();
+        match tenant {
+            Some(tenant) => vec![totals.get(tenant).cloned().unwrap_or_else(|| TenantUsage {
+                tenant: tenant.to_string(),
+                ..Default::default()
+            })],
+            None => totals.values().cloned().collect(),
+        }
+    }
+}
+
+/// Runs the verification and accounts the compilations it has run both into
+/// the meter (if enabled) and into the metrics. Verifications which have not
+/// invoked any compiler (e.g., failed validation) are not accounted.
+pub async fn metered<F: Future>(
+    meter: Option<&UsageMeter>,
+    language: &str,
+    tenant: &str,
+    verification: F,
+) -> F::Output {
+    let (output, usage) = smart_contract_verifier::metered(verification).await;
+    if usage.compiler_invocations > 0 {
+        metrics::count_compilation_usage(language, tenant, &usage);
+        if let Some(meter) = meter {
+            meter.record(tenant, language, &usage);
+        }
+    }
+    output
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tenants::DEFAULT_TENANT;
+    use pretty_assertions::assert_eq;
+
+    fn usage(compiler_invocations: u64, millis: u64, bytes_compiled: u64) -> CompilationUsage {
+        CompilationUsage {
+            compiler_invocations,
+            compilation_time: Duration::from_millis(millis),
+            bytes_compiled,
+        }
+    }
+
+    #[test]
+    fn usage_is_accounted_per_tenant() {
+        let meter = UsageMeter::new(&UsageSettings::default()).unwrap();
+        meter.record("explorer-a", "solidity", &usage(3, 1500, 100));
+        meter.record(DEFAULT_TENANT, "vyper", &usage(1, 250, 10));
+        meter.record("explorer-a", "vyper", &usage(1, 500, 20));
+
+        let expected = vec![
+            TenantUsage {
+                tenant: DEFAULT_TENANT.to_string(),
+                verifications: 1,
+                compiler_invocations: 1,
+                cpu_seconds: 0.25,
+                bytes_compiled: 10,
+            },
+            TenantUsage {
+                tenant: "explorer-a".to_string(),
+                verifications: 2,
+                compiler_invocations: 4,
+                cpu_seconds: 2.0,
+                bytes_compiled: 120,
+            },
+        ];
+        assert_eq!(expected, meter.usage(None));
+        assert_eq!(expected[1..].to_vec(), meter.usage(Some("explorer-a")));
+
+        let expected = vec![TenantUsage {
+            tenant: "explorer-b".to_string(),
+            ..Default::default()
+        }];
+        assert_eq!(expected, meter.usage(Some("explorer-b")));
+    }
+}
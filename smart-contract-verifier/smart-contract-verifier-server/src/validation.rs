@@ -0,0 +1,252 @@
+//! Machine-readable errors for the rejected requests.
+//!
+//! Every request rejected as invalid is answered with `INVALID_ARGUMENT` status
+//! (400 Bad Request for http) which message is a JSON encoded [`ValidationError`],
+//! so that the clients could localize the message and highlight the invalid field.
+
+use actix_web::{
+    error::{InternalError, JsonPayloadError},
+    http::StatusCode,
+    HttpRequest, HttpResponse,
+};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{HexError, VerificationError};
+use std::collections::BTreeMap;
+use tonic::{Code, Status};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// Required field is missing or unspecified
+    MissingField,
+    /// Value is not a valid hex string
+    InvalidHex,
+    /// Value is not a valid compiler version
+    InvalidCompilerVersion,
+    /// Compiler version is valid, but is not available on the service
+    UnknownCompilerVersion,
+    /// Value is not a valid evm version
+    InvalidEvmVersion,
+    /// Value is not a valid JSON of the expected format
+    InvalidJson,
+    /// Source file path is empty, absolute, contains control characters
+    /// or refers to the parent directory (the path is passed as a param)
+    InvalidSourcePath,
+    /// Value is invalid for any other reason described in the message
+    InvalidValue,
+    /// Request body exceeds the size limit
+    PayloadTooLarge,
+    /// Compiler input or output exceeds the configured limit
+    /// (the limit, its maximum and actual values are passed as params)
+    LimitExceeded,
+    /// Request body cannot be parsed
+    MalformedRequest,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationError {
+    pub code: ErrorCode,
+    /// Name of the invalid request field as used in the http api (e.g. "compilerVersion").
+    /// Is empty if the error does not relate to any specific field.
+    pub field: String,
+    /// Human readable description of the error
+    pub message: String,
+    /// Values the message is rendered with (e.g. the unknown compiler version),
+    /// so that the clients could render the localized message themselves
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+}
+
+impl ValidationError {
+    pub fn new(code: ErrorCode, field: &str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            field: field.to_string(),
+            message: message.into(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_param(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Describes the errors the verification fails with because of the invalid request.
+    pub fn from_verification_error(err: &VerificationError) -> Self {
+        match err {
+            VerificationError::VersionNotFound(version) => Self::new(
+                ErrorCode::UnknownCompilerVersion,
+                "compilerVersion",
+                err.to_string(),
+            )
+            .with_param("version", version),
+            VerificationError::LimitExceeded(limit) => {
+                Self::new(ErrorCode::LimitExceeded, "", err.to_string())
+                    .with_param("limit", limit.limit)
+                    .with_param("max", limit.max)
+                    .with_param("actual", limit.actual)
+            }
+            _ => Self::new(ErrorCode::InvalidValue, "bytecode", err.to_string()),
+        }
+    }
+
+    pub(crate) fn encode(&self) -> String {
+        serde_json::to_string(self).expect("validation error serialization cannot fail")
+    }
+}
+
+impl From<ValidationError> for Status {
+    fn from(error: ValidationError) -> Self {
+        Status::invalid_argument(error.encode())
+    }
+}
+
+/// Parses the hex encoded request value (see [`smart_contract_verifier::decode_hex`]).
+/// `description` names the value in the error message (e.g., "bytecode").
+pub fn parse_hex(field: &str, description: &str, value: &str) -> Result<Bytes, ValidationError> {
+    smart_contract_verifier::decode_hex(value).map_err(|err| {
+        let error = ValidationError::new(
+            ErrorCode::InvalidHex,
+            field,
+            format!("Invalid {description}: {err}"),
+        );
+        match err {
+            HexError::InvalidCharacter {
+                character,
+                position,
+            } => error
+                .with_param("character", character.escape_default())
+                .with_param("position", position),
+            HexError::OddLength { digits } => error.with_param("digits", digits),
+        }
+    })
+}
+
+/// Handles the errors of http request bodies parsing, which happen before
+/// the request reaches the service.
+pub fn json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let (status, error) = match &err {
+        JsonPayloadError::OverflowKnownLength { .. } | JsonPayloadError::Overflow { .. } => (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            ValidationError::new(ErrorCode::PayloadTooLarge, "", err.to_string()),
+        ),
+        JsonPayloadError::Deserialize(deserialize_err) => {
+            let error = match missing_field(&deserialize_err.to_string()) {
+                Some(field) => ValidationError::new(
+                    ErrorCode::MissingField,
+                    field,
+                    format!("missing field `{field}`"),
+                ),
+                None => ValidationError::new(ErrorCode::MalformedRequest, "", err.to_string()),
+            };
+            (StatusCode::BAD_REQUEST, error)
+        }
+        _ => (
+            StatusCode::BAD_REQUEST,
+            ValidationError::new(ErrorCode::MalformedRequest, "", err.to_string()),
+        ),
+    };
+    let body = serde_json::json!({
+        "code": Code::InvalidArgument as i32,
+        "message": error.encode(),
+    });
+    InternalError::from_response(err, HttpResponse::build(status).json(body)).into()
+}
+
+/// Extracts the field name from serde "missing field `name` ..." errors.
+fn missing_field(message: &str) -> Option<&str> {
+    message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split_once('`'))
+        .map(|(field, _)| field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[test]
+    fn status_message_is_json() {
+        let status = Status::from(ValidationError::new(
+            ErrorCode::InvalidHex,
+            "bytecode",
+            "Invalid bytecode: odd number of digits",
+        ));
+        assert_eq!(Code::InvalidArgument, status.code());
+
+        let error: ValidationError =
+            serde_json::from_str(status.message()).expect("message should be a valid json");
+        assert_eq!(ErrorCode::InvalidHex, error.code);
+        assert_eq!("bytecode", error.field);
+        assert_eq!(
+            serde_json::json!({
+                "code": "INVALID_HEX",
+                "field": "bytecode",
+                "message": "Invalid bytecode: odd number of digits",
+            }),
+            serde_json::from_str::<serde_json::Value>(status.message()).unwrap()
+        );
+    }
+
+    #[test]
+    fn params_are_encoded_if_present() {
+        let version = smart_contract_verifier::Version::from_str("v0.8.17+commit.8df45f5f")
+            .expect("valid version");
+        let error =
+            ValidationError::from_verification_error(&VerificationError::VersionNotFound(version));
+        assert_eq!(
+            serde_json::json!({"version": "v0.8.17+commit.8df45f5f"}),
+            serde_json::from_str::<serde_json::Value>(&error.encode()).unwrap()["params"]
+        );
+    }
+
+    #[test]
+    fn limit_exceeded_error() {
+        let error = ValidationError::from_verification_error(&VerificationError::LimitExceeded(
+            smart_contract_verifier::LimitExceeded {
+                limit: smart_contract_verifier::Limit::SourceFiles,
+                max: 100,
+                actual: 101,
+            },
+        ));
+        assert_eq!(
+            serde_json::json!({
+                "code": "LIMIT_EXCEEDED",
+                "field": "",
+                "message": "source_files limit exceeded: 101 (maximum is 100)",
+                "params": {"limit": "source_files", "max": "100", "actual": "101"},
+            }),
+            serde_json::from_str::<serde_json::Value>(&error.encode()).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_hex_params() {
+        let error = parse_hex("bytecode", "bytecode", "0x60zz").unwrap_err();
+        assert_eq!(
+            serde_json::json!({
+                "code": "INVALID_HEX",
+                "field": "bytecode",
+                "message": "Invalid bytecode: invalid character 'z' at position 4",
+                "params": {"character": "z", "position": "4"},
+            }),
+            serde_json::from_str::<serde_json::Value>(&error.encode()).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_missing_field() {
+        assert_eq!(
+            Some("bytecode"),
+            missing_field("missing field `bytecode` at line 1 column 2")
+        );
+        assert_eq!(
+            None,
+            missing_field("invalid type: integer `1`, expected a string")
+        );
+    }
+}
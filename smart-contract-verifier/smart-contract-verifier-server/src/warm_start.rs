@@ -0,0 +1,200 @@
+//! Manifests of the cached compilers, so that new replicas could be warmed
+//! with the compilers used by the running ones before receiving traffic.
+//!
+//! The manifest is exported from the compilers found in the `compilers_dir` of each language,
+//! and is then used to download the same compilers into the directories of the new replica.
+
+use crate::{
+    services::{HuffVerifierService, SolidityVerifierService, VyperVerifierService},
+    settings::Settings,
+};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use smart_contract_verifier::{Compilers, EvmCompiler, Version};
+use std::{path::Path, str::FromStr, sync::Arc};
+use tokio::sync::Semaphore;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompilersManifest {
+    pub solidity: Vec<String>,
+    pub vyper: Vec<String>,
+    pub huff: Vec<String>,
+}
+
+/// Compilers of the enabled languages
+struct LanguageCompilers {
+    solidity: Option<Arc<SolidityVerifierService>>,
+    vyper: Option<Arc<VyperVerifierService>>,
+    huff: Option<Arc<HuffVerifierService>>,
+}
+
+impl LanguageCompilers {
+    async fn new(settings: Settings) -> anyhow::Result<Self> {
+        let compilers_lock = Arc::new(Semaphore::new(settings.compilers.max_threads.get()));
+        let solidity = match settings.solidity.enabled {
+            true => Some(Arc::new(
+                SolidityVerifierService::new(
+                    settings.solidity,
+                    compilers_lock.clone(),
+                    settings.extensions.solidity,
+                )
+                .await?,
+            )),
+            false => None,
+        };
+        let vyper = match settings.vyper.enabled {
+            true => Some(Arc::new(
+                VyperVerifierService::new(
+                    settings.vyper,
+                    compilers_lock.clone(),
+                    settings.extensions.vyper,
+                )
+                .await?,
+            )),
+            false => None,
+        };
+        let huff = match settings.huff.enabled {
+            true => Some(Arc::new(
+                HuffVerifierService::new(settings.huff, compilers_lock, settings.extensions.huff)
+                    .await?,
+            )),
+            false => None,
+        };
+        Ok(Self {
+            solidity,
+            vyper,
+            huff,
+        })
+    }
+}
+
+async fn cached_versions<C: EvmCompiler>(compilers: &Compilers<C>) -> Vec<String> {
+    compilers
+        .cached_compilers()
+        .await
+        .into_iter()
+        .map(|compiler| compiler.version.to_string())
+        .collect()
+}
+
+/// Downloads the compilers and returns the number of the ones which could not be downloaded.
+async fn warm<C: EvmCompiler>(
+    compilers: &Compilers<C>,
+    language: &str,
+    versions: &[String],
+) -> anyhow::Result<usize> {
+    let versions = versions
+        .iter()
+        .map(|version| {
+            Version::from_str(version)
+                .with_context(|| format!("invalid {language} compiler version `{version}`"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut failed = 0;
+    for (version, result) in compilers.prewarm(&versions).await {
+        match result {
+            Ok(()) => tracing::info!(language, version = version.to_string(), "compiler warmed"),
+            Err(err) => {
+                failed += 1;
+                tracing::error!(
+                    language,
+                    version = version.to_string(),
+                    "compiler cannot be downloaded: {err}"
+                )
+            }
+        }
+    }
+    Ok(failed)
+}
+
+/// Writes the manifest of the compilers cached by the replica into the file.
+pub async fn export_manifest(settings: Settings, path: &Path) -> anyhow::Result<()> {
+    init_logs(&settings)?;
+    let compilers = LanguageCompilers::new(settings).await?;
+
+    let mut manifest = CompilersManifest::default();
+    if let Some(solidity) = &compilers.solidity {
+        manifest.solidity = cached_versions(solidity.client().compilers()).await;
+    }
+    if let Some(vyper) = &compilers.vyper {
+        manifest.vyper = cached_versions(vyper.client().compilers()).await;
+    }
+    if let Some(huff) = &compilers.huff {
+        manifest.huff = cached_versions(huff.client().compilers()).await;
+    }
+
+    let content = serde_json::to_string_pretty(&manifest)?;
+    std::fs::write(path, content)
+        .with_context(|| format!("failed to write manifest to {}", path.display()))?;
+    tracing::info!(
+        solidity = manifest.solidity.len(),
+        vyper = manifest.vyper.len(),
+        huff = manifest.huff.len(),
+        "compilers manifest has been exported"
+    );
+    Ok(())
+}
+
+/// Downloads all the compilers listed in the manifest. Fails if any of them
+/// could not be downloaded, or belongs to a language which is disabled.
+pub async fn warm_from_manifest(settings: Settings, path: &Path) -> anyhow::Result<()> {
+    init_logs(&settings)?;
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read manifest from {}", path.display()))?;
+    let manifest: CompilersManifest =
+        serde_json::from_str(&content).context("invalid compilers manifest")?;
+    let compilers = LanguageCompilers::new(settings).await?;
+
+    let disabled = |language: &str| anyhow::anyhow!("{language} verifier is disabled");
+    let mut failed = 0;
+    if !manifest.solidity.is_empty() {
+        let solidity = compilers.solidity.ok_or_else(|| disabled("solidity"))?;
+        failed += warm(
+            solidity.client().compilers(),
+            "solidity",
+            &manifest.solidity,
+        )
+        .await?;
+    }
+    if !manifest.vyper.is_empty() {
+        let vyper = compilers.vyper.ok_or_else(|| disabled("vyper"))?;
+        failed += warm(vyper.client().compilers(), "vyper", &manifest.vyper).await?;
+    }
+    if !manifest.huff.is_empty() {
+        let huff = compilers.huff.ok_or_else(|| disabled("huff"))?;
+        failed += warm(huff.client().compilers(), "huff", &manifest.huff).await?;
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{failed} compilers could not be downloaded");
+    }
+    Ok(())
+}
+
+fn init_logs(settings: &Settings) -> anyhow::Result<()> {
+    blockscout_service_launcher::init_logs(
+        "smart_contract_verifier",
+        &settings.tracing,
+        &settings.jaeger,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn manifest_languages_are_optional() {
+        let manifest: CompilersManifest =
+            serde_json::from_str(r#"{"solidity": ["v0.8.19+commit.7dd6d404"]}"#).unwrap();
+        let expected = CompilersManifest {
+            solidity: vec!["v0.8.19+commit.7dd6d404".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(expected, manifest);
+    }
+}
@@ -0,0 +1,114 @@
+use actix_web::{
+    dev::ServiceResponse,
+    test,
+    test::{read_body, read_body_json, TestRequest},
+    App,
+};
+use blockscout_display_bytes::Bytes as DisplayBytes;
+use serde_json::json;
+use sha3::{Digest, Keccak256};
+use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2::{
+    solidity_verifier_actix::route_solidity_verifier, source::MatchType, VerifyResponse,
+};
+use smart_contract_verifier_server::{Settings, SolidityVerifierService};
+use std::{fs, str::FromStr, sync::Arc};
+use tokio::sync::Semaphore;
+
+const CONTRACT_DIR: &str = "tests/contracts/match_type_full";
+const ROUTE: &str = "/api/v2/verifier/solidity/sources:verify-standard-json";
+const PATCHED_RANGE: std::ops::Range<usize> = 16..20;
+
+/// Deployed bytecode of the contract with the patched range overwritten by the `value`
+fn patched_bytecode(value: u8) -> Vec<u8> {
+    let bytecode = fs::read_to_string(format!("{CONTRACT_DIR}/deployed_bytecode"))
+        .expect("Error while reading deployed_bytecode");
+    let mut bytecode = DisplayBytes::from_str(&bytecode)
+        .expect("Invalid deployed bytecode")
+        .to_vec();
+    bytecode[PATCHED_RANGE].fill(value);
+    bytecode
+}
+
+async fn verify(bytecode: &[u8]) -> ServiceResponse {
+    let code_hash = DisplayBytes::from(Keccak256::digest(patched_bytecode(0xff)).to_vec());
+    let settings: Settings = serde_json::from_value(json!({
+        "solidity": {
+            "genesis_patches": {
+                "storage": {
+                    "code_hashes": [code_hash],
+                    "ranges": [{"start": PATCHED_RANGE.start, "end": PATCHED_RANGE.end}],
+                },
+            },
+        }
+    }))
+    .expect("invalid settings");
+    let compilers_lock = Arc::new(Semaphore::new(settings.compilers.max_threads.get()));
+    let solidity_service = SolidityVerifierService::new(
+        settings.solidity,
+        compilers_lock,
+        settings.extensions.solidity,
+    )
+    .await
+    .expect("couldn't initialize solidity service");
+    let app = test::init_service(
+        App::new().configure(|config| route_solidity_verifier(config, Arc::new(solidity_service))),
+    )
+    .await;
+
+    let request = json!({
+        "bytecode": DisplayBytes::from(bytecode.to_vec()),
+        "bytecodeType": "DEPLOYED_BYTECODE",
+        "compilerVersion": "v0.8.7+commit.e28d00a7",
+        "input": fs::read_to_string(format!("{CONTRACT_DIR}/standard_input.json"))
+            .expect("Error while reading standard_input.json"),
+        "genesisPatch": "storage",
+    });
+    TestRequest::post()
+        .uri(ROUTE)
+        .set_json(&request)
+        .send_request(&app)
+        .await
+}
+
+#[tokio::test]
+async fn patch_is_applied_to_configured_bytecode() {
+    let response = verify(&patched_bytecode(0xff)).await;
+    assert!(
+        response.status().is_success(),
+        "Invalid status code (success expected): {}",
+        response.status()
+    );
+
+    let verification_response: VerifyResponse = read_body_json(response).await;
+    assert_eq!(
+        verification_response.status().as_str_name(),
+        "SUCCESS",
+        "Invalid verification status. Response: {verification_response:?}"
+    );
+    assert_eq!(
+        MatchType::Partial,
+        verification_response
+            .source
+            .expect("Verification source is not Some")
+            .match_type(),
+        "Invalid match type"
+    );
+}
+
+#[tokio::test]
+async fn patch_is_refused_for_other_bytecodes() {
+    // Bytes inside the patched range would be ignored if the patch was applied
+    let response = verify(&patched_bytecode(0xee)).await;
+    assert_eq!(
+        actix_web::http::StatusCode::BAD_REQUEST,
+        response.status(),
+        "Invalid status code (bad request expected)"
+    );
+
+    let body = read_body(response).await;
+    let message = std::str::from_utf8(&body).expect("Read body as UTF-8");
+    assert!(
+        message.contains("genesis patch `storage` is not configured for the bytecode"),
+        "Invalid message: {message}"
+    );
+}
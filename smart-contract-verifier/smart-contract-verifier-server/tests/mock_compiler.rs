@@ -0,0 +1,49 @@
+use actix_web::{
+    test,
+    test::{read_body_json, TestRequest},
+    App,
+};
+use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2::{
+    solidity_verifier_actix::route_solidity_verifier, ListCompilerVersionsResponse,
+};
+use smart_contract_verifier_server::{Settings, SolidityVerifierService};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+#[tokio::test]
+async fn versions_are_not_downloaded() {
+    let outputs_dir = std::env::temp_dir().join("mock-compiler-outputs");
+    std::fs::create_dir_all(&outputs_dir).unwrap();
+    let settings: Settings = serde_json::from_value(serde_json::json!({
+        "solidity": {
+            "compiler_backend": "mock",
+            "mock_outputs_dir": outputs_dir,
+            "fetcher": {"mock": {"versions": ["v0.8.19+commit.7dd6d404"]}},
+        }
+    }))
+    .expect("invalid settings");
+    let compilers_lock = Arc::new(Semaphore::new(settings.compilers.max_threads.get()));
+
+    let solidity_service = SolidityVerifierService::new(
+        settings.solidity,
+        compilers_lock,
+        settings.extensions.solidity,
+    )
+    .await
+    .expect("couldn't initialize solidity service");
+    let app = test::init_service(
+        App::new().configure(|config| route_solidity_verifier(config, Arc::new(solidity_service))),
+    )
+    .await;
+
+    let response = TestRequest::get()
+        .uri("/api/v2/verifier/solidity/versions")
+        .send_request(&app)
+        .await;
+    assert!(response.status().is_success(), "{}", response.status());
+    let versions_response: ListCompilerVersionsResponse = read_body_json(response).await;
+    assert_eq!(
+        vec!["v0.8.19+commit.7dd6d404".to_string()],
+        versions_response.compiler_versions
+    );
+}
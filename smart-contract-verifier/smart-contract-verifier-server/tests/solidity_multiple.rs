@@ -326,6 +326,25 @@ mod success_tests {
         test_success(contract_dir, test_input).await;
     }
 
+    #[tokio::test]
+    async fn verifies_the_generated_bytecode_with_not_linked_libraries() {
+        let contract_dir = "contract_with_lib";
+        let test_input =
+            TestInput::new("SimpleStorage", "v0.5.11+commit.22be8592").with_optimization_runs(200);
+        let extra_data = test_success(contract_dir, test_input)
+            .await
+            .extra_data
+            .expect("Was unpacked successfully inside test_success");
+        assert_eq!(
+            extra_data.library_addresses,
+            BTreeMap::from([(
+                format!("{CONTRACTS_DIR}/{contract_dir}/source.sol:BadSafeMath"),
+                "0x9bca1bf2810c9b68f25c82e8ebb9dc0a5301e310".to_string()
+            )]),
+            "Invalid library addresses"
+        );
+    }
+
     #[tokio::test]
     // `whisper` metadata - (bzz0 => bzz1) in solidity 0.5.11()
     async fn verifies_smart_contract_with_new_whisper_metadata() {
@@ -365,6 +384,51 @@ mod success_tests {
         test_success(contract_dir, test_input).await;
     }
 
+    #[tokio::test]
+    async fn resolves_immutable_names_without_extra_outputs() {
+        let contract_dir = "with_immutable_assignment";
+        let test_input = TestInput::new("C", "v0.6.7+commit.b8d736ae")
+            .with_optimization_runs(200)
+            .ignore_creation_tx_input();
+        let extra_data = test_success(contract_dir, test_input)
+            .await
+            .extra_data
+            .expect("Was unpacked successfully inside test_success");
+        assert_eq!(
+            extra_data
+                .immutable_values
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+            vec!["decimals", "maxBalance", "owner"],
+            "Invalid immutable names"
+        );
+    }
+
+    #[tokio::test]
+    async fn extracts_risk_flags_without_extra_outputs() {
+        use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2::verify_response::extra_data::RiskFlags;
+
+        let contract_dir = "issue_5114";
+        let test_input = TestInput::new("TransparentUpgradeableProxy", "v0.8.2+commit.661d1103")
+            .with_optimization_runs(200)
+            .has_constructor_args();
+        let extra_data = test_success(contract_dir, test_input)
+            .await
+            .extra_data
+            .expect("Was unpacked successfully inside test_success");
+        assert_eq!(
+            extra_data.risk_flags,
+            Some(RiskFlags {
+                selfdestruct: false,
+                delegatecall: true,
+                tx_origin_auth: false,
+                inline_assembly: true,
+            }),
+            "Invalid risk flags"
+        );
+    }
+
     #[tokio::test]
     // verifies smart-contract created from another contract
     async fn contract_from_factory() {
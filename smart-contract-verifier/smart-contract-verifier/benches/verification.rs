@@ -0,0 +1,254 @@
+//! Benchmarks of the bytecode comparison and parsing hot paths.
+//!
+//! Contracts are synthesized with the maximum deployed bytecode size (EIP-170) and compiled
+//! by [`MockCompiler`], so that only the verifier itself is measured: metadata splitting,
+//! constructor arguments extraction, immutables masking and the full verification pipeline.
+//!
+//! Run with `cargo bench --bench verification`. Use `-- --save-baseline <name>` before
+//! and `-- --baseline <name>` after a change to compare the results.
+
+use bytes::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ethers_solc::{
+    artifacts::{Source, Sources},
+    CompilerInput, CompilerOutput,
+};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use smart_contract_verifier::{
+    solidity::{
+        metadata,
+        standard_json::{self, StandardJsonContent, VerificationRequest},
+    },
+    Compilers, MockCompiler, MockFetcher, SolidityClient, SolidityCompiler, Version,
+};
+use std::{str::FromStr, sync::Arc};
+use tokio::{runtime::Runtime, sync::Semaphore};
+
+/// Maximum deployed bytecode size (EIP-170)
+const CODE_SIZE: usize = 24 * 1024;
+const INIT_CODE_SIZE: usize = 512;
+const IMMUTABLES: usize = 32;
+const IMMUTABLE_SIZE: usize = 32;
+
+const COMPILER_VERSION: &str = "v0.8.19+commit.7dd6d404";
+const FILE_NAME: &str = "contracts/Main.sol";
+const CONTRACT_NAME: &str = "Main";
+
+const LOCAL_HASH: u8 = 0xaa;
+const MODIFIED_HASH: u8 = 0xcc;
+const OTHER_HASH: u8 = 0xbb;
+
+/// Synthesized contract: the code is random, while immutables are left zeroed
+/// the same way the compiler leaves them.
+struct Contract {
+    init_code: Vec<u8>,
+    code: Vec<u8>,
+    immutable_offsets: Vec<usize>,
+}
+
+impl Contract {
+    fn new() -> Self {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut init_code = vec![0u8; INIT_CODE_SIZE];
+        rng.fill_bytes(&mut init_code);
+        let code_size = CODE_SIZE - metadata(LOCAL_HASH).len();
+        let mut code = vec![0u8; code_size];
+        rng.fill_bytes(&mut code);
+
+        let step = code_size / IMMUTABLES;
+        let immutable_offsets: Vec<_> = (0..IMMUTABLES).map(|i| i * step + 1).collect();
+        for offset in &immutable_offsets {
+            code[*offset..*offset + IMMUTABLE_SIZE].fill(0);
+        }
+
+        Self {
+            init_code,
+            code,
+            immutable_offsets,
+        }
+    }
+
+    fn deployed_bytecode(&self, hash: u8) -> Vec<u8> {
+        [self.code.as_slice(), &metadata(hash)].concat()
+    }
+
+    fn creation_bytecode(&self, hash: u8) -> Vec<u8> {
+        [self.init_code.as_slice(), &self.deployed_bytecode(hash)].concat()
+    }
+
+    /// Deployed bytecode with the values of immutables inserted by the constructor
+    fn deployed_bytecode_with_immutables(&self, hash: u8) -> Vec<u8> {
+        let mut bytecode = self.deployed_bytecode(hash);
+        for offset in &self.immutable_offsets {
+            bytecode[*offset..*offset + IMMUTABLE_SIZE].fill(0x11);
+        }
+        bytecode
+    }
+
+    fn output(&self, hash: u8) -> CompilerOutput {
+        let immutable_references: Vec<_> = self
+            .immutable_offsets
+            .iter()
+            .map(|offset| serde_json::json!({"start": offset, "length": IMMUTABLE_SIZE}))
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "contracts": {
+                FILE_NAME: {
+                    CONTRACT_NAME: {
+                        "abi": [{
+                            "inputs": [{"internalType": "uint256", "name": "value", "type": "uint256"}],
+                            "stateMutability": "nonpayable",
+                            "type": "constructor"
+                        }],
+                        "evm": {
+                            "bytecode": {"object": hex::encode(self.creation_bytecode(hash))},
+                            "deployedBytecode": {
+                                "object": hex::encode(self.deployed_bytecode(hash)),
+                                "immutableReferences": {"7": immutable_references}
+                            }
+                        }
+                    }
+                }
+            },
+            "sources": {},
+        }))
+        .expect("compiler output is valid")
+    }
+}
+
+/// {"ipfs": h'1220<hash>', "solc": h'000813'}
+fn metadata(hash: u8) -> Vec<u8> {
+    let mut metadata = vec![0xa2, 0x64];
+    metadata.extend_from_slice(b"ipfs");
+    metadata.extend_from_slice(&[0x58, 0x22, 0x12, 0x20]);
+    metadata.extend_from_slice(&[hash; 32]);
+    metadata.push(0x64);
+    metadata.extend_from_slice(b"solc");
+    metadata.extend_from_slice(&[0x43, 0x00, 0x08, 0x13, 0x00, 0x33]);
+    metadata
+}
+
+fn constructor_args() -> Vec<u8> {
+    let mut args = vec![0u8; 32];
+    args[31] = 0x2a;
+    args
+}
+
+fn content() -> StandardJsonContent {
+    StandardJsonContent {
+        input: CompilerInput {
+            language: "Solidity".to_string(),
+            sources: Sources::from([(FILE_NAME.into(), Source::new("contract Main {}"))]),
+            settings: Default::default(),
+        },
+        extra_outputs: false,
+    }
+}
+
+/// The verifier compiles the input once more with an unused library added,
+/// so that the metadata hashes could be located by the differing bytes.
+fn modified_input(input: &CompilerInput) -> CompilerInput {
+    let mut input = input.clone();
+    input
+        .settings
+        .libraries
+        .libs
+        .entry("SOME_TEXT_USED_AS_FILE_NAME".into())
+        .or_default()
+        .insert(
+            "_".to_string(),
+            "0xcafecafecafecafecafecafecafecafecafecafe".to_string(),
+        );
+    input
+}
+
+fn client(contract: &Contract) -> Arc<SolidityClient> {
+    let version = Version::from_str(COMPILER_VERSION).unwrap();
+    let input = CompilerInput::from(content());
+    let compiler = MockCompiler::new()
+        .with_output(&input, contract.output(LOCAL_HASH))
+        .with_output(&modified_input(&input), contract.output(MODIFIED_HASH));
+    let compilers = Compilers::new(
+        Arc::new(MockFetcher::new(vec![version])),
+        SolidityCompiler::new().with_backend(Box::new(compiler)),
+        Arc::new(Semaphore::new(1)),
+    );
+    Arc::new(SolidityClient::new(compilers))
+}
+
+fn request(creation_bytecode: Option<Vec<u8>>, deployed_bytecode: Vec<u8>) -> VerificationRequest {
+    VerificationRequest {
+        deployed_bytecode: Bytes::from(deployed_bytecode),
+        creation_bytecode: creation_bytecode.map(Bytes::from),
+        compiler_version: Version::from_str(COMPILER_VERSION).unwrap(),
+        auto_select_compiler_version: false,
+        bytecode_mask: vec![],
+        eof_enabled: false,
+        evm_fork: None,
+        normalization: Default::default(),
+        trace: None,
+        retry_with_flipped_via_ir: false,
+        content: content(),
+    }
+}
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime should be built")
+}
+
+fn metadata_split(c: &mut Criterion) {
+    let bytecode = Contract::new().deployed_bytecode(LOCAL_HASH);
+    c.bench_function("metadata/split_24kb", |b| {
+        b.iter(|| metadata::split(black_box(&bytecode)))
+    });
+}
+
+fn verification(c: &mut Criterion) {
+    let runtime = runtime();
+    let contract = Contract::new();
+    let client = client(&contract);
+
+    let mut group = c.benchmark_group("verification");
+    let cases = [
+        (
+            "creation_input_full_match_24kb",
+            Some([contract.creation_bytecode(LOCAL_HASH), constructor_args()].concat()),
+            vec![],
+        ),
+        (
+            "creation_input_partial_match_24kb",
+            Some([contract.creation_bytecode(OTHER_HASH), constructor_args()].concat()),
+            vec![],
+        ),
+        (
+            "deployed_bytecode_immutables_24kb",
+            None,
+            contract.deployed_bytecode_with_immutables(OTHER_HASH),
+        ),
+    ];
+    for (name, creation_bytecode, deployed_bytecode) in cases {
+        // Ensures the case is verified, so that the failure path is not measured instead
+        runtime
+            .block_on(standard_json::verify(
+                client.clone(),
+                request(creation_bytecode.clone(), deployed_bytecode.clone()),
+            ))
+            .unwrap_or_else(|err| panic!("{name} should be verified: {err}"));
+
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| {
+                standard_json::verify(
+                    client.clone(),
+                    request(creation_bytecode.clone(), deployed_bytecode.clone()),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, metadata_split, verification);
+criterion_main!(benches);
@@ -0,0 +1,110 @@
+//! Textual representation of the addresses returned by the service.
+//!
+//! Addresses are EIP-55 checksummed. Networks which include the chain id into
+//! the checksum (EIP-1191, e.g. RSK) are supported via the optional `chain_id`.
+
+use sha3::{Digest, Keccak256};
+
+pub fn to_checksum(address: &[u8; 20], chain_id: Option<u64>) -> String {
+    let address = hex::encode(address);
+    let hash = match chain_id {
+        Some(chain_id) => Keccak256::digest(format!("{chain_id}0x{address}")),
+        None => Keccak256::digest(&address),
+    };
+    let checksummed: String = address
+        .char_indices()
+        .map(|(index, char)| {
+            let byte = hash[index / 2];
+            let nibble = if index % 2 == 0 {
+                byte >> 4
+            } else {
+                byte & 0x0f
+            };
+            if nibble >= 8 {
+                char.to_ascii_uppercase()
+            } else {
+                char
+            }
+        })
+        .collect();
+    format!("0x{checksummed}")
+}
+
+/// Returns the checksummed address if the value is a hex encoded 20-byte address.
+pub fn format_address(value: &str, chain_id: Option<u64>) -> Option<String> {
+    let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value)).ok()?;
+    let address: [u8; 20] = bytes.try_into().ok()?;
+    Some(to_checksum(&address, chain_id))
+}
+
+/// Checksums the library addresses of serialized compiler settings (`settings.libraries`).
+///
+/// Addresses are replaced in place, so that the rest of the settings is kept as is.
+pub fn format_settings_libraries(settings: &str, chain_id: Option<u64>) -> String {
+    let libraries = serde_json::from_str::<serde_json::Value>(settings)
+        .ok()
+        .and_then(|mut settings| settings.get_mut("libraries").map(serde_json::Value::take));
+    let mut addresses = Vec::new();
+    if let Some(libraries) = &libraries {
+        collect_strings(libraries, &mut addresses);
+    }
+
+    let mut settings = settings.to_string();
+    for address in addresses {
+        if let Some(checksummed) = format_address(address, chain_id) {
+            settings = settings.replace(&format!("\"{address}\""), &format!("\"{checksummed}\""));
+        }
+    }
+    settings
+}
+
+fn collect_strings<'a>(value: &'a serde_json::Value, strings: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(string) => strings.push(string),
+        serde_json::Value::Object(object) => object
+            .values()
+            .for_each(|value| collect_strings(value, strings)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const ADDRESS: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+
+    #[test]
+    fn checksum_addresses() {
+        assert_eq!(
+            Some("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string()),
+            format_address(ADDRESS, None)
+        );
+        assert_eq!(
+            Some("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359".to_string()),
+            format_address("FB6916095CA1DF60BB79CE92CE3EA74C37C5D359", None)
+        );
+        // RSK mainnet and testnet (EIP-1191)
+        assert_eq!(
+            Some("0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD".to_string()),
+            format_address(ADDRESS, Some(30))
+        );
+        assert_eq!(
+            Some("0x5aAeb6053F3e94c9b9A09F33669435E7EF1BEaEd".to_string()),
+            format_address(ADDRESS, Some(31))
+        );
+
+        assert_eq!(None, format_address("0x1234", None));
+        assert_eq!(None, format_address("not an address", None));
+    }
+
+    #[test]
+    fn checksum_settings_libraries() {
+        let settings = format!(
+            r#"{{"optimizer":{{"enabled":false}},"libraries":{{"contracts/Lib.sol":{{"Lib":"{ADDRESS}"}}}},"evmVersion":"london"}}"#
+        );
+        let expected = r#"{"optimizer":{"enabled":false},"libraries":{"contracts/Lib.sol":{"Lib":"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"}},"evmVersion":"london"}"#;
+        assert_eq!(expected, format_settings_libraries(&settings, None));
+    }
+}
@@ -0,0 +1,405 @@
+//! Structured differences between two versions of a contract,
+//! e.g. implementations before and after a proxy upgrade.
+
+use ethabi::{Contract, Param, ParamType};
+use ethers_solc::artifacts::StorageLayout;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ItemKind {
+    Function,
+    Event,
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// The item with the same name exists in both abis but differs in its declaration
+    Changed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AbiChange {
+    pub kind: ItemKind,
+    pub change: ChangeKind,
+    pub name: String,
+    /// Declaration in the old abi; `None` for added items
+    pub old_declaration: Option<String>,
+    /// Declaration in the new abi; `None` for removed items
+    pub new_declaration: Option<String>,
+}
+
+/// Variable of the old storage layout which is not preserved by the new one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageIncompatibility {
+    pub slot: String,
+    pub offset: i64,
+    /// Variable of the old layout as `label: type`
+    pub old_variable: String,
+    /// Variable occupying the same position in the new layout, if any
+    pub new_variable: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContractDiff {
+    pub abi_changes: Vec<AbiChange>,
+    pub storage_incompatibilities: Vec<StorageIncompatibility>,
+}
+
+/// Compares the abis and, if both provided, storage layouts of two contract versions.
+pub fn diff_contracts(
+    old_abi: &Contract,
+    new_abi: &Contract,
+    storage_layouts: Option<(&StorageLayout, &StorageLayout)>,
+) -> ContractDiff {
+    ContractDiff {
+        abi_changes: diff_abis(old_abi, new_abi),
+        storage_incompatibilities: storage_layouts
+            .map(|(old, new)| diff_storage_layouts(old, new))
+            .unwrap_or_default(),
+    }
+}
+
+/// Returns added, removed, and changed functions, events, and errors.
+///
+/// Items are matched by their signatures. An item which signature changed is reported
+/// as a single `Changed` item if it is the only one with such name in both abis,
+/// and as a removal and an addition otherwise (e.g., for overloaded functions).
+pub fn diff_abis(old: &Contract, new: &Contract) -> Vec<AbiChange> {
+    let mut changes = Vec::new();
+    changes.extend(diff_items(
+        ItemKind::Function,
+        functions(old),
+        functions(new),
+    ));
+    changes.extend(diff_items(ItemKind::Event, events(old), events(new)));
+    changes.extend(diff_items(ItemKind::Error, errors(old), errors(new)));
+    changes
+}
+
+/// Item declaration split into the name, the signature it is identified by,
+/// and the full declaration which includes parts not affecting the signature.
+struct Item {
+    name: String,
+    signature: String,
+    declaration: String,
+}
+
+fn params(params: &[Param]) -> String {
+    params
+        .iter()
+        .map(|param| param.kind.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn signature(name: &str, kinds: impl Iterator<Item = ParamType>) -> String {
+    let kinds = kinds.map(|kind| kind.to_string()).collect::<Vec<_>>();
+    format!("{name}({})", kinds.join(","))
+}
+
+fn functions(abi: &Contract) -> Vec<Item> {
+    abi.functions()
+        .map(|function| {
+            let signature = signature(
+                &function.name,
+                function.inputs.iter().map(|param| param.kind.clone()),
+            );
+            let mutability = serde_json::to_value(function.state_mutability)
+                .ok()
+                .and_then(|value| value.as_str().map(str::to_string))
+                .unwrap_or_default();
+            let declaration = format!(
+                "{signature} {mutability} returns ({})",
+                params(&function.outputs)
+            );
+            Item {
+                name: function.name.clone(),
+                signature,
+                declaration,
+            }
+        })
+        .collect()
+}
+
+fn events(abi: &Contract) -> Vec<Item> {
+    abi.events()
+        .map(|event| {
+            let signature = signature(
+                &event.name,
+                event.inputs.iter().map(|param| param.kind.clone()),
+            );
+            let inputs = event
+                .inputs
+                .iter()
+                .map(|param| match param.indexed {
+                    true => format!("{} indexed", param.kind),
+                    false => param.kind.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            let anonymous = if event.anonymous { " anonymous" } else { "" };
+            Item {
+                name: event.name.clone(),
+                signature,
+                declaration: format!("{}({inputs}){anonymous}", event.name),
+            }
+        })
+        .collect()
+}
+
+fn errors(abi: &Contract) -> Vec<Item> {
+    abi.errors()
+        .map(|error| {
+            let signature = signature(
+                &error.name,
+                error.inputs.iter().map(|param| param.kind.clone()),
+            );
+            Item {
+                name: error.name.clone(),
+                declaration: signature.clone(),
+                signature,
+            }
+        })
+        .collect()
+}
+
+fn diff_items(kind: ItemKind, old: Vec<Item>, new: Vec<Item>) -> Vec<AbiChange> {
+    let old: BTreeMap<_, _> = old
+        .into_iter()
+        .map(|item| (item.signature.clone(), item))
+        .collect();
+    let new: BTreeMap<_, _> = new
+        .into_iter()
+        .map(|item| (item.signature.clone(), item))
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut removed: BTreeMap<&str, Vec<&Item>> = BTreeMap::new();
+    let mut added: BTreeMap<&str, Vec<&Item>> = BTreeMap::new();
+    for (signature, old_item) in &old {
+        match new.get(signature) {
+            Some(new_item) if new_item.declaration != old_item.declaration => {
+                changes.push(AbiChange {
+                    kind,
+                    change: ChangeKind::Changed,
+                    name: old_item.name.clone(),
+                    old_declaration: Some(old_item.declaration.clone()),
+                    new_declaration: Some(new_item.declaration.clone()),
+                })
+            }
+            Some(_) => {}
+            None => removed.entry(&old_item.name).or_default().push(old_item),
+        }
+    }
+    for (signature, new_item) in &new {
+        if !old.contains_key(signature) {
+            added.entry(&new_item.name).or_default().push(new_item);
+        }
+    }
+
+    let is_overloaded = |name: &str, items: &BTreeMap<String, Item>| {
+        items.values().filter(|item| item.name == name).count() > 1
+    };
+    for (name, removed_items) in removed {
+        let single_added = added.get(name).and_then(|items| match items.as_slice() {
+            [item] => Some(*item),
+            _ => None,
+        });
+        match (removed_items.as_slice(), single_added) {
+            ([old_item], Some(new_item))
+                if !is_overloaded(name, &old) && !is_overloaded(name, &new) =>
+            {
+                added.remove(name);
+                changes.push(AbiChange {
+                    kind,
+                    change: ChangeKind::Changed,
+                    name: name.to_string(),
+                    old_declaration: Some(old_item.declaration.clone()),
+                    new_declaration: Some(new_item.declaration.clone()),
+                });
+            }
+            _ => changes.extend(removed_items.iter().map(|item| AbiChange {
+                kind,
+                change: ChangeKind::Removed,
+                name: item.name.clone(),
+                old_declaration: Some(item.declaration.clone()),
+                new_declaration: None,
+            })),
+        }
+    }
+    changes.extend(added.into_values().flatten().map(|item| AbiChange {
+        kind,
+        change: ChangeKind::Added,
+        name: item.name.clone(),
+        old_declaration: None,
+        new_declaration: Some(item.declaration.clone()),
+    }));
+
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    changes
+}
+
+/// Returns variables of the old layout which are not kept at the same position
+/// and with the same type by the new layout.
+///
+/// Renamed variables and variables appended to the end of the storage
+/// are considered compatible.
+pub fn diff_storage_layouts(
+    old: &StorageLayout,
+    new: &StorageLayout,
+) -> Vec<StorageIncompatibility> {
+    let type_label = |layout: &StorageLayout, storage_type: &str| {
+        layout
+            .types
+            .get(storage_type)
+            .map(|storage_type| storage_type.label.clone())
+            .unwrap_or_else(|| storage_type.to_string())
+    };
+    let new_variables: BTreeMap<_, _> = new
+        .storage
+        .iter()
+        .map(|variable| ((variable.slot.as_str(), variable.offset), variable))
+        .collect();
+
+    old.storage
+        .iter()
+        .filter_map(|old_variable| {
+            let old_type = type_label(old, &old_variable.storage_type);
+            let new_variable =
+                new_variables.get(&(old_variable.slot.as_str(), old_variable.offset));
+            let new_type = new_variable.map(|variable| type_label(new, &variable.storage_type));
+            if new_type.as_ref() == Some(&old_type) {
+                return None;
+            }
+            Some(StorageIncompatibility {
+                slot: old_variable.slot.clone(),
+                offset: old_variable.offset,
+                old_variable: format!("{}: {old_type}", old_variable.label),
+                new_variable: new_variable
+                    .zip(new_type)
+                    .map(|(variable, new_type)| format!("{}: {new_type}", variable.label)),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn abi(value: serde_json::Value) -> Contract {
+        serde_json::from_value(value).expect("invalid abi")
+    }
+
+    fn storage_layout(value: serde_json::Value) -> StorageLayout {
+        serde_json::from_value(value).expect("invalid storage layout")
+    }
+
+    #[test]
+    fn diff_functions_events_and_errors() {
+        let old = abi(serde_json::json!([
+            {"type": "function", "name": "owner", "inputs": [], "outputs": [{"name": "", "type": "address"}], "stateMutability": "view"},
+            {"type": "function", "name": "transfer", "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}], "outputs": [{"name": "", "type": "bool"}], "stateMutability": "nonpayable"},
+            {"type": "function", "name": "pause", "inputs": [], "outputs": [], "stateMutability": "nonpayable"},
+            {"type": "event", "name": "Transfer", "inputs": [{"name": "from", "type": "address", "indexed": false}, {"name": "to", "type": "address", "indexed": false}], "anonymous": false},
+        ]));
+        let new = abi(serde_json::json!([
+            {"type": "function", "name": "owner", "inputs": [], "outputs": [{"name": "", "type": "address"}], "stateMutability": "view"},
+            {"type": "function", "name": "transfer", "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}], "outputs": [{"name": "", "type": "bool"}], "stateMutability": "payable"},
+            {"type": "function", "name": "pause", "inputs": [{"name": "reason", "type": "string"}], "outputs": [], "stateMutability": "nonpayable"},
+            {"type": "function", "name": "unpause", "inputs": [], "outputs": [], "stateMutability": "nonpayable"},
+            {"type": "event", "name": "Transfer", "inputs": [{"name": "from", "type": "address", "indexed": true}, {"name": "to", "type": "address", "indexed": true}], "anonymous": false},
+            {"type": "error", "name": "Paused", "inputs": []},
+        ]));
+
+        let expected = vec![
+            AbiChange {
+                kind: ItemKind::Function,
+                change: ChangeKind::Changed,
+                name: "pause".into(),
+                old_declaration: Some("pause() nonpayable returns ()".into()),
+                new_declaration: Some("pause(string) nonpayable returns ()".into()),
+            },
+            AbiChange {
+                kind: ItemKind::Function,
+                change: ChangeKind::Changed,
+                name: "transfer".into(),
+                old_declaration: Some("transfer(address,uint256) nonpayable returns (bool)".into()),
+                new_declaration: Some("transfer(address,uint256) payable returns (bool)".into()),
+            },
+            AbiChange {
+                kind: ItemKind::Function,
+                change: ChangeKind::Added,
+                name: "unpause".into(),
+                old_declaration: None,
+                new_declaration: Some("unpause() nonpayable returns ()".into()),
+            },
+            AbiChange {
+                kind: ItemKind::Event,
+                change: ChangeKind::Changed,
+                name: "Transfer".into(),
+                old_declaration: Some("Transfer(address,address)".into()),
+                new_declaration: Some("Transfer(address indexed,address indexed)".into()),
+            },
+            AbiChange {
+                kind: ItemKind::Error,
+                change: ChangeKind::Added,
+                name: "Paused".into(),
+                old_declaration: None,
+                new_declaration: Some("Paused()".into()),
+            },
+        ];
+        assert_eq!(expected, diff_abis(&old, &new));
+    }
+
+    #[test]
+    fn diff_storage() {
+        let types = serde_json::json!({
+            "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+            "t_bool": {"encoding": "inplace", "label": "bool", "numberOfBytes": "1"},
+            "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"},
+        });
+        let variable = |label: &str, slot: &str, offset: i64, storage_type: &str| {
+            serde_json::json!({
+                "astId": 1, "contract": "A.sol:A", "label": label,
+                "offset": offset, "slot": slot, "type": storage_type
+            })
+        };
+        let old = storage_layout(serde_json::json!({
+            "storage": [
+                variable("owner", "0", 0, "t_address"),
+                variable("paused", "0", 20, "t_bool"),
+                variable("total", "1", 0, "t_uint256"),
+            ],
+            "types": types,
+        }));
+        let new = storage_layout(serde_json::json!({
+            "storage": [
+                variable("admin", "0", 0, "t_address"),
+                variable("version", "1", 0, "t_address"),
+                variable("total", "2", 0, "t_uint256"),
+            ],
+            "types": types,
+        }));
+
+        let expected = vec![
+            StorageIncompatibility {
+                slot: "0".into(),
+                offset: 20,
+                old_variable: "paused: bool".into(),
+                new_variable: None,
+            },
+            StorageIncompatibility {
+                slot: "1".into(),
+                offset: 0,
+                old_variable: "total: uint256".into(),
+                new_variable: Some("version: address".into()),
+            },
+        ];
+        assert_eq!(expected, diff_storage_layouts(&old, &new));
+    }
+}
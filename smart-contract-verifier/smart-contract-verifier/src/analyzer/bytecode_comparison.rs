@@ -0,0 +1,210 @@
+//! Comparison of two raw bytecodes without any sources.
+//!
+//! Lets users and other services reuse the comparison the verification relies on:
+//! parts of the bytecodes expected to differ regardless of the sources (metadata hash,
+//! values of immutables, constructor arguments) may be excluded from the comparison.
+
+use crate::solidity::metadata;
+use bytes::Bytes;
+use std::ops::Range;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompareOptions {
+    /// Excludes the CBOR encoded metadata hash appended to the bytecodes
+    pub ignore_metadata: bool,
+    /// Excludes the given byte ranges of both bytecodes (e.g., `immutableReferences`
+    /// of the compiled contract, which are filled by the constructor on deployment)
+    pub ignored_ranges: Vec<Range<usize>>,
+    /// Considers bytes of the remote bytecode following the local one as constructor arguments
+    pub ignore_constructor_args: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Difference {
+    /// Offset of the differing bytes in both bytecodes
+    pub offset: usize,
+    pub local: Bytes,
+    pub remote: Bytes,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Comparison {
+    /// Bytecodes are equal except for the ignored parts
+    pub equal: bool,
+    /// Maximal ranges of differing bytes. If the bytecodes have different lengths,
+    /// the tail of the longer one is reported as a difference as well.
+    pub differences: Vec<Difference>,
+    /// Ranges actually excluded from the comparison (within the compared length)
+    pub ignored_ranges: Vec<Range<usize>>,
+    /// Extracted if `ignore_constructor_args` is set and the remote bytecode is longer
+    pub constructor_args: Option<Bytes>,
+}
+
+pub fn compare(local: &[u8], remote: &[u8], options: &CompareOptions) -> Comparison {
+    let (remote, constructor_args) = match remote.len().checked_sub(local.len()) {
+        Some(args_length) if options.ignore_constructor_args && args_length > 0 => {
+            let (code, args) = remote.split_at(local.len());
+            (code, Some(Bytes::copy_from_slice(args)))
+        }
+        _ => (remote, None),
+    };
+
+    let compared_length = local.len().min(remote.len());
+    let mut ignored_ranges = options.ignored_ranges.clone();
+    if options.ignore_metadata {
+        ignored_ranges.extend(metadata_range(local));
+        ignored_ranges.extend(metadata_range(remote));
+    }
+    let ignored_ranges = normalize(ignored_ranges, compared_length);
+    let is_ignored = |index: usize| ignored_ranges.iter().any(|range| range.contains(&index));
+
+    let mut differences = Vec::new();
+    let mut start: Option<usize> = None;
+    for index in 0..=compared_length {
+        let differs =
+            index < compared_length && !is_ignored(index) && local[index] != remote[index];
+        match (differs, start) {
+            (true, None) => start = Some(index),
+            (false, Some(offset)) => {
+                differences.push(Difference {
+                    offset,
+                    local: Bytes::copy_from_slice(&local[offset..index]),
+                    remote: Bytes::copy_from_slice(&remote[offset..index]),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if local.len() != remote.len() {
+        differences.push(Difference {
+            offset: compared_length,
+            local: Bytes::copy_from_slice(&local[compared_length..]),
+            remote: Bytes::copy_from_slice(&remote[compared_length..]),
+        });
+    }
+
+    Comparison {
+        equal: differences.is_empty(),
+        differences,
+        ignored_ranges,
+        constructor_args,
+    }
+}
+
+fn metadata_range(bytecode: &[u8]) -> Option<Range<usize>> {
+    let (code, metadata) = metadata::split(bytecode);
+    metadata.map(|_| code.len()..bytecode.len())
+}
+
+/// Sorts and merges overlapping ranges, truncating them to the `length`.
+fn normalize(mut ranges: Vec<Range<usize>>, length: usize) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|range| (range.start, range.end));
+    let mut normalized: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        let range = range.start.min(length)..range.end.min(length);
+        if range.is_empty() {
+            continue;
+        }
+        match normalized.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => normalized.push(range),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // {"ipfs": h'1220EB23CE2C13EA8739368F952F6C6A4B1F0623D147D2A19B6D4D26A61AB03FCD3E', "solc": 0.8.14}
+    const METADATA_1: &str = "a2646970667358221220eb23ce2c13ea8739368f952f6c6a4b1f0623d147d2a19b6d4d26a61ab03fcd3e64736f6c634300080e0033";
+    // The same metadata with another ipfs hash
+    const METADATA_2: &str = "a2646970667358221220ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff64736f6c634300080e0033";
+
+    fn bytecode(code: &str, metadata: &str) -> Vec<u8> {
+        hex::decode(format!("{code}{metadata}")).unwrap()
+    }
+
+    #[test]
+    fn compare_with_differences() {
+        let local = hex::decode("6080604052").unwrap();
+        let remote = hex::decode("6080614053").unwrap();
+
+        let comparison = compare(&local, &remote, &CompareOptions::default());
+        assert!(!comparison.equal);
+        assert_eq!(
+            vec![
+                Difference {
+                    offset: 2,
+                    local: Bytes::from_static(&[0x60]),
+                    remote: Bytes::from_static(&[0x61]),
+                },
+                Difference {
+                    offset: 4,
+                    local: Bytes::from_static(&[0x52]),
+                    remote: Bytes::from_static(&[0x53]),
+                },
+            ],
+            comparison.differences
+        );
+    }
+
+    #[test]
+    fn compare_ignoring_metadata_and_constructor_args() {
+        let local = bytecode("6080604052", METADATA_1);
+        let remote = [bytecode("6080604052", METADATA_2), vec![0xca, 0xfe]].concat();
+
+        let comparison = compare(&local, &remote, &CompareOptions::default());
+        assert!(!comparison.equal);
+
+        let options = CompareOptions {
+            ignore_metadata: true,
+            ignore_constructor_args: true,
+            ..Default::default()
+        };
+        let comparison = compare(&local, &remote, &options);
+        assert_eq!(
+            Comparison {
+                equal: true,
+                differences: vec![],
+                ignored_ranges: vec![5..local.len()],
+                constructor_args: Some(Bytes::from_static(&[0xca, 0xfe])),
+            },
+            comparison
+        );
+    }
+
+    #[test]
+    fn compare_ignoring_immutables() {
+        let local =
+            hex::decode("7f0000000000000000000000000000000000000000000000000000000000000000")
+                .unwrap();
+        let mut remote = local.clone();
+        remote[32] = 0x01;
+
+        let options = CompareOptions {
+            ignored_ranges: vec![1..33],
+            ..Default::default()
+        };
+        assert!(compare(&local, &remote, &options).equal);
+    }
+
+    #[test]
+    fn compare_with_different_lengths() {
+        let local = hex::decode("60806040").unwrap();
+        let remote = hex::decode("6080").unwrap();
+
+        let comparison = compare(&local, &remote, &CompareOptions::default());
+        assert_eq!(
+            vec![Difference {
+                offset: 2,
+                local: Bytes::from_static(&[0x60, 0x40]),
+                remote: Bytes::new(),
+            }],
+            comparison.differences
+        );
+    }
+}
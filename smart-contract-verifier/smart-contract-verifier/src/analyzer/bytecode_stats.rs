@@ -0,0 +1,238 @@
+//! Statistics of a raw deployed bytecode which do not require the sources.
+//!
+//! Is intended for explorers to show risk badges for unverified contracts
+//! (e.g., whether the contract could be destroyed or delegates its calls).
+
+use crate::solidity::metadata;
+use std::collections::BTreeMap;
+
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7f;
+const CREATE2: u8 = 0xf5;
+const DELEGATECALL: u8 = 0xf4;
+const SELFDESTRUCT: u8 = 0xff;
+
+/// Gas charged for the contract creation transaction itself
+const CREATION_TX_COST: u64 = 53_000;
+const ZERO_BYTE_COST: u64 = 4;
+const NON_ZERO_BYTE_COST: u64 = 16;
+/// Cost of every 32-byte word of the init code (EIP-3860)
+const INIT_CODE_WORD_COST: u64 = 2;
+const CODE_DEPOSIT_BYTE_COST: u64 = 200;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BytecodeStats {
+    /// Number of occurrences of each opcode by the opcode names. Bytes not
+    /// corresponding to any opcode are reported by their hex values (e.g. "0x0c")
+    pub opcode_histogram: BTreeMap<String, u64>,
+    /// Size of the instructions (without their push data) in bytes
+    pub code_size: usize,
+    /// Size of the data pushed onto the stack by `PUSH` instructions in bytes
+    pub push_data_size: usize,
+    /// Size of the CBOR encoded metadata hash appended to the code in bytes
+    pub metadata_size: usize,
+    /// Lower bound of the gas required to deploy the bytecode: the bytecode is considered
+    /// to be the init code, and the execution of the constructor is not accounted
+    pub estimated_deployment_gas: u64,
+    pub has_selfdestruct: bool,
+    pub has_delegatecall: bool,
+    pub has_create2: bool,
+}
+
+pub fn analyze(bytecode: &[u8]) -> BytecodeStats {
+    let (code, _) = metadata::split(bytecode);
+    let mut stats = BytecodeStats {
+        metadata_size: bytecode.len() - code.len(),
+        estimated_deployment_gas: estimate_deployment_gas(bytecode),
+        ..Default::default()
+    };
+
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        let name = opcode_name(opcode)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("0x{opcode:02x}"));
+        *stats.opcode_histogram.entry(name).or_default() += 1;
+        match opcode {
+            CREATE2 => stats.has_create2 = true,
+            DELEGATECALL => stats.has_delegatecall = true,
+            SELFDESTRUCT => stats.has_selfdestruct = true,
+            _ => {}
+        }
+
+        stats.code_size += 1;
+        pc += 1;
+        if (PUSH1..=PUSH32).contains(&opcode) {
+            // The data of the last push may be truncated
+            let data_size = ((opcode - PUSH1) as usize + 1).min(code.len() - pc);
+            stats.push_data_size += data_size;
+            pc += data_size;
+        }
+    }
+    stats
+}
+
+fn estimate_deployment_gas(bytecode: &[u8]) -> u64 {
+    let calldata_cost: u64 = bytecode
+        .iter()
+        .map(|&byte| match byte {
+            0 => ZERO_BYTE_COST,
+            _ => NON_ZERO_BYTE_COST,
+        })
+        .sum();
+    let length = bytecode.len() as u64;
+    CREATION_TX_COST
+        + calldata_cost
+        + INIT_CODE_WORD_COST * ((length + 31) / 32)
+        + CODE_DEPOSIT_BYTE_COST * length
+}
+
+fn opcode_name(opcode: u8) -> Option<&'static str> {
+    const PUSH: [&str; 32] = [
+        "PUSH1", "PUSH2", "PUSH3", "PUSH4", "PUSH5", "PUSH6", "PUSH7", "PUSH8", "PUSH9", "PUSH10",
+        "PUSH11", "PUSH12", "PUSH13", "PUSH14", "PUSH15", "PUSH16", "PUSH17", "PUSH18", "PUSH19",
+        "PUSH20", "PUSH21", "PUSH22", "PUSH23", "PUSH24", "PUSH25", "PUSH26", "PUSH27", "PUSH28",
+        "PUSH29", "PUSH30", "PUSH31", "PUSH32",
+    ];
+    const DUP: [&str; 16] = [
+        "DUP1", "DUP2", "DUP3", "DUP4", "DUP5", "DUP6", "DUP7", "DUP8", "DUP9", "DUP10", "DUP11",
+        "DUP12", "DUP13", "DUP14", "DUP15", "DUP16",
+    ];
+    const SWAP: [&str; 16] = [
+        "SWAP1", "SWAP2", "SWAP3", "SWAP4", "SWAP5", "SWAP6", "SWAP7", "SWAP8", "SWAP9", "SWAP10",
+        "SWAP11", "SWAP12", "SWAP13", "SWAP14", "SWAP15", "SWAP16",
+    ];
+    const LOG: [&str; 5] = ["LOG0", "LOG1", "LOG2", "LOG3", "LOG4"];
+
+    let name = match opcode {
+        0x00 => "STOP",
+        0x01 => "ADD",
+        0x02 => "MUL",
+        0x03 => "SUB",
+        0x04 => "DIV",
+        0x05 => "SDIV",
+        0x06 => "MOD",
+        0x07 => "SMOD",
+        0x08 => "ADDMOD",
+        0x09 => "MULMOD",
+        0x0a => "EXP",
+        0x0b => "SIGNEXTEND",
+        0x10 => "LT",
+        0x11 => "GT",
+        0x12 => "SLT",
+        0x13 => "SGT",
+        0x14 => "EQ",
+        0x15 => "ISZERO",
+        0x16 => "AND",
+        0x17 => "OR",
+        0x18 => "XOR",
+        0x19 => "NOT",
+        0x1a => "BYTE",
+        0x1b => "SHL",
+        0x1c => "SHR",
+        0x1d => "SAR",
+        0x20 => "KECCAK256",
+        0x30 => "ADDRESS",
+        0x31 => "BALANCE",
+        0x32 => "ORIGIN",
+        0x33 => "CALLER",
+        0x34 => "CALLVALUE",
+        0x35 => "CALLDATALOAD",
+        0x36 => "CALLDATASIZE",
+        0x37 => "CALLDATACOPY",
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3a => "GASPRICE",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3d => "RETURNDATASIZE",
+        0x3e => "RETURNDATACOPY",
+        0x3f => "EXTCODEHASH",
+        0x40 => "BLOCKHASH",
+        0x41 => "COINBASE",
+        0x42 => "TIMESTAMP",
+        0x43 => "NUMBER",
+        0x44 => "PREVRANDAO",
+        0x45 => "GASLIMIT",
+        0x46 => "CHAINID",
+        0x47 => "SELFBALANCE",
+        0x48 => "BASEFEE",
+        0x49 => "BLOBHASH",
+        0x4a => "BLOBBASEFEE",
+        0x50 => "POP",
+        0x51 => "MLOAD",
+        0x52 => "MSTORE",
+        0x53 => "MSTORE8",
+        0x54 => "SLOAD",
+        0x55 => "SSTORE",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x59 => "MSIZE",
+        0x5a => "GAS",
+        0x5b => "JUMPDEST",
+        0x5c => "TLOAD",
+        0x5d => "TSTORE",
+        0x5e => "MCOPY",
+        0x5f => "PUSH0",
+        0x60..=0x7f => PUSH[(opcode - 0x60) as usize],
+        0x80..=0x8f => DUP[(opcode - 0x80) as usize],
+        0x90..=0x9f => SWAP[(opcode - 0x90) as usize],
+        0xa0..=0xa4 => LOG[(opcode - 0xa0) as usize],
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf3 => "RETURN",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xfd => "REVERT",
+        0xfe => "INVALID",
+        0xff => "SELFDESTRUCT",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn analyzes_bytecode() {
+        // PUSH1 0x80 PUSH1 0x40 MSTORE DELEGATECALL 0x0c SELFDESTRUCT PUSH2 0xff (truncated)
+        // followed by the metadata hash with "solc" 0.8.14 version only
+        let bytecode = hex::decode("6080604052f40cff61ffa164736f6c634300080e000a").unwrap();
+
+        let expected = BytecodeStats {
+            opcode_histogram: BTreeMap::from([
+                ("0x0c".to_string(), 1),
+                ("DELEGATECALL".to_string(), 1),
+                ("MSTORE".to_string(), 1),
+                ("PUSH1".to_string(), 2),
+                ("PUSH2".to_string(), 1),
+                ("SELFDESTRUCT".to_string(), 1),
+            ]),
+            code_size: 7,
+            push_data_size: 3,
+            metadata_size: 12,
+            // 53000 + 2 zero bytes * 4 + 20 non-zero bytes * 16 + 1 word * 2 + 22 bytes * 200
+            estimated_deployment_gas: 57_730,
+            has_selfdestruct: true,
+            has_delegatecall: true,
+            has_create2: false,
+        };
+        assert_eq!(expected, analyze(&bytecode));
+    }
+
+    #[test]
+    fn analyzes_empty_bytecode() {
+        let expected = BytecodeStats {
+            estimated_deployment_gas: CREATION_TX_COST,
+            ..Default::default()
+        };
+        assert_eq!(expected, analyze(&[]));
+    }
+}
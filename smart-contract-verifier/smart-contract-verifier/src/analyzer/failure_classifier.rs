@@ -0,0 +1,316 @@
+//! Classification of failed verifications into the most common user mistakes.
+//!
+//! Is used to collect statistics of what users struggle with. Buckets of the
+//! [`VerificationError::NoMatchingContracts`] failures are guessed from the reasons
+//! the compiled contracts did not match and from the settings inferred from the bytecode,
+//! so, as the inference itself, the classification is not guaranteed to be correct.
+
+use super::settings_inference::InferredSettings;
+use crate::{VerificationError, VerificationErrorKind};
+use std::{fmt, str::FromStr};
+
+/// Compiled contracts whose bytecode length differs from the remote one by a larger share
+/// are considered to be compiled from other sources rather than with other settings.
+const MAX_LENGTH_DIFFERENCE_SHARE: f64 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FailureBucket {
+    WrongCompilerVersion,
+    OptimizerMismatch,
+    WrongContractSelected,
+    MissingLibraries,
+    ViaIrMismatch,
+    /// The executable code matches, while the metadata appended to it does not
+    MetadataOnlyMismatch,
+    /// The sources cannot be compiled (including missing imports)
+    CompilationError,
+    Other,
+}
+
+impl FailureBucket {
+    pub const ALL: [FailureBucket; 8] = [
+        FailureBucket::WrongCompilerVersion,
+        FailureBucket::OptimizerMismatch,
+        FailureBucket::WrongContractSelected,
+        FailureBucket::MissingLibraries,
+        FailureBucket::ViaIrMismatch,
+        FailureBucket::MetadataOnlyMismatch,
+        FailureBucket::CompilationError,
+        FailureBucket::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureBucket::WrongCompilerVersion => "wrong_compiler_version",
+            FailureBucket::OptimizerMismatch => "optimizer_mismatch",
+            FailureBucket::WrongContractSelected => "wrong_contract_selected",
+            FailureBucket::MissingLibraries => "missing_libraries",
+            FailureBucket::ViaIrMismatch => "via_ir_mismatch",
+            FailureBucket::MetadataOnlyMismatch => "metadata_only_mismatch",
+            FailureBucket::CompilationError => "compilation_error",
+            FailureBucket::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for FailureBucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FailureBucket {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|bucket| bucket.as_str() == s)
+            .ok_or_else(|| format!("unknown failure bucket: {s}"))
+    }
+}
+
+/// Settings of the request, if known
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RequestedSettings {
+    pub compiler_version: Option<semver::Version>,
+    pub optimization_enabled: Option<bool>,
+    pub via_ir: Option<bool>,
+}
+
+/// Returns `None` for the errors caused by invalid requests or by the service itself.
+pub fn classify(
+    err: &VerificationError,
+    inferred: &InferredSettings,
+    requested: &RequestedSettings,
+) -> Option<FailureBucket> {
+    let bucket = match err {
+        VerificationError::CompilerVersionMismatch(_)
+        | VerificationError::IncompatibleCompilerVersion { .. } => {
+            FailureBucket::WrongCompilerVersion
+        }
+        VerificationError::Compilation(_) | VerificationError::MissingSources(_) => {
+            FailureBucket::CompilationError
+        }
+        VerificationError::UnsupportedOpcodes { .. } => FailureBucket::Other,
+        VerificationError::NoMatchingContracts(mismatches) => {
+            classify_mismatches(mismatches, inferred, requested)
+        }
+        VerificationError::Initialization(_)
+        | VerificationError::VersionNotFound(_)
+        | VerificationError::LimitExceeded(_)
+        | VerificationError::Internal(_) => return None,
+    };
+    Some(bucket)
+}
+
+fn classify_mismatches(
+    mismatches: &[VerificationErrorKind],
+    inferred: &InferredSettings,
+    requested: &RequestedSettings,
+) -> FailureBucket {
+    let differs = |inferred: Option<bool>, requested: Option<bool>| matches!((inferred, requested), (Some(inferred), Some(requested)) if inferred != requested);
+
+    if mismatches
+        .iter()
+        .any(|kind| matches!(kind, VerificationErrorKind::LibraryMissed))
+    {
+        return FailureBucket::MissingLibraries;
+    }
+    // Metadata is compared only if the executable code preceding it has matched
+    if mismatches.iter().any(|kind| {
+        matches!(
+            kind,
+            VerificationErrorKind::MetadataParse(_)
+                | VerificationErrorKind::CompilerVersionMismatch(_)
+        )
+    }) {
+        return FailureBucket::MetadataOnlyMismatch;
+    }
+    if differs(inferred.via_ir, requested.via_ir) {
+        return FailureBucket::ViaIrMismatch;
+    }
+    if differs(
+        inferred.optimization_enabled,
+        requested.optimization_enabled,
+    ) {
+        return FailureBucket::OptimizerMismatch;
+    }
+    if let (Some(inferred), Some(requested)) =
+        (&inferred.compiler_version, &requested.compiler_version)
+    {
+        if inferred != requested {
+            return FailureBucket::WrongCompilerVersion;
+        }
+    }
+
+    let length_differences: Vec<_> = mismatches
+        .iter()
+        .filter_map(|kind| match kind {
+            VerificationErrorKind::BytecodeLengthMismatch { raw, .. }
+            | VerificationErrorKind::BytecodeMismatch { raw, .. } => {
+                Some(length_difference_share(raw.expected.len(), raw.found.len()))
+            }
+            _ => None,
+        })
+        .collect();
+    if !length_differences.is_empty()
+        && length_differences
+            .iter()
+            .all(|share| *share > MAX_LENGTH_DIFFERENCE_SHARE)
+    {
+        return FailureBucket::WrongContractSelected;
+    }
+
+    FailureBucket::Other
+}
+
+fn length_difference_share(local: usize, remote: usize) -> f64 {
+    let max = local.max(remote);
+    if max == 0 {
+        return 0.0;
+    }
+    local.abs_diff(remote) as f64 / max as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DisplayBytes;
+    use bytes::Bytes;
+    use mismatch::Mismatch;
+    use pretty_assertions::assert_eq;
+
+    fn bytecode_mismatch(local_length: usize, remote_length: usize) -> VerificationErrorKind {
+        VerificationErrorKind::BytecodeMismatch {
+            part: Mismatch::new(
+                DisplayBytes::from(Bytes::from_static(&[0x01])),
+                DisplayBytes::from(Bytes::from_static(&[0x02])),
+            ),
+            raw: Mismatch::new(
+                DisplayBytes::from(Bytes::from(vec![0x01; local_length])),
+                DisplayBytes::from(Bytes::from(vec![0x02; remote_length])),
+            ),
+        }
+    }
+
+    fn classify_no_match(
+        mismatches: Vec<VerificationErrorKind>,
+        inferred: &InferredSettings,
+        requested: &RequestedSettings,
+    ) -> Option<FailureBucket> {
+        classify(
+            &VerificationError::NoMatchingContracts(mismatches),
+            inferred,
+            requested,
+        )
+    }
+
+    #[test]
+    fn bucket_names_roundtrip() {
+        for bucket in FailureBucket::ALL {
+            assert_eq!(Ok(bucket), FailureBucket::from_str(bucket.as_str()));
+        }
+        assert!(FailureBucket::from_str("unknown").is_err());
+    }
+
+    #[test]
+    fn classify_top_level_errors() {
+        let inferred = InferredSettings::default();
+        let requested = RequestedSettings::default();
+        let classify = |err| classify(&err, &inferred, &requested);
+
+        assert_eq!(
+            Some(FailureBucket::WrongCompilerVersion),
+            classify(VerificationError::CompilerVersionMismatch(Mismatch::new(
+                semver::Version::new(0, 8, 17),
+                semver::Version::new(0, 8, 18),
+            )))
+        );
+        assert_eq!(
+            Some(FailureBucket::CompilationError),
+            classify(VerificationError::Compilation(vec!["ParserError".into()]))
+        );
+        assert_eq!(
+            None,
+            classify(VerificationError::Internal(anyhow::anyhow!("error")))
+        );
+    }
+
+    #[test]
+    fn classify_mismatched_contracts() {
+        let inferred = InferredSettings {
+            compiler_version: Some(semver::Version::new(0, 8, 17)),
+            optimization_enabled: Some(true),
+            min_evm_version: None,
+            via_ir: Some(false),
+        };
+        let requested = RequestedSettings {
+            compiler_version: Some(semver::Version::new(0, 8, 17)),
+            optimization_enabled: Some(true),
+            via_ir: Some(false),
+        };
+
+        assert_eq!(
+            Some(FailureBucket::MissingLibraries),
+            classify_no_match(
+                vec![
+                    bytecode_mismatch(100, 100),
+                    VerificationErrorKind::LibraryMissed
+                ],
+                &inferred,
+                &requested
+            )
+        );
+        assert_eq!(
+            Some(FailureBucket::MetadataOnlyMismatch),
+            classify_no_match(
+                vec![VerificationErrorKind::MetadataParse("invalid cbor".into())],
+                &inferred,
+                &requested
+            )
+        );
+        assert_eq!(
+            Some(FailureBucket::ViaIrMismatch),
+            classify_no_match(
+                vec![bytecode_mismatch(100, 100)],
+                &inferred,
+                &RequestedSettings {
+                    via_ir: Some(true),
+                    ..requested.clone()
+                }
+            )
+        );
+        assert_eq!(
+            Some(FailureBucket::OptimizerMismatch),
+            classify_no_match(
+                vec![bytecode_mismatch(100, 100)],
+                &inferred,
+                &RequestedSettings {
+                    optimization_enabled: Some(false),
+                    ..requested.clone()
+                }
+            )
+        );
+        assert_eq!(
+            Some(FailureBucket::WrongContractSelected),
+            classify_no_match(
+                vec![bytecode_mismatch(50, 100), bytecode_mismatch(300, 100)],
+                &inferred,
+                &requested
+            )
+        );
+        assert_eq!(
+            Some(FailureBucket::Other),
+            classify_no_match(
+                vec![bytecode_mismatch(50, 100), bytecode_mismatch(98, 100)],
+                &inferred,
+                &requested
+            )
+        );
+        assert_eq!(
+            Some(FailureBucket::Other),
+            classify_no_match(vec![], &InferredSettings::default(), &requested)
+        );
+    }
+}
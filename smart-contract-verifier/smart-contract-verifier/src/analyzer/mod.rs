@@ -0,0 +1,8 @@
+//! Stateless analysis of already verified contracts and raw bytecodes.
+
+pub mod abi_diff;
+pub mod bytecode_comparison;
+pub mod bytecode_stats;
+pub mod failure_classifier;
+pub mod settings_inference;
+pub mod token_metadata;
@@ -0,0 +1,243 @@
+//! Heuristic inference of the compilation settings from the deployed bytecode.
+//!
+//! Is used to hint users which settings to try when the verification fails.
+//! None of the inferred values is guaranteed to be correct.
+
+use crate::solidity::metadata;
+use std::fmt::{Display, Formatter};
+
+const STOP: u8 = 0x00;
+const LT: u8 = 0x10;
+const ISZERO: u8 = 0x15;
+const SHL: u8 = 0x1b;
+const SAR: u8 = 0x1d;
+const CALLDATASIZE: u8 = 0x36;
+const CHAINID: u8 = 0x46;
+const SELFBALANCE: u8 = 0x47;
+const BASEFEE: u8 = 0x48;
+const POP: u8 = 0x50;
+const PUSH0: u8 = 0x5f;
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7f;
+const SWAP1: u8 = 0x90;
+
+/// Minimal number of instructions required to make a conclusion about the optimizer.
+const MIN_INSTRUCTIONS: usize = 100;
+/// Shares of stack cleanup instructions (`POP`) among all the instructions.
+/// Unoptimized code is full of values pushed only to be removed later,
+/// while the optimizer eliminates most of them. Thresholds are chosen empirically.
+const OPTIMIZED_MAX_POP_SHARE: f64 = 0.05;
+const UNOPTIMIZED_MIN_POP_SHARE: f64 = 0.08;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InferredSettings {
+    /// Compiler version from the metadata hash, if the latter is appended to the bytecode
+    pub compiler_version: Option<semver::Version>,
+    /// `None` if no conclusion could be made
+    pub optimization_enabled: Option<bool>,
+    /// The oldest EVM version supporting all the opcodes used by the bytecode,
+    /// if some of the opcodes have been introduced after `byzantium`
+    pub min_evm_version: Option<&'static str>,
+    /// Whether the code has been generated via the IR pipeline (`viaIR`).
+    /// `None` if no conclusion could be made
+    pub via_ir: Option<bool>,
+}
+
+impl InferredSettings {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Returns the hint to flip `viaIR` setting, if the bytecode
+    /// looks like the output of the other code generation pipeline.
+    pub fn via_ir_hint(&self, requested_via_ir: bool) -> Option<&'static str> {
+        match self.via_ir {
+            Some(true) if !requested_via_ir => {
+                Some("compiled code looks like via-ir output, retry with viaIR=true")
+            }
+            Some(false) if requested_via_ir => {
+                Some("compiled code looks like legacy codegen output, retry with viaIR=false")
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for InferredSettings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut settings = Vec::new();
+        if let Some(version) = &self.compiler_version {
+            settings.push(format!("compiler version {version}"));
+        }
+        match self.optimization_enabled {
+            Some(true) => settings.push("optimization enabled".to_string()),
+            Some(false) => settings.push("optimization disabled".to_string()),
+            None => {}
+        }
+        if let Some(evm_version) = self.min_evm_version {
+            settings.push(format!("evm version {evm_version} or newer"));
+        }
+        match self.via_ir {
+            Some(true) => settings.push("via-ir code generation".to_string()),
+            Some(false) => settings.push("legacy code generation".to_string()),
+            None => {}
+        }
+        write!(f, "likely settings: {}", settings.join(", "))
+    }
+}
+
+pub fn infer_settings(deployed_bytecode: &[u8]) -> InferredSettings {
+    let (code, metadata) = metadata::split(deployed_bytecode);
+    let instructions = instructions(code);
+    let opcodes: Vec<_> = instructions.iter().map(|(opcode, _)| *opcode).collect();
+
+    let min_evm_version = if opcodes.contains(&PUSH0) {
+        Some("shanghai")
+    } else if opcodes.contains(&BASEFEE) {
+        Some("london")
+    } else if opcodes.contains(&CHAINID) || opcodes.contains(&SELFBALANCE) {
+        Some("istanbul")
+    } else if opcodes.iter().any(|opcode| (SHL..=SAR).contains(opcode)) {
+        Some("constantinople")
+    } else {
+        None
+    };
+
+    InferredSettings {
+        compiler_version: metadata.and_then(|metadata| metadata.solc),
+        optimization_enabled: optimization_enabled(&opcodes),
+        min_evm_version,
+        via_ir: via_ir(&instructions),
+    }
+}
+
+fn optimization_enabled(opcodes: &[u8]) -> Option<bool> {
+    if opcodes.len() < MIN_INSTRUCTIONS {
+        return None;
+    }
+    let pops = opcodes.iter().filter(|&&opcode| opcode == POP).count();
+    // `SWAP1 POP` is emitted by the legacy code generator to drop the variables
+    // going out of scope and is almost always removed by the optimizer
+    let swap_pops = opcodes
+        .windows(2)
+        .filter(|window| *window == [SWAP1, POP])
+        .count();
+    let pop_share = (pops + swap_pops) as f64 / opcodes.len() as f64;
+    if pop_share <= OPTIMIZED_MAX_POP_SHARE {
+        Some(true)
+    } else if pop_share >= UNOPTIMIZED_MIN_POP_SHARE {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Both code generators start the function dispatcher by checking that the calldata
+/// is long enough to contain a selector. The legacy one jumps to the fallback if it is not
+/// (`PUSH1 0x04 CALLDATASIZE LT PUSH2 tag JUMPI`), while the IR one enters the dispatcher
+/// if it is (`if iszero(lt(calldatasize(), 4))`, i.e. `PUSH1 0x04 CALLDATASIZE LT ISZERO`).
+fn via_ir(instructions: &[(u8, &[u8])]) -> Option<bool> {
+    instructions.windows(4).find_map(|window| match window {
+        [(PUSH1, [0x04]), (CALLDATASIZE, _), (LT, _), (next, _)] => Some(*next == ISZERO),
+        _ => None,
+    })
+}
+
+/// Returns the instructions as opcodes with their push data (empty for non-push ones).
+fn instructions(code: &[u8]) -> Vec<(u8, &[u8])> {
+    let mut instructions = Vec::with_capacity(code.len());
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        pc += 1;
+        let mut data: &[u8] = &[];
+        if (PUSH1..=PUSH32).contains(&opcode) {
+            let end = (pc + (opcode - PUSH1) as usize + 1).min(code.len());
+            data = &code[pc..end];
+            pc = end;
+        }
+        instructions.push((opcode, data));
+    }
+    // Trailing zeros are padding rather than actual instructions
+    while instructions.last().map(|(opcode, _)| *opcode) == Some(STOP) {
+        instructions.pop();
+    }
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn infer_from_metadata_and_opcodes() {
+        // {"ipfs": h'1220EB23CE2C13EA8739368F952F6C6A4B1F0623D147D2A19B6D4D26A61AB03FCD3E', "solc": 0.8.14}
+        let metadata = hex::decode("a2646970667358221220eb23ce2c13ea8739368f952f6c6a4b1f0623d147d2a19b6d4d26a61ab03fcd3e64736f6c634300080e0033").unwrap();
+        // PUSH0 CALLDATALOAD PUSH1 0xe0 SHR PUSH1 0x01 ADD
+        let code = hex::decode("5f3560e01c60010100").unwrap();
+
+        let inferred = infer_settings(&[code, metadata].concat());
+        let expected = InferredSettings {
+            compiler_version: Some(semver::Version::new(0, 8, 14)),
+            optimization_enabled: None,
+            min_evm_version: Some("shanghai"),
+            via_ir: None,
+        };
+        assert_eq!(expected, inferred);
+        assert_eq!(
+            "likely settings: compiler version 0.8.14, evm version shanghai or newer",
+            inferred.to_string()
+        );
+    }
+
+    #[test]
+    fn infer_optimization() {
+        // PUSH1 0x01 ADD
+        let arithmetic = [0x60, 0x01, 0x01];
+        // PUSH1 0x01 SWAP1 POP POP
+        let cleanup = [0x60, 0x01, 0x90, 0x50, 0x50];
+
+        let optimized = arithmetic.repeat(100);
+        assert_eq!(Some(true), infer_settings(&optimized).optimization_enabled);
+
+        let unoptimized = [arithmetic.repeat(50), cleanup.repeat(50)].concat();
+        assert_eq!(
+            Some(false),
+            infer_settings(&unoptimized).optimization_enabled
+        );
+
+        assert_eq!(None, infer_settings(&arithmetic).optimization_enabled);
+        assert!(infer_settings(&arithmetic).is_empty());
+    }
+
+    #[test]
+    fn infer_via_ir() {
+        // PUSH1 0x80 PUSH1 0x40 MSTORE PUSH1 0x04 CALLDATASIZE LT PUSH2 0x0010 JUMPI
+        let legacy = hex::decode("60806040526004361061001057").unwrap();
+        // PUSH1 0x80 PUSH1 0x40 MSTORE PUSH1 0x04 CALLDATASIZE LT ISZERO PUSH2 0x0011 JUMPI
+        let via_ir = hex::decode("6080604052600436101561001157").unwrap();
+
+        let legacy = infer_settings(&legacy);
+        assert_eq!(Some(false), legacy.via_ir);
+        assert_eq!(None, legacy.via_ir_hint(false));
+        assert_eq!(
+            Some("compiled code looks like legacy codegen output, retry with viaIR=false"),
+            legacy.via_ir_hint(true)
+        );
+
+        let via_ir = infer_settings(&via_ir);
+        assert_eq!(Some(true), via_ir.via_ir);
+        assert_eq!(None, via_ir.via_ir_hint(true));
+        assert_eq!(
+            Some("compiled code looks like via-ir output, retry with viaIR=true"),
+            via_ir.via_ir_hint(false)
+        );
+
+        // PUSH1 0x80 PUSH1 0x40 MSTORE
+        assert_eq!(
+            None,
+            infer_settings(&hex::decode("6080604052").unwrap()).via_ir
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use crate::standards::{detect_standards, Standard};
+use bytes::Bytes;
+use ethabi::{Contract, Token};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("contract is not an ERC-20 or ERC-721 token")]
+    NotToken,
+    #[error("invalid constructor arguments: {0}")]
+    InvalidConstructorArguments(ethabi::Error),
+    #[error("invalid value of immutable `{0}`")]
+    InvalidImmutable(String),
+}
+
+/// Token metadata as returned by the contract getters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<String>,
+}
+
+impl TokenMetadata {
+    fn get(&self, field: &str) -> Option<&String> {
+        match field {
+            "name" => self.name.as_ref(),
+            "symbol" => self.symbol.as_ref(),
+            "decimals" => self.decimals.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueSource {
+    ConstructorArguments,
+    Immutables,
+}
+
+/// Value of the token metadata field which does not correspond
+/// to the value the contract was deployed with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub field: String,
+    pub source: ValueSource,
+    /// The value extracted from the constructor arguments or immutables
+    pub expected: String,
+    /// The value returned by the getter
+    pub actual: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckRequest {
+    pub abi: Contract,
+    /// Encoded constructor arguments the contract was deployed with
+    pub constructor_args: Option<Bytes>,
+    /// Map from the immutable variable name to its 32-bytes value
+    pub immutables: BTreeMap<String, Bytes>,
+    pub metadata: TokenMetadata,
+}
+
+const FIELDS: [&str; 3] = ["name", "symbol", "decimals"];
+
+/// Cross-checks token metadata returned by the `name`, `symbol`, and `decimals` getters
+/// against the values passed into the constructor or stored as immutables.
+///
+/// Only fields which getters are declared in the abi are checked.
+/// Constructor parameters and immutables are matched with the fields by their names
+/// (e.g., `name`, `_name`, and `tokenName` all correspond to the `name` field).
+pub fn check_token_metadata(request: &CheckRequest) -> Result<Vec<Discrepancy>, Error> {
+    let standards = detect_standards(&request.abi, &[]);
+    if !standards.contains(&Standard::Erc20) && !standards.contains(&Standard::Erc721) {
+        return Err(Error::NotToken);
+    }
+
+    let constructor_values = match (request.abi.constructor(), &request.constructor_args) {
+        (Some(constructor), Some(args)) if !constructor.inputs.is_empty() => {
+            let kinds: Vec<_> = constructor.inputs.iter().map(|p| p.kind.clone()).collect();
+            let tokens =
+                ethabi::decode(&kinds, args).map_err(Error::InvalidConstructorArguments)?;
+            constructor
+                .inputs
+                .iter()
+                .zip(tokens)
+                .map(|(param, token)| (param.name.clone(), token))
+                .collect()
+        }
+        _ => vec![],
+    };
+
+    let mut discrepancies = Vec::new();
+    for field in FIELDS {
+        let getter = match request
+            .abi
+            .functions_by_name(field)
+            .ok()
+            .and_then(|functions| {
+                functions
+                    .iter()
+                    .find(|f| f.inputs.is_empty() && f.outputs.len() == 1)
+            }) {
+            Some(getter) => getter,
+            None => continue,
+        };
+        let actual = match request.metadata.get(field) {
+            Some(actual) => actual,
+            None => continue,
+        };
+
+        let from_constructor = constructor_values
+            .iter()
+            .find(|(name, _)| corresponds_to(name, field))
+            .map(|(_, token)| (ValueSource::ConstructorArguments, token_to_string(token)));
+        let from_immutables = match request
+            .immutables
+            .iter()
+            .find(|(name, _)| corresponds_to(name, field))
+        {
+            Some((name, value)) => {
+                let tokens = ethabi::decode(&[getter.outputs[0].kind.clone()], value)
+                    .map_err(|_| Error::InvalidImmutable(name.clone()))?;
+                Some((ValueSource::Immutables, token_to_string(&tokens[0])))
+            }
+            None => None,
+        };
+
+        for (source, expected) in from_constructor.into_iter().chain(from_immutables) {
+            if &expected != actual {
+                discrepancies.push(Discrepancy {
+                    field: field.to_string(),
+                    source,
+                    expected,
+                    actual: actual.clone(),
+                })
+            }
+        }
+    }
+
+    Ok(discrepancies)
+}
+
+fn corresponds_to(name: &str, field: &str) -> bool {
+    let name = name.trim_matches('_').to_lowercase();
+    name == field || name.strip_prefix("token") == Some(field)
+}
+
+fn token_to_string(token: &Token) -> String {
+    match token {
+        Token::String(value) => value.clone(),
+        Token::Uint(value) | Token::Int(value) => value.to_string(),
+        // Some tokens (e.g. MKR) store their names as bytes32
+        Token::FixedBytes(value) => String::from_utf8_lossy(value)
+            .trim_end_matches('\0')
+            .to_string(),
+        token => token.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const ERC20_ABI: &str = r#"[
+        {"type":"constructor","inputs":[{"name":"name_","type":"string"},{"name":"symbol_","type":"string"}],"stateMutability":"nonpayable"},
+        {"type":"function","name":"name","inputs":[],"outputs":[{"name":"","type":"string"}],"stateMutability":"view"},
+        {"type":"function","name":"symbol","inputs":[],"outputs":[{"name":"","type":"string"}],"stateMutability":"view"},
+        {"type":"function","name":"decimals","inputs":[],"outputs":[{"name":"","type":"uint8"}],"stateMutability":"view"},
+        {"type":"function","name":"totalSupply","inputs":[],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+        {"type":"function","name":"balanceOf","inputs":[{"name":"","type":"address"}],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+        {"type":"function","name":"transfer","inputs":[{"name":"","type":"address"},{"name":"","type":"uint256"}],"outputs":[{"name":"","type":"bool"}],"stateMutability":"nonpayable"},
+        {"type":"function","name":"transferFrom","inputs":[{"name":"","type":"address"},{"name":"","type":"address"},{"name":"","type":"uint256"}],"outputs":[{"name":"","type":"bool"}],"stateMutability":"nonpayable"},
+        {"type":"function","name":"approve","inputs":[{"name":"","type":"address"},{"name":"","type":"uint256"}],"outputs":[{"name":"","type":"bool"}],"stateMutability":"nonpayable"},
+        {"type":"function","name":"allowance","inputs":[{"name":"","type":"address"},{"name":"","type":"address"}],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"}
+    ]"#;
+
+    fn check(
+        metadata: TokenMetadata,
+        immutables: BTreeMap<String, Bytes>,
+    ) -> Result<Vec<Discrepancy>, Error> {
+        let abi: Contract = serde_json::from_str(ERC20_ABI).unwrap();
+        let constructor_args =
+            ethabi::encode(&[Token::String("Token".into()), Token::String("TKN".into())]);
+        check_token_metadata(&CheckRequest {
+            abi,
+            constructor_args: Some(constructor_args.into()),
+            immutables,
+            metadata,
+        })
+    }
+
+    #[test]
+    fn matching_metadata() {
+        let metadata = TokenMetadata {
+            name: Some("Token".into()),
+            symbol: Some("TKN".into()),
+            decimals: Some("18".into()),
+        };
+        let immutables = BTreeMap::from([(
+            "_decimals".to_string(),
+            Bytes::from(ethabi::encode(&[Token::Uint(18.into())])),
+        )]);
+        assert_eq!(
+            Vec::<Discrepancy>::new(),
+            check(metadata, immutables).unwrap()
+        );
+    }
+
+    #[test]
+    fn spoofed_metadata() {
+        let metadata = TokenMetadata {
+            name: Some("Tether USD".into()),
+            symbol: Some("TKN".into()),
+            decimals: Some("6".into()),
+        };
+        let immutables = BTreeMap::from([(
+            "_decimals".to_string(),
+            Bytes::from(ethabi::encode(&[Token::Uint(18.into())])),
+        )]);
+        let expected = vec![
+            Discrepancy {
+                field: "name".into(),
+                source: ValueSource::ConstructorArguments,
+                expected: "Token".into(),
+                actual: "Tether USD".into(),
+            },
+            Discrepancy {
+                field: "decimals".into(),
+                source: ValueSource::Immutables,
+                expected: "18".into(),
+                actual: "6".into(),
+            },
+        ];
+        assert_eq!(expected, check(metadata, immutables).unwrap());
+    }
+
+    #[test]
+    fn not_token() {
+        let abi: Contract = serde_json::from_str("[]").unwrap();
+        let result = check_token_metadata(&CheckRequest {
+            abi,
+            constructor_args: None,
+            immutables: BTreeMap::new(),
+            metadata: TokenMetadata::default(),
+        });
+        assert!(matches!(result, Err(Error::NotToken)), "{result:?}");
+    }
+}
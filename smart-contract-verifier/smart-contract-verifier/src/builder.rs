@@ -0,0 +1,292 @@
+//! Verification API for the embedders (custom explorers, CI tools, etc.),
+//! which use the verification logic directly rather than via the service.
+//!
+//! ```no_run
+//! # async fn example(request: smart_contract_verifier::solidity::standard_json::VerificationRequest) -> anyhow::Result<()> {
+//! use smart_contract_verifier::VerifierBuilder;
+//!
+//! let verifier = VerifierBuilder::new("/tmp/solidity-compilers").build().await?;
+//! match verifier.verify_solidity(request).await {
+//!     Ok(success) => println!("verified {}", success.contract_name),
+//!     Err(err) => println!("not verified: {err}"),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    compiler::{CompilationLimits, Compilers, Downloader, Fetcher, ListFetcher, SourcesDirs},
+    consts::DEFAULT_SOLIDITY_COMPILER_LIST,
+    solidity::{
+        backend::SolcBackend, multi_part, standard_json, Client as SolidityClient, PackageMirror,
+        SolcValidator, SolidityCompiler, DEFAULT_MAX_PARALLEL_COMPILATIONS_PER_REQUEST,
+    },
+    verifier::{Error, Success},
+};
+use cron::Schedule;
+use std::{num::NonZeroUsize, path::PathBuf, sync::Arc};
+use tokio::sync::Semaphore;
+use url::Url;
+
+/// Configures the compilers used by [`Verifier`].
+///
+/// By default, the compilers are downloaded from the list of the solc releases
+/// ([`DEFAULT_SOLIDITY_COMPILER_LIST`]) into the cache directory, where the compilers
+/// downloaded by the previous runs are reused from.
+pub struct VerifierBuilder {
+    compilers_dir: PathBuf,
+    fetcher: Option<Arc<dyn Fetcher>>,
+    list_url: Option<Url>,
+    refresh_versions_schedule: Option<Schedule>,
+    downloader: Downloader,
+    backend: Option<Box<dyn SolcBackend>>,
+    sources_dirs: SourcesDirs,
+    limits: CompilationLimits,
+    max_threads: NonZeroUsize,
+    max_parallel_compilations: NonZeroUsize,
+    compilation_pool: Option<Arc<Semaphore>>,
+    package_mirror: Option<PackageMirror>,
+}
+
+impl VerifierBuilder {
+    /// Compiler binaries are cached in the `compilers_dir`.
+    pub fn new(compilers_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            compilers_dir: compilers_dir.into(),
+            fetcher: None,
+            list_url: None,
+            refresh_versions_schedule: None,
+            downloader: Downloader::default(),
+            backend: None,
+            sources_dirs: Default::default(),
+            limits: Default::default(),
+            max_threads: std::thread::available_parallelism()
+                .unwrap_or_else(|_| NonZeroUsize::new(8).unwrap()),
+            max_parallel_compilations: NonZeroUsize::new(
+                DEFAULT_MAX_PARALLEL_COMPILATIONS_PER_REQUEST,
+            )
+            .unwrap(),
+            compilation_pool: None,
+            package_mirror: None,
+        }
+    }
+
+    /// Sets the fetcher the compilers are obtained with (e.g., [`S3Fetcher`] or
+    /// [`MockFetcher`]). Overrides the compilers list url and its refresh schedule.
+    ///
+    /// [`S3Fetcher`]: crate::S3Fetcher
+    /// [`MockFetcher`]: crate::MockFetcher
+    pub fn with_fetcher(mut self, fetcher: Arc<dyn Fetcher>) -> Self {
+        self.fetcher = Some(fetcher);
+        self
+    }
+
+    /// Sets the list of the compiler releases the compilers are downloaded from.
+    pub fn with_list_url(mut self, list_url: Url) -> Self {
+        self.list_url = Some(list_url);
+        self
+    }
+
+    /// Refreshes the list of the compiler releases by the schedule.
+    /// The list is fetched once on [`build`](Self::build) otherwise.
+    pub fn with_refresh_versions_schedule(mut self, schedule: Schedule) -> Self {
+        self.refresh_versions_schedule = Some(schedule);
+        self
+    }
+
+    /// Sets the downloader of the compilers listed (e.g., to change the number of parallel downloads).
+    pub fn with_downloader(mut self, downloader: Downloader) -> Self {
+        self.downloader = downloader;
+        self
+    }
+
+    /// Sets the backend the compilers are run with (see [`SolcBackendKind`](crate::SolcBackendKind)).
+    pub fn with_backend(mut self, backend: Box<dyn SolcBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Sets the directories the sources are written to for the compilers reading them from disk.
+    pub fn with_sources_dirs(mut self, sources_dirs: SourcesDirs) -> Self {
+        self.sources_dirs = sources_dirs;
+        self
+    }
+
+    /// Sets the limits the compiler inputs and outputs are checked against.
+    pub fn with_limits(mut self, limits: CompilationLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets the maximum number of compilations run concurrently
+    /// (the number of CPU cores by default).
+    pub fn with_max_threads(mut self, max_threads: NonZeroUsize) -> Self {
+        self.max_threads = max_threads;
+        self
+    }
+
+    /// Sets the maximum number of compilations a single verification may run concurrently.
+    pub fn with_max_parallel_compilations(
+        mut self,
+        max_parallel_compilations: NonZeroUsize,
+    ) -> Self {
+        self.max_parallel_compilations = max_parallel_compilations;
+        self
+    }
+
+    /// Sets the pool of compilation permits all verifications acquire
+    /// (see [`SolidityClient::with_compilation_pool`]).
+    pub fn with_compilation_pool(mut self, compilation_pool: Arc<Semaphore>) -> Self {
+        self.compilation_pool = Some(compilation_pool);
+        self
+    }
+
+    /// Sets the mirror well-known dependencies missing from the sources are fetched from.
+    pub fn with_package_mirror(mut self, package_mirror: PackageMirror) -> Self {
+        self.package_mirror = Some(package_mirror);
+        self
+    }
+
+    /// Fetches the list of the compiler releases (unless the fetcher is set)
+    /// and loads the compilers cached in the directory.
+    pub async fn build(self) -> anyhow::Result<Verifier> {
+        let fetcher: Arc<dyn Fetcher> = match self.fetcher {
+            Some(fetcher) => fetcher,
+            None => {
+                let list_url = match self.list_url {
+                    Some(list_url) => list_url,
+                    None => Url::parse(DEFAULT_SOLIDITY_COMPILER_LIST)?,
+                };
+                Arc::new(
+                    ListFetcher::new(
+                        list_url,
+                        self.compilers_dir.clone(),
+                        self.refresh_versions_schedule,
+                        Some(Arc::new(SolcValidator::default())),
+                    )
+                    .await?
+                    .with_downloader(self.downloader),
+                )
+            }
+        };
+
+        let mut compiler = SolidityCompiler::new().with_sources_dirs(self.sources_dirs);
+        if let Some(backend) = self.backend {
+            compiler = compiler.with_backend(backend);
+        }
+        let compilers = Compilers::new(
+            fetcher,
+            compiler,
+            Arc::new(Semaphore::new(self.max_threads.get())),
+        )
+        .with_limits(self.limits);
+        compilers.load_from_dir(&self.compilers_dir).await;
+
+        let mut client = SolidityClient::new(compilers)
+            .with_max_parallel_compilations(self.max_parallel_compilations);
+        if let Some(compilation_pool) = self.compilation_pool {
+            client = client.with_compilation_pool(compilation_pool);
+        }
+        if let Some(package_mirror) = self.package_mirror {
+            client = client.with_package_mirror(package_mirror);
+        }
+
+        Ok(Verifier {
+            solidity_client: Arc::new(client),
+        })
+    }
+}
+
+/// Verifies the contracts with the compilers configured by [`VerifierBuilder`].
+/// Is cheap to share between the tasks, as the compilers are shared as well.
+#[derive(Clone)]
+pub struct Verifier {
+    solidity_client: Arc<SolidityClient>,
+}
+
+impl Verifier {
+    /// Verifies the contract compiled from the standard json input.
+    pub async fn verify_solidity(
+        &self,
+        request: standard_json::VerificationRequest,
+    ) -> Result<Success, Error> {
+        standard_json::verify(self.solidity_client.clone(), request).await
+    }
+
+    /// Verifies the contract compiled from the source files and the basic settings.
+    pub async fn verify_solidity_multi_part(
+        &self,
+        request: multi_part::VerificationRequest,
+    ) -> Result<Success, Error> {
+        multi_part::verify(self.solidity_client.clone(), request).await
+    }
+
+    /// Client the rest of the [`solidity`](crate::solidity) functions may be called with
+    /// (e.g., compilation without verification).
+    pub fn solidity_client(&self) -> Arc<SolidityClient> {
+        self.solidity_client.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compiler::{MockCompiler, MockFetcher, Version},
+        solidity::standard_json::StandardJsonContent,
+    };
+    use bytes::Bytes;
+    use ethers_solc::{
+        artifacts::{Source, Sources},
+        CompilerInput,
+    };
+    use std::str::FromStr;
+
+    fn request(compiler_version: &str) -> standard_json::VerificationRequest {
+        standard_json::VerificationRequest {
+            deployed_bytecode: Bytes::from_static(&[0x60, 0x80]),
+            creation_bytecode: None,
+            compiler_version: Version::from_str(compiler_version).unwrap(),
+            auto_select_compiler_version: false,
+            bytecode_mask: vec![],
+            eof_enabled: false,
+            evm_fork: None,
+            normalization: Default::default(),
+            trace: None,
+            retry_with_flipped_via_ir: false,
+            content: StandardJsonContent {
+                input: CompilerInput {
+                    language: "Solidity".to_string(),
+                    sources: Sources::from([("A.sol".into(), Source::new("contract A {}"))]),
+                    settings: Default::default(),
+                },
+                extra_outputs: false,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn verifier_uses_configured_compilers() {
+        let compilers_dir = tempfile::tempdir().unwrap();
+        let version = Version::from_str("v0.8.19+commit.7dd6d404").unwrap();
+        let verifier = VerifierBuilder::new(compilers_dir.path())
+            .with_fetcher(Arc::new(MockFetcher::new(vec![version])))
+            .with_backend(Box::new(MockCompiler::new()))
+            .build()
+            .await
+            .expect("verifier should be built");
+
+        let err = verifier
+            .verify_solidity(request("v0.8.18+commit.87f61d96"))
+            .await
+            .expect_err("unknown version should not be verified");
+        assert!(matches!(err, Error::VersionNotFound(_)), "{err:?}");
+
+        // The mock compiler has no outputs, so the compilation fails
+        let err = verifier
+            .verify_solidity(request("v0.8.19+commit.7dd6d404"))
+            .await
+            .expect_err("compilation should fail");
+        assert!(err.to_string().contains("mock compiler"), "{err:?}");
+    }
+}
@@ -0,0 +1,218 @@
+//! Registry of the known chains with their EVM fork schedules and explorer metadata.
+//!
+//! The registry of well-known chains is embedded into the binary, and may be extended
+//! or overridden by the service configuration. The fork schedule of the chain defines
+//! the EVM version the compiled bytecodes are validated against, while chain-specific
+//! normalization passes are applied in addition to the globally configured ones.
+
+use crate::{EvmFork, Normalization};
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr};
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+
+const EMBEDDED_CHAINS: &str = include_str!("chains.json");
+
+/// Moment the fork is activated at
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Activation {
+    Block(u64),
+    /// Unix timestamp (in seconds)
+    Timestamp(u64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForkActivation {
+    pub fork: EvmFork,
+    pub activation: Activation,
+}
+
+#[derive(Clone, Debug)]
+pub struct Chain {
+    pub id: u64,
+    pub name: String,
+    pub explorer_url: Option<Url>,
+    /// Sorted by the forks
+    pub forks: Vec<ForkActivation>,
+    /// Passes applied to the bytecodes of the chain in addition to the global ones
+    pub normalization: Normalization,
+}
+
+impl Chain {
+    pub fn new(id: u64, name: impl Into<String>) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            explorer_url: None,
+            forks: vec![],
+            normalization: Normalization::new(),
+        }
+    }
+
+    pub fn with_explorer_url(mut self, explorer_url: Option<Url>) -> Self {
+        self.explorer_url = explorer_url;
+        self
+    }
+
+    pub fn with_forks(mut self, forks: impl IntoIterator<Item = ForkActivation>) -> Self {
+        self.forks = forks.into_iter().collect();
+        self.forks.sort_by_key(|activation| activation.fork);
+        self
+    }
+
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// The latest fork activated at the `timestamp`. The block heights of the chain
+    /// are not tracked, so the forks activated by blocks are considered already active.
+    /// Returns `None` if the schedule is empty.
+    pub fn evm_fork_at(&self, timestamp: u64) -> Option<EvmFork> {
+        self.forks
+            .iter()
+            .filter(|activation| match activation.activation {
+                Activation::Block(_) => true,
+                Activation::Timestamp(activated_at) => activated_at <= timestamp,
+            })
+            .map(|activation| activation.fork)
+            .max()
+    }
+
+    /// The latest fork activated at the moment
+    pub fn current_evm_fork(&self) -> Option<EvmFork> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+        self.evm_fork_at(now)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ChainRegistry {
+    chains: BTreeMap<u64, Arc<Chain>>,
+}
+
+impl ChainRegistry {
+    /// Registry without any chains
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry of the well-known chains embedded into the binary
+    pub fn embedded() -> Self {
+        let chains: Vec<ChainData> =
+            serde_json::from_str(EMBEDDED_CHAINS).expect("embedded chains are invalid");
+        chains
+            .into_iter()
+            .map(|chain| Chain::try_from(chain).expect("embedded chains are invalid"))
+            .fold(Self::new(), Self::with_chain)
+    }
+
+    /// Adds the chain replacing the one with the same id, if any
+    pub fn with_chain(mut self, chain: Chain) -> Self {
+        self.chains.insert(chain.id, Arc::new(chain));
+        self
+    }
+
+    pub fn get(&self, id: u64) -> Option<Arc<Chain>> {
+        self.chains.get(&id).cloned()
+    }
+
+    /// Chains sorted by their ids
+    pub fn chains(&self) -> impl Iterator<Item = &Chain> {
+        self.chains.values().map(Arc::as_ref)
+    }
+}
+
+#[derive(Deserialize)]
+struct ChainData {
+    id: u64,
+    name: String,
+    explorer_url: Option<Url>,
+    forks: Vec<ForkActivationData>,
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+struct ForkActivationData {
+    #[serde_as(as = "DisplayFromStr")]
+    fork: EvmFork,
+    block: Option<u64>,
+    timestamp: Option<u64>,
+}
+
+impl TryFrom<ChainData> for Chain {
+    type Error = String;
+
+    fn try_from(data: ChainData) -> Result<Self, Self::Error> {
+        let forks = data
+            .forks
+            .into_iter()
+            .map(|fork| {
+                let activation = match (fork.block, fork.timestamp) {
+                    (Some(block), None) => Activation::Block(block),
+                    (None, Some(timestamp)) => Activation::Timestamp(timestamp),
+                    _ => {
+                        return Err(format!(
+                            "chain {}: exactly one of `block` or `timestamp` should be set for {}",
+                            data.id, fork.fork
+                        ))
+                    }
+                };
+                Ok(ForkActivation {
+                    fork: fork.fork,
+                    activation,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Chain::new(data.id, data.name)
+            .with_explorer_url(data.explorer_url)
+            .with_forks(forks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn embedded_registry() {
+        let registry = ChainRegistry::embedded();
+        let ethereum = registry.get(1).expect("ethereum is missing");
+        assert_eq!("Ethereum", ethereum.name);
+        assert_eq!(
+            Some(EvmFork::Paris),
+            ethereum.evm_fork_at(1681338454),
+            "shanghai is activated by the timestamp"
+        );
+        assert_eq!(Some(EvmFork::Shanghai), ethereum.evm_fork_at(1681338455));
+
+        for chain in registry.chains() {
+            assert!(
+                chain.current_evm_fork().is_some(),
+                "chain {} has no activated forks",
+                chain.id
+            );
+        }
+    }
+
+    #[test]
+    fn override_chains() {
+        let registry = ChainRegistry::embedded().with_chain(Chain::new(1, "Custom").with_forks([
+            ForkActivation {
+                fork: EvmFork::London,
+                activation: Activation::Block(0),
+            },
+        ]));
+        let chain = registry.get(1).unwrap();
+        assert_eq!("Custom", chain.name);
+        assert_eq!(Some(EvmFork::London), chain.current_evm_fork());
+        assert_eq!(None, Chain::new(5, "Empty").current_evm_fork());
+    }
+}
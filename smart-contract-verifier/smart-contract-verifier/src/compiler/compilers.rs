@@ -1,14 +1,21 @@
 use super::{
-    download_cache::DownloadCache,
+    download_cache::{CachedCompiler, DownloadCache},
     fetcher::{FetchError, Fetcher},
+    in_flight::{InFlight, Join},
+    limits::{CompilationLimits, LimitExceeded},
+    usage::{self, CompilationUsage},
     version::Version,
 };
 use crate::metrics::{self, GuardedGauge};
 use ethers_solc::{artifacts::Severity, error::SolcError, CompilerInput, CompilerOutput};
+use primitive_types::H256;
+use sha2::{Digest, Sha256};
 use std::{
+    collections::HashMap,
     fmt::Debug,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Instant,
 };
 use thiserror::Error;
 use tokio::sync::{AcquireError, Semaphore};
@@ -26,6 +33,27 @@ pub enum Error {
     Compilation(Vec<String>),
     #[error("failed to acquire lock: {0}")]
     Acquire(#[from] AcquireError),
+    #[error("{0}")]
+    LimitExceeded(#[from] LimitExceeded),
+}
+
+/// Compilations are coalesced by the compiler version and the hash of the json encoded input.
+/// Sources of the input are ordered by their names, so the hash does not depend
+/// on the order the sources have been submitted in.
+type CompilationKey = (Version, H256);
+
+type CompilationResult = Arc<Result<CompilerOutput, Error>>;
+
+/// Sha256 of the json encoded compiler input.
+pub fn input_hash(input: &CompilerInput) -> Result<H256, SolcError> {
+    let input = serde_json::to_vec(input)?;
+    Ok(H256::from_slice(&Sha256::digest(input)))
+}
+
+/// Number of compilations waiting for a free compilation thread. As compilation threads
+/// are shared by all [`Compilers`] of the process, includes the compilations of all of them.
+pub fn compilations_in_queue() -> usize {
+    metrics::COMPILATIONS_IN_QUEUE.get() as usize
 }
 
 #[async_trait::async_trait]
@@ -43,6 +71,11 @@ pub struct Compilers<C> {
     fetcher: Arc<dyn Fetcher>,
     evm_compiler: C,
     threads_semaphore: Arc<Semaphore>,
+    limits: CompilationLimits,
+    /// sha256 hashes of the compiler binaries which have already been calculated
+    hashes: parking_lot::Mutex<HashMap<Version, H256>>,
+    /// Compilations in progress, so that identical concurrent requests are compiled once
+    in_flight: InFlight<CompilationKey, CompilationResult>,
 }
 
 impl<C> Compilers<C>
@@ -59,13 +92,47 @@ where
             fetcher,
             evm_compiler,
             threads_semaphore,
+            limits: Default::default(),
+            hashes: Default::default(),
+            in_flight: Default::default(),
         }
     }
+
+    /// Sets the limits the compiler inputs and outputs are checked against.
+    pub fn with_limits(mut self, limits: CompilationLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Compiles the input. Requests with the same compiler version and input arriving
+    /// while the compilation is in progress wait for it and get the same result.
     #[instrument(name = "download_and_compile", skip(self, input), level = "debug")]
     pub async fn compile(
         &self,
         compiler_version: &Version,
         input: &CompilerInput,
+    ) -> Result<CompilerOutput, Error> {
+        self.limits.check_input(input)?;
+        let key = (compiler_version.clone(), input_hash(input)?);
+
+        let result = match self.in_flight.join(&key).await {
+            Join::Leader(leader) => {
+                let result = Arc::new(self.compile_uncoalesced(compiler_version, input).await);
+                leader.finish(result.clone());
+                result
+            }
+            Join::Follower(result) => {
+                metrics::COMPILATIONS_COALESCED.inc();
+                result
+            }
+        };
+        duplicate_result(&result)
+    }
+
+    async fn compile_uncoalesced(
+        &self,
+        compiler_version: &Version,
+        input: &CompilerInput,
     ) -> Result<CompilerOutput, Error> {
         let path_result = {
             self.cache
@@ -90,10 +157,23 @@ where
             };
             let _compile_timer_guard = metrics::COMPILE_TIME.start_timer();
             let _compile_gauge_guard = metrics::COMPILATIONS_IN_FLIGHT.guarded_inc();
-            self.evm_compiler
+            let started = Instant::now();
+            let output = self
+                .evm_compiler
                 .compile(&path, compiler_version, input)
-                .await?
+                .await;
+            usage::record(CompilationUsage {
+                compiler_invocations: 1,
+                compilation_time: started.elapsed(),
+                bytes_compiled: input
+                    .sources
+                    .values()
+                    .map(|source| source.content.len() as u64)
+                    .sum(),
+            });
+            output?
         };
+        self.limits.check_output(&output)?;
 
         // Compilations errors, warnings and info messages are returned in `CompilerOutput.error`
         let mut errors = Vec::new();
@@ -125,6 +205,58 @@ where
         versions.into_iter().map(|v| v.to_string()).collect()
     }
 
+    /// Returns the compilers downloaded or loaded from the local directory.
+    pub async fn cached_compilers(&self) -> Vec<CachedCompiler> {
+        self.cache.cached().await
+    }
+
+    /// Returns sha256 of the compiler binary, downloading the compiler if required.
+    /// Returns `None` if the compiler is not available.
+    pub async fn compiler_hash(&self, ver: &Version) -> Option<H256> {
+        if let Some(hash) = self.hashes.lock().get(ver) {
+            return Some(*hash);
+        }
+        let path = self.cache.get(self.fetcher.as_ref(), ver).await.ok()?;
+        let hash = tokio::task::spawn_blocking(move || {
+            std::fs::read(path).map(|binary| H256::from_slice(&Sha256::digest(binary)))
+        })
+        .await
+        .ok()?
+        .map_err(|err| {
+            tracing::warn!(
+                ver = ver.to_string(),
+                "compiler binary cannot be read: {err}"
+            )
+        })
+        .ok()?;
+        self.hashes.lock().insert(ver.clone(), hash);
+        Some(hash)
+    }
+
+    /// Removes the compiler binary from the cache. Returns `false` if the version has not been cached.
+    pub async fn evict(&self, ver: &Version) -> std::io::Result<bool> {
+        self.cache.evict(ver).await
+    }
+
+    /// Downloads the compilers in advance, so that the first verifications using them
+    /// do not have to wait for the download.
+    pub async fn prewarm(&self, versions: &[Version]) -> Vec<(Version, Result<(), Error>)> {
+        let fetches = versions.iter().map(|ver| async move {
+            let result = match self.cache.get(self.fetcher.as_ref(), ver).await {
+                Ok(_) => Ok(()),
+                Err(FetchError::NotFound(version)) => Err(Error::VersionNotFound(version)),
+                Err(err) => Err(err.into()),
+            };
+            (ver.clone(), result)
+        });
+        futures::future::join_all(fetches).await
+    }
+
+    /// Updates the list of available versions without waiting for the refresh schedule.
+    pub async fn refresh_versions(&self) {
+        self.fetcher.refresh_versions().await
+    }
+
     pub async fn load_from_dir(&self, dir: &PathBuf) {
         match self.cache.load_from_dir(dir).await {
             Ok(_) => {}
@@ -139,6 +271,20 @@ where
     }
 }
 
+/// Copies the result shared by the coalesced compilations. Errors which cannot be cloned
+/// are passed as their messages, as they are only reported to the users.
+fn duplicate_result(result: &Result<CompilerOutput, Error>) -> Result<CompilerOutput, Error> {
+    match result {
+        Ok(output) => Ok(output.clone()),
+        Err(Error::VersionNotFound(version)) => Err(Error::VersionNotFound(version.clone())),
+        Err(Error::Compilation(errors)) => Err(Error::Compilation(errors.clone())),
+        Err(Error::LimitExceeded(err)) => Err(Error::LimitExceeded(err.clone())),
+        Err(err @ (Error::Fetch(_) | Error::Internal(_) | Error::Acquire(_))) => {
+            Err(Error::Internal(SolcError::Message(err.to_string())))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{super::list_fetcher::ListFetcher, *};
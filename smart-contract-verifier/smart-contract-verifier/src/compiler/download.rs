@@ -0,0 +1,305 @@
+//! Resumable downloads of the compiler binaries.
+//!
+//! The binary is downloaded into the `solc.part` file next to the final one. Transfers
+//! interrupted by network errors (or by the service restart) are continued from the length
+//! of that file via ranged requests. Once completed, the checksum of the file written to disk
+//! is checked, and the file is atomically renamed, so that the cache never contains
+//! partially written or corrupted binaries.
+
+use super::{
+    fetcher::{FetchError, FileValidator},
+    version::Version,
+};
+use mismatch::Mismatch;
+use primitive_types::H256;
+use reqwest::{header, StatusCode};
+use sha2::{Digest, Sha256};
+use std::{
+    io::{ErrorKind, Read},
+    num::NonZeroUsize,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{io::AsyncWriteExt, sync::Semaphore};
+use tracing::instrument;
+use url::Url;
+
+pub const DEFAULT_MAX_PARALLEL_DOWNLOADS: usize = 4;
+const DEFAULT_MAX_ATTEMPTS: usize = 3;
+
+/// Downloads the binaries, running at most `max_parallel_downloads` transfers at once,
+/// so that prewarming a lot of compilers does not saturate slow links.
+#[derive(Clone, Debug)]
+pub struct Downloader {
+    client: reqwest::Client,
+    pool: Arc<Semaphore>,
+    max_attempts: NonZeroUsize,
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new(NonZeroUsize::new(DEFAULT_MAX_PARALLEL_DOWNLOADS).unwrap())
+    }
+}
+
+impl Downloader {
+    pub fn new(max_parallel_downloads: NonZeroUsize) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            pool: Arc::new(Semaphore::new(max_parallel_downloads.get())),
+            max_attempts: NonZeroUsize::new(DEFAULT_MAX_ATTEMPTS).unwrap(),
+        }
+    }
+
+    /// Sets how many times the interrupted transfer is resumed before the download fails.
+    pub fn with_max_attempts(mut self, max_attempts: NonZeroUsize) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Downloads the binary of the version into `{folder}/{version}/solc`.
+    #[instrument(skip(self, validator), level = "debug")]
+    pub async fn download(
+        &self,
+        url: &Url,
+        sha256: H256,
+        folder: &Path,
+        ver: &Version,
+        validator: Option<&dyn FileValidator>,
+    ) -> Result<PathBuf, FetchError> {
+        let _permit = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|err| FetchError::Fetch(err.into()))?;
+
+        let dir = folder.join(ver.to_string());
+        tokio::fs::create_dir_all(&dir).await?;
+        let file = dir.join("solc");
+        let part = dir.join("solc.part");
+
+        let mut attempt = 1;
+        while let Err(err) = self.download_part(url, &part).await {
+            if attempt >= self.max_attempts.get() {
+                return Err(FetchError::Fetch(err));
+            }
+            tracing::warn!(%url, attempt, "compiler download interrupted, resuming: {err:#}");
+            attempt += 1;
+        }
+
+        let found = file_sha256(&part).await?;
+        if found != sha256 {
+            // Resuming the corrupted file would never succeed
+            tokio::fs::remove_file(&part).await?;
+            return Err(Mismatch::new(sha256, found).into());
+        }
+        tokio::fs::set_permissions(&part, std::fs::Permissions::from_mode(0o777)).await?;
+        if let Some(validator) = validator {
+            validator
+                .validate(ver, &part)
+                .await
+                .map_err(FetchError::Validation)?;
+        }
+        tokio::fs::rename(&part, &file).await?;
+
+        Ok(file)
+    }
+
+    /// Appends the rest of the binary to the partially downloaded file.
+    async fn download_part(&self, url: &Url, part: &Path) -> anyhow::Result<()> {
+        let offset = match tokio::fs::metadata(part).await {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == ErrorKind::NotFound => 0,
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut request = self.client.get(url.clone());
+        if offset > 0 {
+            request = request.header(header::RANGE, format!("bytes={offset}-"));
+        }
+        let mut response = request.send().await?;
+        let mut file = match response.status() {
+            StatusCode::PARTIAL_CONTENT => {
+                let content_range = response
+                    .headers()
+                    .get(header::CONTENT_RANGE)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default();
+                if !content_range.starts_with(&format!("bytes {offset}-")) {
+                    tokio::fs::remove_file(part).await?;
+                    anyhow::bail!("unexpected content range: {content_range:?}");
+                }
+                tokio::fs::OpenOptions::new()
+                    .append(true)
+                    .open(part)
+                    .await?
+            }
+            // The file has been downloaded completely before the interruption
+            StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => return Ok(()),
+            // Servers not supporting ranged requests return the whole file
+            status if status.is_success() => tokio::fs::File::create(part).await?,
+            status => anyhow::bail!("unexpected status code: {status}"),
+        };
+
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.sync_all().await?;
+        Ok(())
+    }
+}
+
+async fn file_sha256(path: &Path) -> Result<H256, FetchError> {
+    let path = path.to_path_buf();
+    let span = tracing::debug_span!("check hash result");
+    tokio::task::spawn_blocking(move || -> Result<H256, FetchError> {
+        let _guard = span.enter();
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            match file.read(&mut buffer)? {
+                0 => break,
+                read => hasher.update(&buffer[..read]),
+            }
+        }
+        Ok(H256::from_slice(&hasher.finalize()))
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+    use wiremock::{
+        matchers::{header as header_matcher, method},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const DATA: &str = "this is a compiler binary";
+
+    fn version() -> Version {
+        Version::from_str("v0.4.10+commit.f0d539ae").unwrap()
+    }
+
+    fn sha256(data: &[u8]) -> H256 {
+        H256::from_slice(&Sha256::digest(data))
+    }
+
+    fn setup(partial: Option<&str>) -> (tempfile::TempDir, PathBuf) {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let dir = tmp_dir.path().join(version().to_string());
+        let part = dir.join("solc.part");
+        if let Some(partial) = partial {
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(&part, partial).unwrap();
+        }
+        (tmp_dir, part)
+    }
+
+    #[tokio::test]
+    async fn partial_download_is_resumed() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header_matcher("range", "bytes=10-"))
+            .respond_with(
+                ResponseTemplate::new(206)
+                    .insert_header("content-range", format!("bytes 10-24/{}", DATA.len()))
+                    .set_body_bytes(&DATA.as_bytes()[10..]),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let (tmp_dir, part) = setup(Some(&DATA[..10]));
+        let file = Downloader::default()
+            .download(
+                &Url::parse(&mock_server.uri()).unwrap(),
+                sha256(DATA.as_bytes()),
+                tmp_dir.path(),
+                &version(),
+                None,
+            )
+            .await
+            .expect("download should succeed");
+
+        assert_eq!(DATA, std::fs::read_to_string(&file).unwrap());
+        assert!(!part.exists());
+    }
+
+    #[tokio::test]
+    async fn download_is_restarted_without_range_support() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(DATA))
+            .mount(&mock_server)
+            .await;
+
+        let (tmp_dir, _part) = setup(Some("garbage"));
+        let file = Downloader::default()
+            .download(
+                &Url::parse(&mock_server.uri()).unwrap(),
+                sha256(DATA.as_bytes()),
+                tmp_dir.path(),
+                &version(),
+                None,
+            )
+            .await
+            .expect("download should succeed");
+
+        assert_eq!(DATA, std::fs::read_to_string(&file).unwrap());
+    }
+
+    #[tokio::test]
+    async fn corrupted_download_is_removed() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes("corrupted binary"))
+            .mount(&mock_server)
+            .await;
+
+        let (tmp_dir, part) = setup(None);
+        let err = Downloader::default()
+            .download(
+                &Url::parse(&mock_server.uri()).unwrap(),
+                sha256(DATA.as_bytes()),
+                tmp_dir.path(),
+                &version(),
+                None,
+            )
+            .await
+            .expect_err("checksum mismatch expected");
+
+        assert!(matches!(err, FetchError::HashMismatch(_)), "{err:?}");
+        assert!(!part.exists());
+        assert!(!part.with_file_name("solc").exists());
+    }
+
+    #[tokio::test]
+    async fn failed_download_is_retried() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let (tmp_dir, _part) = setup(None);
+        let err = Downloader::default()
+            .with_max_attempts(NonZeroUsize::new(2).unwrap())
+            .download(
+                &Url::parse(&mock_server.uri()).unwrap(),
+                sha256(DATA.as_bytes()),
+                tmp_dir.path(),
+                &version(),
+                None,
+            )
+            .await
+            .expect_err("download should fail");
+
+        assert!(matches!(err, FetchError::Fetch(_)), "{err:?}");
+    }
+}
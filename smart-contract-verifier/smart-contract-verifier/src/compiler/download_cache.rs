@@ -3,18 +3,31 @@ use super::{
     version::Version,
 };
 use crate::metrics;
-use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc, time::SystemTime};
 use tracing::Instrument;
 
+/// Compiler binary available locally.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedCompiler {
+    pub version: Version,
+    pub path: PathBuf,
+    /// Size of the binary in bytes
+    pub size: u64,
+    /// The last time the compiler has been requested since the service start
+    pub last_used: Option<SystemTime>,
+}
+
 #[derive(Default)]
 pub struct DownloadCache {
     cache: parking_lot::Mutex<HashMap<Version, Arc<tokio::sync::RwLock<Option<PathBuf>>>>>,
+    last_used: parking_lot::Mutex<HashMap<Version, SystemTime>>,
 }
 
 impl DownloadCache {
     pub fn new() -> Self {
         DownloadCache {
             cache: Default::default(),
+            last_used: Default::default(),
         }
     }
 
@@ -40,6 +53,7 @@ impl DownloadCache {
         ver: &Version,
     ) -> Result<PathBuf, FetchError> {
         metrics::DOWNLOAD_CACHE_TOTAL.inc();
+        self.last_used.lock().insert(ver.clone(), SystemTime::now());
         match self.try_get(ver).await {
             Some(file) => {
                 metrics::DOWNLOAD_CACHE_HITS.inc();
@@ -74,6 +88,75 @@ impl DownloadCache {
         }
     }
 
+    /// Returns the compilers available locally, sorted by the versions in descending order.
+    /// Compilers being fetched at the moment are not included.
+    pub async fn cached(&self) -> Vec<CachedCompiler> {
+        let entries: Vec<_> = {
+            let cache = self.cache.lock();
+            cache
+                .iter()
+                .map(|(version, lock)| (version.clone(), lock.clone()))
+                .collect()
+        };
+
+        let mut compilers = Vec::new();
+        for (version, lock) in entries {
+            let path = match lock.try_read() {
+                Ok(path) => path.clone(),
+                Err(_) => None,
+            };
+            if let Some(path) = path {
+                let size = std::fs::metadata(&path)
+                    .map(|metadata| metadata.len())
+                    .unwrap_or_default();
+                let last_used = self.last_used.lock().get(&version).copied();
+                compilers.push(CachedCompiler {
+                    version,
+                    path,
+                    size,
+                    last_used,
+                });
+            }
+        }
+        compilers.sort_by(|x, y| x.version.cmp(&y.version).reverse());
+        compilers
+    }
+
+    /// Removes the compiler from the cache and deletes its binary.
+    /// Returns `false` if the version has not been cached.
+    pub async fn evict(&self, ver: &Version) -> std::io::Result<bool> {
+        let lock = {
+            let cache = self.cache.lock();
+            cache.get(ver).cloned()
+        };
+        let lock = match lock {
+            Some(lock) => lock,
+            None => return Ok(false),
+        };
+
+        // Waits for the compilations using the binary to start and for the fetch in progress
+        let mut entry = lock.write().await;
+        let path = match entry.take() {
+            Some(path) => path,
+            None => return Ok(false),
+        };
+        tracing::info!(target: "compiler_cache", "evicting file version {}", ver);
+        // The file is removed while the entry is still locked,
+        // so that concurrent requests could not start downloading it again
+        let removed = std::fs::remove_file(&path);
+        if let Err(err) = removed {
+            *entry = Some(path);
+            return Err(err);
+        }
+        // Binaries are stored inside the directories named after the versions
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::remove_dir(dir);
+        }
+        self.cache.lock().remove(ver);
+        self.last_used.lock().remove(ver);
+        Ok(true)
+    }
+
     pub async fn load_from_dir(&self, dir: &PathBuf) -> std::io::Result<()> {
         let paths = DownloadCache::read_dir_paths(dir)?;
         let versions = DownloadCache::filter_versions(paths);
@@ -297,4 +380,50 @@ mod tests {
             .expect("version should appear in cache");
         assert!(path.exists(), "solc compiler file should exists");
     }
+
+    #[tokio::test]
+    async fn evict_cached_compiler() {
+        struct MockFileFetcher {
+            folder: PathBuf,
+        }
+
+        #[async_trait]
+        impl Fetcher for MockFileFetcher {
+            async fn fetch(&self, ver: &Version) -> Result<PathBuf, FetchError> {
+                let dir = self.folder.join(ver.to_string());
+                std::fs::create_dir_all(&dir).unwrap();
+                let path = dir.join("solc");
+                std::fs::write(&path, ver.to_string()).unwrap();
+                Ok(path)
+            }
+
+            fn all_versions(&self) -> Vec<Version> {
+                vec![]
+            }
+        }
+
+        let folder = tempfile::tempdir().unwrap();
+        let fetcher = MockFileFetcher {
+            folder: folder.path().to_path_buf(),
+        };
+        let cache = DownloadCache::new();
+        let vers: Vec<_> = (0..2).map(new_version).collect();
+        for ver in &vers {
+            cache.get(&fetcher, ver).await.unwrap();
+        }
+
+        let cached = cache.cached().await;
+        assert_eq!(
+            vec![vers[1].clone(), vers[0].clone()],
+            cached.iter().map(|c| c.version.clone()).collect::<Vec<_>>()
+        );
+        assert!(cached.iter().all(|c| c.size > 0 && c.last_used.is_some()));
+
+        let path = cached[1].path.clone();
+        assert!(cache.evict(&vers[0]).await.unwrap());
+        assert!(!path.exists(), "compiler file should be removed");
+        assert!(!cache.evict(&vers[0]).await.unwrap());
+        assert_eq!(1, cache.cached().await.len());
+        assert_eq!(None, cache.try_get(&vers[0]).await);
+    }
 }
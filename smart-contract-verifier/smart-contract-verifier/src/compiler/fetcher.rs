@@ -40,6 +40,9 @@ pub trait FileValidator: Send + Sync {
 pub trait Fetcher: Send + Sync {
     async fn fetch(&self, ver: &Version) -> Result<PathBuf, FetchError>;
     fn all_versions(&self) -> Vec<Version>;
+    /// Updates the list of available versions out of the refresh schedule.
+    /// Fetchers with static lists of versions do nothing.
+    async fn refresh_versions(&self) {}
 }
 
 #[cfg(target_family = "unix")]
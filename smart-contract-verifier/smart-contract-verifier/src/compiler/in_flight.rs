@@ -0,0 +1,167 @@
+//! Coalescing of identical in-flight compilations.
+//!
+//! Identical requests arriving at the same time (e.g., users submitting the form twice)
+//! would compile the same input several times. Instead, the first request with the key
+//! compiles the input, while the others wait for its result. If the first request is
+//! dropped before completion (e.g., cancelled on the request timeout), one of the waiting
+//! requests takes over the compilation.
+
+use parking_lot::Mutex;
+use std::{collections::HashMap, hash::Hash};
+use tokio::sync::watch;
+
+type Receiver<V> = watch::Receiver<Option<V>>;
+
+pub struct InFlight<K, V> {
+    entries: Mutex<HashMap<K, Receiver<V>>>,
+}
+
+impl<K, V> Default for InFlight<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+        }
+    }
+}
+
+pub enum Join<'a, K: Eq + Hash, V> {
+    /// No computation with the key is in flight, so the value should be computed
+    /// and passed to [`Leader::finish`]
+    Leader(Leader<'a, K, V>),
+    /// Value computed by the concurrent request with the key
+    Follower(V),
+}
+
+impl<K, V> InFlight<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    /// Either registers the caller as the one computing the value for the key,
+    /// or waits for the value computed by the request registered earlier.
+    pub async fn join(&self, key: &K) -> Join<'_, K, V> {
+        loop {
+            let mut receiver = {
+                let mut entries = self.entries.lock();
+                match entries.get(key) {
+                    Some(receiver) => receiver.clone(),
+                    None => {
+                        let (sender, receiver) = watch::channel(None);
+                        entries.insert(key.clone(), receiver);
+                        return Join::Leader(Leader {
+                            in_flight: self,
+                            key: key.clone(),
+                            sender: Some(sender),
+                        });
+                    }
+                }
+            };
+            loop {
+                let value = receiver.borrow().clone();
+                if let Some(value) = value {
+                    return Join::Follower(value);
+                }
+                if receiver.changed().await.is_err() {
+                    // The leader has been dropped without the value, so the key is free again
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Number of the keys whose values are being computed
+    pub fn count(&self) -> usize {
+        self.entries.lock().len()
+    }
+}
+
+/// Request computing the value for the key. The key is released
+/// if the leader is dropped before the value is computed.
+pub struct Leader<'a, K: Eq + Hash, V> {
+    in_flight: &'a InFlight<K, V>,
+    key: K,
+    sender: Option<watch::Sender<Option<V>>>,
+}
+
+impl<'a, K: Eq + Hash, V> Leader<'a, K, V> {
+    /// Passes the value to the waiting requests and releases the key,
+    /// so that the following requests compute the value again.
+    pub fn finish(mut self, value: V) {
+        self.in_flight.entries.lock().remove(&self.key);
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Some(value));
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash, V> Drop for Leader<'a, K, V> {
+    fn drop(&mut self) {
+        if self.sender.is_some() {
+            self.in_flight.entries.lock().remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    async fn compute(in_flight: &InFlight<u32, u32>, computations: &AtomicUsize) -> u32 {
+        match in_flight.join(&1).await {
+            Join::Leader(leader) => {
+                computations.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                leader.finish(42);
+                42
+            }
+            Join::Follower(value) => value,
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_are_coalesced() {
+        let in_flight = InFlight::default();
+        let computations = AtomicUsize::new(0);
+
+        let (first, second) = futures::join!(
+            compute(&in_flight, &computations),
+            compute(&in_flight, &computations)
+        );
+        assert_eq!((42, 42), (first, second));
+        assert_eq!(1, computations.load(Ordering::SeqCst));
+        assert_eq!(0, in_flight.count(), "completed keys should be released");
+
+        compute(&in_flight, &computations).await;
+        assert_eq!(
+            2,
+            computations.load(Ordering::SeqCst),
+            "values should not be kept after completion"
+        );
+    }
+
+    #[tokio::test]
+    async fn follower_takes_over_dropped_leader() {
+        let in_flight = InFlight::default();
+        let computations = AtomicUsize::new(0);
+
+        let leader = match in_flight.join(&1).await {
+            Join::Leader(leader) => leader,
+            Join::Follower(_) => panic!("the first request should be the leader"),
+        };
+        let follower = compute(&in_flight, &computations);
+        let dropped_leader = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(leader);
+        };
+
+        let (value, _) = futures::join!(follower, dropped_leader);
+        assert_eq!(42, value);
+        assert_eq!(1, computations.load(Ordering::SeqCst));
+        assert_eq!(0, in_flight.count());
+    }
+}
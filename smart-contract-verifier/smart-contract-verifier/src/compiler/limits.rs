@@ -0,0 +1,182 @@
+//! Limits of the compiler inputs and outputs.
+//!
+//! Huge inputs (and outputs of the compiler for them) may exhaust the memory of the service,
+//! so inputs exceeding the limits are rejected before the compilation,
+//! and outputs exceeding them are dropped right after it.
+
+use ethers_solc::{CompilerInput, CompilerOutput};
+use std::{fmt, io};
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompilationLimits {
+    /// Maximum number of source files of the compiler input
+    pub max_source_files: Option<usize>,
+    /// Maximum total size (in bytes) of the source files of the compiler input
+    pub max_sources_size: Option<usize>,
+    /// Maximum size (in bytes) of the JSON encoded compiler output
+    pub max_output_size: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Limit {
+    SourceFiles,
+    SourcesSize,
+    OutputSize,
+}
+
+impl Limit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Limit::SourceFiles => "source_files",
+            Limit::SourcesSize => "sources_size",
+            Limit::OutputSize => "output_size",
+        }
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("{limit} limit exceeded: {actual} (maximum is {max})")]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    /// Configured maximum value
+    pub max: usize,
+    /// Actual value, which exceeds the maximum
+    pub actual: usize,
+}
+
+impl CompilationLimits {
+    pub fn check_input(&self, input: &CompilerInput) -> Result<(), LimitExceeded> {
+        check(Limit::SourceFiles, self.max_source_files, || {
+            input.sources.len()
+        })?;
+        check(Limit::SourcesSize, self.max_sources_size, || {
+            input
+                .sources
+                .values()
+                .map(|source| source.content.len())
+                .sum()
+        })
+    }
+
+    pub fn check_output(&self, output: &CompilerOutput) -> Result<(), LimitExceeded> {
+        check(Limit::OutputSize, self.max_output_size, || {
+            // Counts the bytes without allocating the encoded output
+            let mut counter = ByteCounter(0);
+            serde_json::to_writer(&mut counter, output)
+                .map(|_| counter.0)
+                .unwrap_or_default()
+        })
+    }
+}
+
+fn check(
+    limit: Limit,
+    max: Option<usize>,
+    actual: impl FnOnce() -> usize,
+) -> Result<(), LimitExceeded> {
+    let max = match max {
+        Some(max) => max,
+        None => return Ok(()),
+    };
+    let actual = actual();
+    if actual > max {
+        return Err(LimitExceeded { limit, max, actual });
+    }
+    Ok(())
+}
+
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_solc::artifacts::{Source, Sources};
+    use pretty_assertions::assert_eq;
+
+    fn input(sources: &[(&str, &str)]) -> CompilerInput {
+        CompilerInput {
+            language: "Solidity".to_string(),
+            sources: sources
+                .iter()
+                .map(|(path, content)| (path.into(), Source::new(*content)))
+                .collect::<Sources>(),
+            settings: Default::default(),
+        }
+    }
+
+    #[test]
+    fn input_limits() {
+        let input = input(&[("A.sol", "contract A {}"), ("B.sol", "contract B {}")]);
+        assert_eq!(Ok(()), CompilationLimits::default().check_input(&input));
+
+        let limits = CompilationLimits {
+            max_source_files: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(LimitExceeded {
+                limit: Limit::SourceFiles,
+                max: 1,
+                actual: 2
+            }),
+            limits.check_input(&input)
+        );
+
+        let limits = CompilationLimits {
+            max_source_files: Some(2),
+            max_sources_size: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(LimitExceeded {
+                limit: Limit::SourcesSize,
+                max: 20,
+                actual: 26
+            }),
+            limits.check_input(&input)
+        );
+    }
+
+    #[test]
+    fn output_limit() {
+        let output = CompilerOutput::default();
+        let size = serde_json::to_vec(&output).unwrap().len();
+
+        let limits = CompilationLimits {
+            max_output_size: Some(size),
+            ..Default::default()
+        };
+        assert_eq!(Ok(()), limits.check_output(&output));
+
+        let limits = CompilationLimits {
+            max_output_size: Some(size - 1),
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(LimitExceeded {
+                limit: Limit::OutputSize,
+                max: size - 1,
+                actual: size
+            }),
+            limits.check_output(&output)
+        );
+    }
+}
@@ -1,10 +1,10 @@
 use super::{
+    download::Downloader,
     fetcher::{FetchError, Fetcher, FileValidator},
     version::Version,
     versions_fetcher::{VersionsFetcher, VersionsRefresher},
 };
 use async_trait::async_trait;
-use bytes::Bytes;
 use cron::Schedule;
 use primitive_types::H256;
 use std::{collections::HashMap, fmt::Debug, path::PathBuf, sync::Arc};
@@ -94,9 +94,12 @@ impl VersionsFetcher for ListVersionFetcher {
 }
 
 pub struct ListFetcher {
+    versions_fetcher: Arc<ListVersionFetcher>,
     versions: VersionsRefresher<VersionsMap>,
     folder: PathBuf,
     validator: Option<Arc<dyn FileValidator>>,
+    download_url_template: Option<String>,
+    downloader: Downloader,
 }
 
 impl ListFetcher {
@@ -106,17 +109,34 @@ impl ListFetcher {
         refresh_schedule: Option<Schedule>,
         validator: Option<Arc<dyn FileValidator>>,
     ) -> anyhow::Result<Self> {
-        let fetcher = Arc::new(ListVersionFetcher::new(list_url));
-        let versions = VersionsRefresher::new(fetcher, refresh_schedule).await?;
+        let versions_fetcher = Arc::new(ListVersionFetcher::new(list_url));
+        let versions = VersionsRefresher::new(versions_fetcher.clone(), refresh_schedule).await?;
         Ok(Self {
+            versions_fetcher,
             versions,
             folder,
             validator,
+            download_url_template: None,
+            downloader: Downloader::default(),
         })
     }
 
-    #[instrument(skip(self), level = "debug")]
-    async fn fetch_file(&self, ver: &Version) -> Result<(Bytes, H256), FetchError> {
+    /// Compilers are downloaded from the urls built by the template rather than
+    /// from the paths of the list. Is intended for the forks of the compiler
+    /// publishing their releases separately from the list of versions.
+    /// See [`expand_download_url`] for the supported placeholders.
+    pub fn with_download_url_template(mut self, download_url_template: Option<String>) -> Self {
+        self.download_url_template = download_url_template;
+        self
+    }
+
+    /// Sets the downloader of the compilers, which bounds the number of parallel downloads.
+    pub fn with_downloader(mut self, downloader: Downloader) -> Self {
+        self.downloader = downloader;
+        self
+    }
+
+    fn file_url(&self, ver: &Version) -> Result<(Url, H256), FetchError> {
         let file_info = {
             let versions = self.versions.read();
             versions
@@ -125,24 +145,22 @@ impl ListFetcher {
                 .ok_or_else(|| FetchError::NotFound(ver.clone()))?
         };
 
-        let response = reqwest::get(file_info.url)
-            .await
-            .map_err(anyhow::Error::msg)
-            .map_err(FetchError::Fetch)?;
-        let data = response
-            .bytes()
-            .await
-            .map_err(anyhow::Error::msg)
-            .map_err(FetchError::Fetch)?;
-        Ok((data, file_info.sha256))
+        let url = match &self.download_url_template {
+            Some(template) => expand_download_url(template, ver)
+                .map_err(anyhow::Error::msg)
+                .map_err(FetchError::Fetch)?,
+            None => file_info.url,
+        };
+        Ok((url, file_info.sha256))
     }
 }
 
 #[async_trait]
 impl Fetcher for ListFetcher {
     async fn fetch(&self, ver: &Version) -> Result<PathBuf, FetchError> {
-        let (data, hash) = self.fetch_file(ver).await?;
-        super::fetcher::write_executable(data, hash, &self.folder, ver, self.validator.as_deref())
+        let (url, hash) = self.file_url(ver)?;
+        self.downloader
+            .download(&url, hash, &self.folder, ver, self.validator.as_deref())
             .await
     }
 
@@ -150,6 +168,20 @@ impl Fetcher for ListFetcher {
         let versions = self.versions.read();
         versions.iter().map(|(ver, _)| ver.clone()).collect()
     }
+
+    async fn refresh_versions(&self) {
+        self.versions.refresh(self.versions_fetcher.as_ref()).await
+    }
+}
+
+/// Builds the download url of the compiler version by replacing the placeholders
+/// of the template: `{version}` with the full version (e.g., `v0.8.19+commit.7dd6d404`),
+/// and `{short_version}` with the version without the commit hash (e.g., `0.8.19`).
+pub fn expand_download_url(template: &str, ver: &Version) -> Result<Url, url::ParseError> {
+    let url = template
+        .replace("{version}", &ver.to_string())
+        .replace("{short_version}", &ver.version().to_string());
+    Url::parse(&url)
 }
 
 mod json {
@@ -305,6 +337,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn download_urls_are_expanded() {
+        let ver = Version::from_str("0.8.19+commit.7dd6d404").unwrap();
+        let url = expand_download_url(
+            "https://github.com/org/solc/releases/download/{short_version}/solc-{version}",
+            &ver,
+        )
+        .unwrap();
+        assert_eq!(
+            "https://github.com/org/solc/releases/download/0.8.19/solc-v0.8.19+commit.7dd6d404",
+            url.as_str()
+        );
+
+        let nightly = Version::from_str("0.8.15-nightly.2022.5.27+commit.095cc647").unwrap();
+        let url = expand_download_url("https://example.com/{version}", &nightly).unwrap();
+        assert_eq!(
+            "https://example.com/v0.8.15-nightly.2022.5.27+commit.095cc647",
+            url.as_str()
+        );
+
+        assert!(expand_download_url("{version}", &ver).is_err());
+    }
+
     #[tokio::test]
     async fn list_download_versions() {
         let list_url = Url::try_from(DEFAULT_SOLIDITY_COMPILER_LIST).expect("valid url");
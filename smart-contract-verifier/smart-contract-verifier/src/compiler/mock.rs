@@ -0,0 +1,203 @@
+//! Compiler returning predetermined outputs instead of invoking the compiler binaries,
+//! so that the verifier and the http layers could be tested (and run locally)
+//! without downloading the compilers.
+//!
+//! Outputs are keyed by the hash of the json encoded compiler input (see [`input_hash`]).
+//! Compilations of unknown inputs fail with the hash of the input in the message,
+//! so that the missing output could be recorded under that hash.
+
+use super::{compilers::input_hash, fetcher::FetchError, EvmCompiler, Fetcher, Version};
+use crate::solidity::backend::SolcBackend;
+use anyhow::Context;
+use ethers_solc::{error::SolcError, CompilerInput, CompilerOutput};
+use primitive_types::H256;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct MockCompiler {
+    outputs: HashMap<H256, CompilerOutput>,
+}
+
+impl MockCompiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the `output` for the compilations of the `input`.
+    pub fn with_output(mut self, input: &CompilerInput, output: CompilerOutput) -> Self {
+        let hash = input_hash(input).expect("compiler input is serializable");
+        self.outputs.insert(hash, output);
+        self
+    }
+
+    /// Loads the outputs stored in the directory as `{input hash}.json` files,
+    /// where the input hash is hex encoded without the `0x` prefix.
+    pub fn from_dir(dir: &Path) -> anyhow::Result<Self> {
+        let mut outputs = HashMap::new();
+        let entries = std::fs::read_dir(dir)
+            .with_context(|| format!("failed to read mock outputs dir {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+            let hash = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| hex::decode(stem).ok())
+                .filter(|hash| hash.len() == H256::len_bytes())
+                .map(|hash| H256::from_slice(&hash))
+                .with_context(|| {
+                    format!("mock output {} is not named by input hash", path.display())
+                })?;
+            let content = std::fs::read(&path)
+                .with_context(|| format!("failed to read mock output {}", path.display()))?;
+            let output = serde_json::from_slice(&content)
+                .with_context(|| format!("invalid mock output {}", path.display()))?;
+            outputs.insert(hash, output);
+        }
+        Ok(Self { outputs })
+    }
+
+    fn output(&self, input: &CompilerInput) -> Result<CompilerOutput, SolcError> {
+        let hash = input_hash(input)?;
+        self.outputs.get(&hash).cloned().ok_or_else(|| {
+            SolcError::Message(format!(
+                "mock compiler has no output for input {}",
+                hex::encode(hash)
+            ))
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl EvmCompiler for MockCompiler {
+    async fn compile(
+        &self,
+        _path: &Path,
+        _ver: &Version,
+        input: &CompilerInput,
+    ) -> Result<CompilerOutput, SolcError> {
+        self.output(input)
+    }
+}
+
+#[async_trait::async_trait]
+impl SolcBackend for MockCompiler {
+    async fn compile(
+        &self,
+        _solc: &Path,
+        input: &CompilerInput,
+    ) -> Result<CompilerOutput, SolcError> {
+        self.output(input)
+    }
+}
+
+/// Provides the listed versions without downloading anything.
+/// Is expected to be used along with [`MockCompiler`], which ignores the compiler paths.
+#[derive(Clone, Debug, Default)]
+pub struct MockFetcher {
+    versions: Vec<Version>,
+}
+
+impl MockFetcher {
+    pub fn new(versions: Vec<Version>) -> Self {
+        Self { versions }
+    }
+}
+
+#[async_trait::async_trait]
+impl Fetcher for MockFetcher {
+    async fn fetch(&self, ver: &Version) -> Result<PathBuf, FetchError> {
+        if !self.versions.contains(ver) {
+            return Err(FetchError::NotFound(ver.clone()));
+        }
+        Ok(PathBuf::from(format!("mock-{ver}")))
+    }
+
+    fn all_versions(&self) -> Vec<Version> {
+        self.versions.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::Compilers, *};
+    use ethers_solc::artifacts::{Source, Sources};
+    use pretty_assertions::assert_eq;
+    use std::{str::FromStr, sync::Arc};
+    use tokio::sync::Semaphore;
+
+    fn input(source: &str) -> CompilerInput {
+        CompilerInput {
+            language: "Solidity".to_string(),
+            sources: Sources::from([("source.sol".into(), Source::new(source))]),
+            settings: Default::default(),
+        }
+    }
+
+    fn output(contract_name: &str) -> CompilerOutput {
+        serde_json::from_value(serde_json::json!({
+            "contracts": {"source.sol": {contract_name: {"abi": []}}},
+            "sources": {},
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn returns_predetermined_outputs() {
+        let version = Version::from_str("v0.8.19+commit.7dd6d404").unwrap();
+        let compiler = MockCompiler::new().with_output(&input("contract A {}"), output("A"));
+        let compilers = Compilers::new(
+            Arc::new(MockFetcher::new(vec![version.clone()])),
+            compiler,
+            Arc::new(Semaphore::new(1)),
+        );
+
+        let compiled = compilers
+            .compile(&version, &input("contract A {}"))
+            .await
+            .expect("compilation should succeed");
+        assert_eq!(output("A"), compiled);
+
+        let err = compilers
+            .compile(&version, &input("contract B {}"))
+            .await
+            .expect_err("unknown input should fail");
+        let hash = hex::encode(input_hash(&input("contract B {}")).unwrap());
+        assert!(err.to_string().contains(&hash), "unexpected error: {err}");
+
+        let unknown = Version::from_str("v0.8.18+commit.87f61d96").unwrap();
+        let err = compilers
+            .compile(&unknown, &input("contract A {}"))
+            .await
+            .expect_err("unknown version should fail");
+        assert!(matches!(err, super::super::Error::VersionNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn loads_outputs_from_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let hash = hex::encode(input_hash(&input("contract A {}")).unwrap());
+        std::fs::write(
+            dir.path().join(format!("{hash}.json")),
+            serde_json::to_string(&output("A")).unwrap(),
+        )
+        .unwrap();
+
+        let compiler = MockCompiler::from_dir(dir.path()).expect("outputs should be loaded");
+        let compiled = SolcBackend::compile(&compiler, Path::new("solc"), &input("contract A {}"))
+            .await
+            .expect("compilation should succeed");
+        assert_eq!(output("A"), compiled);
+
+        std::fs::write(dir.path().join("output.json"), "{}").unwrap();
+        assert!(
+            MockCompiler::from_dir(dir.path()).is_err(),
+            "outputs should be named by input hashes"
+        );
+    }
+}
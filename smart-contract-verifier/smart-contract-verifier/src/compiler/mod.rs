@@ -1,5 +1,6 @@
 mod version;
 
+mod download;
 mod fetcher;
 mod list_fetcher;
 mod s3_fetcher;
@@ -7,9 +8,20 @@ mod versions_fetcher;
 
 mod compilers;
 mod download_cache;
+mod in_flight;
+mod limits;
+mod mock;
+mod sources_dirs;
+mod usage;
 
-pub use compilers::{Compilers, Error, EvmCompiler};
-pub use fetcher::{Fetcher, FileValidator};
-pub use list_fetcher::ListFetcher;
+pub use compilers::{compilations_in_queue, input_hash, Compilers, Error, EvmCompiler};
+pub use download::{Downloader, DEFAULT_MAX_PARALLEL_DOWNLOADS};
+pub use download_cache::CachedCompiler;
+pub use fetcher::{FetchError, Fetcher, FileValidator};
+pub use limits::{CompilationLimits, Limit, LimitExceeded};
+pub use list_fetcher::{expand_download_url, ListFetcher};
+pub use mock::{MockCompiler, MockFetcher};
 pub use s3_fetcher::S3Fetcher;
+pub use sources_dirs::{SourcesDir, SourcesDirs};
+pub use usage::{metered, CompilationUsage};
 pub use version::Version;
@@ -63,6 +63,7 @@ impl VersionsFetcher for S3VersionFetcher {
 pub struct S3Fetcher {
     bucket: Arc<Bucket>,
     folder: PathBuf,
+    versions_fetcher: Arc<S3VersionFetcher>,
     versions: VersionsRefresher<HashSet<Version>>,
     validator: Option<Arc<dyn FileValidator>>,
 }
@@ -95,11 +96,12 @@ impl S3Fetcher {
         refresh_schedule: Option<Schedule>,
         validator: Option<Arc<dyn FileValidator>>,
     ) -> anyhow::Result<S3Fetcher> {
-        let fetcher = Arc::new(S3VersionFetcher::new(bucket.clone()));
-        let versions = VersionsRefresher::new(fetcher, refresh_schedule).await?;
+        let versions_fetcher = Arc::new(S3VersionFetcher::new(bucket.clone()));
+        let versions = VersionsRefresher::new(versions_fetcher.clone(), refresh_schedule).await?;
         Ok(S3Fetcher {
             bucket,
             folder,
+            versions_fetcher,
             versions,
             validator,
         })
@@ -149,6 +151,10 @@ impl Fetcher for S3Fetcher {
         let versions = self.versions.read();
         versions.iter().cloned().collect()
     }
+
+    async fn refresh_versions(&self) {
+        self.versions.refresh(self.versions_fetcher.as_ref()).await
+    }
 }
 
 #[cfg(test)]
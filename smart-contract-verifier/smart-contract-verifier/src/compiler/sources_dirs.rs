@@ -0,0 +1,353 @@
+//! Ephemeral directories the sources are written to for the compilers
+//! which cannot read them from the standard json input (huffc and solc
+//! versions older than 0.4.11).
+//!
+//! Each compilation gets its own directory which is removed as soon as the
+//! [`SourcesDir`] is dropped, including the cases of the compilation failing,
+//! panicking, or being cancelled (e.g., on the request timeout). Directories
+//! which could not be removed that way (e.g., the process was killed) are
+//! swept by the janitor task removing the directories older than the configured age.
+
+use ethers_solc::{error::SolcError, CompilerInput};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+use tempfile::TempDir;
+
+/// All directories are created with the prefix, so that the janitor
+/// never removes anything else located in the root directory.
+const DIR_PREFIX: &str = "sources-";
+
+#[derive(Clone, Debug)]
+pub struct SourcesDirs {
+    root: PathBuf,
+    /// Maximum total size (in bytes) of the sources of all existing directories
+    max_total_size: Option<u64>,
+    total_size: Arc<AtomicU64>,
+}
+
+impl Default for SourcesDirs {
+    fn default() -> Self {
+        Self::new(std::env::temp_dir().join("compiler-sources"))
+    }
+}
+
+impl SourcesDirs {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            max_total_size: None,
+            total_size: Default::default(),
+        }
+    }
+
+    /// Sets the quota of the total size of the sources written to disk at the same time.
+    /// Compilations which would exceed the quota fail without writing anything.
+    pub fn with_max_total_size(mut self, max_total_size: Option<u64>) -> Self {
+        self.max_total_size = max_total_size;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Total size (in bytes) of the sources of the currently existing directories
+    pub fn total_size(&self) -> u64 {
+        self.total_size.load(Ordering::Relaxed)
+    }
+
+    /// Writes the sources of the input into a new directory. Source names
+    /// are used as the paths relative to it.
+    pub async fn write(&self, input: &CompilerInput) -> Result<SourcesDir, SolcError> {
+        if input.sources.is_empty() {
+            return Err(SolcError::Message("no files were provided".to_string()));
+        }
+        for name in input.sources.keys() {
+            let is_relative = name
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+            if !is_relative {
+                return Err(SolcError::Message(format!(
+                    "source path `{}` leaves the sources directory",
+                    name.display()
+                )));
+            }
+        }
+        let size = input
+            .sources
+            .values()
+            .map(|source| source.content.len() as u64)
+            .sum();
+        let reservation = self.reserve(size)?;
+
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(|err| SolcError::Message(err.to_string()))?;
+        let dir = tempfile::Builder::new()
+            .prefix(DIR_PREFIX)
+            .tempdir_in(&self.root)
+            .map_err(|err| SolcError::Message(err.to_string()))?;
+        // From now on the directory is removed on drop,
+        // even if writing of the files fails or is cancelled
+        let sources_dir = SourcesDir {
+            dir,
+            _reservation: reservation,
+        };
+        for (name, source) in input.sources.iter() {
+            let file_path = sources_dir.path().join(name);
+            // name itself may contain some paths inside
+            if let Some(prefix) = file_path.parent() {
+                tokio::fs::create_dir_all(prefix)
+                    .await
+                    .map_err(|err| SolcError::Message(err.to_string()))?;
+            }
+            tokio::fs::write(&file_path, source.content.as_bytes())
+                .await
+                .map_err(|err| SolcError::Message(err.to_string()))?;
+        }
+        Ok(sources_dir)
+    }
+
+    fn reserve(&self, size: u64) -> Result<Reservation, SolcError> {
+        self.total_size
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |total| {
+                let new_total = total.saturating_add(size);
+                match self.max_total_size {
+                    Some(max) if new_total > max => None,
+                    _ => Some(new_total),
+                }
+            })
+            .map_err(|total| {
+                SolcError::Message(format!(
+                    "sources directories quota exceeded: {total} bytes are in use, \
+                     {size} more requested (maximum is {})",
+                    self.max_total_size.unwrap_or_default()
+                ))
+            })?;
+        Ok(Reservation {
+            size,
+            total_size: self.total_size.clone(),
+        })
+    }
+
+    /// Removes the directories which were created more than `max_age` ago.
+    /// Returns the number of removed directories.
+    pub async fn remove_stale(&self, max_age: Duration) -> std::io::Result<usize> {
+        let root = self.root.clone();
+        tokio::task::spawn_blocking(move || remove_stale_dirs(&root, max_age))
+            .await
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
+    }
+
+    /// Spawns the task removing the stale directories every `interval`.
+    /// Should be spawned once per root directory.
+    pub fn spawn_janitor(
+        &self,
+        max_age: Duration,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let dirs = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                match dirs.remove_stale(max_age).await {
+                    Ok(0) => {}
+                    Ok(removed) => tracing::warn!(
+                        root = dirs.root.to_string_lossy().as_ref(),
+                        "removed {removed} leaked sources directories"
+                    ),
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => tracing::warn!(
+                        root = dirs.root.to_string_lossy().as_ref(),
+                        "failed to remove stale sources directories: {err}"
+                    ),
+                }
+            }
+        })
+    }
+}
+
+fn remove_stale_dirs(root: &Path, max_age: Duration) -> std::io::Result<usize> {
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in std::fs::read_dir(root)? {
+        let entry = entry?;
+        if !entry.file_name().to_string_lossy().starts_with(DIR_PREFIX) {
+            continue;
+        }
+        // Directories may be removed concurrently by their owners (or other janitors)
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        if !metadata.is_dir() {
+            continue;
+        }
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if age > max_age {
+            match std::fs::remove_dir_all(entry.path()) {
+                Ok(()) => removed += 1,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    Ok(removed)
+}
+
+/// Directory with the sources of a single compilation, removed on drop.
+#[derive(Debug)]
+pub struct SourcesDir {
+    dir: TempDir,
+    _reservation: Reservation,
+}
+
+impl SourcesDir {
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+#[derive(Debug)]
+struct Reservation {
+    size: u64,
+    total_size: Arc<AtomicU64>,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        self.total_size.fetch_sub(self.size, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_solc::artifacts::Source;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    fn input(sources: &[(&str, &str)]) -> CompilerInput {
+        CompilerInput {
+            language: "Solidity".to_string(),
+            sources: sources
+                .iter()
+                .map(|(name, content)| (PathBuf::from(name), Source::new(*content)))
+                .collect::<BTreeMap<_, _>>(),
+            settings: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn directories_are_removed_on_drop() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = SourcesDirs::new(root.path().join("sources"));
+
+        let dir = dirs
+            .write(&input(&[
+                ("A.sol", "contract A {}"),
+                ("lib/B.sol", "library B {}"),
+            ]))
+            .await
+            .expect("sources should be written");
+        assert_eq!(
+            "library B {}",
+            std::fs::read_to_string(dir.path().join("lib/B.sol")).unwrap()
+        );
+        assert_eq!(25, dirs.total_size());
+
+        let path = dir.path().to_path_buf();
+        drop(dir);
+        assert!(!path.exists(), "directory should be removed");
+        assert_eq!(0, dirs.total_size());
+    }
+
+    #[tokio::test]
+    async fn cancelled_writes_are_cleaned_up() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = SourcesDirs::new(root.path().to_path_buf());
+
+        let input = input(&[("A.sol", "contract A {}")]);
+        let write = dirs.write(&input);
+        // The future is dropped before completion
+        let _ = tokio::time::timeout(Duration::ZERO, write).await;
+
+        assert_eq!(0, std::fs::read_dir(root.path()).unwrap().count());
+        assert_eq!(0, dirs.total_size());
+    }
+
+    #[tokio::test]
+    async fn quota_is_enforced() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = SourcesDirs::new(root.path().to_path_buf()).with_max_total_size(Some(20));
+
+        let first = dirs
+            .write(&input(&[("A.sol", "contract A {}")]))
+            .await
+            .expect("first sources fit the quota");
+        dirs.write(&input(&[("B.sol", "contract B {}")]))
+            .await
+            .expect_err("quota should be exceeded");
+        assert_eq!(
+            1,
+            std::fs::read_dir(root.path()).unwrap().count(),
+            "rejected sources should not be written"
+        );
+
+        drop(first);
+        dirs.write(&input(&[("B.sol", "contract B {}")]))
+            .await
+            .expect("quota should be released");
+    }
+
+    #[tokio::test]
+    async fn paths_leaving_the_directory_are_rejected() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = SourcesDirs::new(root.path().join("sources"));
+        for name in ["../A.sol", "/tmp/A.sol", "lib/../../A.sol"] {
+            dirs.write(&input(&[(name, "contract A {}")]))
+                .await
+                .expect_err(name);
+        }
+        assert_eq!(0, dirs.total_size());
+    }
+
+    #[tokio::test]
+    async fn stale_directories_are_removed() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = SourcesDirs::new(root.path().to_path_buf());
+
+        let leaked = dirs
+            .write(&input(&[("A.sol", "contract A {}")]))
+            .await
+            .unwrap();
+        let leaked_path = leaked.path().to_path_buf();
+        // Simulates the directory the process failed to remove
+        std::mem::forget(leaked);
+        let unrelated = root.path().join("unrelated");
+        std::fs::create_dir(&unrelated).unwrap();
+
+        assert_eq!(0, dirs.remove_stale(Duration::from_secs(60)).await.unwrap());
+        assert!(leaked_path.exists(), "fresh directories should be kept");
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(
+            1,
+            dirs.remove_stale(Duration::from_millis(10)).await.unwrap()
+        );
+        assert!(!leaked_path.exists(), "stale directory should be removed");
+        assert!(unrelated.exists(), "unrelated directories should be kept");
+    }
+}
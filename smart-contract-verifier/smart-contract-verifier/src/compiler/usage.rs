@@ -0,0 +1,88 @@
+//! Compute resources consumed by the compilations of a verification.
+//!
+//! Compilations are attributed to the verification whose future is run via [`metered`].
+//! Compilations coalesced with the identical ones in progress are not counted,
+//! as the compiler is invoked by the leading verification only.
+
+use std::{cell::RefCell, future::Future, time::Duration};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompilationUsage {
+    /// Number of times the compiler has been invoked
+    pub compiler_invocations: u64,
+    /// Time the compiler processes have been running. Compilers are single-threaded,
+    /// so the time approximates the CPU time consumed.
+    pub compilation_time: Duration,
+    /// Total size of the sources passed to the compiler (in bytes)
+    pub bytes_compiled: u64,
+}
+
+impl CompilationUsage {
+    pub fn add(&mut self, other: &CompilationUsage) {
+        self.compiler_invocations += other.compiler_invocations;
+        self.compilation_time += other.compilation_time;
+        self.bytes_compiled += other.bytes_compiled;
+    }
+}
+
+tokio::task_local! {
+    static USAGE: RefCell<CompilationUsage>;
+}
+
+/// Runs the future and returns its output along with the resources consumed
+/// by the compilations it has run.
+pub async fn metered<F: Future>(future: F) -> (F::Output, CompilationUsage) {
+    USAGE
+        .scope(RefCell::new(CompilationUsage::default()), async move {
+            let output = future.await;
+            let usage = USAGE.with(|usage| *usage.borrow());
+            (output, usage)
+        })
+        .await
+}
+
+/// Adds the usage to the verification being metered. Is a no-op if the compilation
+/// is not run via [`metered`].
+pub(crate) fn record(usage: CompilationUsage) {
+    let _ = USAGE.try_with(|total| total.borrow_mut().add(&usage));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn invocation(bytes_compiled: u64) -> CompilationUsage {
+        CompilationUsage {
+            compiler_invocations: 1,
+            compilation_time: Duration::from_millis(100),
+            bytes_compiled,
+        }
+    }
+
+    #[tokio::test]
+    async fn usage_is_attributed_to_metered_futures() {
+        // Not metered compilations are ignored
+        record(invocation(1));
+
+        let (output, usage) = metered(async {
+            record(invocation(10));
+            tokio::task::yield_now().await;
+            record(invocation(20));
+            "output"
+        })
+        .await;
+        assert_eq!("output", output);
+        assert_eq!(
+            CompilationUsage {
+                compiler_invocations: 2,
+                compilation_time: Duration::from_millis(200),
+                bytes_compiled: 30,
+            },
+            usage
+        );
+
+        let (_, usage) = metered(async {}).await;
+        assert_eq!(CompilationUsage::default(), usage);
+    }
+}
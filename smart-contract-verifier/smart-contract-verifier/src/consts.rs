@@ -5,6 +5,11 @@ pub const DEFAULT_SOLIDITY_COMPILER_LIST: &str =
 pub const DEFAULT_SOLIDITY_COMPILER_LIST: &str =
     "https://solc-bin.ethereum.org/macosx-amd64/list.json";
 
+pub const DEFAULT_SOLIDITY_BUGS_LIST: &str =
+    "https://raw.githubusercontent.com/ethereum/solidity/develop/docs/bugs.json";
+pub const DEFAULT_SOLIDITY_BUGS_BY_VERSION_LIST: &str =
+    "https://raw.githubusercontent.com/ethereum/solidity/develop/docs/bugs_by_version.json";
+
 #[cfg(target_os = "linux")]
 pub const DEFAULT_VYPER_COMPILER_LIST: &str =
     "https://raw.githubusercontent.com/blockscout/solc-bin/main/vyper.list.json";
@@ -12,4 +17,11 @@ pub const DEFAULT_VYPER_COMPILER_LIST: &str =
 pub const DEFAULT_VYPER_COMPILER_LIST: &str =
     "https://raw.githubusercontent.com/blockscout/solc-bin/main/vyper.macos.list.json";
 
+#[cfg(target_os = "linux")]
+pub const DEFAULT_HUFF_COMPILER_LIST: &str =
+    "https://raw.githubusercontent.com/blockscout/solc-bin/main/huff.list.json";
+#[cfg(target_os = "macos")]
+pub const DEFAULT_HUFF_COMPILER_LIST: &str =
+    "https://raw.githubusercontent.com/blockscout/solc-bin/main/huff.macos.list.json";
+
 pub const DEFAULT_SOURCIFY_HOST: &str = "https://sourcify.dev/server/";
@@ -0,0 +1,197 @@
+//! Decompilation of unverified bytecode into pseudo-Solidity, so that explorers
+//! could show something meaningful for the contracts nobody has verified.
+//!
+//! Decompilers are pluggable via the [`Decompiler`] trait. [`HeimdallDecompiler`]
+//! shells out to the [heimdall-rs](https://github.com/Jon-Becker/heimdall-rs) binary,
+//! and [`CachedDecompiler`] keeps the results for the most recently requested bytecodes,
+//! as decompilation is expensive and the same contracts are requested over and over.
+
+use async_trait::async_trait;
+use sha3::{Digest, Keccak256};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    process::Stdio,
+    time::Duration,
+};
+use thiserror::Error;
+use tokio::process::Command;
+
+#[derive(Error, Debug)]
+pub enum DecompilationError {
+    #[error("bytecode is empty")]
+    EmptyBytecode,
+    #[error("decompilation has not finished in {0:?}")]
+    Timeout(Duration),
+    #[error("decompilation failed: {0}")]
+    Failed(String),
+    #[error("internal error: {0:#}")]
+    Internal(#[from] anyhow::Error),
+}
+
+#[async_trait]
+pub trait Decompiler: Send + Sync {
+    /// Returns pseudo-Solidity source reconstructed from the deployed bytecode
+    async fn decompile(&self, bytecode: &[u8]) -> Result<String, DecompilationError>;
+}
+
+pub struct HeimdallDecompiler {
+    path: PathBuf,
+    timeout: Duration,
+}
+
+impl HeimdallDecompiler {
+    /// Name of the file heimdall writes the reconstructed source into
+    const OUTPUT_FILE: &'static str = "decompiled.sol";
+
+    pub fn new(path: PathBuf, timeout: Duration) -> Self {
+        Self { path, timeout }
+    }
+}
+
+#[async_trait]
+impl Decompiler for HeimdallDecompiler {
+    async fn decompile(&self, bytecode: &[u8]) -> Result<String, DecompilationError> {
+        if bytecode.is_empty() {
+            return Err(DecompilationError::EmptyBytecode);
+        }
+        let output_dir = tempfile::tempdir().map_err(anyhow::Error::from)?;
+
+        let process = Command::new(&self.path)
+            .arg("decompile")
+            .arg(format!("0x{}", hex::encode(bytecode)))
+            .arg("--include-sol")
+            .arg("--default")
+            .arg("--output")
+            .arg(output_dir.path())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| anyhow::anyhow!("failed to run {:?}: {err}", self.path))?;
+        let output = tokio::time::timeout(self.timeout, process.wait_with_output())
+            .await
+            .map_err(|_| DecompilationError::Timeout(self.timeout))?
+            .map_err(anyhow::Error::from)?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(DecompilationError::Failed(stderr.trim().to_string()));
+        }
+
+        tokio::fs::read_to_string(output_dir.path().join(Self::OUTPUT_FILE))
+            .await
+            .map_err(|err| {
+                DecompilationError::Failed(format!("decompiled source was not produced: {err}"))
+            })
+    }
+}
+
+/// Keeps at most `capacity` sources, evicting the ones added the earliest.
+/// Only successful decompilations are cached.
+pub struct CachedDecompiler<D> {
+    inner: D,
+    capacity: usize,
+    cache: parking_lot::Mutex<Cache>,
+}
+
+#[derive(Default)]
+struct Cache {
+    sources: HashMap<[u8; 32], String>,
+    insertion_order: VecDeque<[u8; 32]>,
+}
+
+impl<D> CachedDecompiler<D> {
+    pub fn new(inner: D, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity,
+            cache: Default::default(),
+        }
+    }
+
+    fn get(&self, key: &[u8; 32]) -> Option<String> {
+        self.cache.lock().sources.get(key).cloned()
+    }
+
+    fn insert(&self, key: [u8; 32], source: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut cache = self.cache.lock();
+        if cache.sources.insert(key, source).is_none() {
+            cache.insertion_order.push_back(key);
+        }
+        while cache.insertion_order.len() > self.capacity {
+            if let Some(evicted) = cache.insertion_order.pop_front() {
+                cache.sources.remove(&evicted);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<D: Decompiler> Decompiler for CachedDecompiler<D> {
+    async fn decompile(&self, bytecode: &[u8]) -> Result<String, DecompilationError> {
+        let key: [u8; 32] = Keccak256::digest(bytecode).into();
+        if let Some(source) = self.get(&key) {
+            return Ok(source);
+        }
+        let source = self.inner.decompile(bytecode).await?;
+        self.insert(key, source.clone());
+        Ok(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingDecompiler {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Decompiler for CountingDecompiler {
+        async fn decompile(&self, bytecode: &[u8]) -> Result<String, DecompilationError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match bytecode {
+                [] => Err(DecompilationError::EmptyBytecode),
+                _ => Ok(format!("contract C{} {{}}", hex::encode(bytecode))),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn decompilations_are_cached() {
+        let decompiler = CachedDecompiler::new(CountingDecompiler::default(), 2);
+        let calls = || decompiler.inner.calls.load(Ordering::SeqCst);
+
+        assert_eq!(
+            "contract C01 {}",
+            decompiler.decompile(&[0x01]).await.unwrap()
+        );
+        assert_eq!(
+            "contract C01 {}",
+            decompiler.decompile(&[0x01]).await.unwrap()
+        );
+        assert_eq!(1, calls());
+
+        // Failures are not cached
+        assert!(decompiler.decompile(&[]).await.is_err());
+        assert!(decompiler.decompile(&[]).await.is_err());
+        assert_eq!(3, calls());
+
+        // The earliest added source is evicted
+        decompiler.decompile(&[0x02]).await.unwrap();
+        decompiler.decompile(&[0x03]).await.unwrap();
+        assert_eq!(5, calls());
+        decompiler.decompile(&[0x03]).await.unwrap();
+        assert_eq!(5, calls());
+        decompiler.decompile(&[0x01]).await.unwrap();
+        assert_eq!(6, calls());
+    }
+}
@@ -0,0 +1,116 @@
+use ethers_solc::{artifacts::GasEstimates, CompilerOutput};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Compiler outputs not required for verification itself,
+/// but used by explorers to display the verified contract.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExtraOutputs {
+    /// Hex encoded selectors by the method signatures
+    pub method_identifiers: BTreeMap<String, String>,
+    /// Storage layout in the format of Solidity compiler
+    pub storage_layout: Option<Value>,
+    /// Estimated gas costs of the contract creation and its functions
+    pub gas_estimates: Option<GasEstimates>,
+}
+
+/// Returns method identifiers, storage layout, and gas estimates of the contract.
+/// Outputs which were not requested from the compiler are missing.
+pub fn extract_extra_outputs(
+    compiler_output: &CompilerOutput,
+    file_path: &str,
+    contract_name: &str,
+) -> Option<ExtraOutputs> {
+    let contract = compiler_output
+        .contracts
+        .get(file_path)
+        .and_then(|contracts| contracts.get(contract_name))?;
+
+    let method_identifiers = contract
+        .evm
+        .as_ref()
+        .map(|evm| evm.method_identifiers.clone())
+        .unwrap_or_default();
+    let gas_estimates = contract
+        .evm
+        .as_ref()
+        .and_then(|evm| evm.gas_estimates.clone());
+    let layout = &contract.storage_layout;
+    let storage_layout = (!layout.storage.is_empty() || !layout.types.is_empty())
+        .then(|| serde_json::to_value(layout).ok())
+        .flatten();
+    Some(ExtraOutputs {
+        method_identifiers,
+        storage_layout,
+        gas_estimates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn extracts_outputs() {
+        let storage_layout = json!({
+            "storage": [{
+                "astId": 3,
+                "contract": "Token.sol:Token",
+                "label": "totalSupply",
+                "offset": 0,
+                "slot": "0",
+                "type": "t_uint256"
+            }],
+            "types": {
+                "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"}
+            }
+        });
+        let output: CompilerOutput = serde_json::from_value(json!({
+            "contracts": {
+                "Token.sol": {
+                    "Token": {
+                        "abi": [],
+                        "evm": {
+                            "methodIdentifiers": {"totalSupply()": "18160ddd"},
+                            "gasEstimates": {
+                                "creation": {
+                                    "codeDepositCost": "61200",
+                                    "executionCost": "infinite",
+                                    "totalCost": "infinite"
+                                },
+                                "external": {"totalSupply()": "2407"},
+                                "internal": {"_mint(address,uint256)": "infinite"}
+                            }
+                        },
+                        "storageLayout": storage_layout,
+                    },
+                    "Library": {"abi": []}
+                }
+            }
+        }))
+        .unwrap();
+
+        let outputs = extract_extra_outputs(&output, "Token.sol", "Token").unwrap();
+        assert_eq!(
+            BTreeMap::from([("totalSupply()".to_string(), "18160ddd".to_string())]),
+            outputs.method_identifiers
+        );
+        assert_eq!(Some(storage_layout), outputs.storage_layout);
+        let gas_estimates = outputs.gas_estimates.expect("gas estimates are missing");
+        assert_eq!("61200", gas_estimates.creation.code_deposit_cost);
+        assert_eq!("infinite", gas_estimates.creation.total_cost);
+        assert_eq!(
+            BTreeMap::from([("totalSupply()".to_string(), "2407".to_string())]),
+            gas_estimates.external
+        );
+
+        assert_eq!(
+            Some(ExtraOutputs::default()),
+            extract_extra_outputs(&output, "Token.sol", "Library"),
+            "outputs were not requested"
+        );
+        assert_eq!(None, extract_extra_outputs(&output, "Token.sol", "Unknown"));
+    }
+}
@@ -0,0 +1,112 @@
+//! Decoding of the hex encoded values submitted by the users (bytecodes,
+//! constructor arguments, etc.).
+//!
+//! The values are often copied from block explorers or pasted from the terminals,
+//! so the `0x` prefix is optional, digits may be of either case, and whitespace
+//! (including the newlines of wrapped values) is ignored anywhere in the value.
+
+use bytes::Bytes;
+use thiserror::Error;
+
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexError {
+    /// Position is counted in characters of the original value, including the prefix
+    #[error("invalid character {character:?} at position {position}")]
+    InvalidCharacter { character: char, position: usize },
+    /// Number of the hex digits, not counting the prefix and whitespace
+    #[error("odd number of hex digits ({digits})")]
+    OddLength { digits: usize },
+}
+
+/// Decodes the value in a single pass over its characters,
+/// without building the intermediate string of the digits.
+pub fn decode_hex(value: &str) -> Result<Bytes, HexError> {
+    let trimmed = value.trim_start();
+    let mut offset = value[..value.len() - trimmed.len()].chars().count();
+    let digits = match trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+    {
+        Some(digits) => {
+            offset += 2;
+            digits
+        }
+        None => trimmed,
+    };
+
+    let mut bytes = Vec::with_capacity(digits.len() / 2);
+    let mut high = None;
+    let mut count = 0;
+    for (position, character) in digits.chars().enumerate() {
+        if character.is_whitespace() {
+            continue;
+        }
+        let nibble = character.to_digit(16).ok_or(HexError::InvalidCharacter {
+            character,
+            position: offset + position,
+        })? as u8;
+        count += 1;
+        match high.take() {
+            None => high = Some(nibble),
+            Some(high) => bytes.push((high << 4) | nibble),
+        }
+    }
+    if high.is_some() {
+        return Err(HexError::OddLength { digits: count });
+    }
+
+    Ok(Bytes::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn prefix_whitespace_and_case() {
+        let expected = Bytes::from_static(&[0x60, 0x80, 0xab, 0xcd]);
+        for value in [
+            "6080abcd",
+            "0x6080abcd",
+            "0X6080ABCD",
+            "  0x6080\n  abcd\n",
+            "0x60 80\r\nAb\tCd",
+        ] {
+            assert_eq!(Ok(expected.clone()), decode_hex(value), "{value:?}");
+        }
+        assert_eq!(Ok(Bytes::new()), decode_hex(""));
+        assert_eq!(Ok(Bytes::new()), decode_hex(" 0x \n"));
+    }
+
+    #[test]
+    fn errors_are_positioned() {
+        assert_eq!(
+            Err(HexError::InvalidCharacter {
+                character: 'g',
+                position: 6
+            }),
+            decode_hex("0x6080g0")
+        );
+        // Whitespace and the prefix are counted
+        assert_eq!(
+            Err(HexError::InvalidCharacter {
+                character: 'x',
+                position: 8
+            }),
+            decode_hex("\n 0x60\n8x")
+        );
+        // Positions are counted in characters rather than bytes (non-breaking space is 2 bytes long)
+        assert_eq!(
+            Err(HexError::InvalidCharacter {
+                character: 'z',
+                position: 3
+            }),
+            decode_hex("6\u{a0}0z")
+        );
+        assert_eq!(
+            Err(HexError::OddLength { digits: 5 }),
+            decode_hex("0x608 0a")
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Module for compiling Huff contracts via `huffc` command line interface.
+//! `huffc` does not support standard json input, so each source file
+//! defining the `MAIN` macro is compiled separately, and resultant
+//! bytecodes are converted into [`CompilerOutput`].
+
+use crate::compiler::{EvmCompiler, SourcesDirs, Version};
+use ethers_solc::{
+    artifacts::{Contract, Severity},
+    error::{SolcError, SolcIoError},
+    CompilerInput, CompilerOutput,
+};
+use std::{collections::BTreeMap, path::Path, process::Stdio};
+use tokio::process::Command;
+
+#[derive(Default)]
+pub struct HuffCompiler {
+    sources_dirs: SourcesDirs,
+}
+
+impl HuffCompiler {
+    pub fn new() -> Self {
+        HuffCompiler::default()
+    }
+
+    /// Directories the sources are written to, as huffc reads them from the disk only
+    pub fn with_sources_dirs(mut self, sources_dirs: SourcesDirs) -> Self {
+        self.sources_dirs = sources_dirs;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl EvmCompiler for HuffCompiler {
+    async fn compile(
+        &self,
+        path: &Path,
+        _ver: &Version,
+        input: &CompilerInput,
+    ) -> Result<CompilerOutput, SolcError> {
+        compile_using_cli(path, input, &self.sources_dirs).await
+    }
+}
+
+/// Only files defining the `MAIN` macro may be compiled into contracts.
+/// Others are expected to be included by them.
+fn is_main_file(content: &str) -> bool {
+    content.lines().any(|line| {
+        let mut tokens = line.split_whitespace();
+        tokens.next() == Some("#define")
+            && tokens.next() == Some("macro")
+            && tokens
+                .next()
+                .map_or(false, |name| name.split('(').next() == Some("MAIN"))
+    })
+}
+
+/// Runs `huffc` with the provided flag and returns the printed bytecode
+/// (or the error message printed by the compiler).
+async fn run_huffc(
+    huffc: &Path,
+    file_path: &Path,
+    flag: &str,
+    evm_version: Option<String>,
+) -> Result<Result<String, String>, SolcError> {
+    let mut command = Command::new(huffc);
+    // The compiler should not outlive the cancelled compilation and its sources directory
+    command.arg(file_path).arg(flag).kill_on_drop(true);
+    if let Some(evm_version) = evm_version {
+        command.arg("--evm-version").arg(evm_version);
+    }
+    let output = command
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .output()
+        .await
+        .map_err(|err| SolcError::Io(SolcIoError::new(err, huffc)))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let bytecode = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .last()
+        .map(|line| line.trim_start_matches("0x"))
+        .filter(|line| hex::decode(line).is_ok());
+    match bytecode {
+        Some(bytecode) if output.status.success() => Ok(Ok(bytecode.to_string())),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = if stderr.trim().is_empty() {
+                stdout.to_string()
+            } else {
+                stderr.to_string()
+            };
+            Ok(Err(message))
+        }
+    }
+}
+
+fn contract(bytecode: String, deployed_bytecode: String) -> Result<Contract, SolcError> {
+    let contract = serde_json::json!({
+        "evm": {
+            "bytecode": {
+                "object": bytecode,
+            },
+            "deployedBytecode": {
+                "object": deployed_bytecode,
+            },
+        },
+    });
+    Ok(serde_json::from_value(contract)?)
+}
+
+fn compiler_error(message: String) -> ethers_solc::artifacts::Error {
+    ethers_solc::artifacts::Error {
+        source_location: None,
+        secondary_source_locations: vec![],
+        r#type: "".to_string(),
+        component: "".to_string(),
+        severity: Severity::Error,
+        error_code: None,
+        message,
+        formatted_message: None,
+    }
+}
+
+pub async fn compile_using_cli(
+    huffc: &Path,
+    input: &CompilerInput,
+    sources_dirs: &SourcesDirs,
+) -> Result<CompilerOutput, SolcError> {
+    let files_dir = sources_dirs.write(input).await?;
+    let evm_version = input
+        .settings
+        .evm_version
+        .map(|version| version.to_string());
+
+    let mut errors = Vec::new();
+    let mut contracts = BTreeMap::new();
+    for (name, source) in input.sources.iter() {
+        if !is_main_file(&source.content) {
+            continue;
+        }
+        let file_path = files_dir.path().join(name);
+        let bytecode = run_huffc(huffc, &file_path, "--bytecode", evm_version.clone()).await?;
+        let deployed_bytecode =
+            run_huffc(huffc, &file_path, "--bin-runtime", evm_version.clone()).await?;
+        match (bytecode, deployed_bytecode) {
+            (Ok(bytecode), Ok(deployed_bytecode)) => {
+                let contract_name = contract_name(name);
+                let contract = contract(bytecode, deployed_bytecode)?;
+                contracts.insert(
+                    name.to_string_lossy().to_string(),
+                    BTreeMap::from([(contract_name, contract)]),
+                );
+            }
+            (Err(message), _) | (_, Err(message)) => errors.push(compiler_error(message)),
+        }
+    }
+    if contracts.is_empty() && errors.is_empty() {
+        errors.push(compiler_error(
+            "none of the files defines the MAIN macro".to_string(),
+        ));
+    }
+
+    Ok(CompilerOutput {
+        errors,
+        sources: BTreeMap::new(),
+        contracts,
+    })
+}
+
+/// Huff does not have named contracts, so the file name is used instead
+fn contract_name(file_path: &Path) -> String {
+    file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn main_files() {
+        assert!(is_main_file(
+            "#include \"./Utils.huff\"\n#define macro MAIN() = takes(0) returns(0) {\n  0x00 dup1 revert\n}"
+        ));
+        assert!(is_main_file(
+            "#define  macro MAIN ( ) = takes(0) returns(0) {}"
+        ));
+        assert!(!is_main_file(
+            "#define macro MAIN_HELPER() = takes(0) returns(0) {}"
+        ));
+        assert!(!is_main_file(
+            "#define macro CONSTRUCTOR() = takes(0) returns(0) {}"
+        ));
+    }
+
+    #[test]
+    fn contract_names() {
+        assert_eq!("ERC20", contract_name(&PathBuf::from("src/ERC20.huff")));
+        assert_eq!("Main", contract_name(&PathBuf::from("Main.huff")));
+    }
+}
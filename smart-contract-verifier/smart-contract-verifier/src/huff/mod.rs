@@ -0,0 +1,7 @@
+mod client;
+mod compiler;
+
+pub mod multi_part;
+
+pub use client::Client;
+pub use compiler::HuffCompiler;
@@ -0,0 +1,174 @@
+use super::client::Client;
+use crate::{
+    compiler::Version,
+    verifier::{ContractVerifier, Error, Success},
+};
+use bytes::Bytes;
+use ethers_solc::{
+    artifacts::{Settings, Source, Sources},
+    CompilerInput, EvmVersion,
+};
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationRequest {
+    pub deployed_bytecode: Bytes,
+    pub creation_bytecode: Option<Bytes>,
+    pub compiler_version: Version,
+
+    pub content: MultiFileContent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiFileContent {
+    pub sources: BTreeMap<PathBuf, String>,
+    pub evm_version: Option<EvmVersion>,
+}
+
+impl From<MultiFileContent> for CompilerInput {
+    fn from(content: MultiFileContent) -> Self {
+        let mut settings = Settings::default();
+        settings.optimizer.enabled = None;
+        settings.optimizer.runs = None;
+        // huffc uses its own default evm version if none is specified
+        settings.evm_version = content.evm_version;
+
+        let sources: Sources = content
+            .sources
+            .into_iter()
+            .map(|(name, content)| (name, Source::new(content)))
+            .collect();
+        CompilerInput {
+            language: "Huff".to_string(),
+            sources,
+            settings,
+        }
+    }
+}
+
+pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result<Success, Error> {
+    let compiler_input = CompilerInput::from(request.content);
+    let verifier = ContractVerifier::new(
+        client.compilers(),
+        &request.compiler_version,
+        request.creation_bytecode,
+        request.deployed_bytecode,
+        vec![],
+        false,
+        Default::default(),
+    )?;
+
+    // If case of success, we allow middlewares to process success and only then return it to the caller;
+    // Otherwise, we just return an error
+    let success = verifier.verify(&compiler_input).await?;
+    if let Some(middleware) = client.middleware() {
+        middleware.call(&success).await;
+    }
+    Ok(success)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compiler::{Compilers, FetchError, Fetcher},
+        huff::HuffCompiler,
+        DisplayBytes, MatchType,
+    };
+    use pretty_assertions::assert_eq;
+    use std::{os::unix::fs::PermissionsExt, path::Path, str::FromStr};
+    use tokio::sync::Semaphore;
+
+    const COMPILER_VERSION: &str = "0.3.2+commit.cbd9dd1d";
+    /// Returns 42 on any call
+    const DEPLOYED_BYTECODE: &str = "0x602a60005260206000f3";
+    /// Deploys the code above
+    const CREATION_BYTECODE: &str = "0x600a8060093d393df3602a60005260206000f3";
+
+    /// Provides the script printing the bytecodes the way `huffc` does
+    struct ScriptFetcher {
+        huffc: PathBuf,
+    }
+
+    impl ScriptFetcher {
+        fn new(dir: &Path) -> Self {
+            let huffc = dir.join("huffc");
+            let script = format!(
+                "#!/bin/sh\ncase \"$2\" in\n  --bytecode) echo {CREATION_BYTECODE} ;;\n  \
+                 --bin-runtime) echo {DEPLOYED_BYTECODE} ;;\n  *) exit 1 ;;\nesac\n"
+            );
+            std::fs::write(&huffc, script).expect("script writing failed");
+            std::fs::set_permissions(&huffc, std::fs::Permissions::from_mode(0o755))
+                .expect("script permissions update failed");
+            Self { huffc }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Fetcher for ScriptFetcher {
+        async fn fetch(&self, ver: &Version) -> Result<PathBuf, FetchError> {
+            match ver == &Version::from_str(COMPILER_VERSION).unwrap() {
+                true => Ok(self.huffc.clone()),
+                false => Err(FetchError::NotFound(ver.clone())),
+            }
+        }
+
+        fn all_versions(&self) -> Vec<Version> {
+            vec![Version::from_str(COMPILER_VERSION).unwrap()]
+        }
+    }
+
+    async fn verify_bytecode(
+        creation_bytecode: Option<&str>,
+        deployed_bytecode: &str,
+    ) -> Result<Success, Error> {
+        let dir = tempfile::tempdir().expect("temp dir creation failed");
+        let compilers = Compilers::new(
+            Arc::new(ScriptFetcher::new(dir.path())),
+            HuffCompiler::new(),
+            Arc::new(Semaphore::new(1)),
+        );
+        let bytes = |bytecode: &str| DisplayBytes::from_str(bytecode).unwrap().0;
+        let request = VerificationRequest {
+            deployed_bytecode: bytes(deployed_bytecode),
+            creation_bytecode: creation_bytecode.map(bytes),
+            compiler_version: Version::from_str(COMPILER_VERSION).unwrap(),
+            content: MultiFileContent {
+                sources: BTreeMap::from([(
+                    PathBuf::from("src/Answer.huff"),
+                    "#define macro MAIN() = takes(0) returns(0) {\n  0x2a 0x00 mstore 0x20 0x00 return\n}"
+                        .to_string(),
+                )]),
+                evm_version: None,
+            },
+        };
+        verify(Arc::new(Client::new(compilers)), request).await
+    }
+
+    #[tokio::test]
+    async fn verify_deployed_bytecode() {
+        let success = verify_bytecode(None, DEPLOYED_BYTECODE)
+            .await
+            .expect("verification failed");
+        assert_eq!("Answer", success.contract_name);
+        assert_eq!("src/Answer.huff", success.file_path);
+        assert_eq!(MatchType::Full, success.match_type);
+    }
+
+    #[tokio::test]
+    async fn verify_creation_bytecode() {
+        let success = verify_bytecode(Some(CREATION_BYTECODE), "0x")
+            .await
+            .expect("verification failed");
+        assert_eq!(MatchType::Full, success.match_type);
+    }
+
+    #[tokio::test]
+    async fn other_bytecode_is_not_verified() {
+        let result = verify_bytecode(None, "0x602b60005260206000f3").await;
+        assert!(
+            matches!(result, Err(Error::NoMatchingContracts(_))),
+            "invalid result: {result:?}"
+        );
+    }
+}
@@ -1,14 +1,26 @@
+pub mod address_format;
+pub mod analyzer;
+pub mod chains;
+pub mod decompiler;
+pub mod huff;
 pub mod solidity;
 pub mod sourcify;
 pub mod vyper;
 
 pub mod middleware;
 
+mod builder;
 mod common_types;
 mod compiler;
 mod consts;
+mod extra_outputs;
+mod hex_input;
 mod metrics;
+mod natspec;
+mod risk_flags;
 mod scheduler;
+mod source_path;
+mod standards;
 mod verifier;
 
 #[cfg(test)]
@@ -17,16 +29,40 @@ mod tests;
 pub(crate) use blockscout_display_bytes::Bytes as DisplayBytes;
 
 pub use consts::{
+    DEFAULT_HUFF_COMPILER_LIST, DEFAULT_SOLIDITY_BUGS_BY_VERSION_LIST, DEFAULT_SOLIDITY_BUGS_LIST,
     DEFAULT_SOLIDITY_COMPILER_LIST, DEFAULT_SOURCIFY_HOST, DEFAULT_VYPER_COMPILER_LIST,
 };
 
 pub use middleware::Middleware;
 
+pub use chains::{Chain, ChainRegistry};
+pub use decompiler::{CachedDecompiler, DecompilationError, Decompiler, HeimdallDecompiler};
+
+pub use builder::{Verifier, VerifierBuilder};
 pub use common_types::MatchType;
-pub use compiler::{Compilers, Fetcher, ListFetcher, S3Fetcher, Version};
+pub use compiler::{
+    compilations_in_queue, expand_download_url, input_hash, metered, CachedCompiler,
+    CompilationLimits, CompilationUsage, Compilers, Downloader, EvmCompiler, Fetcher, Limit,
+    LimitExceeded, ListFetcher, MockCompiler, MockFetcher, S3Fetcher, SourcesDir, SourcesDirs,
+    Version, DEFAULT_MAX_PARALLEL_DOWNLOADS,
+};
+pub use extra_outputs::{extract_extra_outputs, ExtraOutputs};
+pub use hex_input::{decode_hex, HexError};
+pub use natspec::{extract_natspec, NatSpec};
+pub use risk_flags::{extract_risk_flags, RiskFlags};
+pub use source_path::{validate_source_path, SourcePathError};
 pub use sourcify::{Error as SourcifyError, Success as SourcifySuccess};
-pub use verifier::{BytecodePart, Error as VerificationError, Success as VerificationSuccess};
+pub use standards::{detect_standards, Standard};
+pub use verifier::{
+    BytecodePart, Error as VerificationError, EvmFork, Normalization, NormalizationPass,
+    PatternPass, Success as VerificationSuccess, Trace, TraceStage, TraceStep,
+    VerificationErrorKind,
+};
 
-pub use solidity::{Client as SolidityClient, SolcValidator, SolidityCompiler};
+pub use huff::{Client as HuffClient, HuffCompiler};
+pub use solidity::{
+    backend::{ProcessPoolBackend, SolcBackend, SolcBackendKind},
+    Client as SolidityClient, SolcValidator, SolidityCompiler,
+};
 pub use sourcify::SourcifyApiClient;
 pub use vyper::{Client as VyperClient, VyperCompiler};
@@ -40,6 +40,11 @@ lazy_static! {
         "number of compilations in queue",
     )
     .unwrap();
+    pub static ref COMPILATIONS_COALESCED: IntCounter = register_int_counter!(
+        "smart_contract_verifier_compiles_coalesced",
+        "number of compilations which waited for an identical compilation in flight",
+    )
+    .unwrap();
 }
 
 pub struct GaugeGuard(&'static Gauge);
@@ -0,0 +1,102 @@
+use ethers_solc::CompilerOutput;
+use serde_json::Value;
+
+/// NatSpec documentation of the compiled contract
+/// (https://docs.soliditylang.org/en/latest/natspec-format.html).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NatSpec {
+    pub userdoc: Option<Value>,
+    pub devdoc: Option<Value>,
+}
+
+/// Returns `userdoc` and `devdoc` outputs of the contract. Documentation which
+/// contains nothing except its `kind` and `version` is considered missing.
+pub fn extract_natspec(
+    compiler_output: &CompilerOutput,
+    file_path: &str,
+    contract_name: &str,
+) -> Option<NatSpec> {
+    let contract = compiler_output
+        .contracts
+        .get(file_path)
+        .and_then(|contracts| contracts.get(contract_name))?;
+
+    let natspec = NatSpec {
+        userdoc: serde_json::to_value(&contract.userdoc)
+            .ok()
+            .and_then(non_empty),
+        devdoc: serde_json::to_value(&contract.devdoc)
+            .ok()
+            .and_then(non_empty),
+    };
+    (natspec != NatSpec::default()).then_some(natspec)
+}
+
+fn non_empty(doc: Value) -> Option<Value> {
+    let has_content = doc.as_object().map_or(false, |object| {
+        object.iter().any(|(key, value)| {
+            let is_empty = match value {
+                Value::Null => true,
+                Value::Object(object) => object.is_empty(),
+                Value::Array(array) => array.is_empty(),
+                _ => false,
+            };
+            key != "kind" && key != "version" && !is_empty
+        })
+    });
+    has_content.then_some(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn compiler_output(userdoc: Value, devdoc: Value) -> CompilerOutput {
+        serde_json::from_value(json!({
+            "contracts": {
+                "Token.sol": {
+                    "Token": {
+                        "abi": [],
+                        "userdoc": userdoc,
+                        "devdoc": devdoc,
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn extracts_documentation() {
+        let output = compiler_output(
+            json!({
+                "kind": "user",
+                "version": 1,
+                "methods": {"transfer(address,uint256)": {"notice": "Moves tokens"}},
+                "notice": "A simple token"
+            }),
+            json!({"kind": "dev", "version": 1, "methods": {}}),
+        );
+
+        let natspec = extract_natspec(&output, "Token.sol", "Token").expect("natspec is missing");
+        let userdoc = natspec.userdoc.expect("userdoc is missing");
+        assert_eq!(
+            json!("Moves tokens"),
+            userdoc["methods"]["transfer(address,uint256)"]["notice"]
+        );
+        assert_eq!(json!("A simple token"), userdoc["notice"]);
+        assert_eq!(None, natspec.devdoc, "empty devdoc should be omitted");
+    }
+
+    #[test]
+    fn missing_documentation() {
+        let output = compiler_output(
+            json!({"kind": "user", "version": 1, "methods": {}}),
+            json!({"kind": "dev", "version": 1, "methods": {}}),
+        );
+        assert_eq!(None, extract_natspec(&output, "Token.sol", "Token"));
+        assert_eq!(None, extract_natspec(&output, "Token.sol", "Unknown"));
+    }
+}
@@ -0,0 +1,280 @@
+use ethers_solc::CompilerOutput;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Potentially dangerous constructs used by the verified contract, detected via source ASTs.
+/// Explorers may show them as risk indicators without running a separate analyzer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RiskFlags {
+    /// `selfdestruct` (or deprecated `suicide`) is called, either directly or from assembly
+    pub selfdestruct: bool,
+    /// `delegatecall` is called, either as an address member or from assembly
+    pub delegatecall: bool,
+    /// `tx.origin` is compared with something other than `msg.sender`,
+    /// which usually means it is used for authorization
+    pub tx_origin_auth: bool,
+    /// The contract contains inline assembly blocks
+    pub inline_assembly: bool,
+}
+
+/// Returns the risk flags of the contract. Only the definitions of the contract and
+/// the contracts it inherits from are analyzed. Returns `None` if source ASTs have
+/// not been requested from the compiler, or the contract could not be found in them
+/// (e.g., for Vyper contracts).
+pub fn extract_risk_flags(
+    compiler_output: &CompilerOutput,
+    file_path: &str,
+    contract_name: &str,
+) -> Option<RiskFlags> {
+    let asts: BTreeMap<&str, Value> = compiler_output
+        .sources
+        .iter()
+        .filter_map(|(path, source)| {
+            let ast = serde_json::to_value(source.ast.as_ref()?).ok()?;
+            Some((path.as_str(), ast))
+        })
+        .collect();
+
+    let base_contracts: BTreeSet<u64> = asts
+        .get(file_path)?
+        .get("nodes")
+        .and_then(|nodes| nodes.as_array())?
+        .iter()
+        .find(|node| {
+            node_type(node) == Some("ContractDefinition")
+                && node.get("name").and_then(|name| name.as_str()) == Some(contract_name)
+        })?
+        .get("linearizedBaseContracts")
+        .and_then(|ids| ids.as_array())?
+        .iter()
+        .filter_map(|id| id.as_u64())
+        .collect();
+
+    let mut flags = RiskFlags::default();
+    for ast in asts.values() {
+        let contracts = ast
+            .get("nodes")
+            .and_then(|nodes| nodes.as_array())
+            .into_iter()
+            .flatten()
+            .filter(|node| {
+                node_type(node) == Some("ContractDefinition")
+                    && node
+                        .get("id")
+                        .and_then(|id| id.as_u64())
+                        .map_or(false, |id| base_contracts.contains(&id))
+            });
+        for contract in contracts {
+            collect_flags(contract, &mut flags);
+        }
+    }
+    Some(flags)
+}
+
+fn node_type(node: &Value) -> Option<&str> {
+    node.get("nodeType").and_then(|value| value.as_str())
+}
+
+fn name(node: &Value) -> Option<&str> {
+    node.get("name").and_then(|value| value.as_str())
+}
+
+/// Whether the node is the `<expression>.<member_name>` access
+/// with the expression being an identifier named `identifier`
+fn is_member_access(node: &Value, identifier: &str, member_name: &str) -> bool {
+    node_type(node) == Some("MemberAccess")
+        && node.get("memberName").and_then(|value| value.as_str()) == Some(member_name)
+        && node.get("expression").map_or(false, |expression| {
+            node_type(expression) == Some("Identifier") && name(expression) == Some(identifier)
+        })
+}
+
+fn collect_flags(node: &Value, flags: &mut RiskFlags) {
+    match node {
+        Value::Object(object) => {
+            match node_type(node) {
+                Some("Identifier") if matches!(name(node), Some("selfdestruct" | "suicide")) => {
+                    flags.selfdestruct = true
+                }
+                Some("MemberAccess")
+                    if node.get("memberName").and_then(|value| value.as_str())
+                        == Some("delegatecall") =>
+                {
+                    flags.delegatecall = true
+                }
+                Some("BinaryOperation")
+                    if matches!(
+                        node.get("operator").and_then(|value| value.as_str()),
+                        Some("==" | "!=")
+                    ) =>
+                {
+                    let operands = [node.get("leftExpression"), node.get("rightExpression")];
+                    let is_tx_origin = |operand: &Option<&Value>| {
+                        operand.map_or(false, |operand| is_member_access(operand, "tx", "origin"))
+                    };
+                    let is_msg_sender = |operand: &Option<&Value>| {
+                        operand.map_or(false, |operand| is_member_access(operand, "msg", "sender"))
+                    };
+                    // `msg.sender == tx.origin` checks the caller is not a contract
+                    if operands.iter().any(is_tx_origin) && !operands.iter().any(is_msg_sender) {
+                        flags.tx_origin_auth = true
+                    }
+                }
+                Some("InlineAssembly") => flags.inline_assembly = true,
+                Some("YulFunctionCall") => {
+                    let function_name = node.get("functionName").and_then(name);
+                    match function_name {
+                        Some("selfdestruct") => flags.selfdestruct = true,
+                        Some("delegatecall") => flags.delegatecall = true,
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+            object
+                .values()
+                .for_each(|value| collect_flags(value, flags));
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_flags(value, flags)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn member_access(identifier: &str, member_name: &str) -> Value {
+        json!({
+            "nodeType": "MemberAccess",
+            "memberName": member_name,
+            "expression": {"nodeType": "Identifier", "name": identifier}
+        })
+    }
+
+    fn comparison(left: Value, right: Value) -> Value {
+        json!({
+            "nodeType": "BinaryOperation",
+            "src": "0:0:0",
+            "operator": "==",
+            "leftExpression": left,
+            "rightExpression": right
+        })
+    }
+
+    fn compiler_output() -> CompilerOutput {
+        serde_json::from_value(json!({
+            "sources": {
+                "Proxy.sol": {
+                    "id": 0,
+                    "ast": {
+                        "nodeType": "SourceUnit",
+                        "id": 100,
+                        "src": "0:0:0",
+                        "absolutePath": "Proxy.sol",
+                        "nodes": [
+                            {
+                                "nodeType": "ContractDefinition",
+                                "id": 1,
+                                "src": "0:0:0",
+                                "name": "Proxy",
+                                "linearizedBaseContracts": [1, 2],
+                                "nodes": [{
+                                    "nodeType": "InlineAssembly",
+                                    "src": "0:0:0",
+                                    "AST": {
+                                        "nodeType": "YulBlock",
+                                        "statements": [{
+                                            "nodeType": "YulFunctionCall",
+                                            "functionName": {"nodeType": "YulIdentifier", "name": "delegatecall"},
+                                            "arguments": []
+                                        }]
+                                    }
+                                }]
+                            },
+                            {
+                                "nodeType": "ContractDefinition",
+                                "id": 3,
+                                "src": "0:0:0",
+                                "name": "Unrelated",
+                                "linearizedBaseContracts": [3],
+                                "nodes": [{
+                                    "nodeType": "FunctionCall",
+                                    "src": "0:0:0",
+                                    "expression": {"nodeType": "Identifier", "name": "selfdestruct"}
+                                }]
+                            }
+                        ]
+                    }
+                },
+                "Ownable.sol": {
+                    "id": 1,
+                    "ast": {
+                        "nodeType": "SourceUnit",
+                        "id": 101,
+                        "src": "0:0:1",
+                        "absolutePath": "Ownable.sol",
+                        "nodes": [{
+                            "nodeType": "ContractDefinition",
+                            "id": 2,
+                            "src": "0:0:1",
+                            "name": "Ownable",
+                            "linearizedBaseContracts": [2],
+                            "nodes": [
+                                comparison(member_access("tx", "origin"), json!({"nodeType": "Identifier", "name": "owner"})),
+                                comparison(member_access("msg", "sender"), member_access("tx", "origin"))
+                            ]
+                        }]
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn extracts_flags_of_contract_and_its_bases() {
+        let output = compiler_output();
+        let expected = RiskFlags {
+            selfdestruct: false,
+            delegatecall: true,
+            tx_origin_auth: true,
+            inline_assembly: true,
+        };
+        assert_eq!(
+            Some(expected),
+            extract_risk_flags(&output, "Proxy.sol", "Proxy")
+        );
+
+        let expected = RiskFlags {
+            selfdestruct: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Some(expected),
+            extract_risk_flags(&output, "Proxy.sol", "Unrelated")
+        );
+
+        assert_eq!(None, extract_risk_flags(&output, "Proxy.sol", "Unknown"));
+        assert_eq!(
+            None,
+            extract_risk_flags(&CompilerOutput::default(), "Proxy.sol", "Proxy"),
+            "asts were not requested"
+        );
+    }
+
+    #[test]
+    fn sender_is_not_a_contract_check_is_not_auth() {
+        let mut flags = RiskFlags::default();
+        collect_flags(
+            &comparison(
+                member_access("tx", "origin"),
+                member_access("msg", "sender"),
+            ),
+            &mut flags,
+        );
+        assert_eq!(RiskFlags::default(), flags);
+    }
+}
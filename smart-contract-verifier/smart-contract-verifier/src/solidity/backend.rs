@@ -0,0 +1,360 @@
+//! Backends used to invoke solc with the standard json input.
+//!
+//! The verification pipeline depends only on the standard json input and output types,
+//! so the crate invoking the compiler (`ethers-solc` is deprecated in favour of
+//! `foundry-compilers`) may be replaced by implementing [`SolcBackend`]
+//! without changes to the pipeline itself.
+
+use crate::compiler::MockCompiler;
+use ethers_solc::{
+    error::{SolcError, SolcIoError},
+    CompilerInput, CompilerOutput, Solc,
+};
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::Stdio,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::AsyncWriteExt,
+    process::{Child, Command},
+};
+
+#[async_trait::async_trait]
+pub trait SolcBackend: Send + Sync {
+    async fn compile(
+        &self,
+        solc: &Path,
+        input: &CompilerInput,
+    ) -> Result<CompilerOutput, SolcError>;
+}
+
+/// Compiles using `ethers_solc::Solc`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EthersSolcBackend;
+
+#[async_trait::async_trait]
+impl SolcBackend for EthersSolcBackend {
+    async fn compile(
+        &self,
+        solc: &Path,
+        input: &CompilerInput,
+    ) -> Result<CompilerOutput, SolcError> {
+        Solc::from(solc).async_compile(input).await
+    }
+}
+
+/// Runs `solc --standard-json` directly, passing the input via stdin.
+/// Unlike `ethers-solc`, reports the exit status and stderr of the failed
+/// compiler process, and kills the process if the compilation is cancelled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardJsonBackend;
+
+#[async_trait::async_trait]
+impl SolcBackend for StandardJsonBackend {
+    async fn compile(
+        &self,
+        solc: &Path,
+        input: &CompilerInput,
+    ) -> Result<CompilerOutput, SolcError> {
+        let child = spawn_standard_json(solc).map_err(|err| io_error(err, solc))?;
+        run_standard_json(child, solc, input).await
+    }
+}
+
+/// Runs `solc --standard-json` as [`StandardJsonBackend`] does, but keeps several processes
+/// per compiler already spawned and waiting for the input, so that the compilation does not
+/// wait for the compiler process to start. Is noticeable for small contracts, where
+/// starting the process takes a considerable part of the compilation time.
+///
+/// Solc reads the standard json input until stdin is closed and exits after
+/// the compilation, so each warm process serves a single compilation and is replaced
+/// right after it has been taken. Processes which have already exited, or which have been
+/// idle for longer than `max_idle_time`, are recycled instead of being used.
+pub struct ProcessPoolBackend {
+    warm_processes: usize,
+    max_idle_time: Duration,
+    pools: Mutex<HashMap<PathBuf, VecDeque<WarmProcess>>>,
+}
+
+struct WarmProcess {
+    child: Child,
+    spawned_at: Instant,
+}
+
+impl WarmProcess {
+    fn is_stale(&self, max_idle_time: Duration) -> bool {
+        self.spawned_at.elapsed() > max_idle_time
+    }
+}
+
+impl Default for ProcessPoolBackend {
+    fn default() -> Self {
+        Self::new(NonZeroUsize::new(2).unwrap())
+    }
+}
+
+impl ProcessPoolBackend {
+    pub fn new(warm_processes: NonZeroUsize) -> Self {
+        Self {
+            warm_processes: warm_processes.get(),
+            max_idle_time: Duration::from_secs(300),
+            pools: Default::default(),
+        }
+    }
+
+    pub fn with_max_idle_time(mut self, max_idle_time: Duration) -> Self {
+        self.max_idle_time = max_idle_time;
+        self
+    }
+
+    /// Number of warm processes currently kept for the compiler
+    pub fn warm_processes(&self, solc: &Path) -> usize {
+        self.pools
+            .lock()
+            .get(solc)
+            .map(VecDeque::len)
+            .unwrap_or_default()
+    }
+
+    /// Takes the oldest warm process which is still alive.
+    /// The exited and stale ones are dropped (and killed) along the way.
+    fn take(&self, solc: &Path) -> Option<Child> {
+        let mut pools = self.pools.lock();
+        let pool = pools.get_mut(solc)?;
+        while let Some(mut process) = pool.pop_front() {
+            if process.is_stale(self.max_idle_time) {
+                continue;
+            }
+            match process.child.try_wait() {
+                Ok(None) => return Some(process.child),
+                Ok(Some(status)) => {
+                    tracing::warn!(
+                        solc = %solc.display(),
+                        %status,
+                        "warm solc process has exited"
+                    )
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        solc = %solc.display(),
+                        "warm solc process health check failed: {err}"
+                    )
+                }
+            }
+        }
+        None
+    }
+
+    /// Spawns the processes missing up to `warm_processes` for the compiler.
+    /// Stale processes of all compilers are recycled as well, so that compilers
+    /// which are not used anymore do not keep their processes forever.
+    fn refill(&self, solc: &Path) {
+        let mut pools = self.pools.lock();
+        for pool in pools.values_mut() {
+            pool.retain(|process| !process.is_stale(self.max_idle_time));
+        }
+        pools.retain(|path, pool| !pool.is_empty() || path == solc);
+
+        let pool = pools.entry(solc.to_path_buf()).or_default();
+        while pool.len() < self.warm_processes {
+            match spawn_standard_json(solc) {
+                Ok(child) => pool.push_back(WarmProcess {
+                    child,
+                    spawned_at: Instant::now(),
+                }),
+                Err(err) => {
+                    tracing::warn!(
+                        solc = %solc.display(),
+                        "failed to spawn warm solc process: {err}"
+                    );
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SolcBackend for ProcessPoolBackend {
+    async fn compile(
+        &self,
+        solc: &Path,
+        input: &CompilerInput,
+    ) -> Result<CompilerOutput, SolcError> {
+        let child = match self.take(solc) {
+            Some(child) => child,
+            None => spawn_standard_json(solc).map_err(|err| io_error(err, solc))?,
+        };
+        self.refill(solc);
+        run_standard_json(child, solc, input).await
+    }
+}
+
+fn io_error(err: std::io::Error, solc: &Path) -> SolcError {
+    SolcError::Io(SolcIoError::new(err, solc))
+}
+
+fn spawn_standard_json(solc: &Path) -> std::io::Result<Child> {
+    Command::new(solc)
+        .arg("--standard-json")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+}
+
+async fn run_standard_json(
+    mut child: Child,
+    solc: &Path,
+    input: &CompilerInput,
+) -> Result<CompilerOutput, SolcError> {
+    let input = serde_json::to_vec(input)?;
+    {
+        // stdin must be closed for solc to start the compilation
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        stdin
+            .write_all(&input)
+            .await
+            .map_err(|err| io_error(err, solc))?;
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|err| io_error(err, solc))?;
+
+    if !output.status.success() {
+        return Err(SolcError::Message(format!(
+            "solc exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// Names of the available backends as used in the configuration
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SolcBackendKind {
+    #[default]
+    EthersSolc,
+    StandardJson,
+    StandardJsonPool,
+    /// Returns predetermined outputs instead of invoking the compiler (see [`MockCompiler`])
+    Mock,
+}
+
+impl SolcBackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SolcBackendKind::EthersSolc => "ethers-solc",
+            SolcBackendKind::StandardJson => "standard-json",
+            SolcBackendKind::StandardJsonPool => "standard-json-pool",
+            SolcBackendKind::Mock => "mock",
+        }
+    }
+
+    pub fn backend(&self) -> Box<dyn SolcBackend> {
+        match self {
+            SolcBackendKind::EthersSolc => Box::new(EthersSolcBackend),
+            SolcBackendKind::StandardJson => Box::new(StandardJsonBackend),
+            SolcBackendKind::StandardJsonPool => Box::new(ProcessPoolBackend::default()),
+            SolcBackendKind::Mock => Box::new(MockCompiler::default()),
+        }
+    }
+}
+
+impl fmt::Display for SolcBackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SolcBackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            SolcBackendKind::EthersSolc,
+            SolcBackendKind::StandardJson,
+            SolcBackendKind::StandardJsonPool,
+            SolcBackendKind::Mock,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == s)
+        .ok_or_else(|| format!("unknown solc backend: {s}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_backend_kind() {
+        assert_eq!(
+            Ok(SolcBackendKind::StandardJson),
+            SolcBackendKind::from_str("standard-json")
+        );
+        assert_eq!(
+            Ok(SolcBackendKind::EthersSolc),
+            SolcBackendKind::from_str("ethers-solc")
+        );
+        assert_eq!(
+            Ok(SolcBackendKind::StandardJsonPool),
+            SolcBackendKind::from_str("standard-json-pool")
+        );
+        assert_eq!(Ok(SolcBackendKind::Mock), SolcBackendKind::from_str("mock"));
+        assert!(SolcBackendKind::from_str("foundry").is_err());
+    }
+
+    #[tokio::test]
+    async fn missing_compiler_is_io_error() {
+        let input: CompilerInput =
+            serde_json::from_str(r#"{"language": "Solidity", "sources": {}, "settings": {}}"#)
+                .unwrap();
+        let err = StandardJsonBackend
+            .compile(Path::new("/nonexistent/solc"), &input)
+            .await
+            .expect_err("compilation should fail");
+        assert!(matches!(err, SolcError::Io(_)), "unexpected error: {err}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pool_keeps_warm_processes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Mimics solc: reads the input until stdin is closed and prints the output
+        let dir = tempfile::tempdir().unwrap();
+        let solc = dir.path().join("solc");
+        std::fs::write(&solc, "#!/bin/sh\ncat > /dev/null\necho '{}'\n").unwrap();
+        std::fs::set_permissions(&solc, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let input: CompilerInput =
+            serde_json::from_str(r#"{"language": "Solidity", "sources": {}, "settings": {}}"#)
+                .unwrap();
+        let backend = ProcessPoolBackend::new(NonZeroUsize::new(2).unwrap());
+        assert_eq!(0, backend.warm_processes(&solc));
+        for _ in 0..3 {
+            backend
+                .compile(&solc, &input)
+                .await
+                .expect("compilation should succeed");
+            assert_eq!(2, backend.warm_processes(&solc));
+        }
+
+        let backend = backend.with_max_idle_time(Duration::ZERO);
+        assert!(
+            backend.take(&solc).is_none(),
+            "stale processes should be recycled"
+        );
+    }
+}
@@ -1,10 +1,28 @@
-use super::compiler::SolidityCompiler;
-use crate::{compiler::Compilers, middleware::Middleware, verifier::Success};
-use std::sync::Arc;
+use super::{
+    compiler::SolidityCompiler, dependencies::DependencyResolver, imports::PackageMirror,
+    repository::RepositoryFetcher,
+};
+use crate::{
+    compiler::Compilers,
+    middleware::Middleware,
+    verifier::{Error, Success},
+};
+use anyhow::anyhow;
+use std::{num::NonZeroUsize, sync::Arc};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Maximum number of compilations a single verification request may run concurrently,
+/// unless set via [`Client::with_max_parallel_compilations`].
+pub const DEFAULT_MAX_PARALLEL_COMPILATIONS_PER_REQUEST: usize = 2;
 
 pub struct Client {
     compilers: Arc<Compilers<SolidityCompiler>>,
     middleware: Option<Arc<dyn Middleware<Success>>>,
+    max_parallel_compilations: NonZeroUsize,
+    compilation_pool: Arc<Semaphore>,
+    package_mirror: Option<PackageMirror>,
+    dependency_resolver: Option<DependencyResolver>,
+    repository_fetcher: Option<RepositoryFetcher>,
 }
 
 impl Client {
@@ -24,6 +42,18 @@ impl Client {
         Self {
             compilers,
             middleware: None,
+            max_parallel_compilations: NonZeroUsize::new(
+                DEFAULT_MAX_PARALLEL_COMPILATIONS_PER_REQUEST,
+            )
+            .unwrap(),
+            compilation_pool: Arc::new(Semaphore::new(
+                std::thread::available_parallelism()
+                    .map(NonZeroUsize::get)
+                    .unwrap_or(8),
+            )),
+            package_mirror: None,
+            dependency_resolver: None,
+            repository_fetcher: None,
         }
     }
 
@@ -44,6 +74,50 @@ impl Client {
         self
     }
 
+    /// Set the maximum number of compilations a single verification request
+    /// may run concurrently ([`DEFAULT_MAX_PARALLEL_COMPILATIONS_PER_REQUEST`] by default).
+    ///
+    /// All compilations acquire permits from the compilation pool, thus the limit
+    /// prevents a request with a lot of candidate compilations from occupying
+    /// the whole pool and starving other requests.
+    pub fn with_max_parallel_compilations(
+        mut self,
+        max_parallel_compilations: NonZeroUsize,
+    ) -> Self {
+        self.max_parallel_compilations = max_parallel_compilations;
+        self
+    }
+
+    /// Set the pool of compilation permits shared by all verification requests
+    /// (the number of CPU cores by default). The pool may be shared with other clients
+    /// (e.g., of alternative compiler families) to bound their compilations together.
+    ///
+    /// Permits are granted in the order they are requested in, so requests compiling
+    /// several candidates at once get them interleaved with the other requests.
+    pub fn with_compilation_pool(mut self, compilation_pool: Arc<Semaphore>) -> Self {
+        self.compilation_pool = compilation_pool;
+        self
+    }
+
+    /// Set the mirror well-known dependencies missing from the sources are fetched from.
+    pub fn with_package_mirror(mut self, package_mirror: PackageMirror) -> Self {
+        self.package_mirror = Some(package_mirror);
+        self
+    }
+
+    /// Set the resolver pinned dependencies missing from the sources are fetched with.
+    pub fn with_dependency_resolver(mut self, dependency_resolver: DependencyResolver) -> Self {
+        self.dependency_resolver = Some(dependency_resolver);
+        self
+    }
+
+    /// Set the fetcher of the repositories the contracts may be verified from.
+    /// Verification from repositories is disabled if the fetcher is not set.
+    pub fn with_repository_fetcher(mut self, repository_fetcher: RepositoryFetcher) -> Self {
+        self.repository_fetcher = Some(repository_fetcher);
+        self
+    }
+
     pub fn compilers(&self) -> &Compilers<SolidityCompiler> {
         self.compilers.as_ref()
     }
@@ -52,4 +126,63 @@ impl Client {
     pub fn middleware(&self) -> Option<&dyn Middleware<Success>> {
         self.middleware.as_ref().map(|m| m.as_ref())
     }
+
+    pub fn max_parallel_compilations(&self) -> usize {
+        self.max_parallel_compilations.get()
+    }
+
+    /// Waits for the permit to run a compilation. Is held until the compilation
+    /// result has been verified.
+    pub(crate) async fn acquire_compilation_permit(&self) -> Result<SemaphorePermit<'_>, Error> {
+        self.compilation_pool
+            .acquire()
+            .await
+            .map_err(|err| Error::Internal(anyhow!("compilation pool is closed: {err}")))
+    }
+
+    pub fn package_mirror(&self) -> Option<&PackageMirror> {
+        self.package_mirror.as_ref()
+    }
+
+    pub fn dependency_resolver(&self) -> Option<&DependencyResolver> {
+        self.dependency_resolver.as_ref()
+    }
+
+    pub fn repository_fetcher(&self) -> Option<&RepositoryFetcher> {
+        self.repository_fetcher.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::MockFetcher;
+    use futures::FutureExt;
+
+    fn client(compilation_pool: Arc<Semaphore>) -> Client {
+        let compilers = Compilers::new(
+            Arc::new(MockFetcher::default()),
+            SolidityCompiler::new(),
+            Arc::new(Semaphore::new(4)),
+        );
+        Client::new(compilers).with_compilation_pool(compilation_pool)
+    }
+
+    #[tokio::test]
+    async fn compilation_pool_is_shared() {
+        let compilation_pool = Arc::new(Semaphore::new(1));
+        let first = client(compilation_pool.clone());
+        let second = client(compilation_pool);
+
+        let permit = first.acquire_compilation_permit().await.unwrap();
+        assert!(
+            second.acquire_compilation_permit().now_or_never().is_none(),
+            "the only permit is already acquired"
+        );
+        drop(permit);
+        assert!(matches!(
+            second.acquire_compilation_permit().now_or_never(),
+            Some(Ok(_))
+        ));
+    }
 }
@@ -0,0 +1,164 @@
+//! Compilation of the sources without verifying them against any bytecode.
+//!
+//! Is useful for tooling and for users debugging why their verification fails,
+//! as compilation results are returned as they are instead of being compared.
+
+use super::{
+    client::Client,
+    pragma,
+    standard_json::{self, StandardJsonContent},
+};
+use crate::{compiler::Version, verifier::Error};
+use ethers_solc::{
+    artifacts::{BytecodeObject, Severity},
+    CompilerInput,
+};
+use std::sync::Arc;
+
+pub struct CompileRequest {
+    pub compiler_version: Version,
+    /// If set and the compiler version does not satisfy `pragma solidity`
+    /// of the sources, the newest release satisfying them is used instead
+    pub auto_select_compiler_version: bool,
+
+    pub content: StandardJsonContent,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledContract {
+    pub file_path: String,
+    pub contract_name: String,
+    pub abi: Option<serde_json::Value>,
+    /// Hex encoded creation bytecode. Placeholders of unlinked libraries
+    /// (e.g., `__$<hash>$__`) are left as they are.
+    pub creation_bytecode: Option<String>,
+    /// Hex encoded deployed bytecode. Placeholders of unlinked libraries are left as they are.
+    pub deployed_bytecode: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// One of `error`, `warning` or `info`
+    pub severity: String,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompileSuccess {
+    /// Version actually used for compilation (may differ from the requested one
+    /// if `auto_select_compiler_version` is set)
+    pub compiler_version: Version,
+    pub contracts: Vec<CompiledContract>,
+    /// Warnings and info messages of the compiler
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Compiles the sources. Missing imports are resolved the same way as during verification.
+/// Compilation errors are returned as [`Error::Compilation`].
+pub async fn compile(
+    client: Arc<Client>,
+    request: CompileRequest,
+) -> Result<CompileSuccess, Error> {
+    let mut compiler_input = CompilerInput::from(request.content);
+    standard_json::add_missing_sources(&client, &mut compiler_input).await?;
+
+    let pragmas = pragma::version_pragmas(
+        compiler_input
+            .sources
+            .iter()
+            .map(|(path, source)| (path.as_path(), source.content.as_str())),
+    );
+    let compiler_version = pragma::resolve_compiler_version(
+        client.compilers(),
+        &request.compiler_version,
+        &pragmas,
+        request.auto_select_compiler_version,
+    )?;
+
+    let output = {
+        let _permit = client.acquire_compilation_permit().await?;
+        client
+            .compilers()
+            .compile(&compiler_version, &compiler_input)
+            .await?
+    };
+
+    let diagnostics = output
+        .errors
+        .iter()
+        .map(|diagnostic| Diagnostic {
+            severity: severity(&diagnostic.severity).to_string(),
+            message: diagnostic
+                .formatted_message
+                .clone()
+                .unwrap_or_else(|| diagnostic.message.clone()),
+        })
+        .collect();
+    let contracts = output
+        .contracts
+        .into_iter()
+        .flat_map(|(file_path, contracts)| {
+            contracts.into_iter().map(move |(contract_name, contract)| {
+                let evm = contract.evm;
+                let creation_bytecode = evm
+                    .as_ref()
+                    .and_then(|evm| evm.bytecode.as_ref())
+                    .map(|bytecode| encode_bytecode(&bytecode.object));
+                let deployed_bytecode = evm
+                    .as_ref()
+                    .and_then(|evm| evm.deployed_bytecode.as_ref())
+                    .and_then(|deployed| deployed.bytecode.as_ref())
+                    .map(|bytecode| encode_bytecode(&bytecode.object));
+                CompiledContract {
+                    file_path: file_path.clone(),
+                    contract_name,
+                    abi: contract.abi.map(|abi| abi.abi_value),
+                    creation_bytecode,
+                    deployed_bytecode,
+                }
+            })
+        })
+        .collect();
+
+    Ok(CompileSuccess {
+        compiler_version,
+        contracts,
+        diagnostics,
+    })
+}
+
+fn severity(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    }
+}
+
+fn encode_bytecode(object: &BytecodeObject) -> String {
+    match object {
+        BytecodeObject::Bytecode(bytes) => format!("0x{}", hex::encode(bytes)),
+        BytecodeObject::Unlinked(unlinked) => {
+            format!("0x{}", unlinked.trim_start_matches("0x"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn encode_linked_and_unlinked_bytecodes() {
+        let linked = BytecodeObject::Bytecode(vec![0x60, 0x80].into());
+        assert_eq!("0x6080", encode_bytecode(&linked));
+
+        let unlinked =
+            BytecodeObject::Unlinked("6080__$1234567890abcdef1234567890abcdef12$__".into());
+        assert_eq!(
+            "0x6080__$1234567890abcdef1234567890abcdef12$__",
+            encode_bytecode(&unlinked)
+        );
+    }
+}
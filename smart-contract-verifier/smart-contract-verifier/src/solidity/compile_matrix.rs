@@ -0,0 +1,304 @@
+//! Compilation of the sources with several compiler versions and settings at once.
+//!
+//! A power-user tool for hard verification cases: every combination of the requested
+//! compiler versions and settings is compiled, and the resulting code hashes are returned
+//! along with the contract matching the target bytecode, if any.
+
+use super::{
+    client::Client,
+    standard_json::{self, StandardJsonContent},
+};
+use crate::{
+    compiler::Version,
+    verifier::{ContractVerifier, Error},
+    MatchType,
+};
+use anyhow::anyhow;
+use bytes::Bytes;
+use ethers_solc::{Artifact, CompilerInput, CompilerOutput};
+use futures::StreamExt;
+use sha3::{Digest, Keccak256};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Maximum number of (compiler version, settings) combinations compiled by a single request
+pub const MAX_COMBINATIONS: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    CreationTxInput(Bytes),
+    DeployedBytecode(Bytes),
+}
+
+pub struct CompileMatrixRequest {
+    pub compiler_versions: Vec<Version>,
+    /// Overrides of the input `settings`, e.g. `{"optimizer": {"enabled": true, "runs": 200}}`.
+    /// Objects are merged recursively, other values are replaced. Each of the overrides
+    /// is compiled with every compiler version. If empty, the input settings are compiled as is.
+    pub settings: Vec<serde_json::Value>,
+    /// Bytecode the compiled contracts are matched against
+    pub target: Option<Target>,
+
+    pub content: StandardJsonContent,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatrixCell {
+    pub compiler_version: Version,
+    /// Index of the settings overrides in the request
+    pub settings_index: usize,
+    /// Hex encoded keccak256 hashes of the deployed bytecodes by fully qualified
+    /// contract names (e.g. "contracts/A.sol:A"). Unlinked and empty bytecodes are skipped
+    pub code_hashes: BTreeMap<String, String>,
+    /// Fully qualified name of the contract matching the target bytecode, if any
+    pub matched_contract: Option<String>,
+    pub match_type: Option<MatchType>,
+    /// Set if the combination could not be compiled
+    pub error: Option<String>,
+}
+
+/// Compiles every combination of the compiler versions and the settings overrides.
+/// Cells are returned ordered by the compiler version first and by the settings then.
+/// Failures of individual combinations are returned inside the cells.
+pub async fn compile_matrix(
+    client: Arc<Client>,
+    request: CompileMatrixRequest,
+) -> Result<Vec<MatrixCell>, Error> {
+    if request.compiler_versions.is_empty() {
+        return Err(Error::Initialization(anyhow!(
+            "at least one compiler version is required"
+        )));
+    }
+    let settings = if request.settings.is_empty() {
+        vec![serde_json::json!({})]
+    } else {
+        request.settings
+    };
+    let combinations = request.compiler_versions.len() * settings.len();
+    if combinations > MAX_COMBINATIONS {
+        return Err(Error::Initialization(anyhow!(
+            "too many combinations requested: {combinations} > {MAX_COMBINATIONS}"
+        )));
+    }
+
+    let mut compiler_input = CompilerInput::from(request.content);
+    standard_json::add_missing_sources(&client, &mut compiler_input).await?;
+    let inputs = settings
+        .iter()
+        .enumerate()
+        .map(|(index, overrides)| {
+            apply_settings(&compiler_input, overrides).map_err(|err| {
+                Error::Initialization(anyhow!("settings #{index} are invalid: {err}"))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(target) = &request.target {
+        // Invalid target would fail every single combination otherwise
+        verifier(&client, &request.compiler_versions[0], target)?;
+    }
+
+    let cells = request
+        .compiler_versions
+        .iter()
+        .flat_map(|compiler_version| {
+            inputs
+                .iter()
+                .enumerate()
+                .map(move |(settings_index, input)| (compiler_version, settings_index, input))
+        });
+    let cells = futures::stream::iter(cells)
+        .map(|(compiler_version, settings_index, input)| {
+            let client = &client;
+            let target = request.target.as_ref();
+            async move {
+                let cell = MatrixCell {
+                    compiler_version: compiler_version.clone(),
+                    settings_index,
+                    code_hashes: BTreeMap::new(),
+                    matched_contract: None,
+                    match_type: None,
+                    error: None,
+                };
+                compile_cell(client, cell, input, target).await
+            }
+        })
+        .buffered(client.max_parallel_compilations())
+        .collect()
+        .await;
+    Ok(cells)
+}
+
+async fn compile_cell(
+    client: &Client,
+    mut cell: MatrixCell,
+    input: &CompilerInput,
+    target: Option<&Target>,
+) -> MatrixCell {
+    let _permit = match client.acquire_compilation_permit().await {
+        Ok(permit) => permit,
+        Err(err) => {
+            cell.error = Some(err.to_string());
+            return cell;
+        }
+    };
+    let result = match target {
+        None => client
+            .compilers()
+            .compile(&cell.compiler_version, input)
+            .await
+            .map(|output| (code_hashes(&output), None))
+            .map_err(Error::from),
+        Some(target) => match verifier(client, &cell.compiler_version, target) {
+            Ok(verifier) => match verifier.compile(input).await {
+                Ok(outputs) => Ok((
+                    code_hashes(outputs.compiler_output()),
+                    verifier.verify_compiled(input, &outputs).ok(),
+                )),
+                Err(err) => Err(err),
+            },
+            Err(err) => Err(err),
+        },
+    };
+
+    match result {
+        Ok((code_hashes, success)) => {
+            cell.code_hashes = code_hashes;
+            if let Some(success) = success {
+                cell.matched_contract =
+                    Some(format!("{}:{}", success.file_path, success.contract_name));
+                cell.match_type = Some(success.match_type);
+            }
+        }
+        Err(Error::Compilation(errors)) => cell.error = Some(errors.join("\n")),
+        Err(err) => cell.error = Some(err.to_string()),
+    }
+    cell
+}
+
+fn verifier<'a>(
+    client: &'a Client,
+    compiler_version: &'a Version,
+    target: &Target,
+) -> Result<ContractVerifier<'a, super::SolidityCompiler>, Error> {
+    let (creation_tx_input, deployed_bytecode) = match target {
+        Target::CreationTxInput(bytecode) => (Some(bytecode.clone()), Bytes::new()),
+        Target::DeployedBytecode(bytecode) => (None, bytecode.clone()),
+    };
+    ContractVerifier::new(
+        client.compilers(),
+        compiler_version,
+        creation_tx_input,
+        deployed_bytecode,
+        vec![],
+        false,
+        Default::default(),
+    )
+}
+
+fn code_hashes(output: &CompilerOutput) -> BTreeMap<String, String> {
+    output
+        .contracts
+        .iter()
+        .flat_map(|(file_path, contracts)| {
+            contracts
+                .iter()
+                .filter_map(move |(contract_name, contract)| {
+                    let bytecode = contract.get_deployed_bytecode_bytes()?;
+                    if bytecode.is_empty() {
+                        return None;
+                    }
+                    let hash = Keccak256::digest(&bytecode.0);
+                    Some((
+                        format!("{file_path}:{contract_name}"),
+                        format!("0x{}", hex::encode(hash)),
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Returns the input with the settings overrides applied.
+/// The output selection is kept, as it is required for the verification itself.
+fn apply_settings(
+    input: &CompilerInput,
+    overrides: &serde_json::Value,
+) -> Result<CompilerInput, serde_json::Error> {
+    let mut settings = serde_json::to_value(&input.settings)?;
+    merge(&mut settings, overrides);
+
+    let mut input = input.clone();
+    let output_selection = std::mem::take(&mut input.settings.output_selection);
+    input.settings = serde_json::from_value(settings)?;
+    input.settings.output_selection = output_selection;
+    Ok(input)
+}
+
+fn merge(value: &mut serde_json::Value, overrides: &serde_json::Value) {
+    match (value, overrides) {
+        (serde_json::Value::Object(value), serde_json::Value::Object(overrides)) => {
+            for (key, overrides) in overrides {
+                merge(
+                    value.entry(key.clone()).or_insert(serde_json::Value::Null),
+                    overrides,
+                );
+            }
+        }
+        (value, overrides) => *value = overrides.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_solc::EvmVersion;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn merge_settings() {
+        let mut settings = json!({
+            "optimizer": {"enabled": false, "runs": 200},
+            "evmVersion": "london",
+        });
+        merge(
+            &mut settings,
+            &json!({"optimizer": {"enabled": true}, "evmVersion": "paris", "viaIR": true}),
+        );
+        assert_eq!(
+            json!({
+                "optimizer": {"enabled": true, "runs": 200},
+                "evmVersion": "paris",
+                "viaIR": true,
+            }),
+            settings
+        );
+    }
+
+    #[test]
+    fn apply_settings_keeps_output_selection() {
+        let input: CompilerInput = serde_json::from_value(json!({
+            "language": "Solidity",
+            "sources": {"A.sol": {"content": "contract A {}"}},
+            "settings": {
+                "optimizer": {"enabled": false},
+                "outputSelection": {"*": {"*": ["abi"]}},
+            },
+        }))
+        .unwrap();
+
+        let input = apply_settings(
+            &input,
+            &json!({"optimizer": {"enabled": true, "runs": 1000}, "evmVersion": "london", "outputSelection": {}}),
+        )
+        .expect("valid settings");
+        assert_eq!(Some(true), input.settings.optimizer.enabled);
+        assert_eq!(Some(1000), input.settings.optimizer.runs);
+        assert_eq!(Some(EvmVersion::London), input.settings.evm_version);
+        assert_eq!(
+            json!({"*": {"*": ["abi"]}}),
+            serde_json::to_value(&input.settings.output_selection).unwrap()
+        );
+
+        assert!(apply_settings(&input, &json!({"optimizer": {"runs": "many"}})).is_err());
+    }
+}
@@ -1,14 +1,42 @@
-use super::solc_cli;
-use crate::compiler::{EvmCompiler, Version};
-use ethers_solc::{error::SolcError, CompilerOutput, Solc};
+use super::{
+    backend::{EthersSolcBackend, SolcBackend},
+    solc_cli,
+};
+use crate::compiler::{EvmCompiler, SourcesDirs, Version};
+use ethers_solc::{error::SolcError, CompilerOutput};
 use std::path::Path;
 
-#[derive(Default)]
-pub struct SolidityCompiler {}
+pub struct SolidityCompiler {
+    backend: Box<dyn SolcBackend>,
+    sources_dirs: SourcesDirs,
+}
+
+impl Default for SolidityCompiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl SolidityCompiler {
     pub fn new() -> Self {
-        SolidityCompiler {}
+        SolidityCompiler {
+            backend: Box::new(EthersSolcBackend),
+            sources_dirs: Default::default(),
+        }
+    }
+
+    /// Use the `backend` for compilers supporting the standard json input (0.4.11+).
+    /// Older compilers are always invoked with the command line arguments.
+    pub fn with_backend(mut self, backend: Box<dyn SolcBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Directories the sources are written to for the compilers
+    /// invoked with the command line arguments
+    pub fn with_sources_dirs(mut self, sources_dirs: SourcesDirs) -> Self {
+        self.sources_dirs = sources_dirs;
+        self
     }
 }
 
@@ -21,9 +49,9 @@ impl EvmCompiler for SolidityCompiler {
         input: &ethers_solc::CompilerInput,
     ) -> Result<CompilerOutput, SolcError> {
         if ver.version() < &semver::Version::new(0, 4, 11) {
-            solc_cli::compile_using_cli(path, input).await
+            solc_cli::compile_using_cli(path, input, &self.sources_dirs).await
         } else {
-            Solc::from(path).async_compile(input).await
+            self.backend.compile(path, input).await
         }
     }
 }
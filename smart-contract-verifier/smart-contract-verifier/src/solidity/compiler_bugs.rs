@@ -0,0 +1,463 @@
+//! Known bugs of the Solidity compiler affecting contracts compiled with old versions.
+//!
+//! Bugs are taken from the lists maintained by the Solidity team
+//! (<https://github.com/ethereum/solidity/blob/develop/docs/bugs.json> and
+//! `bugs_by_version.json` next to it). Besides the versions affected, the list describes
+//! the compiler settings the bug requires and source patterns it manifests with,
+//! which are used to estimate whether the contract is actually impacted.
+
+use super::pragma;
+use crate::scheduler;
+use cron::Schedule;
+use ethers_solc::{CompilerInput, EvmVersion};
+use regex::Regex;
+use serde::Deserialize;
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct CompilerBug {
+    pub uid: String,
+    pub name: String,
+    pub summary: String,
+    pub link: Option<String>,
+    /// One of "very low", "low", "medium", "high"
+    pub severity: String,
+    /// The first version the bug is present in. If absent, all the versions
+    /// before the fixed one are affected
+    pub introduced: Option<semver::Version>,
+    /// The first version the bug is fixed in. If absent, the bug has not been fixed yet
+    pub fixed: Option<semver::Version>,
+    /// Compiler settings (e.g., `"optimizer": true`) required for the bug to manifest
+    #[serde(default)]
+    conditions: BTreeMap<String, serde_json::Value>,
+    /// Source patterns the bug manifests with by the kind of the pattern
+    /// (e.g., "regex-source" or "ast-compact-json-path")
+    #[serde(default)]
+    check: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Impact {
+    /// Settings and sources meet all the conditions of the bug
+    Affected,
+    /// Either settings or sources do not meet some condition of the bug
+    NotAffected,
+    /// Some conditions could not be evaluated without compiling the sources
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AffectingBug {
+    pub bug: CompilerBug,
+    pub impact: Impact,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BugReport {
+    /// Bugs present in the compiler version in the order of the list (the newest ones first)
+    pub bugs: Vec<AffectingBug>,
+    /// The earliest version all bugs possibly impacting the contract are fixed in.
+    /// Is absent if there are no such bugs
+    pub recommended_version: Option<semver::Version>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionBugs {
+    bugs: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Lists {
+    bugs: Vec<CompilerBug>,
+    /// Names of the bugs by the released versions they are present in
+    bugs_by_version: BTreeMap<semver::Version, Vec<String>>,
+}
+
+impl Lists {
+    fn from_json(bugs: &str, bugs_by_version: &str) -> Result<Self, serde_json::Error> {
+        let bugs = serde_json::from_str(bugs)?;
+        let bugs_by_version: BTreeMap<semver::Version, VersionBugs> =
+            serde_json::from_str(bugs_by_version)?;
+        Ok(Self {
+            bugs,
+            bugs_by_version: bugs_by_version
+                .into_iter()
+                .map(|(version, version_bugs)| (version, version_bugs.bugs))
+                .collect(),
+        })
+    }
+
+    /// Versions missing in `bugs_by_version` (e.g., nightly builds or releases
+    /// newer than the list) are checked against the bugs introduced and fixed versions.
+    fn version_bugs(&self, version: &semver::Version) -> Vec<&CompilerBug> {
+        match self.bugs_by_version.get(version) {
+            Some(names) => self
+                .bugs
+                .iter()
+                .filter(|bug| names.contains(&bug.name))
+                .collect(),
+            None => self
+                .bugs
+                .iter()
+                .filter(|bug| {
+                    bug.introduced
+                        .as_ref()
+                        .map_or(true, |introduced| version >= introduced)
+                        && bug.fixed.as_ref().map_or(true, |fixed| version < fixed)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CompilerBugs {
+    lists: parking_lot::RwLock<Lists>,
+}
+
+impl CompilerBugs {
+    pub fn from_json(bugs: &str, bugs_by_version: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            lists: parking_lot::RwLock::new(Lists::from_json(bugs, bugs_by_version)?),
+        })
+    }
+
+    /// Fetches the lists and keeps them refreshed on the schedule, if one is provided.
+    pub async fn fetch(
+        bugs_url: Url,
+        bugs_by_version_url: Url,
+        refresh_schedule: Option<Schedule>,
+    ) -> Result<Arc<Self>, anyhow::Error> {
+        let lists = fetch_lists(&bugs_url, &bugs_by_version_url).await?;
+        let compiler_bugs = Arc::new(Self {
+            lists: parking_lot::RwLock::new(lists),
+        });
+        if let Some(schedule) = refresh_schedule {
+            let compiler_bugs = compiler_bugs.clone();
+            scheduler::spawn_job(schedule, "refresh compiler bugs", move || {
+                let compiler_bugs = compiler_bugs.clone();
+                let (bugs_url, bugs_by_version_url) =
+                    (bugs_url.clone(), bugs_by_version_url.clone());
+                async move {
+                    match fetch_lists(&bugs_url, &bugs_by_version_url).await {
+                        Ok(lists) => *compiler_bugs.lists.write() = lists,
+                        Err(err) => tracing::error!("error during compiler bugs refresh: {err:#}"),
+                    }
+                }
+            });
+        }
+        Ok(compiler_bugs)
+    }
+
+    /// Returns the bugs present in the compiler version (the newest ones first).
+    /// Pre-release parts of the version are ignored.
+    pub fn version_bugs(&self, version: &semver::Version) -> Vec<CompilerBug> {
+        let version = semver::Version::new(version.major, version.minor, version.patch);
+        self.lists
+            .read()
+            .version_bugs(&version)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the bugs present in the compiler version and their impact on the sources
+    /// compiled with the input settings. Pre-release parts of the version are ignored.
+    pub fn report(&self, version: &semver::Version, input: &CompilerInput) -> BugReport {
+        let version = semver::Version::new(version.major, version.minor, version.patch);
+        let sources: Vec<String> = input
+            .sources
+            .values()
+            .map(|source| pragma::strip_comments(&source.content))
+            .collect();
+
+        let bugs: Vec<AffectingBug> = self
+            .version_bugs(&version)
+            .into_iter()
+            .map(|bug| {
+                let impact = impact(&bug, &version, input, &sources);
+                AffectingBug { bug, impact }
+            })
+            .collect();
+        let recommended_version = bugs
+            .iter()
+            .filter(|affecting| affecting.impact != Impact::NotAffected)
+            .filter_map(|affecting| affecting.bug.fixed.clone())
+            .max();
+
+        BugReport {
+            bugs,
+            recommended_version,
+        }
+    }
+}
+
+async fn fetch_lists(bugs_url: &Url, bugs_by_version_url: &Url) -> Result<Lists, anyhow::Error> {
+    let fetch = |url: &Url| {
+        let url = url.clone();
+        async move { reqwest::get(url).await?.error_for_status()?.text().await }
+    };
+    let bugs = fetch(bugs_url).await?;
+    let bugs_by_version = fetch(bugs_by_version_url).await?;
+    Ok(Lists::from_json(&bugs, &bugs_by_version)?)
+}
+
+fn impact(
+    bug: &CompilerBug,
+    version: &semver::Version,
+    input: &CompilerInput,
+    sources: &[String],
+) -> Impact {
+    let conditions = bug
+        .conditions
+        .iter()
+        .map(|(name, value)| condition_holds(name, value, version, input, sources));
+    let checks = bug.check.iter().map(|(kind, pattern)| match kind.as_str() {
+        "regex-source" => Regex::new(pattern)
+            .ok()
+            .map(|regex| sources.iter().any(|source| regex.is_match(source))),
+        // Requires the ASTs of the sources, which are not available without compilation
+        _ => None,
+    });
+
+    let mut impact = Impact::Affected;
+    for holds in conditions.chain(checks) {
+        match holds {
+            Some(false) => return Impact::NotAffected,
+            None => impact = Impact::Unknown,
+            Some(true) => {}
+        }
+    }
+    impact
+}
+
+/// Returns `None` if the condition is unknown or could not be evaluated.
+fn condition_holds(
+    name: &str,
+    value: &serde_json::Value,
+    version: &semver::Version,
+    input: &CompilerInput,
+    sources: &[String],
+) -> Option<bool> {
+    let optimizer = &input.settings.optimizer;
+    let optimizer_enabled = optimizer.enabled.unwrap_or_default();
+    let actual = match name {
+        "optimizer" => optimizer_enabled,
+        "yulOptimizer" => optimizer
+            .details
+            .as_ref()
+            .and_then(|details| details.yul)
+            // Is enabled together with the legacy optimizer since 0.6.0
+            .unwrap_or(optimizer_enabled && version >= &semver::Version::new(0, 6, 0)),
+        "viaIR" => input.settings.via_ir.unwrap_or_default(),
+        "ABIEncoderV2" => uses_abi_encoder_v2(version, sources),
+        "evmVersion" => {
+            let evm_version = input.settings.evm_version?;
+            return value
+                .as_str()
+                .and_then(|requirement| evm_version_matches(evm_version, requirement));
+        }
+        _ => return None,
+    };
+    value.as_bool().map(|expected| expected == actual)
+}
+
+fn uses_abi_encoder_v2(version: &semver::Version, sources: &[String]) -> bool {
+    let directive = |source: &str, directive: &str| {
+        source.split(';').any(|statement| {
+            statement
+                .split_whitespace()
+                .eq(directive.split_whitespace())
+        })
+    };
+    sources.iter().any(|source| {
+        directive(source, "pragma experimental ABIEncoderV2")
+            || directive(source, "pragma abicoder v2")
+            // ABI coder v2 is the default one since 0.8.0
+            || (version >= &semver::Version::new(0, 8, 0)
+                && !directive(source, "pragma abicoder v1"))
+    })
+}
+
+/// Checks the evm version against the requirement (e.g., ">=constantinople").
+fn evm_version_matches(evm_version: EvmVersion, requirement: &str) -> Option<bool> {
+    let operator_len = requirement
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(requirement.len());
+    let (operator, required) = requirement.split_at(operator_len);
+    let required = EvmVersion::from_str(required).ok()?;
+    let matches = match operator {
+        ">=" => evm_version >= required,
+        ">" => evm_version > required,
+        "<=" => evm_version <= required,
+        "<" => evm_version < required,
+        "=" | "" => evm_version == required,
+        _ => return None,
+    };
+    Some(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_solc::artifacts::{Settings, Source};
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    const BUGS: &str = r#"[
+        {
+            "uid": "SOL-2022-6",
+            "name": "AbiReencodingHeadOverflowWithStaticArrayCleanup",
+            "summary": "ABI-encoding a tuple with a statically-sized calldata array in the last component would corrupt 32 leading bytes of its first dynamically encoded component.",
+            "link": "https://blog.soliditylang.org/2022/08/08/calldata-tuple-reencoding-head-overflow-bug/",
+            "introduced": "0.5.8",
+            "fixed": "0.8.16",
+            "severity": "medium",
+            "conditions": {"ABIEncoderV2": true}
+        },
+        {
+            "uid": "SOL-2022-4",
+            "name": "InlineAssemblyMemorySideEffects",
+            "summary": "The Yul optimizer may incorrectly remove memory writes from inline assembly blocks, that do not access solidity variables.",
+            "introduced": "0.8.13",
+            "fixed": "0.8.15",
+            "severity": "medium",
+            "conditions": {"yulOptimizer": true},
+            "check": {"regex-source": "\\bassembly\\b"}
+        },
+        {
+            "uid": "SOL-2021-4",
+            "name": "UserDefinedValueTypesBug",
+            "summary": "User defined value types with underlying type shorter than 32 bytes used incorrect storage layout and wasted storage",
+            "introduced": "0.8.8",
+            "fixed": "0.8.9",
+            "severity": "very low",
+            "check": {"ast-compact-json-path": "$..[?(@.nodeType === 'UserDefinedValueTypeDefinition')]"}
+        },
+        {
+            "uid": "SOL-2020-11",
+            "name": "EmptyByteArrayCopy",
+            "summary": "Copying an empty byte array (or string) from memory or calldata to storage can result in data corruption if the target array's length is increased subsequently without storing new data.",
+            "fixed": "0.7.4",
+            "severity": "medium",
+            "conditions": {"evmVersion": ">=constantinople"}
+        }
+    ]"#;
+
+    const BUGS_BY_VERSION: &str = r#"{
+        "0.8.13": {
+            "bugs": ["InlineAssemblyMemorySideEffects"],
+            "released": "2022-03-16"
+        },
+        "0.8.16": {
+            "bugs": [],
+            "released": "2022-08-08"
+        }
+    }"#;
+
+    fn input(source: &str, optimizer: bool) -> CompilerInput {
+        let mut settings = Settings::default();
+        settings.optimizer.enabled = Some(optimizer);
+        CompilerInput {
+            language: "Solidity".to_string(),
+            sources: BTreeMap::from([(PathBuf::from("A.sol"), Source::new(source))]),
+            settings,
+        }
+    }
+
+    fn report(version: &str, input: &CompilerInput) -> Vec<(String, Impact)> {
+        let bugs = CompilerBugs::from_json(BUGS, "{}").expect("valid bugs list");
+        bugs.report(&semver::Version::parse(version).unwrap(), input)
+            .bugs
+            .into_iter()
+            .map(|affecting| (affecting.bug.uid, affecting.impact))
+            .collect()
+    }
+
+    #[test]
+    fn reports_bugs_of_version() {
+        let source = "pragma solidity ^0.8.0; contract A { function f() public { assembly {} } }";
+        assert_eq!(
+            vec![
+                ("SOL-2022-6".to_string(), Impact::Affected),
+                ("SOL-2022-4".to_string(), Impact::Affected),
+            ],
+            report("0.8.13", &input(source, true))
+        );
+        assert_eq!(
+            vec![
+                ("SOL-2022-6".to_string(), Impact::Affected),
+                ("SOL-2022-4".to_string(), Impact::NotAffected),
+            ],
+            report("0.8.14-nightly.2022.5.1", &input(source, false)),
+            "optimizer disabled"
+        );
+        assert_eq!(
+            vec![
+                ("SOL-2022-6".to_string(), Impact::NotAffected),
+                ("SOL-2021-4".to_string(), Impact::Unknown),
+            ],
+            report("0.8.8", &input("pragma abicoder v1;", false))
+        );
+        assert_eq!(
+            Vec::<(String, Impact)>::new(),
+            report("0.8.16", &input(source, true))
+        );
+    }
+
+    #[test]
+    fn bugs_of_listed_versions_are_taken_from_list() {
+        let bugs = CompilerBugs::from_json(BUGS, BUGS_BY_VERSION).expect("valid bugs lists");
+        let uids = |version: &str| -> Vec<String> {
+            bugs.version_bugs(&semver::Version::parse(version).unwrap())
+                .into_iter()
+                .map(|bug| bug.uid)
+                .collect()
+        };
+
+        assert_eq!(vec!["SOL-2022-4"], uids("0.8.13"));
+        assert_eq!(vec!["SOL-2022-4"], uids("0.8.13-nightly.2022.3.10"));
+        assert_eq!(Vec::<String>::new(), uids("0.8.16"));
+        // Is missing in the list, so introduced and fixed versions are used
+        assert_eq!(vec!["SOL-2022-6", "SOL-2022-4"], uids("0.8.14"));
+    }
+
+    #[test]
+    fn evaluates_settings_conditions() {
+        let mut input = input("pragma experimental ABIEncoderV2;", false);
+        input.settings.evm_version = None;
+        assert_eq!(
+            vec![
+                ("SOL-2022-6".to_string(), Impact::Affected),
+                ("SOL-2020-11".to_string(), Impact::Unknown),
+            ],
+            report("0.7.0", &input),
+            "evm version is not specified"
+        );
+
+        input.settings.evm_version = Some(EvmVersion::Byzantium);
+        assert_eq!(
+            vec![
+                ("SOL-2022-6".to_string(), Impact::Affected),
+                ("SOL-2020-11".to_string(), Impact::NotAffected),
+            ],
+            report("0.7.0", &input)
+        );
+    }
+
+    #[test]
+    fn recommends_version_fixing_impacting_bugs() {
+        let bugs = CompilerBugs::from_json(BUGS, "{}").expect("valid bugs list");
+        let version = semver::Version::new(0, 8, 13);
+
+        let report = bugs.report(&version, &input("contract A {}", true));
+        assert_eq!(
+            Some(semver::Version::new(0, 8, 16)),
+            report.recommended_version
+        );
+
+        let report = bugs.report(&version, &input("pragma abicoder v1;", false));
+        assert_eq!(None, report.recommended_version);
+    }
+}
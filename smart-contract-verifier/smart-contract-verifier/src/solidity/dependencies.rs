@@ -0,0 +1,323 @@
+//! Sources of the pinned dependencies (e.g., `@openzeppelin/contracts@4.9.3`)
+//! referenced by the submission via remappings, but not included into it.
+//!
+//! Only the packages allowed by the operator are fetched. Package archives are downloaded
+//! either from the npm registry and verified against the integrity published for the package,
+//! or from the GitHub tags. If the operator specified the checksum of the archive
+//! for the package version, the archive is verified against it as well.
+
+use anyhow::{anyhow, Context};
+use base64::Engine;
+use serde::Deserialize;
+use sha2::{Digest, Sha256, Sha512};
+use std::{collections::BTreeMap, io::Read};
+use url::Url;
+
+/// Maximum total size of the sources extracted from a single package archive.
+const MAX_PACKAGE_SOURCES_SIZE: u64 = 50 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct PinnedPackage {
+    pub name: String,
+    pub version: String,
+}
+
+/// Splits the source unit name of the form `<package>@<version>/<file>`
+/// into the pinned package and the file path relative to the package root.
+pub(crate) fn parse_pinned_path(path: &str) -> Option<(PinnedPackage, &str)> {
+    // Scoped package names start with `@` themselves
+    let version_start = path.get(1..)?.find('@')? + 1;
+    let name = &path[..version_start];
+    let (version, file_path) = path[version_start + 1..].split_once('/')?;
+    if name.is_empty() || version.is_empty() || file_path.is_empty() {
+        return None;
+    }
+    let package = PinnedPackage {
+        name: name.to_string(),
+        version: version.to_string(),
+    };
+    Some((package, file_path))
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AllowedPackage {
+    /// GitHub repository (`owner/name`) the package is fetched from by the tags.
+    /// If not set, the package is fetched from the npm registry.
+    pub github_repository: Option<String>,
+    /// Prefix of the tags corresponding to the package versions (e.g., `v`)
+    pub tag_prefix: String,
+    /// Directory of the repository the package files are located at (e.g., `contracts`)
+    pub directory: String,
+    /// Expected sha256 checksums (hex encoded) of the package archives by the package versions
+    pub checksums: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DependencyResolver {
+    npm_registry: Url,
+    github_url: Url,
+    allowed_packages: BTreeMap<String, AllowedPackage>,
+    client: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmVersion {
+    dist: NpmDist,
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmDist {
+    tarball: Url,
+    integrity: Option<String>,
+}
+
+impl DependencyResolver {
+    /// `github_url` is the host the tag archives are downloaded from
+    /// (e.g., `https://codeload.github.com/`).
+    pub fn new(
+        npm_registry: Url,
+        github_url: Url,
+        allowed_packages: BTreeMap<String, AllowedPackage>,
+    ) -> Self {
+        Self {
+            npm_registry,
+            github_url,
+            allowed_packages,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub(crate) fn is_allowed(&self, package: &PinnedPackage) -> bool {
+        self.allowed_packages.contains_key(&package.name)
+    }
+
+    /// Returns solidity sources of the package by their paths relative to the package root.
+    pub(crate) async fn fetch_package(
+        &self,
+        package: &PinnedPackage,
+    ) -> Result<BTreeMap<String, String>, anyhow::Error> {
+        let allowed = self
+            .allowed_packages
+            .get(&package.name)
+            .ok_or_else(|| anyhow!("package is not allowed: {}", package.name))?;
+
+        let (archive, directory) = match &allowed.github_repository {
+            Some(repository) => {
+                let tag = format!("{}{}", allowed.tag_prefix, package.version);
+                let archive = self.fetch_github_archive(repository, &tag).await?;
+                (archive, allowed.directory.clone())
+            }
+            None => (self.fetch_npm_archive(package).await?, String::new()),
+        };
+        if let Some(expected) = allowed.checksums.get(&package.version) {
+            let found = hex::encode(Sha256::digest(&archive));
+            if !expected
+                .trim_start_matches("0x")
+                .eq_ignore_ascii_case(&found)
+            {
+                return Err(anyhow!(
+                    "checksum mismatch for {}@{}: expected {expected}, found {found}",
+                    package.name,
+                    package.version
+                ));
+            }
+        }
+
+        tokio::task::spawn_blocking(move || extract_sources(&archive, &directory))
+            .await
+            .context("archive extraction task failed")?
+    }
+
+    async fn fetch_npm_archive(&self, package: &PinnedPackage) -> Result<Vec<u8>, anyhow::Error> {
+        // Scoped package names should be url encoded (e.g., `@openzeppelin%2fcontracts`)
+        let path = format!("{}/{}", package.name.replace('/', "%2f"), package.version);
+        let metadata: NpmVersion = self
+            .client
+            .get(self.npm_registry.join(&path)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let integrity = metadata
+            .dist
+            .integrity
+            .ok_or_else(|| anyhow!("package integrity is not published"))?;
+
+        let archive = self.download(metadata.dist.tarball).await?;
+        verify_integrity(&archive, &integrity)?;
+        Ok(archive)
+    }
+
+    async fn fetch_github_archive(
+        &self,
+        repository: &str,
+        tag: &str,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let url = self
+            .github_url
+            .join(&format!("{repository}/tar.gz/refs/tags/{tag}"))?;
+        self.download(url).await
+    }
+
+    async fn download(&self, url: Url) -> Result<Vec<u8>, anyhow::Error> {
+        let bytes = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        Ok(bytes.to_vec())
+    }
+}
+
+/// Verifies the archive against the npm subresource integrity string (e.g., `sha512-<base64>`).
+fn verify_integrity(archive: &[u8], integrity: &str) -> Result<(), anyhow::Error> {
+    let expected = integrity
+        .strip_prefix("sha512-")
+        .ok_or_else(|| anyhow!("unsupported integrity algorithm: {integrity}"))?;
+    let found = base64::engine::general_purpose::STANDARD.encode(Sha512::digest(archive));
+    if expected != found {
+        return Err(anyhow!(
+            "integrity mismatch: expected {expected}, found {found}"
+        ));
+    }
+    Ok(())
+}
+
+/// Extracts solidity files from the gzipped tarball. The root directory of the archive
+/// (`package` for npm and `<repository>-<tag>` for GitHub) is skipped, as well as
+/// the `directory` inside it.
+fn extract_sources(
+    archive: &[u8],
+    directory: &str,
+) -> Result<BTreeMap<String, String>, anyhow::Error> {
+    extract_files(
+        archive,
+        directory,
+        |path| path.ends_with(".sol"),
+        MAX_PACKAGE_SOURCES_SIZE,
+    )
+}
+
+/// Extracts the files accepted by the `filter` from the gzipped tarball the same way
+/// as [`extract_sources`] does, failing if their total size exceeds `max_size`.
+pub(crate) fn extract_files(
+    archive: &[u8],
+    directory: &str,
+    filter: impl Fn(&str) -> bool,
+    max_size: u64,
+) -> Result<BTreeMap<String, String>, anyhow::Error> {
+    let directory = directory.trim_matches('/');
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    let mut sources = BTreeMap::new();
+    let mut total_size = 0;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let entry_path = entry.path()?.to_string_lossy().to_string();
+        let path = match entry_path.split_once('/') {
+            Some((_root, path)) if directory.is_empty() => path,
+            Some((_root, path)) => match path
+                .strip_prefix(directory)
+                .and_then(|path| path.strip_prefix('/'))
+            {
+                Some(path) => path,
+                None => continue,
+            },
+            None => continue,
+        };
+        if !filter(path) {
+            continue;
+        }
+
+        total_size += entry.size();
+        if total_size > max_size {
+            return Err(anyhow!("archive files are too large"));
+        }
+        let mut content = String::new();
+        entry.read_to_string(&mut content)?;
+        sources.insert(path.to_string(), content);
+    }
+    Ok(sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    fn archive(files: &[(&str, &str)]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        let mut encoder = builder.into_inner().unwrap();
+        encoder.flush().unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn parse_pinned_paths() {
+        let package = |name: &str, version: &str| PinnedPackage {
+            name: name.into(),
+            version: version.into(),
+        };
+        assert_eq!(
+            Some((
+                package("@openzeppelin/contracts", "4.9.3"),
+                "token/ERC20/ERC20.sol"
+            )),
+            parse_pinned_path("@openzeppelin/contracts@4.9.3/token/ERC20/ERC20.sol")
+        );
+        assert_eq!(
+            Some((package("solmate", "6.2.0"), "src/tokens/ERC20.sol")),
+            parse_pinned_path("solmate@6.2.0/src/tokens/ERC20.sol")
+        );
+        assert_eq!(
+            None,
+            parse_pinned_path("@openzeppelin/contracts/token/ERC20/ERC20.sol")
+        );
+        assert_eq!(None, parse_pinned_path("solmate@6.2.0"));
+    }
+
+    #[test]
+    fn extract_sources_from_archive() {
+        let archive = archive(&[
+            ("repo-v1.0.0/contracts/token/Token.sol", "contract Token {}"),
+            ("repo-v1.0.0/contracts/README.md", "readme"),
+            ("repo-v1.0.0/test/Token.t.sol", "contract TokenTest {}"),
+        ]);
+
+        let expected = BTreeMap::from([(
+            "token/Token.sol".to_string(),
+            "contract Token {}".to_string(),
+        )]);
+        assert_eq!(expected, extract_sources(&archive, "contracts/").unwrap());
+        assert_eq!(2, extract_sources(&archive, "").unwrap().len());
+    }
+
+    #[test]
+    fn verify_archive_integrity() {
+        let archive = b"archive";
+        let integrity = format!(
+            "sha512-{}",
+            base64::engine::general_purpose::STANDARD.encode(Sha512::digest(archive))
+        );
+        assert!(verify_integrity(archive, &integrity).is_ok());
+        assert!(verify_integrity(b"another archive", &integrity).is_err());
+        assert!(verify_integrity(archive, "sha1-abcdef").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Resolution of the import graph of the sources before compilation.
+//!
+//! Imports of files missing from the sources are otherwise reported by the compiler
+//! with a raw error, which is hard to match with the submitted files. Cyclic imports
+//! are valid in solidity, so they are not reported, but only traversed once.
+//!
+//! Pinned dependencies referenced via remappings (e.g., `@openzeppelin/contracts/=@openzeppelin/contracts@4.9.3/`)
+//! are resolved into the paths of the form `<package>@<version>/<file>`, which are fetched
+//! by the [`DependencyResolver`](super::DependencyResolver) if the package is allowed.
+
+use super::{
+    client::Client,
+    dependencies::{self, PinnedPackage},
+    pragma,
+};
+use crate::verifier::Error;
+use ethers_solc::remappings::Remapping;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+/// Maximum number of sources fetched from the package mirror for a single request.
+const MAX_FETCHED_SOURCES: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MissingSource {
+    /// Source unit name the import has been resolved into
+    pub path: String,
+    pub imported_by: PathBuf,
+}
+
+impl Display for MissingSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "missing source: {} imported by {}",
+            self.path,
+            self.imported_by.to_string_lossy()
+        )
+    }
+}
+
+/// Mirror of npm packages (e.g., `https://unpkg.com`) the well-known dependencies
+/// missing from the sources are fetched from. Only imports of scoped packages
+/// (e.g., `@openzeppelin/contracts/token/ERC20/ERC20.sol`) are fetched.
+#[derive(Clone, Debug)]
+pub struct PackageMirror {
+    url: Url,
+    client: reqwest::Client,
+}
+
+impl PackageMirror {
+    pub fn new(mut url: Url) -> Self {
+        // Otherwise, the last segment of the url would be replaced by the joined paths
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        Self {
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn is_package_path(path: &str) -> bool {
+        path.starts_with('@') && path.split('/').filter(|s| !s.is_empty()).count() > 2
+    }
+
+    async fn fetch(&self, path: &str) -> Option<String> {
+        let url = self.url.join(path).ok()?;
+        let response = self
+            .client
+            .get(url.clone())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match response {
+            Ok(response) => response.text().await.ok(),
+            Err(err) => {
+                tracing::debug!(url = url.as_str(), "source could not be fetched: {err}");
+                None
+            }
+        }
+    }
+}
+
+/// Checks that all imports of the sources can be resolved. Missing pinned dependencies
+/// and well-known packages are fetched via the dependency resolver and the package mirror
+/// of the client, if those are set, and returned to be added to the sources.
+/// Fails with the list of imports which still could not be resolved.
+pub(crate) async fn resolve_imports(
+    client: &Client,
+    sources: &[(&Path, &str)],
+    remappings: &[Remapping],
+) -> Result<BTreeMap<PathBuf, String>, Error> {
+    let mut fetched: BTreeMap<PathBuf, String> = BTreeMap::new();
+    // Sources of the pinned packages fetched during the request (`None` if fetching failed)
+    let mut packages: BTreeMap<PinnedPackage, Option<BTreeMap<String, String>>> = BTreeMap::new();
+    loop {
+        let all_sources = sources.iter().copied().chain(
+            fetched
+                .iter()
+                .map(|(path, content)| (path.as_path(), content.as_str())),
+        );
+        let missing = missing_sources(all_sources, remappings);
+        if missing.is_empty() {
+            return Ok(fetched);
+        }
+
+        let mut is_fetched = false;
+        let paths: BTreeSet<_> = missing
+            .iter()
+            .map(|missing| missing.path.as_str())
+            .collect();
+        for path in paths {
+            if fetched.len() >= MAX_FETCHED_SOURCES {
+                break;
+            }
+            if let Some(content) = fetch_source(client, &mut packages, path).await {
+                fetched.insert(PathBuf::from(path), content);
+                is_fetched = true;
+            }
+        }
+        if !is_fetched {
+            return Err(Error::MissingSources(
+                missing.iter().map(ToString::to_string).collect(),
+            ));
+        }
+    }
+}
+
+async fn fetch_source(
+    client: &Client,
+    packages: &mut BTreeMap<PinnedPackage, Option<BTreeMap<String, String>>>,
+    path: &str,
+) -> Option<String> {
+    let resolver = client.dependency_resolver();
+    match (resolver, dependencies::parse_pinned_path(path)) {
+        (Some(resolver), Some((package, file_path))) if resolver.is_allowed(&package) => {
+            if !packages.contains_key(&package) {
+                let sources = resolver
+                    .fetch_package(&package)
+                    .await
+                    .map_err(|err| {
+                        tracing::warn!(
+                            package = %package.name,
+                            version = %package.version,
+                            "package could not be fetched: {err:#}"
+                        )
+                    })
+                    .ok();
+                packages.insert(package.clone(), sources);
+            }
+            packages.get(&package)?.as_ref()?.get(file_path).cloned()
+        }
+        _ => match client.package_mirror() {
+            Some(mirror) if PackageMirror::is_package_path(path) => mirror.fetch(path).await,
+            _ => None,
+        },
+    }
+}
+
+/// Returns the imports of the sources which could not be resolved into any of the sources.
+pub fn missing_sources<'a>(
+    sources: impl IntoIterator<Item = (&'a Path, &'a str)>,
+    remappings: &[Remapping],
+) -> Vec<MissingSource> {
+    let sources: Vec<_> = sources.into_iter().collect();
+    let source_names: BTreeSet<_> = sources
+        .iter()
+        .map(|(path, _)| path.to_string_lossy())
+        .collect();
+
+    let mut missing = BTreeSet::new();
+    for (file_path, content) in &sources {
+        for import_path in import_paths(content) {
+            let path = resolve_import(file_path, &import_path, remappings);
+            if !source_names.contains(path.as_str()) {
+                missing.insert(MissingSource {
+                    path,
+                    imported_by: file_path.to_path_buf(),
+                });
+            }
+        }
+    }
+    missing.into_iter().collect()
+}
+
+/// Converts the import path into the source unit name, as the compiler does: relative paths
+/// (starting with `./` or `../`) are resolved against the importing file directory,
+/// while direct ones are only remapped.
+pub(crate) fn resolve_import(
+    file_path: &Path,
+    import_path: &str,
+    remappings: &[Remapping],
+) -> String {
+    if !(import_path.starts_with("./") || import_path.starts_with("../")) {
+        return remappings
+            .iter()
+            .filter(|remapping| import_path.starts_with(&remapping.name))
+            .max_by_key(|remapping| remapping.name.len())
+            .map(|remapping| format!("{}{}", remapping.path, &import_path[remapping.name.len()..]))
+            .unwrap_or_else(|| import_path.to_string());
+    }
+
+    let file_path = file_path.to_string_lossy();
+    let mut segments: Vec<&str> = file_path.split('/').collect();
+    // The last segment is the name of the importing file itself
+    segments.pop();
+    for segment in import_path.split('/') {
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Returns paths of all import directives of the source as they are written.
+pub(crate) fn import_paths(source: &str) -> Vec<String> {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    let source = pragma::strip_comments(source);
+    let mut paths = Vec::new();
+    let mut is_import = false;
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let mut literal = String::new();
+                let mut escaped = false;
+                for next in chars.by_ref() {
+                    match next {
+                        _ if escaped => {
+                            literal.push(next);
+                            escaped = false
+                        }
+                        '\\' => escaped = true,
+                        _ if next == c => break,
+                        _ => literal.push(next),
+                    }
+                }
+                // The only string literal of the import directive is the path
+                if std::mem::take(&mut is_import) {
+                    paths.push(literal);
+                }
+            }
+            ';' => is_import = false,
+            c if is_identifier_char(c) => {
+                let mut identifier = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if !is_identifier_char(next) {
+                        break;
+                    }
+                    identifier.push(next);
+                    chars.next();
+                }
+                if identifier == "import" {
+                    is_import = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[test]
+    fn extract_import_paths() {
+        let source = r#"
+            // import "Commented.sol";
+            /* import "Commented.sol"; */
+            import "./A.sol";
+            import * as B from '../B.sol';
+            import {C, D as E} from "@org/lib/C.sol";
+            import "F.sol" as F;
+            contract G { string s = "import 'H.sol';"; }
+        "#;
+        assert_eq!(
+            vec!["./A.sol", "../B.sol", "@org/lib/C.sol", "F.sol"],
+            import_paths(source)
+        );
+    }
+
+    #[test]
+    fn resolve_import_paths() {
+        let file_path = Path::new("contracts/token/Token.sol");
+        assert_eq!(
+            "contracts/token/A.sol",
+            resolve_import(file_path, "./A.sol", &[])
+        );
+        assert_eq!(
+            "contracts/utils/B.sol",
+            resolve_import(file_path, "../utils/./B.sol", &[])
+        );
+        assert_eq!("C.sol", resolve_import(file_path, "C.sol", &[]));
+
+        let remappings = [
+            Remapping::from_str("@org/=lib/org/").unwrap(),
+            Remapping::from_str("@org/lib/=lib/org-lib/").unwrap(),
+        ];
+        assert_eq!(
+            "lib/org-lib/D.sol",
+            resolve_import(file_path, "@org/lib/D.sol", &remappings)
+        );
+        assert_eq!(
+            "lib/org/E.sol",
+            resolve_import(file_path, "@org/E.sol", &remappings)
+        );
+    }
+
+    #[test]
+    fn find_missing_sources() {
+        let sources = [
+            (
+                Path::new("contracts/A.sol"),
+                "import './B.sol'; import 'contracts/C.sol';",
+            ),
+            // Cyclic imports are valid
+            (Path::new("contracts/B.sol"), "import './A.sol';"),
+        ];
+        assert_eq!(
+            vec![MissingSource {
+                path: "contracts/C.sol".into(),
+                imported_by: "contracts/A.sol".into(),
+            }],
+            missing_sources(sources, &[])
+        );
+        assert_eq!(
+            "missing source: contracts/C.sol imported by contracts/A.sol",
+            missing_sources(sources, &[])[0].to_string()
+        );
+    }
+
+    #[test]
+    fn package_paths() {
+        assert!(PackageMirror::is_package_path(
+            "@openzeppelin/contracts/token/ERC20/ERC20.sol"
+        ));
+        assert!(!PackageMirror::is_package_path("@openzeppelin/contracts"));
+        assert!(!PackageMirror::is_package_path("contracts/A.sol"));
+    }
+}
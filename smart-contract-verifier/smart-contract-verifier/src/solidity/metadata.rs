@@ -0,0 +1,264 @@
+//! Parsing of the CBOR encoded metadata Solidity appends to the bytecode
+//! (https://docs.soliditylang.org/en/v0.8.22/metadata.html#encoding-of-the-metadata-hash-in-the-bytecode).
+//!
+//! The set of the encoded keys depends on the compiler version and settings:
+//! the hash may be omitted or be either "ipfs" or "bzzr0"/"bzzr1" one, and builds using
+//! experimental features additionally include the `"experimental": true` entry. Thus,
+//! the metadata length is not fixed and is always taken from the 2-bytes big-endian
+//! length the metadata is followed by. The metadata itself is decoded strictly,
+//! so that random bytes preceding the actual metadata are not mistaken for it.
+
+use minicbor::{data::Type, Decoder};
+use mismatch::Mismatch;
+use semver::Version;
+use std::{collections::HashSet, str::FromStr};
+use thiserror::Error;
+
+/// Size of the encoded metadata length appended to the metadata
+const LENGTH_SIZE: usize = 2;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Metadata {
+    pub solc: Option<Version>,
+    /// Whether the contract was compiled with experimental features enabled
+    pub experimental: bool,
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum MetadataError {
+    #[error("invalid cbor: {0}")]
+    Cbor(String),
+    #[error("metadata is expected to be a map of definite length")]
+    NotDefiniteMap,
+    #[error("\"{0}\" key met more than once")]
+    DuplicateKey(String),
+    #[error("invalid \"{key}\" value: {reason}")]
+    InvalidValue { key: &'static str, reason: String },
+    #[error("metadata is not followed by its length")]
+    MissingLength,
+    #[error("encoded metadata length does not correspond to actual metadata length: {0}")]
+    LengthMismatch(Mismatch<usize>),
+}
+
+impl From<minicbor::decode::Error> for MetadataError {
+    fn from(err: minicbor::decode::Error) -> Self {
+        MetadataError::Cbor(err.to_string())
+    }
+}
+
+/// Parses the metadata located at the beginning of `bytes`.
+///
+/// Returns the metadata and the number of bytes it occupies
+/// including the encoded length following it.
+pub fn parse(bytes: &[u8]) -> Result<(Metadata, usize), MetadataError> {
+    let mut decoder = Decoder::new(bytes);
+    let metadata = decode(&mut decoder)?;
+
+    let metadata_length = decoder.position();
+    let encoded_length = bytes
+        .get(metadata_length..metadata_length + LENGTH_SIZE)
+        .map(|length| u16::from_be_bytes([length[0], length[1]]) as usize)
+        .ok_or(MetadataError::MissingLength)?;
+    if encoded_length != metadata_length {
+        return Err(MetadataError::LengthMismatch(Mismatch::new(
+            metadata_length,
+            encoded_length,
+        )));
+    }
+
+    Ok((metadata, metadata_length + LENGTH_SIZE))
+}
+
+/// Splits off the metadata appended to the end of the bytecode, if any.
+pub fn split(bytecode: &[u8]) -> (&[u8], Option<Metadata>) {
+    let start = bytecode
+        .len()
+        .checked_sub(LENGTH_SIZE)
+        .and_then(|length_start| {
+            let length = u16::from_be_bytes([bytecode[length_start], bytecode[length_start + 1]]);
+            length_start.checked_sub(length as usize)
+        });
+    match start.and_then(|start| {
+        parse(&bytecode[start..])
+            .ok()
+            .map(|(metadata, _)| (start, metadata))
+    }) {
+        Some((start, metadata)) => (&bytecode[..start], Some(metadata)),
+        None => (bytecode, None),
+    }
+}
+
+fn decode(decoder: &mut Decoder) -> Result<Metadata, MetadataError> {
+    let number_of_elements = decoder.map()?.ok_or(MetadataError::NotDefiniteMap)?;
+
+    let mut keys = HashSet::new();
+    let mut metadata = Metadata::default();
+    for _ in 0..number_of_elements {
+        let key = decoder.str()?;
+        // duplicate keys are not allowed in CBOR (RFC 8949)
+        if !keys.insert(key) {
+            return Err(MetadataError::DuplicateKey(key.to_string()));
+        }
+        match key {
+            "solc" => metadata.solc = Some(decode_solc(decoder)?),
+            "experimental" => {
+                metadata.experimental =
+                    decoder.bool().map_err(|err| MetadataError::InvalidValue {
+                        key: "experimental",
+                        reason: err.to_string(),
+                    })?
+            }
+            "ipfs" | "bzzr0" | "bzzr1" => {
+                decoder.bytes()?;
+            }
+            // keys that may be added by the future compiler versions
+            _ => decoder.skip()?,
+        }
+    }
+
+    Ok(metadata)
+}
+
+fn decode_solc(decoder: &mut Decoder) -> Result<Version, MetadataError> {
+    let invalid_value = |reason: String| MetadataError::InvalidValue {
+        key: "solc",
+        reason,
+    };
+    match decoder.datatype()? {
+        // Release builds use a 3 byte encoding of the version
+        // (one byte each for major, minor and patch version number)
+        Type::Bytes => match decoder.bytes()? {
+            [major, minor, patch] => Ok(Version::new(*major as u64, *minor as u64, *patch as u64)),
+            _ => Err(invalid_value(
+                "release build should be encoded as exactly 3 bytes".into(),
+            )),
+        },
+        // Prerelease builds use a complete version string including commit hash and build date
+        Type::String => {
+            Version::from_str(decoder.str()?).map_err(|err| invalid_value(err.to_string()))
+        }
+        type_ => Err(invalid_value(format!(
+            "expected \"string\" or \"bytes\", found \"{type_}\""
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DisplayBytes;
+    use pretty_assertions::assert_eq;
+
+    const HASH_WITH_VERSION: &str = "a2646970667358221220bcc988b1311237f2c00ccd0bfbd8b01d24dc18f720603b0de93fe6327df5362564736f6c634300080e0033";
+    // {"ipfs": h'1220EB23...', "experimental": true, "solc": h'000816'}
+    const EXPERIMENTAL: &str = "a3646970667358221220eb23ce2c13ea8739368f952f6c6a4b1f0623d147d2a19b6d4d26a61ab03fcd3e6c6578706572696d656e74616cf564736f6c63430008160041";
+    // {"experimental": true, "solc": h'000816'}
+    const EXPERIMENTAL_WITHOUT_HASH: &str = "a26c6578706572696d656e74616cf564736f6c63430008160018";
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        DisplayBytes::from_str(hex).unwrap().0.to_vec()
+    }
+
+    #[test]
+    fn parse_release_metadata() {
+        let encoded = bytes(HASH_WITH_VERSION);
+        let expected = Metadata {
+            solc: Some(Version::new(0, 8, 14)),
+            experimental: false,
+        };
+        assert_eq!(Ok((expected, encoded.len())), parse(&encoded));
+    }
+
+    #[test]
+    fn parse_prerelease_metadata() {
+        // {"ipfs": h'1220BA5A...', "solc": "0.8.15-ci.2022.5.23+commit.21591531"}
+        let encoded = bytes("a2646970667358221220ba5af27fe13bc83e671bd6981216d35df49ab3ac923741b8948b277f93fbf73264736f6c637823302e382e31352d63692e323032322e352e32332b636f6d6d69742e32313539313533310054");
+        let expected = Metadata {
+            solc: Some(Version::from_str("0.8.15-ci.2022.5.23+commit.21591531").unwrap()),
+            experimental: false,
+        };
+        assert_eq!(Ok((expected, encoded.len())), parse(&encoded));
+    }
+
+    #[test]
+    fn parse_experimental_metadata() {
+        let expected = Metadata {
+            solc: Some(Version::new(0, 8, 22)),
+            experimental: true,
+        };
+        for hex in [EXPERIMENTAL, EXPERIMENTAL_WITHOUT_HASH] {
+            let encoded = bytes(hex);
+            assert_eq!(
+                Ok((expected.clone(), encoded.len())),
+                parse(&encoded),
+                "{hex}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_metadata_followed_by_other_bytes() {
+        let encoded = bytes(&format!("{EXPERIMENTAL}6080604052"));
+        let (metadata, length) = parse(&encoded).expect("parsing failed");
+        assert!(metadata.experimental);
+        assert_eq!(encoded.len() - 5, length);
+    }
+
+    #[test]
+    fn parse_invalid_metadata() {
+        // not followed by the length
+        let encoded = bytes(&HASH_WITH_VERSION[..HASH_WITH_VERSION.len() - 4]);
+        assert_eq!(Err(MetadataError::MissingLength), parse(&encoded));
+
+        // followed by the length of the metadata without the "experimental" entry
+        let encoded = bytes(&format!("{}0034", &EXPERIMENTAL[..EXPERIMENTAL.len() - 4]));
+        assert_eq!(
+            Err(MetadataError::LengthMismatch(Mismatch::new(0x41, 0x34))),
+            parse(&encoded)
+        );
+
+        // "experimental" is not a boolean
+        let encoded = bytes("a16c6578706572696d656e74616c01000f");
+        assert!(matches!(
+            parse(&encoded),
+            Err(MetadataError::InvalidValue {
+                key: "experimental",
+                ..
+            })
+        ));
+
+        // {"solc": h'000400', "solc": h'00080e'}
+        let encoded = bytes("a264736f6c634300040064736f6c634300080e0013");
+        assert_eq!(
+            Err(MetadataError::DuplicateKey("solc".into())),
+            parse(&encoded)
+        );
+
+        // indefinite-length map
+        let encoded = bytes("bf64736f6c634300080eff000c");
+        assert_eq!(Err(MetadataError::NotDefiniteMap), parse(&encoded));
+    }
+
+    #[test]
+    fn split_experimental_metadata() {
+        let code = bytes("6080604052600080fdfe");
+        let bytecode = [code.clone(), bytes(EXPERIMENTAL)].concat();
+
+        let (split_code, metadata) = split(&bytecode);
+        assert_eq!(code, split_code);
+        assert_eq!(
+            Some(Metadata {
+                solc: Some(Version::new(0, 8, 22)),
+                experimental: true,
+            }),
+            metadata
+        );
+    }
+
+    #[test]
+    fn split_without_metadata() {
+        let bytecode = bytes("6080604052600080fdfe");
+        assert_eq!((bytecode.as_slice(), None), split(&bytecode));
+        assert_eq!((&[][..], None), split(&[]));
+    }
+}
@@ -1,11 +1,23 @@
 mod client;
 mod compiler;
+mod dependencies;
+mod imports;
+mod output_selection;
 mod solc_cli;
 mod validator;
 
+pub mod backend;
+pub mod compile;
+pub mod compile_matrix;
+pub mod compiler_bugs;
+pub mod metadata;
 pub mod multi_part;
+pub mod pragma;
+pub mod repository;
 pub mod standard_json;
 
-pub use client::Client;
+pub use client::{Client, DEFAULT_MAX_PARALLEL_COMPILATIONS_PER_REQUEST};
 pub use compiler::SolidityCompiler;
+pub use dependencies::{AllowedPackage, DependencyResolver};
+pub use imports::{MissingSource, PackageMirror};
 pub use validator::SolcValidator;
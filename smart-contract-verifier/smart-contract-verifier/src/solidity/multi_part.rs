@@ -1,21 +1,40 @@
-use super::client::Client;
+use super::{client::Client, imports, output_selection::output_selection, pragma};
 use crate::{
     compiler::Version,
-    verifier::{ContractVerifier, Error, Success},
+    verifier::{trace, ContractVerifier, Error, EvmFork, Normalization, Success, Trace},
 };
 use bytes::Bytes;
 use ethers_solc::{
     artifacts::{BytecodeHash, Libraries, Settings, SettingsMetadata, Source, Sources},
     CompilerInput, EvmVersion,
 };
+use futures::StreamExt;
 use semver::VersionReq;
-use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+use std::{collections::BTreeMap, ops::Range, path::PathBuf, sync::Arc};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VerificationRequest {
     pub deployed_bytecode: Bytes,
     pub creation_bytecode: Option<Bytes>,
     pub compiler_version: Version,
+    /// If set and the compiler version does not satisfy `pragma solidity`
+    /// of the sources, the newest release satisfying them is used instead
+    pub auto_select_compiler_version: bool,
+    /// Byte ranges of the deployed bytecode excluded from the comparison
+    /// (e.g., known patches of genesis predeploys). May be set only
+    /// if no creation bytecode is provided.
+    pub bytecode_mask: Vec<Range<usize>>,
+    /// If set, the bytecodes starting with `0xEF00` are parsed as EOF containers (EIP-3540).
+    /// Should be enabled only for the chains which support EOF.
+    pub eof_enabled: bool,
+    /// EVM version supported by the chain the contract is deployed on. If set, compiled
+    /// bytecodes are checked for the opcodes not supported by the chain if no contract matches.
+    pub evm_fork: Option<EvmFork>,
+    /// Passes excluding the regions which may differ regardless of the sources
+    /// (e.g., values embedded by the deployment tooling) from the comparison
+    pub normalization: Normalization,
+    /// If set, the steps of the verification are recorded into the trace
+    pub trace: Option<Trace>,
 
     pub content: MultiFileContent,
 }
@@ -26,6 +45,9 @@ pub struct MultiFileContent {
     pub evm_version: Option<EvmVersion>,
     pub optimization_runs: Option<usize>,
     pub contract_libraries: Option<BTreeMap<String, String>>,
+    /// If set, outputs not required for verification itself
+    /// (e.g. storage layout) are requested from the compiler as well
+    pub extra_outputs: bool,
 }
 
 impl From<MultiFileContent> for Vec<CompilerInput> {
@@ -44,6 +66,7 @@ impl From<MultiFileContent> for Vec<CompilerInput> {
             settings.libraries = Libraries { libs };
         }
         settings.evm_version = content.evm_version;
+        settings.output_selection = output_selection(content.extra_outputs);
 
         let sources: Sources = content
             .sources
@@ -58,39 +81,98 @@ impl From<MultiFileContent> for Vec<CompilerInput> {
     }
 }
 
-pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result<Success, Error> {
-    let compiler_version = request.compiler_version;
+pub async fn verify(
+    client: Arc<Client>,
+    mut request: VerificationRequest,
+) -> Result<Success, Error> {
+    let sources: Vec<_> = request
+        .content
+        .sources
+        .iter()
+        .map(|(path, content)| (path.as_path(), content.as_str()))
+        .collect();
+    let fetched_sources = imports::resolve_imports(&client, &sources, &[]).await?;
+    request.content.sources.extend(fetched_sources);
+
+    let pragmas = pragma::version_pragmas(
+        request
+            .content
+            .sources
+            .iter()
+            .map(|(path, content)| (path.as_path(), content.as_str())),
+    );
+    let compiler_version = pragma::resolve_compiler_version(
+        client.compilers(),
+        &request.compiler_version,
+        &pragmas,
+        request.auto_select_compiler_version,
+    )?;
+    trace::record(request.trace.as_ref(), || {
+        trace::input_step(
+            &request.compiler_version,
+            &compiler_version,
+            request.creation_bytecode.as_deref(),
+            &request.deployed_bytecode,
+            &request.bytecode_mask,
+            request.content.sources.len(),
+        )
+    });
 
     let verifier = ContractVerifier::new(
         client.compilers(),
         &compiler_version,
         request.creation_bytecode,
         request.deployed_bytecode,
-    )?;
+        request.bytecode_mask,
+        request.eof_enabled,
+        request.normalization,
+    )?
+    .with_evm_fork(request.evm_fork)
+    .with_trace(request.trace);
 
     let compiler_inputs: Vec<CompilerInput> = request.content.into();
-    for mut compiler_input in compiler_inputs {
-        for metadata in settings_metadata(&compiler_version) {
-            compiler_input.settings.metadata = metadata;
-            let result = verifier.verify(&compiler_input).await;
-
-            // If no matching contracts have been found, try the next settings metadata option
-            if let Err(Error::NoMatchingContracts) = result {
-                continue;
-            }
+    let candidates = compiler_inputs.into_iter().flat_map(|compiler_input| {
+        settings_metadata(&compiler_version)
+            .into_iter()
+            .map(move |metadata| {
+                let mut compiler_input = compiler_input.clone();
+                compiler_input.settings.metadata = metadata;
+                compiler_input
+            })
+    });
 
-            // If any error, it is uncorrectable and should be returned immediately, otherwise
-            // we allow middlewares to process success and only then return it to the caller
-            let success = result?;
-            if let Some(middleware) = client.middleware() {
-                middleware.call(&success).await;
+    // Candidates are independent from each other, so we may compile several of them at once.
+    // The results are still processed in the original order, as candidates are sorted
+    // by their probability of occurring. Remaining compilations are cancelled on return.
+    let mut results = futures::stream::iter(candidates)
+        .map(|compiler_input| {
+            let verifier = &verifier;
+            let client = &client;
+            async move {
+                let _permit = client.acquire_compilation_permit().await?;
+                verifier.verify(&compiler_input).await
             }
-            return Ok(success);
+        })
+        .buffered(client.max_parallel_compilations());
+    let mut mismatches = Vec::new();
+    while let Some(result) = results.next().await {
+        // If no matching contracts have been found, try the next candidate
+        if let Err(Error::NoMatchingContracts(candidate_mismatches)) = result {
+            mismatches.extend(candidate_mismatches);
+            continue;
+        }
+
+        // If any error, it is uncorrectable and should be returned immediately, otherwise
+        // we allow middlewares to process success and only then return it to the caller
+        let success = result?;
+        if let Some(middleware) = client.middleware() {
+            middleware.call(&success).await;
         }
+        return Ok(success);
     }
 
     // No contracts could be verified
-    Err(Error::NoMatchingContracts)
+    Err(Error::NoMatchingContracts(mismatches))
 }
 
 /// Iterates through possible bytecode if required and creates
@@ -153,16 +235,18 @@ mod tests {
                 "some_library".into(),
                 "some_address".into(),
             )])),
+            extra_outputs: false,
         };
-        let expected = r#"{"language":"Solidity","sources":{"source.sol":{"content":"pragma"}},"settings":{"optimizer":{"enabled":true,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers"]}},"evmVersion":"london","libraries":{"source.sol":{"some_library":"some_address"}}}}"#;
+        let expected = r#"{"language":"Solidity","sources":{"source.sol":{"content":"pragma"}},"settings":{"optimizer":{"enabled":true,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode.object","evm.bytecode.linkReferences","evm.deployedBytecode","userdoc","devdoc"]}},"evmVersion":"london","libraries":{"source.sol":{"some_library":"some_address"}}}}"#;
         test_to_input(multi_part, vec![expected]);
         let multi_part = MultiFileContent {
             sources: sources(&[("source.sol", "")]),
             evm_version: Some(EvmVersion::SpuriousDragon),
             optimization_runs: None,
             contract_libraries: None,
+            extra_outputs: true,
         };
-        let expected = r#"{"language":"Solidity","sources":{"source.sol":{"content":""}},"settings":{"optimizer":{"enabled":false},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers"]}},"evmVersion":"spuriousDragon","libraries":{}}}"#;
+        let expected = r#"{"language":"Solidity","sources":{"source.sol":{"content":""}},"settings":{"optimizer":{"enabled":false},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode.object","evm.bytecode.linkReferences","evm.deployedBytecode","userdoc","devdoc","evm.methodIdentifiers","evm.gasEstimates","storageLayout"]}},"evmVersion":"spuriousDragon","libraries":{}}}"#;
         test_to_input(multi_part, vec![expected]);
     }
 
@@ -173,9 +257,10 @@ mod tests {
             evm_version: Some(EvmVersion::London),
             optimization_runs: Some(200),
             contract_libraries: None,
+            extra_outputs: false,
         };
-        let expected_solidity = r#"{"language":"Solidity","sources":{"source.sol":{"content":"pragma"}},"settings":{"optimizer":{"enabled":true,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers"]}},"evmVersion":"london","libraries":{}}}"#;
-        let expected_yul = r#"{"language":"Yul","sources":{"source2.yul":{"content":"object \"A\" {}"}},"settings":{"optimizer":{"enabled":true,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers"]}},"evmVersion":"london","libraries":{}}}"#;
+        let expected_solidity = r#"{"language":"Solidity","sources":{"source.sol":{"content":"pragma"}},"settings":{"optimizer":{"enabled":true,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode.object","evm.bytecode.linkReferences","evm.deployedBytecode","userdoc","devdoc"]}},"evmVersion":"london","libraries":{}}}"#;
+        let expected_yul = r#"{"language":"Yul","sources":{"source2.yul":{"content":"object \"A\" {}"}},"settings":{"optimizer":{"enabled":true,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode.object","evm.bytecode.linkReferences","evm.deployedBytecode","userdoc","devdoc"]}},"evmVersion":"london","libraries":{}}}"#;
         test_to_input(multi_part, vec![expected_solidity, expected_yul]);
     }
 }
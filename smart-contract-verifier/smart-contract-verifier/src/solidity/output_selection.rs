@@ -0,0 +1,68 @@
+use ethers_solc::artifacts::output_selection::OutputSelection;
+use std::collections::BTreeMap;
+
+/// Outputs required by the verifier itself and NatSpec documentation
+/// returned with the result. Requesting anything else (e.g. storage layout)
+/// makes compilation of big projects significantly slower.
+/// Link references are required to fill library placeholders of the creation bytecode.
+const VERIFICATION_OUTPUTS: [&str; 6] = [
+    "abi",
+    "evm.bytecode.object",
+    "evm.bytecode.linkReferences",
+    "evm.deployedBytecode",
+    "userdoc",
+    "devdoc",
+];
+/// Source unit ASTs are required to resolve names of immutables.
+const VERIFICATION_FILE_OUTPUTS: [&str; 1] = ["ast"];
+
+/// Outputs additionally requested if a caller has asked for extra outputs.
+const EXTRA_CONTRACT_OUTPUTS: [&str; 3] =
+    ["evm.methodIdentifiers", "evm.gasEstimates", "storageLayout"];
+
+/// Returns output selection to be used for compilation.
+///
+/// By default, only outputs required for verification are selected.
+/// If `extra_outputs` is set, storage layout, method identifiers and gas estimates
+/// are requested as well.
+pub(crate) fn output_selection(extra_outputs: bool) -> OutputSelection {
+    let mut contract_outputs: Vec<String> =
+        VERIFICATION_OUTPUTS.iter().map(|s| s.to_string()).collect();
+    if extra_outputs {
+        contract_outputs.extend(EXTRA_CONTRACT_OUTPUTS.iter().map(|s| s.to_string()));
+    }
+    let file_outputs = VERIFICATION_FILE_OUTPUTS
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let file_selection = BTreeMap::from([
+        ("".to_string(), file_outputs),
+        ("*".to_string(), contract_outputs),
+    ]);
+
+    OutputSelection(BTreeMap::from([("*".to_string(), file_selection)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn minimal_output_selection() {
+        let selection = serde_json::to_string(&output_selection(false)).unwrap();
+        assert_eq!(
+            selection,
+            r#"{"*":{"":["ast"],"*":["abi","evm.bytecode.object","evm.bytecode.linkReferences","evm.deployedBytecode","userdoc","devdoc"]}}"#
+        );
+    }
+
+    #[test]
+    fn extra_output_selection() {
+        let selection = serde_json::to_string(&output_selection(true)).unwrap();
+        assert_eq!(
+            selection,
+            r#"{"*":{"":["ast"],"*":["abi","evm.bytecode.object","evm.bytecode.linkReferences","evm.deployedBytecode","userdoc","devdoc","evm.methodIdentifiers","evm.gasEstimates","storageLayout"]}}"#
+        );
+    }
+}
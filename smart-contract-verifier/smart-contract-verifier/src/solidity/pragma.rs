@@ -0,0 +1,313 @@
+//! Resolution of the compiler version from `pragma solidity` directives of the sources.
+
+use crate::{
+    compiler::{Compilers, EvmCompiler, Version},
+    verifier::Error,
+};
+use semver::VersionReq;
+use std::{
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+/// Version requirement specified by a `pragma solidity` directive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionPragma {
+    pub file_path: PathBuf,
+    /// Requirement as written in the source file (e.g., `>=0.4.22 <0.9.0`)
+    pub requirement: String,
+    /// Alternatives separated by `||` in the original requirement
+    alternatives: Vec<VersionReq>,
+}
+
+impl VersionPragma {
+    /// Parses the version expression following `pragma solidity`.
+    /// Returns `None` if the expression is not a valid version requirement.
+    pub fn parse(file_path: PathBuf, requirement: &str) -> Option<Self> {
+        let requirement = requirement.split_whitespace().collect::<Vec<_>>().join(" ");
+        let alternatives = requirement
+            .split("||")
+            .map(parse_comparators)
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            file_path,
+            requirement,
+            alternatives,
+        })
+    }
+
+    /// Checks the version against the requirement. Pre-release and build
+    /// parts of the version are ignored, as solc does for nightly builds.
+    pub fn matches(&self, version: &semver::Version) -> bool {
+        let version = semver::Version::new(version.major, version.minor, version.patch);
+        self.alternatives.iter().any(|req| req.matches(&version))
+    }
+}
+
+impl Display for VersionPragma {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            self.file_path.to_string_lossy(),
+            self.requirement
+        )
+    }
+}
+
+/// Extracts version pragmas from all source files.
+/// Pragmas that could not be parsed are skipped, as they would be reported by the compiler itself.
+pub fn version_pragmas<'a>(
+    sources: impl IntoIterator<Item = (&'a Path, &'a str)>,
+) -> Vec<VersionPragma> {
+    sources
+        .into_iter()
+        .flat_map(|(file_path, content)| {
+            pragma_expressions(&strip_comments_and_strings(content))
+                .into_iter()
+                .filter_map(|expression| VersionPragma::parse(file_path.to_path_buf(), &expression))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Returns the version the sources should be compiled with.
+///
+/// If the requested version does not satisfy any of the pragmas, either returns
+/// the newest release satisfying all of them (if `auto_select` is set), or fails
+/// with an error listing the requirements.
+pub(crate) fn resolve_compiler_version<C: EvmCompiler>(
+    compilers: &Compilers<C>,
+    requested: &Version,
+    pragmas: &[VersionPragma],
+    auto_select: bool,
+) -> Result<Version, Error> {
+    let incompatible = || Error::IncompatibleCompilerVersion {
+        version: requested.clone(),
+        pragmas: pragmas.iter().map(ToString::to_string).collect(),
+    };
+
+    if satisfies_all(requested, pragmas) {
+        return Ok(requested.clone());
+    }
+    if !auto_select {
+        return Err(incompatible());
+    }
+    newest_release_satisfying(compilers.all_versions(), pragmas).ok_or_else(incompatible)
+}
+
+fn satisfies_all(version: &Version, pragmas: &[VersionPragma]) -> bool {
+    pragmas
+        .iter()
+        .all(|pragma| pragma.matches(version.version()))
+}
+
+fn newest_release_satisfying(versions: Vec<Version>, pragmas: &[VersionPragma]) -> Option<Version> {
+    versions
+        .into_iter()
+        .filter(|version| matches!(version, Version::Release(_)))
+        .filter(|version| satisfies_all(version, pragmas))
+        .max()
+}
+
+/// Converts solidity version comparators (e.g., `>=0.4.22 <0.9.0`, `0.8.4`, `0.4.0 - 0.5.0`)
+/// into the semver requirement.
+fn parse_comparators(expression: &str) -> Option<VersionReq> {
+    // Operators may be separated from versions by whitespaces (e.g., `>= 0.4.22`)
+    let mut tokens: Vec<String> = Vec::new();
+    let mut pending_operator = String::new();
+    for token in expression.split_whitespace() {
+        if token.chars().all(|c| "^~<>=".contains(c)) {
+            pending_operator.push_str(token);
+        } else {
+            tokens.push(format!("{}{token}", std::mem::take(&mut pending_operator)));
+        }
+    }
+    if !pending_operator.is_empty() || tokens.is_empty() {
+        return None;
+    }
+
+    let mut comparators = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if tokens.peek().map(String::as_str) == Some("-") {
+            // Hyphen range: `0.4.0 - 0.5.0` means `>=0.4.0 <=0.5.0`
+            tokens.next();
+            let upper = tokens.next()?;
+            comparators.push(format!(">={token}"));
+            comparators.push(format!("<={upper}"));
+        } else if token.starts_with(|c: char| c.is_ascii_digit()) {
+            // Solidity treats a version without an operator as an exact one
+            comparators.push(format!("={token}"));
+        } else {
+            comparators.push(token);
+        }
+    }
+
+    VersionReq::parse(&comparators.join(", ")).ok()
+}
+
+/// Returns expressions of all `pragma solidity ...;` directives of the source.
+fn pragma_expressions(source: &str) -> Vec<String> {
+    let mut expressions = Vec::new();
+    let mut rest = source;
+    while let Some(index) = rest.find("pragma") {
+        let is_word_start = rest[..index]
+            .chars()
+            .last()
+            .map_or(true, |c| !(c.is_alphanumeric() || c == '_'));
+        rest = &rest[index + "pragma".len()..];
+        if !is_word_start {
+            continue;
+        }
+        let directive = rest.trim_start();
+        if let Some(directive) = directive.strip_prefix("solidity") {
+            if directive.starts_with(char::is_whitespace) {
+                if let Some(end) = directive.find(';') {
+                    expressions.push(directive[..end].trim().to_string());
+                }
+            }
+        }
+    }
+    expressions
+}
+
+/// Removes comments and contents of string literals from the source,
+/// so that pragmas inside them are ignored.
+fn strip_comments_and_strings(source: &str) -> String {
+    strip_comments_impl(source, false)
+}
+
+/// Removes comments from the source keeping string literals as is.
+pub(super) fn strip_comments(source: &str) -> String {
+    strip_comments_impl(source, true)
+}
+
+fn strip_comments_impl(source: &str, keep_strings: bool) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        result.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+                result.push(' ');
+            }
+            '"' | '\'' => {
+                result.push(c);
+                let mut escaped = false;
+                for next in chars.by_ref() {
+                    if keep_strings {
+                        result.push(next);
+                    }
+                    match next {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        _ if next == c => break,
+                        _ => {}
+                    }
+                }
+                if !keep_strings {
+                    result.push(c);
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn pragma(requirement: &str) -> VersionPragma {
+        VersionPragma::parse("A.sol".into(), requirement).expect("invalid requirement")
+    }
+
+    fn version(version: &str) -> Version {
+        Version::from_str(version).unwrap()
+    }
+
+    #[test]
+    fn parse_requirements() {
+        let check = |requirement: &str, matching: &[&str], not_matching: &[&str]| {
+            let pragma = pragma(requirement);
+            for v in matching {
+                let v = semver::Version::parse(v).unwrap();
+                assert!(pragma.matches(&v), "{requirement} should match {v}");
+            }
+            for v in not_matching {
+                let v = semver::Version::parse(v).unwrap();
+                assert!(!pragma.matches(&v), "{requirement} should not match {v}");
+            }
+        };
+
+        check("^0.8.4", &["0.8.4", "0.8.19"], &["0.8.3", "0.9.0"]);
+        check("0.8.4", &["0.8.4"], &["0.8.5"]);
+        check(
+            ">=0.4.22 <0.9.0",
+            &["0.4.22", "0.8.19"],
+            &["0.4.21", "0.9.0"],
+        );
+        check(">= 0.6.0  < 0.7.0", &["0.6.12"], &["0.7.0"]);
+        check("0.4.0 - 0.5.0", &["0.4.0", "0.5.0"], &["0.5.1"]);
+        check("^0.5.0 || ^0.7.0", &["0.5.17", "0.7.6"], &["0.6.12"]);
+        check("~0.6.2", &["0.6.12"], &["0.6.1", "0.7.0"]);
+        check("^0.8.0", &["0.8.8-nightly.2021.9.9"], &[]);
+
+        assert_eq!(None, VersionPragma::parse("A.sol".into(), ">="));
+        assert_eq!(None, VersionPragma::parse("A.sol".into(), "abicoder"));
+    }
+
+    #[test]
+    fn extract_pragmas() {
+        let source = r#"
+            // pragma solidity 0.4.0;
+            /* pragma solidity 0.5.0; */
+            pragma solidity >=0.8.0
+                <0.9.0;
+            pragma abicoder v2;
+            pragma experimental ABIEncoderV2;
+            contract A { string s = "pragma solidity 0.6.0;"; }
+        "#;
+        let pragmas = version_pragmas([(Path::new("A.sol"), source)]);
+        assert_eq!(vec![pragma(">=0.8.0 <0.9.0")], pragmas);
+        assert_eq!("A.sol: >=0.8.0 <0.9.0", pragmas[0].to_string());
+    }
+
+    #[test]
+    fn select_newest_release() {
+        let versions = vec![
+            version("v0.8.17+commit.8df45f5f"),
+            version("v0.8.7+commit.e28d00a7"),
+            version("v0.8.18-nightly.2022.11.23+commit.eb2f874e"),
+            version("v0.7.6+commit.7338295f"),
+        ];
+        let pragmas = [pragma(">=0.7.0"), pragma("<0.8.10")];
+        assert_eq!(
+            Some(version("v0.8.7+commit.e28d00a7")),
+            newest_release_satisfying(versions.clone(), &pragmas)
+        );
+        assert_eq!(
+            None,
+            newest_release_satisfying(versions, &[pragma("^0.6.0")])
+        );
+    }
+}
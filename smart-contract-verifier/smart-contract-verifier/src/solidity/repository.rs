@@ -0,0 +1,608 @@
+//! Verification of the contracts from their source repositories hosted on GitHub.
+//!
+//! The archive of the specified commit is downloaded, which is the equivalent of a shallow
+//! clone without submodules. The framework of the project (Foundry or Hardhat) is detected
+//! by its configuration files, and the standard json input is built from the project sources
+//! and the files imported by them. Dependencies missing from the repository (e.g., git submodules
+//! or npm packages) are resolved the same way as for any other submission.
+
+use super::{
+    client::Client,
+    dependencies, imports, pragma,
+    standard_json::{self, StandardJsonContent},
+};
+use crate::{
+    compiler::Version,
+    verifier::{Error, EvmFork, Normalization, Success},
+};
+use anyhow::{anyhow, Context};
+use bytes::Bytes;
+use ethers_solc::{
+    artifacts::{Settings, Source, Sources},
+    remappings::Remapping,
+    CompilerInput, EvmVersion,
+};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use url::Url;
+
+/// Maximum size of the downloaded repository archive.
+const MAX_REPOSITORY_ARCHIVE_SIZE: u64 = 50 * 1024 * 1024;
+/// Maximum total size of the files extracted from a single repository archive.
+const MAX_REPOSITORY_FILES_SIZE: u64 = 50 * 1024 * 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Maximum time of the whole archive download
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+const FOUNDRY_CONFIG: &str = "foundry.toml";
+const FOUNDRY_REMAPPINGS: &str = "remappings.txt";
+const HARDHAT_CONFIGS: [&str; 4] = [
+    "hardhat.config.js",
+    "hardhat.config.ts",
+    "hardhat.config.cjs",
+    "hardhat.config.mjs",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Framework {
+    Foundry,
+    Hardhat,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GithubRepository {
+    pub owner: String,
+    pub name: String,
+}
+
+impl Display for GithubRepository {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+impl FromStr for GithubRepository {
+    type Err = anyhow::Error;
+
+    /// Parses the repository url of the form `https://github.com/<owner>/<name>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url = Url::parse(s)?;
+        if url.scheme() != "https" || url.host_str() != Some("github.com") {
+            return Err(anyhow!(
+                "only https://github.com repositories are supported"
+            ));
+        }
+        let segments: Vec<_> = url
+            .path()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let is_valid_name = |name: &str| {
+            !name.starts_with('.')
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        match segments.as_slice() {
+            [owner, name] => {
+                let name = name.strip_suffix(".git").unwrap_or(name);
+                if !is_valid_name(owner) || !is_valid_name(name) || name.is_empty() {
+                    return Err(anyhow!("invalid repository name: {owner}/{name}"));
+                }
+                Ok(Self {
+                    owner: owner.to_string(),
+                    name: name.to_string(),
+                })
+            }
+            _ => Err(anyhow!(
+                "repository url should be of the form https://github.com/<owner>/<name>"
+            )),
+        }
+    }
+}
+
+/// Checks that the commit is specified by its full hash,
+/// so that the verified sources could not be changed afterwards.
+pub fn is_commit_hash(commit: &str) -> bool {
+    commit.len() == 40 && commit.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub struct VerificationRequest {
+    pub deployed_bytecode: Bytes,
+    pub creation_bytecode: Option<Bytes>,
+    pub compiler_version: Version,
+    /// If set and the compiler version does not satisfy `pragma solidity`
+    /// of the sources, the newest release satisfying them is used instead
+    pub auto_select_compiler_version: bool,
+    /// Byte ranges of the deployed bytecode excluded from the comparison
+    /// (e.g., known patches of genesis predeploys). May be set only
+    /// if no creation bytecode is provided.
+    pub bytecode_mask: Vec<Range<usize>>,
+    /// If set, the bytecodes starting with `0xEF00` are parsed as EOF containers (EIP-3540).
+    /// Should be enabled only for the chains which support EOF.
+    pub eof_enabled: bool,
+    /// EVM version supported by the chain the contract is deployed on. If set, compiled
+    /// bytecodes are checked for the opcodes not supported by the chain if no contract matches.
+    pub evm_fork: Option<EvmFork>,
+    /// Passes excluding the regions which may differ regardless of the sources
+    /// (e.g., values embedded by the deployment tooling) from the comparison
+    pub normalization: Normalization,
+
+    pub repository: GithubRepository,
+    /// Full hash of the commit the sources are taken from
+    pub commit: String,
+    /// Directory of the project inside the repository (empty for the repository root)
+    pub path: String,
+    /// If not set, the framework is detected by the configuration files of the project
+    pub framework: Option<Framework>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RepositoryFetcher {
+    github_url: Url,
+    client: reqwest::Client,
+}
+
+impl RepositoryFetcher {
+    /// `github_url` is the host the commit archives are downloaded from
+    /// (e.g., `https://codeload.github.com/`).
+    pub fn new(github_url: Url) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(DOWNLOAD_TIMEOUT)
+            .build()?;
+        Ok(Self { github_url, client })
+    }
+
+    /// Returns solidity sources and configuration files of the project
+    /// by their paths relative to the project directory.
+    pub(crate) async fn fetch(
+        &self,
+        repository: &GithubRepository,
+        commit: &str,
+        path: &str,
+    ) -> Result<BTreeMap<String, String>, anyhow::Error> {
+        if !is_commit_hash(commit) {
+            return Err(anyhow!("commit should be specified by its full hash"));
+        }
+        let path = path.trim_matches('/');
+        if path.split('/').any(|segment| segment == "..") {
+            return Err(anyhow!("project path should not leave the repository"));
+        }
+
+        let url = self
+            .github_url
+            .join(&format!("{repository}/tar.gz/{commit}"))?;
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        let archive = read_limited(response, MAX_REPOSITORY_ARCHIVE_SIZE).await?;
+
+        let path = path.to_string();
+        tokio::task::spawn_blocking(move || {
+            dependencies::extract_files(
+                &archive,
+                &path,
+                |path| path.ends_with(".sol") || is_config_file(path),
+                MAX_REPOSITORY_FILES_SIZE,
+            )
+        })
+        .await
+        .context("archive extraction task failed")?
+    }
+}
+
+/// Reads the response body, failing as soon as its size exceeds `max_size`,
+/// so that huge archives are not buffered in memory.
+async fn read_limited(
+    mut response: reqwest::Response,
+    max_size: u64,
+) -> Result<Vec<u8>, anyhow::Error> {
+    if let Some(content_length) = response.content_length() {
+        if content_length > max_size {
+            return Err(anyhow!("archive is too large: {content_length} bytes"));
+        }
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() as u64 + chunk.len() as u64 > max_size {
+            return Err(anyhow!("archive is too large"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+fn is_config_file(path: &str) -> bool {
+    path == FOUNDRY_CONFIG || path == FOUNDRY_REMAPPINGS || HARDHAT_CONFIGS.contains(&path)
+}
+
+pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result<Success, Error> {
+    let fetcher = client
+        .repository_fetcher()
+        .ok_or_else(|| Error::Internal(anyhow!("verification from repositories is not enabled")))?;
+    let files = fetcher
+        .fetch(&request.repository, &request.commit, &request.path)
+        .await
+        .map_err(|err| {
+            Error::Initialization(anyhow!(
+                "repository {} could not be fetched at {}: {err:#}",
+                request.repository,
+                request.commit
+            ))
+        })?;
+    let input = compiler_input(files, request.framework).map_err(Error::Initialization)?;
+
+    let request = standard_json::VerificationRequest {
+        deployed_bytecode: request.deployed_bytecode,
+        creation_bytecode: request.creation_bytecode,
+        compiler_version: request.compiler_version,
+        auto_select_compiler_version: request.auto_select_compiler_version,
+        bytecode_mask: request.bytecode_mask,
+        eof_enabled: request.eof_enabled,
+        evm_fork: request.evm_fork,
+        normalization: request.normalization,
+        trace: None,
+        retry_with_flipped_via_ir: false,
+        content: StandardJsonContent {
+            input,
+            extra_outputs: false,
+        },
+    };
+    standard_json::verify(client, request).await
+}
+
+fn detect_framework(files: &BTreeMap<String, String>) -> Option<Framework> {
+    if files.contains_key(FOUNDRY_CONFIG) {
+        Some(Framework::Foundry)
+    } else if HARDHAT_CONFIGS
+        .iter()
+        .any(|config| files.contains_key(*config))
+    {
+        Some(Framework::Hardhat)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FoundryConfig {
+    #[serde(default)]
+    profile: BTreeMap<String, FoundryProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct FoundryProfile {
+    src: String,
+    remappings: Vec<String>,
+    optimizer: bool,
+    optimizer_runs: usize,
+    evm_version: Option<String>,
+    via_ir: bool,
+}
+
+impl Default for FoundryProfile {
+    fn default() -> Self {
+        Self {
+            src: "src".to_string(),
+            remappings: vec![],
+            optimizer: true,
+            optimizer_runs: 200,
+            evm_version: None,
+            via_ir: false,
+        }
+    }
+}
+
+/// Returns the directory of the project sources and the compiler settings
+/// specified by the default profile of `foundry.toml` and `remappings.txt`.
+fn foundry_settings(files: &BTreeMap<String, String>) -> Result<(String, Settings), anyhow::Error> {
+    let config: FoundryConfig = match files.get(FOUNDRY_CONFIG) {
+        Some(config) => toml::from_str(config).context("invalid foundry.toml")?,
+        None => FoundryConfig::default(),
+    };
+    let profile = config.profile.get("default");
+    let default_profile = FoundryProfile::default();
+    let profile = profile.unwrap_or(&default_profile);
+
+    let remappings_file = files
+        .get(FOUNDRY_REMAPPINGS)
+        .map(|remappings| {
+            remappings
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let remappings = profile
+        .remappings
+        .iter()
+        .chain(remappings_file.iter())
+        .map(|remapping| {
+            Remapping::from_str(remapping).map_err(|err| anyhow!("invalid remapping: {err}"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut settings = Settings {
+        remappings,
+        via_ir: profile.via_ir.then_some(true),
+        evm_version: parse_evm_version(profile.evm_version.as_deref())?,
+        ..Default::default()
+    };
+    settings.optimizer.enabled = Some(profile.optimizer);
+    settings.optimizer.runs = Some(profile.optimizer_runs);
+    Ok((profile.src.trim_matches('/').to_string(), settings))
+}
+
+/// Returns the directory of the project sources and the compiler settings found
+/// in the hardhat configuration. The configuration is a script which cannot be
+/// evaluated, thus only the literal values of the first compiler are recognized.
+fn hardhat_settings(files: &BTreeMap<String, String>) -> Result<(String, Settings), anyhow::Error> {
+    let config = HARDHAT_CONFIGS
+        .iter()
+        .find_map(|config| files.get(*config))
+        .map(|config| pragma::strip_comments(config))
+        .unwrap_or_default();
+
+    let sources_dir = config_value(&config, "sources")
+        .map(|dir| dir.trim_start_matches("./").trim_matches('/'))
+        .unwrap_or("contracts");
+    let mut settings = Settings {
+        via_ir: config_value(&config, "viaIR")
+            .map(|value| value == "true")
+            .filter(|via_ir| *via_ir),
+        evm_version: parse_evm_version(config_value(&config, "evmVersion"))?,
+        ..Default::default()
+    };
+    settings.optimizer.enabled = Some(config_value(&config, "enabled") == Some("true"));
+    settings.optimizer.runs = Some(
+        config_value(&config, "runs")
+            .map(|runs| runs.parse().context("invalid optimizer runs"))
+            .transpose()?
+            .unwrap_or(200),
+    );
+    Ok((sources_dir.to_string(), settings))
+}
+
+/// Returns the literal value of the first `key: value` entry of the script object.
+fn config_value<'a>(config: &'a str, key: &str) -> Option<&'a str> {
+    let mut rest = config;
+    while let Some(position) = rest.find(key) {
+        let is_key_start = rest[..position]
+            .chars()
+            .last()
+            .map_or(true, |c| !(c.is_alphanumeric() || c == '_'));
+        rest = &rest[position + key.len()..];
+        let value = rest
+            .trim_start_matches(['"', '\''])
+            .trim_start()
+            .strip_prefix(':');
+        if let (true, Some(value)) = (is_key_start, value) {
+            let end = value.find([',', '}', '\n']).unwrap_or(value.len());
+            let value = value[..end].trim().trim_matches(['"', '\'', '`']);
+            return Some(value).filter(|value| !value.is_empty());
+        }
+    }
+    None
+}
+
+fn parse_evm_version(evm_version: Option<&str>) -> Result<Option<EvmVersion>, anyhow::Error> {
+    evm_version
+        .map(|version| {
+            EvmVersion::from_str(version).map_err(|err| anyhow!("invalid evm version: {err}"))
+        })
+        .transpose()
+}
+
+/// Builds the standard json input from the project files. Includes the sources
+/// of the project sources directory and all files of the repository imported by them.
+pub(crate) fn compiler_input(
+    files: BTreeMap<String, String>,
+    framework: Option<Framework>,
+) -> Result<CompilerInput, anyhow::Error> {
+    let framework = framework
+        .or_else(|| detect_framework(&files))
+        .ok_or_else(|| anyhow!("neither foundry nor hardhat configuration was found"))?;
+    let (sources_dir, settings) = match framework {
+        Framework::Foundry => foundry_settings(&files)?,
+        Framework::Hardhat => hardhat_settings(&files)?,
+    };
+
+    let mut included: BTreeSet<&str> = files
+        .keys()
+        .filter(|path| path.ends_with(".sol"))
+        .filter(|path| {
+            sources_dir.is_empty()
+                || path
+                    .strip_prefix(&sources_dir)
+                    .map_or(false, |path| path.starts_with('/'))
+        })
+        .map(String::as_str)
+        .collect();
+    if included.is_empty() {
+        return Err(anyhow!("no sources were found in '{sources_dir}'"));
+    }
+
+    let mut queue: Vec<&str> = included.iter().copied().collect();
+    while let Some(path) = queue.pop() {
+        for import_path in imports::import_paths(&files[path]) {
+            let import =
+                imports::resolve_import(Path::new(path), &import_path, &settings.remappings);
+            if let Some((import, _)) = files.get_key_value(&import) {
+                if included.insert(import.as_str()) {
+                    queue.push(import.as_str());
+                }
+            }
+        }
+    }
+
+    let sources: Sources = included
+        .into_iter()
+        .map(|path| (PathBuf::from(path), Source::new(files[path].clone())))
+        .collect();
+    Ok(CompilerInput {
+        language: "Solidity".to_string(),
+        sources,
+        settings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    fn files(files: &[(&str, &str)]) -> BTreeMap<String, String> {
+        files
+            .iter()
+            .map(|(path, content)| (path.to_string(), content.to_string()))
+            .collect()
+    }
+
+    fn source_names(input: &CompilerInput) -> Vec<String> {
+        input
+            .sources
+            .keys()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn parse_repository_urls() {
+        let expected = GithubRepository {
+            owner: "blockscout".into(),
+            name: "blockscout-rs".into(),
+        };
+        for url in [
+            "https://github.com/blockscout/blockscout-rs",
+            "https://github.com/blockscout/blockscout-rs/",
+            "https://github.com/blockscout/blockscout-rs.git",
+        ] {
+            assert_eq!(expected, GithubRepository::from_str(url).unwrap(), "{url}");
+        }
+        for url in [
+            "http://github.com/blockscout/blockscout-rs",
+            "https://gitlab.com/blockscout/blockscout-rs",
+            "https://github.com/blockscout",
+            "https://github.com/blockscout/blockscout-rs/tree/main",
+            "https://github.com/blockscout/..",
+        ] {
+            assert!(GithubRepository::from_str(url).is_err(), "{url}");
+        }
+    }
+
+    #[test]
+    fn check_commit_hashes() {
+        assert!(is_commit_hash("8df45f5f8632da4817bc7ceb81497518f298d290"));
+        assert!(!is_commit_hash("8df45f5"));
+        assert!(!is_commit_hash(
+            "refs/heads/8df45f5f8632da4817bc7ceb8149751"
+        ));
+    }
+
+    #[test]
+    fn foundry_project() {
+        let files = files(&[
+            (
+                "foundry.toml",
+                "[profile.default]\noptimizer_runs = 1000\nevm_version = \"london\"\nvia_ir = true\n",
+            ),
+            ("remappings.txt", "solmate/=lib/solmate/src/\n"),
+            (
+                "src/Token.sol",
+                "import \"solmate/tokens/ERC20.sol\";\nimport {Math} from \"./utils/Math.sol\";",
+            ),
+            ("src/utils/Math.sol", "library Math {}"),
+            ("lib/solmate/src/tokens/ERC20.sol", "contract ERC20 {}"),
+            ("lib/solmate/src/tokens/ERC721.sol", "contract ERC721 {}"),
+            ("test/Token.t.sol", "import \"forge-std/Test.sol\";"),
+        ]);
+
+        let input = compiler_input(files, None).unwrap();
+        assert_eq!(
+            vec![
+                "lib/solmate/src/tokens/ERC20.sol",
+                "src/Token.sol",
+                "src/utils/Math.sol"
+            ],
+            source_names(&input)
+        );
+        assert_eq!(Some(true), input.settings.optimizer.enabled);
+        assert_eq!(Some(1000), input.settings.optimizer.runs);
+        assert_eq!(Some(EvmVersion::London), input.settings.evm_version);
+        assert_eq!(Some(true), input.settings.via_ir);
+        assert_eq!(1, input.settings.remappings.len());
+    }
+
+    #[test]
+    fn hardhat_project() {
+        let files = files(&[
+            (
+                "hardhat.config.ts",
+                r#"const config = {
+    solidity: {
+        version: "0.8.19",
+        settings: {
+            // enabled: false,
+            optimizer: { enabled: true, runs: 10000 },
+        },
+    },
+    paths: { sources: "./src/contracts" },
+};"#,
+            ),
+            (
+                "src/contracts/Token.sol",
+                "import \"@openzeppelin/contracts/token/ERC20/ERC20.sol\";",
+            ),
+            ("contracts/Mock.sol", "contract Mock {}"),
+        ]);
+
+        let input = compiler_input(files, None).unwrap();
+        assert_eq!(vec!["src/contracts/Token.sol"], source_names(&input));
+        assert_eq!(Some(true), input.settings.optimizer.enabled);
+        assert_eq!(Some(10000), input.settings.optimizer.runs);
+        assert_eq!(None, input.settings.via_ir);
+    }
+
+    #[test]
+    fn framework_hint_overrides_detection() {
+        let files = files(&[
+            ("hardhat.config.js", "module.exports = {};"),
+            ("src/Token.sol", "contract Token {}"),
+        ]);
+        assert!(compiler_input(files.clone(), None).is_err());
+
+        let input = compiler_input(files, Some(Framework::Foundry)).unwrap();
+        assert_eq!(vec!["src/Token.sol"], source_names(&input));
+    }
+
+    #[tokio::test]
+    async fn large_archives_are_not_read() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 1024]))
+            .mount(&mock_server)
+            .await;
+        let get = || async { reqwest::get(mock_server.uri()).await.unwrap() };
+
+        assert_eq!(1024, read_limited(get().await, 1024).await.unwrap().len());
+        let err = read_limited(get().await, 1023).await.unwrap_err();
+        assert!(err.to_string().contains("too large"), "{err:#}");
+    }
+
+    #[test]
+    fn unknown_framework() {
+        let files = files(&[("src/Token.sol", "contract Token {}")]);
+        assert!(compiler_input(files, None).is_err());
+    }
+}
@@ -4,6 +4,7 @@
 //! was added only since 0.4.10 version. So, to compile older versions
 //! we need convert functions for CompilerInput and CompilerOutput.
 
+use crate::compiler::SourcesDirs;
 use ethers_solc::{
     artifacts::Severity,
     error::{SolcError, SolcIoError},
@@ -28,6 +29,7 @@ mod serde_helpers {
 
 mod types {
     use super::serde_helpers;
+    use crate::compiler::{SourcesDir, SourcesDirs};
     use ethers_solc::{
         artifacts::{Contract, Libraries},
         error::SolcError,
@@ -38,8 +40,6 @@ mod types {
         collections::{BTreeMap, HashMap},
         path::PathBuf,
     };
-    use tempfile::TempDir;
-    use tokio::io::AsyncWriteExt;
 
     #[derive(Debug, PartialEq, Eq)]
     pub struct InputArgs {
@@ -98,41 +98,25 @@ mod types {
 
     #[derive(Debug)]
     pub struct InputFiles {
-        pub files_dir: TempDir,
+        pub files_dir: SourcesDir,
         pub file_names: Vec<PathBuf>,
     }
 
     impl InputFiles {
-        pub async fn try_from_compiler_input(input: &CompilerInput) -> Result<Self, SolcError> {
-            if !input.sources.is_empty() {
-                let files_dir =
-                    tempfile::tempdir().map_err(|e| SolcError::Message(e.to_string()))?;
-                let mut file_names = Vec::new();
-                for (name, source) in input.sources.iter() {
-                    let file_path = files_dir.path().join(name);
-                    // name itself may contain some paths inside
-                    let prefix = file_path.parent();
-                    if let Some(prefix) = prefix {
-                        tokio::fs::create_dir_all(prefix)
-                            .await
-                            .map_err(|e| SolcError::Message(e.to_string()))?;
-                    }
-                    let mut file = tokio::fs::File::create(&file_path)
-                        .await
-                        .map_err(|e| SolcError::Message(e.to_string()))?;
-                    file_names.push(file_path);
-                    file.write_all(source.content.as_bytes())
-                        .await
-                        .map_err(|e| SolcError::Message(e.to_string()))?;
-                }
-
-                Ok(InputFiles {
-                    files_dir,
-                    file_names,
-                })
-            } else {
-                Err(SolcError::Message("no files were provided".to_string()))
-            }
+        pub async fn try_from_compiler_input(
+            input: &CompilerInput,
+            sources_dirs: &SourcesDirs,
+        ) -> Result<Self, SolcError> {
+            let files_dir = sources_dirs.write(input).await?;
+            let file_names = input
+                .sources
+                .keys()
+                .map(|name| files_dir.path().join(name))
+                .collect();
+            Ok(InputFiles {
+                files_dir,
+                file_names,
+            })
         }
 
         pub fn build(&self) -> Result<&Vec<PathBuf>, SolcError> {
@@ -239,13 +223,16 @@ fn compiler_error(message: String) -> ethers_solc::artifacts::Error {
 pub async fn compile_using_cli(
     solc: &Path,
     input: &CompilerInput,
+    sources_dirs: &SourcesDirs,
 ) -> Result<CompilerOutput, SolcError> {
     let output = {
         let input_args = types::InputArgs::try_from(input)?;
-        let input_files = types::InputFiles::try_from_compiler_input(input).await?;
+        let input_files = types::InputFiles::try_from_compiler_input(input, sources_dirs).await?;
         Command::new(solc)
             .args(input_args.build())
             .args(input_files.build()?)
+            // The compiler should not outlive the cancelled compilation and its sources directory
+            .kill_on_drop(true)
             .stderr(Stdio::piped())
             .stdout(Stdio::piped())
             .output()
@@ -413,9 +400,10 @@ mod tests {
     async fn correct_input_files() {
         let input: CompilerInput = serde_json::from_str(DEFAULT_COMPILER_INPUT).unwrap();
 
-        let input_files = types::InputFiles::try_from_compiler_input(&input)
-            .await
-            .expect("failed to convert files");
+        let input_files =
+            types::InputFiles::try_from_compiler_input(&input, &SourcesDirs::default())
+                .await
+                .expect("failed to convert files");
         assert!(input_files.files_dir.path().exists());
 
         let expected_files: Vec<PathBuf> = vec!["a.sol", "b.sol", "main.sol"]
@@ -497,7 +485,7 @@ mod tests {
             let solc = get_solc(&version).await;
 
             let input: CompilerInput = serde_json::from_str(DEFAULT_COMPILER_INPUT).unwrap();
-            let output: CompilerOutput = compile_using_cli(&solc, &input)
+            let output: CompilerOutput = compile_using_cli(&solc, &input, &SourcesDirs::default())
                 .await
                 .unwrap_or_else(|_| panic!("failed to compile contracts with {ver}"));
             assert!(
@@ -519,9 +507,10 @@ mod tests {
                     sources,
                     settings: Settings::default(),
                 };
-                let output: CompilerOutput = compile_using_cli(&solc, &input)
-                    .await
-                    .expect("shouldn't return Err, but Ok with errors field");
+                let output: CompilerOutput =
+                    compile_using_cli(&solc, &input, &SourcesDirs::default())
+                        .await
+                        .expect("shouldn't return Err, but Ok with errors field");
                 assert!(output.has_error());
             }
 
@@ -530,7 +519,7 @@ mod tests {
                 sources: BTreeMap::new(),
                 settings: Settings::default(),
             };
-            compile_using_cli(&solc, &input)
+            compile_using_cli(&solc, &input, &SourcesDirs::default())
                 .await
                 .expect_err("should not compile empty files");
         }
@@ -1,22 +1,74 @@
-use super::client::Client;
+use super::{client::Client, imports, output_selection::output_selection, pragma};
 use crate::{
+    analyzer::settings_inference,
     compiler::Version,
-    verifier::{ContractVerifier, Error, Success},
+    verifier::{trace, ContractVerifier, Error, EvmFork, Normalization, Success, Trace},
 };
 use bytes::Bytes;
-use ethers_solc::{artifacts::output_selection::OutputSelection, CompilerInput};
-use std::sync::Arc;
+use ethers_solc::{artifacts::Source, CompilerInput};
+use std::{ops::Range, sync::Arc};
 
 pub struct VerificationRequest {
     pub deployed_bytecode: Bytes,
     pub creation_bytecode: Option<Bytes>,
     pub compiler_version: Version,
+    /// If set and the compiler version does not satisfy `pragma solidity`
+    /// of the sources, the newest release satisfying them is used instead
+    pub auto_select_compiler_version: bool,
+    /// Byte ranges of the deployed bytecode excluded from the comparison
+    /// (e.g., known patches of genesis predeploys). May be set only
+    /// if no creation bytecode is provided.
+    pub bytecode_mask: Vec<Range<usize>>,
+    /// If set, the bytecodes starting with `0xEF00` are parsed as EOF containers (EIP-3540).
+    /// Should be enabled only for the chains which support EOF.
+    pub eof_enabled: bool,
+    /// EVM version supported by the chain the contract is deployed on. If set, compiled
+    /// bytecodes are checked for the opcodes not supported by the chain if no contract matches.
+    pub evm_fork: Option<EvmFork>,
+    /// Passes excluding the regions which may differ regardless of the sources
+    /// (e.g., values embedded by the deployment tooling) from the comparison
+    pub normalization: Normalization,
+    /// If set, the steps of the verification are recorded into the trace
+    pub trace: Option<Trace>,
+    /// If set and no contract matches, while the bytecode looks like the output
+    /// of the other code generation pipeline, the sources are recompiled with `viaIR` flipped
+    pub retry_with_flipped_via_ir: bool,
 
     pub content: StandardJsonContent,
 }
 
+/// Request to verify several contracts compiled from the same sources
+/// (e.g., deployed by a factory with different constructor arguments).
+pub struct BatchVerificationRequest {
+    pub targets: Vec<VerificationTarget>,
+    pub compiler_version: Version,
+    /// If set and the compiler version does not satisfy `pragma solidity`
+    /// of the sources, the newest release satisfying them is used instead
+    pub auto_select_compiler_version: bool,
+    /// If set, the bytecodes starting with `0xEF00` are parsed as EOF containers (EIP-3540).
+    /// Should be enabled only for the chains which support EOF.
+    pub eof_enabled: bool,
+    /// EVM version supported by the chain the contracts are deployed on. If set, compiled
+    /// bytecodes are checked for the opcodes not supported by the chain if no contract matches.
+    pub evm_fork: Option<EvmFork>,
+    /// Passes excluding the regions which may differ regardless of the sources
+    /// (e.g., values embedded by the deployment tooling) from the comparison
+    pub normalization: Normalization,
+
+    pub content: StandardJsonContent,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationTarget {
+    pub deployed_bytecode: Bytes,
+    pub creation_bytecode: Option<Bytes>,
+}
+
 pub struct StandardJsonContent {
     pub input: CompilerInput,
+    /// If set, outputs not required for verification itself
+    /// (e.g. storage layout) are requested from the compiler as well
+    pub extra_outputs: bool,
 }
 
 impl From<StandardJsonContent> for CompilerInput {
@@ -25,22 +77,73 @@ impl From<StandardJsonContent> for CompilerInput {
 
         // always overwrite output selection as it customizes what compiler outputs and
         // is not what is returned to the user, but only used internally by our service
-        let output_selection = OutputSelection::default_output_selection();
-        input.settings.output_selection = output_selection;
+        input.settings.output_selection = output_selection(content.extra_outputs);
 
         input
     }
 }
 
 pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result<Success, Error> {
-    let compiler_input = CompilerInput::from(request.content);
-    let verifier = ContractVerifier::new(
+    let mut compiler_input = CompilerInput::from(request.content);
+    add_missing_sources(&client, &mut compiler_input).await?;
+    let pragmas = pragma::version_pragmas(
+        compiler_input
+            .sources
+            .iter()
+            .map(|(path, source)| (path.as_path(), source.content.as_str())),
+    );
+    let compiler_version = pragma::resolve_compiler_version(
         client.compilers(),
         &request.compiler_version,
+        &pragmas,
+        request.auto_select_compiler_version,
+    )?;
+    // The deployed bytecode is empty if the creation one is provided, while
+    // the runtime part of the latter contains the function dispatcher as well
+    let bytecode = match &request.creation_bytecode {
+        Some(creation_bytecode) if request.deployed_bytecode.is_empty() => {
+            creation_bytecode.clone()
+        }
+        _ => request.deployed_bytecode.clone(),
+    };
+    trace::record(request.trace.as_ref(), || {
+        trace::input_step(
+            &request.compiler_version,
+            &compiler_version,
+            request.creation_bytecode.as_deref(),
+            &request.deployed_bytecode,
+            &request.bytecode_mask,
+            compiler_input.sources.len(),
+        )
+    });
+    let verifier = ContractVerifier::new(
+        client.compilers(),
+        &compiler_version,
         request.creation_bytecode,
         request.deployed_bytecode,
-    )?;
-    let result = verifier.verify(&compiler_input).await;
+        request.bytecode_mask,
+        request.eof_enabled,
+        request.normalization,
+    )?
+    .with_evm_fork(request.evm_fork)
+    .with_trace(request.trace);
+    let permit = client.acquire_compilation_permit().await?;
+    let mut result = verifier.verify(&compiler_input).await;
+    if matches!(result, Err(Error::NoMatchingContracts(_))) && request.retry_with_flipped_via_ir {
+        let requested_via_ir = compiler_input.settings.via_ir.unwrap_or_default();
+        if settings_inference::infer_settings(&bytecode)
+            .via_ir_hint(requested_via_ir)
+            .is_some()
+        {
+            tracing::debug!(
+                via_ir = !requested_via_ir,
+                "no matching contracts, retrying with viaIR flipped"
+            );
+            compiler_input.settings.via_ir = Some(!requested_via_ir);
+            result = verifier.verify(&compiler_input).await;
+        }
+    }
+    drop(permit);
 
     // If case of success, we allow middlewares to process success and only then return it to the caller
     let success = result?;
@@ -49,3 +152,85 @@ pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result
     }
     Ok(success)
 }
+
+/// Checks the imports of the sources, adding the fetched dependencies to the sources.
+pub(crate) async fn add_missing_sources(
+    client: &Client,
+    compiler_input: &mut CompilerInput,
+) -> Result<(), Error> {
+    let sources: Vec<_> = compiler_input
+        .sources
+        .iter()
+        .map(|(path, source)| (path.as_path(), source.content.as_str()))
+        .collect();
+    let fetched_sources =
+        imports::resolve_imports(client, &sources, &compiler_input.settings.remappings).await?;
+    compiler_input.sources.extend(
+        fetched_sources
+            .into_iter()
+            .map(|(path, content)| (path, Source::new(content))),
+    );
+    Ok(())
+}
+
+/// Compiles the sources only once and verifies each of the targets against them.
+/// Results are returned in the order of the targets. Errors which affect all
+/// the targets (e.g., compilation errors) are returned instead of the results.
+pub async fn verify_batch(
+    client: Arc<Client>,
+    request: BatchVerificationRequest,
+) -> Result<Vec<Result<Success, Error>>, Error> {
+    let mut compiler_input = CompilerInput::from(request.content);
+    add_missing_sources(&client, &mut compiler_input).await?;
+    let pragmas = pragma::version_pragmas(
+        compiler_input
+            .sources
+            .iter()
+            .map(|(path, source)| (path.as_path(), source.content.as_str())),
+    );
+    let compiler_version = pragma::resolve_compiler_version(
+        client.compilers(),
+        &request.compiler_version,
+        &pragmas,
+        request.auto_select_compiler_version,
+    )?;
+    let verifiers: Vec<_> = request
+        .targets
+        .into_iter()
+        .map(|target| {
+            ContractVerifier::new(
+                client.compilers(),
+                &compiler_version,
+                target.creation_bytecode,
+                target.deployed_bytecode,
+                vec![],
+                request.eof_enabled,
+                request.normalization.clone(),
+            )
+            .map(|verifier| verifier.with_evm_fork(request.evm_fork))
+        })
+        .collect();
+
+    // Compilation outputs do not depend on the target, so any valid one may compile them
+    let outputs = match verifiers.iter().find_map(|verifier| verifier.as_ref().ok()) {
+        Some(verifier) => {
+            let _permit = client.acquire_compilation_permit().await?;
+            Some(verifier.compile(&compiler_input).await?)
+        }
+        None => None,
+    };
+
+    let mut results = Vec::with_capacity(verifiers.len());
+    for verifier in verifiers {
+        let result = match (verifier, &outputs) {
+            (Ok(verifier), Some(outputs)) => verifier.verify_compiled(&compiler_input, outputs),
+            (Err(err), _) => Err(err),
+            (Ok(_), None) => unreachable!("sources are compiled if any target is valid"),
+        };
+        if let (Ok(success), Some(middleware)) = (&result, client.middleware()) {
+            middleware.call(success).await;
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
@@ -0,0 +1,122 @@
+//! Validation of the submitted source file paths.
+//!
+//! Source file names become compiler virtual paths, and are written to disk
+//! by the compilers working with the file system (e.g. huffc). Only relative
+//! paths which stay inside the sources root are accepted. Separators of both
+//! platforms are checked, as the paths may come from Windows users as well.
+
+use thiserror::Error;
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum SourcePathError {
+    #[error("source file path is empty")]
+    Empty,
+    #[error("source file path `{0}` contains control characters")]
+    ControlCharacters(String),
+    #[error("source file path `{0}` is absolute")]
+    Absolute(String),
+    #[error("source file path `{0}` starts with a drive letter")]
+    DriveLetter(String),
+    #[error("source file path `{0}` refers to the parent directory")]
+    ParentDirectory(String),
+}
+
+/// Returns an error if the path is empty, absolute (including Windows drive
+/// letters and UNC paths), contains control characters or `..` components.
+pub fn validate_source_path(path: &str) -> Result<(), SourcePathError> {
+    if path.is_empty() {
+        return Err(SourcePathError::Empty);
+    }
+    if path.chars().any(char::is_control) {
+        // Escaped, as the path may be printed to logs and error messages
+        return Err(SourcePathError::ControlCharacters(
+            path.escape_default().to_string(),
+        ));
+    }
+    if path.starts_with(['/', '\\']) {
+        return Err(SourcePathError::Absolute(path.to_string()));
+    }
+    let mut chars = path.chars();
+    if let (Some(letter), Some(':')) = (chars.next(), chars.next()) {
+        if letter.is_ascii_alphabetic() {
+            return Err(SourcePathError::DriveLetter(path.to_string()));
+        }
+    }
+    if path.split(['/', '\\']).any(|component| component == "..") {
+        return Err(SourcePathError::ParentDirectory(path.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn valid_paths() {
+        for path in [
+            "Token.sol",
+            "./contracts/Token.sol",
+            "@openzeppelin/contracts/token/ERC20/ERC20.sol",
+            "contracts\\Token.sol",
+            "contracts/..Token..sol",
+            "contracts/.../Token.sol",
+            "contracts/Tok:en.sol",
+            "контракты/Токен.sol",
+        ] {
+            assert_eq!(Ok(()), validate_source_path(path), "{path}");
+        }
+    }
+
+    #[test]
+    fn invalid_paths() {
+        let cases = [
+            ("", SourcePathError::Empty),
+            (
+                "Token\0.sol",
+                SourcePathError::ControlCharacters("Token\\u{0}.sol".into()),
+            ),
+            (
+                "contracts/\nToken.sol",
+                SourcePathError::ControlCharacters("contracts/\\nToken.sol".into()),
+            ),
+            (
+                "/etc/passwd",
+                SourcePathError::Absolute("/etc/passwd".into()),
+            ),
+            (
+                "\\\\server\\share\\Token.sol",
+                SourcePathError::Absolute("\\\\server\\share\\Token.sol".into()),
+            ),
+            (
+                "C:Token.sol",
+                SourcePathError::DriveLetter("C:Token.sol".into()),
+            ),
+            (
+                "c:\\contracts\\Token.sol",
+                SourcePathError::DriveLetter("c:\\contracts\\Token.sol".into()),
+            ),
+            ("..", SourcePathError::ParentDirectory("..".into())),
+            (
+                "../../etc/passwd",
+                SourcePathError::ParentDirectory("../../etc/passwd".into()),
+            ),
+            (
+                "contracts/../../Token.sol",
+                SourcePathError::ParentDirectory("contracts/../../Token.sol".into()),
+            ),
+            (
+                "contracts\\..\\Token.sol",
+                SourcePathError::ParentDirectory("contracts\\..\\Token.sol".into()),
+            ),
+            (
+                "contracts/..",
+                SourcePathError::ParentDirectory("contracts/..".into()),
+            ),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(Err(expected), validate_source_path(path), "{path:?}");
+        }
+    }
+}
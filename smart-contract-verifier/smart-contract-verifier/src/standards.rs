@@ -0,0 +1,253 @@
+use crate::solidity::metadata;
+use std::{collections::HashSet, fmt::Display};
+
+/// Standards which implementation could be detected for verified contracts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Standard {
+    Erc20,
+    Erc721,
+    Erc1155,
+    Erc4626,
+    Ownable,
+    AccessControl,
+}
+
+impl Standard {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Standard::Erc20 => "ERC20",
+            Standard::Erc721 => "ERC721",
+            Standard::Erc1155 => "ERC1155",
+            Standard::Erc4626 => "ERC4626",
+            Standard::Ownable => "Ownable",
+            Standard::AccessControl => "AccessControl",
+        }
+    }
+}
+
+impl Display for Standard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+struct Requirements {
+    standard: Standard,
+    /// Functions (as canonical signatures) the contract abi must contain
+    functions: &'static [&'static str],
+    /// ERC-165 interface id. If the contract abi contains `supportsInterface`,
+    /// the standard is considered implemented when the id is pushed somewhere in the bytecode.
+    interface_id: Option<[u8; 4]>,
+}
+
+const ERC20_FUNCTIONS: &[&str] = &[
+    "totalSupply()",
+    "balanceOf(address)",
+    "transfer(address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "approve(address,uint256)",
+    "allowance(address,address)",
+];
+
+const REQUIREMENTS: &[Requirements] = &[
+    Requirements {
+        standard: Standard::Erc20,
+        functions: ERC20_FUNCTIONS,
+        interface_id: None,
+    },
+    Requirements {
+        standard: Standard::Erc721,
+        functions: &[
+            "balanceOf(address)",
+            "ownerOf(uint256)",
+            "safeTransferFrom(address,address,uint256)",
+            "safeTransferFrom(address,address,uint256,bytes)",
+            "transferFrom(address,address,uint256)",
+            "approve(address,uint256)",
+            "setApprovalForAll(address,bool)",
+            "getApproved(uint256)",
+            "isApprovedForAll(address,address)",
+        ],
+        interface_id: Some([0x80, 0xac, 0x58, 0xcd]),
+    },
+    Requirements {
+        standard: Standard::Erc1155,
+        functions: &[
+            "balanceOf(address,uint256)",
+            "balanceOfBatch(address[],uint256[])",
+            "setApprovalForAll(address,bool)",
+            "isApprovedForAll(address,address)",
+            "safeTransferFrom(address,address,uint256,uint256,bytes)",
+            "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+        ],
+        interface_id: Some([0xd9, 0xb6, 0x7a, 0x26]),
+    },
+    Requirements {
+        standard: Standard::Erc4626,
+        functions: &[
+            "asset()",
+            "totalAssets()",
+            "convertToShares(uint256)",
+            "convertToAssets(uint256)",
+            "maxDeposit(address)",
+            "previewDeposit(uint256)",
+            "deposit(uint256,address)",
+            "maxMint(address)",
+            "previewMint(uint256)",
+            "mint(uint256,address)",
+            "maxWithdraw(address)",
+            "previewWithdraw(uint256)",
+            "withdraw(uint256,address,address)",
+            "maxRedeem(address)",
+            "previewRedeem(uint256)",
+            "redeem(uint256,address,address)",
+        ],
+        interface_id: None,
+    },
+    Requirements {
+        standard: Standard::Ownable,
+        functions: &["owner()", "transferOwnership(address)"],
+        interface_id: None,
+    },
+    Requirements {
+        standard: Standard::AccessControl,
+        functions: &[
+            "hasRole(bytes32,address)",
+            "getRoleAdmin(bytes32)",
+            "grantRole(bytes32,address)",
+            "revokeRole(bytes32,address)",
+            "renounceRole(bytes32,address)",
+        ],
+        interface_id: Some([0x79, 0x65, 0xdb, 0x0b]),
+    },
+];
+
+const SUPPORTS_INTERFACE: &str = "supportsInterface(bytes4)";
+const PUSH1: u8 = 0x60;
+const PUSH4: u8 = 0x63;
+const PUSH32: u8 = 0x7f;
+
+/// Detects standards implemented by the contract based on its abi
+/// and (if available) deployed bytecode.
+///
+/// Returned standards are sorted and do not contain duplicates.
+pub fn detect_standards(abi: &ethabi::Contract, deployed_bytecode: &[u8]) -> Vec<Standard> {
+    let signatures: HashSet<String> = abi
+        .functions()
+        .map(|function| {
+            let inputs = function
+                .inputs
+                .iter()
+                .map(|param| param.kind.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{}({})", function.name, inputs)
+        })
+        .collect();
+    let supports_interface = signatures.contains(SUPPORTS_INTERFACE);
+
+    let mut standards: Vec<_> = REQUIREMENTS
+        .iter()
+        .filter(|requirements| {
+            let implements_functions = requirements
+                .functions
+                .iter()
+                .all(|function| signatures.contains(*function));
+            let declares_interface = supports_interface
+                && requirements
+                    .interface_id
+                    .map(|id| pushes_interface_id(deployed_bytecode, id))
+                    .unwrap_or_default();
+            implements_functions || declares_interface
+        })
+        .map(|requirements| requirements.standard)
+        .collect();
+
+    // ERC-4626 vaults are ERC-20 tokens themselves
+    if standards.contains(&Standard::Erc4626) && !standards.contains(&Standard::Erc20) {
+        standards.retain(|standard| standard != &Standard::Erc4626);
+    }
+
+    standards.sort();
+    standards
+}
+
+/// The code is walked instruction by instruction, so that the data of other push
+/// instructions is not taken for `PUSH4`. The metadata hash appended to the code is skipped.
+fn pushes_interface_id(bytecode: &[u8], interface_id: [u8; 4]) -> bool {
+    let (code, _) = metadata::split(bytecode);
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        if opcode == PUSH4 && code.get(pc + 1..pc + 5) == Some(interface_id.as_slice()) {
+            return true;
+        }
+        pc += 1;
+        if (PUSH1..=PUSH32).contains(&opcode) {
+            pc += (opcode - PUSH1) as usize + 1;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn abi(functions: &[&str]) -> ethabi::Contract {
+        let functions = functions
+            .iter()
+            .map(|signature| {
+                let (name, inputs) = signature.trim_end_matches(')').split_once('(').unwrap();
+                let inputs = inputs
+                    .split(',')
+                    .filter(|input| !input.is_empty())
+                    .map(|input| format!(r#"{{"name":"","type":"{input}"}}"#))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!(
+                    r#"{{"type":"function","name":"{name}","inputs":[{inputs}],"outputs":[],"stateMutability":"nonpayable"}}"#
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        serde_json::from_str(&format!("[{functions}]")).expect("Invalid abi")
+    }
+
+    #[test]
+    fn detects_erc20_and_ownable() {
+        let mut functions = ERC20_FUNCTIONS.to_vec();
+        functions.extend(["owner()", "transferOwnership(address)"]);
+        let standards = detect_standards(&abi(&functions), &[]);
+        assert_eq!(vec![Standard::Erc20, Standard::Ownable], standards);
+    }
+
+    #[test]
+    fn partial_implementation_is_not_detected() {
+        let standards = detect_standards(&abi(&ERC20_FUNCTIONS[1..]), &[]);
+        assert_eq!(Vec::<Standard>::new(), standards);
+    }
+
+    #[test]
+    fn detects_interface_id_in_bytecode() {
+        let functions = [SUPPORTS_INTERFACE, "balanceOf(address)"];
+        // PUSH4 0x80ac58cd
+        let bytecode = [0x60, 0x00, 0x63, 0x80, 0xac, 0x58, 0xcd, 0x14];
+        let standards = detect_standards(&abi(&functions), &bytecode);
+        assert_eq!(vec![Standard::Erc721], standards);
+
+        // Without `supportsInterface` pushed value is not considered
+        let standards = detect_standards(&abi(&functions[1..]), &bytecode);
+        assert_eq!(Vec::<Standard>::new(), standards);
+    }
+
+    #[test]
+    fn interface_id_inside_push_data_is_not_detected() {
+        let functions = [SUPPORTS_INTERFACE, "balanceOf(address)"];
+        // PUSH5 0x6380ac58cd
+        let bytecode = [0x60, 0x00, 0x64, 0x63, 0x80, 0xac, 0x58, 0xcd, 0x14];
+        let standards = detect_standards(&abi(&functions), &bytecode);
+        assert_eq!(Vec::<Standard>::new(), standards);
+    }
+}
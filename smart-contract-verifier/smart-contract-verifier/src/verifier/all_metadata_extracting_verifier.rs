@@ -1,17 +1,22 @@
 use super::{
     base::{self, VerificationSuccess},
-    bytecode::{Bytecode, BytecodePart, LocalBytecode, Source},
+    bytecode::{Bytecode, LocalBytecode, PartRange, Source, SourceKind},
+    disassembly, eof,
     errors::{BytecodeInitError, VerificationError, VerificationErrorKind},
+    immutables, libraries,
+    normalization::Normalization,
+    trace::{self, Trace, TraceStage, TraceStep},
 };
 use crate::{
+    solidity::metadata,
     verifier::bytecode::{CreationTxInput, DeployedBytecode},
     DisplayBytes, MatchType,
 };
 use bytes::Bytes;
-use ethabi::{Constructor, Token};
+use ethabi::{Constructor, ParamType, Token};
 use ethers_solc::{artifacts::Contract, Artifact, CompilerOutput};
 use mismatch::Mismatch;
-use solidity_metadata::MetadataHash;
+use std::{collections::BTreeMap, ops::Range};
 
 /// Verifier used for contract verification.
 ///
@@ -20,13 +25,29 @@ use solidity_metadata::MetadataHash;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Verifier<T> {
     remote_bytecode: Bytecode<T>,
+    /// Byte ranges of the remote bytecode excluded from the comparison
+    bytecode_mask: Vec<Range<usize>>,
+    /// Whether the remote bytecode may be an EOF container (EIP-3540)
+    eof_enabled: bool,
+    /// Whether the immutables of the deployed bytecode embedded into the creation
+    /// transaction input are excluded from the comparison
+    creation_immutables_masked: bool,
+    /// Passes masking the regions which may differ regardless of the sources
+    normalization: Normalization,
+    /// Exact metadata bytes of the deployed bytecode provided along with
+    /// the creation transaction input; anchor the constructor arguments extraction
+    deployed_metadata: Option<Bytes>,
 }
 
 impl<T: Source + Send + Sync> base::Verifier for Verifier<T> {
     type Input = (CompilerOutput, CompilerOutput);
 
-    fn verify(&self, input: &Self::Input) -> Result<VerificationSuccess, Vec<VerificationError>> {
-        self.verify(&input.0, &input.1)
+    fn verify(
+        &self,
+        input: &Self::Input,
+        trace: Option<&Trace>,
+    ) -> Result<VerificationSuccess, Vec<VerificationError>> {
+        self.verify(&input.0, &input.1, trace)
     }
 }
 
@@ -35,19 +56,68 @@ impl<T: Source> Verifier<T> {
         let bytecode = Bytecode::new(input)?;
         Ok(Self {
             remote_bytecode: bytecode,
+            bytecode_mask: vec![],
+            eof_enabled: false,
+            creation_immutables_masked: false,
+            normalization: Normalization::default(),
+            deployed_metadata: None,
         })
     }
 
+    /// Excludes the specified byte ranges of the remote bytecode from the comparison.
+    /// Is intended for bytecodes patched after the compilation (e.g., genesis predeploys).
+    /// Successful verification of the masked bytecode results in a partial match at most.
+    pub fn with_bytecode_mask(mut self, bytecode_mask: Vec<Range<usize>>) -> Self {
+        self.bytecode_mask = bytecode_mask;
+        self
+    }
+
+    /// Enables parsing of the EOF containers, which is disabled by default,
+    /// as chains not supporting EOF may have legacy bytecodes starting with `0xEF00`.
+    pub fn with_eof(mut self, eof_enabled: bool) -> Self {
+        self.eof_enabled = eof_enabled;
+        self
+    }
+
+    /// Excludes the immutables from the comparison of the creation transaction inputs.
+    /// Is intended for compilers which may leave non-zero bytes at the positions
+    /// of the immutables inside the deployed bytecode embedded into the creation one.
+    /// Has no effect on the verification of the deployed bytecode.
+    pub fn with_creation_immutables_masked(mut self, creation_immutables_masked: bool) -> Self {
+        self.creation_immutables_masked = creation_immutables_masked;
+        self
+    }
+
+    /// Excludes the regions found by the normalization passes from the comparison.
+    /// As with the bytecode mask, successful verification of the normalized bytecode
+    /// results in a partial match at most.
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Anchors the constructor arguments extraction on the metadata of the deployed bytecode:
+    /// the runtime code embedded into the creation transaction input ends with exactly
+    /// the same bytes, so the arguments may only start right after them.
+    /// Has no effect if the deployed bytecode has no metadata.
+    pub fn with_deployed_bytecode(mut self, deployed_bytecode: &Bytes) -> Self {
+        let (code, metadata) = metadata::split(deployed_bytecode);
+        self.deployed_metadata = metadata.map(|_| deployed_bytecode.slice(code.len()..));
+        self
+    }
+
     /// Verifies input data provided on initialization by comparing it
     /// with compiler output received when compiling source data locally.
     ///
     /// Iterates through all contracts received from local compilation and
     /// returns [`VerificationSuccess`] with file path and contract name
     /// of succeeded contract, if any. Otherwise, returns [`None`].
+    /// Steps performed for each of the contracts are recorded into the trace, if any.
     pub fn verify(
         &self,
         output: &CompilerOutput,
         output_modified: &CompilerOutput,
+        trace: Option<&Trace>,
     ) -> Result<VerificationSuccess, Vec<VerificationError>> {
         let not_found_in_modified_compiler_output_error =
             |file_path: String, contract_name: Option<String>| match contract_name {
@@ -98,13 +168,20 @@ impl<T: Source> Verifier<T> {
                     }
                 };
 
-                match self.compare(contract, contract_modified) {
+                let step = |stage| TraceStep::new(stage).with_contract(path, name);
+                match self.compare(contract, contract_modified, trace, step) {
                     Ok(ComparisonSuccess {
                         abi,
                         constructor_args,
                         local_bytecode,
                         match_type,
+                        immutable_values,
+                        library_addresses,
                     }) => {
+                        let immutable_values = immutables::resolve_names(output, immutable_values)
+                            .into_iter()
+                            .map(|(name, value)| (name, DisplayBytes::from(value)))
+                            .collect();
                         return Ok(VerificationSuccess {
                             file_path: path.clone(),
                             contract_name: name.clone(),
@@ -113,7 +190,12 @@ impl<T: Source> Verifier<T> {
 
                             local_bytecode_parts: local_bytecode.into(),
                             match_type,
-                        })
+                            immutable_values,
+                            library_addresses: library_addresses
+                                .into_iter()
+                                .map(|(name, address)| (name, DisplayBytes::from(address)))
+                                .collect(),
+                        });
                     }
                     Err(err) => {
                         let error =
@@ -140,10 +222,13 @@ impl<T: Source> Verifier<T> {
     }
 
     /// Tries to verify the remote bytecode via locally compiled contract.
+    /// `step` creates the trace steps related to the contract.
     fn compare(
         &self,
         contract: &Contract,
         contract_modified: &Contract,
+        trace: Option<&Trace>,
+        step: impl Fn(TraceStage) -> TraceStep,
     ) -> Result<ComparisonSuccess<T>, VerificationErrorKind> {
         let creation_tx_input: Bytecode<CreationTxInput> =
             Bytecode::try_from(contract).map_err(|err| match err {
@@ -178,13 +263,150 @@ impl<T: Source> Verifier<T> {
             (creation_tx_input, deployed_bytecode),
             (creation_tx_input_modified, deployed_bytecode_modified),
         )?;
+        trace::record(trace, || {
+            local_bytecode.part_ranges().iter().enumerate().fold(
+                step(TraceStage::MetadataBoundaries)
+                    .with_bytes("local_bytecode", local_bytecode.bytecode()),
+                |trace_step, (i, part)| {
+                    let (kind, range) = match part {
+                        PartRange::Main { range } => ("main", range),
+                        PartRange::Metadata { range, .. } => ("metadata", range),
+                    };
+                    let raw = &local_bytecode.bytecode()[range.clone()];
+                    trace_step.with_detail(
+                        format!("part_{i}"),
+                        format!("{kind} {range:?} {}", trace::hash(raw)),
+                    )
+                },
+            )
+        });
+
+        // Values of immutables are inserted by the constructor, and addresses of not linked
+        // libraries on deployment, so the remote bytecode differs from the compiled one
+        // at their positions regardless of the sources
+        let (immutable_references, link_references) = match T::source_kind() {
+            SourceKind::DeployedBytecode => (
+                immutables::immutable_references(contract),
+                libraries::deployed_bytecode(contract).map(libraries::link_references),
+            ),
+            SourceKind::CreationTxInput => (
+                BTreeMap::new(),
+                libraries::creation_bytecode(contract).map(libraries::link_references),
+            ),
+        };
+        // Values inside the creation transaction input are not the ones the contract
+        // has been deployed with, so they are masked, but not reported
+        let creation_immutable_ranges: Vec<_> = match T::source_kind() {
+            SourceKind::CreationTxInput if self.creation_immutables_masked => {
+                immutables::creation_immutable_references(contract)
+                    .into_values()
+                    .flatten()
+                    .collect()
+            }
+            _ => vec![],
+        };
+        let link_references = link_references.unwrap_or_default();
+
+        // Auxiliary data is appended to the data section of EOF containers on deployment,
+        // thus is not part of the locally compiled bytecode
+        let remote_bytecode_without_aux_data = match T::source_kind() {
+            SourceKind::DeployedBytecode if self.eof_enabled => {
+                eof::remove_aux_data(self.remote_bytecode.bytecode(), local_bytecode.bytecode())
+                    .map(|(bytecode, _aux_data)| {
+                        Bytecode::new(bytecode).expect("local bytecode is not empty")
+                    })
+            }
+            _ => None,
+        };
+        let original_remote_bytecode = remote_bytecode_without_aux_data
+            .as_ref()
+            .unwrap_or(&self.remote_bytecode);
+
+        let immutable_values =
+            extract_ranges(original_remote_bytecode.bytecode(), &immutable_references);
+        let library_addresses =
+            extract_ranges(original_remote_bytecode.bytecode(), &link_references);
+        let immutable_ranges: Vec<_> = immutable_references
+            .into_values()
+            .flatten()
+            .chain(creation_immutable_ranges)
+            .collect();
+        let library_ranges: Vec<_> = link_references.into_values().flatten().collect();
+        let linked_ranges: Vec<_> = immutable_ranges
+            .iter()
+            .chain(&library_ranges)
+            .cloned()
+            .collect();
+        // Both the linked and the masked regions are patched in the single copy
+        // of the remote bytecode, which is made only if some byte differs
+        let mut patched = None;
+        apply_bytecode_mask(
+            original_remote_bytecode.bytecode(),
+            local_bytecode.bytecode(),
+            &linked_ranges,
+            &mut patched,
+        );
+
+        let unmasked_remote_bytecode = patched
+            .as_deref()
+            .unwrap_or(&original_remote_bytecode.bytecode()[..]);
+        let normalized_ranges = self
+            .normalization
+            .masked_ranges(local_bytecode.bytecode(), unmasked_remote_bytecode);
+        let bytecode_mask: Vec<_> = self
+            .bytecode_mask
+            .iter()
+            .chain(&normalized_ranges)
+            .cloned()
+            .collect();
+        let is_masked = apply_bytecode_mask(
+            original_remote_bytecode.bytecode(),
+            local_bytecode.bytecode(),
+            &bytecode_mask,
+            &mut patched,
+        );
+        let patched_remote_bytecode = patched.map(|bytecode| {
+            Bytecode::new(Bytes::from(bytecode))
+                .expect("masked bytecode has the same length as the original")
+        });
+        let remote_bytecode = patched_remote_bytecode
+            .as_ref()
+            .unwrap_or(original_remote_bytecode);
+        trace::record(trace, || {
+            step(TraceStage::RegionsMasked)
+                .with_ranges("immutable_ranges", &immutable_ranges)
+                .with_ranges("library_ranges", &library_ranges)
+                .with_ranges("bytecode_mask", &self.bytecode_mask)
+                .with_ranges("normalized_ranges", &normalized_ranges)
+                .with_detail("normalization_passes", self.normalization.names().join(","))
+                .with_detail(
+                    "eof_aux_data_removed",
+                    remote_bytecode_without_aux_data.is_some(),
+                )
+                .with_bytes("remote_bytecode", remote_bytecode.bytecode())
+        });
 
-        let match_type = Self::compare_creation_tx_inputs(&self.remote_bytecode, &local_bytecode)?;
+        let comparison = Self::compare_creation_tx_inputs(remote_bytecode, &local_bytecode);
+        trace::record(trace, || {
+            let result = match &comparison {
+                Ok(match_type) => format!("{match_type:?}").to_lowercase(),
+                Err(err) => err.to_string(),
+            };
+            step(TraceStage::Comparison)
+                .with_bytes("local_bytecode", local_bytecode.bytecode())
+                .with_bytes("remote_bytecode", remote_bytecode.bytecode())
+                .with_detail("result", result)
+        });
+        let match_type = match comparison? {
+            // Some bytes of the remote bytecode differ from the local ones
+            _ if is_masked => MatchType::Partial,
+            match_type => match_type,
+        };
 
         let abi = contract.get_abi().map(|abi| abi.into_owned());
 
-        let constructor_args = Self::extract_constructor_args(
-            self.remote_bytecode.bytecode(),
+        let constructor_args = self.extract_constructor_args(
+            remote_bytecode.bytecode(),
             local_bytecode.bytecode(),
             abi.as_ref().and_then(|abi| abi.constructor()),
         )?;
@@ -194,6 +416,8 @@ impl<T: Source> Verifier<T> {
             constructor_args,
             local_bytecode,
             match_type,
+            immutable_values,
+            library_addresses,
         })
     }
 
@@ -226,7 +450,7 @@ impl<T: Source> Verifier<T> {
         Self::compare_bytecode_parts(
             remote_creation_tx_input,
             local_creation_tx_input,
-            local_bytecode.bytecode_parts(),
+            local_bytecode.part_ranges(),
         )?;
 
         Ok(MatchType::Partial)
@@ -241,7 +465,7 @@ impl<T: Source> Verifier<T> {
     fn compare_bytecode_parts(
         remote_raw: &Bytes,
         local_raw: &Bytes,
-        local_parts: &Vec<BytecodePart>,
+        local_parts: &[PartRange],
     ) -> Result<(), VerificationErrorKind> {
         // A caller should ensure that this precondition holds.
         // Currently only `compare_creation_tx_inputs` calls current function,
@@ -252,31 +476,27 @@ impl<T: Source> Verifier<T> {
             "Local bytecode is greater than remote"
         );
 
-        let mut i = 0usize; // keep track of current processing position of `remote_raw`
-
+        // Parts cover the local bytecode, so their ranges are the positions of the remote one
+        // to be compared with them
         for part in local_parts {
             match part {
-                BytecodePart::Main { raw } => {
-                    if raw != &remote_raw[i..i + raw.len()] {
+                PartRange::Main { range } => {
+                    if local_raw[range.clone()] != remote_raw[range.clone()] {
                         return Err(VerificationErrorKind::BytecodeMismatch {
                             part: Mismatch::new(
-                                raw.clone().into(),
-                                remote_raw.slice(i..i + raw.len()).into(),
+                                local_raw.slice(range.clone()).into(),
+                                remote_raw.slice(range.clone()).into(),
                             ),
                             raw: Mismatch::new(local_raw.clone().into(), remote_raw.clone().into()),
                         });
                     }
                 }
-                BytecodePart::Metadata { metadata, raw, .. } => {
+                PartRange::Metadata { metadata, range } => {
                     let (remote_metadata, remote_metadata_length) =
-                        MetadataHash::from_cbor(&remote_raw[i..])
+                        metadata::parse(&remote_raw[range.start..])
                             .map_err(|err| VerificationErrorKind::MetadataParse(err.to_string()))?;
 
-                    let start_index = i + remote_metadata_length;
-                    let raw_start_index = raw.len() - 2;
-                    if remote_raw[start_index..start_index + 2]
-                        != raw[raw_start_index..raw_start_index + 2]
-                    {
+                    if remote_metadata_length != range.len() {
                         return Err(VerificationErrorKind::MetadataParse(
                             "metadata length mismatch".into(),
                         ));
@@ -297,8 +517,6 @@ impl<T: Source> Verifier<T> {
                     }
                 }
             }
-
-            i += part.size();
         }
 
         Ok(())
@@ -306,12 +524,88 @@ impl<T: Source> Verifier<T> {
 
     /// Extracts constructor arguments from the creation transaction input specified on
     /// [`Verifier`] initialization.
+    ///
+    /// Constructor arguments are expected to start right after the locally compiled bytecode.
+    /// If the arguments obtained that way are invalid (e.g., metadata hashes of local and
+    /// remote bytecodes differ in length), the boundary is inferred from the remote bytecode
+    /// instead (see [`Self::constructor_args_boundaries`]). `ethabi::decode` ignores
+    /// trailing bytes, so arguments are also required to have the length of their encoding
+    /// (see [`has_valid_args_length`]); otherwise arguments which coincidentally contain
+    /// metadata-like bytes could be split at them.
     fn extract_constructor_args(
+        &self,
         remote_raw: &Bytes,
         local_raw: &Bytes,
         abi_constructor: Option<&Constructor>,
     ) -> Result<Option<Bytes>, VerificationErrorKind> {
-        let encoded_constructor_args = remote_raw.slice(local_raw.len()..);
+        let result = Self::extract_constructor_args_starting_at(
+            remote_raw,
+            local_raw.len(),
+            abi_constructor,
+        );
+        let has_valid_length = matches!(
+            &result,
+            Ok(args) if has_valid_args_length(args.as_deref().unwrap_or_default(), abi_constructor)
+        );
+        if has_valid_length || !T::has_constructor_args() {
+            return result;
+        }
+        // Arguments with unexpected trailing bytes are still accepted if no better boundary is found
+        self.extract_inferred_constructor_args(remote_raw, abi_constructor)
+            .map(Ok)
+            .unwrap_or(result)
+    }
+
+    /// Extracts constructor arguments starting at the first of the inferred boundaries
+    /// the arguments are valid and have the expected length for.
+    fn extract_inferred_constructor_args(
+        &self,
+        remote_raw: &Bytes,
+        abi_constructor: Option<&Constructor>,
+    ) -> Option<Option<Bytes>> {
+        self.constructor_args_boundaries(remote_raw)
+            .into_iter()
+            .filter_map(|start| {
+                Self::extract_constructor_args_starting_at(remote_raw, start, abi_constructor).ok()
+            })
+            .find(|args| {
+                has_valid_args_length(args.as_deref().unwrap_or_default(), abi_constructor)
+            })
+    }
+
+    /// Positions of the remote bytecode the constructor arguments may start at,
+    /// the most reliable ones first.
+    ///
+    /// If the deployed bytecode has been provided, the arguments may start right after
+    /// any occurrence of its metadata. The arguments themselves may contain the same bytes
+    /// (e.g., the bytecode deployed by a factory), so the earliest occurrences come first.
+    /// The last one is inferred from the creation code via [`disassembly::creation_code_length`],
+    /// or from the header for EOF containers (see [`eof::container_length`]).
+    fn constructor_args_boundaries(&self, remote_raw: &[u8]) -> Vec<usize> {
+        let mut boundaries: Vec<usize> = match &self.deployed_metadata {
+            Some(metadata) => remote_raw
+                .windows(metadata.len())
+                .enumerate()
+                .filter(|(_, window)| *window == &metadata[..])
+                .map(|(start, _)| start + metadata.len())
+                .collect(),
+            None => vec![],
+        };
+        let code_length = if self.eof_enabled && eof::is_eof(remote_raw) {
+            eof::container_length(remote_raw).ok()
+        } else {
+            disassembly::creation_code_length(remote_raw)
+        };
+        boundaries.extend(code_length);
+        boundaries
+    }
+
+    fn extract_constructor_args_starting_at(
+        remote_raw: &Bytes,
+        start_index: usize,
+        abi_constructor: Option<&Constructor>,
+    ) -> Result<Option<Bytes>, VerificationErrorKind> {
+        let encoded_constructor_args = remote_raw.slice(start_index..);
         let encoded_constructor_args = if encoded_constructor_args.is_empty() {
             None
         } else {
@@ -358,11 +652,94 @@ impl<T: Source> Verifier<T> {
     }
 }
 
+/// Replaces bytes of the remote bytecode located inside the mask with corresponding
+/// bytes of the local one. Ranges (or their parts) exceeding any of the bytecodes are ignored.
+///
+/// The remote bytecode is copied into `patched` when the first byte is changed;
+/// if `patched` is already set, the bytes are replaced in place, so that masks
+/// applied one after another result in the single copy of the remote bytecode.
+///
+/// Returns `false` if no byte has been changed.
+fn apply_bytecode_mask(
+    remote: &[u8],
+    local: &[u8],
+    mask: &[Range<usize>],
+    patched: &mut Option<Vec<u8>>,
+) -> bool {
+    let end_limit = remote.len().min(local.len());
+    let mut changed = false;
+    for range in mask {
+        let range = range.start.min(end_limit)..range.end.min(end_limit);
+        let current = patched.as_deref().unwrap_or(remote);
+        if !range.is_empty() && current[range.clone()] != local[range.clone()] {
+            patched.get_or_insert_with(|| remote.to_vec())[range.clone()]
+                .copy_from_slice(&local[range]);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Checks that the length of ABI encoded constructor arguments matches their encoding.
+/// `ethabi::decode` ignores trailing bytes, so the arguments are encoded back from
+/// the decoded values: any bytes left after the tails of dynamic parameters (or after
+/// the heads of static ones) make the encoding shorter than the arguments.
+fn has_valid_args_length(encoded: &[u8], abi_constructor: Option<&Constructor>) -> bool {
+    let param_types: Vec<ParamType> = abi_constructor
+        .map(|constructor| {
+            constructor
+                .inputs
+                .iter()
+                .map(|input| lossless_param_type(&input.kind))
+                .collect()
+        })
+        .unwrap_or_default();
+    ethabi::decode(&param_types, encoded).map_or(false, |tokens| {
+        ethabi::encode(&tokens).len() == encoded.len()
+    })
+}
+
+/// Strings are encoded the same way as bytes, but invalid UTF-8 sequences are replaced
+/// when decoded as strings, so that their encoding back would have a different length.
+fn lossless_param_type(kind: &ParamType) -> ParamType {
+    match kind {
+        ParamType::String => ParamType::Bytes,
+        ParamType::Array(kind) => ParamType::Array(Box::new(lossless_param_type(kind))),
+        ParamType::FixedArray(kind, size) => {
+            ParamType::FixedArray(Box::new(lossless_param_type(kind)), *size)
+        }
+        ParamType::Tuple(kinds) => {
+            ParamType::Tuple(kinds.iter().map(lossless_param_type).collect())
+        }
+        kind => kind.clone(),
+    }
+}
+
+/// Extracts the bytes located inside the ranges from the remote bytecode.
+/// All ranges of the same key contain the same value, so the first one is used.
+/// Ranges exceeding the bytecode are ignored.
+fn extract_ranges(
+    remote: &Bytes,
+    ranges: &BTreeMap<String, Vec<Range<usize>>>,
+) -> BTreeMap<String, Bytes> {
+    ranges
+        .iter()
+        .filter_map(|(key, ranges)| {
+            let range = ranges.first()?;
+            (range.end <= remote.len()).then(|| (key.clone(), remote.slice(range.clone())))
+        })
+        .collect()
+}
+
 struct ComparisonSuccess<T> {
     pub abi: Option<ethabi::Contract>,
     pub constructor_args: Option<Bytes>,
     pub local_bytecode: LocalBytecode<T>,
     pub match_type: MatchType,
+    /// Values of immutables by their AST ids
+    pub immutable_values: BTreeMap<String, Bytes>,
+    /// Addresses of not linked libraries by the fully qualified library names
+    pub library_addresses: BTreeMap<String, Bytes>,
 }
 
 #[cfg(test)]
@@ -437,3 +814,264 @@ mod verifier_initialization_tests {
         assert_eq!(verifier.unwrap_err(), BytecodeInitError::Empty)
     }
 }
+
+#[cfg(test)]
+mod bytecode_mask_tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn mask_replaces_differing_bytes() {
+        let remote = Bytes::from_static(&[0x60, 0x01, 0xaa, 0xbb, 0x00]);
+        let local = Bytes::from_static(&[0x60, 0x01, 0x00, 0x00, 0x00]);
+
+        let mut masked = None;
+        assert!(apply_bytecode_mask(&remote, &local, &[2..4], &mut masked));
+        assert_eq!(Some(local.to_vec()), masked);
+
+        let mut masked = None;
+        assert!(apply_bytecode_mask(&remote, &local, &[2..3], &mut masked));
+        assert_eq!(Some(vec![0x60, 0x01, 0x00, 0xbb, 0x00]), masked);
+    }
+
+    #[test]
+    fn mask_without_differences() {
+        let remote = Bytes::from_static(&[0x60, 0x01, 0xaa, 0xbb, 0x00]);
+        let local = Bytes::from_static(&[0x60, 0x01, 0x00, 0x00, 0x00]);
+
+        let mut masked = None;
+        assert!(!apply_bytecode_mask(&remote, &local, &[], &mut masked));
+        assert!(!apply_bytecode_mask(&remote, &local, &[0..2], &mut masked));
+        assert_eq!(None, masked);
+    }
+
+    #[test]
+    fn mask_exceeding_bytecode() {
+        let remote = Bytes::from_static(&[0x60, 0x01, 0xaa, 0xbb]);
+        let local = Bytes::from_static(&[0x60, 0x01, 0x00]);
+
+        let mut masked = None;
+        assert!(apply_bytecode_mask(
+            &remote,
+            &local,
+            &[2..10, 20..30],
+            &mut masked
+        ));
+        assert_eq!(Some(vec![0x60, 0x01, 0x00, 0xbb]), masked);
+    }
+
+    #[test]
+    fn masks_are_applied_to_single_copy() {
+        let remote = Bytes::from_static(&[0x60, 0x01, 0xaa, 0xbb, 0x00]);
+        let local = Bytes::from_static(&[0x60, 0x01, 0x00, 0x00, 0x00]);
+
+        let mut masked = None;
+        assert!(apply_bytecode_mask(&remote, &local, &[2..3], &mut masked));
+        // Bytes already replaced by the previous mask are not reported as changed
+        assert!(!apply_bytecode_mask(&remote, &local, &[2..3], &mut masked));
+        assert!(apply_bytecode_mask(&remote, &local, &[3..4], &mut masked));
+        assert_eq!(Some(local.to_vec()), masked);
+    }
+
+    #[test]
+    fn extract_values_from_ranges() {
+        let remote = Bytes::from_static(&[0x60, 0xaa, 0xbb, 0x60, 0xaa, 0xbb, 0x60, 0xcc]);
+        let ranges = BTreeMap::from([
+            ("3".to_string(), vec![1..3, 4..6]),
+            ("5".to_string(), vec![7..8]),
+            ("7".to_string(), vec![7..9]),
+        ]);
+        assert_eq!(
+            BTreeMap::from([
+                ("3".to_string(), Bytes::from_static(&[0xaa, 0xbb])),
+                ("5".to_string(), Bytes::from_static(&[0xcc])),
+            ]),
+            extract_ranges(&remote, &ranges)
+        );
+    }
+}
+
+#[cfg(test)]
+mod constructor_args_tests {
+    use super::{super::bytecode::CreationTxInput, *};
+    use ethabi::{ethereum_types::U256, Param};
+    use pretty_assertions::assert_eq;
+    use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+    // {"ipfs": h'1220EB23CE2C13EA8739368F952F6C6A4B1F0623D147D2A19B6D4D26A61AB03FCD3E', "solc": 0.8.14}
+    const METADATA: &str = "a2646970667358221220eb23ce2c13ea8739368f952f6c6a4b1f0623d147d2a19b6d4d26a61ab03fcd3e64736f6c634300080e0033";
+    const INIT_CODE: &str = "608060405234801561001057600080fd5b5061017f806100206000396000f3fe";
+    const RUNTIME_CODE: &str = "608060405234801561001057600080fd5b50600436106100365760003560e01c";
+
+    fn metadata() -> Vec<u8> {
+        hex::decode(METADATA).unwrap()
+    }
+
+    fn abi_constructor(kinds: Vec<ParamType>) -> Constructor {
+        let inputs = kinds
+            .into_iter()
+            .enumerate()
+            .map(|(i, kind)| Param {
+                name: format!("arg{i}"),
+                kind,
+                internal_type: None,
+            })
+            .collect();
+        Constructor { inputs }
+    }
+
+    /// Returns the verifier anchored on the deployed bytecode, and the local creation bytecode,
+    /// which is one byte shorter than the remote one, so that the arguments could not be
+    /// extracted right after it.
+    fn setup(
+        init_code: &[u8],
+        runtime_code: &[u8],
+        args: &[u8],
+    ) -> (Verifier<CreationTxInput>, Bytes, Bytes) {
+        let deployed = [runtime_code, &metadata()].concat();
+        let creation = [init_code, &deployed].concat();
+        let remote = Bytes::from([creation.as_slice(), args].concat());
+        let verifier = Verifier::<CreationTxInput>::new(remote.clone())
+            .unwrap()
+            .with_deployed_bytecode(&Bytes::from(deployed));
+        let local = Bytes::from(creation[..creation.len() - 1].to_vec());
+        (verifier, remote, local)
+    }
+
+    #[test]
+    fn args_containing_metadata_are_not_split() {
+        let runtime_code = hex::decode(RUNTIME_CODE).unwrap();
+        // E.g., a factory receiving the bytecode it deploys
+        let args = ethabi::encode(&[Token::Bytes(
+            [runtime_code.as_slice(), &metadata()].concat(),
+        )]);
+        let (verifier, remote, local) =
+            setup(&hex::decode(INIT_CODE).unwrap(), &runtime_code, &args);
+
+        let constructor = abi_constructor(vec![ParamType::Bytes]);
+        let extracted = verifier
+            .extract_constructor_args(&remote, &local, Some(&constructor))
+            .expect("args should be extracted");
+        assert_eq!(Some(Bytes::from(args)), extracted);
+    }
+
+    #[test]
+    fn leniently_decoded_args_are_validated_by_length() {
+        // The init code coincidentally contains the metadata as well
+        let init_code = [hex::decode(INIT_CODE).unwrap(), metadata()].concat();
+        let args = ethabi::encode(&[Token::Uint(0xfff.into())]);
+        let (verifier, remote, local) =
+            setup(&init_code, &hex::decode(RUNTIME_CODE).unwrap(), &args);
+
+        // Both the arguments following the local bytecode and following the first
+        // metadata occurrence are decoded as `uint256`, yet have invalid lengths
+        let constructor = abi_constructor(vec![ParamType::Uint(256)]);
+        let extracted = verifier
+            .extract_constructor_args(&remote, &local, Some(&constructor))
+            .expect("args should be extracted");
+        assert_eq!(Some(Bytes::from(args)), extracted);
+    }
+
+    #[test]
+    fn args_with_trailing_bytes_are_still_accepted() {
+        let args = [ethabi::encode(&[Token::Uint(0xfff.into())]), vec![0x01]].concat();
+        let (verifier, remote, local) = setup(
+            &hex::decode(INIT_CODE).unwrap(),
+            &hex::decode(RUNTIME_CODE).unwrap(),
+            &args,
+        );
+        let local = remote.slice(..local.len() + 1);
+
+        let constructor = abi_constructor(vec![ParamType::Uint(256)]);
+        let extracted = verifier
+            .extract_constructor_args(&remote, &local, Some(&constructor))
+            .expect("args should be extracted");
+        assert_eq!(Some(Bytes::from(args)), extracted);
+    }
+
+    #[test]
+    fn static_args_length() {
+        let constructor = abi_constructor(vec![
+            ParamType::Uint(256),
+            ParamType::FixedArray(Box::new(ParamType::Address), 2),
+            ParamType::Tuple(vec![ParamType::Bool, ParamType::FixedBytes(32)]),
+        ]);
+        assert!(has_valid_args_length(&[0; 5 * 32], Some(&constructor)));
+        assert!(!has_valid_args_length(&[0; 4 * 32], Some(&constructor)));
+        assert!(!has_valid_args_length(&[0; 6 * 32], Some(&constructor)));
+
+        assert!(has_valid_args_length(&[], None));
+        assert!(has_valid_args_length(&[], Some(&abi_constructor(vec![]))));
+    }
+
+    #[test]
+    fn dynamic_args_length() {
+        let constructor = abi_constructor(vec![
+            ParamType::Uint(256),
+            ParamType::FixedArray(Box::new(ParamType::String), 2),
+        ]);
+        let args = ethabi::encode(&[
+            Token::Uint(1.into()),
+            Token::FixedArray(vec![
+                Token::String("a".to_string()),
+                Token::String("b".repeat(40)),
+            ]),
+        ]);
+        assert!(has_valid_args_length(&args, Some(&constructor)));
+        // Decoded successfully, but the trailing words are not part of the encoding
+        assert!(!has_valid_args_length(
+            &[args.as_slice(), &[0; 32]].concat(),
+            Some(&constructor)
+        ));
+        assert!(!has_valid_args_length(&[0; 5 * 32], Some(&constructor)));
+        assert!(!has_valid_args_length(
+            &args[..args.len() - 32],
+            Some(&constructor)
+        ));
+    }
+
+    #[test]
+    fn invalid_utf8_string_args_length() {
+        let constructor = abi_constructor(vec![ParamType::Array(Box::new(ParamType::String))]);
+        let args = ethabi::encode(&[Token::Array(vec![Token::Bytes(vec![0xff; 31])])]);
+        assert!(has_valid_args_length(&args, Some(&constructor)));
+    }
+
+    fn random_bytes(rng: &mut StdRng, len: Range<usize>) -> Vec<u8> {
+        let mut bytes = vec![0u8; rng.gen_range(len)];
+        rng.fill_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn fuzz_args_with_metadata_like_bytes() {
+        let mut rng = StdRng::seed_from_u64(203);
+        let constructor = abi_constructor(vec![ParamType::Uint(256), ParamType::Bytes]);
+        let metadata = metadata();
+
+        for _ in 0..300 {
+            let init_code = random_bytes(&mut rng, 1..64);
+            let runtime_code = random_bytes(&mut rng, 1..64);
+
+            // Random bytes interleaved with the metadata and its prefixes
+            let mut value = vec![];
+            for _ in 0..rng.gen_range(0..4) {
+                value.extend(random_bytes(&mut rng, 0..40));
+                let prefix_len = rng.gen_range(2..=metadata.len());
+                value.extend_from_slice(&metadata[..prefix_len]);
+            }
+            let args = ethabi::encode(&[
+                Token::Uint(U256::from_big_endian(&random_bytes(&mut rng, 32..33))),
+                Token::Bytes(value),
+            ]);
+
+            let (verifier, remote, local) = setup(&init_code, &runtime_code, &args);
+            let local = local.slice(..local.len() + 1 - rng.gen_range(1..32));
+
+            let extracted = verifier
+                .extract_constructor_args(&remote, &local, Some(&constructor))
+                .expect("args should be extracted");
+            assert_eq!(Some(Bytes::from(args)), extracted);
+        }
+    }
+}
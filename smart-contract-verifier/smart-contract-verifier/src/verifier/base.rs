@@ -1,8 +1,10 @@
 use super::{
     bytecode::{BytecodePart, LocalBytecode},
     errors::VerificationError,
+    trace::Trace,
 };
 use crate::{DisplayBytes, MatchType};
+use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct LocalBytecodeParts {
@@ -13,8 +15,8 @@ pub struct LocalBytecodeParts {
 impl<T> From<LocalBytecode<T>> for LocalBytecodeParts {
     fn from(local_bytecode: LocalBytecode<T>) -> Self {
         LocalBytecodeParts {
-            creation_tx_input_parts: local_bytecode.creation_tx_input_parts,
-            deployed_bytecode_parts: local_bytecode.deployed_bytecode_parts,
+            creation_tx_input_parts: local_bytecode.creation_tx_input_parts(),
+            deployed_bytecode_parts: local_bytecode.deployed_bytecode_parts(),
         }
     }
 }
@@ -30,6 +32,10 @@ pub struct VerificationSuccess {
 
     pub local_bytecode_parts: LocalBytecodeParts,
     pub match_type: MatchType,
+    /// Values of immutables extracted from the deployed bytecode by the variable names
+    pub immutable_values: BTreeMap<String, DisplayBytes>,
+    /// Addresses of the libraries not linked during the compilation by the fully qualified names
+    pub library_addresses: BTreeMap<String, DisplayBytes>,
 }
 
 /// Combine different verifiers
@@ -37,6 +43,10 @@ pub trait Verifier: Send + Sync {
     /// Verification input (in most cases consists the output returned by compiler)
     type Input;
 
-    /// Verifies provided input data
-    fn verify(&self, input: &Self::Input) -> Result<VerificationSuccess, Vec<VerificationError>>;
+    /// Verifies provided input data. Performed steps are recorded into the trace, if any
+    fn verify(
+        &self,
+        input: &Self::Input,
+        trace: Option<&Trace>,
+    ) -> Result<VerificationSuccess, Vec<VerificationError>>;
 }
@@ -1,9 +1,12 @@
-use super::errors::{BytecodeInitError, VerificationErrorKind};
-use bytes::{Buf, Bytes};
+use super::{
+    errors::{BytecodeInitError, VerificationErrorKind},
+    libraries,
+};
+use crate::solidity::metadata::{self, Metadata, MetadataError};
+use bytes::Bytes;
 use ethers_solc::{artifacts::Contract, Artifact};
 use mismatch::Mismatch;
-use solidity_metadata::MetadataHash;
-use std::marker::PhantomData;
+use std::{marker::PhantomData, ops::Range};
 
 /// Types that can be used as Bytecode source indicator
 pub trait Source {
@@ -32,6 +35,11 @@ impl Source for DeployedBytecode {
     fn try_bytes_from_contract(contract: &Contract) -> Result<Bytes, BytecodeInitError> {
         let bytes = contract
             .get_deployed_bytecode_bytes()
+            .map(|bytes| bytes.0.clone())
+            // Placeholders of not linked libraries are compared as zeros
+            .or_else(|| {
+                libraries::deployed_bytecode(contract).and_then(libraries::zero_linked_bytes)
+            })
             .ok_or_else(|| {
                 let bytecode = contract
                     .get_deployed_bytecode_object()
@@ -40,9 +48,7 @@ impl Source for DeployedBytecode {
                     .unwrap_or_default()
                     .to_string();
                 BytecodeInitError::InvalidDeployedBytecode(bytecode)
-            })?
-            .0
-            .clone();
+            })?;
 
         Ok(bytes)
     }
@@ -65,6 +71,11 @@ impl Source for CreationTxInput {
     fn try_bytes_from_contract(contract: &Contract) -> Result<Bytes, BytecodeInitError> {
         let bytes = contract
             .get_bytecode_bytes()
+            .map(|bytes| bytes.0.clone())
+            // Placeholders of not linked libraries are compared as zeros
+            .or_else(|| {
+                libraries::creation_bytecode(contract).and_then(libraries::zero_linked_bytes)
+            })
             .ok_or_else(|| {
                 let bytecode = contract
                     .get_bytecode_object()
@@ -73,9 +84,7 @@ impl Source for CreationTxInput {
                     .unwrap_or_default()
                     .to_string();
                 BytecodeInitError::InvalidCreationTxInput(bytecode)
-            })?
-            .0
-            .clone();
+            })?;
 
         Ok(bytes)
     }
@@ -127,7 +136,7 @@ impl<T: Source> TryFrom<&Contract> for Bytecode<T> {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BytecodePart {
     Main { raw: Bytes },
-    Metadata { raw: Bytes, metadata: MetadataHash },
+    Metadata { raw: Bytes, metadata: Metadata },
 }
 
 impl BytecodePart {
@@ -139,6 +148,42 @@ impl BytecodePart {
     }
 }
 
+/// Location of a [`BytecodePart`] inside the bytecode it has been split from.
+///
+/// Parts are stored as ranges of the single bytecode buffer, so that the comparison
+/// operates on slices of it, and [`BytecodePart`]s are created only for the response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PartRange {
+    Main {
+        range: Range<usize>,
+    },
+    Metadata {
+        range: Range<usize>,
+        metadata: Metadata,
+    },
+}
+
+impl PartRange {
+    pub fn range(&self) -> &Range<usize> {
+        match self {
+            PartRange::Main { range } => range,
+            PartRange::Metadata { range, .. } => range,
+        }
+    }
+
+    fn part(&self, bytecode: &Bytes) -> BytecodePart {
+        match self {
+            PartRange::Main { range } => BytecodePart::Main {
+                raw: bytecode.slice(range.clone()),
+            },
+            PartRange::Metadata { range, metadata } => BytecodePart::Metadata {
+                raw: bytecode.slice(range.clone()),
+                metadata: metadata.clone(),
+            },
+        }
+    }
+}
+
 /// Encapsulates result of local source code compilation.
 /// Splits compiled creation transaction input.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -146,8 +191,8 @@ pub struct LocalBytecode<T> {
     creation_tx_input: Bytecode<CreationTxInput>,
     deployed_bytecode: Bytecode<DeployedBytecode>,
 
-    pub creation_tx_input_parts: Vec<BytecodePart>,
-    pub deployed_bytecode_parts: Vec<BytecodePart>,
+    creation_tx_input_ranges: Vec<PartRange>,
+    deployed_bytecode_ranges: Vec<PartRange>,
 
     source: PhantomData<T>,
 }
@@ -169,11 +214,11 @@ impl<T> LocalBytecode<T> {
             Bytecode<DeployedBytecode>,
         ),
     ) -> Result<Self, VerificationErrorKind> {
-        let creation_tx_input_parts = Self::split(
+        let creation_tx_input_ranges = Self::split(
             creation_tx_input.bytecode(),
             creation_tx_input_modified.bytecode(),
         )?;
-        let deployed_bytecode_parts = Self::split(
+        let deployed_bytecode_ranges = Self::split(
             deployed_bytecode.bytecode(),
             deployed_bytecode_modified.bytecode(),
         )?;
@@ -181,8 +226,8 @@ impl<T> LocalBytecode<T> {
         Ok(Self {
             creation_tx_input,
             deployed_bytecode,
-            creation_tx_input_parts,
-            deployed_bytecode_parts,
+            creation_tx_input_ranges,
+            deployed_bytecode_ranges,
 
             source: Default::default(),
         })
@@ -198,24 +243,46 @@ impl<T> LocalBytecode<T> {
         }
     }
 
-    pub fn bytecode_parts(&self) -> &Vec<BytecodePart>
+    pub fn part_ranges(&self) -> &[PartRange]
     where
         T: Source,
     {
         match T::source_kind() {
-            SourceKind::CreationTxInput => &self.creation_tx_input_parts,
-            SourceKind::DeployedBytecode => &self.deployed_bytecode_parts,
+            SourceKind::CreationTxInput => &self.creation_tx_input_ranges,
+            SourceKind::DeployedBytecode => &self.deployed_bytecode_ranges,
         }
     }
 
-    /// Splits bytecode onto [`BytecodePart`]s using bytecode with modified metadata hashes.
+    pub fn bytecode_parts(&self) -> Vec<BytecodePart>
+    where
+        T: Source,
+    {
+        Self::parts(self.bytecode(), self.part_ranges())
+    }
+
+    pub fn creation_tx_input_parts(&self) -> Vec<BytecodePart> {
+        Self::parts(
+            self.creation_tx_input.bytecode(),
+            &self.creation_tx_input_ranges,
+        )
+    }
+
+    pub fn deployed_bytecode_parts(&self) -> Vec<BytecodePart> {
+        Self::parts(
+            self.deployed_bytecode.bytecode(),
+            &self.deployed_bytecode_ranges,
+        )
+    }
+
+    fn parts(bytecode: &Bytes, ranges: &[PartRange]) -> Vec<BytecodePart> {
+        ranges.iter().map(|range| range.part(bytecode)).collect()
+    }
+
+    /// Splits bytecode onto [`PartRange`]s using bytecode with modified metadata hashes.
     ///
     /// Any error here is [`VerificationErrorKind::InternalError`], as both original
     /// and modified bytecodes are obtained as a result of local compilation.
-    fn split(
-        raw: &Bytes,
-        raw_modified: &Bytes,
-    ) -> Result<Vec<BytecodePart>, VerificationErrorKind> {
+    fn split(raw: &[u8], raw_modified: &[u8]) -> Result<Vec<PartRange>, VerificationErrorKind> {
         if raw.len() != raw_modified.len() {
             return Err(VerificationErrorKind::InternalError(format!(
                 "bytecode and modified bytecode length mismatch: {}",
@@ -223,80 +290,71 @@ impl<T> LocalBytecode<T> {
             )));
         }
 
-        let parts_total_size = |parts: &Vec<BytecodePart>| -> usize {
-            parts.iter().fold(0, |size, el| size + el.size())
-        };
-
         let mut result = Vec::new();
 
         let mut i = 0usize;
         while i < raw.len() {
-            let decoded = Self::parse_bytecode_parts(&raw.slice(i..raw.len()), &raw_modified[i..])?;
-            let decoded_size = parts_total_size(&decoded);
+            let decoded = Self::parse_part_ranges(raw, raw_modified, i)?;
+            i = decoded.last().map(|part| part.range().end).unwrap_or(i);
             result.extend(decoded);
-
-            i += decoded_size;
         }
 
         Ok(result)
     }
 
-    /// Finds the next [`BytecodePart`]s into a series of bytes.
+    /// Finds the next [`PartRange`]s of the bytecode starting at the `offset`.
     ///
-    /// Parses at most one [`BytecodePart::Main`] and one [`BytecodePart::Metadata`].
-    fn parse_bytecode_parts(
-        raw: &Bytes,
+    /// Parses at most one [`PartRange::Main`] and one [`PartRange::Metadata`].
+    fn parse_part_ranges(
+        raw: &[u8],
         raw_modified: &[u8],
-    ) -> Result<Vec<BytecodePart>, VerificationErrorKind> {
+        offset: usize,
+    ) -> Result<Vec<PartRange>, VerificationErrorKind> {
         let mut parts = Vec::new();
 
         let len = raw.len();
 
         // search for the first non-matching byte
-        let mut index = raw
+        let mut index = raw[offset..]
             .iter()
-            .zip(raw_modified.iter())
-            .position(|(a, b)| a != b);
+            .zip(raw_modified[offset..].iter())
+            .position(|(a, b)| a != b)
+            .map(|i| offset + i);
 
         // There is some non-matching byte - part of the metadata part byte.
         if let Some(mut i) = index {
             // `i` is the first different byte. The metadata hash itself started somewhere earlier
-            // (at least for "a1"/"a2" indicating number of elements in cbor mapping).
-            // Next steps are trying to find that beginning.
-
-            let mut result = MetadataHash::from_cbor(&raw[i..]);
-            while result.is_err() {
+            // (at least for "a1"/"a2"/"a3" indicating number of elements in cbor mapping).
+            // Next steps are trying to find that beginning. The metadata is parsed strictly
+            // and should be followed by its encoded length, so that the bytes
+            // preceding the actual metadata beginning are not mistaken for it.
+
+            let mut length_error = None;
+            let mut result = metadata::parse(&raw[i..]);
+            while let Err(err) = &result {
+                // Metadata has been decoded but is not followed by the valid length
+                if matches!(
+                    err,
+                    MetadataError::MissingLength | MetadataError::LengthMismatch(_)
+                ) {
+                    length_error.get_or_insert_with(|| err.to_string());
+                }
                 // It is the beginning of the bytecode segment but no metadata hash has been parsed
-                if i == 0 {
+                if i == offset {
                     return Err(VerificationErrorKind::InternalError(
-                        "failed to parse bytecode part".into(),
+                        length_error.unwrap_or_else(|| "failed to parse bytecode part".into()),
                     ));
                 }
                 i -= 1;
 
-                result = MetadataHash::from_cbor(&raw[i..]);
+                result = metadata::parse(&raw[i..]);
             }
 
+            // The length includes the encoded metadata length
             let (metadata, metadata_length) = result.unwrap();
 
-            if len < i + metadata_length + 2 {
-                return Err(VerificationErrorKind::InternalError(
-                    "failed to parse metadata length".into(),
-                ));
-            }
-            // Decode length of metadata hash representation
-            let mut metadata_length_raw =
-                raw.slice((i + metadata_length)..(i + metadata_length + 2));
-            let encoded_metadata_length = metadata_length_raw.get_u16() as usize;
-            if encoded_metadata_length != metadata_length {
-                return Err(VerificationErrorKind::InternalError(format!(
-                    "encoded metadata length does not correspond to actual metadata length: {}",
-                    Mismatch::new(metadata_length, encoded_metadata_length)
-                )));
-            }
-
-            parts.push(BytecodePart::Metadata {
-                raw: raw.slice(i..(i + metadata_length + 2)),
+            parts.push(PartRange::Metadata {
+                range: i..(i + metadata_length),
                 metadata,
             });
 
@@ -307,13 +365,8 @@ impl<T> LocalBytecode<T> {
         // If there is something before metadata part (if any)
         // belongs to main part
         let i = index.unwrap_or(len);
-        if i > 0 {
-            parts.insert(
-                0,
-                BytecodePart::Main {
-                    raw: raw.slice(0..i),
-                },
-            )
+        if i > offset {
+            parts.insert(0, PartRange::Main { range: offset..i })
         }
 
         Ok(parts)
@@ -391,8 +444,8 @@ mod local_bytecode_initialization_tests {
             .expect("Metadata bytecode part is invalid hex")
             .0;
         let (metadata, len) =
-            MetadataHash::from_cbor(&raw).expect("Metadata bytecode part is not metadata");
-        if raw.len() != len + 2 {
+            metadata::parse(&raw).expect("Metadata bytecode part is not metadata");
+        if raw.len() != len {
             panic!("Metadata bytecode part has invalid length");
         }
         BytecodePart::Metadata { raw, metadata }
@@ -426,7 +479,7 @@ mod local_bytecode_initialization_tests {
                 "Invalid bytecode"
             );
             assert_eq!(
-                &vec![main_bytecode_part(CREATION_TX_INPUT_MAIN_PART_1)],
+                vec![main_bytecode_part(CREATION_TX_INPUT_MAIN_PART_1)],
                 local_bytecode.bytecode_parts(),
                 "Invalid bytecode parts"
             );
@@ -452,7 +505,7 @@ mod local_bytecode_initialization_tests {
                 "Invalid bytecode"
             );
             assert_eq!(
-                &vec![main_bytecode_part(DEPLOYED_BYTECODE_MAIN_PART_1)],
+                vec![main_bytecode_part(DEPLOYED_BYTECODE_MAIN_PART_1)],
                 local_bytecode.bytecode_parts(),
                 "Invalid bytecode parts"
             );
@@ -489,7 +542,7 @@ mod local_bytecode_initialization_tests {
                 "Invalid bytecode"
             );
             assert_eq!(
-                &vec![
+                vec![
                     main_bytecode_part(CREATION_TX_INPUT_MAIN_PART_1),
                     metadata_bytecode_part(METADATA_PART_1)
                 ],
@@ -518,7 +571,7 @@ mod local_bytecode_initialization_tests {
                 "Invalid bytecode"
             );
             assert_eq!(
-                &vec![
+                vec![
                     main_bytecode_part(DEPLOYED_BYTECODE_MAIN_PART_1),
                     metadata_bytecode_part(METADATA_PART_1)
                 ],
@@ -564,7 +617,7 @@ mod local_bytecode_initialization_tests {
                 "Invalid bytecode"
             );
             assert_eq!(
-                &vec![
+                vec![
                     main_bytecode_part(CREATION_TX_INPUT_MAIN_PART_1),
                     metadata_bytecode_part(METADATA_PART_1),
                     main_bytecode_part(CREATION_TX_INPUT_MAIN_PART_2),
@@ -595,7 +648,7 @@ mod local_bytecode_initialization_tests {
                 "Invalid bytecode"
             );
             assert_eq!(
-                &vec![
+                vec![
                     main_bytecode_part(DEPLOYED_BYTECODE_MAIN_PART_1),
                     metadata_bytecode_part(METADATA_PART_1),
                     main_bytecode_part(DEPLOYED_BYTECODE_MAIN_PART_2),
@@ -641,7 +694,7 @@ mod local_bytecode_initialization_tests {
                 "Invalid bytecode"
             );
             assert_eq!(
-                &vec![
+                vec![
                     main_bytecode_part(CREATION_TX_INPUT_MAIN_PART_1),
                     metadata_bytecode_part(METADATA_PART_1),
                     metadata_bytecode_part(METADATA_PART_2),
@@ -671,7 +724,7 @@ mod local_bytecode_initialization_tests {
                 "Invalid bytecode"
             );
             assert_eq!(
-                &vec![
+                vec![
                     main_bytecode_part(DEPLOYED_BYTECODE_MAIN_PART_1),
                     metadata_bytecode_part(METADATA_PART_1),
                     metadata_bytecode_part(METADATA_PART_2),
@@ -682,6 +735,52 @@ mod local_bytecode_initialization_tests {
         }
     }
 
+    #[test]
+    fn with_experimental_metadata() {
+        // {"ipfs": h'1220...', "experimental": true, "solc": h'000816'}
+        let metadata = "a3646970667358221220eb23ce2c13ea8739368f952f6c6a4b1f0623d147d2a19b6d4d26a61ab03fcd3e6c6578706572696d656e74616cf564736f6c63430008160041";
+        let metadata_modified = metadata.replace("eb23ce2c", "00000000");
+
+        let creation_tx_input_str = format!("{CREATION_TX_INPUT_MAIN_PART_1}{metadata}");
+        let creation_tx_input_modified_str =
+            format!("{CREATION_TX_INPUT_MAIN_PART_1}{metadata_modified}");
+
+        let deployed_bytecode_str = format!("{DEPLOYED_BYTECODE_MAIN_PART_1}{metadata}");
+        let deployed_bytecode_modified_str =
+            format!("{DEPLOYED_BYTECODE_MAIN_PART_1}{metadata_modified}");
+
+        let Bytecodes {
+            local_bytecode,
+            deployed_bytecode,
+            ..
+        }: Bytecodes<DeployedBytecode> = new_local_bytecode(
+            (&creation_tx_input_str, &deployed_bytecode_str),
+            (
+                &creation_tx_input_modified_str,
+                &deployed_bytecode_modified_str,
+            ),
+        )
+        .expect("Initialization of local bytecode failed");
+        assert_eq!(
+            deployed_bytecode.bytecode(),
+            local_bytecode.bytecode(),
+            "Invalid bytecode"
+        );
+        let metadata_part = metadata_bytecode_part(metadata);
+        match &metadata_part {
+            BytecodePart::Metadata { metadata, .. } => assert!(metadata.experimental),
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            vec![
+                main_bytecode_part(DEPLOYED_BYTECODE_MAIN_PART_1),
+                metadata_part
+            ],
+            local_bytecode.bytecode_parts(),
+            "Invalid bytecode parts"
+        );
+    }
+
     #[test]
     fn with_different_lengths_should_fail() {
         let creation_tx_input_str = format!("{CREATION_TX_INPUT_MAIN_PART_1}{METADATA_PART_1}");
@@ -0,0 +1,157 @@
+//! Consistency pre-check of the creation transaction input and the deployed bytecode
+//! provided together.
+//!
+//! The runtime code is copied into the creation transaction input verbatim (except for
+//! immutables), so the deployed bytecode produced by the creation code should be found there.
+//! The most common reason for it not to be is a proxy: the creation code of the proxy is
+//! submitted along with the runtime code of its implementation, or vice versa.
+
+use super::errors::BytecodeInitError;
+use crate::solidity::metadata;
+
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7f;
+const DELEGATECALL: u8 = 0xf4;
+
+/// Runtime code of the EIP-1167 minimal proxy with the implementation address in between.
+const MINIMAL_PROXY_PREFIX: [u8; 10] = [0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d, 0x3d, 0x36, 0x3d, 0x73];
+const MINIMAL_PROXY_SUFFIX: [u8; 15] = [
+    0x5a, 0xf4, 0x3d, 0x82, 0x80, 0x3e, 0x90, 0x3d, 0x91, 0x60, 0x2b, 0x57, 0xfd, 0x5b, 0xf3,
+];
+
+/// Checks that the deployed bytecode could have been produced by the creation transaction input.
+/// Returns an error explaining the likely reason if it clearly could not.
+pub(crate) fn check(
+    creation_tx_input: &[u8],
+    deployed_bytecode: &[u8],
+) -> Result<(), BytecodeInitError> {
+    let inconsistent = |reason: String| Err(BytecodeInitError::InconsistentBytecodes(reason));
+
+    if let Some(implementation) = minimal_proxy_implementation(deployed_bytecode) {
+        return inconsistent(format!(
+            "deployed bytecode is an EIP-1167 minimal proxy delegating to 0x{}; \
+            verify the implementation contract at that address instead",
+            hex::encode(implementation)
+        ));
+    }
+
+    if deployed_bytecode.len() > creation_tx_input.len() {
+        return inconsistent(format!(
+            "deployed bytecode is longer than the creation transaction input ({} > {} bytes)",
+            deployed_bytecode.len(),
+            creation_tx_input.len()
+        ));
+    }
+
+    // Metadata hash is appended to the runtime code and is not affected by immutables,
+    // thus is the most reliable part to look for
+    let (code, metadata) = metadata::split(deployed_bytecode);
+    if metadata.is_some() {
+        let metadata = &deployed_bytecode[code.len()..];
+        if !contains(creation_tx_input, metadata) {
+            let reason = if delegates(code) {
+                "deployed bytecode seems to be a proxy, while the creation transaction input \
+                deploys a different contract; verify the implementation contract instead, \
+                or submit the creation transaction input of the proxy"
+            } else {
+                "metadata hash of the deployed bytecode is not found in the creation transaction \
+                input; the bytecodes seem to belong to different contracts (e.g., creation code \
+                of a proxy and deployed bytecode of its implementation)"
+            };
+            return inconsistent(reason.to_string());
+        }
+    }
+
+    Ok(())
+}
+
+fn minimal_proxy_implementation(deployed_bytecode: &[u8]) -> Option<&[u8]> {
+    let address = deployed_bytecode
+        .strip_prefix(&MINIMAL_PROXY_PREFIX)?
+        .strip_suffix(&MINIMAL_PROXY_SUFFIX)?;
+    (address.len() == 20).then_some(address)
+}
+
+fn delegates(code: &[u8]) -> bool {
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        if opcode == DELEGATECALL {
+            return true;
+        }
+        pc += 1;
+        if (PUSH1..=PUSH32).contains(&opcode) {
+            pc += (opcode - PUSH1) as usize + 1;
+        }
+    }
+    false
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // {"ipfs": h'1220EB23CE2C13EA8739368F952F6C6A4B1F0623D147D2A19B6D4D26A61AB03FCD3E', "solc": 0.8.14}
+    const METADATA: &str = "a2646970667358221220eb23ce2c13ea8739368f952f6c6a4b1f0623d147d2a19b6d4d26a61ab03fcd3e64736f6c634300080e0033";
+    // PUSH1 0x80 PUSH1 0x40 MSTORE PUSH1 0x2a PUSH1 0x00 SSTORE
+    const RUNTIME_CODE: &str = "6080604052602a600055";
+    // PUSH1 0x80 PUSH1 0x40 MSTORE PUSH1 0x2a PUSH1 0x00 CODECOPY
+    const CREATION_CODE: &str = "6080604052602a60003900";
+
+    fn bytes(parts: &[&str]) -> Vec<u8> {
+        hex::decode(parts.concat()).unwrap()
+    }
+
+    #[test]
+    fn consistent_bytecodes() {
+        let deployed_bytecode = bytes(&[RUNTIME_CODE, METADATA]);
+        let creation_tx_input = bytes(&[CREATION_CODE, RUNTIME_CODE, METADATA, "0000002a"]);
+        assert_eq!(Ok(()), check(&creation_tx_input, &deployed_bytecode));
+
+        // No conclusion could be made without metadata
+        let creation_tx_input = bytes(&[CREATION_CODE, "0000"]);
+        assert_eq!(Ok(()), check(&creation_tx_input, &bytes(&[RUNTIME_CODE])));
+    }
+
+    #[test]
+    fn inconsistent_bytecodes() {
+        let other_metadata = METADATA.replace("eb23ce2c", "00000000");
+        let creation_tx_input = bytes(&[CREATION_CODE, RUNTIME_CODE, &other_metadata]);
+
+        let err = check(&creation_tx_input, &bytes(&[RUNTIME_CODE, METADATA])).unwrap_err();
+        assert!(err.to_string().contains("metadata hash"), "{err}");
+
+        // PUSH1 0x00 DELEGATECALL
+        let proxy = bytes(&["6000f4", METADATA]);
+        let err = check(&creation_tx_input, &proxy).unwrap_err();
+        assert!(err.to_string().contains("seems to be a proxy"), "{err}");
+
+        let err = check(&bytes(&[CREATION_CODE]), &bytes(&[RUNTIME_CODE, METADATA])).unwrap_err();
+        assert!(err.to_string().contains("is longer"), "{err}");
+    }
+
+    #[test]
+    fn minimal_proxy() {
+        let implementation = "bebebebebebebebebebebebebebebebebebebebe";
+        let minimal_proxy = bytes(&[
+            "363d3d373d3d3d363d73",
+            implementation,
+            "5af43d82803e903d91602b57fd5bf3",
+        ]);
+        let creation_tx_input = bytes(&[CREATION_CODE, RUNTIME_CODE, METADATA]);
+
+        let err = check(&creation_tx_input, &minimal_proxy).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(&format!("minimal proxy delegating to 0x{implementation}")),
+            "{err}"
+        );
+    }
+}
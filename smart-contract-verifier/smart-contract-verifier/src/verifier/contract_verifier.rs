@@ -1,18 +1,27 @@
 use super::{
     all_metadata_extracting_verifier, base,
-    base::LocalBytecodeParts,
+    base::{LocalBytecodeParts, VerificationSuccess},
     bytecode::{CreationTxInput, DeployedBytecode},
+    consistency,
     errors::{BytecodeInitError, VerificationError, VerificationErrorKind},
+    evm_fork::{self, EvmFork},
+    normalization::Normalization,
+    trace::{self, Trace, TraceStage, TraceStep},
 };
 use crate::{
     compiler::{self, Compilers, EvmCompiler},
+    vyper::blueprint::BlueprintPreamble,
     DisplayBytes, MatchType,
 };
 use anyhow::anyhow;
 use bytes::Bytes;
-use ethers_solc::{CompilerInput, CompilerOutput};
+use ethers_solc::{Artifact, CompilerInput, CompilerOutput};
 use mismatch::Mismatch;
-use std::{ops::Add, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::{Add, Range},
+    path::PathBuf,
+};
 use thiserror::Error;
 use tracing::instrument;
 
@@ -26,10 +35,32 @@ pub enum Error {
     Compilation(Vec<String>),
     #[error("{0}")]
     Internal(anyhow::Error),
+    /// Contains the reasons each of the compiled contracts does not match the bytecode
     #[error("No contract could be verified with provided data")]
-    NoMatchingContracts,
+    NoMatchingContracts(Vec<VerificationErrorKind>),
     #[error("Invalid compiler version: {0}")]
     CompilerVersionMismatch(Mismatch<semver::Version>),
+    #[error(
+        "Compiler version {version} does not satisfy the source pragmas: {}",
+        .pragmas.join("; ")
+    )]
+    IncompatibleCompilerVersion {
+        version: compiler::Version,
+        pragmas: Vec<String>,
+    },
+    #[error("{}", .0.join("; "))]
+    MissingSources(Vec<String>),
+    #[error(
+        "Compiled bytecode uses opcodes not supported by the chain EVM version ({evm_fork}): {}; \
+        set evmVersion to {evm_fork} or earlier",
+        .opcodes.join(", ")
+    )]
+    UnsupportedOpcodes {
+        evm_fork: EvmFork,
+        opcodes: Vec<String>,
+    },
+    #[error("{0}")]
+    LimitExceeded(compiler::LimitExceeded),
 }
 
 impl From<BytecodeInitError> for Error {
@@ -43,6 +74,7 @@ impl From<compiler::Error> for Error {
         match error {
             compiler::Error::VersionNotFound(version) => Error::VersionNotFound(version),
             compiler::Error::Compilation(details) => Error::Compilation(details),
+            compiler::Error::LimitExceeded(limit) => Error::LimitExceeded(limit),
             err => Error::Internal(anyhow!(err)),
         }
     }
@@ -60,12 +92,34 @@ pub struct Success {
     pub constructor_args: Option<DisplayBytes>,
     pub local_bytecode_parts: LocalBytecodeParts,
     pub match_type: MatchType,
+    /// Preamble of the blueprint contract (ERC-5202) if the deployed bytecode was a blueprint one
+    pub blueprint: Option<BlueprintPreamble>,
+    /// Values of immutables extracted from the deployed bytecode by the variable names.
+    /// Is empty for creation transaction inputs, as they do not contain immutables yet.
+    pub immutable_values: BTreeMap<String, DisplayBytes>,
+    /// Addresses of the libraries not linked during the compilation, extracted from
+    /// the remote bytecode by the fully qualified library names (e.g. "contracts/Lib.sol:Lib")
+    pub library_addresses: BTreeMap<String, DisplayBytes>,
+}
+
+/// Outputs of the compiler input required to verify the bytecodes compiled from it.
+pub struct CompilationOutputs {
+    outputs: (CompilerOutput, CompilerOutput),
+}
+
+impl CompilationOutputs {
+    /// Output of the compiler input as it is
+    pub fn compiler_output(&self) -> &CompilerOutput {
+        &self.outputs.0
+    }
 }
 
 pub struct ContractVerifier<'a, T> {
     compilers: &'a Compilers<T>,
     compiler_version: &'a compiler::Version,
     verifier: Box<dyn base::Verifier<Input = (CompilerOutput, CompilerOutput)>>,
+    evm_fork: Option<EvmFork>,
+    trace: Option<Trace>,
 }
 
 impl<'a, T: EvmCompiler> ContractVerifier<'a, T> {
@@ -74,25 +128,113 @@ impl<'a, T: EvmCompiler> ContractVerifier<'a, T> {
         compiler_version: &'a compiler::Version,
         creation_tx_input: Option<Bytes>,
         deployed_bytecode: Bytes,
+        bytecode_mask: Vec<Range<usize>>,
+        eof_enabled: bool,
+        normalization: Normalization,
     ) -> Result<Self, Error> {
         let verifier: Box<dyn base::Verifier<Input = (CompilerOutput, CompilerOutput)>> =
             match creation_tx_input {
-                None => Box::new(all_metadata_extracting_verifier::Verifier::<
-                    DeployedBytecode,
-                >::new(deployed_bytecode)?),
-                Some(creation_tx_input) => Box::new(all_metadata_extracting_verifier::Verifier::<
-                    CreationTxInput,
-                >::new(creation_tx_input)?),
+                None => Box::new(
+                    all_metadata_extracting_verifier::Verifier::<DeployedBytecode>::new(
+                        deployed_bytecode,
+                    )?
+                    .with_bytecode_mask(bytecode_mask)
+                    .with_eof(eof_enabled)
+                    .with_normalization(normalization),
+                ),
+                Some(_) if !bytecode_mask.is_empty() => {
+                    return Err(Error::Initialization(anyhow!(
+                        "bytecode mask may be applied to the deployed bytecode only"
+                    )))
+                }
+                Some(creation_tx_input) => {
+                    // The deployed bytecode is not verified in that case, but if provided,
+                    // allows to detect the inputs which cannot be verified before compilation
+                    if !deployed_bytecode.is_empty() {
+                        consistency::check(&creation_tx_input, &deployed_bytecode)?;
+                    }
+                    Box::new(
+                        all_metadata_extracting_verifier::Verifier::<CreationTxInput>::new(
+                            creation_tx_input,
+                        )?
+                        .with_eof(eof_enabled)
+                        .with_deployed_bytecode(&deployed_bytecode)
+                        .with_creation_immutables_masked(masks_creation_immutables(
+                            compiler_version,
+                        ))
+                        .with_normalization(normalization),
+                    )
+                }
             };
         Ok(Self {
             compilers,
             compiler_version,
             verifier,
+            evm_fork: None,
+            trace: None,
         })
     }
 
+    /// Sets the EVM version supported by the chain the bytecode is deployed on.
+    /// If no contract matches, while the compiled bytecodes use opcodes not supported
+    /// by the chain, [`Error::UnsupportedOpcodes`] is returned instead of [`Error::NoMatchingContracts`].
+    pub fn with_evm_fork(mut self, evm_fork: Option<EvmFork>) -> Self {
+        self.evm_fork = evm_fork;
+        self
+    }
+
+    /// Sets the trace the verification steps are recorded into.
+    pub fn with_trace(mut self, trace: Option<Trace>) -> Self {
+        self.trace = trace;
+        self
+    }
+
     #[instrument(skip(self, compiler_input), level = "debug")]
     pub async fn verify(&self, compiler_input: &CompilerInput) -> Result<Success, Error> {
+        let outputs = self.compile(compiler_input).await?;
+        let verification_success = self.match_outputs(&outputs.outputs)?;
+
+        let (compiler_output, _) = outputs.outputs;
+        // We accept compiler input and compiler version by reference, so that we
+        // avoid their cloning if verification fails.
+        // In case of success, they will be cloned exactly once.
+        Ok(self.success(compiler_input, compiler_output, verification_success))
+    }
+
+    /// Verifies the bytecode against the outputs of already compiled input.
+    /// Allows to verify several bytecodes deployed from the same sources
+    /// (e.g., factory deployed contracts) compiling them only once.
+    pub fn verify_compiled(
+        &self,
+        compiler_input: &CompilerInput,
+        outputs: &CompilationOutputs,
+    ) -> Result<Success, Error> {
+        let verification_success = self.match_outputs(&outputs.outputs)?;
+        let compiler_output = outputs.outputs.0.clone();
+        Ok(self.success(compiler_input, compiler_output, verification_success))
+    }
+
+    /// Compiles the input into the outputs required for verification.
+    /// The outputs depend on the compiler version and the input only,
+    /// so they may be shared by the verifiers of different bytecodes.
+    pub async fn compile(
+        &self,
+        compiler_input: &CompilerInput,
+    ) -> Result<CompilationOutputs, Error> {
+        trace::record(self.trace.as_ref(), || {
+            let settings = serde_json::to_vec(&compiler_input.settings).unwrap_or_default();
+            compiler_input.sources.iter().fold(
+                TraceStep::new(TraceStage::Compilation)
+                    .with_detail("compiler_version", self.compiler_version)
+                    .with_bytes("settings", &settings),
+                |step, (path, source)| {
+                    step.with_detail(
+                        format!("source_hash:{}", path.display()),
+                        trace::hash(source.content.as_bytes()),
+                    )
+                },
+            )
+        });
         let compiler_output = self
             .compilers
             .compile(self.compiler_version, compiler_input)
@@ -120,9 +262,30 @@ impl<'a, T: EvmCompiler> ContractVerifier<'a, T> {
                 .await?
         };
 
-        let outputs = (compiler_output, compiler_output_modified);
-        let verification_success = self.verifier.verify(&outputs).map_err(|errs| {
-            errs.into_iter()
+        Ok(CompilationOutputs {
+            outputs: (compiler_output, compiler_output_modified),
+        })
+    }
+
+    fn match_outputs(
+        &self,
+        outputs: &(CompilerOutput, CompilerOutput),
+    ) -> Result<VerificationSuccess, Error> {
+        let result = self.verifier.verify(outputs, self.trace.as_ref());
+        trace::record(self.trace.as_ref(), || {
+            let step = TraceStep::new(TraceStage::Result);
+            match &result {
+                Ok(success) => step
+                    .with_contract(&success.file_path, &success.contract_name)
+                    .with_detail(
+                        "match_type",
+                        format!("{:?}", success.match_type).to_lowercase(),
+                    ),
+                Err(errs) => step.with_detail("mismatched_contracts", errs.len()),
+            }
+        });
+        result.map_err(|errs| {
+            errs.iter()
                 .find_map(|err| match err {
                     // Even one CompilerVersionMismatch error indicates that provided
                     // compiler version does not correspond to on chain bytecode.
@@ -142,19 +305,40 @@ impl<'a, T: EvmCompiler> ContractVerifier<'a, T> {
                             }),
                         ..
                     } => Some(Error::CompilerVersionMismatch(Mismatch::new(
-                        version,
+                        version.clone(),
                         self.compiler_version.version().clone(),
                     ))),
                     _ => None,
                 })
-                .unwrap_or(Error::NoMatchingContracts)
-        })?;
+                .or_else(|| self.unsupported_opcodes(&outputs.0))
+                .unwrap_or_else(|| {
+                    Error::NoMatchingContracts(errs.into_iter().map(|err| err.kind).collect())
+                })
+        })
+    }
 
-        let (compiler_output, _) = outputs;
-        // We accept compiler input and compiler version by reference, so that we
-        // avoid their cloning if verification fails.
-        // In case of success, they will be cloned exactly once.
-        Ok(Success {
+    fn unsupported_opcodes(&self, compiler_output: &CompilerOutput) -> Option<Error> {
+        let evm_fork = self.evm_fork?;
+        let opcodes: BTreeSet<_> = compiler_output
+            .contracts
+            .values()
+            .flat_map(|contracts| contracts.values())
+            .filter_map(|contract| contract.get_deployed_bytecode_bytes())
+            .flat_map(|bytecode| evm_fork::unsupported_opcodes(&bytecode.0, evm_fork))
+            .collect();
+        (!opcodes.is_empty()).then(|| Error::UnsupportedOpcodes {
+            evm_fork,
+            opcodes: opcodes.into_iter().map(str::to_string).collect(),
+        })
+    }
+
+    fn success(
+        &self,
+        compiler_input: &CompilerInput,
+        compiler_output: CompilerOutput,
+        verification_success: VerificationSuccess,
+    ) -> Success {
+        Success {
             compiler_input: compiler_input.clone(),
             compiler_output,
             compiler_version: self.compiler_version.clone(),
@@ -164,6 +348,17 @@ impl<'a, T: EvmCompiler> ContractVerifier<'a, T> {
             constructor_args: verification_success.constructor_args,
             local_bytecode_parts: verification_success.local_bytecode_parts,
             match_type: verification_success.match_type,
-        })
+            blueprint: None,
+            immutable_values: verification_success.immutable_values,
+            library_addresses: verification_success.library_addresses,
+        }
     }
 }
+
+/// Solidity 0.8.21 relaxed the initialization of immutables (they may be assigned
+/// anywhere in the constructor, including conditionally), after which the bytes
+/// at their positions inside the deployed bytecode embedded into the creation
+/// bytecode are not guaranteed to match the locally compiled ones.
+fn masks_creation_immutables(compiler_version: &compiler::Version) -> bool {
+    compiler_version.version() >= &semver::Version::new(0, 8, 21)
+}
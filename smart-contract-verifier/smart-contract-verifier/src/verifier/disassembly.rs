@@ -0,0 +1,148 @@
+//! Minimal analysis of creation transaction inputs.
+//!
+//! Used to find out where the contract code ends and constructor arguments begin
+//! in cases the metadata hash cannot be relied on (is absent or malformed).
+
+const POP: u8 = 0x50;
+const CODECOPY: u8 = 0x39;
+const PUSH0: u8 = 0x5f;
+const PUSH32: u8 = 0x7f;
+const DUP1: u8 = 0x80;
+const DUP16: u8 = 0x8f;
+const SWAP1: u8 = 0x90;
+const SWAP16: u8 = 0x9f;
+const RETURN: u8 = 0xf3;
+
+/// Returns the length of the contract code in the creation transaction input,
+/// which is the offset constructor arguments start at.
+///
+/// Constructors generated by Solidity and Vyper finish by copying the runtime code
+/// into memory via `CODECOPY` and returning it via `RETURN`. The runtime code is
+/// located at the end of the contract code, so `offset + size` of that copy is
+/// the code boundary. Only constant stack values are tracked, thus `None` is returned
+/// if the pattern could not be recognized.
+pub(crate) fn creation_code_length(creation_tx_input: &[u8]) -> Option<usize> {
+    // Values known at the analysis time. `None` corresponds to unknown values.
+    let mut stack: Vec<Option<usize>> = Vec::new();
+    // (offset, size) of the last code copied into the beginning of the memory
+    let mut runtime_code: Option<(usize, usize)> = None;
+
+    let mut pc = 0;
+    while pc < creation_tx_input.len() {
+        // Instructions located inside the runtime code are not part of the constructor
+        if matches!(runtime_code, Some((offset, _)) if pc >= offset) {
+            break;
+        }
+
+        let opcode = creation_tx_input[pc];
+        pc += 1;
+        match opcode {
+            PUSH0..=PUSH32 => {
+                let size = (opcode - PUSH0) as usize;
+                let data = creation_tx_input.get(pc..pc + size)?;
+                pc += size;
+                stack.push(to_usize(data));
+            }
+            DUP1..=DUP16 => {
+                let depth = (opcode - DUP1) as usize + 1;
+                let value = stack
+                    .len()
+                    .checked_sub(depth)
+                    .and_then(|index| stack[index]);
+                stack.push(value);
+            }
+            SWAP1..=SWAP16 => {
+                let depth = (opcode - SWAP1) as usize + 1;
+                let len = stack.len();
+                if len > depth {
+                    stack.swap(len - 1, len - 1 - depth);
+                } else {
+                    stack.clear();
+                }
+            }
+            POP => {
+                stack.pop();
+            }
+            CODECOPY => {
+                let mut pop = || stack.pop().flatten();
+                let (dest_offset, offset, size) = (pop(), pop(), pop());
+                if let (Some(0), Some(offset), Some(size)) = (dest_offset, offset, size) {
+                    if size > 0 {
+                        runtime_code = Some((offset, size));
+                    }
+                }
+            }
+            RETURN => {
+                if let Some((offset, size)) = runtime_code {
+                    return offset
+                        .checked_add(size)
+                        .filter(|&end| end <= creation_tx_input.len());
+                }
+                stack.clear();
+            }
+            // Effects of other instructions on the stack are not tracked
+            _ => stack.clear(),
+        }
+    }
+
+    None
+}
+
+fn to_usize(data: &[u8]) -> Option<usize> {
+    let significant = data
+        .iter()
+        .position(|&byte| byte != 0)
+        .map(|index| &data[index..])
+        .unwrap_or_default();
+    if significant.len() > std::mem::size_of::<usize>() {
+        return None;
+    }
+    Some(
+        significant
+            .iter()
+            .fold(0usize, |value, &byte| (value << 8) | byte as usize),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn decode(hex: &str) -> Vec<u8> {
+        hex::decode(hex).expect("Invalid hex")
+    }
+
+    #[test]
+    fn solidity_creation_code() {
+        // PUSH2 0x0005 DUP1 PUSH2 0x000e PUSH1 0x00 CODECOPY PUSH1 0x00 RETURN INVALID
+        // followed by 5 bytes of runtime code and 32 bytes of constructor arguments
+        let code = decode(&format!(
+            "{}{}{}",
+            "6100058061000e6000396000f3fe",
+            "6080604052",
+            "00".repeat(32)
+        ));
+        assert_eq!(Some(0x0e + 0x05), creation_code_length(&code));
+    }
+
+    #[test]
+    fn unknown_values_are_ignored() {
+        // PUSH2 0x0005 CODESIZE PUSH1 0x00 CODECOPY PUSH1 0x00 RETURN
+        let code = decode("610005386000396000f3");
+        assert_eq!(None, creation_code_length(&code));
+    }
+
+    #[test]
+    fn code_copied_out_of_range() {
+        // PUSH2 0xffff DUP1 PUSH2 0x000e PUSH1 0x00 CODECOPY PUSH1 0x00 RETURN
+        let code = decode("61ffff8061000e6000396000f3");
+        assert_eq!(None, creation_code_length(&code));
+    }
+
+    #[test]
+    fn truncated_push() {
+        let code = decode("61ff");
+        assert_eq!(None, creation_code_length(&code));
+    }
+}
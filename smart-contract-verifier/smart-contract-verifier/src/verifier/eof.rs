@@ -0,0 +1,422 @@
+//! Parsing of the EVM Object Format (EOF) containers (EIP-3540, EIP-3670).
+//!
+//! EOF bytecode starts with the `0xEF00` magic followed by a header with the sizes
+//! of the sections. Unlike the legacy bytecode, the end of the container is defined
+//! by the header, so the constructor arguments appended to an init container do not
+//! have to be found by analyzing the constructor code.
+//!
+//! The data section of a deployed container may be extended by the auxiliary data
+//! (e.g., values of immutables) provided by the init code on deployment, which
+//! updates the data size in the header as well.
+
+use bytes::{Bytes, BytesMut};
+use thiserror::Error;
+
+const MAGIC: [u8; 2] = [0xef, 0x00];
+const VERSION: u8 = 0x01;
+
+const KIND_TYPES: u8 = 0x01;
+const KIND_CODE: u8 = 0x02;
+const KIND_CONTAINER: u8 = 0x03;
+const KIND_DATA: u8 = 0xff;
+const TERMINATOR: u8 = 0x00;
+
+const TYPE_SIZE: usize = 4;
+const MAX_CODE_SECTIONS: usize = 1024;
+const MAX_CONTAINER_SECTIONS: usize = 256;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EofError {
+    #[error("bytecode does not start with the EOF magic")]
+    NotEof,
+    #[error("unsupported EOF version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("invalid EOF header: {0}")]
+    InvalidHeader(String),
+    #[error("EOF container is truncated: {declared} bytes declared, {actual} found")]
+    Truncated { declared: usize, actual: usize },
+    #[error("invalid EOF code section {section}: {reason}")]
+    InvalidCode { section: usize, reason: String },
+}
+
+/// EOF container split into the sections. The bytecode may contain trailing bytes
+/// after the container (e.g., constructor arguments).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Container<'a> {
+    pub header: &'a [u8],
+    pub types: &'a [u8],
+    pub code_sections: Vec<&'a [u8]>,
+    pub container_sections: Vec<&'a [u8]>,
+    /// Data section. May be shorter than declared for containers not deployed yet
+    /// (the auxiliary data is appended on deployment).
+    pub data: &'a [u8],
+    pub declared_data_size: usize,
+    /// Offset of the data size inside the header
+    data_size_offset: usize,
+}
+
+impl<'a> Container<'a> {
+    /// Length of the container as declared by the header
+    pub fn length(&self) -> usize {
+        self.data_offset() + self.declared_data_size
+    }
+
+    fn data_offset(&self) -> usize {
+        self.header.len()
+            + self.types.len()
+            + self
+                .code_sections
+                .iter()
+                .map(|code| code.len())
+                .sum::<usize>()
+            + self
+                .container_sections
+                .iter()
+                .map(|container| container.len())
+                .sum::<usize>()
+    }
+}
+
+pub fn is_eof(bytecode: &[u8]) -> bool {
+    bytecode.starts_with(&MAGIC)
+}
+
+/// Parses the container the bytecode starts with and validates its code sections.
+pub fn parse(bytecode: &[u8]) -> Result<Container, EofError> {
+    if !is_eof(bytecode) {
+        return Err(EofError::NotEof);
+    }
+    let mut reader = Reader {
+        bytecode,
+        position: MAGIC.len(),
+    };
+
+    let version = reader.u8()?;
+    if version != VERSION {
+        return Err(EofError::UnsupportedVersion(version));
+    }
+
+    reader.expect_kind(KIND_TYPES, "types section")?;
+    let types_size = reader.u16()?;
+
+    reader.expect_kind(KIND_CODE, "code section")?;
+    let code_sizes = reader.sizes(MAX_CODE_SECTIONS, "code")?;
+    if types_size != code_sizes.len() * TYPE_SIZE {
+        return Err(EofError::InvalidHeader(format!(
+            "types section size {types_size} does not correspond to {} code sections",
+            code_sizes.len()
+        )));
+    }
+
+    let container_sizes = if reader.peek() == Some(KIND_CONTAINER) {
+        reader.u8()?;
+        reader.sizes(MAX_CONTAINER_SECTIONS, "container")?
+    } else {
+        vec![]
+    };
+
+    reader.expect_kind(KIND_DATA, "data section")?;
+    let data_size_offset = reader.position;
+    let declared_data_size = reader.u16()?;
+    reader.expect_kind(TERMINATOR, "header terminator")?;
+    let header = &bytecode[..reader.position];
+
+    let body_size =
+        types_size + code_sizes.iter().sum::<usize>() + container_sizes.iter().sum::<usize>();
+    if bytecode.len() < header.len() + body_size {
+        return Err(EofError::Truncated {
+            declared: header.len() + body_size + declared_data_size,
+            actual: bytecode.len(),
+        });
+    }
+    let types = reader.take(types_size);
+    let code_sections: Vec<_> = code_sizes.iter().map(|&size| reader.take(size)).collect();
+    let container_sections = container_sizes
+        .iter()
+        .map(|&size| reader.take(size))
+        .collect();
+    let data = reader.take(declared_data_size.min(bytecode.len() - reader.position));
+
+    for (section, code) in code_sections.iter().enumerate() {
+        validate_code(code).map_err(|reason| EofError::InvalidCode { section, reason })?;
+    }
+
+    Ok(Container {
+        header,
+        types,
+        code_sections,
+        container_sections,
+        data,
+        declared_data_size,
+        data_size_offset,
+    })
+}
+
+/// Length of the (init) container the bytecode starts with.
+/// Bytes following the container are the constructor arguments.
+pub fn container_length(bytecode: &[u8]) -> Result<usize, EofError> {
+    let container = parse(bytecode)?;
+    if container.data.len() < container.declared_data_size {
+        return Err(EofError::Truncated {
+            declared: container.length(),
+            actual: bytecode.len(),
+        });
+    }
+    Ok(container.length())
+}
+
+/// Removes the auxiliary data appended to the data section of the deployed container
+/// on deployment, so that it may be compared with the locally compiled one.
+///
+/// Returns the deployed container with the data size of the local one and the removed
+/// auxiliary data. Returns `None` if the containers have different structure.
+pub fn remove_aux_data(deployed: &[u8], local: &[u8]) -> Option<(Bytes, Bytes)> {
+    let deployed_container = parse(deployed).ok()?;
+    let local_container = parse(local).ok()?;
+    if deployed_container.header.len() != local_container.header.len()
+        || deployed_container.data_offset() != local_container.data_offset()
+        || deployed_container.declared_data_size < local_container.declared_data_size
+        || deployed.len() < local.len()
+    {
+        return None;
+    }
+
+    let offset = deployed_container.data_size_offset;
+    let mut patched = BytesMut::from(&deployed[..local.len()]);
+    patched[offset..offset + 2].copy_from_slice(&local[offset..offset + 2]);
+    let aux_data = Bytes::copy_from_slice(&deployed[local.len()..]);
+    Some((patched.freeze(), aux_data))
+}
+
+/// Validates the instructions of the code section (EIP-3670): deprecated instructions
+/// are not allowed, and immediate arguments of the instructions may not be truncated.
+fn validate_code(code: &[u8]) -> Result<(), String> {
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        if let Some(name) = deprecated_instruction(opcode) {
+            return Err(format!("deprecated instruction {name} at {pc}"));
+        }
+        let immediate_size = match opcode {
+            // RJUMPV: max_index followed by (max_index + 1) relative offsets
+            0xe2 => {
+                let max_index = *code
+                    .get(pc + 1)
+                    .ok_or_else(|| format!("truncated RJUMPV at {pc}"))?;
+                1 + 2 * (max_index as usize + 1)
+            }
+            opcode => immediate_size(opcode),
+        };
+        pc += 1 + immediate_size;
+        if pc > code.len() {
+            return Err(format!("truncated immediate argument of 0x{opcode:02x}"));
+        }
+    }
+    Ok(())
+}
+
+fn immediate_size(opcode: u8) -> usize {
+    match opcode {
+        // PUSH1..PUSH32
+        0x60..=0x7f => (opcode - 0x5f) as usize,
+        // RJUMP, RJUMPI, CALLF, JUMPF, DATALOADN
+        0xe0 | 0xe1 | 0xe3 | 0xe5 | 0xd1 => 2,
+        // DUPN, SWAPN, EXCHANGE, EOFCREATE, RETURNCONTRACT
+        0xe6 | 0xe7 | 0xe8 | 0xec | 0xee => 1,
+        _ => 0,
+    }
+}
+
+fn deprecated_instruction(opcode: u8) -> Option<&'static str> {
+    let name = match opcode {
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3f => "EXTCODEHASH",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x5a => "GAS",
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xff => "SELFDESTRUCT",
+        _ => return None,
+    };
+    Some(name)
+}
+
+struct Reader<'a> {
+    bytecode: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytecode.get(self.position).copied()
+    }
+
+    fn u8(&mut self) -> Result<u8, EofError> {
+        let value = self
+            .peek()
+            .ok_or_else(|| EofError::InvalidHeader("unexpected end of the header".into()))?;
+        self.position += 1;
+        Ok(value)
+    }
+
+    fn u16(&mut self) -> Result<usize, EofError> {
+        Ok(((self.u8()? as usize) << 8) | self.u8()? as usize)
+    }
+
+    fn expect_kind(&mut self, kind: u8, name: &str) -> Result<(), EofError> {
+        let actual = self.u8()?;
+        if actual != kind {
+            return Err(EofError::InvalidHeader(format!(
+                "expected {name} (0x{kind:02x}), found 0x{actual:02x}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn sizes(&mut self, max_sections: usize, name: &str) -> Result<Vec<usize>, EofError> {
+        let number = self.u16()?;
+        if number == 0 || number > max_sections {
+            return Err(EofError::InvalidHeader(format!(
+                "invalid number of {name} sections: {number}"
+            )));
+        }
+        (0..number)
+            .map(|_| match self.u16()? {
+                0 => Err(EofError::InvalidHeader(format!("empty {name} section"))),
+                size => Ok(size),
+            })
+            .collect()
+    }
+
+    /// The caller must ensure the bytecode is long enough
+    fn take(&mut self, size: usize) -> &'a [u8] {
+        let result = &self.bytecode[self.position..self.position + size];
+        self.position += size;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BufMut;
+    use pretty_assertions::assert_eq;
+
+    // PUSH1 0x2a PUSH0 SSTORE STOP
+    const CODE: &[u8] = &[0x60, 0x2a, 0x5f, 0x55, 0x00];
+    const DATA: &[u8] = &[0xa2, 0x64, 0x00, 0x33];
+
+    /// Encodes a container for tests: (code sections, container sections, data, declared data size)
+    fn encode(
+        code_sections: &[&[u8]],
+        containers: &[&[u8]],
+        data: &[u8],
+        data_size: u16,
+    ) -> Vec<u8> {
+        let mut result = BytesMut::new();
+        result.put_slice(&MAGIC);
+        result.put_u8(VERSION);
+        result.put_u8(KIND_TYPES);
+        result.put_u16((code_sections.len() * TYPE_SIZE) as u16);
+        result.put_u8(KIND_CODE);
+        result.put_u16(code_sections.len() as u16);
+        for code in code_sections {
+            result.put_u16(code.len() as u16);
+        }
+        if !containers.is_empty() {
+            result.put_u8(KIND_CONTAINER);
+            result.put_u16(containers.len() as u16);
+            for container in containers {
+                result.put_u16(container.len() as u16);
+            }
+        }
+        result.put_u8(KIND_DATA);
+        result.put_u16(data_size);
+        result.put_u8(TERMINATOR);
+        for _ in code_sections {
+            result.put_slice(&[0x00, 0x80, 0x00, 0x01]);
+        }
+        for code in code_sections {
+            result.put_slice(code);
+        }
+        for container in containers {
+            result.put_slice(container);
+        }
+        result.put_slice(data);
+        result.to_vec()
+    }
+
+    #[test]
+    fn parse_container() {
+        let runtime = encode(&[CODE], &[], DATA, DATA.len() as u16);
+        let init = encode(&[CODE, CODE], &[&runtime], DATA, DATA.len() as u16);
+
+        let container = parse(&init).expect("valid container");
+        assert_eq!(vec![CODE, CODE], container.code_sections);
+        assert_eq!(vec![runtime.as_slice()], container.container_sections);
+        assert_eq!(DATA, container.data);
+        assert_eq!(init.len(), container.length());
+
+        let mut with_args = init.clone();
+        with_args.extend_from_slice(&[0u8; 32]);
+        assert_eq!(Ok(init.len()), container_length(&with_args));
+    }
+
+    #[test]
+    fn invalid_containers() {
+        assert_eq!(Err(EofError::NotEof), parse(CODE).map(|_| ()));
+
+        let mut container = encode(&[CODE], &[], DATA, DATA.len() as u16);
+        container[2] = 0x02;
+        assert_eq!(
+            Err(EofError::UnsupportedVersion(2)),
+            parse(&container).map(|_| ())
+        );
+
+        let container = encode(&[CODE], &[], DATA, DATA.len() as u16);
+        let truncated = &container[..container.len() - DATA.len() - 1];
+        assert!(matches!(parse(truncated), Err(EofError::Truncated { .. })));
+        // Data section may be incomplete for not deployed containers only
+        let without_data = &container[..container.len() - 1];
+        assert!(parse(without_data).is_ok());
+        assert!(container_length(without_data).is_err());
+
+        // PUSH1 0x00 DELEGATECALL
+        let container = encode(&[&[0x60, 0x00, 0xf4]], &[], DATA, DATA.len() as u16);
+        let err = parse(&container).unwrap_err();
+        assert!(err.to_string().contains("DELEGATECALL"), "{err}");
+
+        // PUSH2 0x01
+        let container = encode(&[&[0x61, 0x01]], &[], DATA, DATA.len() as u16);
+        let err = parse(&container).unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{err}");
+    }
+
+    #[test]
+    fn aux_data() {
+        let local = encode(&[CODE], &[], DATA, DATA.len() as u16);
+        let aux_data = [0xbe; 32];
+        let deployed = encode(
+            &[CODE],
+            &[],
+            &[DATA, aux_data.as_slice()].concat(),
+            (DATA.len() + aux_data.len()) as u16,
+        );
+
+        let (patched, removed) = remove_aux_data(&deployed, &local).expect("same structure");
+        assert_eq!(local, patched.to_vec());
+        assert_eq!(aux_data.to_vec(), removed.to_vec());
+
+        let other = encode(&[CODE, CODE], &[], DATA, DATA.len() as u16);
+        assert_eq!(None, remove_aux_data(&deployed, &other));
+    }
+}
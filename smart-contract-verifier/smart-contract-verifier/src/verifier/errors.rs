@@ -13,6 +13,8 @@ pub enum BytecodeInitError {
     InvalidDeployedBytecode(String),
     #[error("bytecode is empty")]
     Empty,
+    #[error("creation transaction input does not correspond to the deployed bytecode: {0}")]
+    InconsistentBytecodes(String),
 }
 
 /// Enumerates errors that may occur during a single contract verification.
@@ -0,0 +1,159 @@
+//! Availability of the opcodes on chains which are behind the latest EVM fork.
+//!
+//! Recent compilers target recent forks by default (e.g., solc 0.8.20 emits `PUSH0`
+//! introduced by Shanghai), so the sources compiled with the default settings
+//! may never match the bytecode deployed on a chain which does not support the fork.
+
+use crate::solidity::metadata;
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EvmFork {
+    Homestead,
+    TangerineWhistle,
+    SpuriousDragon,
+    Byzantium,
+    Constantinople,
+    Petersburg,
+    Istanbul,
+    Berlin,
+    London,
+    Paris,
+    Shanghai,
+    Cancun,
+    Prague,
+}
+
+impl EvmFork {
+    const ALL: [EvmFork; 13] = [
+        EvmFork::Homestead,
+        EvmFork::TangerineWhistle,
+        EvmFork::SpuriousDragon,
+        EvmFork::Byzantium,
+        EvmFork::Constantinople,
+        EvmFork::Petersburg,
+        EvmFork::Istanbul,
+        EvmFork::Berlin,
+        EvmFork::London,
+        EvmFork::Paris,
+        EvmFork::Shanghai,
+        EvmFork::Cancun,
+        EvmFork::Prague,
+    ];
+
+    /// Name of the fork as used by the `evmVersion` compiler setting
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EvmFork::Homestead => "homestead",
+            EvmFork::TangerineWhistle => "tangerineWhistle",
+            EvmFork::SpuriousDragon => "spuriousDragon",
+            EvmFork::Byzantium => "byzantium",
+            EvmFork::Constantinople => "constantinople",
+            EvmFork::Petersburg => "petersburg",
+            EvmFork::Istanbul => "istanbul",
+            EvmFork::Berlin => "berlin",
+            EvmFork::London => "london",
+            EvmFork::Paris => "paris",
+            EvmFork::Shanghai => "shanghai",
+            EvmFork::Cancun => "cancun",
+            EvmFork::Prague => "prague",
+        }
+    }
+}
+
+impl fmt::Display for EvmFork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EvmFork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|fork| fork.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown evm fork: {s}"))
+    }
+}
+
+/// Opcodes introduced after Homestead with the forks introducing them
+const FORK_OPCODES: [(u8, &str, EvmFork); 16] = [
+    (0x3d, "RETURNDATASIZE", EvmFork::Byzantium),
+    (0x3e, "RETURNDATACOPY", EvmFork::Byzantium),
+    (0xfa, "STATICCALL", EvmFork::Byzantium),
+    (0xfd, "REVERT", EvmFork::Byzantium),
+    (0x1b, "SHL", EvmFork::Constantinople),
+    (0x1c, "SHR", EvmFork::Constantinople),
+    (0x1d, "SAR", EvmFork::Constantinople),
+    (0x3f, "EXTCODEHASH", EvmFork::Constantinople),
+    (0xf5, "CREATE2", EvmFork::Constantinople),
+    (0x46, "CHAINID", EvmFork::Istanbul),
+    (0x47, "SELFBALANCE", EvmFork::Istanbul),
+    (0x48, "BASEFEE", EvmFork::London),
+    (0x5f, "PUSH0", EvmFork::Shanghai),
+    (0x5c, "TLOAD", EvmFork::Cancun),
+    (0x5d, "TSTORE", EvmFork::Cancun),
+    (0x5e, "MCOPY", EvmFork::Cancun),
+];
+
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7f;
+
+/// Returns the names of the opcodes used by the code which are not available
+/// on the `fork`. The metadata hash appended to the code is not analyzed.
+pub fn unsupported_opcodes(bytecode: &[u8], fork: EvmFork) -> BTreeSet<&'static str> {
+    let (code, _) = metadata::split(bytecode);
+    let mut result = BTreeSet::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        if let Some((_, name, _)) = FORK_OPCODES
+            .iter()
+            .find(|(value, _, introduced)| *value == opcode && *introduced > fork)
+        {
+            result.insert(*name);
+        }
+        pc += 1;
+        if (PUSH1..=PUSH32).contains(&opcode) {
+            pc += (opcode - PUSH1) as usize + 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_forks() {
+        assert_eq!(Ok(EvmFork::Shanghai), EvmFork::from_str("shanghai"));
+        assert_eq!(
+            Ok(EvmFork::TangerineWhistle),
+            EvmFork::from_str("tangerinewhistle")
+        );
+        assert!(EvmFork::from_str("osaka2").is_err());
+        assert!(EvmFork::Paris < EvmFork::Shanghai);
+    }
+
+    #[test]
+    fn find_unsupported_opcodes() {
+        // PUSH0 PUSH1 0x5f TSTORE CHAINID
+        let code = hex::decode("5f605f5d46").unwrap();
+        assert_eq!(
+            BTreeSet::from(["PUSH0", "TSTORE"]),
+            unsupported_opcodes(&code, EvmFork::Paris)
+        );
+        assert_eq!(
+            BTreeSet::from(["TSTORE"]),
+            unsupported_opcodes(&code, EvmFork::Shanghai)
+        );
+        assert_eq!(
+            BTreeSet::<&str>::new(),
+            unsupported_opcodes(&code, EvmFork::Cancun)
+        );
+    }
+}
@@ -0,0 +1,265 @@
+//! Immutable variables of the compiled contracts.
+//!
+//! Values of immutables are inserted into the deployed bytecode by the constructor,
+//! so the compiler leaves zeros at their positions listed in `immutableReferences`.
+
+use bytes::Bytes;
+use ethers_solc::{artifacts::Contract, Artifact, CompilerOutput};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+};
+
+/// Byte ranges the immutables occupy in the deployed bytecode by the AST ids of the immutables.
+pub(crate) type ImmutableReferences = BTreeMap<String, Vec<Range<usize>>>;
+
+pub(crate) fn immutable_references(contract: &Contract) -> ImmutableReferences {
+    contract
+        .evm
+        .as_ref()
+        .and_then(|evm| evm.deployed_bytecode.as_ref())
+        .map(|deployed_bytecode| {
+            deployed_bytecode
+                .immutable_references
+                .iter()
+                .map(|(id, offsets)| {
+                    let ranges = offsets
+                        .iter()
+                        .map(|offset| {
+                            let start = offset.start as usize;
+                            start..start + offset.length as usize
+                        })
+                        .collect();
+                    (id.clone(), ranges)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Byte ranges the immutables occupy in the copy of the deployed bytecode embedded into
+/// the creation bytecode (the constructor copies it into memory, inserts the values
+/// of the immutables, and returns it). Empty if the deployed bytecode is not found
+/// inside the creation one (e.g., the libraries have not been linked).
+pub(crate) fn creation_immutable_references(contract: &Contract) -> ImmutableReferences {
+    let references = immutable_references(contract);
+    if references.is_empty() {
+        return references;
+    }
+    let offset = contract
+        .get_bytecode_bytes()
+        .zip(contract.get_deployed_bytecode_bytes())
+        .and_then(|(creation_bytecode, deployed_bytecode)| {
+            find(&creation_bytecode, &deployed_bytecode)
+        });
+    match offset {
+        Some(offset) => references
+            .into_iter()
+            .map(|(id, ranges)| {
+                let ranges = ranges
+                    .into_iter()
+                    .map(|range| range.start + offset..range.end + offset)
+                    .collect();
+                (id, ranges)
+            })
+            .collect(),
+        None => ImmutableReferences::new(),
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Replaces AST ids of the immutables with their names. The names are resolved via source
+/// ASTs, so ids are left as is if ASTs have not been requested from the compiler,
+/// or if several immutables have the same name.
+///
+/// Values of address (and contract) typed immutables are shortened to 20 bytes,
+/// so that they can be formatted as addresses.
+pub(crate) fn resolve_names(
+    output: &CompilerOutput,
+    values: BTreeMap<String, Bytes>,
+) -> BTreeMap<String, Bytes> {
+    if values.is_empty() {
+        return values;
+    }
+
+    let ids: BTreeSet<u64> = values.keys().filter_map(|id| id.parse().ok()).collect();
+    let mut variables = BTreeMap::new();
+    for source in output.sources.values() {
+        if let Some(ast) = &source.ast {
+            let ast = serde_json::to_value(ast).unwrap_or_default();
+            collect_variables(&ast, &ids, &mut variables);
+        }
+    }
+
+    let mut resolved = BTreeMap::new();
+    for (id, value) in values {
+        let variable = id.parse::<u64>().ok().and_then(|id| variables.get(&id));
+        let value = match variable {
+            Some(variable) if variable.is_address => shorten_address(value),
+            _ => value,
+        };
+        let name = variable
+            .map(|variable| &variable.name)
+            .filter(|name| !resolved.contains_key(*name))
+            .cloned();
+        resolved.insert(name.unwrap_or(id), value);
+    }
+    resolved
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Variable {
+    name: String,
+    is_address: bool,
+}
+
+/// Leaves the last 20 bytes of the 32-byte word if the rest of it is zero.
+fn shorten_address(value: Bytes) -> Bytes {
+    match value.len().checked_sub(20) {
+        Some(padding) if value[..padding].iter().all(|byte| *byte == 0) => value.slice(padding..),
+        _ => value,
+    }
+}
+
+fn collect_variables(
+    node: &serde_json::Value,
+    ids: &BTreeSet<u64>,
+    variables: &mut BTreeMap<u64, Variable>,
+) {
+    match node {
+        serde_json::Value::Object(object) => {
+            let is_variable = object.get("nodeType").and_then(|value| value.as_str())
+                == Some("VariableDeclaration");
+            let id = object.get("id").and_then(|value| value.as_u64());
+            let name = object.get("name").and_then(|value| value.as_str());
+            if let (true, Some(id), Some(name)) = (is_variable, id, name) {
+                if ids.contains(&id) {
+                    let type_string = object
+                        .get("typeDescriptions")
+                        .and_then(|descriptions| descriptions.get("typeString"))
+                        .and_then(|value| value.as_str())
+                        .unwrap_or_default();
+                    let is_address =
+                        type_string.starts_with("address") || type_string.starts_with("contract ");
+                    variables.insert(
+                        id,
+                        Variable {
+                            name: name.to_string(),
+                            is_address,
+                        },
+                    );
+                }
+            }
+            object
+                .values()
+                .for_each(|value| collect_variables(value, ids, variables));
+        }
+        serde_json::Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_variables(value, ids, variables)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn collect_variables_from_ast() {
+        let ast = serde_json::json!({
+            "nodeType": "SourceUnit",
+            "nodes": [{
+                "nodeType": "ContractDefinition",
+                "id": 10,
+                "name": "Token",
+                "nodes": [
+                    {"nodeType": "VariableDeclaration", "id": 3, "name": "owner", "mutability": "immutable", "typeDescriptions": {"typeString": "address payable"}},
+                    {"nodeType": "VariableDeclaration", "id": 5, "name": "decimals", "mutability": "immutable", "typeDescriptions": {"typeString": "uint8"}},
+                    {"nodeType": "VariableDeclaration", "id": 6, "name": "counter", "mutability": "mutable"}
+                ]
+            }]
+        });
+        let mut variables = BTreeMap::new();
+        collect_variables(&ast, &BTreeSet::from([3, 5, 10]), &mut variables);
+        assert_eq!(
+            BTreeMap::from([
+                (
+                    3,
+                    Variable {
+                        name: "owner".to_string(),
+                        is_address: true
+                    }
+                ),
+                (
+                    5,
+                    Variable {
+                        name: "decimals".to_string(),
+                        is_address: false
+                    }
+                )
+            ]),
+            variables
+        );
+    }
+
+    #[test]
+    fn immutable_references_in_creation_bytecode() {
+        // The deployed bytecode `0x6000000000` is located at offset 5 of the creation bytecode
+        let contract: Contract = serde_json::from_value(serde_json::json!({
+            "evm": {
+                "bytecode": {"object": "6004600a606000000000"},
+                "deployedBytecode": {
+                    "object": "6000000000",
+                    "immutableReferences": {"5": [{"start": 1, "length": 2}]}
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            BTreeMap::from([("5".to_string(), vec![1..3])]),
+            immutable_references(&contract)
+        );
+        assert_eq!(
+            BTreeMap::from([("5".to_string(), vec![6..8])]),
+            creation_immutable_references(&contract)
+        );
+
+        let contract: Contract = serde_json::from_value(serde_json::json!({
+            "evm": {
+                "bytecode": {"object": "6004600a60"},
+                "deployedBytecode": {
+                    "object": "6000000000",
+                    "immutableReferences": {"5": [{"start": 1, "length": 2}]}
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            ImmutableReferences::new(),
+            creation_immutable_references(&contract),
+            "deployed bytecode is not embedded into the creation one"
+        );
+    }
+
+    #[test]
+    fn shorten_address_values() {
+        let address = [[0u8; 12].as_slice(), [0xca; 20].as_slice()].concat();
+        assert_eq!(
+            Bytes::from(vec![0xca; 20]),
+            shorten_address(Bytes::from(address))
+        );
+
+        let not_address = Bytes::from(vec![0xff; 32]);
+        assert_eq!(not_address.clone(), shorten_address(not_address));
+    }
+}
@@ -0,0 +1,129 @@
+//! External libraries the compiled contracts are linked with.
+//!
+//! If library addresses are not specified in the compiler input, the compiler leaves
+//! placeholders in the bytecode at the positions listed in `linkReferences`.
+//! Those are filled with zeros locally, and actual addresses are read from the remote bytecode.
+
+use bytes::Bytes;
+use ethers_solc::artifacts::{Bytecode, BytecodeObject, Contract};
+use std::{collections::BTreeMap, ops::Range};
+
+/// Byte ranges the library addresses occupy in the bytecode by the fully qualified library names.
+pub(crate) type LinkReferences = BTreeMap<String, Vec<Range<usize>>>;
+
+pub(crate) fn creation_bytecode(contract: &Contract) -> Option<&Bytecode> {
+    contract.evm.as_ref()?.bytecode.as_ref()
+}
+
+pub(crate) fn deployed_bytecode(contract: &Contract) -> Option<&Bytecode> {
+    contract
+        .evm
+        .as_ref()?
+        .deployed_bytecode
+        .as_ref()?
+        .bytecode
+        .as_ref()
+}
+
+pub(crate) fn link_references(bytecode: &Bytecode) -> LinkReferences {
+    bytecode
+        .link_references
+        .iter()
+        .flat_map(|(file, libraries)| {
+            libraries.iter().map(move |(library, offsets)| {
+                let ranges = offsets
+                    .iter()
+                    .map(|offset| {
+                        let start = offset.start as usize;
+                        start..start + offset.length as usize
+                    })
+                    .collect();
+                (format!("{file}:{library}"), ranges)
+            })
+        })
+        .collect()
+}
+
+/// Converts the unlinked bytecode into bytes filling library placeholders with zeros.
+/// Returns `None` if the bytecode still could not be decoded.
+pub(crate) fn zero_linked_bytes(bytecode: &Bytecode) -> Option<Bytes> {
+    let object = match &bytecode.object {
+        BytecodeObject::Bytecode(bytes) => return Some(bytes.0.clone()),
+        BytecodeObject::Unlinked(object) => object.trim_start_matches("0x"),
+    };
+    if !object.is_ascii() {
+        return None;
+    }
+
+    let mut object = object.to_string();
+    for range in link_references(bytecode).values().flatten() {
+        let hex_range = range.start * 2..range.end * 2;
+        if hex_range.end > object.len() {
+            return None;
+        }
+        object.replace_range(hex_range.clone(), &"0".repeat(hex_range.len()));
+    }
+    hex::decode(object).ok().map(Bytes::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_solc::artifacts::Offsets;
+    use pretty_assertions::assert_eq;
+
+    fn unlinked_bytecode(object: &str, offsets: Vec<Offsets>) -> Bytecode {
+        Bytecode {
+            function_debug_data: Default::default(),
+            object: BytecodeObject::Unlinked(object.to_string()),
+            opcodes: None,
+            source_map: None,
+            generated_sources: vec![],
+            link_references: BTreeMap::from([(
+                "contracts/Lib.sol".to_string(),
+                BTreeMap::from([("Lib".to_string(), offsets)]),
+            )]),
+        }
+    }
+
+    #[test]
+    fn fill_placeholders_with_zeros() {
+        let placeholder = "__$1234567890abcdef1234567890abcdef12$__";
+        let object = format!("0x73{placeholder}6000{placeholder}");
+        let bytecode = unlinked_bytecode(
+            &object,
+            vec![
+                Offsets {
+                    start: 1,
+                    length: 20,
+                },
+                Offsets {
+                    start: 23,
+                    length: 20,
+                },
+            ],
+        );
+
+        assert_eq!(
+            BTreeMap::from([("contracts/Lib.sol:Lib".to_string(), vec![1..21, 23..43])]),
+            link_references(&bytecode)
+        );
+
+        let expected = Bytes::from(
+            hex::decode(format!("73{}6000{}", "0".repeat(40), "0".repeat(40))).unwrap(),
+        );
+        assert_eq!(Some(expected), zero_linked_bytes(&bytecode));
+    }
+
+    #[test]
+    fn invalid_link_references() {
+        let bytecode = unlinked_bytecode(
+            "0x73__$1234567890abcdef1234567890abcdef12$__",
+            vec![Offsets {
+                start: 2,
+                length: 20,
+            }],
+        );
+        assert_eq!(None, zero_linked_bytes(&bytecode));
+    }
+}
@@ -2,9 +2,21 @@
 mod all_metadata_extracting_verifier;
 mod base;
 mod bytecode;
+mod consistency;
+mod disassembly;
+mod eof;
 mod errors;
+mod evm_fork;
+mod immutables;
+mod libraries;
+mod normalization;
+pub(crate) mod trace;
 
 mod contract_verifier;
 
 pub use bytecode::BytecodePart;
-pub use contract_verifier::{ContractVerifier, Error, Success};
+pub use contract_verifier::{CompilationOutputs, ContractVerifier, Error, Success};
+pub use errors::VerificationErrorKind;
+pub use evm_fork::EvmFork;
+pub use normalization::{Normalization, NormalizationPass, PatternPass};
+pub use trace::{Trace, TraceStage, TraceStep};
@@ -0,0 +1,201 @@
+//! Normalization of the bytecodes before the comparison.
+//!
+//! Some deployment tooling embeds values not derived from the sources into the bytecode
+//! (e.g., the deployer address or the chain id folded into constants). Normalization passes
+//! find such regions and exclude them from the comparison. Passes may be implemented via
+//! [`NormalizationPass`], or configured as byte patterns via [`PatternPass`].
+//! Bytecodes verified after being normalized result in a partial match at most.
+
+use std::{fmt, ops::Range, sync::Arc};
+
+pub trait NormalizationPass: fmt::Debug + Send + Sync {
+    /// Name the pass is referred to by (e.g., in the verification trace)
+    fn name(&self) -> &str;
+
+    /// Byte ranges of the remote bytecode to be excluded from the comparison
+    /// with the locally compiled one.
+    fn masked_ranges(&self, local: &[u8], remote: &[u8]) -> Vec<Range<usize>>;
+}
+
+/// Ordered set of the passes applied to the bytecodes
+#[derive(Clone, Debug, Default)]
+pub struct Normalization {
+    passes: Vec<Arc<dyn NormalizationPass>>,
+}
+
+impl Normalization {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_pass(mut self, pass: impl NormalizationPass + 'static) -> Self {
+        self.passes.push(Arc::new(pass));
+        self
+    }
+
+    /// Appends the passes of the `other` normalization
+    pub fn with_passes_of(mut self, other: &Normalization) -> Self {
+        self.passes.extend(other.passes.iter().cloned());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    /// Ranges masked by any of the passes
+    pub fn masked_ranges(&self, local: &[u8], remote: &[u8]) -> Vec<Range<usize>> {
+        self.passes
+            .iter()
+            .flat_map(|pass| pass.masked_ranges(local, remote))
+            .collect()
+    }
+}
+
+/// Passes are compared by their names, as the passes themselves may not be comparable
+impl PartialEq for Normalization {
+    fn eq(&self, other: &Self) -> bool {
+        self.names() == other.names()
+    }
+}
+
+impl Eq for Normalization {}
+
+/// Masks the bytes matching wildcards of the pattern. The pattern is a hex string,
+/// where `??` stands for any byte. E.g., `73????????????????????????????????????????30`
+/// matches `PUSH20 <address> ADDRESS` and masks the pushed address. The pattern must match
+/// both bytecodes at the same position, so the bytes outside the wildcards are still compared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternPass {
+    name: String,
+    /// `None` stands for a wildcard
+    pattern: Vec<Option<u8>>,
+}
+
+impl PatternPass {
+    pub fn new(name: impl Into<String>, pattern: &str) -> Result<Self, String> {
+        let pattern = pattern.strip_prefix("0x").unwrap_or(pattern);
+        if pattern.is_empty() || pattern.len() % 2 != 0 || !pattern.is_ascii() {
+            return Err(format!(
+                "pattern must consist of the whole number of bytes: {pattern}"
+            ));
+        }
+        let pattern = (0..pattern.len())
+            .step_by(2)
+            .map(|i| match &pattern[i..i + 2] {
+                "??" => Ok(None),
+                byte => u8::from_str_radix(byte, 16)
+                    .map(Some)
+                    .map_err(|_| format!("invalid pattern byte: {byte}")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if pattern.iter().all(Option::is_some) {
+            return Err("pattern must contain at least one wildcard byte".to_string());
+        }
+
+        Ok(Self {
+            name: name.into(),
+            pattern,
+        })
+    }
+
+    fn matches_at(&self, bytecode: &[u8], position: usize) -> bool {
+        bytecode
+            .get(position..position + self.pattern.len())
+            .map(|window| {
+                window
+                    .iter()
+                    .zip(&self.pattern)
+                    .all(|(byte, expected)| expected.map_or(true, |expected| expected == *byte))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Ranges of the consecutive wildcards of the pattern matched at the position
+    fn wildcard_ranges(&self, position: usize) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = vec![];
+        for (i, _) in self
+            .pattern
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| byte.is_none())
+        {
+            match ranges.last_mut() {
+                Some(range) if range.end == position + i => range.end += 1,
+                _ => ranges.push(position + i..position + i + 1),
+            }
+        }
+        ranges
+    }
+}
+
+impl NormalizationPass for PatternPass {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn masked_ranges(&self, local: &[u8], remote: &[u8]) -> Vec<Range<usize>> {
+        let mut ranges = vec![];
+        let mut position = 0;
+        while position + self.pattern.len() <= local.len() {
+            if self.matches_at(local, position) && self.matches_at(remote, position) {
+                ranges.extend(self.wildcard_ranges(position));
+                position += self.pattern.len();
+            } else {
+                position += 1;
+            }
+        }
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_pattern() {
+        let pass = PatternPass::new("chain-id", "0x60??46").unwrap();
+        assert_eq!(vec![Some(0x60), None, Some(0x46)], pass.pattern);
+
+        assert!(PatternPass::new("empty", "").is_err());
+        assert!(PatternPass::new("odd", "60?").is_err());
+        assert!(PatternPass::new("invalid", "60zz").is_err());
+        assert!(PatternPass::new("no-wildcards", "6046").is_err());
+    }
+
+    #[test]
+    fn pattern_masks_wildcards() {
+        let pass = PatternPass::new("push2-address", "61????30").unwrap();
+        let local = [0x00, 0x61, 0x00, 0x00, 0x30, 0x61, 0x00, 0x00, 0x31];
+        let remote = [0x00, 0x61, 0xca, 0xfe, 0x30, 0x61, 0xca, 0xfe, 0x31];
+        assert_eq!(vec![2..4], pass.masked_ranges(&local, &remote));
+
+        // Bytes outside the wildcards differ
+        let remote = [0x00, 0x62, 0xca, 0xfe, 0x30, 0x00, 0x00, 0x00, 0x00];
+        assert_eq!(
+            Vec::<Range<usize>>::new(),
+            pass.masked_ranges(&local, &remote)
+        );
+    }
+
+    #[test]
+    fn normalization_combines_passes() {
+        let normalization = Normalization::new()
+            .with_pass(PatternPass::new("first", "60??").unwrap())
+            .with_pass(PatternPass::new("second", "????46").unwrap());
+        assert_eq!(vec!["first", "second"], normalization.names());
+
+        let local = [0x60, 0x01, 0x00, 0x00, 0x46];
+        let remote = [0x60, 0x02, 0x01, 0x02, 0x46];
+        assert_eq!(
+            vec![1..2, 2..4],
+            normalization.masked_ranges(&local, &remote)
+        );
+    }
+}
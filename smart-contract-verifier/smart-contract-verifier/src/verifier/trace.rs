@@ -0,0 +1,187 @@
+//! Step-by-step trace of the verification pipeline.
+//!
+//! Is requested by the users who want to understand why the result has been reached
+//! (e.g., auditors): which inputs have been verified, where the metadata has been found,
+//! which regions of the bytecodes have been excluded from the comparison, and which
+//! bytecodes have been compared. Bytecodes are referred to by their keccak256 hashes,
+//! so that the steps could be reproduced independently.
+
+use crate::compiler::Version;
+use sha3::{Digest, Keccak256};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TraceStage {
+    /// Inputs of the request after the normalization (e.g., resolved compiler version)
+    InputNormalized,
+    /// Compiler input the sources have been compiled with
+    Compilation,
+    /// Parts the locally compiled bytecode has been split into
+    MetadataBoundaries,
+    /// Byte ranges of the remote bytecode excluded from the comparison
+    RegionsMasked,
+    /// Comparison of the local and remote bytecodes of the contract
+    Comparison,
+    /// Final result of the verification
+    Result,
+}
+
+impl TraceStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TraceStage::InputNormalized => "input_normalized",
+            TraceStage::Compilation => "compilation",
+            TraceStage::MetadataBoundaries => "metadata_boundaries",
+            TraceStage::RegionsMasked => "regions_masked",
+            TraceStage::Comparison => "comparison",
+            TraceStage::Result => "result",
+        }
+    }
+}
+
+impl fmt::Display for TraceStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    pub stage: TraceStage,
+    /// Fully qualified name of the contract the step relates to (e.g. "contracts/A.sol:A")
+    pub contract: Option<String>,
+    pub details: BTreeMap<String, String>,
+}
+
+impl TraceStep {
+    pub fn new(stage: TraceStage) -> Self {
+        Self {
+            stage,
+            contract: None,
+            details: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_contract(mut self, file_path: &str, contract_name: &str) -> Self {
+        self.contract = Some(format!("{file_path}:{contract_name}"));
+        self
+    }
+
+    pub fn with_detail(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.details.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// Adds the length and the keccak256 hash of the bytes as `{key}_length` and `{key}_hash`.
+    pub fn with_bytes(self, key: &str, bytes: &[u8]) -> Self {
+        self.with_detail(format!("{key}_length"), bytes.len())
+            .with_detail(format!("{key}_hash"), hash(bytes))
+    }
+
+    pub fn with_ranges(self, key: &str, ranges: &[Range<usize>]) -> Self {
+        let ranges: Vec<_> = ranges
+            .iter()
+            .map(|range| format!("{}..{}", range.start, range.end))
+            .collect();
+        self.with_detail(key, ranges.join(","))
+    }
+}
+
+/// Collects the steps of the verification. Clones share the same steps,
+/// so the trace could be read by the caller after it has been passed into the verification.
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    steps: Arc<Mutex<Vec<TraceStep>>>,
+}
+
+impl Trace {
+    pub fn record(&self, step: TraceStep) {
+        self.steps.lock().unwrap().push(step);
+    }
+
+    pub fn steps(&self) -> Vec<TraceStep> {
+        self.steps.lock().unwrap().clone()
+    }
+}
+
+impl PartialEq for Trace {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.steps, &other.steps) || self.steps() == other.steps()
+    }
+}
+
+impl Eq for Trace {}
+
+/// Step describing the inputs the verification starts with, i.e., after the bytecodes
+/// have been decoded, the imports of the sources resolved, and the compiler version selected.
+pub(crate) fn input_step(
+    requested_compiler_version: &Version,
+    compiler_version: &Version,
+    creation_tx_input: Option<&[u8]>,
+    deployed_bytecode: &[u8],
+    bytecode_mask: &[Range<usize>],
+    sources: usize,
+) -> TraceStep {
+    let step = TraceStep::new(TraceStage::InputNormalized)
+        .with_detail("requested_compiler_version", requested_compiler_version)
+        .with_detail("compiler_version", compiler_version)
+        .with_detail("sources", sources)
+        .with_ranges("bytecode_mask", bytecode_mask);
+    match creation_tx_input {
+        Some(creation_tx_input) => step
+            .with_detail("bytecode_type", "creation_input")
+            .with_bytes("bytecode", creation_tx_input),
+        None => step
+            .with_detail("bytecode_type", "deployed_bytecode")
+            .with_bytes("bytecode", deployed_bytecode),
+    }
+}
+
+/// Records the step if the trace has been requested.
+pub(crate) fn record(trace: Option<&Trace>, step: impl FnOnce() -> TraceStep) {
+    if let Some(trace) = trace {
+        trace.record(step())
+    }
+}
+
+/// Hex encoded keccak256 hash of the bytes
+pub(crate) fn hash(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(Keccak256::digest(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn clones_share_steps() {
+        let trace = Trace::default();
+        record(Some(&trace.clone()), || {
+            TraceStep::new(TraceStage::Comparison)
+                .with_contract("contracts/A.sol", "A")
+                .with_bytes("local_bytecode", &[])
+                .with_ranges("masked_ranges", &[0..2, 5..7])
+        });
+        record(None, || unreachable!("trace has not been requested"));
+
+        let expected = vec![TraceStep {
+            stage: TraceStage::Comparison,
+            contract: Some("contracts/A.sol:A".into()),
+            details: BTreeMap::from([
+                (
+                    "local_bytecode_hash".into(),
+                    "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470".into(),
+                ),
+                ("local_bytecode_length".into(), "0".into()),
+                ("masked_ranges".into(), "0..2,5..7".into()),
+            ]),
+        }];
+        assert_eq!(expected, trace.steps());
+    }
+}
@@ -0,0 +1,125 @@
+//! Parsing of blueprint contracts (https://eips.ethereum.org/EIPS/eip-5202).
+//!
+//! Blueprint deployed bytecode consists of the preamble followed by the initcode
+//! of the contract to be created via `create_from_blueprint`:
+//! `0xFE71 <version bits (6)><length encoding bits (2)> [data length] [data] <initcode>`.
+
+use bytes::Bytes;
+use thiserror::Error;
+
+const BLUEPRINT_PREFIX: [u8; 2] = [0xfe, 0x71];
+/// Length encoding bits value which is reserved by the standard
+const RESERVED_LENGTH_ENCODING: u8 = 0b11;
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum BlueprintError {
+    #[error("blueprint preamble is truncated")]
+    Truncated,
+    #[error("blueprint preamble uses reserved length encoding bits")]
+    ReservedLengthEncoding,
+    #[error("blueprint does not contain initcode")]
+    EmptyInitcode,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlueprintPreamble {
+    /// Version of the blueprint standard (upper 6 bits of the third byte)
+    pub erc_version: u8,
+    /// Data section of the preamble, if any
+    pub data: Option<Bytes>,
+}
+
+/// Splits the bytecode into the blueprint preamble and the initcode.
+/// Returns `None` if the bytecode is not a blueprint one.
+pub fn parse(bytecode: &Bytes) -> Option<Result<(BlueprintPreamble, Bytes), BlueprintError>> {
+    if !bytecode.starts_with(&BLUEPRINT_PREFIX) {
+        return None;
+    }
+    Some(parse_preamble(bytecode))
+}
+
+fn parse_preamble(bytecode: &Bytes) -> Result<(BlueprintPreamble, Bytes), BlueprintError> {
+    let version_byte = *bytecode
+        .get(BLUEPRINT_PREFIX.len())
+        .ok_or(BlueprintError::Truncated)?;
+    let erc_version = version_byte >> 2;
+    let length_encoding = version_byte & 0b11;
+    if length_encoding == RESERVED_LENGTH_ENCODING {
+        return Err(BlueprintError::ReservedLengthEncoding);
+    }
+
+    let mut offset = BLUEPRINT_PREFIX.len() + 1;
+    let data = match length_encoding {
+        0 => None,
+        length_encoding => {
+            let length_bytes = bytecode
+                .get(offset..offset + length_encoding as usize)
+                .ok_or(BlueprintError::Truncated)?;
+            let data_length = length_bytes
+                .iter()
+                .fold(0usize, |length, &byte| (length << 8) | byte as usize);
+            offset += length_encoding as usize;
+            if bytecode.len() < offset + data_length {
+                return Err(BlueprintError::Truncated);
+            }
+            let data = bytecode.slice(offset..offset + data_length);
+            offset += data_length;
+            Some(data)
+        }
+    };
+
+    let initcode = bytecode.slice(offset..);
+    if initcode.is_empty() {
+        return Err(BlueprintError::EmptyInitcode);
+    }
+    Ok((BlueprintPreamble { erc_version, data }, initcode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_without_data() {
+        let bytecode = Bytes::from_static(&[0xfe, 0x71, 0x00, 0x60, 0x01]);
+        let expected = BlueprintPreamble {
+            erc_version: 0,
+            data: None,
+        };
+        assert_eq!(
+            Some(Ok((expected, Bytes::from_static(&[0x60, 0x01])))),
+            parse(&bytecode)
+        );
+    }
+
+    #[test]
+    fn parse_with_data() {
+        let bytecode = Bytes::from_static(&[0xfe, 0x71, 0x05, 0x02, 0xca, 0xfe, 0x60, 0x01]);
+        let expected = BlueprintPreamble {
+            erc_version: 1,
+            data: Some(Bytes::from_static(&[0xca, 0xfe])),
+        };
+        assert_eq!(
+            Some(Ok((expected, Bytes::from_static(&[0x60, 0x01])))),
+            parse(&bytecode)
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(None, parse(&Bytes::from_static(&[0x60, 0x01])));
+        assert_eq!(
+            Some(Err(BlueprintError::ReservedLengthEncoding)),
+            parse(&Bytes::from_static(&[0xfe, 0x71, 0x03, 0x60]))
+        );
+        assert_eq!(
+            Some(Err(BlueprintError::Truncated)),
+            parse(&Bytes::from_static(&[0xfe, 0x71, 0x01, 0x05, 0x60]))
+        );
+        assert_eq!(
+            Some(Err(BlueprintError::EmptyInitcode)),
+            parse(&Bytes::from_static(&[0xfe, 0x71, 0x00]))
+        );
+    }
+}
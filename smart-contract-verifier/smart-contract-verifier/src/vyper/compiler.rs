@@ -28,10 +28,11 @@ mod types {
     use std::collections::BTreeMap;
 
     use ethers_solc::{
-        artifacts::{Contract, Error, Severity},
+        artifacts::{Contract, Error, Severity, StorageLayout},
         CompilerOutput,
     };
     use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
 
     #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
     pub struct SourceLocation {
@@ -48,12 +49,21 @@ mod types {
         pub formatted_message: Option<String>,
     }
 
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+    pub struct VyperContract {
+        #[serde(flatten)]
+        pub contract: Contract,
+        /// Storage layout of the contract. Is returned only if `layout` output has been selected
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub layout: Option<Value>,
+    }
+
     #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
     pub struct VyperCompilerOutput {
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         pub errors: Vec<VyperError>,
         #[serde(default)]
-        pub contracts: BTreeMap<String, BTreeMap<String, Contract>>,
+        pub contracts: BTreeMap<String, BTreeMap<String, VyperContract>>,
     }
 
     impl From<VyperCompilerOutput> for CompilerOutput {
@@ -72,13 +82,143 @@ mod types {
                     error_code: None,
                 })
                 .collect();
+            let contracts = vyper
+                .contracts
+                .into_iter()
+                .map(|(file, contracts)| {
+                    let contracts = contracts
+                        .into_iter()
+                        .map(|(name, vyper_contract)| {
+                            let mut contract = vyper_contract.contract;
+                            if let Some(layout) = vyper_contract.layout {
+                                contract.storage_layout = storage_layout(&name, &layout);
+                            }
+                            (name, contract)
+                        })
+                        .collect();
+                    (file, contracts)
+                })
+                .collect();
             CompilerOutput {
                 errors,
                 sources: BTreeMap::new(),
-                contracts: vyper.contracts,
+                contracts,
             }
         }
     }
+
+    /// Converts the Vyper storage layout into the format used by Solidity compiler
+    /// (https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html#json-output),
+    /// so that the layouts of both languages may be processed the same way.
+    /// Vyper type names are used as the type identifiers, and the variables
+    /// of the imported modules are labeled as `module.variable`.
+    fn storage_layout(contract_name: &str, layout: &Value) -> StorageLayout {
+        // Newer compilers put storage variables under the separate key
+        // along with the code (immutables) and transient storage layouts
+        let variables = layout.get("storage_layout").unwrap_or(layout);
+        let mut storage = vec![];
+        let mut types = serde_json::Map::new();
+        collect_variables(contract_name, "", variables, &mut storage, &mut types);
+        storage.sort_by_key(|(slot, _)| *slot);
+        let storage: Vec<_> = storage.into_iter().map(|(_, variable)| variable).collect();
+        serde_json::from_value(json!({ "storage": storage, "types": types })).unwrap_or_default()
+    }
+
+    fn collect_variables(
+        contract_name: &str,
+        prefix: &str,
+        variables: &Value,
+        storage: &mut Vec<(u64, Value)>,
+        types: &mut serde_json::Map<String, Value>,
+    ) {
+        let Some(variables) = variables.as_object() else {
+            return;
+        };
+        for (name, variable) in variables {
+            let label = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}.{name}")
+            };
+            let slot = variable.get("slot").and_then(Value::as_u64);
+            let type_name = variable.get("type").and_then(Value::as_str);
+            match (slot, type_name) {
+                (Some(slot), Some(type_name)) => {
+                    let location = variable.get("location").and_then(Value::as_str);
+                    if location.map_or(false, |location| location != "storage") {
+                        continue;
+                    }
+                    let n_slots = variable.get("n_slots").and_then(Value::as_u64).unwrap_or(1);
+                    storage.push((
+                        slot,
+                        json!({
+                            "astId": 0,
+                            "contract": contract_name,
+                            "label": label,
+                            "offset": 0,
+                            "slot": slot.to_string(),
+                            "type": type_name,
+                        }),
+                    ));
+                    types.entry(type_name).or_insert_with(|| {
+                        json!({
+                            "encoding": "inplace",
+                            "label": type_name,
+                            "numberOfBytes": (n_slots * 32).to_string(),
+                        })
+                    });
+                }
+                // Variables of the imported modules
+                _ => collect_variables(contract_name, &label, variable, storage, types),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn storage_layout_json(layout: Value) -> Value {
+            serde_json::to_value(storage_layout("Token", &layout)).unwrap()
+        }
+
+        #[test]
+        fn converts_storage_layout() {
+            let expected = json!({
+                "storage": [
+                    {"astId": 0, "contract": "Token", "label": "total_supply", "offset": 0, "slot": "0", "type": "uint256"},
+                    {"astId": 0, "contract": "Token", "label": "balances", "offset": 0, "slot": "1", "type": "HashMap[address, uint256]"},
+                    {"astId": 0, "contract": "Token", "label": "ownable.owner", "offset": 0, "slot": "2", "type": "address"},
+                ],
+                "types": {
+                    "HashMap[address, uint256]": {"encoding": "inplace", "label": "HashMap[address, uint256]", "numberOfBytes": "32"},
+                    "address": {"encoding": "inplace", "label": "address", "numberOfBytes": "32"},
+                    "uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"},
+                }
+            });
+
+            let layout = json!({
+                "storage_layout": {
+                    "total_supply": {"type": "uint256", "slot": 0, "n_slots": 1},
+                    "balances": {"type": "HashMap[address, uint256]", "slot": 1, "n_slots": 1},
+                    "ownable": {"owner": {"type": "address", "slot": 2, "n_slots": 1}},
+                },
+                "code_layout": {
+                    "DECIMALS": {"type": "uint8", "offset": 0, "length": 32},
+                }
+            });
+            assert_eq!(expected, storage_layout_json(layout), "nested layout");
+
+            let layout = json!({
+                "total_supply": {"type": "uint256", "location": "storage", "slot": 0},
+                "balances": {"type": "HashMap[address, uint256]", "location": "storage", "slot": 1},
+                "ownable": {"owner": {"type": "address", "location": "storage", "slot": 2}},
+                "DECIMALS": {"type": "uint8", "location": "code", "offset": 0, "length": 32},
+            });
+            assert_eq!(expected, storage_layout_json(layout), "flat layout");
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,151 @@
+//! Detection of the compiler version from the metadata Vyper appends to the bytecode.
+//!
+//! Known formats (depending on the compiler version):
+//! - `<0.3.4`: `a1 65 "vyper" 83 major minor patch` at the end of the bytecode;
+//! - `0.3.4..0.3.10`: the same CBOR map followed by its 2-bytes big-endian length;
+//! - `>=0.3.10`: CBOR array which last element is the `{"vyper": [major, minor, patch]}` map,
+//!   followed by its 2-bytes big-endian length.
+
+use minicbor::{data::Type, Decoder};
+
+/// CBOR encoded `{"vyper": [` prefix of the legacy metadata
+const LEGACY_METADATA_PREFIX: [u8; 8] = [0xa1, 0x65, b'v', b'y', b'p', b'e', b'r', 0x83];
+const LEGACY_METADATA_LENGTH: usize = LEGACY_METADATA_PREFIX.len() + 3;
+
+/// Returns the compiler version encoded into the metadata at the end of the bytecode.
+///
+/// Constructor arguments are not expected to be appended, thus, it is intended
+/// to be used with deployed bytecodes (or creation bytecodes without arguments).
+pub fn detect_compiler_version(bytecode: &[u8]) -> Option<semver::Version> {
+    length_prefixed_metadata(bytecode)
+        .and_then(|metadata| parse_metadata(metadata).ok().flatten())
+        .or_else(|| legacy_metadata(bytecode))
+}
+
+/// Returns the compiler version encoded into the metadata of the creation bytecode.
+///
+/// ABI encoded constructor arguments, which length is a multiple of 32 bytes,
+/// may be appended to the bytecode, so the metadata is looked for before each of the words.
+pub fn detect_creation_compiler_version(bytecode: &[u8]) -> Option<semver::Version> {
+    (0..=bytecode.len() / 32)
+        .map(|words| &bytecode[..bytecode.len() - words * 32])
+        .find_map(detect_compiler_version)
+}
+
+fn length_prefixed_metadata(bytecode: &[u8]) -> Option<&[u8]> {
+    let (rest, length) = bytecode.split_at(bytecode.len().checked_sub(2)?);
+    let length = u16::from_be_bytes([length[0], length[1]]) as usize;
+    rest.get(rest.len().checked_sub(length)?..)
+}
+
+fn legacy_metadata(bytecode: &[u8]) -> Option<semver::Version> {
+    let metadata = bytecode.get(bytecode.len().checked_sub(LEGACY_METADATA_LENGTH)?..)?;
+    let (prefix, version) = metadata.split_at(LEGACY_METADATA_PREFIX.len());
+    // Version numbers less than 24 are encoded by a single byte
+    (prefix == LEGACY_METADATA_PREFIX && version.iter().all(|&v| v < 24))
+        .then(|| semver::Version::new(version[0].into(), version[1].into(), version[2].into()))
+}
+
+fn parse_metadata(metadata: &[u8]) -> Result<Option<semver::Version>, minicbor::decode::Error> {
+    let mut decoder = Decoder::new(metadata);
+    let version = match decoder.datatype()? {
+        Type::Map => parse_version_map(&mut decoder)?,
+        Type::Array => {
+            let length = decoder.array()?.unwrap_or_default();
+            let mut version = None;
+            for _ in 0..length {
+                match decoder.datatype()? {
+                    Type::Map => version = parse_version_map(&mut decoder)?,
+                    _ => decoder.skip()?,
+                }
+            }
+            version
+        }
+        _ => None,
+    };
+    // The whole metadata should be consumed, otherwise the bytes are not a metadata
+    Ok(version.filter(|_| decoder.position() == metadata.len()))
+}
+
+fn parse_version_map(
+    decoder: &mut Decoder,
+) -> Result<Option<semver::Version>, minicbor::decode::Error> {
+    let mut version = None;
+    for _ in 0..decoder.map()?.unwrap_or_default() {
+        if decoder.str()? == "vyper" && decoder.array()? == Some(3) {
+            version = Some(semver::Version::new(
+                decoder.u64()?,
+                decoder.u64()?,
+                decoder.u64()?,
+            ));
+        } else {
+            decoder.skip()?;
+        }
+    }
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DisplayBytes;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn detect(bytecode: &str) -> Option<semver::Version> {
+        detect_compiler_version(&DisplayBytes::from_str(bytecode).unwrap().0)
+    }
+
+    #[test]
+    fn detect_legacy_version() {
+        assert_eq!(
+            Some(semver::Version::new(0, 2, 15)),
+            detect("0x6003361161000c57a16576797065728300020f")
+        );
+    }
+
+    #[test]
+    fn detect_length_prefixed_version() {
+        assert_eq!(
+            Some(semver::Version::new(0, 3, 6)),
+            detect("0x6003361161000c57600080fda165767970657283000306000b")
+        );
+    }
+
+    #[test]
+    fn detect_array_version() {
+        // [runtime size, [data sizes], immutables size, {"vyper": [0, 3, 10]}]
+        assert_eq!(
+            Some(semver::Version::new(0, 3, 10)),
+            detect("0x6003361161000c578419012c8000a16576797065728300030a0011")
+        );
+    }
+
+    #[test]
+    fn detect_version_before_constructor_arguments() {
+        let bytecode = "0x6003361161000c57600080fda165767970657283000306000b";
+        let arguments = "0000000000000000000000000000000000000000000000000000000000000001\
+                         000000000000000000000000000000000000000000000000000000000000000b";
+        let with_arguments = DisplayBytes::from_str(&format!("{bytecode}{arguments}")).unwrap();
+        assert_eq!(
+            Some(semver::Version::new(0, 3, 6)),
+            detect_creation_compiler_version(&with_arguments)
+        );
+        assert_eq!(
+            Some(semver::Version::new(0, 3, 6)),
+            detect_creation_compiler_version(&DisplayBytes::from_str(bytecode).unwrap())
+        );
+        assert_eq!(None, detect_compiler_version(&with_arguments));
+    }
+
+    #[test]
+    fn no_metadata() {
+        assert_eq!(None, detect("0x"));
+        assert_eq!(None, detect("0x6003361161000c57"));
+        // Solidity metadata should not be recognized as a Vyper one
+        assert_eq!(
+            None,
+            detect("0x6080604052600080fdfea26469706673582212201bc3e5a6822adc0f0b84464a262e0b8b02a4a145e5971e7bce020c5f2334dfcb64736f6c63430008070033")
+        );
+    }
+}
@@ -1,6 +1,8 @@
 mod client;
 mod compiler;
 
+pub mod blueprint;
+pub mod metadata;
 pub mod multi_part;
 
 pub use client::Client;
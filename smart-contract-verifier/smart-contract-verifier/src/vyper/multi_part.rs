@@ -1,4 +1,8 @@
-use super::client::Client;
+use super::{
+    blueprint::{self, BlueprintPreamble},
+    client::Client,
+    metadata,
+};
 use crate::{
     compiler::Version,
     verifier::{ContractVerifier, Error, Success},
@@ -8,6 +12,7 @@ use ethers_solc::{
     artifacts::{Settings, Source, Sources},
     CompilerInput, EvmVersion,
 };
+use mismatch::Mismatch;
 use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -23,8 +28,15 @@ pub struct VerificationRequest {
 pub struct MultiFileContent {
     pub sources: BTreeMap<PathBuf, String>,
     pub evm_version: Option<EvmVersion>,
+    /// If set, outputs not required for verification itself
+    /// (e.g. storage layout) are requested from the compiler as well
+    pub extra_outputs: bool,
 }
 
+/// Outputs additionally requested if a caller has asked for extra outputs.
+/// Method identifiers are the part of the default output selection.
+const EXTRA_CONTRACT_OUTPUTS: [&str; 1] = ["layout"];
+
 impl From<MultiFileContent> for CompilerInput {
     fn from(content: MultiFileContent) -> Self {
         let mut settings = Settings::default();
@@ -36,6 +48,13 @@ impl From<MultiFileContent> for CompilerInput {
             // default evm version for vyper
             settings.evm_version = Some(EvmVersion::Istanbul)
         };
+        if content.extra_outputs {
+            for file_selection in settings.output_selection.0.values_mut() {
+                if let Some(contract_outputs) = file_selection.get_mut("*") {
+                    contract_outputs.extend(EXTRA_CONTRACT_OUTPUTS.iter().map(|s| s.to_string()));
+                }
+            }
+        }
 
         let sources: Sources = content
             .sources
@@ -51,19 +70,128 @@ impl From<MultiFileContent> for CompilerInput {
 }
 
 pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result<Success, Error> {
+    let (request, blueprint) = unwrap_blueprint(request)?;
+    validate_compiler_version(&request)?;
+
     let compiler_input = CompilerInput::from(request.content);
     let verifier = ContractVerifier::new(
         client.compilers(),
         &request.compiler_version,
         request.creation_bytecode,
         request.deployed_bytecode,
+        vec![],
+        false,
+        Default::default(),
     )?;
 
     // If case of success, we allow middlewares to process success and only then return it to the caller;
     // Otherwise, we just return an error
-    let success = verifier.verify(&compiler_input).await?;
+    let mut success = verifier.verify(&compiler_input).await?;
+    success.blueprint = blueprint;
     if let Some(middleware) = client.middleware() {
         middleware.call(&success).await;
     }
     Ok(success)
 }
+
+/// Blueprint contracts (ERC-5202) store the initcode of the contract behind the preamble
+/// as their deployed bytecode. In that case the initcode is verified as the creation
+/// bytecode, as the blueprint itself is never executed.
+fn unwrap_blueprint(
+    mut request: VerificationRequest,
+) -> Result<(VerificationRequest, Option<BlueprintPreamble>), Error> {
+    if request.creation_bytecode.is_some() {
+        return Ok((request, None));
+    }
+    match blueprint::parse(&request.deployed_bytecode) {
+        None => Ok((request, None)),
+        Some(Err(err)) => Err(Error::Initialization(anyhow::anyhow!(
+            "invalid blueprint bytecode: {err}"
+        ))),
+        Some(Ok((preamble, initcode))) => {
+            request.creation_bytecode = Some(initcode);
+            request.deployed_bytecode = Bytes::new();
+            Ok((request, Some(preamble)))
+        }
+    }
+}
+
+/// Fails fast if the compiler version encoded into the bytecode metadata
+/// differs from the requested one, so that no compilation is required.
+fn validate_compiler_version(request: &VerificationRequest) -> Result<(), Error> {
+    let detected = match &request.creation_bytecode {
+        // Creation bytecodes are followed by the constructor arguments
+        Some(creation_bytecode) => metadata::detect_creation_compiler_version(creation_bytecode),
+        None => metadata::detect_compiler_version(&request.deployed_bytecode),
+    };
+    let requested = request.compiler_version.version();
+    let requested = semver::Version::new(requested.major, requested.minor, requested.patch);
+    match detected {
+        Some(detected) if detected != requested => Err(Error::CompilerVersionMismatch(
+            Mismatch::new(requested, detected),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DisplayBytes;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn contract_outputs(extra_outputs: bool) -> Vec<String> {
+        let input = CompilerInput::from(MultiFileContent {
+            sources: BTreeMap::from([("source.vy".into(), "".into())]),
+            evm_version: None,
+            extra_outputs,
+        });
+        input.settings.output_selection.0["*"]["*"].clone()
+    }
+
+    fn request(compiler_version: &str, creation_bytecode: &str) -> VerificationRequest {
+        VerificationRequest {
+            deployed_bytecode: Bytes::new(),
+            creation_bytecode: Some(DisplayBytes::from_str(creation_bytecode).unwrap().0),
+            compiler_version: Version::from_str(compiler_version).unwrap(),
+            content: MultiFileContent {
+                sources: BTreeMap::new(),
+                evm_version: None,
+                extra_outputs: false,
+            },
+        }
+    }
+
+    #[test]
+    fn compiler_version_with_constructor_arguments() {
+        // Metadata of v0.3.6 followed by a single `uint256` argument
+        let creation_bytecode = "0x6003361161000c57600080fda165767970657283000306000b\
+                                 000000000000000000000000000000000000000000000000000000000000002a";
+
+        let result =
+            validate_compiler_version(&request("v0.3.6+commit.4a2124d0", creation_bytecode));
+        assert!(result.is_ok(), "matching version: {result:?}");
+
+        let result =
+            validate_compiler_version(&request("v0.3.7+commit.6020b8bb", creation_bytecode));
+        assert!(
+            matches!(result, Err(Error::CompilerVersionMismatch(_))),
+            "other version: {result:?}"
+        );
+    }
+
+    #[test]
+    fn extra_output_selection() {
+        let outputs = contract_outputs(false);
+        assert!(
+            outputs.contains(&"evm.methodIdentifiers".to_string()),
+            "method identifiers should always be requested"
+        );
+        assert!(!outputs.contains(&"layout".to_string()));
+
+        let mut expected = outputs;
+        expected.push("layout".to_string());
+        assert_eq!(expected, contract_outputs(true));
+    }
+}
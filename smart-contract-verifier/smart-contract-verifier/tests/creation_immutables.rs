@@ -0,0 +1,171 @@
+//! Solidity 0.8.21+ may leave non-zero bytes at the positions of the immutables inside
+//! the deployed bytecode embedded into the creation transaction input. The tests compile
+//! the contract with the real compiler and fill those positions in the way such compilers do.
+
+use bytes::Bytes;
+use ethers_solc::{artifacts::Contract, Artifact, CompilerInput};
+use smart_contract_verifier::{
+    solidity::{
+        self,
+        standard_json::{StandardJsonContent, VerificationRequest},
+    },
+    Compilers, ListFetcher, MatchType, SolidityClient, SolidityCompiler, VerificationError,
+    VerificationSuccess, Version, DEFAULT_SOLIDITY_COMPILER_LIST,
+};
+use std::{ops::Range, str::FromStr, sync::Arc};
+use tokio::sync::Semaphore;
+
+const COMPILER_VERSION: &str = "v0.8.21+commit.d9974bed";
+
+/// Immutables are assigned conditionally, which is allowed since 0.8.21
+const SOURCE: &str = r#"
+// SPDX-License-Identifier: MIT
+pragma solidity 0.8.21;
+
+contract Immutables {
+    uint256 public immutable value;
+    address public immutable owner;
+
+    constructor() {
+        if (block.chainid == 1) {
+            value = 1;
+        } else {
+            value = 2;
+        }
+        owner = msg.sender;
+    }
+}
+"#;
+
+async fn compilers() -> Arc<Compilers<SolidityCompiler>> {
+    let url = DEFAULT_SOLIDITY_COMPILER_LIST
+        .try_into()
+        .expect("Getting url");
+    let compilers_dir = tempfile::tempdir().expect("Temp dir creation failed");
+    let fetcher = ListFetcher::new(url, compilers_dir.into_path(), None, None)
+        .await
+        .expect("Fetch releases");
+    let compilers = Compilers::new(
+        Arc::new(fetcher),
+        SolidityCompiler::new(),
+        Arc::new(Semaphore::new(4)),
+    );
+    Arc::new(compilers)
+}
+
+fn compiler_input() -> CompilerInput {
+    serde_json::from_value(serde_json::json!({
+        "language": "Solidity",
+        "sources": {"Immutables.sol": {"content": SOURCE}},
+        "settings": {
+            "optimizer": {"enabled": false, "runs": 200},
+            "outputSelection": {
+                "*": {"*": ["abi", "evm.bytecode", "evm.deployedBytecode"]}
+            }
+        }
+    }))
+    .expect("Invalid compiler input")
+}
+
+/// Ranges of the immutables inside the deployed bytecode embedded into the creation one
+fn creation_immutable_ranges(contract: &Contract) -> Vec<Range<usize>> {
+    let creation_bytecode = contract
+        .get_bytecode_bytes()
+        .expect("creation bytecode")
+        .to_vec();
+    let deployed_bytecode = contract
+        .get_deployed_bytecode_bytes()
+        .expect("deployed bytecode")
+        .to_vec();
+    let offset = creation_bytecode
+        .windows(deployed_bytecode.len())
+        .position(|window| window == deployed_bytecode)
+        .expect("deployed bytecode is not embedded into the creation one");
+    contract
+        .evm
+        .as_ref()
+        .and_then(|evm| evm.deployed_bytecode.as_ref())
+        .expect("deployed bytecode")
+        .immutable_references
+        .values()
+        .flatten()
+        .map(|reference| {
+            let start = offset + reference.start as usize;
+            start..start + reference.length as usize
+        })
+        .collect()
+}
+
+/// Returns the creation bytecode with the immutables filled in, and the ranges of the immutables.
+async fn remote_creation_bytecode(
+    compilers: &Compilers<SolidityCompiler>,
+) -> (Vec<u8>, Vec<Range<usize>>) {
+    let compiler_version = Version::from_str(COMPILER_VERSION).unwrap();
+    let output = compilers
+        .compile(&compiler_version, &compiler_input())
+        .await
+        .expect("Compilation failed");
+    let contract = &output.contracts["Immutables.sol"]["Immutables"];
+
+    let mut bytecode = contract
+        .get_bytecode_bytes()
+        .expect("creation bytecode")
+        .to_vec();
+    let ranges = creation_immutable_ranges(contract);
+    assert!(!ranges.is_empty(), "immutables are not referenced");
+    for range in &ranges {
+        bytecode[range.clone()].fill(0xab);
+    }
+    (bytecode, ranges)
+}
+
+async fn verify(
+    compilers: Arc<Compilers<SolidityCompiler>>,
+    creation_bytecode: Vec<u8>,
+) -> Result<VerificationSuccess, VerificationError> {
+    let client = SolidityClient::new_arc(compilers);
+    let request = VerificationRequest {
+        deployed_bytecode: Bytes::new(),
+        creation_bytecode: Some(Bytes::from(creation_bytecode)),
+        compiler_version: Version::from_str(COMPILER_VERSION).unwrap(),
+        auto_select_compiler_version: false,
+        bytecode_mask: vec![],
+        eof_enabled: false,
+        evm_fork: None,
+        normalization: Default::default(),
+        trace: None,
+        retry_with_flipped_via_ir: false,
+        content: StandardJsonContent {
+            input: compiler_input(),
+            extra_outputs: false,
+        },
+    };
+    solidity::standard_json::verify(Arc::new(client), request).await
+}
+
+#[tokio::test]
+async fn filled_creation_immutables_are_full_match() {
+    let compilers = compilers().await;
+    let (creation_bytecode, _ranges) = remote_creation_bytecode(&compilers).await;
+
+    let success = verify(compilers, creation_bytecode)
+        .await
+        .expect("Verification failed");
+    assert_eq!(MatchType::Full, success.match_type);
+    assert_eq!("Immutables", success.contract_name);
+}
+
+#[tokio::test]
+async fn bytes_outside_creation_immutables_are_compared() {
+    let compilers = compilers().await;
+    let (mut creation_bytecode, ranges) = remote_creation_bytecode(&compilers).await;
+
+    // The byte right before the immutable is the `PUSH32` opcode it is read with
+    let tampered = ranges[0].start - 1;
+    creation_bytecode[tampered] ^= 0xff;
+    let result = verify(compilers, creation_bytecode).await;
+    assert!(
+        matches!(result, Err(VerificationError::NoMatchingContracts(_))),
+        "tampered byte at {tampered} should not be masked: {result:?}"
+    );
+}
@@ -60,11 +60,18 @@ pub mod solidity {
                 deployed_bytecode: source.deployed_bytecode,
                 creation_bytecode: source.creation_bytecode,
                 compiler_version: source.compiler_version,
+                auto_select_compiler_version: false,
+                bytecode_mask: vec![],
+                eof_enabled: false,
+                evm_fork: None,
+                normalization: Default::default(),
+                trace: None,
                 content: multi_part::MultiFileContent {
                     sources: source.sources,
                     evm_version: source.evm_version,
                     optimization_runs: source.optimization_runs,
                     contract_libraries: source.contract_libraries,
+                    extra_outputs: false,
                 },
             }
         }
@@ -84,7 +91,17 @@ pub mod solidity {
                 deployed_bytecode: multi_part_request.deployed_bytecode,
                 creation_bytecode: multi_part_request.creation_bytecode,
                 compiler_version: multi_part_request.compiler_version,
-                content: standard_json::StandardJsonContent { input },
+                auto_select_compiler_version: multi_part_request.auto_select_compiler_version,
+                bytecode_mask: multi_part_request.bytecode_mask,
+                eof_enabled: multi_part_request.eof_enabled,
+                evm_fork: multi_part_request.evm_fork,
+                normalization: multi_part_request.normalization,
+                retry_with_flipped_via_ir: false,
+                trace: multi_part_request.trace,
+                content: standard_json::StandardJsonContent {
+                    input,
+                    extra_outputs: false,
+                },
             }
         }
     }
@@ -146,6 +163,7 @@ pub mod vyper {
                 content: multi_part::MultiFileContent {
                     sources: source.sources,
                     evm_version: source.evm_version,
+                    extra_outputs: false,
                 },
             }
         }
@@ -0,0 +1,368 @@
+//! Typed async client of the smart-contract-verifier http api (`/api/v2`).
+//!
+//! Requests and responses are the types generated from the service proto definitions,
+//! so the client is always in sync with the service it is built together with.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), verifier_client::Error> {
+//! let client = verifier_client::Client::new("http://localhost:8050".parse().unwrap())
+//!     .with_header("x-api-key", "secret")?;
+//! let versions = client.list_solidity_compiler_versions().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2 as proto;
+
+use proto::{
+    CheckTokenMetadataRequest, CheckTokenMetadataResponse, CompareBytecodesRequest,
+    CompareBytecodesResponse, DetectVyperCompilerVersionRequest,
+    DetectVyperCompilerVersionResponse, DiffContractsRequest, DiffContractsResponse,
+    EvictCachedCompilersRequest, EvictCachedCompilersResponse, ListCachedCompilersResponse,
+    ListCompilerVersionsResponse, ListSigningKeysResponse, PrewarmCompilersRequest,
+    PrewarmCompilersResponse, RefreshCompilerVersionsRequest, RefreshCompilerVersionsResponse,
+    VerifyBatchResponse, VerifyHuffMultiPartRequest, VerifyRecordSignatureRequest,
+    VerifyRecordSignatureResponse, VerifyResponse, VerifySolidityGithubRepositoryRequest,
+    VerifySolidityMultiPartRequest, VerifySolidityStandardJsonBatchRequest,
+    VerifySolidityStandardJsonRequest, VerifySourcifyRequest, VerifyVyperMultiPartRequest,
+};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use url::Url;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("invalid url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("invalid header: {0}")]
+    InvalidHeader(String),
+    /// The service responded with a non-success status.
+    /// Invalid requests are responded with `400 Bad Request`, which message
+    /// is a JSON encoded validation error (code, field and message).
+    #[error("service responded with {status}: {message}")]
+    Api { status: StatusCode, message: String },
+}
+
+#[derive(Clone, Debug)]
+pub struct Client {
+    base_url: Url,
+    http: reqwest::Client,
+    headers: HeaderMap,
+}
+
+impl Client {
+    pub fn new(base_url: Url) -> Self {
+        Self {
+            base_url,
+            http: reqwest::Client::new(),
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Use the preconfigured http client (e.g., with custom timeouts or proxy).
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Send the header with every request (e.g., the api key or the tenant id).
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self, Error> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|err| Error::InvalidHeader(format!("{name}: {err}")))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|err| Error::InvalidHeader(format!("{name}: {err}")))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    /* Solidity */
+
+    pub async fn verify_solidity_multi_part(
+        &self,
+        request: &VerifySolidityMultiPartRequest,
+    ) -> Result<VerifyResponse, Error> {
+        self.post(
+            "/api/v2/verifier/solidity/sources:verify-multi-part",
+            request,
+        )
+        .await
+    }
+
+    pub async fn verify_solidity_standard_json(
+        &self,
+        request: &VerifySolidityStandardJsonRequest,
+    ) -> Result<VerifyResponse, Error> {
+        self.post(
+            "/api/v2/verifier/solidity/sources:verify-standard-json",
+            request,
+        )
+        .await
+    }
+
+    pub async fn verify_solidity_standard_json_batch(
+        &self,
+        request: &VerifySolidityStandardJsonBatchRequest,
+    ) -> Result<VerifyBatchResponse, Error> {
+        self.post(
+            "/api/v2/verifier/solidity/sources:verify-standard-json-batch",
+            request,
+        )
+        .await
+    }
+
+    pub async fn verify_solidity_github_repository(
+        &self,
+        request: &VerifySolidityGithubRepositoryRequest,
+    ) -> Result<VerifyResponse, Error> {
+        self.post(
+            "/api/v2/verifier/solidity/sources:verify-github-repository",
+            request,
+        )
+        .await
+    }
+
+    pub async fn list_solidity_compiler_versions(
+        &self,
+    ) -> Result<ListCompilerVersionsResponse, Error> {
+        self.get("/api/v2/verifier/solidity/versions").await
+    }
+
+    /* Vyper */
+
+    pub async fn verify_vyper_multi_part(
+        &self,
+        request: &VerifyVyperMultiPartRequest,
+    ) -> Result<VerifyResponse, Error> {
+        self.post("/api/v2/verifier/vyper/sources:verify-multi-part", request)
+            .await
+    }
+
+    pub async fn list_vyper_compiler_versions(
+        &self,
+    ) -> Result<ListCompilerVersionsResponse, Error> {
+        self.get("/api/v2/verifier/vyper/versions").await
+    }
+
+    pub async fn detect_vyper_compiler_version(
+        &self,
+        request: &DetectVyperCompilerVersionRequest,
+    ) -> Result<DetectVyperCompilerVersionResponse, Error> {
+        self.post("/api/v2/verifier/vyper/versions:detect", request)
+            .await
+    }
+
+    /* Huff */
+
+    pub async fn verify_huff_multi_part(
+        &self,
+        request: &VerifyHuffMultiPartRequest,
+    ) -> Result<VerifyResponse, Error> {
+        self.post("/api/v2/verifier/huff/sources:verify-multi-part", request)
+            .await
+    }
+
+    pub async fn list_huff_compiler_versions(&self) -> Result<ListCompilerVersionsResponse, Error> {
+        self.get("/api/v2/verifier/huff/versions").await
+    }
+
+    /* Sourcify */
+
+    pub async fn verify_sourcify(
+        &self,
+        request: &VerifySourcifyRequest,
+    ) -> Result<VerifyResponse, Error> {
+        self.post("/api/v2/verifier/sourcify/sources:verify", request)
+            .await
+    }
+
+    /* Contract analyzer */
+
+    pub async fn check_token_metadata(
+        &self,
+        request: &CheckTokenMetadataRequest,
+    ) -> Result<CheckTokenMetadataResponse, Error> {
+        self.post("/api/v2/analyzer/tokens:check-metadata", request)
+            .await
+    }
+
+    pub async fn compare_bytecodes(
+        &self,
+        request: &CompareBytecodesRequest,
+    ) -> Result<CompareBytecodesResponse, Error> {
+        self.post("/api/v2/analyzer/bytecodes:compare", request)
+            .await
+    }
+
+    pub async fn diff_contracts(
+        &self,
+        request: &DiffContractsRequest,
+    ) -> Result<DiffContractsResponse, Error> {
+        self.post("/api/v2/analyzer/contracts:diff", request).await
+    }
+
+    /* Verification records */
+
+    pub async fn verify_record_signature(
+        &self,
+        request: &VerifyRecordSignatureRequest,
+    ) -> Result<VerifyRecordSignatureResponse, Error> {
+        self.post("/api/v2/verifier/records:verify-signature", request)
+            .await
+    }
+
+    pub async fn list_signing_keys(&self) -> Result<ListSigningKeysResponse, Error> {
+        self.get("/api/v2/verifier/records/keys").await
+    }
+
+    /* Compilers admin. `language` is one of "solidity", "vyper", or "huff" */
+
+    pub async fn list_cached_compilers(
+        &self,
+        language: &str,
+    ) -> Result<ListCachedCompilersResponse, Error> {
+        self.get(&format!("/api/v2/admin/compilers/{language}/cache"))
+            .await
+    }
+
+    pub async fn evict_cached_compilers(
+        &self,
+        request: &EvictCachedCompilersRequest,
+    ) -> Result<EvictCachedCompilersResponse, Error> {
+        let path = format!("/api/v2/admin/compilers/{}/cache:evict", request.language);
+        self.post(&path, request).await
+    }
+
+    pub async fn prewarm_compilers(
+        &self,
+        request: &PrewarmCompilersRequest,
+    ) -> Result<PrewarmCompilersResponse, Error> {
+        let path = format!("/api/v2/admin/compilers/{}/cache:prewarm", request.language);
+        self.post(&path, request).await
+    }
+
+    pub async fn refresh_compiler_versions(
+        &self,
+        request: &RefreshCompilerVersionsRequest,
+    ) -> Result<RefreshCompilerVersionsResponse, Error> {
+        let path = format!(
+            "/api/v2/admin/compilers/{}/versions:refresh",
+            request.language
+        );
+        self.post(&path, request).await
+    }
+
+    async fn get<Response: DeserializeOwned>(&self, path: &str) -> Result<Response, Error> {
+        let request = self.http.get(self.base_url.join(path)?);
+        self.send(request).await
+    }
+
+    async fn post<Request: Serialize, Response: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &Request,
+    ) -> Result<Response, Error> {
+        let request = self.http.post(self.base_url.join(path)?).json(body);
+        self.send(request).await
+    }
+
+    async fn send<Response: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Response, Error> {
+        let response = request.headers(self.headers.clone()).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await?;
+            return Err(Error::Api {
+                status,
+                message: error_message(body),
+            });
+        }
+        Ok(response.json().await?)
+    }
+}
+
+/// Errors are returned as `{"code": <grpc code>, "message": <message>}`.
+/// Falls back to the raw body if it has any other format.
+fn error_message(body: String) -> String {
+    serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| value.get("message")?.as_str().map(str::to_string))
+        .unwrap_or(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn sends_requests_with_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/verifier/solidity/versions"))
+            .and(header("x-api-key", "secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "compilerVersions": ["v0.8.17+commit.8df45f5f"]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri().parse().unwrap())
+            .with_header("x-api-key", "secret")
+            .unwrap();
+        let response = client
+            .list_solidity_compiler_versions()
+            .await
+            .expect("request failed");
+        assert_eq!(
+            vec!["v0.8.17+commit.8df45f5f".to_string()],
+            response.compiler_versions
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_api_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/verifier/vyper/versions:detect"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "code": 3,
+                "message": "invalid bytecode"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::new(server.uri().parse().unwrap());
+        let err = client
+            .detect_vyper_compiler_version(&DetectVyperCompilerVersionRequest {
+                bytecode: "0x".to_string(),
+            })
+            .await
+            .expect_err("request should fail");
+        match err {
+            Error::Api { status, message } => {
+                assert_eq!(StatusCode::BAD_REQUEST, status);
+                assert_eq!("invalid bytecode", message);
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn raw_error_body() {
+        assert_eq!(
+            "Service Unavailable",
+            error_message("Service Unavailable".to_string())
+        );
+    }
+}